@@ -6,9 +6,10 @@ use std::{
     time::{Duration, Instant},
 };
 
+use gpui::prelude::FluentBuilder as _;
 use gpui::*;
 use gpui_component::{
-    Root,
+    Root, h_flex,
     resizable::{ResizablePanelEvent, ResizableState, h_resizable, resizable_panel},
     v_flex,
 };
@@ -46,13 +47,24 @@ where
     element.text_sm()
 }
 
-pub(crate) const PAPYRU2_CONF_FILE_NAME: &str = "papyru2_conf.toml";
+pub(crate) const PAPYRU2_CONF_FILE_NAME: &str = crate::path_resolver::CONF_FILE_NAME;
 pub(crate) const REQ_COLR_DEFAULT_BACKGROUND_RGB_HEX: u32 = 0xFDFDE6;
 pub(crate) const REQ_COLR_DEFAULT_FOREGROUND_RGB_HEX: u32 = 0x000000;
 pub(crate) const REQ_EDITOR_DEFAULT_CODE_EDITOR: &str = "text";
 pub(crate) const REQ_EDITOR_DEFAULT_SOFT_WRAP: bool = true;
 pub(crate) const REQ_EDITOR_DEFAULT_LINE_NUMBER: bool = false;
 pub(crate) const REQ_EDITOR_DEFAULT_SHOW_WHITESPACES: bool = false;
+pub(crate) const REQ_EDITOR_DEFAULT_PLACEHOLDER: &str = "File is auto saved";
+pub(crate) const REQ_EDITOR_DEFAULT_RENAME_DELAY_MS: u64 = 800;
+pub(crate) const REQ_EDITOR_DEFAULT_TAB_WIDTH: usize = 4;
+pub(crate) const REQ_EDITOR_DEFAULT_TYPEWRITER_SCROLLING: bool = false;
+pub(crate) const REQ_EDITOR_DEFAULT_SPELL_CHECK: bool = true;
+const REQ_EDITOR_MAX_TAB_WIDTH: usize = 16;
+const REQ_EDITOR_MIN_FONT_SIZE: f32 = 6.0;
+const REQ_EDITOR_MAX_FONT_SIZE: f32 = 72.0;
+pub(crate) const REQ_EDITOR_DEFAULT_NEW_NOTE_EXTENSION:
+    crate::file_update_handler::NoteFileExtension =
+    crate::file_update_handler::NoteFileExtension::Txt;
 const REQ_COLR_MAX_RGB_HEX: u32 = 0x00FF_FFFF;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -70,12 +82,21 @@ impl Default for UiColorConfig {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub(crate) struct EditorConfig {
     pub code_editor: String,
     pub soft_wrap: bool,
     pub line_number: bool,
     pub show_whitespaces: bool,
+    pub new_note_extension: crate::file_update_handler::NoteFileExtension,
+    pub placeholder: String,
+    pub rename_delay_ms: u64,
+    pub tab_width: usize,
+    pub typewriter_scrolling: bool,
+    pub spell_check: bool,
+    /// `None` follows the theme's mono font and size.
+    pub font_family: Option<String>,
+    pub font_size: Option<f32>,
 }
 
 impl Default for EditorConfig {
@@ -85,6 +106,14 @@ impl Default for EditorConfig {
             soft_wrap: REQ_EDITOR_DEFAULT_SOFT_WRAP,
             line_number: REQ_EDITOR_DEFAULT_LINE_NUMBER,
             show_whitespaces: REQ_EDITOR_DEFAULT_SHOW_WHITESPACES,
+            new_note_extension: REQ_EDITOR_DEFAULT_NEW_NOTE_EXTENSION,
+            placeholder: REQ_EDITOR_DEFAULT_PLACEHOLDER.to_string(),
+            rename_delay_ms: REQ_EDITOR_DEFAULT_RENAME_DELAY_MS,
+            tab_width: REQ_EDITOR_DEFAULT_TAB_WIDTH,
+            typewriter_scrolling: REQ_EDITOR_DEFAULT_TYPEWRITER_SCROLLING,
+            spell_check: REQ_EDITOR_DEFAULT_SPELL_CHECK,
+            font_family: None,
+            font_size: None,
         }
     }
 }
@@ -115,6 +144,22 @@ struct ReqEditorSection {
     line_number: Option<bool>,
     #[serde(default)]
     show_whitespaces: Option<bool>,
+    #[serde(default)]
+    new_note_extension: Option<String>,
+    #[serde(default)]
+    placeholder: Option<String>,
+    #[serde(default)]
+    rename_delay_ms: Option<u64>,
+    #[serde(default)]
+    tab_width: Option<usize>,
+    #[serde(default)]
+    typewriter_scrolling: Option<bool>,
+    #[serde(default)]
+    spell_check: Option<bool>,
+    #[serde(default)]
+    font_family: Option<String>,
+    #[serde(default)]
+    font_size: Option<f32>,
 }
 
 pub(crate) fn req_colr_rgb_hex_to_hsla(rgb_hex: u32) -> Hsla {
@@ -135,13 +180,19 @@ fn req_colr_hex_text(rgb_hex: u32) -> String {
 
 fn req_colr_default_config_toml(colors: UiColorConfig, editor: &EditorConfig) -> String {
     format!(
-        "[color]\nbackground = 0x{:06x}\nforeground = 0x{:06x}\n\n[editor]\ncode_editor = \"{}\"\nsoft_wrap = {}\nline_number = {}\nshow_whitespaces = {}\n",
+        "[color]\nbackground = 0x{:06x}\nforeground = 0x{:06x}\n\n[editor]\ncode_editor = \"{}\"\nsoft_wrap = {}\nline_number = {}\nshow_whitespaces = {}\nnew_note_extension = \"{}\"\nplaceholder = {}\nrename_delay_ms = {}\ntab_width = {}\ntypewriter_scrolling = {}\nspell_check = {}\n",
         colors.background_rgb_hex,
         colors.foreground_rgb_hex,
         editor.code_editor,
         editor.soft_wrap,
         editor.line_number,
-        editor.show_whitespaces
+        editor.show_whitespaces,
+        editor.new_note_extension.as_str(),
+        toml::Value::String(editor.placeholder.clone()),
+        editor.rename_delay_ms,
+        editor.tab_width,
+        editor.typewriter_scrolling,
+        editor.spell_check
     )
 }
 
@@ -167,7 +218,11 @@ fn write_default_ui_color_config(
     })?;
     std::fs::create_dir_all(parent)?;
     let editor_defaults = req_editor_default_config();
-    let default_toml = req_colr_default_config_toml(colors, &editor_defaults);
+    let default_toml = format!(
+        "{}\n{}",
+        req_colr_default_config_toml(colors, &editor_defaults),
+        crate::confirmations::default_confirmations_toml()
+    );
     std::fs::write(path, default_toml.as_bytes())
 }
 
@@ -254,12 +309,14 @@ fn load_req_editor_config_result(path: &std::path::Path) -> std::io::Result<Edit
     let defaults = req_editor_default_config();
     if !path.is_file() {
         trace_debug(format!(
-            "req-editor config missing path={} defaults code_editor={} soft_wrap={} line_number={} show_whitespaces={}",
+            "req-editor config missing path={} defaults code_editor={} soft_wrap={} line_number={} show_whitespaces={} new_note_extension={} placeholder='{}'",
             path.display(),
             defaults.code_editor,
             defaults.soft_wrap,
             defaults.line_number,
-            defaults.show_whitespaces
+            defaults.show_whitespaces,
+            defaults.new_note_extension.as_str(),
+            compact_text(&defaults.placeholder)
         ));
         return Ok(defaults);
     }
@@ -268,6 +325,19 @@ fn load_req_editor_config_result(path: &std::path::Path) -> std::io::Result<Edit
     let parsed: ReqColrConfigFile = toml::from_str(&raw)
         .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error.to_string()))?;
 
+    let new_note_extension = match parsed.editor.new_note_extension.as_deref() {
+        Some(raw_extension) => crate::file_update_handler::NoteFileExtension::parse(raw_extension)
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "req-editor invalid editor.new_note_extension value={raw_extension} (expected txt or md)"
+                    ),
+                )
+            })?,
+        None => defaults.new_note_extension,
+    };
+
     let resolved = EditorConfig {
         code_editor: parsed
             .editor
@@ -283,14 +353,47 @@ fn load_req_editor_config_result(path: &std::path::Path) -> std::io::Result<Edit
             .editor
             .show_whitespaces
             .unwrap_or(defaults.show_whitespaces),
+        new_note_extension,
+        // An explicit empty string disables the placeholder; only a missing key uses the default.
+        placeholder: parsed
+            .editor
+            .placeholder
+            .unwrap_or_else(|| defaults.placeholder.clone()),
+        // 0 renames on every keystroke.
+        rename_delay_ms: parsed
+            .editor
+            .rename_delay_ms
+            .unwrap_or(defaults.rename_delay_ms),
+        tab_width: parsed
+            .editor
+            .tab_width
+            .unwrap_or(defaults.tab_width)
+            .clamp(1, REQ_EDITOR_MAX_TAB_WIDTH),
+        typewriter_scrolling: parsed
+            .editor
+            .typewriter_scrolling
+            .unwrap_or(defaults.typewriter_scrolling),
+        spell_check: parsed.editor.spell_check.unwrap_or(defaults.spell_check),
+        font_family: parsed
+            .editor
+            .font_family
+            .map(|family| family.trim().to_string())
+            .filter(|family| !family.is_empty()),
+        font_size: parsed
+            .editor
+            .font_size
+            .filter(|size| size.is_finite())
+            .map(|size| size.clamp(REQ_EDITOR_MIN_FONT_SIZE, REQ_EDITOR_MAX_FONT_SIZE)),
     };
     trace_debug(format!(
-        "req-editor config loaded path={} code_editor={} soft_wrap={} line_number={} show_whitespaces={} searchable=true",
+        "req-editor config loaded path={} code_editor={} soft_wrap={} line_number={} show_whitespaces={} new_note_extension={} placeholder='{}' searchable=true",
         path.display(),
         resolved.code_editor,
         resolved.soft_wrap,
         resolved.line_number,
-        resolved.show_whitespaces
+        resolved.show_whitespaces,
+        resolved.new_note_extension.as_str(),
+        compact_text(&resolved.placeholder)
     ));
     Ok(resolved)
 }
@@ -301,31 +404,87 @@ pub(crate) fn load_req_editor_config(path: &std::path::Path) -> EditorConfig {
         Err(error) => {
             let defaults = req_editor_default_config();
             trace_debug(format!(
-                "req-editor config fallback path={} error={} defaults code_editor={} soft_wrap={} line_number={} show_whitespaces={} searchable=true",
+                "req-editor config fallback path={} error={} defaults code_editor={} soft_wrap={} line_number={} show_whitespaces={} new_note_extension={} placeholder='{}' searchable=true",
                 path.display(),
                 error,
                 defaults.code_editor,
                 defaults.soft_wrap,
                 defaults.line_number,
-                defaults.show_whitespaces
+                defaults.show_whitespaces,
+                defaults.new_note_extension.as_str(),
+                compact_text(&defaults.placeholder)
             ));
             defaults
         }
     }
 }
 
+/// Sets `key = value` in `[section]` of a conf file's text, keeping every other line (and
+/// its comments) as written. `value` is a TOML literal.
+pub(crate) fn upsert_conf_value(text: &str, section: &str, key: &str, value: &str) -> String {
+    let header = format!("[{section}]");
+    let entry = format!("{key} = {value}");
+    let mut lines = text.lines().map(str::to_string).collect::<Vec<_>>();
+    let Some(header_index) = lines.iter().position(|line| line.trim() == header) else {
+        if !lines.last().is_none_or(|line| line.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(header);
+        lines.push(entry);
+        return lines.join("\n") + "\n";
+    };
+    let section_end = lines[header_index + 1..]
+        .iter()
+        .position(|line| line.trim_start().starts_with('['))
+        .map(|offset| header_index + 1 + offset)
+        .unwrap_or(lines.len());
+    let existing = (header_index + 1..section_end).find(|index| {
+        lines[*index]
+            .split_once('=')
+            .is_some_and(|(name, _)| name.trim() == key)
+    });
+    match existing {
+        Some(index) => lines[index] = entry,
+        None => lines.insert(header_index + 1, entry),
+    }
+    lines.join("\n") + "\n"
+}
+
+pub(crate) fn write_conf_value(
+    path: &std::path::Path,
+    section: &str,
+    key: &str,
+    value: &str,
+) -> std::io::Result<()> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(error),
+    };
+    crate::atomic_io::write_atomic(
+        path,
+        upsert_conf_value(&text, section, key, value).as_bytes(),
+    )?;
+    trace_debug(format!(
+        "req-editor conf value written path={} {section}.{key}={value}",
+        path.display()
+    ));
+    Ok(())
+}
+
 pub(crate) fn apply_req_colr_theme_overrides(ui_color_config: UiColorConfig, cx: &mut App) {
     let background = req_colr_rgb_hex_to_hsla(ui_color_config.background_rgb_hex);
     let foreground = req_colr_rgb_hex_to_hsla(ui_color_config.foreground_rgb_hex);
 
     let theme = gpui_component::Theme::global_mut(cx);
-    theme.background = background;
     theme.foreground = foreground;
 
     let mut highlight_theme = (*theme.highlight_theme).clone();
-    highlight_theme.style.editor_background = Some(background);
     highlight_theme.style.editor_foreground = Some(foreground);
     theme.highlight_theme = std::sync::Arc::new(highlight_theme);
+    // A see-through window keeps its opacity across a scheme change.
+    let alpha = theme.background.a;
+    crate::window_overlay::set_theme_background(theme, background, alpha);
 
     trace_debug(format!(
         "req-colr theme override applied background={} foreground={} editor_background_synced=true",
@@ -508,7 +667,7 @@ fn startup_display_id_for_window_options(
 
 fn window_position_state_trace(state: &crate::window_position::WindowPositionState) -> String {
     format!(
-        "x={} y={} width={} height={} mode={:?} monitor_id={:?} monitor_uuid={:?} dpi_scale={:?} splitter_sizes={:?}",
+        "x={} y={} width={} height={} mode={:?} monitor_id={:?} monitor_uuid={:?} dpi_scale={:?} splitter_sizes={:?} editor_zoom={:?} always_on_top={} opacity={:?}",
         state.x,
         state.y,
         state.width,
@@ -518,6 +677,9 @@ fn window_position_state_trace(state: &crate::window_position::WindowPositionSta
         state.monitor_uuid.as_deref(),
         state.dpi_scale,
         state.splitter_sizes,
+        state.editor_zoom,
+        state.always_on_top,
+        state.opacity,
     )
 }
 
@@ -608,7 +770,7 @@ fn startup_window_position_expected_bounds_for_close_save(
 }
 
 #[cfg(target_os = "windows")]
-fn window_hwnd(window: &Window) -> Option<HWND> {
+pub(crate) fn window_hwnd(window: &Window) -> Option<HWND> {
     let handle = <Window as HasWindowHandle>::window_handle(window).ok()?;
 
     match handle.as_raw() {
@@ -695,18 +857,73 @@ pub struct Papyru2App {
     pub(crate) singleline: Entity<crate::singleline_input::SingleLineInput>,
     pub(crate) editor: Entity<Papyru2Editor>,
     pub(crate) file_tree: Entity<FileTreeView>,
+    pub(crate) calendar: Entity<crate::calendar_view::CalendarView>,
+    pub(crate) left_panel_tab: crate::calendar_view::LeftPanelTab,
     pub(crate) layout_split_state: Entity<ResizableState>,
     pub(crate) split_left_panel_size: Pixels,
+    pub(crate) ui_state: crate::ui_state::UiState,
+    pub(crate) sidebar_animation_width: Option<Pixels>,
+    pub(crate) sidebar_animation_generation: u64,
     pub(crate) last_window_width: Pixels,
+    /// Where the window was while last windowed, saved as the restore bounds of a maximized
+    /// or fullscreen window.
+    pub(crate) last_windowed_bounds: Option<Bounds<Pixels>>,
+    pub(crate) always_on_top: bool,
+    /// 1.0 is opaque; lowered for a floating notes overlay.
+    pub(crate) window_opacity: f32,
     pub(crate) layout_split_subscription: Subscription,
     pub(crate) file_workflow: crate::file_update_handler::SinglelineCreateFileWorkflow,
     pub(crate) editor_autosave: crate::file_update_handler::EditorAutoSaveCoordinator,
+    pub(crate) editor_autosave_worker: crate::file_update_handler::EditorAutoSaveWorker,
+    pub(crate) title_rename_debouncer: crate::file_update_handler::TitleRenameDebouncer,
+    pub(crate) title_rename_debounce_worker: crate::file_update_handler::TitleRenameDebounceWorker,
     pub(crate) _subscriptions: Vec<Subscription>,
     pub(crate) app_paths: crate::path_resolver::AppPaths,
     pub(crate) _file_tree_watcher: crate::file_tree_watcher::FileTreeWatcher,
+    pub(crate) vault_availability: crate::vault_availability::VaultAvailability,
+    pub(crate) vaults: crate::vaults::VaultManager,
+    pub(crate) active_vault_root: crate::vaults::ActiveVaultRoot,
+    pub(crate) mirror_backup: Option<crate::mirror_backup::MirrorBackupWorker>,
+    pub(crate) mirror_backup_status: crate::mirror_backup::MirrorBackupStatus,
+    pub(crate) git_versioning: Option<crate::git_versioning::GitVersioningWorker>,
+    pub(crate) vault_backup: crate::vault_backup::VaultBackupConfig,
+    /// A backup or restore is running on its own thread.
+    pub(crate) vault_backup_running: bool,
+    /// When the newest archive was taken; scheduled backups run once it is old enough.
+    pub(crate) vault_backup_last_at: Option<chrono::DateTime<chrono::Local>>,
+    pub(crate) sync_status: crate::sync_status::SyncStatus,
+    pub(crate) folder_import_running: bool,
+    pub(crate) vault_export: Option<crate::export::VaultExportStatus>,
+    pub(crate) conflict_merge: Option<crate::sync_conflicts::ConflictMerge>,
+    pub(crate) split_editor: Option<crate::split_editor::SplitEditor>,
+    pub(crate) quick_capture: Option<crate::quick_capture::QuickCaptureWindow>,
+    /// The active color scheme's background and text, for views opened after it was picked.
+    pub(crate) ui_color_config: UiColorConfig,
+    _quick_capture_hotkey: Option<global_hotkey::GlobalHotKeyManager>,
+    /// Session only; every start begins with the full layout.
+    pub(crate) focus_mode: bool,
+    pub(crate) note_templates: crate::note_templates::NoteTemplateChoice,
+    pub(crate) autosave_indicator: crate::status_bar::AutoSaveIndicator,
     pub(crate) selection_focus_reassert_pending: bool,
+    pub(crate) pending_note_open: Option<crate::file_tree::PendingNoteOpen>,
     pub(crate) rpc_highlight_active: bool,
     pub(crate) rpc_highlight_line_1_based: Option<u32>,
+    pub(crate) toasts: crate::toasts::ToastCenter,
+    pub(crate) tag_index: Option<crate::tag_index::TagIndexStore>,
+    pub(crate) search_replace: Entity<crate::search_replace::SearchReplacePanel>,
+    pub(crate) bookmarks: Option<crate::bookmarks::BookmarkStore>,
+    pub(crate) markdown_preview: Entity<crate::markdown_preview::MarkdownPreview>,
+    pub(crate) scratch_panes: Entity<crate::scratch_panes::ScratchPanes>,
+    pub(crate) confirmations: crate::confirmations::ConfirmationPolicy,
+    pub(crate) external_change: crate::external_change::ExternalChangeTracker,
+    pub(crate) duplicate_title_gate: crate::duplicate_titles::DuplicateTitleGate,
+    pub(crate) duplicate_title_notice: Option<crate::duplicate_titles::DuplicateTitleNotice>,
+    pub(crate) title_mode: crate::note_titles::TitleMode,
+    /// The note last named after its first line under `TitleMode::FirstLine`.
+    pub(crate) auto_title: Option<crate::note_titles::AutoTitle>,
+    pub(crate) title_link_moves: crate::note_links::TitleLinkMoves,
+    pub(crate) active_picker: Option<crate::pickers::ActivePicker>,
+    pub(crate) storage_monitor: crate::storage_report::StorageMonitor,
 }
 
 #[derive(Copy, Clone, Debug, Default)]
@@ -738,6 +955,144 @@ impl Papyru2App {
         }
 
         let key = event.keystroke.key.as_str().to_ascii_lowercase();
+        let modifiers = &event.keystroke.modifiers;
+        if modifiers.control && !modifiers.shift && !modifiers.alt && key == "\\" {
+            trace_debug("app keydown ctrl+\\ toggle sidebar");
+            self.toggle_sidebar(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "h" {
+            trace_debug("app keydown ctrl+shift+h toggle search_replace");
+            self.toggle_search_replace(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "m" {
+            trace_debug("app keydown ctrl+shift+m toggle markdown_preview");
+            self.toggle_markdown_preview(cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "n" {
+            trace_debug("app keydown ctrl+shift+n add scratch_pane");
+            self.add_scratch_pane(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "e" {
+            trace_debug("app keydown ctrl+shift+e export settings");
+            self.export_settings(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "x" {
+            trace_debug("app keydown ctrl+shift+x export note as html");
+            self.export_current_note_as_html(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "o" {
+            trace_debug("app keydown ctrl+shift+o open external file");
+            self.prompt_open_external_file(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "r" {
+            trace_debug("app keydown ctrl+shift+r daily review");
+            self.run_daily_review(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "u" {
+            trace_debug("app keydown ctrl+shift+u storage report");
+            self.show_storage_report(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "i" {
+            trace_debug("app keydown ctrl+shift+i import settings");
+            self.import_settings(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "b" {
+            trace_debug("app keydown ctrl+shift+b vault backup");
+            self.show_vault_backup_menu(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "k" {
+            trace_debug("app keydown ctrl+shift+k import folder");
+            self.prompt_import_folder(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "y" {
+            trace_debug("app keydown ctrl+shift+y note history");
+            self.show_note_history(0, window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && !modifiers.alt && matches!(key.as_str(), "=" | "+" | "-" | "0") {
+            trace_debug(format!("app keydown ctrl+{key} editor zoom"));
+            self.zoom_editor(
+                match key.as_str() {
+                    "-" => Some(-1),
+                    "0" => None,
+                    _ => Some(1),
+                },
+                cx,
+            );
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && !modifiers.alt && key == "g" {
+            if modifiers.shift {
+                trace_debug("app keydown ctrl+shift+g go to date");
+                self.show_goto_date(window, cx);
+            } else {
+                trace_debug("app keydown ctrl+g go to line");
+                self.show_goto_line(window, cx);
+            }
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "f" {
+            trace_debug("app keydown ctrl+shift+f toggle focus mode");
+            self.toggle_focus_mode(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "t" {
+            trace_debug("app keydown ctrl+shift+t color scheme");
+            self.show_color_scheme_menu(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "p" {
+            trace_debug("app keydown ctrl+shift+p toggle always on top");
+            self.toggle_always_on_top(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "j" {
+            trace_debug("app keydown ctrl+shift+j cycle window opacity");
+            self.cycle_window_opacity(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && modifiers.shift && !modifiers.alt && key == "l" {
+            trace_debug("app keydown ctrl+shift+l editor display options");
+            self.show_editor_display_menu(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        // Scratch panes edit text only; keys typed there never drive file-tree shortcuts.
+        if self.scratch_panes.read(cx).is_focused(window, cx) {
+            cx.propagate();
+            return;
+        }
         let is_delete_key =
             key == "delete" || key == "backspace" || key == "forwarddelete" || key == "del";
         if !is_delete_key {
@@ -821,6 +1176,8 @@ impl Papyru2App {
                         .filter(|size| is_valid_split_panel_size(*size))
                         .unwrap_or(this.split_left_panel_size);
                     this.split_left_panel_size = next_left_panel_size;
+                    this.ui_state.file_tree_width = Some(f32::from(next_left_panel_size));
+                    this.persist_ui_state();
 
                     let layout_split_state = this.layout_split_state.clone();
                     this.top_bars.update(cx, |top_bars, _| {
@@ -879,13 +1236,23 @@ impl Papyru2App {
 
     fn new(
         window: &mut Window,
-        app_paths: crate::path_resolver::AppPaths,
+        mut app_paths: crate::path_resolver::AppPaths,
         restored_splitter_left_size: Option<f32>,
+        restored_editor_zoom: Option<f32>,
         startup_window_position_guard: Rc<RefCell<Option<StartupWindowPositionGuard>>>,
         ui_color_config: UiColorConfig,
         editor_config: EditorConfig,
         cx: &mut Context<Self>,
     ) -> Self {
+        // `ui_state.toml` holds the panel sizes; older installs only have the splitter in
+        // `window_position.toml`.
+        let ui_state = crate::ui_state::load_ui_state(
+            app_paths
+                .config_file_path(crate::ui_state::UI_STATE_FILE_NAME)
+                .as_path(),
+        );
+        let restored_splitter_left_size =
+            ui_state.file_tree_width().or(restored_splitter_left_size);
         let split_left_panel_size = normalize_split_left_panel_size(restored_splitter_left_size);
         trace_debug(format!(
             "window_position splitter restore left_size={} applied={}",
@@ -893,18 +1260,102 @@ impl Papyru2App {
             restored_splitter_left_size.is_some()
         ));
 
+        let vaults = crate::vaults::VaultManager::load(
+            app_paths.config_file_path(crate::vaults::VAULTS_FILE_NAME),
+            app_paths.user_document_dir.as_path(),
+        );
+        let active_vault = if app_paths.documents_dir_from_cli() {
+            app_paths.user_document_dir.clone()
+        } else {
+            vaults
+                .registry()
+                .active_root(app_paths.user_document_dir.as_path())
+        };
+        if active_vault != app_paths.user_document_dir {
+            app_paths.switch_user_document_dir(active_vault);
+            if let Err(error) = std::fs::create_dir_all(&app_paths.recyclebin_dir) {
+                trace_debug(format!(
+                    "req-vlt1 active vault prepare failed error={error}"
+                ));
+            }
+        }
+        let active_vault_name = vaults.vault_name(app_paths.user_document_dir.as_path());
+        if let Err(error) = vaults.activate_storage_backend(
+            app_paths.user_document_dir.as_path(),
+            app_paths.vault_data_dir().as_path(),
+        ) {
+            trace_debug(format!(
+                "req-dav remote vault unavailable, using local backend error={error}"
+            ));
+        }
+        vaults.activate_save_transforms(
+            app_paths.user_document_dir.as_path(),
+            crate::save_transforms::load_save_transforms(
+                app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+            ),
+        );
+        trace_debug(format!(
+            "req-vlt1 active vault name={active_vault_name} root={}",
+            app_paths.user_document_dir.display()
+        ));
+        let active_vault_root =
+            crate::vaults::ActiveVaultRoot::new(app_paths.user_document_dir.clone());
+
         let layout_split_state = cx.new(|_| ResizableState::default());
         let top_bars = cx.new(|cx| {
-            TopBars::new(
+            let mut top_bars = TopBars::new(
                 window,
                 layout_split_state.clone(),
                 split_left_panel_size,
                 ui_color_config,
                 cx,
-            )
+            );
+            top_bars.set_active_vault_name(active_vault_name, cx);
+            top_bars.set_file_tree_collapsed(ui_state.file_tree_collapsed, cx);
+            top_bars
         });
         let singleline = top_bars.read(cx).singleline();
-        let editor = cx.new(|cx| Papyru2Editor::new(window, ui_color_config, editor_config, cx));
+        let new_note_extension = editor_config.new_note_extension;
+        let rename_delay_ms = editor_config.rename_delay_ms;
+        let spell_checker = std::sync::Arc::new(
+            crate::spell_check::SpellChecker::load(app_paths.data_dir.as_path()).unwrap_or_else(
+                |error| {
+                    trace_debug(format!(
+                        "spell_check dictionaries load failed error={error} (bundled only)"
+                    ));
+                    crate::spell_check::SpellChecker::bundled()
+                },
+            ),
+        );
+        let confirmations = crate::confirmations::load_confirmation_policy(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        );
+        let language_map = crate::syntax_language::load_language_map(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        );
+        let transform_toggles = crate::editor_transforms::load_editor_transform_toggles(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        );
+        let note_metadata = crate::note_metadata::load_note_metadata_config(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        );
+        crate::note_metadata::set_note_metadata_enabled(note_metadata.front_matter);
+        let title_mode = crate::note_titles::load_title_mode(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        );
+        let editor = cx.new(|cx| {
+            let mut editor =
+                Papyru2Editor::new(window, ui_color_config, editor_config, spell_checker, cx);
+            editor.set_confirmation_policy(confirmations);
+            editor.set_language_map(language_map);
+            editor
+                .set_hide_note_metadata(note_metadata.front_matter && note_metadata.hide_in_editor);
+            editor.set_transform_toggles(transform_toggles);
+            if let Some(zoom) = restored_editor_zoom {
+                editor.set_zoom(zoom, cx);
+            }
+            editor
+        });
         let protected_delete_roots = vec![
             app_paths.data_dir.clone(),
             app_paths.user_document_dir.clone(),
@@ -914,7 +1365,22 @@ impl Papyru2App {
             "file_tree app root_dir={}",
             file_tree_root_dir.display()
         ));
-        let startup_daily_dir = match crate::file_update_handler::ensure_daily_directory(
+        if let Some(layout) = crate::storage_layout::load_storage_layout(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        ) {
+            trace_debug(format!(
+                "req-lay1 storage layout={}",
+                layout.as_config_value()
+            ));
+            crate::storage_layout::set_active_storage_layout(layout);
+        }
+        if let Some(rules) = crate::filename_rules::load_filename_rules(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        ) {
+            trace_debug(format!("req-fnr1 filename rules={rules:?}"));
+            crate::filename_rules::set_active_filename_rules(rules);
+        }
+        let startup_daily_dir = match crate::file_update_handler::ensure_note_directory(
             app_paths.user_document_dir.as_path(),
             chrono::Local::now(),
         ) {
@@ -932,14 +1398,30 @@ impl Papyru2App {
                 panic!("file_tree req-ftr18 startup daily_dir ensure failed: {error}");
             }
         };
+        let old_folder_policy = crate::file_tree::load_old_folder_policy(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        );
+        let calendar_root = file_tree_root_dir.clone();
+        let (duplicate_title_gate, duplicate_title_notice_rx) =
+            crate::duplicate_titles::DuplicateTitleGate::new(file_tree_root_dir.clone());
+        Self::spawn_duplicate_title_notice_loop(duplicate_title_notice_rx, window, cx);
+        let calendar = cx.new(|_| crate::calendar_view::CalendarView::new(calendar_root));
         let file_tree = cx.new(move |cx| {
-            FileTreeView::new(
+            let mut file_tree = FileTreeView::new(
                 protected_delete_roots,
                 file_tree_root_dir.clone(),
                 ui_color_config,
                 cx,
-            )
+            );
+            if old_folder_policy.is_some() {
+                file_tree.set_old_folder_policy(old_folder_policy, cx);
+            }
+            file_tree
         });
+        let search_replace =
+            cx.new(|cx| crate::search_replace::SearchReplacePanel::new(window, cx));
+        let markdown_preview = cx.new(|_| crate::markdown_preview::MarkdownPreview::new());
+        let scratch_panes = cx.new(|_| crate::scratch_panes::ScratchPanes::new());
         let (file_tree_watcher, file_tree_refresh_rx) =
             match crate::file_tree_watcher::start_file_tree_watcher(
                 app_paths.user_document_dir.clone(),
@@ -951,7 +1433,59 @@ impl Papyru2App {
                 }
             };
         let file_workflow = crate::file_update_handler::SinglelineCreateFileWorkflow::new();
-        let editor_autosave = crate::file_update_handler::EditorAutoSaveCoordinator::new();
+        file_workflow.set_new_note_extension(new_note_extension);
+        file_workflow.set_queue_limits(crate::file_update_handler::load_queue_limits(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        ));
+        let default_note_template = crate::note_templates::load_default_note_template_name(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        )
+        .map(|name| {
+            app_paths
+                .config_file_path(crate::note_templates::NOTE_TEMPLATES_DIR_NAME)
+                .join(name)
+        });
+        trace_debug(format!(
+            "req-tpl1 default note template={:?}",
+            default_note_template
+        ));
+        let note_templates = crate::note_templates::NoteTemplateChoice::new(default_note_template);
+        trace_debug(format!(
+            "req-newf39 new_note_extension={}",
+            new_note_extension.as_str()
+        ));
+        crate::file_times::open_file_times_sidecar(app_paths.data_dir.as_path());
+        crate::snapshots::open_snapshot_store(
+            app_paths.data_dir.as_path(),
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        );
+        let autosave_journal_path = app_paths
+            .data_dir
+            .join(crate::file_update_handler::EDITOR_AUTOSAVE_JOURNAL_FILE_NAME);
+        match crate::file_update_handler::replay_autosave_journal(autosave_journal_path.as_path()) {
+            Ok(recovered) => {
+                for path in recovered {
+                    trace_debug(format!(
+                        "autosave journal recovered path={}",
+                        path.display()
+                    ));
+                }
+            }
+            Err(error) => trace_debug(format!("autosave journal replay failed error={error}")),
+        }
+        let editor_autosave = crate::file_update_handler::EditorAutoSaveCoordinator::with_journal(
+            autosave_journal_path,
+        );
+        let workflow_journal_path = crate::workflow_journal::workflow_journal_path(&app_paths);
+        let restored_workflow =
+            match crate::workflow_journal::replay_workflow_journal(workflow_journal_path.as_path())
+            {
+                Ok(restored) => restored,
+                Err(error) => {
+                    trace_debug(format!("workflow journal replay failed error={error}"));
+                    crate::workflow_journal::RestoredWorkflow::default()
+                }
+            };
 
         let window_position_path =
             app_paths.config_file_path(crate::window_position::WINDOW_POSITION_FILE_NAME);
@@ -969,14 +1503,44 @@ impl Papyru2App {
             cx,
         );
 
-        crate::file_update_handler::spawn_editor_autosave_worker(
-            editor_autosave.clone(),
-            file_workflow.clone(),
+        let tag_index = match crate::tag_index::TagIndexStore::open(
+            app_paths.data_dir.as_path(),
+            app_paths.user_document_dir.as_path(),
+        ) {
+            Ok(store) => Some(store),
+            Err(error) => {
+                trace_debug(format!("req-tag1 tag_index open failed error={error}"));
+                None
+            }
+        };
+        let bookmarks = match crate::bookmarks::BookmarkStore::open(app_paths.data_dir.as_path()) {
+            Ok(store) => Some(store),
+            Err(error) => {
+                trace_debug(format!("req-bmk1 bookmarks open failed error={error}"));
+                None
+            }
+        };
+        let (editor_autosave_worker, autosave_event_rx) =
+            crate::file_update_handler::spawn_editor_autosave_worker(
+                editor_autosave.clone(),
+                file_workflow.clone(),
+                tag_index.clone(),
+            );
+        Self::spawn_autosave_status_loop(autosave_event_rx, window, cx);
+        Self::spawn_remote_sync_loop(window, cx);
+        let title_rename_debouncer = crate::file_update_handler::TitleRenameDebouncer::new(
+            std::time::Duration::from_millis(rename_delay_ms),
         );
+        let title_rename_debounce_worker =
+            crate::file_update_handler::spawn_title_rename_debounce_worker(
+                title_rename_debouncer.clone(),
+                file_workflow.clone(),
+                duplicate_title_gate.clone(),
+            );
         let (quic_rpc_ui_tx, quic_rpc_ui_rx) =
             smol::channel::unbounded::<crate::quic_rpc::QuicRpcUiCommand>();
         crate::quic_rpc::spawn_quic_rpc_server(
-            app_paths.clone(),
+            active_vault_root.clone(),
             file_workflow.clone(),
             quic_rpc_ui_tx,
         );
@@ -998,14 +1562,119 @@ impl Papyru2App {
             trace_debug("quic_rpc ui bridge loop detached");
         })
         .detach();
-        cx.spawn(async move |this, cx| {
-            while file_tree_refresh_rx.recv().await.is_ok() {
-                let Some(this) = this.upgrade() else {
+        let rename_outcomes = file_workflow.subscribe_rename_outcomes();
+        cx.spawn_in(window, async move |this, cx| {
+            while let Ok(outcome) = rename_outcomes.recv().await {
+                let updated = this.update_in(cx, |app, window, cx| {
+                    app.complete_singleline_rename(outcome, window, cx);
+                });
+                if updated.is_err() {
                     break;
-                };
-                let _ = this.update(cx, |app, cx| app.apply_file_tree_watcher_refresh(cx));
+                }
+            }
+            trace_debug("rename outcome loop detached");
+        })
+        .detach();
+        let dispatcher_stalls = file_workflow.subscribe_dispatcher_stalls();
+        cx.spawn_in(window, async move |this, cx| {
+            while let Ok(stall) = dispatcher_stalls.recv().await {
+                let updated = this.update_in(cx, |app, _, cx| {
+                    app.show_transient_notice(stall.notice(), cx);
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+            trace_debug("dispatcher stall loop detached");
+        })
+        .detach();
+        let edit_path_updates = file_workflow.subscribe_edit_path_updates();
+        cx.spawn_in(window, async move |this, cx| {
+            while let Ok(path) = edit_path_updates.recv().await {
+                let updated = this.update_in(cx, |app, window, cx| {
+                    app.apply_workflow_edit_path_update(path, window, cx);
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+            trace_debug("workflow edit path update loop detached");
+        })
+        .detach();
+        Self::spawn_file_tree_refresh_loop(file_tree_refresh_rx, window, cx);
+        if let Some(schedule) = crate::daily_review::load_daily_review_schedule(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        ) {
+            Self::spawn_daily_review_schedule(schedule, window, cx);
+        }
+        let quick_capture_hotkey = crate::quick_capture::load_quick_capture_hotkey(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        )
+        .and_then(|hotkey| Self::register_quick_capture_hotkey(hotkey, window, cx));
+        let mirror_backup = crate::mirror_backup::load_mirror_backup_config(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        )
+        .map(|config| {
+            trace_debug(format!(
+                "req-bak1 mirror backup enabled dir={} interval_s={}",
+                config.mirror_dir.display(),
+                config.interval.as_secs()
+            ));
+            let (worker, event_rx) =
+                crate::mirror_backup::spawn_mirror_backup_worker(config, active_vault_root.clone());
+            Self::spawn_mirror_backup_event_loop(event_rx, window, cx);
+            worker
+        });
+        let git_versioning = crate::git_versioning::load_git_versioning_config(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+        )
+        .map(|config| {
+            trace_debug(format!(
+                "req-git1 git versioning enabled program={} interval_s={:?} after_autosave={}",
+                config.program.display(),
+                config.interval.map(|interval| interval.as_secs()),
+                config.after_autosave
+            ));
+            let (worker, event_rx) = crate::git_versioning::spawn_git_versioning_worker(
+                config,
+                active_vault_root.clone(),
+            );
+            Self::spawn_git_versioning_event_loop(event_rx, window, cx);
+            worker
+        });
+        let vault_backup = crate::vault_backup::load_vault_backup_config(
+            app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+            app_paths.data_dir.as_path(),
+        );
+        let vault_backup_last_at =
+            crate::vault_backup::list_vault_backups(vault_backup.archive_dir.as_path())
+                .last()
+                .and_then(|archive| crate::vault_backup::vault_backup_taken_at(archive));
+        if let Some(interval) = vault_backup.interval {
+            trace_debug(format!(
+                "req-vbk1 scheduled vault backup dir={} interval_s={}",
+                vault_backup.archive_dir.display(),
+                interval.as_secs()
+            ));
+            Self::spawn_vault_backup_schedule(interval, window, cx);
+        }
+        let storage_monitor = crate::storage_report::StorageMonitor::new(
+            app_paths.user_document_dir.clone(),
+            crate::storage_report::load_storage_warning_limit(
+                app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME).as_path(),
+            ),
+        );
+        let vault_availability_rx =
+            crate::vault_availability::spawn_vault_availability_monitor(active_vault_root.clone());
+        cx.spawn_in(window, async move |this, cx| {
+            while let Ok((root, available)) = vault_availability_rx.recv().await {
+                let updated = this.update_in(cx, |app, window, cx| {
+                    app.apply_vault_availability(root.as_path(), available, window, cx);
+                });
+                if updated.is_err() {
+                    break;
+                }
             }
-            trace_debug("file_tree watcher refresh loop detached");
         })
         .detach();
 
@@ -1018,14 +1687,122 @@ impl Papyru2App {
                         this.handle_file_tree_selection_changed(path.clone(), window, cx);
                     }
                     FileTreeEvent::OpenFile(path) => {
-                        this.sync_singleline_from_file_tree_selection(path.as_path(), window, cx);
                         let _ = this.open_file(path.clone(), window, cx);
                     }
                     FileTreeEvent::RecyclebinDeleteRequested(paths) => {
                         this.on_file_tree_delete_requested(paths.clone(), window, cx);
                     }
+                    FileTreeEvent::TagFilterSelected(tag) => {
+                        this.apply_tag_filter_selection(tag.clone(), cx);
+                    }
+                    FileTreeEvent::BookmarkSelected { path, line } => {
+                        this.open_bookmark(path.clone(), *line, window, cx);
+                    }
+                    FileTreeEvent::RenameRequested(path) => {
+                        this.rename_from_tree(path.clone(), window, cx);
+                    }
+                    FileTreeEvent::DuplicateRequested(path) => {
+                        this.duplicate_from_tree(path.clone(), window, cx);
+                    }
+                    FileTreeEvent::MergeConflictRequested(path) => {
+                        this.open_conflict_merge(path.clone(), window, cx);
+                    }
+                    FileTreeEvent::ExportPlainTextRequested(path) => {
+                        this.export_plain_text_from_tree(path.clone(), window, cx);
+                    }
+                    FileTreeEvent::ConvertToUtf8Requested(path) => {
+                        this.convert_note_to_utf8(path.clone(), window, cx);
+                    }
+                    FileTreeEvent::OpenInSplitRequested(path) => {
+                        this.open_in_split(path.clone(), window, cx);
+                    }
+                    FileTreeEvent::RevealRequested(path) => {
+                        trace_debug(format!("file_tree reveal path={}", path.display()));
+                        cx.reveal_path(path.as_path());
+                    }
+                    FileTreeEvent::SearchInFolderRequested(folder) => {
+                        this.search_in_folder(folder.clone(), window, cx);
+                    }
+                    FileTreeEvent::MoveRequested { from, to_dir } => {
+                        this.move_from_tree(from.clone(), to_dir.clone(), window, cx);
+                    }
+                    FileTreeEvent::CreateFolderRequested { parent, name } => {
+                        this.create_folder_from_tree(parent.clone(), name, cx);
+                    }
+                },
+            ),
+            cx.subscribe_in(
+                &calendar,
+                window,
+                |this, _, event: &crate::calendar_view::CalendarEvent, window, cx| {
+                    this.handle_calendar_event(event, window, cx);
+                },
+            ),
+            cx.subscribe_in(
+                &search_replace,
+                window,
+                move |this,
+                      _,
+                      event: &crate::search_replace::SearchReplaceEvent,
+                      window,
+                      cx| match event {
+                    crate::search_replace::SearchReplaceEvent::ApplyRequested {
+                        paths,
+                        find,
+                        replace,
+                    } => {
+                        let (paths, find, replace) = (paths.clone(), find.clone(), replace.clone());
+                        let policy = this.confirmations;
+                        crate::confirmations::run_confirmed(
+                            this,
+                            policy,
+                            crate::confirmations::ConfirmationKind::ReplaceAll,
+                            &format!("Replace '{find}' in {} note(s)?", paths.len()),
+                            window,
+                            cx,
+                            move |this, window, cx| {
+                                this.apply_bulk_replace(paths, find, replace, window, cx)
+                            },
+                        );
+                    }
+                    crate::search_replace::SearchReplaceEvent::PickFolder => {
+                        this.open_folder_picker(
+                            "Search in folder",
+                            |this, selection, window, cx| {
+                                if let crate::pickers::PickerSelection::Folder(folder) = selection
+                                {
+                                    this.search_in_folder(folder, window, cx);
+                                }
+                            },
+                            window,
+                            cx,
+                        );
+                    }
+                    crate::search_replace::SearchReplaceEvent::PickDateRange { from, to } => {
+                        this.open_date_range_picker(
+                            "Search notes dated",
+                            *from,
+                            *to,
+                            |this, selection, window, cx| {
+                                if let crate::pickers::PickerSelection::DateRange { from, to } =
+                                    selection
+                                {
+                                    this.search_replace.update(cx, |panel, cx| {
+                                        panel.set_date_range(from, to, window, cx)
+                                    });
+                                }
+                            },
+                            window,
+                            cx,
+                        );
+                    }
+                    crate::search_replace::SearchReplaceEvent::Closed => {
+                        trace_debug("app received SearchReplaceEvent::Closed");
+                        cx.notify();
+                    }
                 },
             ),
+            cx.observe(&scratch_panes, |_, _, cx| cx.notify()),
             cx.subscribe_in(
                 &top_bars,
                 window,
@@ -1038,6 +1815,18 @@ impl Papyru2App {
                         trace_debug("app received TopBarsEvent::PressPlus");
                         this.handle_plus_button(window, cx);
                     }
+                    crate::top_bars::TopBarsEvent::LongPressPlus => {
+                        trace_debug("app received TopBarsEvent::LongPressPlus");
+                        this.show_note_template_picker(window, cx);
+                    }
+                    crate::top_bars::TopBarsEvent::PressSidebarToggle => {
+                        trace_debug("app received TopBarsEvent::PressSidebarToggle");
+                        this.toggle_sidebar(window, cx);
+                    }
+                    crate::top_bars::TopBarsEvent::PressVaultSwitcher => {
+                        trace_debug("app received TopBarsEvent::PressVaultSwitcher");
+                        this.show_vault_switcher(window, cx);
+                    }
                 },
             ),
             cx.subscribe_in(
@@ -1047,23 +1836,33 @@ impl Papyru2App {
                     match event {
                         crate::singleline_input::SingleLineEvent::PressEnter => {
                             trace_debug("app received SingleLineEvent::PressEnter");
+                            this.commit_title_link_moves(window, cx);
                             this.transfer_singleline_enter(window, cx);
                         }
+                        crate::singleline_input::SingleLineEvent::Blur => {
+                            trace_debug("app received SingleLineEvent::Blur");
+                            this.commit_title_link_moves(window, cx);
+                        }
                         crate::singleline_input::SingleLineEvent::PressDown => {
                             trace_debug("app received SingleLineEvent::PressDown");
                             this.ensure_new_file_flow("singleline_down", window, cx);
                             this.transfer_singleline_down(window, cx);
                         }
+                        crate::singleline_input::SingleLineEvent::PasteMultiline(text) => {
+                            trace_debug("app received SingleLineEvent::PasteMultiline");
+                            this.paste_multiline_into_singleline(text, window, cx);
+                        }
                         crate::singleline_input::SingleLineEvent::ValueChanged {
+                            previous,
                             value,
                             cursor_char,
                         } => {
-                            trace_debug(format!(
+                            crate::log::trace_input(format!(
                                 "app received SingleLineEvent::ValueChanged cursor={} value='{}'",
                                 cursor_char,
                                 compact_text(value)
                             ));
-                            this.on_singleline_value_changed(value, window, cx);
+                            this.on_singleline_value_changed(previous, value, window, cx);
                         }
                     }
                 },
@@ -1098,9 +1897,36 @@ impl Papyru2App {
                     crate::editor::EditorEvent::UserInteraction => {
                         this.clear_rpc_highlight_on_editor_interaction();
                     }
+                    crate::editor::EditorEvent::ViewportScrolled => {
+                        this.sync_markdown_preview(false, cx);
+                    }
                     crate::editor::EditorEvent::UserBufferChanged { value } => {
                         this.clear_rpc_highlight_on_editor_interaction();
                         this.on_editor_user_buffer_changed(value, cx);
+                        this.schedule_auto_title(value, cx);
+                        this.sync_title_mirror_from_editor(value, window, cx);
+                        this.sync_markdown_preview(true, cx);
+                    }
+                    crate::editor::EditorEvent::BookmarkToggleRequested { line } => {
+                        trace_debug(format!(
+                            "app received EditorEvent::BookmarkToggleRequested line={line}"
+                        ));
+                        this.toggle_bookmark_at_line(*line, cx);
+                    }
+                    crate::editor::EditorEvent::BookmarksShifted { lines } => {
+                        this.store_shifted_bookmarks(lines.clone(), cx);
+                    }
+                    crate::editor::EditorEvent::ImagePasted(image) => {
+                        trace_debug("app received EditorEvent::ImagePasted");
+                        this.paste_image_attachment(image.clone(), window, cx);
+                    }
+                    crate::editor::EditorEvent::BackgroundLoadFinished { path, error } => {
+                        trace_debug(format!(
+                            "app received EditorEvent::BackgroundLoadFinished path={} error={:?}",
+                            path.display(),
+                            error
+                        ));
+                        this.finish_background_note_open(path.clone(), error.clone(), window, cx);
                     }
                 },
             ),
@@ -1122,6 +1948,9 @@ impl Papyru2App {
                 );
             }
             this.last_window_width = current_width;
+            if let WindowBounds::Windowed(windowed_bounds) = window.window_bounds() {
+                this.last_windowed_bounds = Some(windowed_bounds);
+            }
 
             let now = Instant::now();
             let should_save = debounced_save_clock
@@ -1168,6 +1997,9 @@ impl Papyru2App {
         }));
 
         file_workflow.reset_startup_to_neutral();
+        file_workflow.attach_transition_journal(crate::workflow_journal::WorkflowJournal::new(
+            workflow_journal_path,
+        ));
         singleline.update(cx, |singleline, cx| {
             singleline.apply_cursor(0, window, cx);
             singleline.focus(window, cx);
@@ -1182,58 +2014,266 @@ impl Papyru2App {
             singleline,
             editor,
             file_tree,
+            calendar,
+            left_panel_tab: crate::calendar_view::LeftPanelTab::default(),
             layout_split_state,
             split_left_panel_size,
+            ui_state,
+            sidebar_animation_width: None,
+            sidebar_animation_generation: 0,
             last_window_width: startup_window_position_guard
                 .borrow()
                 .as_ref()
                 .map(|guard| guard.expected_bounds.get_bounds().size.width)
                 .unwrap_or_else(|| current_window_width(window)),
+            last_windowed_bounds: Some(
+                startup_window_position_guard
+                    .borrow()
+                    .as_ref()
+                    .map(|guard| guard.expected_bounds)
+                    .unwrap_or_else(|| window.window_bounds())
+                    .get_bounds(),
+            ),
+            always_on_top: false,
+            window_opacity: 1.0,
             layout_split_subscription,
             file_workflow,
             editor_autosave,
+            editor_autosave_worker,
+            title_rename_debouncer,
+            title_rename_debounce_worker,
             _subscriptions: subscriptions,
             app_paths,
             _file_tree_watcher: file_tree_watcher,
+            vault_availability: crate::vault_availability::VaultAvailability::default(),
+            vaults,
+            active_vault_root,
+            mirror_backup,
+            mirror_backup_status: crate::mirror_backup::MirrorBackupStatus::default(),
+            git_versioning,
+            vault_backup,
+            vault_backup_running: false,
+            vault_backup_last_at,
+            sync_status: crate::sync_status::SyncStatus::default(),
+            folder_import_running: false,
+            vault_export: None,
+            conflict_merge: None,
+            split_editor: None,
+            quick_capture: None,
+            ui_color_config,
+            _quick_capture_hotkey: quick_capture_hotkey,
+            focus_mode: false,
+            note_templates,
+            autosave_indicator: crate::status_bar::AutoSaveIndicator::default(),
             selection_focus_reassert_pending: false,
+            pending_note_open: None,
             rpc_highlight_active: false,
             rpc_highlight_line_1_based: None,
+            toasts: crate::toasts::ToastCenter::default(),
+            tag_index,
+            search_replace,
+            bookmarks,
+            markdown_preview,
+            scratch_panes,
+            confirmations,
+            external_change: {
+                let mut tracker = crate::external_change::ExternalChangeTracker::default();
+                tracker.set_ignore_note_metadata(note_metadata.front_matter);
+                tracker
+            },
+            duplicate_title_gate,
+            duplicate_title_notice: None,
+            title_mode,
+            auto_title: None,
+            title_link_moves: crate::note_links::TitleLinkMoves::default(),
+            active_picker: None,
+            storage_monitor,
         };
+        this.sync_tag_sidebar(cx);
+        this.sync_bookmarks_to_components(cx);
 
         this.apply_req_ftr18_startup_daily_folder_positioning(startup_daily_dir, window, cx);
+        this.restore_workflow_session(restored_workflow, window, cx);
+        this.check_storage_layout_migration(window, cx);
+        this.start_storage_monitor(cx);
 
         this
     }
+
+    pub(crate) fn spawn_file_tree_refresh_loop(
+        file_tree_refresh_rx: smol::channel::Receiver<Vec<std::path::PathBuf>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, cx| {
+            while let Ok(changed_paths) = file_tree_refresh_rx.recv().await {
+                let updated = this.update_in(cx, |app, window, cx| {
+                    app.apply_file_tree_watcher_refresh(cx);
+                    app.apply_storage_changes(&changed_paths, cx);
+                    app.check_current_file_external_change(window, cx);
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+            trace_debug("file_tree watcher refresh loop detached");
+        })
+        .detach();
+    }
+
+    // A watch on a root that vanished does not come back by itself.
+    pub(crate) fn restart_file_tree_watcher(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match crate::file_tree_watcher::start_file_tree_watcher(
+            self.app_paths.user_document_dir.clone(),
+        ) {
+            Ok((watcher, refresh_rx)) => {
+                self._file_tree_watcher = watcher;
+                Self::spawn_file_tree_refresh_loop(refresh_rx, window, cx);
+            }
+            Err(error) => {
+                trace_debug(format!("file_tree watcher restart failed error={error}"));
+            }
+        }
+    }
+
+    /// A plain informational toast; see `toasts` for the other severities.
+    pub(crate) fn show_transient_notice(&mut self, message: String, cx: &mut Context<Self>) {
+        self.show_toast(crate::toasts::ToastSeverity::Info, message, cx);
+    }
 }
 
 impl Render for Papyru2App {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let search_replace = self
+            .search_replace
+            .read(cx)
+            .is_visible()
+            .then(|| self.search_replace.clone());
+        let markdown_preview = self.markdown_preview.read(cx).is_visible().then(|| {
+            div()
+                .flex_1()
+                .min_w_0()
+                .child(self.markdown_preview.clone())
+        });
+        let (scratch_shown, scratch_active) = {
+            let scratch_panes = self.scratch_panes.read(cx);
+            (scratch_panes.has_panes(), scratch_panes.is_scratch_active())
+        };
+        let scratch_panes = scratch_shown.then(|| {
+            div()
+                .w_full()
+                .when(scratch_active, |scratch| scratch.flex_1().min_h_0())
+                .child(self.scratch_panes.clone())
+        });
+        // The note editor stays mounted while a scratch tab is shown so its state is untouched.
+        let editor = div()
+            .w_full()
+            .flex_1()
+            .min_h_0()
+            .when(scratch_active, |editor| editor.hidden())
+            .child(self.editor.clone());
+        let editor = match self.split_editor.as_ref() {
+            Some(split) if !scratch_active => h_resizable("editor-split")
+                .with_state(&split.layout_state)
+                .child(
+                    resizable_panel()
+                        .when_some(self.ui_state.editor_split_width(), |panel, width| {
+                            panel.size(width)
+                        })
+                        .child(editor),
+                )
+                .child(resizable_panel().child(split.view.clone()))
+                .into_any_element(),
+            _ => editor.into_any_element(),
+        };
+        let editor_column = v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .children(scratch_panes)
+            .child(editor);
+        let body = if let Some(merge) = self.conflict_merge.as_ref() {
+            merge.view.clone().into_any_element()
+        } else if self.focus_mode {
+            h_flex()
+                .size_full()
+                .justify_center()
+                .child(
+                    editor_column
+                        .w_full()
+                        .max_w(px(crate::focus_mode::FOCUS_MODE_MAX_LINE_WIDTH_PX)),
+                )
+                .into_any_element()
+        } else if let Some(sidebar_width) = self.sidebar_override_width() {
+            h_flex()
+                .size_full()
+                .when(sidebar_width > px(0.), |body| {
+                    body.child(
+                        div()
+                            .h_full()
+                            .w(sidebar_width)
+                            .flex_none()
+                            .overflow_hidden()
+                            .child(self.render_left_panel(cx)),
+                    )
+                })
+                .child(
+                    h_flex()
+                        .flex_1()
+                        .min_w_0()
+                        .h_full()
+                        .pl(px(SHARED_INTER_PANEL_SPACING_PX))
+                        .child(editor_column)
+                        .children(markdown_preview),
+                )
+                .into_any_element()
+        } else {
+            h_resizable("bottom-split")
+                .with_state(&self.layout_split_state)
+                .child(
+                    resizable_panel()
+                        .size(self.split_left_panel_size)
+                        .child(self.render_left_panel(cx)),
+                )
+                .child(
+                    resizable_panel().child(
+                        h_flex()
+                            .size_full()
+                            .pl(px(SHARED_INTER_PANEL_SPACING_PX))
+                            .child(editor_column)
+                            .children(markdown_preview),
+                    ),
+                )
+                .into_any_element()
+        };
+        let status_bar = if self.focus_mode {
+            None
+        } else {
+            self.render_status_bar(cx)
+        };
         v_flex()
             .id("papyru2")
             .size_full()
             .capture_key_down(cx.listener(Self::on_key_down))
             .gap_2()
             .p_2()
-            .child(self.top_bars.clone())
-            .child(
-                div().flex_1().child(
-                    h_resizable("bottom-split")
-                        .with_state(&self.layout_split_state)
-                        .child(
-                            resizable_panel()
-                                .size(self.split_left_panel_size)
-                                .child(self.file_tree.clone()),
-                        )
-                        .child(
-                            resizable_panel().child(
-                                div()
-                                    .size_full()
-                                    .pl(px(SHARED_INTER_PANEL_SPACING_PX))
-                                    .child(self.editor.clone()),
-                            ),
-                        ),
-                ),
-            )
+            .when(!self.focus_mode, |app| app.child(self.top_bars.clone()))
+            .children(self.render_toasts(cx))
+            .children(self.render_vault_disconnected_banner(cx))
+            .children(self.render_external_file_banner(cx))
+            .children(self.render_duplicate_title_banner(cx))
+            .children(self.render_publish_lock_banner(cx))
+            .children(self.render_vault_export_progress(cx))
+            .children(search_replace)
+            .children(self.render_active_picker())
+            .child(div().flex_1().child(body))
+            .children(self.render_sync_activity(cx))
+            .children(self.render_toast_history(cx))
+            .children(status_bar)
     }
 }
 
@@ -1567,6 +2607,7 @@ mod tests {
             recyclebin_dir: PathBuf::from("C:/tmp/app_home/data/user_document/recyclebin"),
             log_dir: PathBuf::from("C:/tmp/app_home/log"),
             bin_dir: PathBuf::from("C:/tmp/app_home/bin"),
+            override_report: Vec::new(),
         };
 
         assert_eq!(
@@ -1786,7 +2827,8 @@ mod tests {
         let startup_bounds = crate::window_position::resolve_startup_window_bounds(
             Some(&persisted),
             fallback_bounds,
-            startup_display_resolution.display_bounds,
+            startup_display_snapshots.as_slice(),
+            Some(0),
             false,
         );
         let expected_bounds = WindowBounds::Windowed(bounds(
@@ -1868,7 +2910,8 @@ mod tests {
         let startup_bounds = crate::window_position::resolve_startup_window_bounds(
             None,
             fallback_bounds,
-            startup_display_resolution.display_bounds,
+            startup_display_snapshots.as_slice(),
+            Some(1),
             false,
         );
         assert_eq!(startup_bounds, fallback_bounds);
@@ -2102,6 +3145,33 @@ mod editor_config_tests {
         );
     }
 
+    #[test]
+    fn editor_test13_upsert_conf_value_keeps_comments_and_other_sections() {
+        let text = "[editor]\n# wrap long lines\nsoft_wrap = true\n#line_number = true\n\n[debug]\nlog = false\n";
+        let toggled = super::upsert_conf_value(text, "editor", "soft_wrap", "false");
+        assert_eq!(
+            toggled,
+            "[editor]\n# wrap long lines\nsoft_wrap = false\n#line_number = true\n\n[debug]\nlog = false\n"
+        );
+        let added = super::upsert_conf_value(&toggled, "editor", "line_number", "true");
+        assert!(added.starts_with("[editor]\nline_number = true\n# wrap long lines\n"));
+        assert!(added.contains("#line_number = true\n"));
+        let appended = super::upsert_conf_value("[debug]\nlog = false", "editor", "tab_width", "2");
+        assert_eq!(
+            appended,
+            "[debug]\nlog = false\n\n[editor]\ntab_width = 2\n"
+        );
+
+        let root = req_editor_test_temp_root("editor_test13");
+        let config_path = root.join("conf").join(super::PAPYRU2_CONF_FILE_NAME);
+        super::write_conf_value(config_path.as_path(), "editor", "tab_width", "40")
+            .expect("write conf value");
+        let resolved = super::load_req_editor_config(config_path.as_path());
+        assert_eq!(resolved.tab_width, 16);
+        assert!(resolved.soft_wrap);
+        req_editor_test_cleanup(root.as_path());
+    }
+
     #[test]
     fn editor_test2_req_editor_missing_section_uses_defaults() {
         let root = req_editor_test_temp_root("editor_test2");
@@ -2165,6 +3235,8 @@ mod editor_config_tests {
         assert!(raw.contains("soft_wrap = true"));
         assert!(raw.contains("line_number = false"));
         assert!(raw.contains("show_whitespaces = false"));
+        assert!(raw.contains("new_note_extension = \"txt\""));
+        assert!(raw.contains("placeholder = \"File is auto saved\""));
 
         req_editor_test_cleanup(root.as_path());
     }
@@ -2188,25 +3260,147 @@ mod editor_config_tests {
             resolved.show_whitespaces,
             super::REQ_EDITOR_DEFAULT_SHOW_WHITESPACES
         );
+        assert_eq!(
+            resolved.new_note_extension,
+            super::REQ_EDITOR_DEFAULT_NEW_NOTE_EXTENSION
+        );
+
+        req_editor_test_cleanup(root.as_path());
+    }
+
+    #[test]
+    fn editor_test7_req_newf39_new_note_extension_loads_md_and_rejects_unknown() {
+        let root = req_editor_test_temp_root("editor_test7");
+        let config_path = root.join("conf").join(super::PAPYRU2_CONF_FILE_NAME);
+        std::fs::create_dir_all(config_path.parent().expect("config parent")).expect("mkdir conf");
+        std::fs::write(
+            config_path.as_path(),
+            "[editor]\nnew_note_extension = \"md\"\n",
+        )
+        .expect("write md extension config");
+
+        let resolved = super::load_req_editor_config(config_path.as_path());
+        assert_eq!(
+            resolved.new_note_extension,
+            crate::file_update_handler::NoteFileExtension::Md
+        );
+
+        std::fs::write(
+            config_path.as_path(),
+            "[editor]\nnew_note_extension = \"docx\"\n",
+        )
+        .expect("write invalid extension config");
+        let fallback = super::load_req_editor_config(config_path.as_path());
+        assert_eq!(fallback, super::req_editor_default_config());
+
+        req_editor_test_cleanup(root.as_path());
+    }
+
+    #[test]
+    fn editor_test12_rename_delay_defaults_and_overrides() {
+        let root = req_editor_test_temp_root("editor_test12");
+        let config_path = root.join("conf").join(super::PAPYRU2_CONF_FILE_NAME);
+        std::fs::create_dir_all(config_path.parent().expect("config parent")).expect("mkdir conf");
+        std::fs::write(config_path.as_path(), "[editor]\nsoft_wrap = false\n")
+            .expect("write config without rename delay");
+        let resolved = super::load_req_editor_config(config_path.as_path());
+        assert_eq!(
+            resolved.rename_delay_ms,
+            super::REQ_EDITOR_DEFAULT_RENAME_DELAY_MS
+        );
+
+        std::fs::write(config_path.as_path(), "[editor]\nrename_delay_ms = 0\n")
+            .expect("write config with rename delay");
+        let resolved = super::load_req_editor_config(config_path.as_path());
+        assert_eq!(resolved.rename_delay_ms, 0);
+
+        req_editor_test_cleanup(root.as_path());
+    }
+
+    #[test]
+    fn editor_test10_req_editor14_placeholder_is_configurable() {
+        let root = req_editor_test_temp_root("editor_test10");
+        let config_path = root.join("conf").join(super::PAPYRU2_CONF_FILE_NAME);
+        std::fs::create_dir_all(config_path.parent().expect("config parent")).expect("mkdir conf");
+        std::fs::write(
+            config_path.as_path(),
+            "[editor]\nplaceholder = \"Write here \\\"now\\\"\"\n",
+        )
+        .expect("write placeholder config");
+
+        let resolved = super::load_req_editor_config(config_path.as_path());
+        assert_eq!(resolved.placeholder, "Write here \"now\"");
+        assert_eq!(resolved.code_editor, super::REQ_EDITOR_DEFAULT_CODE_EDITOR);
+
+        let defaults = super::req_editor_default_config();
+        let raw =
+            super::req_colr_default_config_toml(super::req_colr_default_ui_colors(), &defaults);
+        let reparsed: super::ReqColrConfigFile = toml::from_str(&raw).expect("parse default toml");
+        assert_eq!(
+            reparsed.editor.placeholder.as_deref(),
+            Some(super::REQ_EDITOR_DEFAULT_PLACEHOLDER)
+        );
 
         req_editor_test_cleanup(root.as_path());
     }
 }
 
 pub fn run() {
-    let cli_override = match crate::path_resolver::parse_cli_mode_override(std::env::args()) {
-        Ok(override_mode) => override_mode,
+    let cli_args = match crate::cli::parse_cli_args(std::env::args_os()) {
+        Ok(cli_args) => cli_args,
         Err(error) => {
             trace_debug(format!("path_resolver CLI parse failed error={error}"));
-            eprintln!("papyru2 CLI override parsing failed: {error}");
-            eprintln!("use either --portable or --installed (not both)");
+            eprintln!("papyru2 CLI parsing failed: {error}");
+            eprintln!("{}", crate::cli::CLI_USAGE);
             return;
         }
     };
+    let cli_override = cli_args.mode_override;
+    #[cfg(feature = "smoke-test")]
+    let smoke_steps = match std::env::var_os(crate::smoke_test::SMOKE_TEST_ENV) {
+        Some(script) => match crate::smoke_test::load_smoke_script(std::path::Path::new(&script)) {
+            Ok(steps) => Some(steps),
+            Err(error) => {
+                eprintln!("papyru2 smoke test script unreadable: {error}");
+                return;
+            }
+        },
+        None => None,
+    };
+    // Scripted runs create and rename notes; keep them in the throwaway demo sandbox.
+    #[cfg(feature = "smoke-test")]
+    let cli_override = if smoke_steps.is_some() {
+        Some(crate::path_resolver::CliRunModeOverride::Demo)
+    } else {
+        cli_override
+    };
+    // Relative paths refer to the directory papyru2 was started from.
+    let cli_launch = cli_args.launch.map(|action| match action {
+        crate::cli::CliLaunchAction::OpenFile(path) if path.is_relative() => {
+            crate::cli::CliLaunchAction::OpenFile(
+                std::env::current_dir()
+                    .map(|dir| dir.join(&path))
+                    .unwrap_or(path),
+            )
+        }
+        action => action,
+    });
 
-    let resolved_paths = match cli_override {
-        Some(mode) => crate::path_resolver::AppPaths::resolve_with_cli_override(Some(mode)),
-        None => crate::path_resolver::AppPaths::resolve(),
+    let cli_documents_dir = cli_args.documents_dir.map(|dir| {
+        if dir.is_relative() {
+            std::env::current_dir()
+                .map(|cwd| cwd.join(&dir))
+                .unwrap_or(dir)
+        } else {
+            dir
+        }
+    });
+
+    let resolved_paths = match (cli_override, cli_documents_dir) {
+        (None, None) => crate::path_resolver::AppPaths::resolve(),
+        (mode, documents_dir) => {
+            crate::path_resolver::AppPaths::resolve_with_cli_override(mode, documents_dir)
+        }
     };
 
     let app_paths = match resolved_paths {
@@ -2217,6 +3411,12 @@ pub fn run() {
             return;
         }
     };
+    if app_paths.mode == crate::path_resolver::RunEnvPattern::Demo {
+        eprintln!(
+            "papyru2 demo mode: sample vault at {} (real notes are not touched)",
+            app_paths.app_home.display()
+        );
+    }
 
     let color_config_path = app_paths.config_file_path(PAPYRU2_CONF_FILE_NAME);
     let req_log_profile_default = crate::log::req_log_profile_default_enabled();
@@ -2227,6 +3427,7 @@ pub fn run() {
         req_log_config_override,
     );
     crate::log::configure_trace_debug_enabled(req_log_effective_enabled);
+    crate::log::configure_trace_categories_from_config(color_config_path.as_path());
 
     crate::log::configure_trace_debug_log_path(&app_paths);
     if let Err(error) = crate::log::prepare_startup_log_files(&app_paths) {
@@ -2237,7 +3438,25 @@ pub fn run() {
         "req-log startup profile_default={} config_override={req_log_config_override:?} effective={req_log_effective_enabled}",
         req_log_profile_default
     ));
-    trace_debug(format!("path_resolver cli_override={cli_override:?}"));
+    trace_debug(format!(
+        "path_resolver cli_override={cli_override:?} cli_launch={cli_launch:?}"
+    ));
+
+    // Held until run() returns; a second launch forwards its arguments here instead.
+    let (_instance_guard, instance_handoff_rx) =
+        match crate::single_instance::acquire_single_instance(
+            app_paths.data_dir.as_path(),
+            cli_launch.as_ref(),
+        ) {
+            crate::single_instance::InstanceStartup::Primary { guard, handoff_rx } => {
+                (Some(guard), Some(handoff_rx))
+            }
+            crate::single_instance::InstanceStartup::HandedOff => {
+                eprintln!("papyru2 is already running; the request was passed to its window");
+                return;
+            }
+            crate::single_instance::InstanceStartup::Unguarded => (None, None),
+        };
 
     let config_file = app_paths.config_file_path("app.toml");
     let log_file = app_paths.log_file_path("papyru2.log");
@@ -2255,7 +3474,34 @@ pub fn run() {
         config_file.display(),
         log_file.display()
     ));
+    for entry in &app_paths.override_report {
+        match &entry.status {
+            crate::path_resolver::PathOverrideStatus::Applied => trace_debug(format!(
+                "path_resolver dir_override key={} source={} value={} status=applied",
+                entry.env_key,
+                entry.source.label(),
+                entry.value.display()
+            )),
+            crate::path_resolver::PathOverrideStatus::Rejected(reason) => {
+                trace_debug(format!(
+                    "path_resolver dir_override key={} source={} value={} status=rejected reason={reason}",
+                    entry.env_key,
+                    entry.source.label(),
+                    entry.value.display()
+                ));
+                eprintln!(
+                    "papyru2 ignored {}={} ({reason})",
+                    entry.env_key,
+                    entry.value.display()
+                );
+            }
+        }
+    }
 
+    crate::i18n::activate_translations_from_config(
+        color_config_path.as_path(),
+        app_paths.conf_dir.as_path(),
+    );
     let ui_color_config = load_or_create_ui_color_config(color_config_path.as_path());
     trace_debug(format!(
         "req-colr startup colors path={} background={} foreground={}",
@@ -2263,14 +3509,22 @@ pub fn run() {
         req_colr_hex_text(ui_color_config.background_rgb_hex),
         req_colr_hex_text(ui_color_config.foreground_rgb_hex),
     ));
+    let color_scheme = crate::color_schemes::load_active_color_scheme(
+        color_config_path.as_path(),
+        app_paths.conf_dir.as_path(),
+        ui_color_config,
+    );
+    let ui_color_config = color_scheme.ui_colors();
     let editor_config = load_req_editor_config(color_config_path.as_path());
     trace_debug(format!(
-        "req-editor startup config path={} code_editor={} soft_wrap={} line_number={} show_whitespaces={} searchable=true",
+        "req-editor startup config path={} code_editor={} soft_wrap={} line_number={} show_whitespaces={} new_note_extension={} placeholder='{}' searchable=true",
         color_config_path.display(),
         editor_config.code_editor,
         editor_config.soft_wrap,
         editor_config.line_number,
-        editor_config.show_whitespaces
+        editor_config.show_whitespaces,
+        editor_config.new_note_extension.as_str(),
+        compact_text(&editor_config.placeholder)
     ));
 
     let window_position_path =
@@ -2300,12 +3554,19 @@ pub fn run() {
         "window_position splitter startup restore left_size={:?}",
         restored_splitter_left_size
     ));
+    let restored_editor_zoom = persisted_window_position
+        .as_ref()
+        .and_then(|state| state.editor_zoom());
+    let restored_window_overlay = persisted_window_position
+        .as_ref()
+        .map(|state| (state.always_on_top, state.opacity()))
+        .unwrap_or_default();
 
     let app = Application::new().with_assets(AppAssets);
 
     app.run(move |cx| {
         gpui_component::init(cx);
-        apply_req_colr_theme_overrides(ui_color_config, cx);
+        crate::color_schemes::apply_color_scheme(&color_scheme, cx);
 
         let primary_display = cx.primary_display();
         let primary_monitor_id = primary_display.as_ref().map(|display| u32::from(display.id()));
@@ -2379,11 +3640,14 @@ pub fn run() {
             startup_display_resolution.display_bounds,
             default_centered_bounds,
         );
+        let ignore_exact_position =
+            crate::window_position::should_ignore_exact_position_for_wayland();
         let startup_bounds = crate::window_position::resolve_startup_window_bounds(
             persisted_window_position.as_ref(),
             fallback_bounds,
-            startup_display_resolution.display_bounds,
-            crate::window_position::should_ignore_exact_position_for_wayland(),
+            startup_display_snapshots.as_slice(),
+            primary_monitor_id,
+            ignore_exact_position,
         );
         let startup_display_id_for_options = startup_display_id_for_window_options(
             persisted_window_position.as_ref(),
@@ -2412,6 +3676,30 @@ pub fn run() {
         let editor_config = editor_config;
         cx.spawn(async move |cx| {
             cx.open_window(window_options, move |window, cx| {
+                // The target monitor's scale is only known once the window is on it.
+                let startup_bounds = match persisted_window_position
+                    .as_ref()
+                    .and_then(|state| state.rescaled_for_dpi(window.scale_factor()))
+                {
+                    Some(rescaled) => {
+                        let rescaled_bounds = crate::window_position::resolve_startup_window_bounds(
+                            Some(&rescaled),
+                            fallback_bounds,
+                            startup_display_snapshots.as_slice(),
+                            primary_monitor_id,
+                            ignore_exact_position,
+                        );
+                        trace_debug(format!(
+                            "window_position startup dpi rescale saved_scale={:?} current_scale={} bounds={startup_bounds:?} rescaled_bounds={rescaled_bounds:?}",
+                            persisted_window_position
+                                .as_ref()
+                                .and_then(|state| state.dpi_scale),
+                            window.scale_factor(),
+                        ));
+                        rescaled_bounds
+                    }
+                    None => startup_bounds,
+                };
                 let startup_window_position_guard =
                     Rc::new(RefCell::new(startup_window_position_guard(
                         persisted_window_position
@@ -2433,6 +3721,7 @@ pub fn run() {
                         window,
                         app_paths,
                         restored_splitter_left_size,
+                        restored_editor_zoom,
                         app_startup_window_position_guard,
                         ui_color_config,
                         editor_config,
@@ -2440,69 +3729,109 @@ pub fn run() {
                     )
                 });
 
+                let (always_on_top, opacity) = restored_window_overlay;
+                view.update(cx, |app, cx| {
+                    app.restore_window_overlay(always_on_top, opacity, window, cx)
+                });
+
+                if let Some(handoff_rx) = instance_handoff_rx.clone() {
+                    view.update(cx, |_, cx| {
+                        Papyru2App::spawn_instance_handoff_loop(handoff_rx, window, cx)
+                    });
+                }
+
+                #[cfg(feature = "smoke-test")]
+                if let Some(steps) = smoke_steps.clone() {
+                    view.update(cx, |_, cx| Papyru2App::run_smoke_script(steps, window, cx));
+                }
+
+                if let Some(action) = cli_launch.clone() {
+                    let launch_view = view.clone();
+                    window.defer(cx, move |window, cx| {
+                        launch_view.update(cx, |app, cx| {
+                            app.apply_cli_launch_action(action, window, cx)
+                        });
+                    });
+                }
+
                 let close_save_path = window_position_path.clone();
                 let close_view = view.clone();
                 let close_startup_window_position_guard = startup_window_position_guard.clone();
-                window.on_window_should_close(cx, move |window, cx| {
-                    let pre_close_saved = cx.update_entity(&close_view, |app, cx| {
-                        app.flush_editor_content_before_context_switch("req-aus7-window-close", cx)
-                    });
-                    if !pre_close_saved {
-                        trace_debug("autosave pre-close aborted close");
-                        return false;
-                    }
+                let finish_close: crate::scratch_panes::FinishWindowClose =
+                    Rc::new(move |window: &mut Window, cx: &mut App| {
+                        let pre_close_saved = cx.update_entity(&close_view, |app, cx| {
+                            app.flush_editor_content_before_context_switch("req-aus7-window-close", cx)
+                        });
+                        if !pre_close_saved {
+                            trace_debug("autosave pre-close aborted close");
+                            return false;
+                        }
 
-                    let state = cx.update_entity(&close_view, |app, cx| {
-                        app.capture_window_position_state(window, cx)
-                    });
-                    let state = if let Some(observed_bounds) = state.to_window_bounds() {
-                        if let Some(expected_bounds) =
-                            startup_window_position_expected_bounds_for_close_save(
-                                &close_startup_window_position_guard.borrow(),
-                                observed_bounds,
-                            )
-                        {
-                            let guarded_state = crate::window_position::WindowPositionState::from_window_bounds(
-                                expected_bounds,
-                                state.monitor_id,
-                                state.monitor_uuid.clone(),
-                                state.dpi_scale,
-                            )
-                            .with_splitter_sizes(
+                        let state = cx.update_entity(&close_view, |app, cx| {
+                            app.capture_window_position_state(window, cx)
+                        });
+                        let state = if let Some(observed_bounds) = state.to_window_bounds() {
+                            if let Some(expected_bounds) =
+                                startup_window_position_expected_bounds_for_close_save(
+                                    &close_startup_window_position_guard.borrow(),
+                                    observed_bounds,
+                                )
+                            {
+                                let guarded_state = crate::window_position::WindowPositionState::from_window_bounds(
+                                    expected_bounds,
+                                    state.monitor_id,
+                                    state.monitor_uuid.clone(),
+                                    state.dpi_scale,
+                                )
+                                .with_splitter_sizes(
+                                    state
+                                        .splitter_sizes
+                                        .as_ref()
+                                        .map(|sizes| {
+                                            sizes.iter().copied().map(px).collect::<Vec<Pixels>>()
+                                        })
+                                        .unwrap_or_default()
+                                        .as_slice(),
+                                )
+                                .with_editor_zoom(state.editor_zoom)
+                                .with_window_overlay(state.always_on_top, state.opacity);
+                                trace_debug(format!(
+                                    "window_position close save guard replaced observed_bounds={observed_bounds:?} expected_bounds={expected_bounds:?}"
+                                ));
+                                guarded_state
+                            } else {
                                 state
-                                    .splitter_sizes
-                                    .as_ref()
-                                    .map(|sizes| {
-                                        sizes.iter().copied().map(px).collect::<Vec<Pixels>>()
-                                    })
-                                    .unwrap_or_default()
-                                    .as_slice(),
-                            );
-                            trace_debug(format!(
-                                "window_position close save guard replaced observed_bounds={observed_bounds:?} expected_bounds={expected_bounds:?}"
-                            ));
-                            guarded_state
+                            }
                         } else {
                             state
-                        }
-                    } else {
-                        state
-                    };
-                    trace_debug(format!(
-                        "window_position close save path={} {}",
-                        close_save_path.display(),
-                        window_position_state_trace(&state)
-                    ));
-                    if let Err(error) = crate::window_position::save_window_position_atomic(
-                        close_save_path.as_path(),
-                        &state,
-                    ) {
+                        };
                         trace_debug(format!(
-                            "window_position close save failed path={} error={error}",
-                            close_save_path.display()
+                            "window_position close save path={} {}",
+                            close_save_path.display(),
+                            window_position_state_trace(&state)
                         ));
-                    }
-                    true
+                        if let Err(error) = crate::window_position::save_window_position_atomic(
+                            close_save_path.as_path(),
+                            &state,
+                        ) {
+                            trace_debug(format!(
+                                "window_position close save failed path={} error={error}",
+                                close_save_path.display()
+                            ));
+                        }
+                        cx.update_entity(&close_view, |app, _| {
+                            app.shutdown_background_workers()
+                        });
+                        true
+                    });
+                let scratch_view = view.clone();
+                window.on_window_should_close(cx, move |window, cx| {
+                    crate::scratch_panes::confirm_scratch_panes_before_close(
+                        &scratch_view,
+                        &finish_close,
+                        window,
+                        cx,
+                    )
                 });
 
                 cx.new(|cx| Root::new(view, window, cx))
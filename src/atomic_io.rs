@@ -0,0 +1,441 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+const ATOMIC_TEMP_NAME_ATTEMPTS: u32 = 16;
+
+static ATOMIC_TEMP_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Replaces `path` with `bytes` so a reader sees either the old file or the new one, never a
+/// partial write. A failed replace leaves the old file untouched. A symlink is written through
+/// to its target, and the new file keeps the old one's permissions.
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    write_atomic_with_replace(path, bytes, replace_target_with_temp)
+}
+
+pub(crate) fn write_atomic_with_replace<F>(
+    path: &Path,
+    bytes: &[u8],
+    replace_fn: F,
+) -> io::Result<()>
+where
+    F: Fn(&Path, &Path) -> io::Result<()>,
+{
+    crate::file_update_handler::ensure_current_worker()?;
+    let path = resolve_symlinked_target(path)?;
+    let path = path.as_path();
+    let parent = path.parent().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "atomic write path has no parent directory: {}",
+                path.display()
+            ),
+        )
+    })?;
+    fs::create_dir_all(parent)?;
+
+    let (temp_path, mut temp_file) = create_temp_file(path).map_err(|error| {
+        io::Error::new(
+            error.kind(),
+            format!("atomic write failed (create temp): {error}"),
+        )
+    })?;
+    let mut temp_guard = TempFileGuard(Some(temp_path));
+    copy_target_permissions(path, &temp_file)
+        .and_then(|()| io::Write::write_all(&mut temp_file, bytes))
+        .map_err(|error| {
+            io::Error::new(
+                error.kind(),
+                format!("atomic write failed (write temp): {error}"),
+            )
+        })?;
+    temp_file.sync_all().map_err(|error| {
+        io::Error::new(
+            error.kind(),
+            format!("atomic write failed (sync temp): {error}"),
+        )
+    })?;
+    drop(temp_file);
+
+    let temp_path = temp_guard.path();
+    replace_fn(temp_path, path).map_err(|error| {
+        io::Error::new(
+            error.kind(),
+            format!("atomic write failed (replace target): {error}"),
+        )
+    })?;
+    temp_guard.disarm();
+
+    // The new content is in place either way; a failed directory sync only weakens the
+    // crash guarantee, so it must not report the write as failed.
+    if let Err(error) = sync_parent_dir(parent) {
+        crate::log::trace_debug(format!(
+            "atomic write sync parent failed path={} error={error}",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+// Removes the temp file when the write bails out at any point before the replace went through.
+struct TempFileGuard(Option<PathBuf>);
+
+impl TempFileGuard {
+    fn path(&self) -> &Path {
+        self.0.as_deref().expect("temp file guard already disarmed")
+    }
+
+    fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for TempFileGuard {
+    fn drop(&mut self) {
+        let Some(path) = self.0.take() else {
+            return;
+        };
+        if let Err(error) = cleanup_temp_file(&path) {
+            crate::log::trace_debug(format!(
+                "atomic write cleanup temp failed path={} error={error}",
+                path.display()
+            ));
+        }
+    }
+}
+
+// Two writers of the same target (autosave and an RPC write, or two app instances) must not
+// share a temp file, so each attempt gets its own name and is created exclusively.
+fn create_temp_file(path: &Path) -> io::Result<(PathBuf, fs::File)> {
+    let mut last_error = None;
+    for _ in 0..ATOMIC_TEMP_NAME_ATTEMPTS {
+        let temp_path =
+            temp_path_for_atomic_write(path, ATOMIC_TEMP_SEQUENCE.fetch_add(1, Ordering::Relaxed))?;
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)
+        {
+            Ok(file) => return Ok((temp_path, file)),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {
+                last_error = Some(error);
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| io::Error::from(io::ErrorKind::AlreadyExists)))
+}
+
+fn temp_path_for_atomic_write(path: &Path, sequence: u64) -> io::Result<PathBuf> {
+    let parent = path.parent().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "atomic write path has no parent directory: {}",
+                path.display()
+            ),
+        )
+    })?;
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("atomic write path has no file name: {}", path.display()),
+        )
+    })?;
+    Ok(parent.join(format!(
+        "{}.{}-{sequence}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    )))
+}
+
+// The rename would otherwise swap the link itself for a plain file, e.g. a file opened from
+// outside the vault through a link in the user's dotfiles.
+fn resolve_symlinked_target(path: &Path) -> io::Result<PathBuf> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_symlink() => fs::canonicalize(path),
+        _ => Ok(path.to_path_buf()),
+    }
+}
+
+// The temp file is created with default permissions. ReplaceFileW keeps the target's ACLs
+// and attributes on Windows; elsewhere the mode is copied over before the rename.
+#[cfg(unix)]
+fn copy_target_permissions(target: &Path, temp_file: &fs::File) -> io::Result<()> {
+    match fs::metadata(target) {
+        Ok(metadata) => temp_file.set_permissions(metadata.permissions()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(error),
+    }
+}
+
+#[cfg(not(unix))]
+fn copy_target_permissions(_target: &Path, _temp_file: &fs::File) -> io::Result<()> {
+    Ok(())
+}
+
+fn cleanup_temp_file(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(error),
+    }
+}
+
+fn replace_target_with_temp(temp_path: &Path, target_path: &Path) -> io::Result<()> {
+    // Safety invariant: never delete the existing target before a replacement operation succeeds.
+    // On replace failure, caller keeps the last-good target file intact.
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::ffi::OsStrExt;
+        use std::ptr::{null, null_mut};
+
+        use windows_sys::Win32::Storage::FileSystem::ReplaceFileW;
+
+        if !target_path.exists() {
+            return fs::rename(temp_path, target_path);
+        }
+
+        let mut target_wide = target_path
+            .as_os_str()
+            .encode_wide()
+            .chain(Some(0))
+            .collect::<Vec<u16>>();
+        let mut temp_wide = temp_path
+            .as_os_str()
+            .encode_wide()
+            .chain(Some(0))
+            .collect::<Vec<u16>>();
+
+        let result = unsafe {
+            ReplaceFileW(
+                target_wide.as_mut_ptr(),
+                temp_wide.as_mut_ptr(),
+                null(),
+                0,
+                null_mut(),
+                null_mut(),
+            )
+        };
+        if result == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        return Ok(());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        fs::rename(temp_path, target_path)
+    }
+}
+
+/// Moves `from` to `to`, failing with `AlreadyExists` instead of replacing whatever is at `to`
+/// by the time the move runs.
+pub(crate) fn rename_no_clobber(from: &Path, to: &Path) -> io::Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::ffi::OsStrExt;
+
+        use windows_sys::Win32::Storage::FileSystem::MoveFileExW;
+
+        let from_wide = from
+            .as_os_str()
+            .encode_wide()
+            .chain(Some(0))
+            .collect::<Vec<u16>>();
+        let to_wide = to
+            .as_os_str()
+            .encode_wide()
+            .chain(Some(0))
+            .collect::<Vec<u16>>();
+        // Without MOVEFILE_REPLACE_EXISTING the move fails when `to` exists.
+        let result = unsafe { MoveFileExW(from_wide.as_ptr(), to_wide.as_ptr(), 0) };
+        if result == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        return Ok(());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        // rename(2) replaces a file or an empty folder at `to`, so claim the name first with a
+        // call that fails when it is taken, then move onto the placeholder.
+        let is_dir = fs::symlink_metadata(from)?.is_dir();
+        if is_dir {
+            fs::create_dir(to)?;
+        } else {
+            fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(to)?;
+        }
+        fs::rename(from, to).inspect_err(|_| {
+            let _ = if is_dir {
+                fs::remove_dir(to)
+            } else {
+                fs::remove_file(to)
+            };
+        })
+    }
+}
+
+// The rename is only durable once the directory entry is on disk. Windows has no directory
+// handle to sync; ReplaceFileW/MoveFile already go through the journal there.
+fn sync_parent_dir(parent: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        fs::File::open(parent)?.sync_all()
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = parent;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_atomic_io_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&root).expect("create temp root");
+        root
+    }
+
+    fn dir_entries(root: &Path) -> Vec<PathBuf> {
+        let mut entries = fs::read_dir(root)
+            .expect("read dir")
+            .map(|entry| entry.expect("entry").path())
+            .collect::<Vec<_>>();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn aio_test1_write_creates_parent_and_leaves_no_temp_files() {
+        let root = new_temp_root("aio_test1");
+        let path = root.join("nested").join("note.txt");
+
+        write_atomic(&path, b"first").expect("first write");
+        write_atomic(&path, b"second").expect("second write");
+
+        assert_eq!(fs::read_to_string(&path).expect("read"), "second");
+        assert_eq!(dir_entries(&root.join("nested")), vec![path]);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn aio_test2_stale_temp_name_is_skipped_and_failure_keeps_target() {
+        let root = new_temp_root("aio_test2");
+        let path = root.join("note.txt");
+        fs::write(&path, "old").expect("seed");
+        // Whatever name comes next is already taken by a leftover from another writer.
+        let next = ATOMIC_TEMP_SEQUENCE.load(Ordering::Relaxed);
+        let stale = (next..next + 4)
+            .map(|sequence| temp_path_for_atomic_write(&path, sequence).expect("temp path"))
+            .collect::<Vec<_>>();
+        for temp in &stale {
+            fs::write(temp, "stale").expect("write stale temp");
+        }
+
+        let error = write_atomic_with_replace(&path, b"new", |temp, _target| {
+            assert!(!stale.contains(&temp.to_path_buf()));
+            Err(io::Error::other("forced replace failure"))
+        })
+        .expect_err("forced replace failure expected");
+        assert!(error.to_string().contains("replace target"));
+        assert_eq!(fs::read_to_string(&path).expect("read"), "old");
+        assert_eq!(dir_entries(&root).len(), 1 + stale.len());
+
+        write_atomic(&path, b"new").expect("write");
+        assert_eq!(fs::read_to_string(&path).expect("read"), "new");
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn aio_test3_write_keeps_the_symlink_and_the_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = new_temp_root("aio_test3");
+        let target = root.join("real.txt");
+        let link = root.join("link.txt");
+        fs::write(&target, "old").expect("seed");
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o640)).expect("chmod");
+        std::os::unix::fs::symlink(&target, &link).expect("symlink");
+
+        write_atomic(&link, b"new").expect("write through link");
+        assert!(
+            fs::symlink_metadata(&link)
+                .expect("link metadata")
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(fs::read_to_string(&target).expect("read"), "new");
+        assert_eq!(
+            fs::metadata(&target)
+                .expect("metadata")
+                .permissions()
+                .mode()
+                & 0o777,
+            0o640
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn aio_test4_no_clobber_rename_keeps_an_existing_target() {
+        let root = new_temp_root("aio_test4");
+        let from = root.join("a.txt");
+        let taken = root.join("b.txt");
+        fs::write(&from, "moving").expect("seed from");
+        fs::write(&taken, "kept").expect("seed taken");
+        let error = rename_no_clobber(&from, &taken).expect_err("taken name");
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&taken).expect("read taken"), "kept");
+        assert_eq!(fs::read_to_string(&from).expect("read from"), "moving");
+
+        let folder = root.join("folder");
+        fs::create_dir(&folder).expect("folder");
+        fs::write(folder.join("n.txt"), "x").expect("seed folder");
+        fs::create_dir(root.join("empty")).expect("empty");
+        let error = rename_no_clobber(&folder, &root.join("empty")).expect_err("empty folder");
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+
+        rename_no_clobber(&folder, &root.join("moved")).expect("free name");
+        assert!(root.join("moved").join("n.txt").is_file());
+        assert!(!folder.exists());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn aio_test5_temp_file_is_removed_even_when_the_replace_panics() {
+        let root = new_temp_root("aio_test5");
+        let path = root.join("note.txt");
+        fs::write(&path, "old").expect("seed");
+
+        let panicked = std::panic::catch_unwind(|| {
+            let _ = write_atomic_with_replace(&path, b"new", |_temp, _target| {
+                panic!("replace blew up")
+            });
+        });
+        assert!(panicked.is_err());
+        assert_eq!(fs::read_to_string(&path).expect("read"), "old");
+        assert_eq!(dir_entries(&root), vec![path.clone()]);
+        let _ = fs::remove_dir_all(root);
+    }
+}
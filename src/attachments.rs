@@ -0,0 +1,234 @@
+use std::{
+    fs, io,
+    io::Write as _,
+    path::{Component, Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use gpui::{Context, Image, ImageFormat, Window};
+
+use crate::log::trace_debug;
+
+/// Pasted images go into this folder at the vault root and are linked as `/assets/...`, so the
+/// link keeps working when the note is moved or filed into today's folder.
+pub(crate) const ATTACHMENTS_DIR_NAME: &str = "assets";
+
+pub(crate) fn image_file_extension(format: ImageFormat) -> &'static str {
+    match format {
+        ImageFormat::Png => "png",
+        ImageFormat::Jpeg => "jpg",
+        ImageFormat::Webp => "webp",
+        ImageFormat::Gif => "gif",
+        ImageFormat::Svg => "svg",
+        ImageFormat::Bmp => "bmp",
+        ImageFormat::Tiff => "tiff",
+    }
+}
+
+/// Writes `bytes` to `<vault>/assets/<note stem>-<timestamp>.<extension>`, never overwriting
+/// an earlier attachment.
+pub(crate) fn save_note_attachment(
+    vault_root: &Path,
+    note_path: &Path,
+    bytes: &[u8],
+    extension: &str,
+    now: DateTime<Local>,
+) -> io::Result<PathBuf> {
+    let stem = note_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or_else(|| "note".to_string());
+    let assets_dir = vault_root.join(ATTACHMENTS_DIR_NAME);
+    fs::create_dir_all(&assets_dir)?;
+
+    let base = format!("{stem}-{}", now.format("%Y%m%d-%H%M%S"));
+    let mut suffix = 1usize;
+    loop {
+        let file_name = if suffix == 1 {
+            format!("{base}.{extension}")
+        } else {
+            format!("{base}_{suffix}.{extension}")
+        };
+        let target = assets_dir.join(file_name);
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&target)
+        {
+            Ok(mut file) => {
+                if let Err(error) = file.write_all(bytes).and_then(|()| file.sync_all()) {
+                    drop(file);
+                    let _ = fs::remove_file(&target);
+                    return Err(error);
+                }
+                crate::storage_backend::storage_backend_containing(target.as_path())
+                    .added(target.as_path());
+                return Ok(target);
+            }
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => suffix += 1,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// `![](/assets/name.png)`: rooted at the vault, with `/` separators on every platform.
+pub(crate) fn markdown_image_link(vault_root: &Path, attachment_path: &Path) -> Option<String> {
+    let relative = attachment_path.strip_prefix(vault_root).ok()?;
+    let target = relative
+        .components()
+        .map(|component| {
+            crate::notes_import::percent_encode_link_segment(
+                &component.as_os_str().to_string_lossy(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    Some(format!("![](/{target})"))
+}
+
+/// The target of a block that is nothing but one markdown image.
+pub(crate) fn markdown_image_target(block_text: &str) -> Option<&str> {
+    let rest = block_text.trim().strip_prefix("![")?;
+    let (_, rest) = rest.split_once("](")?;
+    let target = rest.strip_suffix(')')?;
+    (!target.is_empty() && !target.contains(')')).then_some(target)
+}
+
+/// A local image target: `/...` inside `vault_root`, anything else inside `note_dir`. URLs,
+/// drive paths and `..` are ignored.
+pub(crate) fn resolve_attachment_path(
+    vault_root: &Path,
+    note_dir: &Path,
+    target: &str,
+) -> Option<PathBuf> {
+    if target.contains("://") {
+        return None;
+    }
+    let (base, target) = match target.strip_prefix('/') {
+        Some(rooted) => (vault_root, rooted),
+        None => (note_dir, target),
+    };
+    let relative = PathBuf::from(crate::notes_import::percent_decode_link(target));
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return None;
+    }
+    Some(base.join(relative))
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn paste_image_attachment(
+        &mut self,
+        image: Image,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(note_path) = self.file_workflow.current_edit_path() else {
+            self.show_transient_notice(crate::i18n::tr("notice.paste_needs_title").to_string(), cx);
+            return;
+        };
+        let vault_root = self.app_paths.user_document_dir.clone();
+        let saved = save_note_attachment(
+            vault_root.as_path(),
+            note_path.as_path(),
+            &image.bytes,
+            image_file_extension(image.format),
+            Local::now(),
+        );
+        let attachment = match saved {
+            Ok(attachment) => attachment,
+            Err(error) => {
+                self.show_transient_notice(
+                    crate::i18n::tr_with(
+                        "notice.paste_save_failed",
+                        &[("error", error.to_string().as_str())],
+                    ),
+                    cx,
+                );
+                return;
+            }
+        };
+        trace_debug(format!(
+            "req-att1 image pasted note={} attachment={} bytes={}",
+            note_path.display(),
+            attachment.display(),
+            image.bytes.len()
+        ));
+        let Some(link) = markdown_image_link(vault_root.as_path(), attachment.as_path()) else {
+            return;
+        };
+        self.editor.update(cx, |editor, cx| {
+            editor.insert_text_at_cursor(&link, window, cx)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn att_test1_attachment_is_saved_under_the_vault_and_linked_from_its_root() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_attachments_att_test1_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        let day = root.join("2026").join("03").join("07");
+        fs::create_dir_all(&day).expect("create day");
+        let note = day.join("trip notes.md");
+        let now = Local.with_ymd_and_hms(2026, 3, 7, 9, 30, 0).unwrap();
+
+        let first = save_note_attachment(&root, &note, b"png-1", "png", now).expect("save");
+        let second = save_note_attachment(&root, &note, b"png-2", "png", now).expect("save");
+        assert_eq!(
+            first,
+            root.join(ATTACHMENTS_DIR_NAME)
+                .join("trip notes-20260307-093000.png")
+        );
+        assert_eq!(
+            second,
+            root.join(ATTACHMENTS_DIR_NAME)
+                .join("trip notes-20260307-093000_2.png")
+        );
+        assert_eq!(fs::read(&first).expect("read"), b"png-1");
+
+        let link = markdown_image_link(&root, &first).expect("link");
+        assert_eq!(link, "![](/assets/trip%20notes-20260307-093000.png)");
+        let target = markdown_image_target(&link).expect("image block");
+        assert_eq!(resolve_attachment_path(&root, &day, target), Some(first));
+        // The same link resolves from whatever folder the note is moved to.
+        assert_eq!(
+            resolve_attachment_path(&root, &root.join("archive"), target),
+            resolve_attachment_path(&root, &day, target)
+        );
+        // Links written before attachments moved to the vault root still resolve beside the note.
+        assert_eq!(
+            resolve_attachment_path(&root, &day, "assets/old.png"),
+            Some(day.join("assets").join("old.png"))
+        );
+        assert_eq!(resolve_attachment_path(&root, &day, "../secret.png"), None);
+        assert_eq!(resolve_attachment_path(&root, &day, "/../secret.png"), None);
+        assert_eq!(
+            resolve_attachment_path(&root, &day, "https://example.com/a.png"),
+            None
+        );
+        assert_eq!(markdown_image_target("see ![](a.png) here"), None);
+
+        let odd = root.join(ATTACHMENTS_DIR_NAME).join("100% #1 (draft).png");
+        let link = markdown_image_link(&root, &odd).expect("link");
+        assert_eq!(link, "![](/assets/100%25%20%231%20%28draft%29.png)");
+        let target = markdown_image_target(&link).expect("image block");
+        assert_eq!(resolve_attachment_path(&root, &day, target), Some(odd));
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -173,6 +173,7 @@ mod tests {
                 .join("recyclebin"),
             log_dir: app_home.join("log"),
             bin_dir: app_home.join("bin"),
+            override_report: Vec::new(),
         };
         paths.ensure_dirs().expect("ensure app dirs");
         paths
@@ -12,12 +12,14 @@ use zip::write::SimpleFileOptions;
 const APP_BINARY_NAME: &str = "papyru2";
 const PIN_BINARY_NAME: &str = "papyru2_pin_file";
 const TEXTFILE_IMPORT_BINARY_NAME: &str = "papyru2_textfile_import";
+const NOTES_IMPORT_BINARY_NAME: &str = "papyru2_notes_import";
 const PORTABLE_MARKER_FILE: &str = "papyru2.portable";
 const CONFIG_FILE_NAME: &str = "papyru2_conf.toml";
-const PORTABLE_BINARY_NAMES: [&str; 3] = [
+const PORTABLE_BINARY_NAMES: [&str; 4] = [
     APP_BINARY_NAME,
     PIN_BINARY_NAME,
     TEXTFILE_IMPORT_BINARY_NAME,
+    NOTES_IMPORT_BINARY_NAME,
 ];
 
 fn main() {
@@ -375,9 +377,11 @@ mod tests {
         let app_binary = platform.executable_name(APP_BINARY_NAME);
         let pin_binary = platform.executable_name(PIN_BINARY_NAME);
         let import_binary = platform.executable_name(TEXTFILE_IMPORT_BINARY_NAME);
+        let notes_import_binary = platform.executable_name(NOTES_IMPORT_BINARY_NAME);
         fs::write(bin_dir.join(&app_binary), b"main-binary")?;
         fs::write(bin_dir.join(&pin_binary), b"pin-binary")?;
         fs::write(bin_dir.join(&import_binary), b"import-binary")?;
+        fs::write(bin_dir.join(&notes_import_binary), b"notes-import-binary")?;
 
         let artifact =
             package_portable_release(platform, version, &bin_dir, &out_dir, &config_path)?;
@@ -413,6 +417,11 @@ mod tests {
                 .by_name(&format!("{root_name}/bin/{import_binary}"))
                 .is_ok()
         );
+        assert!(
+            archive
+                .by_name(&format!("{root_name}/bin/{notes_import_binary}"))
+                .is_ok()
+        );
         assert!(
             archive
                 .by_name(&format!("{root_name}/conf/{CONFIG_FILE_NAME}"))
@@ -0,0 +1,357 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+pub(crate) const BOOKMARKS_FILE_NAME: &str = "bookmarks.json";
+
+type BookmarkMap = BTreeMap<PathBuf, BTreeSet<u32>>;
+
+// Bookmarks are stored as 0-based editor lines per note under `data_dir/bookmarks.json`.
+#[derive(Clone, Debug)]
+pub(crate) struct BookmarkStore {
+    store_path: PathBuf,
+    inner: Arc<Mutex<BookmarkMap>>,
+}
+
+impl BookmarkStore {
+    pub fn open(data_dir: &Path) -> io::Result<Self> {
+        let store_path = data_dir.join(BOOKMARKS_FILE_NAME);
+        let mut bookmarks = match fs::read(&store_path) {
+            Ok(bytes) => serde_json::from_slice::<BookmarkMap>(&bytes)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => BookmarkMap::new(),
+            Err(error) => return Err(error),
+        };
+        let before = bookmarks.len();
+        bookmarks.retain(|path, _| path.is_file());
+        crate::log::trace_debug(format!(
+            "req-bmk1 bookmarks open path={} notes={} pruned={}",
+            store_path.display(),
+            bookmarks.len(),
+            before - bookmarks.len()
+        ));
+        Ok(Self {
+            store_path,
+            inner: Arc::new(Mutex::new(bookmarks)),
+        })
+    }
+
+    fn save(&self, bookmarks: &BookmarkMap) -> io::Result<()> {
+        let bytes = serde_json::to_vec_pretty(bookmarks)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+        crate::atomic_io::write_atomic(self.store_path.as_path(), &bytes)
+    }
+
+    /// Returns `true` when the line is bookmarked after the toggle.
+    pub fn toggle(&self, path: &Path, line: u32) -> io::Result<bool> {
+        let mut bookmarks = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let lines = bookmarks.entry(path.to_path_buf()).or_default();
+        let bookmarked = lines.insert(line) || !lines.remove(&line);
+        if lines.is_empty() {
+            bookmarks.remove(path);
+        }
+        self.save(&bookmarks)?;
+        Ok(bookmarked)
+    }
+
+    pub fn lines_for(&self, path: &Path) -> BTreeSet<u32> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(path)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn entries(&self) -> Vec<(PathBuf, u32)> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .flat_map(|(path, lines)| lines.iter().map(move |line| (path.clone(), *line)))
+            .collect()
+    }
+
+    pub fn rename_note(&self, old_path: &Path, new_path: &Path) -> io::Result<bool> {
+        let mut bookmarks = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Some(lines) = bookmarks.remove(old_path) else {
+            return Ok(false);
+        };
+        bookmarks.insert(new_path.to_path_buf(), lines);
+        self.save(&bookmarks)?;
+        Ok(true)
+    }
+
+    /// Replaces the note's bookmarked lines, e.g. after an edit moved them.
+    pub fn set_lines(&self, path: &Path, lines: BTreeSet<u32>) -> io::Result<()> {
+        let mut bookmarks = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if lines.is_empty() {
+            bookmarks.remove(path);
+        } else {
+            bookmarks.insert(path.to_path_buf(), lines);
+        }
+        self.save(&bookmarks)
+    }
+}
+
+/// Moves bookmarked lines along with an edit from `previous` to `value`: lines below the edit
+/// shift by the number of lines it added or removed, lines merged into the edited line fold
+/// onto it, and bookmarks on whole lines that were deleted are dropped.
+pub(crate) fn shift_lines_for_edit(
+    lines: &BTreeSet<u32>,
+    previous: &str,
+    value: &str,
+) -> BTreeSet<u32> {
+    let (range, replacement) = crate::editor::changed_span(previous, value);
+    let removed_text = &previous[range.clone()];
+    let start_line = previous[..range.start].matches('\n').count() as i64;
+    let removed = removed_text.matches('\n').count() as i64;
+    let inserted = replacement.matches('\n').count() as i64;
+    let at_line_start = range.start == 0 || previous[..range.start].ends_with('\n');
+    let whole_lines = at_line_start
+        && (removed_text.is_empty() || removed_text.ends_with('\n'))
+        && (replacement.is_empty() || replacement.ends_with('\n'));
+    // A whole-line edit owns lines `start..start + removed`; any other edit keeps the start
+    // line and swallows the `removed` lines after it.
+    let first_moved = if whole_lines {
+        start_line + removed
+    } else {
+        start_line + removed + 1
+    };
+    lines
+        .iter()
+        .filter_map(|&line| {
+            let line = i64::from(line);
+            if line < start_line || (!whole_lines && line == start_line) {
+                Some(line)
+            } else if line >= first_moved {
+                Some(line + inserted - removed)
+            } else if whole_lines && inserted == 0 {
+                None
+            } else {
+                Some(start_line)
+            }
+        })
+        .filter_map(|line| u32::try_from(line).ok())
+        .collect()
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn toggle_bookmark_at_line(&mut self, line: u32, cx: &mut gpui::Context<Self>) {
+        let Some(bookmarks) = self.bookmarks.as_ref() else {
+            return;
+        };
+        let Some(path) = self.file_workflow.current_edit_path() else {
+            crate::log::trace_debug("req-bmk1 toggle skipped reason=no-current-note");
+            return;
+        };
+        match bookmarks.toggle(path.as_path(), line) {
+            Ok(bookmarked) => crate::log::trace_debug(format!(
+                "req-bmk1 toggle path={} line={} bookmarked={}",
+                path.display(),
+                line,
+                bookmarked
+            )),
+            Err(error) => crate::log::trace_debug(format!(
+                "req-bmk1 toggle failed path={} line={} error={error}",
+                path.display(),
+                line
+            )),
+        }
+        self.sync_bookmarks_to_components(cx);
+    }
+
+    pub(crate) fn store_shifted_bookmarks(
+        &mut self,
+        lines: BTreeSet<u32>,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        let Some(bookmarks) = self.bookmarks.as_ref() else {
+            return;
+        };
+        let Some(path) = self.file_workflow.current_edit_path() else {
+            return;
+        };
+        if let Err(error) = bookmarks.set_lines(path.as_path(), lines) {
+            crate::log::trace_debug(format!(
+                "req-bmk1 shifted bookmarks save failed path={} error={error}",
+                path.display()
+            ));
+        }
+        self.sync_bookmarks_to_components(cx);
+    }
+
+    pub(crate) fn sync_bookmarks_to_components(&mut self, cx: &mut gpui::Context<Self>) {
+        let Some(bookmarks) = self.bookmarks.clone() else {
+            return;
+        };
+        let lines = self
+            .editor
+            .read(cx)
+            .current_editing_file_path()
+            .map(|path| bookmarks.lines_for(path.as_path()))
+            .unwrap_or_default();
+        self.editor
+            .update(cx, |editor, cx| editor.set_bookmarked_lines(lines, cx));
+        let entries = bookmarks.entries();
+        self.file_tree
+            .update(cx, |file_tree, cx| file_tree.set_bookmarks(entries, cx));
+    }
+
+    pub(crate) fn migrate_bookmarks_on_rename(&self, old_path: &Path, new_path: &Path) {
+        let Some(bookmarks) = self.bookmarks.as_ref() else {
+            return;
+        };
+        // Only a real on-disk rename carries bookmarks over; switching notes does not.
+        if old_path == new_path || old_path.exists() || !new_path.is_file() {
+            return;
+        }
+        match bookmarks.rename_note(old_path, new_path) {
+            Ok(true) => crate::log::trace_debug(format!(
+                "req-bmk1 bookmarks moved old={} new={}",
+                old_path.display(),
+                new_path.display()
+            )),
+            Ok(false) => {}
+            Err(error) => crate::log::trace_debug(format!(
+                "req-bmk1 bookmarks move failed old={} new={} error={error}",
+                old_path.display(),
+                new_path.display()
+            )),
+        }
+    }
+
+    pub(crate) fn open_bookmark(
+        &mut self,
+        path: PathBuf,
+        line: u32,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        let already_open = self.file_workflow.current_edit_path().as_ref() == Some(&path);
+        crate::log::trace_debug(format!(
+            "req-bmk1 open bookmark path={} line={} already_open={}",
+            path.display(),
+            line,
+            already_open
+        ));
+        let jump =
+            move |this: &mut Self, window: &mut gpui::Window, cx: &mut gpui::Context<Self>| {
+                this.editor.update(cx, |editor, cx| {
+                    editor.apply_cursor(line, 0, window, cx);
+                    editor.focus(window, cx);
+                });
+            };
+        if already_open {
+            jump(self, window, cx);
+        } else {
+            self.open_file_then(path, window, cx, jump);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_bookmark_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    #[test]
+    fn bookmark_test1_toggle_persists_and_reopens() {
+        let root = new_temp_root("bookmark_test1");
+        let note = root.join("note.txt");
+        fs::write(&note, "a\nb\nc").expect("write note");
+
+        let store = BookmarkStore::open(root.as_path()).expect("open store");
+        assert!(store.toggle(note.as_path(), 2).expect("toggle on"));
+        assert!(store.toggle(note.as_path(), 0).expect("toggle on"));
+        assert!(!store.toggle(note.as_path(), 2).expect("toggle off"));
+
+        let reopened = BookmarkStore::open(root.as_path()).expect("reopen store");
+        assert_eq!(reopened.lines_for(note.as_path()), BTreeSet::from([0]));
+        assert_eq!(reopened.entries(), vec![(note, 0)]);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn bookmark_test2_rename_moves_lines_and_open_prunes_missing_notes() {
+        let root = new_temp_root("bookmark_test2");
+        let old_note = root.join("old.txt");
+        let new_note = root.join("new.txt");
+        fs::write(&old_note, "x").expect("write note");
+
+        let store = BookmarkStore::open(root.as_path()).expect("open store");
+        store.toggle(old_note.as_path(), 4).expect("toggle");
+        fs::rename(&old_note, &new_note).expect("rename note");
+        assert!(
+            store
+                .rename_note(old_note.as_path(), new_note.as_path())
+                .expect("rename bookmarks")
+        );
+        assert_eq!(store.lines_for(new_note.as_path()), BTreeSet::from([4]));
+
+        fs::remove_file(&new_note).expect("remove note");
+        let reopened = BookmarkStore::open(root.as_path()).expect("reopen store");
+        assert!(reopened.entries().is_empty());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn bookmark_test3_lines_follow_inserted_and_deleted_lines() {
+        let lines = BTreeSet::from([0, 2, 3]);
+        let text = "a\nb\nc\nd";
+
+        // Enter at the end of "a" pushes everything below it down.
+        assert_eq!(
+            shift_lines_for_edit(&lines, text, "a\n\nb\nc\nd"),
+            BTreeSet::from([0, 3, 4])
+        );
+        // Pasting two whole lines above "c" moves "c" itself.
+        assert_eq!(
+            shift_lines_for_edit(&lines, text, "a\nb\nx\ny\nc\nd"),
+            BTreeSet::from([0, 4, 5])
+        );
+        // Deleting the whole "b" line moves the bookmarks below it up.
+        assert_eq!(
+            shift_lines_for_edit(&lines, text, "a\nc\nd"),
+            BTreeSet::from([0, 1, 2])
+        );
+        // Deleting the bookmarked "c" line drops its bookmark.
+        assert_eq!(
+            shift_lines_for_edit(&lines, text, "a\nb\nd"),
+            BTreeSet::from([0, 2])
+        );
+        // Backspace at the head of "d" joins it onto the bookmarked "c" line.
+        assert_eq!(
+            shift_lines_for_edit(&lines, text, "a\nb\ncd"),
+            BTreeSet::from([0, 2])
+        );
+        // Typing within a line moves nothing.
+        assert_eq!(shift_lines_for_edit(&lines, text, "a\nbb\nc\nd"), lines);
+    }
+}
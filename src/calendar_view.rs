@@ -0,0 +1,464 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Datelike, Local, Months, NaiveDate};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex, v_flex,
+};
+
+use crate::log::trace_debug;
+use crate::pickers::{CALENDAR_CELL_WIDTH_PX, CALENDAR_WEEKDAY_LABELS, month_grid};
+use crate::storage_layout::StorageLayout;
+
+/// Which view fills the left panel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum LeftPanelTab {
+    #[default]
+    Files,
+    Calendar,
+}
+
+/// The folder a day's notes are filed into under `layout`.
+pub(crate) fn day_folder(layout: &StorageLayout, root: &Path, date: NaiveDate) -> PathBuf {
+    layout.date_directory(root, date)
+}
+
+/// Notes of each day in `month`, keyed by day of month and sorted by name. A folder that
+/// holds a single day (the daily layout) dates its notes by the folder; one shared by several
+/// days (monthly, flat, weekly patterns) dates them by modification time. Days without notes
+/// are left out; a missing folder is an empty month.
+pub(crate) fn note_days_in_month(
+    layout: &StorageLayout,
+    root: &Path,
+    month: NaiveDate,
+) -> io::Result<BTreeMap<u32, Vec<PathBuf>>> {
+    let mut folders = BTreeMap::<PathBuf, Vec<u32>>::new();
+    for day in (1..=31).filter(|day| month.with_day(*day).is_some()) {
+        if let Some(date) = month.with_day(day) {
+            folders
+                .entry(day_folder(layout, root, date))
+                .or_default()
+                .push(day);
+        }
+    }
+    let mut days = BTreeMap::<u32, Vec<PathBuf>>::new();
+    for (folder, folder_days) in folders {
+        let entries = match fs::read_dir(&folder) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(error),
+        };
+        for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+            if !path.is_file()
+                || crate::file_update_handler::NoteFileExtension::from_path(&path).is_none()
+            {
+                continue;
+            }
+            let day = if let [day] = folder_days.as_slice() {
+                Some(*day)
+            } else {
+                fs::metadata(&path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .map(|modified| chrono::DateTime::<Local>::from(modified).date_naive())
+                    .filter(|date| date.with_day(1) == Some(month))
+                    .map(|date| date.day())
+                    .filter(|day| folder_days.contains(day))
+            };
+            if let Some(day) = day {
+                days.entry(day).or_default().push(path);
+            }
+        }
+    }
+    for notes in days.values_mut() {
+        notes.sort();
+    }
+    Ok(days)
+}
+
+#[derive(Clone, Debug)]
+pub enum CalendarEvent {
+    DayClicked {
+        date: NaiveDate,
+        first_note: Option<PathBuf>,
+    },
+}
+
+/// Month calendar for the left panel; days with notes are highlighted. The month is scanned
+/// in the background, and only while the calendar tab is showing; changes made while it is
+/// hidden are picked up when it is shown again.
+pub struct CalendarView {
+    root: PathBuf,
+    month: NaiveDate,
+    note_days: BTreeMap<u32, Vec<PathBuf>>,
+    visible: bool,
+    stale: bool,
+    scan_generation: u64,
+}
+
+impl EventEmitter<CalendarEvent> for CalendarView {}
+
+impl CalendarView {
+    pub fn new(root: PathBuf) -> Self {
+        let today = Local::now().date_naive();
+        Self {
+            root,
+            month: today.with_day(1).unwrap_or(today),
+            note_days: BTreeMap::new(),
+            visible: false,
+            stale: true,
+            scan_generation: 0,
+        }
+    }
+
+    pub fn set_root(&mut self, root: PathBuf, cx: &mut Context<Self>) {
+        self.root = root;
+        self.note_days.clear();
+        self.refresh(cx);
+    }
+
+    pub fn set_visible(&mut self, visible: bool, cx: &mut Context<Self>) {
+        self.visible = visible;
+        if visible && self.stale {
+            self.refresh(cx);
+        }
+    }
+
+    /// Called after the file tree watcher reports changes.
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        if !self.visible {
+            self.stale = true;
+            return;
+        }
+        self.stale = false;
+        self.scan_generation += 1;
+        let generation = self.scan_generation;
+        let layout = crate::storage_layout::active_storage_layout();
+        let root = self.root.clone();
+        let month = self.month;
+        let scan = cx
+            .background_executor()
+            .spawn(async move { note_days_in_month(&layout, root.as_path(), month) });
+        cx.spawn(async move |this, cx| {
+            let note_days = scan.await;
+            let _ = this.update(cx, |calendar, cx| {
+                calendar.finish_scan(generation, month, note_days, cx)
+            });
+        })
+        .detach();
+    }
+
+    fn finish_scan(
+        &mut self,
+        generation: u64,
+        month: NaiveDate,
+        note_days: io::Result<BTreeMap<u32, Vec<PathBuf>>>,
+        cx: &mut Context<Self>,
+    ) {
+        // A newer scan (another month, another vault) owns the grid now.
+        if generation != self.scan_generation {
+            return;
+        }
+        self.note_days = note_days.unwrap_or_else(|error| {
+            trace_debug(format!(
+                "req-cal month scan failed root={} month={} error={error}",
+                self.root.display(),
+                month.format("%Y-%m")
+            ));
+            BTreeMap::new()
+        });
+        cx.notify();
+    }
+
+    fn show_month(&mut self, month: Option<NaiveDate>, cx: &mut Context<Self>) {
+        if let Some(month) = month {
+            self.month = month;
+            self.note_days.clear();
+            self.refresh(cx);
+            cx.notify();
+        }
+    }
+}
+
+impl Render for CalendarView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let today = Local::now().date_naive();
+        let month = self.month;
+        let header = h_flex()
+            .gap_1()
+            .items_center()
+            .child(
+                Button::new("calendar-prev")
+                    .xsmall()
+                    .ghost()
+                    .label("<")
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.show_month(month.checked_sub_months(Months::new(1)), cx)
+                    })),
+            )
+            .child(
+                div()
+                    .w(px(CALENDAR_CELL_WIDTH_PX * 4.))
+                    .text_center()
+                    .child(month.format("%B %Y").to_string()),
+            )
+            .child(
+                Button::new("calendar-next")
+                    .xsmall()
+                    .ghost()
+                    .label(">")
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.show_month(month.checked_add_months(Months::new(1)), cx)
+                    })),
+            )
+            .child(
+                Button::new("calendar-today")
+                    .xsmall()
+                    .ghost()
+                    .label(crate::i18n::tr("button.today"))
+                    .on_click(
+                        cx.listener(move |this, _, _, cx| this.show_month(today.with_day(1), cx)),
+                    ),
+            );
+        let mut weeks =
+            v_flex()
+                .text_xs()
+                .child(
+                    h_flex().children(CALENDAR_WEEKDAY_LABELS.iter().map(|label| {
+                        div()
+                            .w(px(CALENDAR_CELL_WIDTH_PX))
+                            .text_center()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(*label)
+                    })),
+                );
+        for (week_index, week) in month_grid(month).chunks(7).enumerate() {
+            let mut row = h_flex();
+            for (day_index, day) in week.iter().enumerate() {
+                let cell = div().w(px(CALENDAR_CELL_WIDTH_PX)).py_0p5().text_center();
+                let Some(day) = *day else {
+                    row = row.child(cell);
+                    continue;
+                };
+                let first_note = self
+                    .note_days
+                    .get(&day.day())
+                    .and_then(|notes| notes.first().cloned());
+                let has_notes = first_note.is_some();
+                row = row.child(
+                    cell.id(("calendar-day", week_index * 7 + day_index))
+                        .rounded_sm()
+                        .cursor_pointer()
+                        .when(day == today, |cell| {
+                            cell.border_1().border_color(cx.theme().border)
+                        })
+                        .when(has_notes, |cell| {
+                            cell.font_weight(FontWeight::BOLD)
+                                .bg(cx.theme().accent.opacity(0.4))
+                        })
+                        .when(!has_notes, |cell| {
+                            cell.text_color(cx.theme().muted_foreground)
+                        })
+                        .child(day.day().to_string())
+                        .on_click(cx.listener(move |_, _, _, cx| {
+                            cx.emit(CalendarEvent::DayClicked {
+                                date: day,
+                                first_note: first_note.clone(),
+                            })
+                        })),
+                );
+            }
+            weeks = weeks.child(row);
+        }
+        let note_count = self.note_days.values().map(Vec::len).sum::<usize>();
+        v_flex()
+            .size_full()
+            .gap_1()
+            .p_1()
+            .child(header)
+            .child(weeks)
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(format!(
+                        "{note_count} note(s) on {} day(s)",
+                        self.note_days.len()
+                    )),
+            )
+    }
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn set_left_panel_tab(&mut self, tab: LeftPanelTab, cx: &mut Context<Self>) {
+        if self.left_panel_tab == tab {
+            return;
+        }
+        trace_debug(format!("req-cal left panel tab={tab:?}"));
+        self.left_panel_tab = tab;
+        self.calendar.update(cx, |calendar, cx| {
+            calendar.set_visible(tab == LeftPanelTab::Calendar, cx)
+        });
+        cx.notify();
+    }
+
+    /// A day with notes opens its first note; an empty day shows its folder in the file
+    /// tree when there is one.
+    pub(crate) fn handle_calendar_event(
+        &mut self,
+        event: &CalendarEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let CalendarEvent::DayClicked { date, first_note } = event;
+        if let Some(note) = first_note {
+            trace_debug(format!(
+                "req-cal open first note date={date} path={}",
+                note.display()
+            ));
+            self.open_file(note.clone(), window, cx);
+            return;
+        }
+        let folder = day_folder(
+            &crate::storage_layout::active_storage_layout(),
+            self.app_paths.user_document_dir.as_path(),
+            *date,
+        );
+        if folder.is_dir() {
+            self.set_left_panel_tab(LeftPanelTab::Files, cx);
+            self.reveal_folder_in_tree(folder, window, cx);
+        } else {
+            self.show_transient_notice(
+                crate::i18n::tr_with(
+                    "notice.no_notes_on",
+                    &[("date", date.format("%Y-%m-%d").to_string().as_str())],
+                ),
+                cx,
+            );
+        }
+    }
+
+    pub(crate) fn render_left_panel(&self, cx: &mut Context<Self>) -> AnyElement {
+        let tab = self.left_panel_tab;
+        let tab_button = |id: &'static str, label: &'static str, target: LeftPanelTab| {
+            let button = Button::new(id).xsmall().label(label);
+            if tab == target {
+                button.primary()
+            } else {
+                button.ghost()
+            }
+        };
+        let tabs = h_flex()
+            .gap_1()
+            .pb_1()
+            .child(
+                tab_button("left-panel-files", "Files", LeftPanelTab::Files).on_click(
+                    cx.listener(|app, _, _, cx| app.set_left_panel_tab(LeftPanelTab::Files, cx)),
+                ),
+            )
+            .child(
+                tab_button("left-panel-calendar", "Calendar", LeftPanelTab::Calendar).on_click(
+                    cx.listener(|app, _, _, cx| app.set_left_panel_tab(LeftPanelTab::Calendar, cx)),
+                ),
+            );
+        let body = match tab {
+            LeftPanelTab::Files => self.file_tree.clone().into_any_element(),
+            LeftPanelTab::Calendar => self.calendar.clone().into_any_element(),
+        };
+        v_flex()
+            .size_full()
+            .child(tabs)
+            .child(div().flex_1().min_h_0().child(body))
+            .into_any_element()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn cal_test1_month_scan_finds_note_days_from_daily_folders() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_calendar_cal_test1_{}_{stamp}",
+            std::process::id()
+        ));
+        let daily = StorageLayout::Daily;
+        let march = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        fs::create_dir_all(day_folder(&daily, &root, day(4))).expect("create day 4");
+        fs::create_dir_all(day_folder(&daily, &root, day(9))).expect("create day 9");
+        fs::create_dir_all(root.join("2026").join("03").join("99")).expect("create bogus day");
+        fs::create_dir_all(root.join("2026").join("03").join("notes")).expect("create folder");
+        fs::write(day_folder(&daily, &root, day(4)).join("b.md"), "b").expect("write b");
+        fs::write(day_folder(&daily, &root, day(4)).join("a.txt"), "a").expect("write a");
+        fs::write(day_folder(&daily, &root, day(9)).join("image.png"), "x").expect("write png");
+        fs::write(root.join("2026").join("03").join("99").join("x.txt"), "x").expect("write x");
+
+        let days = note_days_in_month(&daily, &root, march).expect("scan");
+        assert_eq!(
+            days.into_iter().collect::<Vec<_>>(),
+            vec![(
+                4,
+                vec![
+                    day_folder(&daily, &root, day(4)).join("a.txt"),
+                    day_folder(&daily, &root, day(4)).join("b.md"),
+                ]
+            )]
+        );
+        assert!(
+            note_days_in_month(&daily, &root, NaiveDate::from_ymd_opt(2025, 1, 1).unwrap())
+                .expect("scan empty")
+                .is_empty()
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn cal_test2_shared_folders_date_notes_by_modification_time() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_calendar_cal_test2_{}_{stamp}",
+            std::process::id()
+        ));
+        let monthly = StorageLayout::Monthly;
+        let march = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let folder = day_folder(&monthly, &root, march);
+        assert_eq!(folder, root.join("2026").join("03"));
+        fs::create_dir_all(&folder).expect("create month folder");
+        let set_modified = |path: &Path, day: u32| {
+            let modified = NaiveDate::from_ymd_opt(2026, 3, day)
+                .and_then(|date| date.and_hms_opt(12, 0, 0))
+                .and_then(|noon| noon.and_local_timezone(Local).earliest())
+                .expect("local noon");
+            fs::File::options()
+                .write(true)
+                .open(path)
+                .and_then(|file| file.set_modified(SystemTime::from(modified)))
+                .expect("set modified");
+        };
+        fs::write(folder.join("plan.md"), "plan").expect("write plan");
+        set_modified(&folder.join("plan.md"), 12);
+        fs::write(folder.join("later.txt"), "later").expect("write later");
+
+        let days = note_days_in_month(&monthly, &root, march).expect("scan");
+        assert_eq!(days.get(&12), Some(&vec![folder.join("plan.md")]));
+        assert_eq!(days.values().map(Vec::len).sum::<usize>(), 1);
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,224 @@
+use std::{ffi::OsString, io, path::PathBuf};
+
+use crate::path_resolver::CliRunModeOverride;
+
+pub const CLI_USAGE: &str = "usage: papyru2 [--portable | --installed | --demo] [--documents <dir>] [<file> | --new \"title\"]";
+
+/// What the window does once it is up, besides restoring the usual empty state.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum CliLaunchAction {
+    /// Open an existing note in Edit state; relative paths are resolved by the caller.
+    OpenFile(PathBuf),
+    /// Create a new note named after the title, as if it had been typed in the title field.
+    NewNote(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CliArgs {
+    pub mode_override: Option<CliRunModeOverride>,
+    /// `--documents <dir>`: the notes folder for this run, ahead of env and settings.
+    pub documents_dir: Option<PathBuf>,
+    pub launch: Option<CliLaunchAction>,
+}
+
+fn invalid_input(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message.into())
+}
+
+/// Parses the process arguments (including the program name in first position). Paths are
+/// taken as given, so file names that are not valid UTF-8 still open.
+pub fn parse_cli_args<I, S>(args: I) -> io::Result<CliArgs>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<OsString>,
+{
+    let mut force_portable = false;
+    let mut force_installed = false;
+    let mut force_demo = false;
+    let mut new_title: Option<String> = None;
+    let mut documents_dir: Option<PathBuf> = None;
+    let mut open_path: Option<PathBuf> = None;
+
+    let mut args = args.into_iter().skip(1).map(Into::<OsString>::into);
+    while let Some(raw_arg) = args.next() {
+        let Some(arg) = raw_arg.to_str() else {
+            if raw_arg.to_string_lossy().starts_with("--") {
+                return Err(invalid_input(format!(
+                    "{} is not valid UTF-8; pass the value as a separate argument",
+                    raw_arg.to_string_lossy()
+                )));
+            }
+            if open_path.replace(PathBuf::from(raw_arg)).is_some() {
+                return Err(invalid_input("only one file path can be opened at launch"));
+            }
+            continue;
+        };
+        match arg {
+            "--portable" => force_portable = true,
+            "--installed" => force_installed = true,
+            "--demo" => force_demo = true,
+            "--new" => {
+                let title = args
+                    .next()
+                    .ok_or_else(|| invalid_input("--new needs a title"))?
+                    .into_string()
+                    .map_err(|_| invalid_input("--new title is not valid UTF-8"))?;
+                if new_title.replace(title).is_some() {
+                    return Err(invalid_input("--new given more than once"));
+                }
+            }
+            "--documents" => {
+                let dir = args
+                    .next()
+                    .ok_or_else(|| invalid_input("--documents needs a folder"))?;
+                if documents_dir.replace(PathBuf::from(dir)).is_some() {
+                    return Err(invalid_input("--documents given more than once"));
+                }
+            }
+            _ if arg.starts_with("--documents=") => {
+                let dir = PathBuf::from(&arg["--documents=".len()..]);
+                if documents_dir.replace(dir).is_some() {
+                    return Err(invalid_input("--documents given more than once"));
+                }
+            }
+            _ if arg.starts_with("--new=") => {
+                let title = arg["--new=".len()..].to_string();
+                if new_title.replace(title).is_some() {
+                    return Err(invalid_input("--new given more than once"));
+                }
+            }
+            // Unknown switches are left alone; some platforms pass their own to the app.
+            _ if arg.starts_with('-') => {}
+            _ => {
+                if open_path.replace(PathBuf::from(arg)).is_some() {
+                    return Err(invalid_input("only one file path can be opened at launch"));
+                }
+            }
+        }
+    }
+
+    let selected_count = [force_portable, force_installed, force_demo]
+        .into_iter()
+        .filter(|selected| *selected)
+        .count();
+    if selected_count > 1 {
+        return Err(invalid_input(
+            "conflicting CLI overrides: use only one of --portable, --installed, --demo",
+        ));
+    }
+    let mode_override = if force_portable {
+        Some(CliRunModeOverride::Portable)
+    } else if force_installed {
+        Some(CliRunModeOverride::Installed)
+    } else if force_demo {
+        Some(CliRunModeOverride::Demo)
+    } else {
+        None
+    };
+
+    let launch = match (open_path, new_title) {
+        (Some(_), Some(_)) => {
+            return Err(invalid_input("use either a file path or --new, not both"));
+        }
+        (Some(path), None) => Some(CliLaunchAction::OpenFile(path)),
+        (None, Some(title)) if title.trim().is_empty() => {
+            return Err(invalid_input("--new needs a non-empty title"));
+        }
+        (None, Some(title)) => Some(CliLaunchAction::NewNote(title)),
+        (None, None) => None,
+    };
+
+    if documents_dir
+        .as_ref()
+        .is_some_and(|dir| dir.as_os_str().is_empty())
+    {
+        return Err(invalid_input("--documents needs a non-empty folder"));
+    }
+
+    Ok(CliArgs {
+        mode_override,
+        documents_dir,
+        launch,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_test1_file_path_and_new_title_become_launch_actions() {
+        assert_eq!(
+            parse_cli_args(["papyru2", "notes/today.txt"]).expect("parse path"),
+            CliArgs {
+                mode_override: None,
+                documents_dir: None,
+                launch: Some(CliLaunchAction::OpenFile(PathBuf::from("notes/today.txt"))),
+            }
+        );
+        assert_eq!(
+            parse_cli_args(["papyru2", "--portable", "--new", "meeting notes"]).expect("parse new"),
+            CliArgs {
+                mode_override: Some(CliRunModeOverride::Portable),
+                documents_dir: None,
+                launch: Some(CliLaunchAction::NewNote("meeting notes".to_string())),
+            }
+        );
+        assert_eq!(
+            parse_cli_args(["papyru2", "--new=idea"])
+                .expect("parse new=")
+                .launch,
+            Some(CliLaunchAction::NewNote("idea".to_string()))
+        );
+        assert_eq!(
+            parse_cli_args(["papyru2", "-psn_0_12345"]).expect("ignore platform switch"),
+            CliArgs::default()
+        );
+    }
+
+    #[test]
+    fn cli_test2_conflicting_or_incomplete_arguments_are_rejected() {
+        assert!(parse_cli_args(["papyru2", "a.txt", "b.txt"]).is_err());
+        assert!(parse_cli_args(["papyru2", "a.txt", "--new", "x"]).is_err());
+        assert!(parse_cli_args(["papyru2", "--new"]).is_err());
+        assert!(parse_cli_args(["papyru2", "--new", "  "]).is_err());
+        assert!(parse_cli_args(["papyru2", "--demo", "--installed"]).is_err());
+    }
+
+    #[test]
+    fn cli_test3_documents_flag_takes_a_folder() {
+        let args = parse_cli_args(["papyru2", "--documents", "/sync/notes", "today.txt"])
+            .expect("parse documents");
+        assert_eq!(args.documents_dir, Some(PathBuf::from("/sync/notes")));
+        assert_eq!(
+            args.launch,
+            Some(CliLaunchAction::OpenFile(PathBuf::from("today.txt")))
+        );
+        assert_eq!(
+            parse_cli_args(["papyru2", "--documents=/sync/notes"])
+                .expect("parse documents=")
+                .documents_dir,
+            Some(PathBuf::from("/sync/notes"))
+        );
+        assert!(parse_cli_args(["papyru2", "--documents"]).is_err());
+        assert!(parse_cli_args(["papyru2", "--documents="]).is_err());
+        assert!(parse_cli_args(["papyru2", "--documents", "/a", "--documents", "/b"]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cli_test4_non_utf8_path_opens_as_given() {
+        use std::os::unix::ffi::OsStringExt;
+
+        // Shift-JIS bytes for a Japanese file name.
+        let name = OsString::from_vec(vec![0x83, 0x81, 0x83, 0x82, b'.', b't', b'x', b't']);
+        let args = parse_cli_args([OsString::from("papyru2"), name.clone()]).expect("parse path");
+        assert_eq!(
+            args.launch,
+            Some(CliLaunchAction::OpenFile(PathBuf::from(name.clone())))
+        );
+        let mut documents = OsString::from("--documents=");
+        documents.push(&name);
+        assert!(parse_cli_args([OsString::from("papyru2"), documents]).is_err());
+    }
+}
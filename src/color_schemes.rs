@@ -0,0 +1,370 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use gpui::*;
+
+use crate::app::{UiColorConfig, req_colr_rgb_hex_to_hsla};
+use crate::log::trace_debug;
+
+pub(crate) const THEMES_DIR_NAME: &str = "themes";
+/// The `[color]` background and foreground on top of the stock theme.
+pub(crate) const DEFAULT_SCHEME_NAME: &str = "default";
+pub(crate) const HIGH_CONTRAST_SCHEME_NAME: &str = "high-contrast";
+const MAX_RGB_HEX: u32 = 0x00FF_FFFF;
+
+#[derive(Clone, Copy)]
+struct StockColors {
+    selection: Hsla,
+    primary: Hsla,
+    ring: Hsla,
+}
+
+static STOCK_COLORS: OnceLock<StockColors> = OnceLock::new();
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ColorScheme {
+    pub name: String,
+    pub background_rgb_hex: u32,
+    pub foreground_rgb_hex: u32,
+    /// `None` keeps the stock theme's color.
+    pub selection_rgb_hex: Option<u32>,
+    pub accent_rgb_hex: Option<u32>,
+}
+
+impl ColorScheme {
+    pub fn ui_colors(&self) -> UiColorConfig {
+        UiColorConfig {
+            background_rgb_hex: self.background_rgb_hex,
+            foreground_rgb_hex: self.foreground_rgb_hex,
+        }
+    }
+
+    fn from_ui_colors(name: &str, colors: UiColorConfig) -> Self {
+        Self {
+            name: name.to_string(),
+            background_rgb_hex: colors.background_rgb_hex,
+            foreground_rgb_hex: colors.foreground_rgb_hex,
+            selection_rgb_hex: None,
+            accent_rgb_hex: None,
+        }
+    }
+}
+
+/// White on black with a yellow accent; shipped so it is there without writing a file.
+fn high_contrast_scheme() -> ColorScheme {
+    ColorScheme {
+        name: HIGH_CONTRAST_SCHEME_NAME.to_string(),
+        background_rgb_hex: 0x000000,
+        foreground_rgb_hex: 0xffffff,
+        selection_rgb_hex: Some(0x1f4fff),
+        accent_rgb_hex: Some(0xffd700),
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct SchemeFile {
+    name: Option<String>,
+    background: Option<u32>,
+    foreground: Option<u32>,
+    selection: Option<u32>,
+    accent: Option<u32>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct ColorConfigFile {
+    #[serde(default)]
+    color: ColorConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct ColorConfigSection {
+    scheme: Option<String>,
+}
+
+fn validate_rgb_hex(field_name: &str, rgb_hex: Option<u32>) -> Result<Option<u32>, String> {
+    match rgb_hex {
+        Some(value) if value > MAX_RGB_HEX => {
+            Err(format!("{field_name}=0x{value:08x} exceeds 24-bit rgb"))
+        }
+        _ => Ok(rgb_hex),
+    }
+}
+
+/// A scheme file; colors it leaves out come from `[color]`, and `name` defaults to the
+/// file stem.
+fn parse_scheme_file(stem: &str, raw: &str, base: UiColorConfig) -> Result<ColorScheme, String> {
+    let parsed = toml::from_str::<SchemeFile>(raw).map_err(|error| error.to_string())?;
+    let name = parsed
+        .name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| stem.to_string());
+    Ok(ColorScheme {
+        name,
+        background_rgb_hex: validate_rgb_hex("background", parsed.background)?
+            .unwrap_or(base.background_rgb_hex),
+        foreground_rgb_hex: validate_rgb_hex("foreground", parsed.foreground)?
+            .unwrap_or(base.foreground_rgb_hex),
+        selection_rgb_hex: validate_rgb_hex("selection", parsed.selection)?,
+        accent_rgb_hex: validate_rgb_hex("accent", parsed.accent)?,
+    })
+}
+
+/// The built-in schemes, then `themes_dir/*.toml` by file name. A file named like an
+/// earlier scheme replaces it, so `high-contrast.toml` can retune the shipped one.
+pub(crate) fn load_color_schemes(themes_dir: &Path, base: UiColorConfig) -> Vec<ColorScheme> {
+    let mut schemes = vec![
+        ColorScheme::from_ui_colors(DEFAULT_SCHEME_NAME, base),
+        high_contrast_scheme(),
+    ];
+    let mut files = fs::read_dir(themes_dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file()
+                        && path
+                            .extension()
+                            .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"))
+                })
+                .collect::<Vec<PathBuf>>()
+        })
+        .unwrap_or_default();
+    files.sort();
+    for path in files {
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let parsed = fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|raw| parse_scheme_file(&stem, &raw, base));
+        match parsed {
+            Ok(scheme) => match schemes
+                .iter_mut()
+                .find(|known| known.name.eq_ignore_ascii_case(&scheme.name))
+            {
+                Some(known) => *known = scheme,
+                None => schemes.push(scheme),
+            },
+            Err(error) => trace_debug(format!(
+                "req-theme scheme skipped path={} error={error}",
+                path.display()
+            )),
+        }
+    }
+    schemes
+}
+
+/// `[color] scheme`; unset means the default scheme.
+pub(crate) fn load_scheme_name(config_path: &Path) -> String {
+    fs::read_to_string(config_path)
+        .ok()
+        .and_then(|raw| toml::from_str::<ColorConfigFile>(&raw).ok())
+        .and_then(|parsed| parsed.color.scheme)
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_SCHEME_NAME.to_string())
+}
+
+/// The named scheme, or the default one when no scheme has that name any more.
+pub(crate) fn select_color_scheme(schemes: &[ColorScheme], name: &str) -> ColorScheme {
+    schemes
+        .iter()
+        .find(|scheme| scheme.name.eq_ignore_ascii_case(name))
+        .or_else(|| schemes.first())
+        .cloned()
+        .unwrap_or_else(|| {
+            ColorScheme::from_ui_colors(DEFAULT_SCHEME_NAME, UiColorConfig::default())
+        })
+}
+
+pub(crate) fn load_active_color_scheme(
+    config_path: &Path,
+    conf_dir: &Path,
+    base: UiColorConfig,
+) -> ColorScheme {
+    let name = load_scheme_name(config_path);
+    let schemes = load_color_schemes(conf_dir.join(THEMES_DIR_NAME).as_path(), base);
+    let scheme = select_color_scheme(&schemes, &name);
+    trace_debug(format!(
+        "req-theme active scheme configured={name} resolved={} available={}",
+        scheme.name,
+        schemes.len()
+    ));
+    scheme
+}
+
+/// Background and foreground go through the `[color]` override; selection and accent only
+/// replace the stock theme's when the scheme sets them.
+pub(crate) fn apply_color_scheme(scheme: &ColorScheme, cx: &mut App) {
+    crate::app::apply_req_colr_theme_overrides(scheme.ui_colors(), cx);
+    let theme = gpui_component::Theme::global_mut(cx);
+    // Taken before the first scheme touches them, so switching back restores them.
+    let stock = *STOCK_COLORS.get_or_init(|| StockColors {
+        selection: theme.selection,
+        primary: theme.primary,
+        ring: theme.ring,
+    });
+    theme.selection = scheme
+        .selection_rgb_hex
+        .map_or(stock.selection, req_colr_rgb_hex_to_hsla);
+    let accent = scheme.accent_rgb_hex.map(req_colr_rgb_hex_to_hsla);
+    theme.primary = accent.unwrap_or(stock.primary);
+    theme.ring = accent.unwrap_or(stock.ring);
+    trace_debug(format!(
+        "req-theme scheme applied name={} selection={:?} accent={:?}",
+        scheme.name, scheme.selection_rgb_hex, scheme.accent_rgb_hex
+    ));
+}
+
+impl crate::app::Papyru2App {
+    /// Hands the scheme's colors to every view that paints its own background and text.
+    pub(crate) fn set_ui_color_config(&mut self, colors: UiColorConfig, cx: &mut Context<Self>) {
+        self.ui_color_config = colors;
+        self.file_tree.update(cx, |file_tree, cx| {
+            file_tree.set_ui_color_config(colors, cx);
+        });
+        self.editor.update(cx, |editor, cx| {
+            editor.set_ui_color_config(colors, cx);
+        });
+        self.singleline.update(cx, |singleline, cx| {
+            singleline.set_ui_color_config(colors, cx);
+        });
+        if let Some(split) = self.split_editor.as_ref() {
+            split
+                .view
+                .update(cx, |view, cx| view.set_ui_color_config(colors, cx));
+        }
+        if let Some(capture) = self.quick_capture.as_ref() {
+            capture
+                .view
+                .update(cx, |view, cx| view.set_ui_color_config(colors, cx));
+        }
+    }
+
+    /// Ctrl+Shift+T: lists the schemes, applies the one picked and saves it as
+    /// `[color] scheme`.
+    pub(crate) fn show_color_scheme_menu(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let config_path = self
+            .app_paths
+            .config_file_path(crate::app::PAPYRU2_CONF_FILE_NAME);
+        let base = crate::app::load_or_create_ui_color_config(config_path.as_path());
+        let schemes = load_color_schemes(
+            self.app_paths.conf_dir.join(THEMES_DIR_NAME).as_path(),
+            base,
+        );
+        let current = select_color_scheme(&schemes, &load_scheme_name(config_path.as_path()));
+        let mut labels = schemes
+            .iter()
+            .map(|scheme| {
+                if scheme.name == current.name {
+                    crate::i18n::tr_with("label.current", &[("name", scheme.name.as_str())])
+                } else {
+                    scheme.name.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        labels.push(crate::i18n::tr("button.cancel").to_string());
+        let label_refs = labels.iter().map(String::as_str).collect::<Vec<_>>();
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &crate::i18n::tr("prompt.color_scheme"),
+            Some(&crate::i18n::tr("prompt.color_scheme_detail")),
+            &label_refs,
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(choice) = answer.await else {
+                return;
+            };
+            let Some(scheme) = schemes.get(choice).cloned() else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                apply_color_scheme(&scheme, cx);
+                app.set_ui_color_config(scheme.ui_colors(), cx);
+                window.refresh();
+                let config_path = app
+                    .app_paths
+                    .config_file_path(crate::app::PAPYRU2_CONF_FILE_NAME);
+                let value = toml::Value::String(scheme.name.clone()).to_string();
+                if let Err(error) =
+                    crate::app::write_conf_value(config_path.as_path(), "color", "scheme", &value)
+                {
+                    app.show_toast(
+                        crate::toasts::ToastSeverity::Error,
+                        crate::i18n::tr_with(
+                            "notice.setting_save_failed",
+                            &[
+                                ("setting", "color.scheme"),
+                                ("error", error.to_string().as_str()),
+                            ],
+                        ),
+                        cx,
+                    );
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_test1_scheme_files_load_after_built_ins_and_fall_back_to_color_section() {
+        let dir = std::env::temp_dir().join(format!("papyru2_theme_test1_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let themes_dir = dir.join(THEMES_DIR_NAME);
+        fs::create_dir_all(&themes_dir).unwrap();
+        fs::write(
+            themes_dir.join("night.toml"),
+            "background = 0x101010\nselection = 0x334455\naccent = 0xff8800\n",
+        )
+        .unwrap();
+        fs::write(
+            themes_dir.join("contrast.toml"),
+            "name = \"High-Contrast\"\nbackground = 0x000000\nforeground = 0xffff00\n",
+        )
+        .unwrap();
+        fs::write(themes_dir.join("broken.toml"), "background = 0x1000000\n").unwrap();
+        fs::write(themes_dir.join("notes.txt"), "background = 0x222222\n").unwrap();
+        let base = UiColorConfig {
+            background_rgb_hex: 0xf7f2ec,
+            foreground_rgb_hex: 0x437085,
+        };
+
+        let schemes = load_color_schemes(&themes_dir, base);
+        let names = schemes
+            .iter()
+            .map(|scheme| scheme.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["default", "High-Contrast", "night"]);
+        assert_eq!(schemes[0].ui_colors(), base);
+        assert_eq!(schemes[1].foreground_rgb_hex, 0xffff00);
+        assert_eq!(schemes[1].selection_rgb_hex, None);
+        let night = select_color_scheme(&schemes, "NIGHT");
+        assert_eq!(night.background_rgb_hex, 0x101010);
+        assert_eq!(night.foreground_rgb_hex, 0x437085);
+        assert_eq!(night.accent_rgb_hex, Some(0xff8800));
+        assert_eq!(select_color_scheme(&schemes, "gone").name, "default");
+
+        let config_path = dir.join("papyru2_conf.toml");
+        assert_eq!(load_scheme_name(&config_path), DEFAULT_SCHEME_NAME);
+        fs::write(&config_path, "[color]\nscheme = \" night \"\n").unwrap();
+        assert_eq!(load_scheme_name(&config_path), "night");
+        assert_eq!(
+            load_active_color_scheme(&config_path, &dir, base).name,
+            "night"
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,342 @@
+use std::{io, path::Path};
+
+use gpui::*;
+
+use crate::log::trace_debug;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ConfirmationKind {
+    Trash,
+    Purge,
+    OverwriteOnConflict,
+    LargeFileOpen,
+    ReplaceAll,
+    PublishLock,
+    LinkRewrite,
+}
+
+pub(crate) const CONFIRMATION_KINDS: [ConfirmationKind; 7] = [
+    ConfirmationKind::Trash,
+    ConfirmationKind::Purge,
+    ConfirmationKind::OverwriteOnConflict,
+    ConfirmationKind::LargeFileOpen,
+    ConfirmationKind::ReplaceAll,
+    ConfirmationKind::PublishLock,
+    ConfirmationKind::LinkRewrite,
+];
+
+impl ConfirmationKind {
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Trash => "trash",
+            Self::Purge => "purge",
+            Self::OverwriteOnConflict => "overwrite_on_conflict",
+            Self::LargeFileOpen => "large_file_open",
+            Self::ReplaceAll => "replace_all",
+            Self::PublishLock => "publish_lock",
+            Self::LinkRewrite => "link_rewrite",
+        }
+    }
+
+    /// Irreversible actions: their prompt cannot be turned off from settings. An overwrite on
+    /// conflict is snapshotted first, so it can be set like the rest.
+    pub fn is_dangerous(self) -> bool {
+        matches!(self, Self::Purge)
+    }
+
+    fn default_mode(self) -> ConfirmationMode {
+        match self {
+            // Trash is recoverable from the recycle bin and has never asked.
+            Self::Trash => ConfirmationMode::Always,
+            Self::Purge
+            | Self::OverwriteOnConflict
+            | Self::LargeFileOpen
+            | Self::ReplaceAll
+            | Self::PublishLock
+            | Self::LinkRewrite => ConfirmationMode::Ask,
+        }
+    }
+
+    fn confirm_label(self) -> SharedString {
+        crate::i18n::tr(&format!("confirm.{}", self.key()))
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Self::Trash => 0,
+            Self::Purge => 1,
+            Self::OverwriteOnConflict => 2,
+            Self::LargeFileOpen => 3,
+            Self::ReplaceAll => 4,
+            Self::PublishLock => 5,
+            Self::LinkRewrite => 6,
+        }
+    }
+}
+
+/// `always` proceeds without asking, `never` declines without asking, `ask` prompts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ConfirmationMode {
+    Always,
+    Never,
+    Ask,
+}
+
+impl ConfirmationMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Never => "never",
+            Self::Ask => "ask",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "always" => Some(Self::Always),
+            "never" => Some(Self::Never),
+            "ask" => Some(Self::Ask),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ConfirmationPolicy {
+    modes: [ConfirmationMode; CONFIRMATION_KINDS.len()],
+}
+
+impl Default for ConfirmationPolicy {
+    fn default() -> Self {
+        Self {
+            modes: CONFIRMATION_KINDS.map(ConfirmationKind::default_mode),
+        }
+    }
+}
+
+impl ConfirmationPolicy {
+    /// The effective mode; dangerous kinds always resolve to `Ask`.
+    pub fn mode(&self, kind: ConfirmationKind) -> ConfirmationMode {
+        if kind.is_dangerous() {
+            return ConfirmationMode::Ask;
+        }
+        self.modes[kind.index()]
+    }
+
+    fn set_mode(&mut self, kind: ConfirmationKind, mode: ConfirmationMode) {
+        self.modes[kind.index()] = mode;
+    }
+
+    fn trace_text(&self) -> String {
+        CONFIRMATION_KINDS
+            .iter()
+            .map(|kind| format!("{}={}", kind.key(), self.mode(*kind).as_str()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct ConfirmationsConfigFile {
+    #[serde(default)]
+    confirmations: std::collections::BTreeMap<String, String>,
+}
+
+pub(crate) fn default_confirmations_toml() -> String {
+    let policy = ConfirmationPolicy::default();
+    let mut text = String::from("[confirmations]\n");
+    for kind in CONFIRMATION_KINDS {
+        text.push_str(&format!(
+            "{} = \"{}\"\n",
+            kind.key(),
+            policy.mode(kind).as_str()
+        ));
+    }
+    text
+}
+
+fn load_confirmation_policy_result(path: &Path) -> io::Result<ConfirmationPolicy> {
+    let mut policy = ConfirmationPolicy::default();
+    if !path.is_file() {
+        return Ok(policy);
+    }
+    let raw = std::fs::read_to_string(path)?;
+    let parsed: ConfirmationsConfigFile = toml::from_str(&raw)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+    for (key, value) in parsed.confirmations {
+        let Some(kind) = CONFIRMATION_KINDS
+            .into_iter()
+            .find(|kind| kind.key() == key)
+        else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("req-cnf invalid confirmations key={key}"),
+            ));
+        };
+        let mode = ConfirmationMode::parse(&value).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "req-cnf invalid confirmations.{key} value={value} (expected always, never or ask)"
+                ),
+            )
+        })?;
+        if kind.is_dangerous() && mode != ConfirmationMode::Ask {
+            trace_debug(format!(
+                "req-cnf confirmations.{key}={} ignored reason=locked-to-ask",
+                mode.as_str()
+            ));
+        }
+        policy.set_mode(kind, mode);
+    }
+    Ok(policy)
+}
+
+pub(crate) fn load_confirmation_policy(path: &Path) -> ConfirmationPolicy {
+    match load_confirmation_policy_result(path) {
+        Ok(policy) => {
+            trace_debug(format!(
+                "req-cnf config loaded path={} {}",
+                path.display(),
+                policy.trace_text()
+            ));
+            policy
+        }
+        Err(error) => {
+            let defaults = ConfirmationPolicy::default();
+            trace_debug(format!(
+                "req-cnf config fallback path={} error={error} defaults {}",
+                path.display(),
+                defaults.trace_text()
+            ));
+            defaults
+        }
+    }
+}
+
+/// Runs `on_confirmed` now, never, or after the user accepts a prompt, as the policy says.
+pub(crate) fn run_confirmed<T, F>(
+    this: &mut T,
+    policy: ConfirmationPolicy,
+    kind: ConfirmationKind,
+    message: &str,
+    window: &mut Window,
+    cx: &mut Context<T>,
+    on_confirmed: F,
+) where
+    T: 'static,
+    F: FnOnce(&mut T, &mut Window, &mut Context<T>) + 'static,
+{
+    let mode = policy.mode(kind);
+    trace_debug(format!(
+        "req-cnf confirm kind={} mode={}",
+        kind.key(),
+        mode.as_str()
+    ));
+    match mode {
+        ConfirmationMode::Always => on_confirmed(this, window, cx),
+        ConfirmationMode::Never => {}
+        ConfirmationMode::Ask => {
+            let level = if kind.is_dangerous() {
+                PromptLevel::Critical
+            } else {
+                PromptLevel::Warning
+            };
+            let confirm = kind.confirm_label();
+            let cancel = crate::i18n::tr("button.cancel");
+            let answer = window.prompt(
+                level,
+                message,
+                None,
+                &[confirm.as_ref(), cancel.as_ref()],
+                cx,
+            );
+            cx.spawn_in(window, async move |this, cx| {
+                let accepted = answer.await.ok() == Some(0);
+                trace_debug(format!(
+                    "req-cnf prompt kind={} accepted={accepted}",
+                    kind.key()
+                ));
+                if accepted {
+                    let _ = this.update_in(cx, |this, window, cx| on_confirmed(this, window, cx));
+                }
+            })
+            .detach();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        fs,
+        path::PathBuf,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_confirmations_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    #[test]
+    fn cnf_test1_settings_override_modes_but_dangerous_prompts_stay_ask() {
+        let root = new_temp_root("cnf_test1");
+        let path = root.join("papyru2_conf.toml");
+        fs::write(
+            &path,
+            "[confirmations]\ntrash = \"ask\"\nreplace_all = \"never\"\npurge = \"always\"\noverwrite_on_conflict = \"never\"\n",
+        )
+        .expect("write config");
+
+        let policy = load_confirmation_policy(path.as_path());
+
+        assert_eq!(policy.mode(ConfirmationKind::Trash), ConfirmationMode::Ask);
+        assert_eq!(
+            policy.mode(ConfirmationKind::ReplaceAll),
+            ConfirmationMode::Never
+        );
+        assert_eq!(policy.mode(ConfirmationKind::Purge), ConfirmationMode::Ask);
+        assert_eq!(
+            policy.mode(ConfirmationKind::OverwriteOnConflict),
+            ConfirmationMode::Never
+        );
+        assert_eq!(
+            policy.mode(ConfirmationKind::LargeFileOpen),
+            ConfirmationMode::Ask
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn cnf_test2_invalid_value_falls_back_to_defaults_and_defaults_round_trip() {
+        let root = new_temp_root("cnf_test2");
+        let path = root.join("papyru2_conf.toml");
+        fs::write(&path, "[confirmations]\ntrash = \"sometimes\"\n").expect("write config");
+        assert_eq!(
+            load_confirmation_policy(path.as_path()),
+            ConfirmationPolicy::default()
+        );
+
+        fs::write(&path, default_confirmations_toml()).expect("write defaults");
+        assert_eq!(
+            load_confirmation_policy_result(path.as_path()).expect("load defaults"),
+            ConfirmationPolicy::default()
+        );
+        assert_eq!(
+            ConfirmationPolicy::default().mode(ConfirmationKind::Trash),
+            ConfirmationMode::Always
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+}
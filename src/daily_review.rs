@@ -0,0 +1,460 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, Local, NaiveTime};
+use gpui::*;
+
+use crate::log::trace_debug;
+
+pub(crate) const DAILY_REVIEW_TEMPLATE_FILE_NAME: &str = "daily_review_template.md";
+const DAILY_REVIEW_SCHEDULE_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Used when `conf/daily_review_template.md` does not exist.
+pub(crate) const DEFAULT_DAILY_REVIEW_TEMPLATE: &str = "\
+# Daily Review {{date}}
+
+{{note_count}} notes, {{word_count}} words.
+
+## Notes
+{{notes}}
+
+## Open tasks
+{{open_tasks}}
+";
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct DailyReviewConfigFile {
+    #[serde(default)]
+    daily_review: DailyReviewConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct DailyReviewConfigSection {
+    schedule: Option<String>,
+}
+
+/// `[daily_review] schedule = "HH:MM"` generates the review once a day after that time.
+pub(crate) fn load_daily_review_schedule(path: &Path) -> Option<NaiveTime> {
+    let raw = fs::read_to_string(path).ok()?;
+    let parsed: DailyReviewConfigFile = match toml::from_str(&raw) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            trace_debug(format!(
+                "req-drv1 config parse failed path={} error={error}",
+                path.display()
+            ));
+            return None;
+        }
+    };
+    let value = parsed.daily_review.schedule?;
+    let schedule = NaiveTime::parse_from_str(value.trim(), "%H:%M");
+    trace_debug(format!(
+        "req-drv1 schedule value='{value}' parsed={:?}",
+        schedule.as_ref().ok()
+    ));
+    schedule.ok()
+}
+
+fn daily_review_stem(now: DateTime<Local>) -> String {
+    format!("Daily Review {}", now.format("%Y-%m-%d"))
+}
+
+pub(crate) fn daily_review_file_name(now: DateTime<Local>) -> String {
+    format!("{}.md", daily_review_stem(now))
+}
+
+// Today's review and the `_2`, `_3` copies made when it already existed.
+fn is_daily_review_file(path: &Path, now: DateTime<Local>) -> bool {
+    let stem = daily_review_stem(now);
+    path.file_stem()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_prefix(stem.as_str()))
+        .is_some_and(|rest| {
+            rest.is_empty()
+                || rest
+                    .strip_prefix('_')
+                    .is_some_and(|suffix| suffix.parse::<usize>().is_ok())
+        })
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ReviewedNote {
+    file_name: String,
+    title: String,
+    word_count: usize,
+    open_tasks: Vec<String>,
+    created: Option<DateTime<Local>>,
+}
+
+fn open_task_text(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    ["- [ ]", "* [ ]", "+ [ ]"]
+        .iter()
+        .find_map(|marker| trimmed.strip_prefix(marker))
+        .map(str::trim)
+}
+
+fn review_note(path: &Path) -> io::Result<ReviewedNote> {
+    let text = crate::editor::read_editor_text_from_disk(path)?;
+    Ok(ReviewedNote {
+        file_name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        title: path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        word_count: text
+            .split_whitespace()
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .count(),
+        open_tasks: text
+            .lines()
+            .filter_map(open_task_text)
+            .filter(|task| !task.is_empty())
+            .map(str::to_string)
+            .collect(),
+        created: crate::file_times::created_time(path).map(DateTime::<Local>::from),
+    })
+}
+
+// Notes edited today are moved into today's folder for the layout. Under a monthly or flat
+// layout that folder also holds other days' notes, so only the ones dated today are kept.
+fn gather_todays_notes(
+    user_document_dir: &Path,
+    layout: &crate::storage_layout::StorageLayout,
+    now: DateTime<Local>,
+) -> io::Result<Vec<ReviewedNote>> {
+    let today = now.date_naive();
+    let mut paths = match fs::read_dir(layout.directory(user_document_dir, now)) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter(|path| {
+                path.extension()
+                    .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+                    .is_some_and(|extension| extension == "txt" || extension == "md")
+            })
+            .filter(|path| !is_daily_review_file(path, now))
+            .filter(|path| crate::storage_layout::note_date(path, user_document_dir) == Some(today))
+            .collect::<Vec<PathBuf>>(),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(error) => return Err(error),
+    };
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| review_note(path.as_path()))
+        .collect()
+}
+
+fn render_daily_review(template: &str, now: DateTime<Local>, notes: &[ReviewedNote]) -> String {
+    let notes_text = if notes.is_empty() {
+        "- (no notes today)".to_string()
+    } else {
+        notes
+            .iter()
+            .map(|note| {
+                format!(
+                    "- [{}](<{}>) ({} words)",
+                    note.title, note.file_name, note.word_count
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let tasks = notes
+        .iter()
+        .flat_map(|note| {
+            note.open_tasks
+                .iter()
+                .map(move |task| format!("- [ ] {task} ([{}](<{}>))", note.title, note.file_name))
+        })
+        .collect::<Vec<_>>();
+    let tasks_text = if tasks.is_empty() {
+        "- (no open tasks)".to_string()
+    } else {
+        tasks.join("\n")
+    };
+    let word_count = notes.iter().map(|note| note.word_count).sum::<usize>();
+    // Today's folder also holds older notes edited today; only these were started today.
+    let new_count = notes
+        .iter()
+        .filter(|note| {
+            note.created
+                .is_some_and(|created| created.date_naive() == now.date_naive())
+        })
+        .count();
+
+    template
+        .replace("{{date}}", &now.format("%Y-%m-%d").to_string())
+        .replace("{{note_count}}", &notes.len().to_string())
+        .replace("{{new_count}}", &new_count.to_string())
+        .replace("{{word_count}}", &word_count.to_string())
+        .replace("{{notes}}", &notes_text)
+        .replace("{{open_tasks}}", &tasks_text)
+}
+
+/// Writes today's review note next to today's notes and returns its path. A review that is
+/// already there may hold the user's own additions, so a new one gets a `_2` style name.
+pub(crate) fn generate_daily_review(
+    user_document_dir: &Path,
+    template_path: &Path,
+    now: DateTime<Local>,
+) -> io::Result<PathBuf> {
+    let template = match fs::read_to_string(template_path) {
+        Ok(template) => template,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            DEFAULT_DAILY_REVIEW_TEMPLATE.to_string()
+        }
+        Err(error) => return Err(error),
+    };
+    let layout = crate::storage_layout::active_storage_layout();
+    let notes = gather_todays_notes(user_document_dir, &layout, now)?;
+    let daily_dir = crate::file_update_handler::ensure_note_directory(user_document_dir, now)?;
+    let text = render_daily_review(&template, now, &notes);
+    let backend = crate::storage_backend::storage_backend_for(user_document_dir);
+    let stem = daily_review_stem(now);
+    let mut suffix = 1usize;
+    let review_path = loop {
+        let path = crate::file_update_handler::resolve_unique_path(
+            daily_dir.as_path(),
+            &stem,
+            "md",
+            suffix,
+        );
+        match backend.create_new(&path) {
+            Ok(()) => break path,
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => suffix += 1,
+            Err(error) => return Err(error),
+        }
+    };
+    backend.write(review_path.as_path(), text.as_bytes())?;
+    trace_debug(format!(
+        "req-drv1 review written path={} notes={}",
+        review_path.display(),
+        notes.len()
+    ));
+    Ok(review_path)
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn run_daily_review(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        // The open note may be one of today's notes, or the review itself.
+        if !self.flush_editor_content_before_context_switch("req-drv1-daily-review", cx) {
+            return;
+        }
+        let template_path = self
+            .app_paths
+            .config_file_path(DAILY_REVIEW_TEMPLATE_FILE_NAME);
+        match generate_daily_review(
+            self.app_paths.user_document_dir.as_path(),
+            template_path.as_path(),
+            Local::now(),
+        ) {
+            Ok(review_path) => {
+                self.apply_file_tree_watcher_refresh(cx);
+                if !self.open_file(review_path.clone(), window, cx) {
+                    self.show_transient_notice(
+                        crate::i18n::tr_with(
+                            "notice.daily_review_written",
+                            &[("path", review_path.display().to_string().as_str())],
+                        ),
+                        cx,
+                    );
+                }
+            }
+            Err(error) => {
+                self.show_transient_notice(
+                    crate::i18n::tr_with(
+                        "notice.daily_review_failed",
+                        &[("error", error.to_string().as_str())],
+                    ),
+                    cx,
+                );
+            }
+        }
+    }
+
+    // The scheduled run only fills a missing review; one already there may hold the user's
+    // own additions.
+    pub(crate) fn spawn_daily_review_schedule(
+        schedule: NaiveTime,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(DAILY_REVIEW_SCHEDULE_POLL_INTERVAL)
+                    .await;
+                let now = Local::now();
+                if now.time() < schedule {
+                    continue;
+                }
+                let updated = this.update_in(cx, |app, _, cx| {
+                    let review_path = crate::file_update_handler::note_directory(
+                        app.app_paths.user_document_dir.as_path(),
+                        now,
+                    )
+                    .join(daily_review_file_name(now));
+                    if review_path.exists() {
+                        return;
+                    }
+                    let template_path = app
+                        .app_paths
+                        .config_file_path(DAILY_REVIEW_TEMPLATE_FILE_NAME);
+                    let message = match generate_daily_review(
+                        app.app_paths.user_document_dir.as_path(),
+                        template_path.as_path(),
+                        now,
+                    ) {
+                        Ok(_) => crate::i18n::tr("notice.daily_review_ready").to_string(),
+                        Err(error) => crate::i18n::tr_with(
+                            "notice.scheduled_daily_review_failed",
+                            &[("error", error.to_string().as_str())],
+                        ),
+                    };
+                    app.apply_file_tree_watcher_refresh(cx);
+                    app.show_transient_notice(message, cx);
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_daily_review_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    #[test]
+    fn drv_test1_review_lists_todays_notes_tasks_and_words_with_links() {
+        let root = new_temp_root("drv_test1");
+        let now = Local::now();
+        let daily_dir =
+            crate::file_update_handler::ensure_note_directory(root.as_path(), now).expect("dir");
+        fs::write(
+            daily_dir.join("plan.md"),
+            "ship it\n- [ ] write tests\n- [x] done already\n",
+        )
+        .expect("write plan");
+        fs::write(daily_dir.join("memo.txt"), "three words here").expect("write memo");
+
+        let review_path = generate_daily_review(
+            root.as_path(),
+            root.join("missing_template.md").as_path(),
+            now,
+        )
+        .expect("generate");
+        let review = fs::read_to_string(&review_path).expect("read review");
+
+        assert_eq!(review_path, daily_dir.join(daily_review_file_name(now)));
+        assert!(review.contains("2 notes, 10 words."));
+        assert!(review.contains("- [memo](<memo.txt>) (3 words)"));
+        assert!(review.contains("- [ ] write tests ([plan](<plan.md>))"));
+        assert!(!review.contains("done already"));
+
+        // Regenerating keeps the first review and does not count it as a note.
+        fs::write(&review_path, "my own notes").expect("edit review");
+        let again = generate_daily_review(
+            root.as_path(),
+            root.join("missing_template.md").as_path(),
+            now,
+        )
+        .expect("regenerate");
+        assert_eq!(
+            again.file_name().and_then(|name| name.to_str()),
+            Some(format!("{}_2.md", daily_review_stem(now)).as_str())
+        );
+        assert_eq!(
+            fs::read_to_string(&review_path).expect("read first"),
+            "my own notes"
+        );
+        assert!(
+            fs::read_to_string(again)
+                .expect("read again")
+                .contains("2 notes")
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn drv_test2_custom_template_and_schedule_are_read_from_conf() {
+        let root = new_temp_root("drv_test2");
+        let template_path = root.join(DAILY_REVIEW_TEMPLATE_FILE_NAME);
+        fs::write(&template_path, "{{date}}: {{note_count}}\n").expect("write template");
+        let now = Local::now();
+        let review_path =
+            generate_daily_review(root.as_path(), template_path.as_path(), now).expect("generate");
+        assert_eq!(
+            fs::read_to_string(review_path).expect("read"),
+            format!("{}: 0\n", now.format("%Y-%m-%d"))
+        );
+
+        let conf = root.join("papyru2_conf.toml");
+        fs::write(&conf, "[daily_review]\nschedule = \"18:30\"\n").expect("write conf");
+        assert_eq!(
+            load_daily_review_schedule(conf.as_path()),
+            NaiveTime::from_hms_opt(18, 30, 0)
+        );
+        fs::write(&conf, "[daily_review]\nschedule = \"late\"\n").expect("write conf");
+        assert_eq!(load_daily_review_schedule(conf.as_path()), None);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn drv_test3_flat_layout_reviews_only_notes_dated_today() {
+        let root = new_temp_root("drv_test3");
+        let now = Local::now();
+        fs::write(root.join("today.md"), "fresh words").expect("write today");
+        let old = root.join("old.md");
+        fs::write(&old, "stale words").expect("write old");
+        fs::File::options()
+            .write(true)
+            .open(&old)
+            .and_then(|file| file.set_modified(SystemTime::now() - Duration::from_secs(3 * 86_400)))
+            .expect("age old note");
+        fs::create_dir_all(root.join("folder")).expect("create folder");
+        fs::write(root.join("folder").join("nested.md"), "nested").expect("write nested");
+
+        let notes = gather_todays_notes(
+            root.as_path(),
+            &crate::storage_layout::StorageLayout::Flat,
+            now,
+        )
+        .expect("gather");
+
+        assert_eq!(
+            notes
+                .iter()
+                .map(|note| note.file_name.as_str())
+                .collect::<Vec<_>>(),
+            ["today.md"]
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+}
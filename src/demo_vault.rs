@@ -0,0 +1,105 @@
+use chrono::{DateTime, Duration, Local};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DEMO_HOME_DIR_PREFIX: &str = "papyru2_demo";
+
+struct DemoNote {
+    days_ago: i64,
+    file_name: &'static str,
+    content: &'static str,
+}
+
+const DEMO_NOTES: &[DemoNote] = &[
+    DemoNote {
+        days_ago: 0,
+        file_name: "welcome.txt",
+        content: include_str!("../assets/demo/welcome.txt"),
+    },
+    DemoNote {
+        days_ago: 0,
+        file_name: "markdown_sample.md",
+        content: include_str!("../assets/demo/markdown_sample.md"),
+    },
+    DemoNote {
+        days_ago: 1,
+        file_name: "yesterday.txt",
+        content: include_str!("../assets/demo/yesterday.txt"),
+    },
+];
+
+pub fn demo_app_home(temp_root: &Path) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    temp_root.join(format!(
+        "{DEMO_HOME_DIR_PREFIX}_{}_{}",
+        std::process::id(),
+        stamp
+    ))
+}
+
+pub fn populate_demo_vault(
+    user_document_dir: &Path,
+    now: DateTime<Local>,
+) -> io::Result<Vec<PathBuf>> {
+    let mut written = Vec::with_capacity(DEMO_NOTES.len());
+    for note in DEMO_NOTES {
+        let day = now - Duration::days(note.days_ago);
+        let dir = user_document_dir.join(day.format("%Y/%m/%d").to_string());
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(note.file_name);
+        fs::write(&path, note.content)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_now() -> DateTime<Local> {
+        DateTime::parse_from_rfc3339("2026-02-28T12:34:56.789+00:00")
+            .expect("parse fixed timestamp")
+            .with_timezone(&Local)
+    }
+
+    #[test]
+    fn demo_test1_sample_vault_is_written_into_daily_directories() {
+        let root = demo_app_home(std::env::temp_dir().as_path());
+        let user_document_dir = root.join("data").join("user_document");
+        let now = fixed_now();
+
+        let written = populate_demo_vault(user_document_dir.as_path(), now).expect("populate");
+
+        assert_eq!(written.len(), DEMO_NOTES.len());
+        let today_dir = user_document_dir.join(now.format("%Y/%m/%d").to_string());
+        let yesterday_dir =
+            user_document_dir.join((now - Duration::days(1)).format("%Y/%m/%d").to_string());
+        assert!(today_dir.join("welcome.txt").is_file());
+        assert!(today_dir.join("markdown_sample.md").is_file());
+        assert!(yesterday_dir.join("yesterday.txt").is_file());
+        let welcome = fs::read_to_string(today_dir.join("welcome.txt")).expect("read welcome");
+        assert!(welcome.contains("demo vault"));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn demo_test2_demo_app_home_is_created_under_temp_root() {
+        let temp_root = std::env::temp_dir();
+        let first = demo_app_home(temp_root.as_path());
+
+        assert!(first.starts_with(temp_root.as_path()));
+        assert!(
+            first
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(DEMO_HOME_DIR_PREFIX))
+        );
+    }
+}
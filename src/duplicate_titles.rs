@@ -0,0 +1,416 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use chrono::Local;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+};
+
+use crate::log::trace_debug;
+
+fn stem_key(stem: &str) -> String {
+    stem.trim().to_lowercase()
+}
+
+/// Every note in the vault by lowercase file stem. Built on first lookup and rebuilt on the
+/// lookup after the watcher reports changes; lookups only happen on the rename debounce
+/// thread, so the walk never blocks typing.
+#[derive(Debug)]
+pub(crate) struct StemIndex {
+    root: PathBuf,
+    by_stem: Option<HashMap<String, Vec<PathBuf>>>,
+}
+
+impl StemIndex {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            by_stem: None,
+        }
+    }
+
+    pub fn set_root(&mut self, root: PathBuf) {
+        self.root = root;
+        self.by_stem = None;
+    }
+
+    pub fn invalidate(&mut self) {
+        self.by_stem = None;
+    }
+
+    /// Notes whose stem equals `stem` (ignoring case), sorted by path.
+    pub fn notes_named(&mut self, stem: &str) -> &[PathBuf] {
+        let root = self.root.as_path();
+        self.by_stem
+            .get_or_insert_with(|| build_stem_index(root))
+            .get(&stem_key(stem))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+}
+
+fn build_stem_index(root: &Path) -> HashMap<String, Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    if let Err(error) = crate::tag_index::collect_note_paths(root, &mut paths) {
+        trace_debug(format!(
+            "req-dup stem index walk failed root={} error={error}",
+            root.display()
+        ));
+    }
+    paths.sort();
+    let mut by_stem = HashMap::<String, Vec<PathBuf>>::new();
+    for path in paths {
+        if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+            by_stem.entry(stem_key(stem)).or_default().push(path);
+        }
+    }
+    trace_debug(format!(
+        "req-dup stem index built root={} stems={}",
+        root.display(),
+        by_stem.len()
+    ));
+    by_stem
+}
+
+/// Shown instead of creating a note whose title another note already has.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct DuplicateTitleNotice {
+    pub stem: String,
+    pub existing: PathBuf,
+    pub other_count: usize,
+}
+
+#[derive(Debug)]
+struct DuplicateTitleGateState {
+    index: StemIndex,
+    /// Stem the user chose "Use this title anyway" for.
+    confirmed: Option<String>,
+    /// The rename waiting on the banner.
+    held: Option<crate::file_update_handler::PendingTitleRename>,
+}
+
+/// Checks a title once typing pauses, right before the debounced rename: a note whose title
+/// another note already has keeps its old name until the user picks a banner button.
+#[derive(Clone)]
+pub(crate) struct DuplicateTitleGate {
+    inner: Arc<Mutex<DuplicateTitleGateState>>,
+    notices: smol::channel::Sender<Option<DuplicateTitleNotice>>,
+}
+
+impl DuplicateTitleGate {
+    pub fn new(root: PathBuf) -> (Self, smol::channel::Receiver<Option<DuplicateTitleNotice>>) {
+        let (notices, notice_rx) = smol::channel::unbounded();
+        let gate = Self {
+            inner: Arc::new(Mutex::new(DuplicateTitleGateState {
+                index: StemIndex::new(root),
+                confirmed: None,
+                held: None,
+            })),
+            notices,
+        };
+        (gate, notice_rx)
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, DuplicateTitleGateState> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn set_root(&self, root: PathBuf) {
+        let mut state = self.state();
+        state.index.set_root(root);
+        state.confirmed = None;
+        state.held = None;
+    }
+
+    pub fn invalidate(&self) {
+        self.state().index.invalidate();
+    }
+
+    /// Called on the debounce thread. `false` holds the rename and raises the banner.
+    pub fn allow_rename(&self, pending: &crate::file_update_handler::PendingTitleRename) -> bool {
+        let stem = crate::file_update_handler::stem_from_singleline_value(
+            &pending.singleline_value,
+            Local::now(),
+        );
+        let mut state = self.state();
+        state.held = None;
+        let confirmed = state
+            .confirmed
+            .as_ref()
+            .is_some_and(|confirmed| stem_key(confirmed) == stem_key(&stem));
+        let notice = if confirmed || pending.singleline_value.trim().is_empty() {
+            None
+        } else {
+            // The note itself already carrying the title is not a duplicate.
+            let others = state
+                .index
+                .notes_named(&stem)
+                .iter()
+                .filter(|path| **path != pending.path)
+                .cloned()
+                .collect::<Vec<_>>();
+            others
+                .split_first()
+                .map(|(existing, rest)| DuplicateTitleNotice {
+                    stem: stem.clone(),
+                    existing: existing.clone(),
+                    other_count: rest.len(),
+                })
+        };
+        let allowed = notice.is_none();
+        if !allowed {
+            trace_debug(format!(
+                "req-dup rename held path={} stem='{}'",
+                pending.path.display(),
+                crate::app::compact_text(&stem)
+            ));
+            state.held = Some(pending.clone());
+        }
+        drop(state);
+        let _ = self.notices.try_send(notice);
+        allowed
+    }
+
+    /// "Use this title anyway": the held rename, now allowed.
+    pub fn confirm_held(&self) -> Option<crate::file_update_handler::PendingTitleRename> {
+        let mut state = self.state();
+        let held = state.held.take()?;
+        state.confirmed = Some(crate::file_update_handler::stem_from_singleline_value(
+            &held.singleline_value,
+            Local::now(),
+        ));
+        Some(held)
+    }
+
+    /// A held rename belongs to the note it was typed for; it is dropped once another note
+    /// is open.
+    pub fn on_edit_path_changed(&self, path: Option<&Path>) -> bool {
+        let mut state = self.state();
+        if state
+            .held
+            .as_ref()
+            .is_some_and(|held| Some(held.path.as_path()) != path)
+        {
+            state.held = None;
+            state.confirmed = None;
+            return true;
+        }
+        false
+    }
+
+    pub fn discard_held(&self) {
+        self.state().held = None;
+    }
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn spawn_duplicate_title_notice_loop(
+        notice_rx: smol::channel::Receiver<Option<DuplicateTitleNotice>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, cx| {
+            while let Ok(notice) = notice_rx.recv().await {
+                let updated = this.update_in(cx, |app, _, cx| {
+                    app.set_duplicate_title_notice(notice, cx);
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    pub(crate) fn release_duplicate_title_hold(
+        &mut self,
+        path: Option<&Path>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.duplicate_title_gate.on_edit_path_changed(path) {
+            self.set_duplicate_title_notice(None, cx);
+        }
+    }
+
+    fn set_duplicate_title_notice(
+        &mut self,
+        notice: Option<DuplicateTitleNotice>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.duplicate_title_notice != notice {
+            self.duplicate_title_notice = notice;
+            cx.notify();
+        }
+    }
+
+    fn open_duplicate_title_existing(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(notice) = self.duplicate_title_notice.take() else {
+            return;
+        };
+        trace_debug(format!(
+            "req-dup open existing path={}",
+            notice.existing.display()
+        ));
+        self.duplicate_title_gate.discard_held();
+        if !self.open_file(notice.existing.clone(), window, cx) {
+            self.show_transient_notice(
+                crate::i18n::tr_with(
+                    "notice.cannot_open_path",
+                    &[("path", notice.existing.display().to_string().as_str())],
+                ),
+                cx,
+            );
+        }
+        cx.notify();
+    }
+
+    fn rename_despite_duplicate_title(&mut self, cx: &mut Context<Self>) {
+        let Some(notice) = self.duplicate_title_notice.take() else {
+            return;
+        };
+        trace_debug(format!(
+            "req-dup use title anyway stem='{}'",
+            crate::app::compact_text(&notice.stem)
+        ));
+        if let Some(held) = self.duplicate_title_gate.confirm_held() {
+            crate::file_update_handler::dispatch_title_rename(&self.file_workflow, held);
+        }
+        cx.notify();
+    }
+
+    pub(crate) fn render_duplicate_title_banner(
+        &self,
+        cx: &mut Context<Self>,
+    ) -> Option<AnyElement> {
+        let notice = self.duplicate_title_notice.as_ref()?;
+        let location = notice
+            .existing
+            .strip_prefix(&self.app_paths.user_document_dir)
+            .unwrap_or(notice.existing.as_path())
+            .display()
+            .to_string();
+        let others = match notice.other_count {
+            0 => String::new(),
+            count => format!(" (and {count} more)"),
+        };
+        Some(
+            h_flex()
+                .w_full()
+                .gap_2()
+                .px_2()
+                .py_1()
+                .items_center()
+                .text_xs()
+                .bg(cx.theme().warning)
+                .child(div().flex_1().child(format!(
+                    "A note named \"{}\" already exists: {location}{others}",
+                    notice.stem
+                )))
+                .child(
+                    Button::new("duplicate-title-open")
+                        .small()
+                        .label(crate::i18n::tr("button.open_existing"))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.open_duplicate_title_existing(window, cx)
+                        })),
+                )
+                .child(
+                    Button::new("duplicate-title-create")
+                        .small()
+                        .ghost()
+                        .label(crate::i18n::tr("button.use_title_anyway"))
+                        .on_click(
+                            cx.listener(|this, _, _, cx| this.rename_despite_duplicate_title(cx)),
+                        ),
+                )
+                .into_any_element(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn dup_test1_stem_index_matches_vault_wide_and_ignores_case() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_duplicate_titles_dup_test1_{}_{stamp}",
+            std::process::id()
+        ));
+        let day_a = root.join("2026").join("03").join("04");
+        let day_b = root.join("2026").join("05").join("11");
+        fs::create_dir_all(&day_a).expect("create day a");
+        fs::create_dir_all(&day_b).expect("create day b");
+        fs::write(day_b.join("Meeting.md"), "b").expect("write b");
+        fs::write(day_a.join("meeting.txt"), "a").expect("write a");
+        fs::write(day_a.join("meeting.png"), "x").expect("write png");
+
+        let mut index = StemIndex::new(root.clone());
+        assert_eq!(
+            index.notes_named("MEETING"),
+            [day_a.join("meeting.txt"), day_b.join("Meeting.md")]
+        );
+        assert!(index.notes_named("meet").is_empty());
+
+        fs::write(day_b.join("plan.txt"), "p").expect("write plan");
+        assert!(index.notes_named("plan").is_empty());
+        index.invalidate();
+        assert_eq!(index.notes_named("plan"), [day_b.join("plan.txt")]);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn dup_test2_gate_holds_the_rename_until_confirmed() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_duplicate_titles_dup_test2_{}_{stamp}",
+            std::process::id()
+        ));
+        let day = root.join("2026").join("03").join("04");
+        fs::create_dir_all(&day).expect("create day");
+        fs::write(day.join("meeting.txt"), "a").expect("write existing");
+        fs::write(day.join("m.txt"), "").expect("write new note");
+        let pending = |path: PathBuf, value: &str| crate::file_update_handler::PendingTitleRename {
+            path,
+            singleline_value: value.to_string(),
+            user_document_dir: root.clone(),
+        };
+
+        let (gate, notices) = DuplicateTitleGate::new(root.clone());
+        let typed = pending(day.join("m.txt"), "Meeting");
+        assert!(!gate.allow_rename(&typed));
+        let notice = notices.try_recv().expect("notice").expect("duplicate");
+        assert_eq!(notice.existing, day.join("meeting.txt"));
+        // The existing note keeping its own title is not a duplicate of itself.
+        assert!(gate.allow_rename(&pending(day.join("meeting.txt"), "meeting")));
+        assert_eq!(notices.try_recv().expect("notice"), None);
+
+        assert!(!gate.allow_rename(&typed));
+        assert_eq!(gate.confirm_held(), Some(typed.clone()));
+        assert!(gate.allow_rename(&typed));
+
+        gate.set_root(root.clone());
+        assert!(!gate.allow_rename(&typed));
+        assert!(gate.on_edit_path_changed(Some(day.join("other.txt").as_path())));
+        assert_eq!(gate.confirm_held(), None);
+        let _ = fs::remove_dir_all(root);
+    }
+}
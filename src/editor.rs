@@ -1,9 +1,18 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeSet,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
+use gpui::prelude::FluentBuilder as _;
 use gpui::*;
 use gpui_component::{
-    ActiveTheme,
-    input::{Input, InputState},
+    ActiveTheme, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    highlighter::{Diagnostic, DiagnosticSeverity},
+    input::{Input, InputState, TabSize},
 };
 
 use gpui_component::input::InputEvent;
@@ -13,7 +22,41 @@ pub enum EditorEvent {
     PressUpAtFirstLine,
     FocusGained,
     UserInteraction,
-    UserBufferChanged { value: String },
+    UserBufferChanged {
+        value: String,
+    },
+    BookmarkToggleRequested {
+        line: u32,
+    },
+    /// A user edit moved the bookmarked lines; the app persists the new set.
+    BookmarksShifted {
+        lines: BTreeSet<u32>,
+    },
+    /// The first line shown at the top of the editor changed.
+    ViewportScrolled,
+    /// Ctrl+V with an image and no text on the clipboard; the app saves it as an attachment.
+    ImagePasted(Image),
+    /// A background open is done; `error` is set when the note could not be read.
+    BackgroundLoadFinished {
+        path: PathBuf,
+        error: Option<String>,
+    },
+}
+
+struct EditorFindBar {
+    query_input: Entity<InputState>,
+    replace_input: Entity<InputState>,
+    options: crate::editor_find::FindOptions,
+    matches: Vec<Range<usize>>,
+    active_match: Option<usize>,
+    error: Option<String>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Clone, Debug)]
+struct SpellSuggestionMenu {
+    misspelling: crate::spell_check::Misspelling,
+    suggestions: Vec<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -29,19 +72,162 @@ pub struct Papyru2Editor {
     last_cursor: gpui_component::input::Position,
     pending_programmatic_change_events: usize,
     current_editing_file_path: Option<PathBuf>,
+    /// Set while a large note is read in the background; edits are reverted until it lands.
+    loading_path: Option<PathBuf>,
+    load_error: Option<String>,
+    load_generation: u64,
+    hide_note_metadata: bool,
     _subscriptions: Vec<Subscription>,
     font_size_logged_once: bool,
     ui_color_config: crate::app::UiColorConfig,
+    layout_prefs: crate::note_front_matter::NoteLayoutPrefs,
+    language_map: crate::syntax_language::LanguageMap,
+    /// Picked from the status bar for the open buffer; cleared when another note opens.
+    language_override: Option<String>,
+    current_language: String,
+    focus_mode: bool,
+    /// Lines left undimmed while focus mode is on.
+    focus_paragraph: Option<Range<u32>>,
+    /// Where the input was laid out last frame, in window coordinates.
+    input_bounds: Option<Bounds<Pixels>>,
+    top_visible_line: u32,
+    font_family: Option<SharedString>,
+    base_font_size: Option<f32>,
+    zoom: f32,
+    transforms: crate::editor_transforms::EditorTransformToggles,
+    auto_closers: crate::editor_transforms::AutoClosers,
+    spell_checker: Arc<crate::spell_check::SpellChecker>,
+    misspellings: Vec<crate::spell_check::Misspelling>,
+    spell_check_cache: crate::spell_check::SpellCheckCache,
+    spell_check_generation: u64,
+    spell_suggestion_menu: Option<SpellSuggestionMenu>,
+    display: EditorDisplayOptions,
+    bookmarked_lines: BTreeSet<u32>,
+    find_bar: Option<EditorFindBar>,
+    confirmations: crate::confirmations::ConfirmationPolicy,
 }
 
 impl EventEmitter<EditorEvent> for Papyru2Editor {}
 
+const EDITOR_TAB_WIDTH_CHOICES: [usize; 3] = [2, 4, 8];
+
+/// The `[editor]` display settings that can be switched while papyru2 runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct EditorDisplayOptions {
+    pub soft_wrap: bool,
+    pub line_number: bool,
+    pub tab_width: usize,
+    pub typewriter_scrolling: bool,
+    pub spell_check: bool,
+}
+
+impl EditorDisplayOptions {
+    /// 2 -> 4 -> 8 -> 2; a configured width outside the cycle goes to the next larger one.
+    pub fn next_tab_width(self) -> usize {
+        EDITOR_TAB_WIDTH_CHOICES
+            .iter()
+            .copied()
+            .find(|width| *width > self.tab_width)
+            .unwrap_or(EDITOR_TAB_WIDTH_CHOICES[0])
+    }
+}
+
 pub(crate) fn req_editor_editor_font_size_policy() -> &'static str {
     crate::app::req_editor_shared_text_size_policy()
 }
 
 pub(crate) fn read_editor_text_from_disk(path: &Path) -> std::io::Result<String> {
-    std::fs::read_to_string(path)
+    crate::text_encoding::open_note_text(path)
+}
+
+/// Notes from this size on are read on a background task behind a loading indicator.
+pub(crate) const EDITOR_BACKGROUND_LOAD_BYTES: u64 = 1024 * 1024;
+/// Opening a note from this size on goes through the `large_file_open` confirmation.
+pub(crate) const EDITOR_LARGE_FILE_PROMPT_BYTES: u64 = 16 * 1024 * 1024;
+/// Typing pauses this long before the changed lines are spell-checked.
+const SPELL_CHECK_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(300);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum EditorLoadMode {
+    Inline,
+    Background,
+    ConfirmThenBackground,
+}
+
+pub(crate) fn editor_load_mode(size_bytes: u64) -> EditorLoadMode {
+    if size_bytes >= EDITOR_LARGE_FILE_PROMPT_BYTES {
+        EditorLoadMode::ConfirmThenBackground
+    } else if size_bytes >= EDITOR_BACKGROUND_LOAD_BYTES {
+        EditorLoadMode::Background
+    } else {
+        EditorLoadMode::Inline
+    }
+}
+
+fn resolve_editor_language(
+    path: &Path,
+    layout_prefs: &crate::note_front_matter::NoteLayoutPrefs,
+    language_map: &crate::syntax_language::LanguageMap,
+) -> String {
+    if let Some(language) = layout_prefs.language.as_ref() {
+        return language.clone();
+    }
+    language_map.language_for_path(path)
+}
+
+// Screenshots often arrive with a text flavor too (a file name or URL); text paste wins then.
+fn clipboard_image_without_text(cx: &App) -> Option<Image> {
+    let item = cx.read_from_clipboard()?;
+    if item.text().is_some_and(|text| !text.trim().is_empty()) {
+        return None;
+    }
+    item.entries().iter().find_map(|entry| match entry {
+        ClipboardEntry::Image(image) => Some(image.clone()),
+        _ => None,
+    })
+}
+
+// Keeps the cursor on the same body line after the front-matter block grows or shrinks.
+fn shift_cursor_line(cursor_line: u32, previous_value: &str, value: &str) -> u32 {
+    let previous_lines = previous_value.split('\n').count() as i64;
+    let lines = value.split('\n').count() as i64;
+    (i64::from(cursor_line) + lines - previous_lines).clamp(0, i64::from(u32::MAX)) as u32
+}
+
+/// The byte range of `previous` that differs from `value`, and what replaces it there; a
+/// whole-buffer edit shrinks to this so it lands in the input as one ranged, undoable change.
+pub(crate) fn changed_span<'a>(previous: &str, value: &'a str) -> (Range<usize>, &'a str) {
+    let prefix = previous
+        .char_indices()
+        .zip(value.chars())
+        .find(|((_, old), new)| old != new)
+        .map_or(previous.len().min(value.len()), |((index, _), _)| index);
+    let suffix = previous[prefix..]
+        .chars()
+        .rev()
+        .zip(value[prefix..].chars().rev())
+        .take_while(|(old, new)| old == new)
+        .map(|(old, _)| old.len_utf8())
+        .sum::<usize>();
+    (
+        prefix..previous.len() - suffix,
+        &value[prefix..value.len() - suffix],
+    )
+}
+
+fn utf16_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+fn byte_offset_for_utf16(text: &str, utf16_offset: usize) -> usize {
+    let mut utf16 = 0usize;
+    for (byte_index, ch) in text.char_indices() {
+        if utf16 >= utf16_offset {
+            return byte_index;
+        }
+        utf16 += ch.len_utf16();
+    }
+    text.len()
 }
 
 fn should_emit_backspace_at_line_head_on_change(
@@ -77,6 +263,53 @@ fn should_emit_backspace_at_line_head_on_change(
 }
 
 const RPC_SCROLL_CENTERING_HALF_LINES_ESTIMATE: u32 = 9;
+const EDITOR_GUTTER_MIN_DIGITS: usize = 2;
+const EDITOR_GUTTER_PADDING_CHARS: usize = 2;
+const EDITOR_MONO_ADVANCE_EM_ESTIMATE: f32 = 0.6;
+pub(crate) const EDITOR_ZOOM_DEFAULT: f32 = 1.0;
+const EDITOR_ZOOM_MIN: f32 = 0.5;
+const EDITOR_ZOOM_MAX: f32 = 3.0;
+const EDITOR_ZOOM_STEP: f32 = 0.1;
+
+/// `None` for a zoom that is not finite or outside 50%..=300%.
+pub(crate) fn normalize_editor_zoom(zoom: f32) -> Option<f32> {
+    (zoom.is_finite() && (EDITOR_ZOOM_MIN..=EDITOR_ZOOM_MAX).contains(&zoom)).then_some(zoom)
+}
+
+// Rounded to whole steps so repeated zooming never drifts off 100%.
+fn step_editor_zoom(zoom: f32, steps: i32) -> f32 {
+    let stepped = ((zoom / EDITOR_ZOOM_STEP).round() + steps as f32) * EDITOR_ZOOM_STEP;
+    ((stepped * 100.0).round() / 100.0).clamp(EDITOR_ZOOM_MIN, EDITOR_ZOOM_MAX)
+}
+
+// gpui-component does not expose the line-number gutter bounds, so hit-testing uses the
+// digit count of the last line number at an estimated monospace advance.
+fn editor_gutter_width_px(total_lines: u32, font_size_px: f32) -> f32 {
+    let digits = total_lines
+        .max(1)
+        .to_string()
+        .len()
+        .max(EDITOR_GUTTER_MIN_DIGITS);
+    (digits + EDITOR_GUTTER_PADDING_CHARS) as f32 * font_size_px * EDITOR_MONO_ADVANCE_EM_ESTIMATE
+}
+
+// Text area wide enough for `columns` characters plus the caret, after the gutter.
+fn wrap_column_width_px(columns: u32, ch_advance_px: f32, gutter_px: f32) -> f32 {
+    gutter_px + columns.saturating_add(1) as f32 * ch_advance_px
+}
+
+fn is_editor_gutter_hit(offset_x_px: f32, total_lines: u32, font_size_px: f32) -> bool {
+    offset_x_px >= 0.0 && offset_x_px < editor_gutter_width_px(total_lines, font_size_px)
+}
+
+// Scroll-up delta that first takes the input to its top, whatever the current offset.
+const TYPEWRITER_SCROLL_TO_TOP_PX: f32 = 1.0e9;
+
+// How far down, from the top of the text, the input has to scroll for the caret line to sit
+// in the middle of the viewport. Near the top of a note it stays at 0.
+fn typewriter_scroll_top(caret_top_px: f32, line_height_px: f32, viewport_height_px: f32) -> f32 {
+    (caret_top_px + line_height_px / 2.0 - viewport_height_px / 2.0).max(0.0)
+}
 
 fn rpc_centering_anchor_line(target_line_0_based: u32, total_lines: usize) -> u32 {
     let bounded_total_lines = total_lines.max(1).min(u32::MAX as usize) as u32;
@@ -96,6 +329,7 @@ impl Papyru2Editor {
         window: &mut Window,
         ui_color_config: crate::app::UiColorConfig,
         editor_config: crate::app::EditorConfig,
+        spell_checker: Arc<crate::spell_check::SpellChecker>,
         cx: &mut Context<Self>,
     ) -> Self {
         let input_state = cx.new(|cx| {
@@ -103,8 +337,12 @@ impl Papyru2Editor {
                 .code_editor(editor_config.code_editor.clone())
                 .line_number(editor_config.line_number)
                 .soft_wrap(editor_config.soft_wrap)
+                .tab_size(TabSize {
+                    tab_size: editor_config.tab_width,
+                    hard_tabs: false,
+                })
                 .searchable(true)
-                .placeholder("File is auto saved")
+                .placeholder(editor_config.placeholder.clone())
         });
 
         let (last_value, last_cursor) = {
@@ -112,22 +350,24 @@ impl Papyru2Editor {
             (initial.value().to_string(), initial.cursor_position())
         };
 
-        let _subscriptions = vec![cx.subscribe_in(&input_state, window, {
-            move |this, state, event: &InputEvent, _window, cx| match event {
+        let mut _subscriptions = vec![cx.subscribe_in(&input_state, window, {
+            move |this, state, event: &InputEvent, window, cx| match event {
                 InputEvent::Change => {
                     let state = state.read(cx);
                     let cursor = state.cursor_position();
                     let value = state.value().to_string();
-                    crate::log::trace_debug(format!(
-                        "editor InputEvent::Change cursor=({}, {}) value='{}'",
+                    crate::log::trace_input(format!(
+                        "editor InputEvent::Change cursor=({}, {}) len={}",
                         cursor.line,
                         cursor.character,
-                        crate::app::compact_text(&value)
+                        value.len()
                     ));
+                    this.schedule_spell_check(cx);
+                    this.refresh_find_matches(&value, cx);
 
                     if this.pending_programmatic_change_events > 0 {
                         this.pending_programmatic_change_events -= 1;
-                        crate::log::trace_debug(format!(
+                        crate::log::trace_input(format!(
                             "editor InputEvent::Change ignored as programmatic (remaining={})",
                             this.pending_programmatic_change_events
                         ));
@@ -136,6 +376,14 @@ impl Papyru2Editor {
                         return;
                     }
 
+                    if (this.layout_prefs.is_readonly() || this.loading_path.is_some())
+                        && value != this.last_value
+                    {
+                        this.revert_readonly_change(window, cx);
+                        return;
+                    }
+                    this.auto_closers.follow_edit(&this.last_value, &value);
+
                     let should_emit_backspace = should_emit_backspace_at_line_head_on_change(
                         &this.last_value,
                         &this.last_cursor,
@@ -174,104 +422,1379 @@ impl Papyru2Editor {
                         cx.emit(EditorEvent::BackspaceAtLineHead);
                     }
 
-                    if value != this.last_value {
-                        crate::log::trace_debug(format!(
-                            "editor emit UserBufferChanged len={} cursor=({}, {})",
-                            value.len(),
-                            cursor.line,
-                            cursor.character
-                        ));
-                        cx.emit(EditorEvent::UserBufferChanged {
-                            value: value.clone(),
-                        });
-                    }
+                    if value != this.last_value {
+                        crate::log::trace_input(format!(
+                            "editor emit UserBufferChanged len={} cursor=({}, {})",
+                            value.len(),
+                            cursor.line,
+                            cursor.character
+                        ));
+                        let previous = std::mem::take(&mut this.last_value);
+                        this.emit_user_buffer_changed(&previous, value.clone(), cx);
+                        if this.typewriter_scrolling_active() {
+                            this.center_cursor_line(window, cx);
+                        }
+                    }
+
+                    this.last_value = value;
+                    this.last_cursor = cursor;
+                }
+                InputEvent::PressEnter { secondary } => {
+                    crate::log::trace_debug(format!(
+                        "editor InputEvent::PressEnter secondary={secondary}"
+                    ));
+                }
+                InputEvent::Focus => {
+                    crate::log::trace_debug("editor InputEvent::Focus");
+                    cx.emit(EditorEvent::FocusGained);
+                }
+                InputEvent::Blur => {
+                    crate::log::trace_debug("editor InputEvent::Blur");
+                }
+            }
+        })];
+        // Arrow keys and clicks move the cursor without a Change event; the observer keeps
+        // the focus-mode paragraph on the cursor.
+        _subscriptions
+            .push(cx.observe(&input_state, |this, _, cx| this.refresh_focus_paragraph(cx)));
+
+        crate::log::trace_debug(format!(
+            "req-editor8 editor font_size_policy={}",
+            req_editor_editor_font_size_policy()
+        ));
+        crate::log::trace_debug(format!(
+            "req-editor startup editor_config code_editor={} soft_wrap={} line_number={} show_whitespaces={} placeholder='{}' searchable=true",
+            editor_config.code_editor,
+            editor_config.soft_wrap,
+            editor_config.line_number,
+            editor_config.show_whitespaces,
+            crate::app::compact_text(&editor_config.placeholder)
+        ));
+        if editor_config.show_whitespaces {
+            crate::log::trace_debug(
+                "req-editor10 show_whitespaces=true requested but current gpui-component API has no show_whitespaces toggle; preserving config for future API support",
+            );
+        }
+
+        Self {
+            input_state,
+            last_value,
+            last_cursor,
+            pending_programmatic_change_events: 0,
+            current_editing_file_path: None,
+            loading_path: None,
+            load_error: None,
+            load_generation: 0,
+            hide_note_metadata: false,
+            _subscriptions,
+            font_size_logged_once: false,
+            ui_color_config,
+            layout_prefs: crate::note_front_matter::NoteLayoutPrefs::default(),
+            language_map: crate::syntax_language::LanguageMap::default(),
+            language_override: None,
+            current_language: crate::syntax_language::PLAIN_TEXT_LANGUAGE.to_string(),
+            focus_mode: false,
+            focus_paragraph: None,
+            input_bounds: None,
+            top_visible_line: 0,
+            font_family: editor_config.font_family.clone().map(SharedString::from),
+            base_font_size: editor_config.font_size,
+            zoom: EDITOR_ZOOM_DEFAULT,
+            transforms: crate::editor_transforms::EditorTransformToggles::default(),
+            auto_closers: crate::editor_transforms::AutoClosers::default(),
+            spell_checker,
+            misspellings: Vec::new(),
+            spell_check_cache: crate::spell_check::SpellCheckCache::default(),
+            spell_check_generation: 0,
+            spell_suggestion_menu: None,
+            display: EditorDisplayOptions {
+                soft_wrap: editor_config.soft_wrap,
+                line_number: editor_config.line_number,
+                tab_width: editor_config.tab_width,
+                typewriter_scrolling: editor_config.typewriter_scrolling,
+                spell_check: editor_config.spell_check,
+            },
+            bookmarked_lines: BTreeSet::new(),
+            find_bar: None,
+            confirmations: crate::confirmations::ConfirmationPolicy::default(),
+        }
+    }
+
+    // Each edit restarts the wait; only the last one in a burst of typing runs the check.
+    fn schedule_spell_check(&mut self, cx: &mut Context<Self>) {
+        self.spell_check_generation += 1;
+        if !self.display.spell_check {
+            return;
+        }
+        let generation = self.spell_check_generation;
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(SPELL_CHECK_DEBOUNCE).await;
+            let _ = this.update(cx, |this, cx| {
+                if this.spell_check_generation == generation {
+                    this.refresh_spell_check(cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn refresh_spell_check(&mut self, cx: &mut Context<Self>) {
+        let value = self.input_state.read(cx).value();
+        let checked_lines = self.spell_check_cache.update(&self.spell_checker, &value);
+        let misspellings = self.spell_check_cache.misspellings();
+        if misspellings == self.misspellings {
+            return;
+        }
+        crate::log::trace_debug(format!(
+            "req-spell1 spell_check languages={:?} checked_lines={} misspellings={}",
+            self.spell_checker.languages(),
+            checked_lines,
+            misspellings.len()
+        ));
+        self.set_misspellings(misspellings, cx);
+    }
+
+    fn set_misspellings(
+        &mut self,
+        misspellings: Vec<crate::spell_check::Misspelling>,
+        cx: &mut Context<Self>,
+    ) {
+        self.misspellings = misspellings;
+        self.spell_suggestion_menu = None;
+        self.sync_diagnostics(cx);
+    }
+
+    pub fn set_bookmarked_lines(&mut self, lines: BTreeSet<u32>, cx: &mut Context<Self>) {
+        if lines == self.bookmarked_lines {
+            return;
+        }
+        crate::log::trace_debug(format!("req-bmk1 editor bookmarked_lines={:?}", lines));
+        self.bookmarked_lines = lines;
+        self.sync_diagnostics(cx);
+    }
+
+    // Every user edit goes out through here so bookmarks below it follow the text they mark.
+    fn emit_user_buffer_changed(&mut self, previous: &str, value: String, cx: &mut Context<Self>) {
+        if !self.bookmarked_lines.is_empty() {
+            let lines =
+                crate::bookmarks::shift_lines_for_edit(&self.bookmarked_lines, previous, &value);
+            if lines != self.bookmarked_lines {
+                crate::log::trace_debug(format!(
+                    "req-bmk1 editor bookmarks shifted to {:?}",
+                    lines
+                ));
+                self.bookmarked_lines = lines.clone();
+                self.sync_diagnostics(cx);
+                cx.emit(EditorEvent::BookmarksShifted { lines });
+            }
+        }
+        cx.emit(EditorEvent::UserBufferChanged { value });
+    }
+
+    pub fn display_options(&self) -> EditorDisplayOptions {
+        self.display
+    }
+
+    /// Soft wrap and line numbers switch immediately; the input only takes a tab width when
+    /// it is built, so a new width applies from the next start.
+    pub fn set_display_options(
+        &mut self,
+        options: EditorDisplayOptions,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if options == self.display {
+            return;
+        }
+        crate::log::trace_debug(format!(
+            "req-editor display options soft_wrap={} line_number={} tab_width={} typewriter_scrolling={} spell_check={}",
+            options.soft_wrap,
+            options.line_number,
+            options.tab_width,
+            options.typewriter_scrolling,
+            options.spell_check
+        ));
+        let spell_check_changed = options.spell_check != self.display.spell_check;
+        self.display = options;
+        let soft_wrap = self.soft_wrap_active();
+        self.input_state.update(cx, |state, cx| {
+            state.set_soft_wrap(soft_wrap, window, cx);
+            state.set_line_number(options.line_number, window, cx);
+        });
+        if spell_check_changed {
+            self.spell_check_cache.clear();
+            self.schedule_spell_check(cx);
+            if !options.spell_check {
+                self.set_misspellings(Vec::new(), cx);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Configured size (or the theme's, plus the half pixel the editor has always added)
+    /// times the zoom.
+    fn font_size_px(&self, cx: &App) -> f32 {
+        self.base_font_size
+            .unwrap_or_else(|| f32::from(cx.theme().font_size) + 0.5)
+            * self.zoom
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    pub fn set_zoom(&mut self, zoom: f32, cx: &mut Context<Self>) {
+        let zoom = normalize_editor_zoom(zoom).unwrap_or(EDITOR_ZOOM_DEFAULT);
+        if zoom != self.zoom {
+            crate::log::trace_debug(format!("req-editor zoom={zoom}"));
+            self.zoom = zoom;
+            cx.notify();
+        }
+    }
+
+    /// Background and text follow the color scheme picked while running.
+    pub fn set_ui_color_config(
+        &mut self,
+        ui_color_config: crate::app::UiColorConfig,
+        cx: &mut Context<Self>,
+    ) {
+        self.ui_color_config = ui_color_config;
+        cx.notify();
+    }
+
+    fn typewriter_scrolling_active(&self) -> bool {
+        self.display.typewriter_scrolling || self.layout_prefs.is_typewriter()
+    }
+
+    // The input has no scroll API of its own, so the view is moved with two wheel events over
+    // it: one back to the top, one down to where the caret line sits mid-viewport. The caret
+    // itself never moves. It runs a frame later, once the input has laid out the edit and
+    // scrolled the caret into view itself.
+    fn center_cursor_line(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        cx.on_next_frame(window, |this, window, cx| {
+            let Some(bounds) = this.input_bounds else {
+                return;
+            };
+            let caret_top = this.input_state.update(cx, |state, cx| {
+                let value = state.value();
+                let caret = utf16_len(&value[..state.cursor().min(value.len())]);
+                state
+                    .bounds_for_range(caret..caret, bounds, window, cx)
+                    .map(|caret| caret.top() - bounds.top())
+            });
+            let Some(caret_top) = caret_top else {
+                return;
+            };
+            let scroll_top = typewriter_scroll_top(
+                f32::from(caret_top),
+                f32::from(window.line_height()),
+                f32::from(bounds.size.height),
+            );
+            let over_input = bounds.center();
+            window.defer(cx, move |window, cx| {
+                let mouse_position = window.mouse_position();
+                for delta_y in [TYPEWRITER_SCROLL_TO_TOP_PX, -scroll_top] {
+                    window.dispatch_event(
+                        PlatformInput::ScrollWheel(ScrollWheelEvent {
+                            position: over_input,
+                            delta: ScrollDelta::Pixels(point(px(0.), px(delta_y))),
+                            modifiers: Modifiers::default(),
+                            touch_phase: TouchPhase::Moved,
+                        }),
+                        cx,
+                    );
+                }
+                // Hover state follows the real pointer again.
+                window.dispatch_event(
+                    PlatformInput::MouseMove(MouseMoveEvent {
+                        position: mouse_position,
+                        pressed_button: None,
+                        modifiers: Modifiers::default(),
+                    }),
+                    cx,
+                );
+            });
+        });
+    }
+
+    pub fn top_visible_line(&self) -> u32 {
+        self.top_visible_line
+    }
+
+    // Wheel scrolling and caret moves past the edge both scroll the input; the layout that
+    // says which line is now at the top is only there a frame later.
+    fn refresh_top_visible_line_next_frame(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        cx.on_next_frame(window, |this, window, cx| {
+            let Some(bounds) = this.input_bounds else {
+                return;
+            };
+            let line = this.input_state.update(cx, |state, cx| {
+                let offset = state.character_index_for_point(
+                    bounds.origin + point(px(1.), px(1.)),
+                    window,
+                    cx,
+                )?;
+                let value = state.value();
+                Some(
+                    crate::editor_find::byte_offset_to_line_char(
+                        &value,
+                        byte_offset_for_utf16(&value, offset),
+                    )
+                    .0,
+                )
+            });
+            if let Some(line) = line
+                && line != this.top_visible_line
+            {
+                this.top_visible_line = line;
+                cx.emit(EditorEvent::ViewportScrolled);
+            }
+        });
+    }
+
+    fn on_scroll_wheel(
+        &mut self,
+        _: &ScrollWheelEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.refresh_top_visible_line_next_frame(window, cx);
+    }
+
+    pub fn set_focus_mode(&mut self, focus_mode: bool, cx: &mut Context<Self>) {
+        self.focus_mode = focus_mode;
+        self.focus_paragraph = None;
+        self.refresh_focus_paragraph(cx);
+        cx.notify();
+    }
+
+    fn refresh_focus_paragraph(&mut self, cx: &mut Context<Self>) {
+        let paragraph = self.focus_mode.then(|| {
+            let state = self.input_state.read(cx);
+            crate::focus_mode::paragraph_line_range(state.value(), state.cursor_position().line)
+        });
+        // Scrolling moves the paragraph on screen too, so the scrim follows every change.
+        self.focus_paragraph = paragraph;
+        if self.focus_mode {
+            cx.notify();
+        }
+    }
+
+    /// The band of the input, top to bottom and relative to its top edge, that the current
+    /// paragraph takes on screen; the rest is dimmed. The input only lays out what is visible,
+    /// so both edges are clamped to the visible text first.
+    fn focus_paragraph_band(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<(Pixels, Pixels)> {
+        let paragraph = self.focus_paragraph.clone()?;
+        let bounds = self.input_bounds?;
+        self.input_state.update(cx, |state, cx| {
+            let value = state.value();
+            let start = crate::editor_transforms::byte_offset(&value, paragraph.start, 0)?;
+            let end = crate::editor_transforms::byte_offset(&value, paragraph.end, 0);
+            let (start, end) = (
+                utf16_len(&value[..start]),
+                end.map(|end| utf16_len(&value[..end])),
+            );
+            let first_visible = state.character_index_for_point(
+                bounds.origin + point(px(1.), px(1.)),
+                window,
+                cx,
+            )?;
+            let last_visible = state
+                .character_index_for_point(
+                    bounds.bottom_left() + point(px(1.), px(-1.)),
+                    window,
+                    cx,
+                )
+                .unwrap_or(usize::MAX);
+            let mut edge = |offset: usize| {
+                if offset <= first_visible {
+                    Some(bounds.top())
+                } else if offset > last_visible {
+                    Some(bounds.bottom())
+                } else {
+                    state
+                        .bounds_for_range(offset..offset, bounds, window, cx)
+                        .map(|line| line.top())
+                }
+            };
+            let top = edge(start)?;
+            let bottom = match end {
+                Some(end) => edge(end)?,
+                None => bounds.bottom(),
+            };
+            Some((top - bounds.top(), bottom.max(top) - bounds.top()))
+        })
+    }
+
+    // Spelling hints, bookmark markers and find matches share the input's diagnostics set.
+    fn sync_diagnostics(&mut self, cx: &mut Context<Self>) {
+        let misspellings = &self.misspellings;
+        let bookmarked_lines = &self.bookmarked_lines;
+        let find_matches = self
+            .find_bar
+            .as_ref()
+            .map(|find_bar| find_bar.matches.as_slice())
+            .unwrap_or_default();
+        self.input_state.update(cx, |state, cx| {
+            let value = state.value();
+            let find_positions = crate::editor_find::byte_offsets_to_line_chars(
+                &value,
+                find_matches
+                    .iter()
+                    .flat_map(|range| [range.start, range.end]),
+            )
+            .chunks_exact(2)
+            .map(|ends| {
+                gpui_component::input::Position {
+                    line: ends[0].0,
+                    character: ends[0].1,
+                }..gpui_component::input::Position {
+                    line: ends[1].0,
+                    character: ends[1].1,
+                }
+            })
+            .collect::<Vec<_>>();
+            let line_lengths = value
+                .split('\n')
+                .map(|line| line.chars().count().min(u32::MAX as usize) as u32)
+                .collect::<Vec<_>>();
+            let Some(diagnostics) = state.diagnostics_mut() else {
+                return;
+            };
+            diagnostics.clear();
+            for range in find_positions {
+                diagnostics.push(
+                    Diagnostic::new(range, "Find match").with_severity(DiagnosticSeverity::Warning),
+                );
+            }
+            for line in bookmarked_lines {
+                let Some(length) = line_lengths.get(*line as usize) else {
+                    continue;
+                };
+                diagnostics.push(
+                    Diagnostic::new(
+                        gpui_component::input::Position {
+                            line: *line,
+                            character: 0,
+                        }..gpui_component::input::Position {
+                            line: *line,
+                            character: *length,
+                        },
+                        "Bookmark",
+                    )
+                    .with_severity(DiagnosticSeverity::Info),
+                );
+            }
+            for misspelling in misspellings {
+                diagnostics.push(
+                    Diagnostic::new(
+                        gpui_component::input::Position {
+                            line: misspelling.line,
+                            character: misspelling.start_char,
+                        }..gpui_component::input::Position {
+                            line: misspelling.line,
+                            character: misspelling.end_char,
+                        },
+                        format!("Unknown word '{}'", misspelling.word),
+                    )
+                    .with_severity(DiagnosticSeverity::Hint),
+                );
+            }
+            cx.notify();
+        });
+    }
+
+    // Runs after the input has moved the cursor for this click, so the cursor line is the
+    // clicked line. Plain click selects the line; Ctrl+click toggles a bookmark on it.
+    fn on_left_mouse_up(
+        &mut self,
+        event: &MouseUpEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.display.line_number {
+            return;
+        }
+        let Some(origin_x) = self.input_bounds.map(|bounds| bounds.origin.x) else {
+            return;
+        };
+        let snapshot = self.snapshot(cx);
+        let total_lines = crate::quic_rpc_protocol::content_line_count(&snapshot.value)
+            .min(u32::MAX as usize) as u32;
+        let offset_x_px = f32::from(event.position.x - origin_x);
+        if !is_editor_gutter_hit(offset_x_px, total_lines, self.font_size_px(cx)) {
+            return;
+        }
+
+        let line = snapshot.cursor_line;
+        crate::log::trace_debug(format!(
+            "req-bmk1 gutter click line={} ctrl={} offset_x={:.1}",
+            line, event.modifiers.control, offset_x_px
+        ));
+        if event.modifiers.control {
+            cx.emit(EditorEvent::BookmarkToggleRequested { line });
+        } else {
+            self.select_whole_line(line, total_lines, window, cx);
+        }
+        cx.stop_propagation();
+    }
+
+    fn select_whole_line(
+        &mut self,
+        line: u32,
+        total_lines: u32,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.apply_cursor(line, 0, window, cx);
+        if line.saturating_add(1) < total_lines {
+            window.dispatch_action(Box::new(gpui_component::input::SelectDown), cx);
+        } else {
+            window.dispatch_action(Box::new(gpui_component::input::SelectToEnd), cx);
+        }
+    }
+
+    // The word under the pointer, not the caret: a right-click does not move the caret.
+    fn on_right_mouse_down(
+        &mut self,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let clicked = self.input_state.update(cx, |state, cx| {
+            let utf16_offset = state.character_index_for_point(event.position, window, cx)?;
+            let value = state.value();
+            Some(crate::editor_find::byte_offset_to_line_char(
+                &value,
+                byte_offset_for_utf16(&value, utf16_offset),
+            ))
+        });
+        let menu = clicked
+            .and_then(|(line, character)| {
+                crate::spell_check::misspelling_at(&self.misspellings, line, character)
+            })
+            .map(|misspelling| SpellSuggestionMenu {
+                misspelling: misspelling.clone(),
+                suggestions: self.spell_checker.suggestions(
+                    &misspelling.word,
+                    crate::spell_check::SPELL_CHECK_MAX_SUGGESTIONS,
+                ),
+            });
+        crate::log::trace_debug(format!(
+            "req-spell1 right-click at={:?} word={} suggestions={}",
+            clicked,
+            menu.as_ref()
+                .map(|menu| menu.misspelling.word.as_str())
+                .unwrap_or("<none>"),
+            menu.as_ref()
+                .map(|menu| menu.suggestions.len())
+                .unwrap_or(0)
+        ));
+        self.spell_suggestion_menu = menu;
+        cx.notify();
+    }
+
+    fn apply_spell_suggestion(
+        &mut self,
+        suggestion_index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(menu) = self.spell_suggestion_menu.take() else {
+            return;
+        };
+        let Some(replacement) = menu.suggestions.get(suggestion_index) else {
+            return;
+        };
+        if self.layout_prefs.is_readonly() {
+            crate::log::trace_debug("req-spell1 suggestion skipped reason=readonly-note");
+            cx.notify();
+            return;
+        }
+        let snapshot = self.snapshot(cx);
+        let value = crate::spell_check::replace_misspelling(
+            &snapshot.value,
+            &menu.misspelling,
+            replacement,
+        );
+        let cursor_char = menu.misspelling.start_char + replacement.chars().count() as u32;
+        crate::log::trace_debug(format!(
+            "req-spell1 replace word={} with={}",
+            menu.misspelling.word, replacement
+        ));
+        self.apply_text_and_cursor(
+            value.clone(),
+            menu.misspelling.line,
+            cursor_char,
+            window,
+            cx,
+        );
+        self.emit_user_buffer_changed(&snapshot.value, value, cx);
+    }
+
+    fn open_find_bar(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.find_bar.is_none() {
+            let query_input = cx.new(|cx| {
+                InputState::new(window, cx).placeholder(crate::i18n::tr("placeholder.find"))
+            });
+            let replace_input = cx.new(|cx| {
+                InputState::new(window, cx).placeholder(crate::i18n::tr("placeholder.replace"))
+            });
+            let _subscriptions = vec![cx.subscribe_in(
+                &query_input,
+                window,
+                |this, _, event: &InputEvent, _window, cx| {
+                    if let InputEvent::Change = event {
+                        let value = this.input_state.read(cx).value().to_string();
+                        this.refresh_find_matches(&value, cx);
+                    }
+                },
+            )];
+            self.find_bar = Some(EditorFindBar {
+                query_input,
+                replace_input,
+                options: crate::editor_find::FindOptions::default(),
+                matches: Vec::new(),
+                active_match: None,
+                error: None,
+                _subscriptions,
+            });
+            crate::log::trace_debug("req-find1 find bar opened");
+        }
+        if let Some(find_bar) = self.find_bar.as_ref() {
+            find_bar
+                .query_input
+                .update(cx, |state, cx| state.focus(window, cx));
+        }
+        cx.notify();
+    }
+
+    fn close_find_bar(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.find_bar.take().is_none() {
+            return;
+        }
+        crate::log::trace_debug("req-find1 find bar closed");
+        self.sync_diagnostics(cx);
+        self.focus(window, cx);
+        cx.notify();
+    }
+
+    fn refresh_find_matches(&mut self, text: &str, cx: &mut Context<Self>) {
+        let cursor = self.input_state.read(cx).cursor_position();
+        let cursor_offset =
+            crate::editor_find::line_char_to_byte_offset(text, cursor.line, cursor.character);
+        let Some(find_bar) = self.find_bar.as_mut() else {
+            return;
+        };
+        let query = find_bar.query_input.read(cx).value().to_string();
+        match crate::editor_find::find_matches(text, &query, find_bar.options) {
+            Ok(matches) => {
+                find_bar.active_match =
+                    crate::editor_find::next_match_index(&matches, cursor_offset, true);
+                find_bar.matches = matches;
+                find_bar.error = None;
+            }
+            Err(error) => {
+                find_bar.matches.clear();
+                find_bar.active_match = None;
+                find_bar.error = Some(error);
+            }
+        }
+        self.sync_diagnostics(cx);
+        cx.notify();
+    }
+
+    fn toggle_find_option(&mut self, regex: bool, cx: &mut Context<Self>) {
+        let Some(find_bar) = self.find_bar.as_mut() else {
+            return;
+        };
+        if regex {
+            find_bar.options.regex = !find_bar.options.regex;
+        } else {
+            find_bar.options.case_sensitive = !find_bar.options.case_sensitive;
+        }
+        crate::log::trace_debug(format!(
+            "req-find1 options case_sensitive={} regex={}",
+            find_bar.options.case_sensitive, find_bar.options.regex
+        ));
+        let value = self.input_state.read(cx).value().to_string();
+        self.refresh_find_matches(&value, cx);
+    }
+
+    fn navigate_find_match(&mut self, forward: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.input_state.read(cx).value().to_string();
+        let Some(find_bar) = self.find_bar.as_mut() else {
+            return;
+        };
+        let count = find_bar.matches.len();
+        if count == 0 {
+            return;
+        }
+        let next = match find_bar.active_match {
+            Some(active) if forward => (active + 1) % count,
+            Some(active) => (active + count - 1) % count,
+            None => 0,
+        };
+        find_bar.active_match = Some(next);
+        let (line, character) =
+            crate::editor_find::byte_offset_to_line_char(&text, find_bar.matches[next].start);
+        self.apply_cursor(line, character, window, cx);
+        cx.notify();
+    }
+
+    fn replace_find_matches(&mut self, all: bool, window: &mut Window, cx: &mut Context<Self>) {
+        if self.layout_prefs.is_readonly() {
+            crate::log::trace_debug("req-find1 replace skipped reason=readonly-note");
+            return;
+        }
+        let snapshot = self.snapshot(cx);
+        let Some(find_bar) = self.find_bar.as_ref() else {
+            return;
+        };
+        let query = find_bar.query_input.read(cx).value().to_string();
+        let replacement = find_bar.replace_input.read(cx).value().to_string();
+        let result = if all {
+            crate::editor_find::replace_all_matches(
+                &snapshot.value,
+                &query,
+                &replacement,
+                find_bar.options,
+            )
+        } else {
+            match find_bar
+                .active_match
+                .and_then(|active| find_bar.matches.get(active).cloned())
+            {
+                Some(range) => crate::editor_find::replace_match(
+                    &snapshot.value,
+                    range,
+                    &query,
+                    &replacement,
+                    find_bar.options,
+                )
+                .map(|value| (value, 1)),
+                None => return,
+            }
+        };
+        let (value, replaced) = match result {
+            Ok(result) => result,
+            Err(error) => {
+                if let Some(find_bar) = self.find_bar.as_mut() {
+                    find_bar.error = Some(error);
+                }
+                cx.notify();
+                return;
+            }
+        };
+        if replaced == 0 || value == snapshot.value {
+            return;
+        }
+        crate::log::trace_debug(format!("req-find1 replace all={all} replaced={replaced}"));
+        self.apply_text_and_cursor(
+            value.clone(),
+            snapshot.cursor_line,
+            snapshot.cursor_char,
+            window,
+            cx,
+        );
+        self.schedule_spell_check(cx);
+        self.refresh_find_matches(&value, cx);
+        self.emit_user_buffer_changed(&snapshot.value, value, cx);
+    }
+
+    fn request_replace_all(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(count) = self
+            .find_bar
+            .as_ref()
+            .map(|find_bar| find_bar.matches.len())
+        else {
+            return;
+        };
+        if count == 0 {
+            return;
+        }
+        let policy = self.confirmations;
+        crate::confirmations::run_confirmed(
+            self,
+            policy,
+            crate::confirmations::ConfirmationKind::ReplaceAll,
+            &format!("Replace {count} match(es) in this note?"),
+            window,
+            cx,
+            |this, window, cx| this.replace_find_matches(true, window, cx),
+        );
+    }
+
+    pub fn set_confirmation_policy(&mut self, policy: crate::confirmations::ConfirmationPolicy) {
+        self.confirmations = policy;
+    }
+
+    pub fn set_transform_toggles(
+        &mut self,
+        toggles: crate::editor_transforms::EditorTransformToggles,
+    ) {
+        self.transforms = toggles;
+    }
+
+    pub fn set_language_map(&mut self, language_map: crate::syntax_language::LanguageMap) {
+        self.language_map = language_map;
+    }
+
+    /// Opened notes are shown without the `created`/`modified`/`tags` keys autosave keeps.
+    pub fn set_hide_note_metadata(&mut self, hide: bool) {
+        self.hide_note_metadata = hide;
+    }
+
+    /// After autosave stamps `saved`, gives the buffer the same front matter block so line
+    /// numbers in search results, bookmarks and goto match the file. Waits for a save while the
+    /// editor is focused, since moving the caret would take focus from elsewhere.
+    pub fn follow_stamped_note_metadata(
+        &mut self,
+        saved: &Path,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.hide_note_metadata
+            || !crate::note_metadata::stamps_note(saved)
+            || self.current_editing_file_path.as_deref() != Some(saved)
+            || !self.is_focused(window, cx)
+        {
+            return;
+        }
+        let text = self.input_state.read(cx).value().to_string();
+        let stamped = crate::note_metadata::stamp_note_metadata(
+            &text,
+            crate::file_times::created_time(saved),
+            chrono::Local::now(),
+        );
+        let header_lines = |text: &str| {
+            text.lines().count()
+                - crate::note_front_matter::strip_front_matter(text)
+                    .lines()
+                    .count()
+        };
+        let delta = header_lines(&stamped) as i64 - header_lines(&text) as i64;
+        if delta == 0 {
+            return;
+        }
+        let cursor = self.input_state.read(cx).cursor_position();
+        let line = (cursor.line as i64 + delta).max(0) as u32;
+        self.apply_edit(&stamped, Some((line, cursor.character)), window, cx);
+    }
+
+    pub fn current_language(&self) -> &str {
+        &self.current_language
+    }
+
+    pub fn language_override(&self) -> Option<&str> {
+        self.language_override.as_deref()
+    }
+
+    /// `None` goes back to the language chosen when the note was opened.
+    pub fn set_language_override(&mut self, language: Option<String>, cx: &mut Context<Self>) {
+        let Some(path) = self.current_editing_file_path.clone() else {
+            return;
+        };
+        let resolved = language.clone().unwrap_or_else(|| {
+            resolve_editor_language(path.as_path(), &self.layout_prefs, &self.language_map)
+        });
+        crate::log::trace_debug(format!(
+            "req-lang highlighter override path={} language={resolved} override={:?}",
+            path.display(),
+            language
+        ));
+        self.language_override = language;
+        self.current_language = resolved.clone();
+        self.input_state.update(cx, |state, cx| {
+            state.set_highlighter(resolved, cx);
+            cx.notify();
+        });
+        cx.notify();
+    }
+
+    fn on_find_bar_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event.keystroke.key.as_str().to_ascii_lowercase().as_str() {
+            "enter" | "return" => {
+                self.navigate_find_match(!event.keystroke.modifiers.shift, window, cx);
+                cx.stop_propagation();
+            }
+            "escape" => {
+                self.close_find_bar(window, cx);
+                cx.stop_propagation();
+            }
+            _ => cx.propagate(),
+        }
+    }
+
+    fn render_find_bar(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let find_bar = self.find_bar.as_ref()?;
+        let status = match (&find_bar.error, find_bar.active_match) {
+            (Some(_), _) => "invalid".to_string(),
+            (None, Some(active)) => format!("{}/{}", active + 1, find_bar.matches.len()),
+            (None, None) => format!("0/{}", find_bar.matches.len()),
+        };
+        let option_button = |id: &'static str, label: &'static str, active: bool| {
+            let button = Button::new(id).small().label(label);
+            if active {
+                button.primary()
+            } else {
+                button.ghost()
+            }
+        };
+        Some(
+            h_flex()
+                .absolute()
+                .bottom_0()
+                .right_0()
+                .gap_1()
+                .p_1()
+                .bg(cx.theme().popover)
+                .border_1()
+                .border_color(cx.theme().border)
+                .text_xs()
+                .on_key_down(cx.listener(Self::on_find_bar_key_down))
+                .child(div().w(px(160.)).child(Input::new(&find_bar.query_input)))
+                .child(status)
+                .child(
+                    option_button("find-case", "Aa", find_bar.options.case_sensitive)
+                        .on_click(cx.listener(|this, _, _, cx| this.toggle_find_option(false, cx))),
+                )
+                .child(
+                    option_button("find-regex", ".*", find_bar.options.regex)
+                        .on_click(cx.listener(|this, _, _, cx| this.toggle_find_option(true, cx))),
+                )
+                .child(
+                    Button::new("find-previous")
+                        .ghost()
+                        .small()
+                        .label("↑")
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.navigate_find_match(false, window, cx);
+                        })),
+                )
+                .child(
+                    Button::new("find-next")
+                        .ghost()
+                        .small()
+                        .label("↓")
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.navigate_find_match(true, window, cx);
+                        })),
+                )
+                .child(div().w(px(120.)).child(Input::new(&find_bar.replace_input)))
+                .child(
+                    Button::new("find-replace")
+                        .ghost()
+                        .small()
+                        .label(crate::i18n::tr("button.replace"))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.replace_find_matches(false, window, cx);
+                        })),
+                )
+                .child(
+                    Button::new("find-replace-all")
+                        .ghost()
+                        .small()
+                        .label(crate::i18n::tr("button.all"))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.request_replace_all(window, cx);
+                        })),
+                )
+                .child(
+                    Button::new("find-close")
+                        .ghost()
+                        .small()
+                        .label("×")
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.close_find_bar(window, cx);
+                        })),
+                ),
+        )
+    }
+
+    fn render_spell_suggestion_menu(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let menu = self.spell_suggestion_menu.as_ref()?;
+        let mut row = h_flex()
+            .absolute()
+            .top_0()
+            .right_0()
+            .gap_1()
+            .p_1()
+            .bg(cx.theme().popover)
+            .border_1()
+            .border_color(cx.theme().border)
+            .text_xs()
+            .child(format!("'{}':", menu.misspelling.word));
+        if menu.suggestions.is_empty() {
+            row = row.child("no suggestions");
+        }
+        for (index, suggestion) in menu.suggestions.iter().enumerate() {
+            row = row.child(
+                Button::new(("spell-suggestion", index))
+                    .ghost()
+                    .small()
+                    .label(suggestion.clone())
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.apply_spell_suggestion(index, window, cx);
+                    })),
+            );
+        }
+        Some(
+            row.child(
+                Button::new("spell-suggestion-dismiss")
+                    .ghost()
+                    .small()
+                    .label("×")
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.spell_suggestion_menu = None;
+                        cx.notify();
+                    })),
+            ),
+        )
+    }
+
+    fn revert_readonly_change(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.last_value.clone();
+        let cursor = gpui_component::input::Position {
+            line: self.last_cursor.line,
+            character: self.last_cursor.character,
+        };
+        self.pending_programmatic_change_events += 1;
+        crate::log::trace_debug(format!(
+            "req-fm1 readonly note edit reverted path={} pending={}",
+            self.current_editing_file_path
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "<none>".to_string()),
+            self.pending_programmatic_change_events
+        ));
+        self.input_state.update(cx, move |state, cx| {
+            state.set_value(text, window, cx);
+            state.set_cursor_position(cursor, window, cx);
+        });
+    }
+
+    fn load_note_layout_prefs(
+        &mut self,
+        path: &Path,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> String {
+        self.layout_prefs = crate::note_front_matter::parse_note_layout_prefs(content);
+        let language = resolve_editor_language(path, &self.layout_prefs, &self.language_map);
+        self.language_override = None;
+        self.current_language = language.clone();
+        crate::log::trace_debug(format!(
+            "req-fm1 layout prefs path={} language={} readonly={} wrap_column={:?} typewriter={}",
+            path.display(),
+            language,
+            self.layout_prefs.is_readonly(),
+            self.layout_prefs.wrap_column,
+            self.layout_prefs.is_typewriter()
+        ));
+        let soft_wrap = self.soft_wrap_active();
+        self.input_state.update(cx, |state, cx| {
+            state.set_soft_wrap(soft_wrap, window, cx);
+        });
+        language
+    }
+
+    // A note's `wrap_column` wraps it at that column even with soft wrap off in the settings.
+    fn soft_wrap_active(&self) -> bool {
+        self.display.soft_wrap || self.layout_prefs.wrap_column.is_some()
+    }
 
-                    this.last_value = value;
-                    this.last_cursor = cursor;
-                }
-                InputEvent::PressEnter { secondary } => {
-                    crate::log::trace_debug(format!(
-                        "editor InputEvent::PressEnter secondary={secondary}"
-                    ));
-                }
-                InputEvent::Focus => {
-                    crate::log::trace_debug("editor InputEvent::Focus");
-                    cx.emit(EditorEvent::FocusGained);
-                }
-                InputEvent::Blur => {
-                    crate::log::trace_debug("editor InputEvent::Blur");
-                }
-            }
-        })];
+    /// How wide the input may grow so soft wrap breaks lines at the note's `wrap_column`.
+    fn wrap_column_width(&self, window: &Window, cx: &App) -> Option<Pixels> {
+        let columns = self.layout_prefs.wrap_column?;
+        let font_size = px(self.font_size_px(cx));
+        let family = self
+            .font_family
+            .clone()
+            .unwrap_or_else(|| cx.theme().mono_font_family.clone());
+        let text_system = window.text_system();
+        let ch_advance = text_system
+            .ch_advance(text_system.resolve_font(&font(family)), font_size)
+            .map(f32::from)
+            .unwrap_or(f32::from(font_size) * EDITOR_MONO_ADVANCE_EM_ESTIMATE);
+        let gutter = if self.display.line_number {
+            let total_lines =
+                crate::quic_rpc_protocol::content_line_count(&self.input_state.read(cx).value())
+                    .min(u32::MAX as usize) as u32;
+            editor_gutter_width_px(total_lines, f32::from(font_size))
+        } else {
+            0.0
+        };
+        Some(px(wrap_column_width_px(columns, ch_advance, gutter)))
+    }
 
+    // Ctrl+Alt+R / Ctrl+Alt+T flip the note's readonly / typewriter front-matter keys and
+    // report the rewritten buffer as a user change so autosave persists it.
+    fn toggle_note_layout_flag(&mut self, key: &str, window: &mut Window, cx: &mut Context<Self>) {
+        if self.current_editing_file_path.is_none() {
+            crate::log::trace_debug(format!(
+                "req-fm1 toggle key={key} skipped reason=no-current-file"
+            ));
+            return;
+        }
+        if self.layout_prefs.is_publish_locked() {
+            crate::log::trace_debug(format!(
+                "req-pub1 toggle key={key} skipped reason=publish-locked"
+            ));
+            return;
+        }
+        let enabled = match key {
+            crate::note_front_matter::FRONT_MATTER_READONLY_KEY => {
+                self.layout_prefs.readonly.unwrap_or(false)
+            }
+            _ => self.layout_prefs.is_typewriter(),
+        };
+        let snapshot = self.snapshot(cx);
+        let value = crate::note_front_matter::upsert_front_matter_value(
+            &snapshot.value,
+            key,
+            if enabled { "false" } else { "true" },
+        );
+        let cursor_line = shift_cursor_line(snapshot.cursor_line, &snapshot.value, &value);
+        self.apply_text_and_cursor(value.clone(), cursor_line, snapshot.cursor_char, window, cx);
+        self.layout_prefs = crate::note_front_matter::parse_note_layout_prefs(&value);
         crate::log::trace_debug(format!(
-            "req-editor8 editor font_size_policy={}",
-            req_editor_editor_font_size_policy()
+            "req-fm1 toggle key={key} enabled={} (write back via autosave)",
+            !enabled
         ));
+        self.emit_user_buffer_changed(&snapshot.value, value, cx);
+    }
+
+    pub fn publish_lock(&self) -> Option<&str> {
+        self.layout_prefs.publish_lock.as_deref()
+    }
+
+    /// Adds (`Some(published_at)`) or removes the publish lock in the note's front-matter;
+    /// the one edit a locked note accepts.
+    pub fn set_publish_lock(
+        &mut self,
+        published_at: Option<&str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.current_editing_file_path.is_none() {
+            return;
+        }
+        let snapshot = self.snapshot(cx);
+        let key = crate::note_front_matter::FRONT_MATTER_PUBLISH_LOCK_KEY;
+        let value = match published_at {
+            Some(published_at) => crate::note_front_matter::upsert_front_matter_value(
+                &snapshot.value,
+                key,
+                published_at,
+            ),
+            None => crate::note_front_matter::remove_front_matter_value(&snapshot.value, key),
+        };
+        if value == snapshot.value {
+            return;
+        }
+        let cursor_line = shift_cursor_line(snapshot.cursor_line, &snapshot.value, &value);
+        self.apply_text_and_cursor(value.clone(), cursor_line, snapshot.cursor_char, window, cx);
+        self.layout_prefs = crate::note_front_matter::parse_note_layout_prefs(&value);
         crate::log::trace_debug(format!(
-            "req-editor startup editor_config code_editor={} soft_wrap={} line_number={} show_whitespaces={} searchable=true",
-            editor_config.code_editor,
-            editor_config.soft_wrap,
-            editor_config.line_number,
-            editor_config.show_whitespaces
+            "req-pub1 publish lock set={:?} (write back via autosave)",
+            published_at
         ));
-        if editor_config.show_whitespaces {
-            crate::log::trace_debug(
-                "req-editor10 show_whitespaces=true requested but current gpui-component API has no show_whitespaces toggle; preserving config for future API support",
-            );
-        }
+        self.emit_user_buffer_changed(&snapshot.value, value, cx);
+        cx.notify();
+    }
 
-        Self {
-            input_state,
-            last_value,
-            last_cursor,
-            pending_programmatic_change_events: 0,
-            current_editing_file_path: None,
-            _subscriptions,
-            font_size_logged_once: false,
-            ui_color_config,
+    /// Replaces the selection with `text` as if typed, so it is one undo step and the buffer
+    /// change goes through the usual Change handling; readonly notes are left untouched.
+    pub fn insert_text_at_cursor(
+        &mut self,
+        text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.layout_prefs.is_readonly() {
+            return;
         }
+        self.input_state.update(cx, |state, cx| {
+            state.replace_text_in_range(None, text, window, cx);
+        });
     }
 
-    fn on_key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+    fn on_key_down(&mut self, event: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
         if !event.is_held {
             cx.emit(EditorEvent::UserInteraction);
         }
+        self.refresh_top_visible_line_next_frame(window, cx);
+        if self.spell_suggestion_menu.take().is_some() {
+            cx.notify();
+        }
+        // Keys typed into the find bar are the find bar's own.
+        if !self.editor_input_focused(window, cx) {
+            cx.propagate();
+            return;
+        }
         let key_raw = event.keystroke.key.as_str();
         let key = key_raw.to_ascii_lowercase();
-        crate::log::trace_debug(format!(
-            "editor keydown raw='{}' key='{}' held={} key_char={}",
-            key_raw,
-            key,
-            event.is_held,
-            event.keystroke.key_char.as_deref().unwrap_or("<none>")
+        crate::log::trace_input(format!(
+            "editor keydown raw='{}' key='{}' held={}",
+            key_raw, key, event.is_held
         ));
 
+        let modifiers = &event.keystroke.modifiers;
+        if modifiers.control && !modifiers.alt && !modifiers.shift && key == "f" {
+            self.open_find_bar(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if modifiers.control && !modifiers.alt && !modifiers.shift && key == "v" {
+            if let Some(image) = clipboard_image_without_text(cx) {
+                cx.emit(EditorEvent::ImagePasted(image));
+                cx.stop_propagation();
+                return;
+            }
+        }
+        if modifiers.control && modifiers.alt && !event.is_held {
+            let toggle_key = match key.as_str() {
+                "r" => Some(crate::note_front_matter::FRONT_MATTER_READONLY_KEY),
+                "t" => Some(crate::note_front_matter::FRONT_MATTER_TYPEWRITER_KEY),
+                _ => None,
+            };
+            if let Some(toggle_key) = toggle_key {
+                self.toggle_note_layout_flag(toggle_key, window, cx);
+                cx.stop_propagation();
+                return;
+            }
+        }
+
+        if !modifiers.control
+            && !modifiers.alt
+            && !modifiers.platform
+            && let Some((edit, new_closer)) = self.transform_for_key(event, window, cx)
+        {
+            crate::log::trace_input(format!(
+                "req-xform key='{key}' cursor=({}, {})",
+                edit.cursor_line, edit.cursor_char
+            ));
+            let previous = self.last_value.clone();
+            self.apply_edit(
+                &edit.value,
+                Some((edit.cursor_line, edit.cursor_char)),
+                window,
+                cx,
+            );
+            if let Some(closer) = new_closer {
+                self.auto_closers.insert(closer);
+            }
+            if edit.value != previous {
+                self.emit_user_buffer_changed(&previous, edit.value, cx);
+            }
+            cx.stop_propagation();
+            return;
+        }
+
         if key == "backspace" || key == "delete" {
             let snapshot = self.snapshot(cx);
-            crate::log::trace_debug(format!(
-                "editor backspace candidate cursor=({}, {}) value='{}'",
+            crate::log::trace_input(format!(
+                "editor backspace candidate cursor=({}, {}) len={}",
                 snapshot.cursor_line,
                 snapshot.cursor_char,
-                crate::app::compact_text(&snapshot.value)
+                snapshot.value.len()
             ));
         }
 
         cx.propagate();
     }
 
+    // Sits between the key and the input: a matching keystroke is turned into a ranged,
+    // undoable edit here and never reaches `InputState`. Selections are left to the input.
+    // A new pair comes back with the offset of its closer in the edited buffer.
+    fn transform_for_key(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<(crate::editor_transforms::TextEdit, Option<usize>)> {
+        if self.layout_prefs.is_readonly() || self.current_editing_file_path.is_none() {
+            return None;
+        }
+        let has_selection = self.input_state.update(cx, |state, cx| {
+            state
+                .selected_text_range(false, window, cx)
+                .is_some_and(|selection| !selection.range.is_empty())
+        });
+        if has_selection {
+            return None;
+        }
+        let snapshot = self.snapshot(cx);
+        let (line, character) = (snapshot.cursor_line, snapshot.cursor_char);
+        let closer_at_cursor = self
+            .auto_closers
+            .is_closer_at(&snapshot.value, line, character);
+        match event.keystroke.key.as_str() {
+            "enter" if self.transforms.list_continuation && !event.keystroke.modifiers.shift => {
+                crate::editor_transforms::continue_list(&snapshot.value, line, character)
+                    .map(|edit| (edit, None))
+            }
+            "backspace" if self.transforms.auto_pair => {
+                crate::editor_transforms::delete_empty_pair(
+                    &snapshot.value,
+                    line,
+                    character,
+                    closer_at_cursor,
+                )
+                .map(|edit| (edit, None))
+            }
+            _ if self.transforms.auto_pair => {
+                let typed = event.keystroke.key_char.as_deref()?;
+                let mut chars = typed.chars();
+                let (Some(typed), None) = (chars.next(), chars.next()) else {
+                    return None;
+                };
+                let edit = crate::editor_transforms::auto_pair(
+                    &snapshot.value,
+                    line,
+                    character,
+                    typed,
+                    closer_at_cursor,
+                )?;
+                let offset =
+                    crate::editor_transforms::byte_offset(&snapshot.value, line, character)?;
+                if edit.value == snapshot.value {
+                    // Stepped over the closer; typing it again inserts a real one.
+                    self.auto_closers.remove(offset);
+                    Some((edit, None))
+                } else {
+                    Some((edit, Some(offset + typed.len_utf8())))
+                }
+            }
+            _ => None,
+        }
+    }
+
     fn on_move_up_action(
         &mut self,
         _: &gpui_component::input::MoveUp,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if !self.editor_input_focused(window, cx) {
+            cx.propagate();
+            return;
+        }
         let snapshot = self.snapshot(cx);
-        crate::log::trace_debug(format!(
-            "editor action MoveUp captured cursor=({}, {}) value='{}'",
-            snapshot.cursor_line,
-            snapshot.cursor_char,
-            crate::app::compact_text(&snapshot.value)
+        crate::log::trace_input(format!(
+            "editor action MoveUp captured cursor=({}, {})",
+            snapshot.cursor_line, snapshot.cursor_char
         ));
 
         if snapshot.cursor_line == 0 {
@@ -283,6 +1806,13 @@ impl Papyru2Editor {
         }
     }
 
+    fn editor_input_focused(&self, window: &Window, cx: &App) -> bool {
+        self.input_state
+            .read(cx)
+            .focus_handle(cx)
+            .is_focused(window)
+    }
+
     pub fn snapshot(&self, cx: &App) -> EditorSnapshot {
         let state = self.input_state.read(cx);
         let cursor = state.cursor_position();
@@ -304,6 +1834,7 @@ impl Papyru2Editor {
     ) {
         let text: SharedString = text.into();
         let text_owned = text.to_string();
+        self.auto_closers.clear();
 
         self.pending_programmatic_change_events += 1;
         crate::log::trace_debug(format!(
@@ -330,6 +1861,43 @@ impl Papyru2Editor {
         };
     }
 
+    /// Turns the buffer into `value` by replacing only the span that changed, so the edit can be
+    /// undone and the view keeps its scroll position. `cursor` then moves the caret (which also
+    /// focuses the editor); `None` leaves it after the replaced span.
+    pub fn apply_edit(
+        &mut self,
+        value: &str,
+        cursor: Option<(u32, u32)>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let previous = self.input_state.read(cx).value().to_string();
+        if previous == value {
+            if let Some((line, character)) = cursor {
+                self.apply_cursor(line, character, window, cx);
+            }
+            return;
+        }
+        self.auto_closers.follow_edit(&previous, value);
+        let (range, replacement) = changed_span(&previous, value);
+        let range_utf16 = utf16_len(&previous[..range.start])..utf16_len(&previous[..range.end]);
+
+        self.pending_programmatic_change_events += 1;
+        self.input_state.update(cx, |state, cx| {
+            state.replace_text_in_range(Some(range_utf16), replacement, window, cx);
+            if let Some((line, character)) = cursor {
+                state.set_cursor_position(
+                    gpui_component::input::Position { line, character },
+                    window,
+                    cx,
+                );
+            }
+        });
+
+        self.last_value = value.to_string();
+        self.last_cursor = self.input_state.read(cx).cursor_position();
+    }
+
     pub fn apply_cursor(
         &mut self,
         cursor_line: u32,
@@ -363,11 +1931,7 @@ impl Papyru2Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let language = path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("txt")
-            .to_string();
+        let language = self.load_note_layout_prefs(path.as_path(), &content, window, cx);
         let total_lines = crate::quic_rpc_protocol::content_line_count(&content);
         let anchor_line = rpc_centering_anchor_line(cursor_line, total_lines);
 
@@ -435,6 +1999,10 @@ impl Papyru2Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> bool {
+        // A background load still in flight would land on top of this note otherwise.
+        self.load_generation += 1;
+        self.loading_path = None;
+        self.load_error = None;
         let content = match read_editor_text_from_disk(path.as_path()) {
             Ok(content) => content,
             Err(error) => {
@@ -442,6 +2010,7 @@ impl Papyru2Editor {
                     "editor open_file read_failed path={} error={error}",
                     path.display()
                 ));
+                self.load_error = Some(error.to_string());
                 return false;
             }
         };
@@ -450,13 +2019,116 @@ impl Papyru2Editor {
             path.display(),
             content.len()
         ));
+        self.show_opened_content(path, content, window, cx);
+        true
+    }
+
+    /// Like [`Self::open_file`] without blocking the UI thread: the editor is emptied and
+    /// locked behind a loading indicator, and the text is set once it has been read.
+    pub fn open_file_in_background(
+        &mut self,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.load_generation += 1;
+        let generation = self.load_generation;
+        self.loading_path = Some(path.clone());
+        self.load_error = None;
+        crate::log::trace_debug(format!(
+            "editor open_file background start path={} generation={generation}",
+            path.display()
+        ));
+        self.pending_programmatic_change_events += 1;
+        self.input_state
+            .update(cx, |state, cx| state.set_value("", window, cx));
+        self.last_value = String::new();
+        self.last_cursor = gpui_component::input::Position {
+            line: 0,
+            character: 0,
+        };
+        let read = cx.background_executor().spawn({
+            let path = path.clone();
+            async move { read_editor_text_from_disk(path.as_path()) }
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let content = read.await;
+            let _ = this.update_in(cx, |editor, window, cx| {
+                editor.finish_background_load(generation, path, content, window, cx)
+            });
+        })
+        .detach();
+        cx.notify();
+    }
 
-        let language = path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("txt")
-            .to_string();
+    fn finish_background_load(
+        &mut self,
+        generation: u64,
+        path: PathBuf,
+        content: std::io::Result<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if generation != self.load_generation {
+            crate::log::trace_debug(format!(
+                "editor open_file background stale path={} generation={generation}",
+                path.display()
+            ));
+            return;
+        }
+        let error = match content {
+            Ok(content) => {
+                crate::log::trace_debug(format!(
+                    "editor open_file background loaded path={} bytes={}",
+                    path.display(),
+                    content.len()
+                ));
+                self.loading_path = None;
+                self.show_opened_content(path.clone(), content, window, cx);
+                None
+            }
+            // The app leaves the note on this event, so the empty buffer is never saved to it.
+            Err(error) => {
+                crate::log::trace_debug(format!(
+                    "editor open_file background failed path={} error={error}",
+                    path.display()
+                ));
+                self.loading_path = None;
+                self.load_error = Some(error.to_string());
+                Some(error.to_string())
+            }
+        };
+        cx.emit(EditorEvent::BackgroundLoadFinished { path, error });
+        cx.notify();
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loading_path.is_some()
+    }
+
+    /// Why the last open failed, e.g. a binary file or unreadable encoding.
+    pub fn load_error(&self) -> Option<&str> {
+        self.load_error.as_deref()
+    }
+
+    fn show_opened_content(
+        &mut self,
+        path: PathBuf,
+        content: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let language = self.load_note_layout_prefs(path.as_path(), &content, window, cx);
+        let content = if self.hide_note_metadata
+            && crate::file_update_handler::NoteFileExtension::from_path(&path)
+                == Some(crate::file_update_handler::NoteFileExtension::Md)
+        {
+            crate::note_metadata::strip_note_metadata(&content)
+        } else {
+            content
+        };
 
+        self.auto_closers.clear();
         self.pending_programmatic_change_events += 1;
         crate::log::trace_debug(format!(
             "editor mark programmatic change (open_file, pending={})",
@@ -481,7 +2153,6 @@ impl Papyru2Editor {
             line: 0,
             character: 0,
         };
-        true
     }
 
     pub fn set_current_editing_file_path(&mut self, path: Option<PathBuf>) {
@@ -494,8 +2165,8 @@ impl Papyru2Editor {
 }
 
 impl Render for Papyru2Editor {
-    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let experimental_text_size_px = px(f32::from(cx.theme().font_size) + 0.5);
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let experimental_text_size_px = px(self.font_size_px(cx));
         let background_rgb_hex = self.ui_color_config.background_rgb_hex;
         let foreground_rgb_hex = self.ui_color_config.foreground_rgb_hex;
 
@@ -513,22 +2184,225 @@ impl Render for Papyru2Editor {
             self.font_size_logged_once = true;
         }
 
+        let wrap_column_width = self.wrap_column_width(window, cx);
+        let spell_suggestion_menu = self.render_spell_suggestion_menu(cx);
+        let find_bar = self.render_find_bar(cx);
+        let loading_indicator = self.loading_path.as_ref().map(|path| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let text = match self.load_error.as_ref() {
+                Some(error) => format!("Could not load {name}: {error}"),
+                None => format!("Loading {name}\u{2026}"),
+            };
+            div()
+                .absolute()
+                .top_2()
+                .right_4()
+                .px_2()
+                .rounded_sm()
+                .text_xs()
+                .bg(cx.theme().secondary)
+                .text_color(cx.theme().muted_foreground)
+                .child(text)
+        });
+        // Focus mode lays the editor background over everything but the current paragraph;
+        // syntax colours come from the highlighter, so dimming the text colour would miss them.
+        let focus_scrims = self
+            .focus_mode
+            .then(|| self.focus_paragraph_band(window, cx))
+            .flatten()
+            .map(|(top, bottom)| {
+                let scrim = crate::app::req_colr_rgb_hex_to_hsla(background_rgb_hex)
+                    .opacity(1.0 - crate::focus_mode::FOCUS_MODE_DIM_OPACITY);
+                [
+                    div().absolute().left_0().right_0().top_0().h(top).bg(scrim),
+                    div()
+                        .absolute()
+                        .left_0()
+                        .right_0()
+                        .bottom_0()
+                        .top(bottom)
+                        .bg(scrim),
+                ]
+            })
+            .into_iter()
+            .flatten();
+        let origin_entity = cx.entity();
+
         div()
+            .relative()
             .size_full()
-            .bg(crate::app::req_colr_rgb_hex_to_hsla(background_rgb_hex))
+            .bg(crate::window_overlay::panel_background(
+                background_rgb_hex,
+                cx,
+            ))
             .text_color(crate::app::req_colr_rgb_hex_to_hsla(foreground_rgb_hex))
             .capture_key_down(cx.listener(Self::on_key_down))
             .capture_action(cx.listener(Self::on_move_up_action))
+            .on_scroll_wheel(cx.listener(Self::on_scroll_wheel))
+            .on_mouse_down(MouseButton::Right, cx.listener(Self::on_right_mouse_down))
+            .on_mouse_up(MouseButton::Left, cx.listener(Self::on_left_mouse_up))
+            .on_children_prepainted(move |children_bounds, _window, cx| {
+                let input_bounds = children_bounds.first().copied();
+                origin_entity.update(cx, |this, _cx| {
+                    this.input_bounds = input_bounds;
+                });
+            })
             .child(
                 crate::app::apply_req_editor_shared_text_size(
                     Input::new(&self.input_state)
                         .appearance(false)
                         .size_full()
-                        .font_family(cx.theme().mono_font_family.clone())
+                        .when_some(wrap_column_width, |input, width| input.max_w(width))
+                        .font_family(
+                            self.font_family
+                                .clone()
+                                .unwrap_or_else(|| cx.theme().mono_font_family.clone()),
+                        )
                         .text_color(crate::app::req_colr_rgb_hex_to_hsla(foreground_rgb_hex)),
                 )
                 .text_size(experimental_text_size_px),
             )
+            .children(focus_scrims)
+            .children(spell_suggestion_menu)
+            .children(find_bar)
+            .children(loading_indicator)
+    }
+}
+
+impl crate::app::Papyru2App {
+    /// Ctrl+= / Ctrl+- step the editor zoom by 10%, Ctrl+0 (`steps == None`) resets it. The
+    /// level is saved with the window position.
+    pub(crate) fn zoom_editor(&mut self, steps: Option<i32>, cx: &mut Context<Self>) {
+        let zoom = self.editor.update(cx, |editor, cx| {
+            let zoom = steps
+                .map(|steps| step_editor_zoom(editor.zoom(), steps))
+                .unwrap_or(EDITOR_ZOOM_DEFAULT);
+            editor.set_zoom(zoom, cx);
+            editor.zoom()
+        });
+        let percent = format!("{:.0}", zoom * 100.0);
+        self.show_transient_notice(
+            crate::i18n::tr_with("notice.editor_zoom", &[("percent", percent.as_str())]),
+            cx,
+        );
+    }
+
+    /// Prompt-based switcher for the editor display settings; each choice is applied, saved
+    /// to `[editor]` in the conf file, and the menu comes back for the next switch.
+    pub(crate) fn show_editor_display_menu(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let options = self.editor.read(cx).display_options();
+        let on_off = |key: &str, enabled: bool| {
+            let state = crate::i18n::tr(if enabled { "label.on" } else { "label.off" });
+            crate::i18n::tr_with(key, &[("state", state.as_ref())])
+        };
+        let tab_width = options.tab_width.to_string();
+        let next_tab_width = options.next_tab_width().to_string();
+        let labels = [
+            on_off("label.soft_wrap", options.soft_wrap),
+            on_off("label.line_numbers", options.line_number),
+            crate::i18n::tr_with(
+                "label.tab_width",
+                &[
+                    ("width", tab_width.as_str()),
+                    ("next", next_tab_width.as_str()),
+                ],
+            ),
+            on_off("label.typewriter_scrolling", options.typewriter_scrolling),
+            on_off("label.spell_check", options.spell_check),
+            crate::i18n::tr("button.done").to_string(),
+        ];
+        let label_refs = labels.iter().map(String::as_str).collect::<Vec<_>>();
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &crate::i18n::tr("prompt.editor_display"),
+            Some(&crate::i18n::tr("prompt.editor_display_detail")),
+            &label_refs,
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(choice) = answer.await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                let (next, key, value) = match choice {
+                    0 => (
+                        EditorDisplayOptions {
+                            soft_wrap: !options.soft_wrap,
+                            ..options
+                        },
+                        "soft_wrap",
+                        (!options.soft_wrap).to_string(),
+                    ),
+                    1 => (
+                        EditorDisplayOptions {
+                            line_number: !options.line_number,
+                            ..options
+                        },
+                        "line_number",
+                        (!options.line_number).to_string(),
+                    ),
+                    2 => (
+                        EditorDisplayOptions {
+                            tab_width: options.next_tab_width(),
+                            ..options
+                        },
+                        "tab_width",
+                        options.next_tab_width().to_string(),
+                    ),
+                    3 => (
+                        EditorDisplayOptions {
+                            typewriter_scrolling: !options.typewriter_scrolling,
+                            ..options
+                        },
+                        "typewriter_scrolling",
+                        (!options.typewriter_scrolling).to_string(),
+                    ),
+                    4 => (
+                        EditorDisplayOptions {
+                            spell_check: !options.spell_check,
+                            ..options
+                        },
+                        "spell_check",
+                        (!options.spell_check).to_string(),
+                    ),
+                    _ => return,
+                };
+                app.editor.update(cx, |editor, cx| {
+                    editor.set_display_options(next, window, cx)
+                });
+                let config_path = app
+                    .app_paths
+                    .config_file_path(crate::app::PAPYRU2_CONF_FILE_NAME);
+                if let Err(error) =
+                    crate::app::write_conf_value(config_path.as_path(), "editor", key, &value)
+                {
+                    let setting = format!("editor.{key}");
+                    app.show_transient_notice(
+                        crate::i18n::tr_with(
+                            "notice.setting_save_failed",
+                            &[
+                                ("setting", setting.as_str()),
+                                ("error", error.to_string().as_str()),
+                            ],
+                        ),
+                        cx,
+                    );
+                } else if key == "tab_width" {
+                    app.show_transient_notice(
+                        crate::i18n::tr_with(
+                            "notice.tab_width_after_restart",
+                            &[("width", value.as_str())],
+                        ),
+                        cx,
+                    );
+                }
+                app.show_editor_display_menu(window, cx);
+            });
+        })
+        .detach();
     }
 }
 
@@ -626,7 +2500,7 @@ mod tests {
         let flushed = workflow
             .flush_editor_content_in_edit(stale_text_from_previous_file, root.as_path())
             .expect("flush stale fileA content before selection switch");
-        assert!(flushed);
+        assert!(flushed.is_some());
         let path_a_after_flush = workflow
             .current_edit_path()
             .expect("current fileA path after pre-switch flush");
@@ -644,7 +2518,7 @@ mod tests {
                 editor_text: format!("{loaded_selected_text}\nB-new"),
             })
             .expect("autosave edited selected file");
-        assert!(saved);
+        assert!(saved.is_some());
         let path_b_after_save = workflow
             .current_edit_path()
             .expect("current fileB path after autosave");
@@ -675,6 +2549,107 @@ mod tests {
         remove_temp_root(root.as_path());
     }
 
+    #[test]
+    fn editor_test8_req_fm1_front_matter_language_overrides_extension() {
+        let prefs = crate::note_front_matter::parse_note_layout_prefs(
+            "---\nlanguage: markdown\n---\n# title",
+        );
+        assert_eq!(
+            super::resolve_editor_language(
+                Path::new("C:/tmp/note.txt"),
+                &prefs,
+                &Default::default()
+            ),
+            "markdown"
+        );
+        assert_eq!(
+            super::resolve_editor_language(
+                Path::new("C:/tmp/note.txt"),
+                &Default::default(),
+                &Default::default()
+            ),
+            crate::syntax_language::PLAIN_TEXT_LANGUAGE
+        );
+        assert_eq!(
+            super::shift_cursor_line(
+                2,
+                "line-a\nline-b\nline-c",
+                "---\nreadonly: true\n---\nline-a\nline-b\nline-c"
+            ),
+            5
+        );
+    }
+
+    #[test]
+    fn editor_test15_typewriter_scroll_puts_the_caret_line_mid_viewport() {
+        assert_eq!(super::typewriter_scroll_top(500.0, 20.0, 300.0), 360.0);
+        assert_eq!(super::typewriter_scroll_top(100.0, 20.0, 300.0), 0.0);
+        assert_eq!(super::typewriter_scroll_top(0.0, 20.0, 300.0), 0.0);
+    }
+
+    #[test]
+    fn editor_test16_zoom_steps_stay_on_the_grid_and_clamp() {
+        assert_eq!(super::step_editor_zoom(1.0, 1), 1.1);
+        assert_eq!(super::step_editor_zoom(1.0, -1), 0.9);
+        let mut zoom = 1.0;
+        for _ in 0..7 {
+            zoom = super::step_editor_zoom(zoom, 1);
+        }
+        for _ in 0..7 {
+            zoom = super::step_editor_zoom(zoom, -1);
+        }
+        assert_eq!(zoom, 1.0);
+        assert_eq!(super::step_editor_zoom(2.95, 1), 3.0);
+        assert_eq!(super::step_editor_zoom(0.5, -1), 0.5);
+        assert_eq!(super::normalize_editor_zoom(1.3), Some(1.3));
+        assert_eq!(super::normalize_editor_zoom(f32::NAN), None);
+        assert_eq!(super::normalize_editor_zoom(9.0), None);
+    }
+
+    #[test]
+    fn editor_test17_large_notes_load_in_the_background_and_huge_ones_ask_first() {
+        use super::{EditorLoadMode, editor_load_mode};
+        assert_eq!(editor_load_mode(0), EditorLoadMode::Inline);
+        assert_eq!(
+            editor_load_mode(super::EDITOR_BACKGROUND_LOAD_BYTES - 1),
+            EditorLoadMode::Inline
+        );
+        assert_eq!(
+            editor_load_mode(super::EDITOR_BACKGROUND_LOAD_BYTES),
+            EditorLoadMode::Background
+        );
+        assert_eq!(
+            editor_load_mode(super::EDITOR_LARGE_FILE_PROMPT_BYTES),
+            EditorLoadMode::ConfirmThenBackground
+        );
+    }
+
+    #[test]
+    fn editor_test14_tab_width_cycles_through_common_widths() {
+        let options = |tab_width| EditorDisplayOptions {
+            soft_wrap: true,
+            line_number: false,
+            tab_width,
+            typewriter_scrolling: false,
+            spell_check: true,
+        };
+        assert_eq!(options(2).next_tab_width(), 4);
+        assert_eq!(options(4).next_tab_width(), 8);
+        assert_eq!(options(8).next_tab_width(), 2);
+        assert_eq!(options(3).next_tab_width(), 4);
+        assert_eq!(options(16).next_tab_width(), 2);
+    }
+
+    #[test]
+    fn editor_test11_req_bmk1_gutter_hit_width_tracks_line_number_digits() {
+        assert_eq!(super::editor_gutter_width_px(1, 10.0), 24.0);
+        assert_eq!(super::editor_gutter_width_px(99, 10.0), 24.0);
+        assert_eq!(super::editor_gutter_width_px(1000, 10.0), 36.0);
+        assert!(super::is_editor_gutter_hit(5.0, 12, 10.0));
+        assert!(!super::is_editor_gutter_hit(24.0, 12, 10.0));
+        assert!(!super::is_editor_gutter_hit(-1.0, 12, 10.0));
+    }
+
     #[test]
     fn assoc_test21_req_assoc14_blank_origin_noop_change_emits_backspace_signal() {
         let previous_cursor = gpui_component::input::Position {
@@ -754,4 +2729,30 @@ mod tests {
             &origin_cursor,
         ));
     }
+
+    #[test]
+    fn editor_test18_changed_span_covers_only_the_edited_text() {
+        assert_eq!(super::changed_span("Old\nbody", "New\nbody"), (0..3, "New"));
+        assert_eq!(super::changed_span("a()", "a(x)"), (2..2, "x"));
+        assert_eq!(super::changed_span("- a\n", "- a\n- "), (4..4, "- "));
+        assert_eq!(super::changed_span("日本語", "日本"), (6..9, ""));
+        assert_eq!(super::changed_span("aa", "aaa"), (2..2, "a"));
+        assert_eq!(super::changed_span("same", "same"), (4..4, ""));
+    }
+
+    #[test]
+    fn editor_test19_clicked_utf16_offset_maps_back_to_bytes() {
+        let text = "a😀b\n日本";
+        assert_eq!(super::byte_offset_for_utf16(text, 0), 0);
+        assert_eq!(super::byte_offset_for_utf16(text, 1), 1);
+        assert_eq!(super::byte_offset_for_utf16(text, 3), 5);
+        assert_eq!(super::byte_offset_for_utf16(text, 5), 7);
+        assert_eq!(super::byte_offset_for_utf16(text, 99), text.len());
+    }
+
+    #[test]
+    fn editor_test20_wrap_column_width_fits_the_columns_after_the_gutter() {
+        assert_eq!(super::wrap_column_width_px(80, 8.0, 0.0), 648.0);
+        assert_eq!(super::wrap_column_width_px(10, 10.0, 40.0), 150.0);
+    }
 }
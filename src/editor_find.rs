@@ -0,0 +1,273 @@
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+pub(crate) const FIND_MAX_MATCHES: usize = 10_000;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct FindOptions {
+    pub case_sensitive: bool,
+    pub regex: bool,
+}
+
+// Literal queries are escaped so both modes share one matcher; an invalid pattern is
+// reported to the find bar instead of clearing the previous matches silently.
+fn build_find_regex(query: &str, options: FindOptions) -> Result<Regex, String> {
+    let pattern = if options.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .multi_line(true)
+        .build()
+        .map_err(|error| error.to_string())
+}
+
+pub(crate) fn find_matches(
+    text: &str,
+    query: &str,
+    options: FindOptions,
+) -> Result<Vec<Range<usize>>, String> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let regex = build_find_regex(query, options)?;
+    Ok(regex
+        .find_iter(text)
+        .filter(|found| !found.is_empty())
+        .take(FIND_MAX_MATCHES)
+        .map(|found| found.range())
+        .collect())
+}
+
+/// Converts a byte offset into the editor's `(line, character)` position.
+pub(crate) fn byte_offset_to_line_char(text: &str, offset: usize) -> (u32, u32) {
+    let offset = offset.min(text.len());
+    let before = &text[..offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+    let character = before[line_start..].chars().count();
+    (
+        line.min(u32::MAX as usize) as u32,
+        character.min(u32::MAX as usize) as u32,
+    )
+}
+
+/// `byte_offset_to_line_char` for many ascending offsets, such as match starts and ends,
+/// in one walk over `text`.
+pub(crate) fn byte_offsets_to_line_chars(
+    text: &str,
+    offsets: impl IntoIterator<Item = usize>,
+) -> Vec<(u32, u32)> {
+    let mut positions = Vec::new();
+    let (mut line, mut character) = (0usize, 0usize);
+    let mut chars = text.char_indices().peekable();
+    for offset in offsets {
+        while let Some((_, ch)) = chars.next_if(|(index, _)| *index < offset) {
+            if ch == '\n' {
+                line += 1;
+                character = 0;
+            } else {
+                character += 1;
+            }
+        }
+        positions.push((
+            line.min(u32::MAX as usize) as u32,
+            character.min(u32::MAX as usize) as u32,
+        ));
+    }
+    positions
+}
+
+pub(crate) fn line_char_to_byte_offset(text: &str, line: u32, character: u32) -> usize {
+    let mut offset = 0usize;
+    for (index, line_text) in text.split('\n').enumerate() {
+        if index as u32 == line {
+            return offset
+                + line_text
+                    .char_indices()
+                    .nth(character as usize)
+                    .map(|(byte, _)| byte)
+                    .unwrap_or(line_text.len());
+        }
+        offset += line_text.len() + 1;
+    }
+    text.len()
+}
+
+/// Index of the first match at/after (forward) or before (backward) `cursor_offset`,
+/// wrapping around the buffer.
+pub(crate) fn next_match_index(
+    matches: &[Range<usize>],
+    cursor_offset: usize,
+    forward: bool,
+) -> Option<usize> {
+    if matches.is_empty() {
+        return None;
+    }
+    if forward {
+        Some(
+            matches
+                .iter()
+                .position(|range| range.start >= cursor_offset)
+                .unwrap_or(0),
+        )
+    } else {
+        Some(
+            matches
+                .iter()
+                .rposition(|range| range.start < cursor_offset)
+                .unwrap_or(matches.len() - 1),
+        )
+    }
+}
+
+/// Replaces one match; regex mode expands `$1`-style groups from that match.
+pub(crate) fn replace_match(
+    text: &str,
+    range: Range<usize>,
+    query: &str,
+    replacement: &str,
+    options: FindOptions,
+) -> Result<String, String> {
+    if text.get(range.clone()).is_none() {
+        return Err("match is out of date".to_string());
+    }
+    let expanded = if options.regex {
+        let regex = build_find_regex(query, options)?;
+        let captures = regex
+            .captures_at(text, range.start)
+            .filter(|captures| captures.get(0).map(|found| found.range()) == Some(range.clone()))
+            .ok_or("match is out of date")?;
+        let mut expanded = String::new();
+        captures.expand(replacement, &mut expanded);
+        expanded
+    } else {
+        replacement.to_string()
+    };
+    let mut rewritten = String::with_capacity(text.len() + expanded.len());
+    rewritten.push_str(&text[..range.start]);
+    rewritten.push_str(&expanded);
+    rewritten.push_str(&text[range.end..]);
+    Ok(rewritten)
+}
+
+// Mirrors `find_matches`: empty matches are skipped so the count equals what the bar showed.
+pub(crate) fn replace_all_matches(
+    text: &str,
+    query: &str,
+    replacement: &str,
+    options: FindOptions,
+) -> Result<(String, usize), String> {
+    if query.is_empty() {
+        return Ok((text.to_string(), 0));
+    }
+    let regex = build_find_regex(query, options)?;
+    let mut rewritten = String::with_capacity(text.len());
+    let mut copied_until = 0usize;
+    let mut count = 0usize;
+    for captures in regex.captures_iter(text) {
+        let Some(found) = captures.get(0) else {
+            continue;
+        };
+        if found.is_empty() || count >= FIND_MAX_MATCHES {
+            continue;
+        }
+        rewritten.push_str(&text[copied_until..found.start()]);
+        if options.regex {
+            captures.expand(replacement, &mut rewritten);
+        } else {
+            rewritten.push_str(replacement);
+        }
+        copied_until = found.end();
+        count += 1;
+    }
+    rewritten.push_str(&text[copied_until..]);
+    Ok((rewritten, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_test1_literal_search_honors_case_option_and_escapes_pattern() {
+        let text = "Todo: a.b\ntodo a_b";
+
+        assert_eq!(
+            find_matches(text, "todo", FindOptions::default()).expect("find"),
+            vec![0..4, 10..14]
+        );
+        let case_sensitive = FindOptions {
+            case_sensitive: true,
+            regex: false,
+        };
+        assert_eq!(
+            find_matches(text, "todo", case_sensitive).expect("find"),
+            vec![10..14]
+        );
+        assert_eq!(
+            find_matches(text, "a.b", FindOptions::default()).expect("find"),
+            vec![6..9]
+        );
+        assert!(
+            find_matches(
+                text,
+                "(",
+                FindOptions {
+                    case_sensitive: false,
+                    regex: true
+                }
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn find_test2_navigation_wraps_and_offsets_round_trip() {
+        let matches = vec![2..4, 10..12];
+
+        assert_eq!(next_match_index(&matches, 0, true), Some(0));
+        assert_eq!(next_match_index(&matches, 3, true), Some(1));
+        assert_eq!(next_match_index(&matches, 11, true), Some(0));
+        assert_eq!(next_match_index(&matches, 2, false), Some(1));
+        assert_eq!(next_match_index(&matches, 11, false), Some(1));
+        assert_eq!(next_match_index(&[], 0, true), None);
+
+        let text = "ab\nこんにちは x";
+        let offset = text.find('x').expect("x offset");
+        assert_eq!(byte_offset_to_line_char(text, offset), (1, 6));
+        assert_eq!(line_char_to_byte_offset(text, 1, 6), offset);
+
+        let offsets = [0, 2, 3, offset, offset + 1, text.len() + 5];
+        assert_eq!(
+            byte_offsets_to_line_chars(text, offsets),
+            offsets
+                .iter()
+                .map(|offset| byte_offset_to_line_char(text, *offset))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn find_test3_replace_one_and_all_expand_groups_only_in_regex_mode() {
+        let regex_mode = FindOptions {
+            case_sensitive: true,
+            regex: true,
+        };
+        let text = "id=1 id=22";
+
+        let one = replace_match(text, 5..10, r"id=(\d+)", "#$1", regex_mode).expect("replace one");
+        assert_eq!(one, "id=1 #22");
+
+        let (all, count) =
+            replace_all_matches(text, r"id=(\d+)", "#$1", regex_mode).expect("replace all");
+        assert_eq!((all.as_str(), count), ("#1 #22", 2));
+
+        let (literal, count) = replace_all_matches("$1 x $1", "x", "$1", FindOptions::default())
+            .expect("literal replace");
+        assert_eq!((literal.as_str(), count), ("$1 $1 $1", 1));
+    }
+}
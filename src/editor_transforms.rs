@@ -0,0 +1,366 @@
+use std::{fs, path::Path};
+
+use crate::log::trace_debug;
+
+const AUTO_PAIRS: [(char, char); 6] = [
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('"', '"'),
+    ('\'', '\''),
+    ('`', '`'),
+];
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct TransformConfigFile {
+    #[serde(default)]
+    editor: TransformConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct TransformConfigSection {
+    auto_pair: Option<bool>,
+    list_continuation: Option<bool>,
+}
+
+/// `[editor] auto_pair` / `list_continuation`; both default on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct EditorTransformToggles {
+    pub auto_pair: bool,
+    pub list_continuation: bool,
+}
+
+impl Default for EditorTransformToggles {
+    fn default() -> Self {
+        Self {
+            auto_pair: true,
+            list_continuation: true,
+        }
+    }
+}
+
+pub(crate) fn load_editor_transform_toggles(path: &Path) -> EditorTransformToggles {
+    let defaults = EditorTransformToggles::default();
+    let Ok(raw) = fs::read_to_string(path) else {
+        return defaults;
+    };
+    match toml::from_str::<TransformConfigFile>(&raw) {
+        Ok(parsed) => EditorTransformToggles {
+            auto_pair: parsed.editor.auto_pair.unwrap_or(defaults.auto_pair),
+            list_continuation: parsed
+                .editor
+                .list_continuation
+                .unwrap_or(defaults.list_continuation),
+        },
+        Err(error) => {
+            trace_debug(format!(
+                "req-xform config parse failed path={} error={error}",
+                path.display()
+            ));
+            defaults
+        }
+    }
+}
+
+/// Buffer and caret after a transform, in the editor's (line, char) positions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TextEdit {
+    pub value: String,
+    pub cursor_line: u32,
+    pub cursor_char: u32,
+}
+
+struct CursorLine<'a> {
+    lines: Vec<&'a str>,
+    line: usize,
+    before: String,
+    after: String,
+}
+
+fn cursor_line(text: &str, line: u32, character: u32) -> Option<CursorLine<'_>> {
+    let lines = text.split('\n').collect::<Vec<_>>();
+    let line = line as usize;
+    let current = lines.get(line)?;
+    let split = current
+        .char_indices()
+        .nth(character as usize)
+        .map(|(index, _)| index)
+        .unwrap_or(current.len());
+    if current[..split].chars().count() != character as usize {
+        return None;
+    }
+    Some(CursorLine {
+        before: current[..split].to_string(),
+        after: current[split..].to_string(),
+        lines,
+        line,
+    })
+}
+
+fn rebuild(cursor: &CursorLine<'_>, replacement: &str) -> String {
+    let mut lines = cursor
+        .lines
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    lines[cursor.line] = replacement.to_string();
+    lines.join("\n")
+}
+
+/// The byte offset of a (line, char) position in `text`.
+pub(crate) fn byte_offset(text: &str, line: u32, character: u32) -> Option<usize> {
+    let line_start = if line == 0 {
+        0
+    } else {
+        text.match_indices('\n').nth(line as usize - 1)?.0 + 1
+    };
+    let line_text = text[line_start..].split('\n').next().unwrap_or_default();
+    let mut chars = line_text.char_indices().map(|(index, _)| index);
+    let within = match chars.nth(character as usize) {
+        Some(index) => index,
+        None if line_text.chars().count() == character as usize => line_text.len(),
+        None => return None,
+    };
+    Some(line_start + within)
+}
+
+/// Closers that [`auto_pair`] inserted, by byte offset in the buffer. Only these are stepped
+/// over or deleted together with their opener; a closer the user typed is left alone.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct AutoClosers {
+    offsets: Vec<usize>,
+}
+
+impl AutoClosers {
+    pub fn clear(&mut self) {
+        self.offsets.clear();
+    }
+
+    pub fn is_closer_at(&self, text: &str, line: u32, character: u32) -> bool {
+        byte_offset(text, line, character).is_some_and(|offset| self.offsets.contains(&offset))
+    }
+
+    pub fn insert(&mut self, offset: usize) {
+        if !self.offsets.contains(&offset) {
+            self.offsets.push(offset);
+        }
+    }
+
+    pub fn remove(&mut self, offset: usize) {
+        self.offsets.retain(|closer| *closer != offset);
+    }
+
+    /// Follows an edit from `previous` to `value`: closers after it move along, closers
+    /// inside it are gone.
+    pub fn follow_edit(&mut self, previous: &str, value: &str) {
+        if previous == value || self.offsets.is_empty() {
+            return;
+        }
+        let (range, replacement) = crate::editor::changed_span(previous, value);
+        self.offsets.retain_mut(|offset| {
+            if *offset >= range.end {
+                *offset = *offset - range.end + range.start + replacement.len();
+                true
+            } else {
+                *offset < range.start
+            }
+        });
+    }
+}
+
+/// Typing an opener inserts its closer too; typing a closer right before the same closer
+/// steps over it when `step_over` says that closer was auto-inserted. Quotes are not paired
+/// after a letter or digit (don't, it's) and nothing is paired right before a word.
+pub(crate) fn auto_pair(
+    text: &str,
+    line: u32,
+    character: u32,
+    typed: char,
+    step_over: bool,
+) -> Option<TextEdit> {
+    let cursor = cursor_line(text, line, character)?;
+    let previous = cursor.before.chars().last();
+    let next = cursor.after.chars().next();
+    let is_closer = AUTO_PAIRS.iter().any(|(_, close)| *close == typed);
+    if is_closer && next == Some(typed) {
+        return step_over.then(|| TextEdit {
+            value: text.to_string(),
+            cursor_line: line,
+            cursor_char: character + 1,
+        });
+    }
+    let (open, close) = AUTO_PAIRS.iter().find(|(open, _)| *open == typed)?;
+    if open == close && previous.is_some_and(|previous| previous.is_alphanumeric()) {
+        return None;
+    }
+    if next.is_some_and(|next| {
+        !next.is_whitespace() && !AUTO_PAIRS.iter().any(|(_, close)| *close == next)
+    }) {
+        return None;
+    }
+    let replacement = format!("{}{open}{close}{}", cursor.before, cursor.after);
+    Some(TextEdit {
+        value: rebuild(&cursor, &replacement),
+        cursor_line: line,
+        cursor_char: character + 1,
+    })
+}
+
+/// Backspace between an opener and its auto-inserted closer removes both.
+pub(crate) fn delete_empty_pair(
+    text: &str,
+    line: u32,
+    character: u32,
+    closer_auto_inserted: bool,
+) -> Option<TextEdit> {
+    if !closer_auto_inserted {
+        return None;
+    }
+    let cursor = cursor_line(text, line, character)?;
+    let previous = cursor.before.chars().last()?;
+    let next = cursor.after.chars().next()?;
+    if !AUTO_PAIRS.contains(&(previous, next)) {
+        return None;
+    }
+    let before = &cursor.before[..cursor.before.len() - previous.len_utf8()];
+    let after = &cursor.after[next.len_utf8()..];
+    Some(TextEdit {
+        value: rebuild(&cursor, &format!("{before}{after}")),
+        cursor_line: line,
+        cursor_char: character - 1,
+    })
+}
+
+// ("  ", "- ") for "  - item"; numbered markers come back already incremented.
+fn next_list_marker(line: &str) -> Option<(&str, String, usize)> {
+    let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+    let (indent, rest) = line.split_at(indent_len);
+    for bullet in ["- [ ] ", "- [x] ", "- [X] ", "- ", "* ", "+ "] {
+        if rest.starts_with(bullet) {
+            let next = if bullet.contains('[') {
+                "- [ ] "
+            } else {
+                bullet
+            };
+            return Some((indent, next.to_string(), bullet.len()));
+        }
+    }
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 || digits > 9 {
+        return None;
+    }
+    let delimiter = rest[digits..].chars().next()?;
+    if !matches!(delimiter, '.' | ')') || !rest[digits + 1..].starts_with(' ') {
+        return None;
+    }
+    let number = rest[..digits].parse::<u64>().ok()?;
+    Some((indent, format!("{}{delimiter} ", number + 1), digits + 2))
+}
+
+/// Enter on a `- `, `* `, `+ `, `- [ ] ` or `1. ` item starts the next item with the same
+/// indent; Enter on an item with nothing after its marker ends the list instead.
+pub(crate) fn continue_list(text: &str, line: u32, character: u32) -> Option<TextEdit> {
+    let cursor = cursor_line(text, line, character)?;
+    let full_line = format!("{}{}", cursor.before, cursor.after);
+    let (indent, marker, marker_len) = next_list_marker(&full_line)?;
+    if cursor.before.len() < indent.len() + marker_len {
+        return None;
+    }
+    if full_line[indent.len() + marker_len..].trim().is_empty() {
+        return Some(TextEdit {
+            value: rebuild(&cursor, ""),
+            cursor_line: line,
+            cursor_char: 0,
+        });
+    }
+    let next_line = format!("{indent}{marker}");
+    let cursor_char = next_line.chars().count() as u32;
+    let replacement = format!("{}\n{next_line}{}", cursor.before, cursor.after);
+    Some(TextEdit {
+        value: rebuild(&cursor, &replacement),
+        cursor_line: line + 1,
+        cursor_char,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(value: &str, cursor_line: u32, cursor_char: u32) -> Option<TextEdit> {
+        Some(TextEdit {
+            value: value.to_string(),
+            cursor_line,
+            cursor_char,
+        })
+    }
+
+    #[test]
+    fn xform_test1_auto_pair_inserts_steps_over_and_deletes_pairs() {
+        assert_eq!(auto_pair("call", 0, 4, '(', false), edit("call()", 0, 5));
+        assert_eq!(auto_pair("call()", 0, 5, ')', true), edit("call()", 0, 6));
+        assert_eq!(auto_pair("call()", 0, 5, ')', false), None);
+        assert_eq!(
+            auto_pair("a\nsay ", 1, 4, '"', false),
+            edit("a\nsay \"\"", 1, 5)
+        );
+        assert_eq!(auto_pair("say \"\"", 0, 5, '"', false), None);
+        assert_eq!(auto_pair("don", 0, 3, '\'', false), None);
+        assert_eq!(auto_pair("word", 0, 0, '[', false), None);
+        assert_eq!(auto_pair("(x)", 0, 1, '[', false), None);
+        assert_eq!(auto_pair("f()", 0, 2, '[', false), edit("f([])", 0, 3));
+        assert_eq!(auto_pair("日本", 0, 2, '(', false), edit("日本()", 0, 3));
+        assert_eq!(auto_pair("abc", 0, 9, '(', false), None);
+
+        assert_eq!(delete_empty_pair("f()", 0, 2, true), edit("f", 0, 1));
+        assert_eq!(delete_empty_pair("f()", 0, 2, false), None);
+        assert_eq!(delete_empty_pair("f(x)", 0, 2, true), None);
+        assert_eq!(delete_empty_pair("", 0, 0, true), None);
+    }
+
+    #[test]
+    fn xform_test3_only_auto_inserted_closers_are_tracked_through_edits() {
+        assert_eq!(byte_offset("ab\n日本x", 1, 2), Some(9));
+        assert_eq!(byte_offset("ab\ncd", 1, 2), Some(5));
+        assert_eq!(byte_offset("ab\ncd", 1, 3), None);
+
+        let mut closers = AutoClosers::default();
+        // "f(|)": the closer sits at byte 2.
+        closers.insert(2);
+        assert!(closers.is_closer_at("f()", 0, 2));
+        // Typing inside the pair moves the closer along.
+        closers.follow_edit("f()", "f(ab)");
+        assert!(closers.is_closer_at("f(ab)", 0, 4));
+        // A line added above moves it too.
+        closers.follow_edit("f(ab)", "x\nf(ab)");
+        assert!(closers.is_closer_at("x\nf(ab)", 1, 4));
+        // Deleting the closer forgets it.
+        closers.follow_edit("x\nf(ab)", "x\nf(ab");
+        assert!(!closers.is_closer_at("x\nf(ab)", 1, 4));
+        assert_eq!(closers, AutoClosers::default());
+    }
+
+    #[test]
+    fn xform_test2_list_items_continue_and_empty_items_end_the_list() {
+        assert_eq!(continue_list("- one", 0, 5), edit("- one\n- ", 1, 2));
+        assert_eq!(
+            continue_list("x\n  * two", 1, 7),
+            edit("x\n  * two\n  * ", 2, 4)
+        );
+        assert_eq!(continue_list("9. nine", 0, 7), edit("9. nine\n10. ", 1, 4));
+        assert_eq!(continue_list("1) a", 0, 4), edit("1) a\n2) ", 1, 3));
+        assert_eq!(
+            continue_list("- [x] done", 0, 10),
+            edit("- [x] done\n- [ ] ", 1, 6)
+        );
+        assert_eq!(
+            continue_list("- split here", 0, 7),
+            edit("- split\n-  here", 1, 2)
+        );
+        assert_eq!(continue_list("a\n- \nb", 1, 2), edit("a\n\nb", 1, 0));
+        assert_eq!(continue_list("- item", 0, 1), None);
+        assert_eq!(continue_list("plain", 0, 5), None);
+        assert_eq!(continue_list("1.5 kg", 0, 6), None);
+        assert_eq!(continue_list("-dash", 0, 5), None);
+    }
+}
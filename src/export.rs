@@ -0,0 +1,1589 @@
+use std::{
+    collections::HashSet,
+    fs, io,
+    io::Write as _,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+};
+
+use base64::Engine as _;
+use chrono::{DateTime, Local};
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+};
+use serde::{Deserialize, Serialize};
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+use crate::log::trace_debug;
+use crate::workflow_error::{WorkflowError, WorkflowResult, at_path};
+
+/// Attachments above this size still export, but the user is asked first.
+pub(crate) const HTML_EXPORT_LARGE_ATTACHMENT_BYTES: u64 = 5 * 1024 * 1024;
+/// Same, for all inlined attachments of one note together.
+pub(crate) const HTML_EXPORT_LARGE_TOTAL_BYTES: u64 = 20 * 1024 * 1024;
+
+const VAULT_EXPORT_FILE_PREFIX: &str = "papyru2_export_";
+const VAULT_EXPORT_INDEX_NAME: &str = "index.html";
+pub(crate) const EXPORT_PROFILES_FILE_NAME: &str = "export_profiles.toml";
+
+const HTML_EXPORT_STYLE: &str = "\
+body{max-width:46em;margin:2em auto;padding:0 1em;font-family:system-ui,sans-serif;line-height:1.6;color:#222;background:#fff}\
+pre,code{font-family:ui-monospace,Consolas,monospace;font-size:0.92em}\
+pre{padding:0.8em;overflow-x:auto;background:#f4f4f4;border-radius:4px;white-space:pre-wrap}\
+img{max-width:100%}\
+table{border-collapse:collapse}th,td{border:1px solid #ccc;padding:0.3em 0.6em}\
+blockquote{margin-left:0;padding-left:1em;border-left:3px solid #ccc;color:#555}";
+
+#[derive(Debug)]
+pub(crate) struct HtmlExport {
+    pub html: String,
+    pub large_attachments: Vec<(PathBuf, u64)>,
+    pub missing_attachments: Vec<String>,
+    pub attachment_bytes: u64,
+}
+
+impl HtmlExport {
+    pub fn needs_size_warning(&self) -> bool {
+        !self.large_attachments.is_empty() || self.attachment_bytes > HTML_EXPORT_LARGE_TOTAL_BYTES
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn is_markdown_note(path: &Path) -> bool {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+        .is_some_and(|extension| extension == "md" || extension == "markdown")
+}
+
+/// `None` for anything that is not an image; only images are ever inlined.
+fn image_mime_type(path: &Path) -> Option<&'static str> {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        "svg" => Some("image/svg+xml"),
+        "bmp" => Some("image/bmp"),
+        _ => None,
+    }
+}
+
+// A shared HTML file must not carry files from outside the vault, so an image source is
+// followed (`..` and symlinks included) and kept only when it lands inside `vault_root`.
+// `/...` is rooted at the vault, the way pasted images are linked.
+fn resolve_export_image(vault_root: &Path, note_dir: &Path, src: &str) -> Option<PathBuf> {
+    let (base, src) = match src.strip_prefix('/') {
+        Some(rooted) => (vault_root, rooted),
+        None => (note_dir, src),
+    };
+    let relative = Path::new(src);
+    if relative.is_absolute() || relative.has_root() {
+        return None;
+    }
+    let resolved = fs::canonicalize(base.join(relative)).ok()?;
+    let vault_root = fs::canonicalize(vault_root).ok()?;
+    (resolved.starts_with(&vault_root) && image_mime_type(resolved.as_path()).is_some())
+        .then_some(resolved)
+}
+
+// Image sources come out of the markdown compiler html-escaped and percent-encoded.
+fn decode_image_src(src: &str) -> String {
+    let unescaped = src.replace("&quot;", "\"").replace("&amp;", "&");
+    let bytes = unescaped.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = (bytes[index] == b'%')
+            .then(|| unescaped.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn is_remote_or_inline_src(src: &str) -> bool {
+    let lower = src.to_ascii_lowercase();
+    ["http://", "https://", "data:"]
+        .iter()
+        .any(|prefix| lower.starts_with(prefix))
+}
+
+fn note_body_html(note_path: &Path, text: &str) -> String {
+    if !is_markdown_note(note_path) {
+        return format!("<pre>{}</pre>", escape_html(text));
+    }
+    let mut options = markdown::Options::gfm();
+    options.parse.constructs.frontmatter = true;
+    options.compile.allow_any_img_src = true;
+    markdown::to_html_with_options(text, &options)
+        .unwrap_or_else(|_| format!("<pre>{}</pre>", escape_html(text)))
+}
+
+/// Renders one note as a standalone HTML page; local images inside `vault_root` are read from
+/// disk (relative to the note) and inlined as base64 data URLs.
+pub(crate) fn build_note_html_export(
+    vault_root: &Path,
+    note_path: &Path,
+    text: &str,
+) -> io::Result<HtmlExport> {
+    let note_dir = note_path.parent().unwrap_or(Path::new("."));
+    let title = note_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let body = note_body_html(note_path, text);
+
+    let image_src = regex::Regex::new(r#"<img src="([^"]*)""#)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error.to_string()))?;
+    let mut large_attachments = Vec::new();
+    let mut missing_attachments = Vec::new();
+    let mut attachment_bytes = 0u64;
+    let mut inlined_body = String::with_capacity(body.len());
+    let mut last_end = 0;
+    for captures in image_src.captures_iter(&body) {
+        let (Some(whole), Some(src)) = (captures.get(0), captures.get(1)) else {
+            continue;
+        };
+        inlined_body.push_str(&body[last_end..whole.start()]);
+        last_end = whole.end();
+        let decoded = decode_image_src(src.as_str());
+        if decoded.is_empty() || is_remote_or_inline_src(&decoded) {
+            inlined_body.push_str(whole.as_str());
+            continue;
+        }
+        let Some(attachment_path) = resolve_export_image(vault_root, note_dir, &decoded) else {
+            trace_debug(format!(
+                "req-exp1 attachment skipped src={decoded} reason=missing-or-outside-vault-or-not-image"
+            ));
+            missing_attachments.push(decoded);
+            inlined_body.push_str(whole.as_str());
+            continue;
+        };
+        match fs::read(&attachment_path) {
+            Ok(bytes) => {
+                let size = bytes.len() as u64;
+                attachment_bytes += size;
+                if size > HTML_EXPORT_LARGE_ATTACHMENT_BYTES {
+                    large_attachments.push((attachment_path.clone(), size));
+                }
+                inlined_body.push_str(&format!(
+                    "<img src=\"data:{};base64,{}\"",
+                    image_mime_type(attachment_path.as_path()).unwrap_or_default(),
+                    base64::engine::general_purpose::STANDARD.encode(bytes)
+                ));
+            }
+            Err(error) => {
+                trace_debug(format!(
+                    "req-exp1 attachment missing path={} error={error}",
+                    attachment_path.display()
+                ));
+                missing_attachments.push(decoded);
+                inlined_body.push_str(whole.as_str());
+            }
+        }
+    }
+    inlined_body.push_str(&body[last_end..]);
+
+    let html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{HTML_EXPORT_STYLE}</style>\n</head>\n<body>\n{inlined_body}\n</body>\n</html>\n",
+        escape_html(&title)
+    );
+    Ok(HtmlExport {
+        html,
+        large_attachments,
+        missing_attachments,
+        attachment_bytes,
+    })
+}
+
+pub(crate) fn format_megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VaultExportFormat {
+    Zip,
+    Folder,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VaultExportProgress {
+    pub done: usize,
+    pub total: usize,
+    pub current: String,
+}
+
+/// Writes every note into `target_dir` as a zip or a flat folder with an index.html.
+/// `progress` gets one message per note; setting `cancel` stops before the next one.
+#[derive(Debug, Clone)]
+pub struct VaultExportRequest {
+    pub user_document_dir: PathBuf,
+    pub target_dir: PathBuf,
+    pub format: VaultExportFormat,
+    pub now: DateTime<Local>,
+    pub progress: smol::channel::Sender<VaultExportProgress>,
+    pub cancel: Arc<AtomicBool>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VaultExportReport {
+    pub target: PathBuf,
+    pub notes: usize,
+    /// Nothing is left behind when the user cancels.
+    pub cancelled: bool,
+}
+
+// Notes outside the recycle bin, relative to the vault, in tree order.
+fn collect_vault_export_notes(user_document_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut notes = Vec::new();
+    crate::tag_index::collect_note_paths(user_document_dir, &mut notes)?;
+    let mut relative = notes
+        .iter()
+        .filter_map(|note| note.strip_prefix(user_document_dir).ok())
+        .filter(|note| !note.starts_with("recyclebin"))
+        .map(Path::to_path_buf)
+        .collect::<Vec<_>>();
+    relative.sort();
+    Ok(relative)
+}
+
+/// `2026/03/01/plan.md` becomes `2026-03-01 plan.md`; a clash gets `_2` like new notes do.
+pub(crate) fn flat_export_names(notes: &[PathBuf]) -> Vec<String> {
+    let mut taken = HashSet::new();
+    notes
+        .iter()
+        .map(|note| {
+            let folders = note
+                .parent()
+                .map(|parent| {
+                    parent
+                        .components()
+                        .map(|component| component.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("-")
+                })
+                .unwrap_or_default();
+            let stem = note
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let stem = if folders.is_empty() {
+                stem
+            } else {
+                format!("{folders} {stem}")
+            };
+            let extension = note
+                .extension()
+                .map(|extension| extension.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let mut suffix = 1usize;
+            loop {
+                let name = if suffix == 1 {
+                    format!("{stem}.{extension}")
+                } else {
+                    format!("{stem}_{suffix}.{extension}")
+                };
+                if taken.insert(name.to_lowercase()) {
+                    break name;
+                }
+                suffix += 1;
+            }
+        })
+        .collect()
+}
+
+fn escape_href(name: &str) -> String {
+    name.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+// One list per folder, linking each note by its exported name.
+fn vault_export_index_html(
+    vault_name: &str,
+    notes: &[PathBuf],
+    names: &[String],
+    now: DateTime<Local>,
+) -> String {
+    let mut body = format!(
+        "<h1>{}</h1>\n<p>{} note(s), exported {}</p>\n",
+        escape_html(vault_name),
+        notes.len(),
+        now.format("%Y-%m-%d %H:%M")
+    );
+    let mut current_folder = None;
+    for (note, name) in notes.iter().zip(names) {
+        let folder = note
+            .parent()
+            .map(|parent| parent.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+        if current_folder.as_ref() != Some(&folder) {
+            if current_folder.is_some() {
+                body.push_str("</ul>\n");
+            }
+            let heading = if folder.is_empty() {
+                "/"
+            } else {
+                folder.as_str()
+            };
+            body.push_str(&format!("<h2>{}</h2>\n<ul>\n", escape_html(heading)));
+            current_folder = Some(folder);
+        }
+        let title = note
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        body.push_str(&format!(
+            "<li><a href=\"{}\">{}</a></li>\n",
+            escape_href(name),
+            escape_html(&title)
+        ));
+    }
+    if current_folder.is_some() {
+        body.push_str("</ul>\n");
+    }
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{HTML_EXPORT_STYLE}</style>\n</head>\n<body>\n{body}</body>\n</html>\n",
+        escape_html(vault_name)
+    )
+}
+
+enum VaultExportSink {
+    Zip(Box<ZipWriter<fs::File>>),
+    Folder(PathBuf),
+}
+
+impl VaultExportSink {
+    fn create(format: VaultExportFormat, partial: &Path) -> io::Result<Self> {
+        match format {
+            VaultExportFormat::Zip => {
+                let file = fs::File::create(partial)?;
+                Ok(Self::Zip(Box::new(ZipWriter::new(file))))
+            }
+            VaultExportFormat::Folder => {
+                fs::create_dir(partial)?;
+                Ok(Self::Folder(partial.to_path_buf()))
+            }
+        }
+    }
+
+    fn add(&mut self, name: &str, bytes: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Zip(zip) => {
+                let options = SimpleFileOptions::default()
+                    .compression_method(zip::CompressionMethod::Deflated);
+                zip.start_file(name, options).map_err(io::Error::other)?;
+                zip.write_all(bytes)
+            }
+            Self::Folder(dir) => fs::write(dir.join(name), bytes),
+        }
+    }
+
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Zip(zip) => zip.finish().map_err(io::Error::other)?.sync_all(),
+            Self::Folder(_) => Ok(()),
+        }
+    }
+}
+
+fn remove_partial_export(partial: &Path) {
+    let removed = if partial.is_dir() {
+        fs::remove_dir_all(partial)
+    } else {
+        fs::remove_file(partial)
+    };
+    match removed {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => trace_debug(format!(
+            "req-vexp1 partial export cleanup failed path={} error={error}",
+            partial.display()
+        )),
+        _ => {}
+    }
+}
+
+/// Copies every note (minus the recycle bin) into one flat zip or folder named after `now`,
+/// plus an index.html linking them by folder. The output appears under its final name only
+/// once complete; a cancelled or failed export leaves nothing behind.
+// `dir` may not exist yet: its nearest existing folder is resolved and the rest appended, so
+// links and `..` cannot hide that it lies inside the vault.
+fn canonical_target_dir(dir: &Path) -> io::Result<PathBuf> {
+    let mut missing = Vec::new();
+    let mut existing = dir;
+    loop {
+        match fs::canonicalize(existing) {
+            Ok(canonical) => {
+                return Ok(missing
+                    .iter()
+                    .rev()
+                    .fold(canonical, |path, name| path.join(name)));
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+                    return Err(error);
+                };
+                missing.push(name);
+                existing = parent;
+            }
+            Err(error) => return Err(at_path(existing)(error)),
+        }
+    }
+}
+
+pub(crate) fn write_vault_export(request: &VaultExportRequest) -> io::Result<VaultExportReport> {
+    let root = request.user_document_dir.as_path();
+    let vault = fs::canonicalize(root).map_err(at_path(root))?;
+    if canonical_target_dir(&request.target_dir)?.starts_with(&vault) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{} is inside the notes folder; pick a folder outside it",
+                request.target_dir.display()
+            ),
+        ));
+    }
+    let notes = collect_vault_export_notes(root).map_err(at_path(root))?;
+    let names = flat_export_names(&notes);
+    let vault_name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let index_html = vault_export_index_html(&vault_name, &notes, &names, request.now);
+
+    fs::create_dir_all(&request.target_dir).map_err(at_path(&request.target_dir))?;
+    let base_name = format!(
+        "{VAULT_EXPORT_FILE_PREFIX}{}",
+        request.now.format("%Y%m%d_%H%M%S")
+    );
+    let target = match request.format {
+        VaultExportFormat::Zip => request.target_dir.join(format!("{base_name}.zip")),
+        VaultExportFormat::Folder => request.target_dir.join(&base_name),
+    };
+    if target.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", target.display()),
+        ));
+    }
+    let mut partial = target.clone().into_os_string();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+    let total = notes.len();
+    let written = (|| -> io::Result<bool> {
+        let mut sink =
+            VaultExportSink::create(request.format, &partial).map_err(at_path(&partial))?;
+        for (done, (note, name)) in notes.iter().zip(&names).enumerate() {
+            if request.cancel.load(Ordering::SeqCst) {
+                return Ok(false);
+            }
+            let _ = request.progress.send_blocking(VaultExportProgress {
+                done,
+                total,
+                current: note.display().to_string(),
+            });
+            let source = root.join(note);
+            let bytes = fs::read(&source).map_err(at_path(&source))?;
+            sink.add(name, &bytes).map_err(at_path(&partial))?;
+        }
+        sink.add(VAULT_EXPORT_INDEX_NAME, index_html.as_bytes())
+            .map_err(at_path(&partial))?;
+        sink.finish().map_err(at_path(&partial))?;
+        Ok(true)
+    })();
+    let finished = written.and_then(|completed| {
+        if completed {
+            fs::rename(&partial, &target).map_err(at_path(&target))?;
+        }
+        Ok(completed)
+    });
+    let completed = match finished {
+        Ok(true) => true,
+        Ok(false) => {
+            remove_partial_export(&partial);
+            false
+        }
+        Err(error) => {
+            remove_partial_export(&partial);
+            return Err(error);
+        }
+    };
+    trace_debug(format!(
+        "req-vexp1 vault export target={} notes={total} completed={completed}",
+        target.display()
+    ));
+    Ok(VaultExportReport {
+        target,
+        notes: total,
+        cancelled: !completed,
+    })
+}
+
+/// Shown while a vault export runs on its thread.
+pub(crate) struct VaultExportStatus {
+    pub progress: Option<VaultExportProgress>,
+    pub cancel: Arc<AtomicBool>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportLineEnding {
+    #[default]
+    Lf,
+    Crlf,
+    /// Whatever each note already uses.
+    Keep,
+}
+
+/// One `[[profile]]` in `conf/export_profiles.toml`: how a note is cleaned before it is
+/// handed to someone else.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlainTextExportProfile {
+    pub name: String,
+    pub strip_front_matter: bool,
+    pub strip_tags: bool,
+    pub line_ending: ExportLineEnding,
+    pub extension: String,
+}
+
+impl Default for PlainTextExportProfile {
+    fn default() -> Self {
+        Self {
+            name: "Plain text".to_string(),
+            strip_front_matter: true,
+            strip_tags: true,
+            line_ending: ExportLineEnding::Lf,
+            extension: "txt".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ExportProfilesFile {
+    #[serde(default, rename = "profile")]
+    profiles: Vec<PlainTextExportProfile>,
+}
+
+/// The profiles in `path`. A missing file is written with the default profile, so there is
+/// one to copy from when adding more.
+pub(crate) fn load_export_profiles(path: &Path) -> io::Result<Vec<PlainTextExportProfile>> {
+    let file = if path.is_file() {
+        let raw = fs::read_to_string(path)?;
+        toml::from_str::<ExportProfilesFile>(&raw)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?
+    } else {
+        let defaults = ExportProfilesFile {
+            profiles: vec![PlainTextExportProfile::default()],
+        };
+        let text = toml::to_string(&defaults)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+        crate::atomic_io::write_atomic(path, text.as_bytes())?;
+        defaults
+    };
+    if file.profiles.is_empty() {
+        return Ok(vec![PlainTextExportProfile::default()]);
+    }
+    Ok(file.profiles)
+}
+
+pub(crate) fn clean_note_for_export(text: &str, profile: &PlainTextExportProfile) -> String {
+    let body = if profile.strip_front_matter {
+        crate::note_front_matter::strip_front_matter(text)
+    } else {
+        text
+    };
+    let body = if profile.strip_tags {
+        crate::tag_index::strip_tags(body)
+    } else {
+        body.to_string()
+    };
+    let mut cleaned = body.trim_start_matches(['\r', '\n']).trim_end().to_string();
+    cleaned.push('\n');
+    match profile.line_ending {
+        ExportLineEnding::Lf => cleaned.replace("\r\n", "\n"),
+        ExportLineEnding::Crlf => cleaned.replace("\r\n", "\n").replace('\n', "\r\n"),
+        ExportLineEnding::Keep => cleaned,
+    }
+}
+
+/// The notes `sources` stand for: folders by every note inside them, sorted, each note once.
+pub(crate) fn collect_plain_text_export_notes(sources: &[PathBuf]) -> Vec<PathBuf> {
+    let mut notes = Vec::new();
+    for source in sources {
+        if source.is_dir() {
+            let mut inside = Vec::new();
+            if let Err(error) = crate::tag_index::collect_note_paths(source, &mut inside) {
+                trace_debug(format!(
+                    "req-ptx1 folder walk failed path={} error={error}",
+                    source.display()
+                ));
+            }
+            inside.sort();
+            notes.extend(inside);
+        } else if crate::file_update_handler::NoteFileExtension::from_path(source).is_some() {
+            notes.push(source.clone());
+        }
+    }
+    let mut seen = HashSet::new();
+    notes.retain(|note| seen.insert(note.clone()));
+    notes
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlainTextExportReport {
+    pub written: Vec<PathBuf>,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// Writes a cleaned copy of each note into `target_dir` under its own name with the
+/// profile's extension (`_2` on a clash). The notes themselves are not touched.
+pub(crate) fn write_plain_text_export(
+    notes: &[PathBuf],
+    target_dir: &Path,
+    profile: &PlainTextExportProfile,
+) -> PlainTextExportReport {
+    let extension = match profile.extension.trim().trim_start_matches('.') {
+        "" => "txt",
+        extension => extension,
+    };
+    let mut report = PlainTextExportReport::default();
+    for note in notes {
+        let stem = note
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "note".to_string());
+        let written = fs::read_to_string(note).and_then(|text| {
+            let mut suffix = 1;
+            let target = loop {
+                let target = crate::file_update_handler::resolve_unique_path(
+                    target_dir, &stem, extension, suffix,
+                );
+                if !target.exists() {
+                    break target;
+                }
+                suffix += 1;
+            };
+            let cleaned = clean_note_for_export(&text, profile);
+            crate::atomic_io::write_atomic(target.as_path(), cleaned.as_bytes())?;
+            Ok(target)
+        });
+        match written {
+            Ok(target) => report.written.push(target),
+            Err(error) => report.failed.push((note.clone(), error.to_string())),
+        }
+    }
+    report
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn export_current_note_as_html(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(note_path) = self.file_workflow.current_edit_path() else {
+            self.show_transient_notice(crate::i18n::tr("notice.export_needs_note").to_string(), cx);
+            return;
+        };
+        let text = self.editor.read(cx).snapshot(cx).value;
+        let export = match build_note_html_export(
+            self.app_paths.user_document_dir.as_path(),
+            note_path.as_path(),
+            &text,
+        ) {
+            Ok(export) => export,
+            Err(error) => {
+                self.show_transient_notice(
+                    crate::i18n::tr_with(
+                        "notice.html_export_failed",
+                        &[("error", error.to_string().as_str())],
+                    ),
+                    cx,
+                );
+                return;
+            }
+        };
+        trace_debug(format!(
+            "req-exp1 html export prepared path={} html_len={} attachments_bytes={} large={} missing={}",
+            note_path.display(),
+            export.html.len(),
+            export.attachment_bytes,
+            export.large_attachments.len(),
+            export.missing_attachments.len()
+        ));
+
+        if !export.needs_size_warning() {
+            self.prompt_html_export_target(note_path, export, window, cx);
+            return;
+        }
+        let mut detail = export
+            .large_attachments
+            .iter()
+            .map(|(path, size)| {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                format!("{name}: {}", format_megabytes(*size))
+            })
+            .collect::<Vec<_>>();
+        detail.push(crate::i18n::tr_with(
+            "prompt.all_attachments",
+            &[("size", format_megabytes(export.attachment_bytes).as_str())],
+        ));
+        let buttons = [
+            crate::i18n::tr("button.export_anyway"),
+            crate::i18n::tr("button.cancel"),
+        ];
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            &crate::i18n::tr("prompt.large_attachments"),
+            Some(&detail.join("\n")),
+            &[buttons[0].as_ref(), buttons[1].as_ref()],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            if answer.await.ok() != Some(0) {
+                return;
+            }
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.prompt_html_export_target(note_path, export, window, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn prompt_html_export_target(
+        &mut self,
+        note_path: PathBuf,
+        export: HtmlExport,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let directory = note_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| self.app_paths.user_document_dir.clone());
+        let file_name = format!(
+            "{}.html",
+            note_path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "note".to_string())
+        );
+        let answer = cx.prompt_for_new_path(directory.as_path(), Some(&file_name));
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(target))) = answer.await else {
+                return;
+            };
+            let result = crate::atomic_io::write_atomic(target.as_path(), export.html.as_bytes());
+            let _ = this.update_in(cx, |app, window, cx| {
+                let exported = result.is_ok();
+                let target_text = target.display().to_string();
+                let message = match result {
+                    Ok(()) if export.missing_attachments.is_empty() => crate::i18n::tr_with(
+                        "notice.exported_to",
+                        &[("path", target_text.as_str())],
+                    ),
+                    Ok(()) => crate::i18n::tr_with(
+                        "notice.exported_to_missing_images",
+                        &[
+                            ("path", target_text.as_str()),
+                            (
+                                "count",
+                                export.missing_attachments.len().to_string().as_str(),
+                            ),
+                        ],
+                    ),
+                    Err(error) => crate::i18n::tr_with(
+                        "notice.html_export_failed",
+                        &[("error", error.to_string().as_str())],
+                    ),
+                };
+                trace_debug(format!(
+                    "req-exp1 html export target={} message='{message}'",
+                    target.display()
+                ));
+                app.show_transient_notice(message, cx);
+                if exported {
+                    app.offer_publish_lock(note_path, window, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    // Locking is what keeps the note from drifting away from the copy just published; the
+    // `publish_lock` confirmation setting decides whether that happens, asks, or is skipped.
+    fn offer_publish_lock(
+        &mut self,
+        note_path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.file_workflow.current_edit_path().as_deref() != Some(note_path.as_path())
+            || self.editor.read(cx).publish_lock().is_some()
+        {
+            return;
+        }
+        let policy = self.confirmations;
+        crate::confirmations::run_confirmed(
+            self,
+            policy,
+            crate::confirmations::ConfirmationKind::PublishLock,
+            "Lock this note so it keeps matching the exported copy?",
+            window,
+            cx,
+            move |app, window, cx| {
+                // The user may have switched notes while the prompt was open.
+                if app.file_workflow.current_edit_path().as_deref() != Some(note_path.as_path()) {
+                    return;
+                }
+                let published_at = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+                app.editor.update(cx, |editor, cx| {
+                    editor.set_publish_lock(Some(&published_at), window, cx)
+                });
+                cx.notify();
+            },
+        );
+    }
+
+    fn unlock_published_note(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.set_publish_lock(None, window, cx));
+        cx.notify();
+    }
+
+    pub(crate) fn render_publish_lock_banner(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let published_at = self.editor.read(cx).publish_lock()?.to_string();
+        Some(
+            h_flex()
+                .w_full()
+                .gap_2()
+                .px_2()
+                .py_1()
+                .items_center()
+                .text_xs()
+                .bg(cx.theme().warning)
+                .child(div().flex_1().child(format!(
+                    "Locked since it was published ({published_at}). Unlock to edit; the exported copy will no longer match."
+                )))
+                .child(
+                    Button::new("publish-lock-unlock")
+                        .small()
+                        .label(crate::i18n::tr("button.unlock"))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.unlock_published_note(window, cx)
+                        })),
+                )
+                .into_any_element(),
+        )
+    }
+
+    pub(crate) fn choose_vault_export_format(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.vault_export.is_some() {
+            self.show_transient_notice(
+                crate::i18n::tr("notice.vault_export_running").to_string(),
+                cx,
+            );
+            return;
+        }
+        let buttons = [
+            crate::i18n::tr("button.zip_archive"),
+            crate::i18n::tr("button.folder"),
+            crate::i18n::tr("button.cancel"),
+        ];
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &crate::i18n::tr("prompt.export_vault"),
+            Some(&crate::i18n::tr("prompt.export_vault_detail")),
+            &[
+                buttons[0].as_ref(),
+                buttons[1].as_ref(),
+                buttons[2].as_ref(),
+            ],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let format = match answer.await {
+                Ok(0) => VaultExportFormat::Zip,
+                Ok(1) => VaultExportFormat::Folder,
+                _ => return,
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.pick_vault_export_target(format, window, cx)
+            });
+        })
+        .detach();
+    }
+
+    fn pick_vault_export_target(
+        &mut self,
+        format: VaultExportFormat,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let answer = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some(crate::i18n::tr("button.export_here")),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = answer.await else {
+                return;
+            };
+            let Some(target_dir) = paths.into_iter().next() else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.run_vault_export(format, target_dir, window, cx)
+            });
+        })
+        .detach();
+    }
+
+    fn run_vault_export(
+        &mut self,
+        format: VaultExportFormat,
+        target_dir: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.flush_editor_content_before_context_switch("req-vexp1-export", cx) {
+            self.show_transient_notice(
+                crate::i18n::tr("notice.export_unsaved_note").to_string(),
+                cx,
+            );
+            return;
+        }
+        // Reading every note and writing the archive can take minutes on a large vault, so
+        // it runs on a thread of its own rather than holding up the workflow worker.
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (progress_tx, progress_rx) = smol::channel::unbounded();
+        let request = VaultExportRequest {
+            user_document_dir: self.app_paths.user_document_dir.clone(),
+            target_dir,
+            format,
+            now: Local::now(),
+            progress: progress_tx,
+            cancel: cancel.clone(),
+        };
+        let (report_tx, report_rx) = smol::channel::bounded::<WorkflowResult<VaultExportReport>>(1);
+        thread::spawn(move || {
+            let result = write_vault_export(&request)
+                .map_err(|error| WorkflowError::from_io(error, request.target_dir.as_path()));
+            let _ = report_tx.try_send(result);
+        });
+        trace_debug(format!("req-vexp1 vault export started format={format:?}"));
+        self.vault_export = Some(VaultExportStatus {
+            progress: None,
+            cancel,
+        });
+        cx.notify();
+        cx.spawn_in(window, async move |this, cx| {
+            while let Ok(progress) = progress_rx.recv().await {
+                let updated = this.update_in(cx, |app, _, cx| {
+                    if let Some(status) = app.vault_export.as_mut() {
+                        status.progress = Some(progress);
+                        cx.notify();
+                    }
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(result) = report_rx.recv().await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, _, cx| {
+                app.vault_export = None;
+                let message = match result {
+                    Ok(report) if report.cancelled => {
+                        crate::i18n::tr("notice.vault_export_cancelled").to_string()
+                    }
+                    Ok(report) => crate::i18n::tr_with(
+                        "notice.exported_notes_to",
+                        &[
+                            ("count", report.notes.to_string().as_str()),
+                            ("path", report.target.display().to_string().as_str()),
+                        ],
+                    ),
+                    Err(error) => {
+                        trace_debug(format!("req-vexp1 vault export failed error={error}"));
+                        error.notice(crate::i18n::tr("error.export_failed"))
+                    }
+                };
+                app.show_transient_notice(message, cx);
+            });
+        })
+        .detach();
+    }
+
+    pub(crate) fn render_vault_export_progress(
+        &self,
+        cx: &mut Context<Self>,
+    ) -> Option<AnyElement> {
+        let status = self.vault_export.as_ref()?;
+        let (label, fraction) = match &status.progress {
+            Some(progress) => (
+                crate::i18n::tr_with(
+                    "label.exporting_vault",
+                    &[
+                        ("done", (progress.done + 1).to_string().as_str()),
+                        ("total", progress.total.to_string().as_str()),
+                        ("name", progress.current.as_str()),
+                    ],
+                ),
+                (progress.done + 1) as f32 / progress.total.max(1) as f32,
+            ),
+            None => (
+                crate::i18n::tr("label.export_collecting_notes").to_string(),
+                0.,
+            ),
+        };
+        let cancelling = status.cancel.load(Ordering::SeqCst);
+        Some(
+            h_flex()
+                .w_full()
+                .gap_2()
+                .px_2()
+                .py_1()
+                .items_center()
+                .text_xs()
+                .bg(cx.theme().popover)
+                .child(div().flex_1().min_w_0().truncate().child(label))
+                .child(
+                    div()
+                        .w(px(160.))
+                        .h(px(6.))
+                        .rounded_sm()
+                        .bg(cx.theme().border)
+                        .child(
+                            div()
+                                .h_full()
+                                .rounded_sm()
+                                .w(relative(fraction.clamp(0., 1.)))
+                                .bg(cx.theme().accent),
+                        ),
+                )
+                .child(
+                    Button::new("vault-export-cancel")
+                        .small()
+                        .ghost()
+                        .label(crate::i18n::tr(if cancelling {
+                            "button.cancelling"
+                        } else {
+                            "button.cancel"
+                        }))
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            if let Some(status) = this.vault_export.as_ref() {
+                                status.cancel.store(true, Ordering::SeqCst);
+                                cx.notify();
+                            }
+                        })),
+                )
+                .into_any_element(),
+        )
+    }
+
+    /// From the file tree: the selection when `path` is part of it, otherwise just `path`;
+    /// folders stand for every note inside them and are walked in the background.
+    pub(crate) fn export_plain_text_from_tree(
+        &mut self,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let selection = self.file_tree.read(cx).selected_paths();
+        let sources = if selection.contains(&path) {
+            selection
+        } else {
+            vec![path]
+        };
+        let walk = cx
+            .background_executor()
+            .spawn(async move { collect_plain_text_export_notes(&sources) });
+        cx.spawn_in(window, async move |this, cx| {
+            let notes = walk.await;
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.choose_plain_text_export_profile(notes, window, cx)
+            });
+        })
+        .detach();
+    }
+
+    fn choose_plain_text_export_profile(
+        &mut self,
+        notes: Vec<PathBuf>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if notes.is_empty() {
+            self.show_transient_notice(
+                crate::i18n::tr("notice.no_notes_to_export").to_string(),
+                cx,
+            );
+            return;
+        }
+        let profiles_path = self.app_paths.config_file_path(EXPORT_PROFILES_FILE_NAME);
+        let profiles = match load_export_profiles(profiles_path.as_path()) {
+            Ok(profiles) => profiles,
+            Err(error) => {
+                self.show_transient_notice(
+                    crate::i18n::tr_with(
+                        "notice.cannot_read",
+                        &[
+                            ("path", profiles_path.display().to_string().as_str()),
+                            ("error", error.to_string().as_str()),
+                        ],
+                    ),
+                    cx,
+                );
+                return;
+            }
+        };
+        if let [profile] = profiles.as_slice() {
+            self.pick_plain_text_export_target(notes, profile.clone(), window, cx);
+            return;
+        }
+        let mut labels = profiles
+            .iter()
+            .map(|profile| profile.name.clone())
+            .collect::<Vec<_>>();
+        labels.push(crate::i18n::tr("button.cancel").to_string());
+        let label_refs = labels.iter().map(String::as_str).collect::<Vec<_>>();
+        let detail = crate::i18n::tr_with(
+            "prompt.plain_text_export_detail",
+            &[
+                ("count", notes.len().to_string().as_str()),
+                ("path", profiles_path.display().to_string().as_str()),
+            ],
+        );
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &crate::i18n::tr("prompt.plain_text_export"),
+            Some(&detail),
+            &label_refs,
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(choice) = answer.await else {
+                return;
+            };
+            let Some(profile) = profiles.get(choice).cloned() else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.pick_plain_text_export_target(notes, profile, window, cx)
+            });
+        })
+        .detach();
+    }
+
+    fn pick_plain_text_export_target(
+        &mut self,
+        notes: Vec<PathBuf>,
+        profile: PlainTextExportProfile,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let answer = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some(crate::i18n::tr("button.export_here")),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = answer.await else {
+                return;
+            };
+            let Some(target_dir) = paths.into_iter().next() else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.run_plain_text_export(notes, target_dir, profile, window, cx)
+            });
+        })
+        .detach();
+    }
+
+    fn run_plain_text_export(
+        &mut self,
+        notes: Vec<PathBuf>,
+        target_dir: PathBuf,
+        profile: PlainTextExportProfile,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // The open note may be among them; export what the editor shows.
+        if !self.flush_editor_content_before_context_switch("req-ptx1-export", cx) {
+            self.show_transient_notice(
+                crate::i18n::tr("notice.export_unsaved_note").to_string(),
+                cx,
+            );
+            return;
+        }
+        let request = crate::file_update_handler::PlainTextExportRequest {
+            notes,
+            target_dir: target_dir.clone(),
+            profile: profile.clone(),
+        };
+        let (report_tx, report_rx) =
+            smol::channel::bounded::<WorkflowResult<PlainTextExportReport>>(1);
+        if let Err(error) = self
+            .file_workflow
+            .plain_text_export_async(request, move |result| {
+                let _ = report_tx.try_send(result);
+            })
+        {
+            self.show_transient_notice(error.notice(crate::i18n::tr("error.export_failed")), cx);
+            return;
+        }
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(result) = report_rx.recv().await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, _, cx| {
+                let report = match result {
+                    Ok(report) => report,
+                    Err(error) => {
+                        trace_debug(format!("req-ptx1 plain text export failed error={error}"));
+                        app.show_transient_notice(
+                            error.notice(crate::i18n::tr("error.export_failed")),
+                            cx,
+                        );
+                        return;
+                    }
+                };
+                trace_debug(format!(
+                    "req-ptx1 plain text export profile={} target={} written={} failed={}",
+                    profile.name,
+                    target_dir.display(),
+                    report.written.len(),
+                    report.failed.len()
+                ));
+                let written = report.written.len().to_string();
+                let target_text = target_dir.display().to_string();
+                let mut args = vec![
+                    ("count", written.as_str()),
+                    ("profile", profile.name.as_str()),
+                    ("path", target_text.as_str()),
+                ];
+                let failed = report.failed.len().to_string();
+                let key = if report.failed.is_empty() {
+                    "notice.plain_text_exported"
+                } else {
+                    args.push(("failed", failed.as_str()));
+                    "notice.plain_text_exported_with_failures"
+                };
+                let message = crate::i18n::tr_with(key, &args);
+                app.show_transient_notice(message, cx);
+            });
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_export_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    #[test]
+    fn exp_test1_markdown_note_inlines_local_images_and_keeps_remote_ones() {
+        let root = new_temp_root("exp_test1");
+        fs::create_dir_all(root.join("img dir")).expect("create img dir");
+        fs::write(
+            root.join("img dir").join("dot.png"),
+            [0x89, b'P', b'N', b'G'],
+        )
+        .expect("png");
+        let note = root.join("trip.md");
+        let text = "---\ntags: [a]\n---\n# Trip <1>\n\n![dot](<img dir/dot.png>)\n![web](https://example.com/a.png)\n![gone](missing.png)\n";
+
+        let export = build_note_html_export(root.as_path(), note.as_path(), text).expect("export");
+
+        assert!(export.html.contains("<title>trip</title>"));
+        assert!(export.html.contains("<h1>Trip &lt;1&gt;</h1>"));
+        assert!(
+            export
+                .html
+                .contains("src=\"data:image/png;base64,iVBORw==\"")
+        );
+        assert!(export.html.contains("src=\"https://example.com/a.png\""));
+        assert!(!export.html.contains("tags: [a]"));
+        assert_eq!(export.missing_attachments, vec!["missing.png".to_string()]);
+        assert_eq!(export.attachment_bytes, 4);
+        assert!(!export.needs_size_warning());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn exp_test2_plain_note_is_escaped_and_large_attachment_is_flagged() {
+        let root = new_temp_root("exp_test2");
+        let plain = build_note_html_export(
+            root.as_path(),
+            root.join("memo.txt").as_path(),
+            "a < b\n# not a heading",
+        )
+        .expect("plain export");
+        assert!(plain.html.contains("<pre>a &lt; b\n# not a heading</pre>"));
+
+        let big = vec![0u8; HTML_EXPORT_LARGE_ATTACHMENT_BYTES as usize + 1];
+        fs::write(root.join("big.jpg"), &big).expect("write big");
+        let export = build_note_html_export(
+            root.as_path(),
+            root.join("photo.md").as_path(),
+            "![big](big.jpg)",
+        )
+        .expect("export");
+        assert!(export.needs_size_warning());
+        assert_eq!(export.large_attachments.len(), 1);
+        assert!(export.html.contains("data:image/jpeg;base64,"));
+        let _ = fs::remove_dir_all(root);
+    }
+
+    fn vault_export_request(
+        root: &Path,
+        format: VaultExportFormat,
+        second: u32,
+    ) -> (
+        VaultExportRequest,
+        smol::channel::Receiver<VaultExportProgress>,
+    ) {
+        use chrono::TimeZone;
+        let (progress, progress_rx) = smol::channel::unbounded();
+        let request = VaultExportRequest {
+            user_document_dir: root.join("vault"),
+            target_dir: root.join("out"),
+            format,
+            now: Local
+                .with_ymd_and_hms(2026, 3, 1, 9, 0, second)
+                .single()
+                .expect("local time"),
+            progress,
+            cancel: Arc::new(AtomicBool::new(false)),
+        };
+        (request, progress_rx)
+    }
+
+    #[test]
+    fn exp_test3_vault_export_flattens_notes_with_index_and_cancel_leaves_nothing() {
+        let root = new_temp_root("exp_test3");
+        let vault = root.join("vault");
+        fs::create_dir_all(vault.join("2026/03/01")).expect("create day");
+        fs::create_dir_all(vault.join("2026-03/01")).expect("create clashing day");
+        fs::create_dir_all(vault.join("recyclebin")).expect("create recyclebin");
+        fs::write(vault.join("2026/03/01/plan & ideas.md"), "# plan").expect("plan");
+        fs::write(vault.join("2026-03/01/plan & ideas.md"), "other").expect("clash");
+        fs::write(vault.join("top.txt"), "top").expect("top");
+        fs::write(vault.join("2026/03/01/photo.png"), "png").expect("png");
+        fs::write(vault.join("recyclebin/old.md"), "gone").expect("trashed");
+
+        let (request, progress_rx) = vault_export_request(&root, VaultExportFormat::Folder, 0);
+        let report = write_vault_export(&request).expect("folder export");
+        let folder = root.join("out").join("papyru2_export_20260301_090000");
+        assert_eq!(
+            report,
+            VaultExportReport {
+                target: folder.clone(),
+                notes: 3,
+                cancelled: false,
+            }
+        );
+        let mut exported = fs::read_dir(&folder)
+            .expect("read export")
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        exported.sort();
+        assert_eq!(
+            exported,
+            [
+                "2026-03-01 plan & ideas.md",
+                "2026-03-01 plan & ideas_2.md",
+                "index.html",
+                "top.txt",
+            ]
+        );
+        let index = fs::read_to_string(folder.join("index.html")).expect("index");
+        assert!(index.contains("<h2>2026/03/01</h2>"));
+        assert!(index.contains(
+            "<li><a href=\"2026-03-01%20plan%20%26%20ideas.md\">plan &amp; ideas</a></li>"
+        ));
+        assert!(!index.contains("old"));
+        let progress = std::iter::from_fn(|| progress_rx.try_recv().ok()).collect::<Vec<_>>();
+        assert_eq!(progress.len(), 3);
+        assert_eq!(progress[2].done, 2);
+        assert_eq!(progress[2].total, 3);
+
+        let (request, _progress_rx) = vault_export_request(&root, VaultExportFormat::Zip, 1);
+        let report = write_vault_export(&request).expect("zip export");
+        let mut zip = zip::ZipArchive::new(fs::File::open(&report.target).expect("open zip"))
+            .expect("read zip");
+        assert_eq!(zip.len(), 4);
+        assert!(zip.by_name("index.html").is_ok());
+
+        let (request, _progress_rx) = vault_export_request(&root, VaultExportFormat::Zip, 2);
+        request.cancel.store(true, Ordering::SeqCst);
+        let report = write_vault_export(&request).expect("cancelled export");
+        assert!(report.cancelled);
+        assert_eq!(fs::read_dir(root.join("out")).expect("read out").count(), 2);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn exp_test4_plain_text_export_strips_front_matter_and_tags_per_profile() {
+        let root = new_temp_root("exp_test4");
+        let profiles_path = root.join("export_profiles.toml");
+        let profiles = load_export_profiles(&profiles_path).expect("default profiles");
+        assert_eq!(profiles, vec![PlainTextExportProfile::default()]);
+        assert!(profiles_path.is_file());
+
+        let note =
+            "---\r\npin: true\r\n---\r\n\r\nPlan for #work today\r\n#todo #later\r\n\r\nDone.\r\n";
+        let profile = PlainTextExportProfile::default();
+        assert_eq!(
+            clean_note_for_export(note, &profile),
+            "Plan for today\n\nDone.\n"
+        );
+        assert_eq!(
+            clean_note_for_export("#urgent #call  Bob\n\t#x\tfirst\n", &profile),
+            "Bob\nfirst\n"
+        );
+        let keep_everything = PlainTextExportProfile {
+            strip_front_matter: false,
+            strip_tags: false,
+            line_ending: ExportLineEnding::Crlf,
+            ..PlainTextExportProfile::default()
+        };
+        assert_eq!(
+            clean_note_for_export("a #b\nc\n", &keep_everything),
+            "a #b\r\nc\r\n"
+        );
+
+        let notes = root.join("notes");
+        let out = root.join("out");
+        fs::create_dir_all(&notes).expect("notes dir");
+        fs::create_dir_all(&out).expect("out dir");
+        fs::write(notes.join("plan.md"), note).expect("write note");
+        fs::write(out.join("plan.txt"), "already here").expect("write clash");
+        fs::write(notes.join("image.png"), b"png").expect("write image");
+        assert_eq!(
+            collect_plain_text_export_notes(&[notes.clone(), notes.join("plan.md")]),
+            vec![notes.join("plan.md")]
+        );
+        let report = write_plain_text_export(
+            &[notes.join("plan.md"), notes.join("missing.md")],
+            &out,
+            &profile,
+        );
+        assert_eq!(report.written, vec![out.join("plan_2.txt")]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(
+            fs::read_to_string(out.join("plan_2.txt")).expect("read export"),
+            "Plan for today\n\nDone.\n"
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn exp_test5_html_export_inlines_only_images_inside_the_vault() {
+        let root = new_temp_root("exp_test5");
+        let vault = root.join("vault");
+        fs::create_dir_all(vault.join("2026").join("assets")).expect("create vault");
+        fs::write(vault.join("2026").join("assets").join("ok.png"), [1u8]).expect("ok png");
+        fs::write(root.join("secret.png"), [2u8]).expect("outside png");
+        fs::write(vault.join("notes.txt"), "private").expect("txt");
+        let note = vault.join("2026").join("trip.md");
+        let text = "![ok](assets/ok.png)\n![rooted](/2026/assets/ok.png)\n![up](../../secret.png)\n![abs](/etc/hosts)\n![txt](../notes.txt)\n";
+
+        let export = build_note_html_export(vault.as_path(), note.as_path(), text).expect("export");
+
+        assert!(export.html.contains("src=\"data:image/png;base64,AQ==\""));
+        assert!(!export.html.contains("Ag=="));
+        assert_eq!(export.attachment_bytes, 2);
+        assert_eq!(
+            export.missing_attachments,
+            vec![
+                "../../secret.png".to_string(),
+                "/etc/hosts".to_string(),
+                "../notes.txt".to_string()
+            ]
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn exp_test6_vault_export_refuses_a_target_inside_the_vault() {
+        let root = new_temp_root("exp_test6");
+        fs::create_dir_all(root.join("vault/2026/03/01")).expect("create day");
+        fs::write(root.join("vault/2026/03/01/plan.md"), "# plan").expect("plan");
+
+        let (mut request, _progress_rx) = vault_export_request(&root, VaultExportFormat::Folder, 0);
+        request.target_dir = root.join("vault").join("exports").join("today");
+        let error = write_vault_export(&request).expect_err("target inside the vault");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(!root.join("vault").join("exports").exists());
+
+        fs::create_dir_all(root.join("out")).expect("create out");
+        request.target_dir = root.join("out").join("..").join("vault");
+        let error = write_vault_export(&request).expect_err("the vault itself");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,391 @@
+use std::{
+    collections::VecDeque,
+    hash::{DefaultHasher, Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+};
+
+use gpui::*;
+
+use crate::log::trace_debug;
+
+// Every buffer value handed to autosave may still land on disk later, so a bounded history
+// is kept; a disk text found in it is one of our own writes, not an outside change.
+pub(crate) const EXTERNAL_CHANGE_KNOWN_TEXTS: usize = 256;
+
+#[derive(Debug, Default)]
+pub(crate) struct ExternalChangeTracker {
+    path: Option<PathBuf>,
+    known_text_hashes: VecDeque<u64>,
+    prompt_open: bool,
+    // Autosave restamps `modified` on every write, so the managed keys never count.
+    ignore_note_metadata: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ExternalChangeAction {
+    Unchanged,
+    Reload,
+    Conflict,
+}
+
+fn text_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl ExternalChangeTracker {
+    pub fn reset(&mut self, path: Option<PathBuf>, text: &str) {
+        self.path = path;
+        self.known_text_hashes.clear();
+        self.note_text(text);
+    }
+
+    pub fn set_ignore_note_metadata(&mut self, ignore: bool) {
+        self.ignore_note_metadata = ignore;
+    }
+
+    fn comparable<'a>(&self, text: &'a str) -> std::borrow::Cow<'a, str> {
+        if self.ignore_note_metadata {
+            std::borrow::Cow::Owned(crate::note_metadata::strip_note_metadata(text))
+        } else {
+            std::borrow::Cow::Borrowed(text)
+        }
+    }
+
+    pub fn note_text(&mut self, text: &str) {
+        let hash = text_hash(&self.comparable(text));
+        if self.known_text_hashes.back() == Some(&hash) {
+            return;
+        }
+        if self.known_text_hashes.len() == EXTERNAL_CHANGE_KNOWN_TEXTS {
+            self.known_text_hashes.pop_front();
+        }
+        self.known_text_hashes.push_back(hash);
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// A clean buffer follows the disk silently; a dirty one needs the user to decide.
+    pub fn classify(
+        &self,
+        disk_text: &str,
+        buffer: &str,
+        buffer_dirty: bool,
+    ) -> ExternalChangeAction {
+        let disk_text = self.comparable(disk_text);
+        if disk_text == self.comparable(buffer)
+            || self.known_text_hashes.contains(&text_hash(&disk_text))
+        {
+            return ExternalChangeAction::Unchanged;
+        }
+        if buffer_dirty {
+            ExternalChangeAction::Conflict
+        } else {
+            ExternalChangeAction::Reload
+        }
+    }
+}
+
+/// `<stem>_copy.<ext>` next to `path`, numbered when taken, written without touching `path`.
+pub(crate) fn save_conflict_copy(path: &Path, text: &str) -> io::Result<PathBuf> {
+    let parent = path.parent().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "conflict copy path has no parent",
+        )
+    })?;
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    let backend = crate::storage_backend::storage_backend_containing(path);
+    let mut suffix = 1usize;
+    loop {
+        let candidate = if suffix == 1 {
+            parent.join(format!("{stem}_copy{extension}"))
+        } else {
+            parent.join(format!("{stem}_copy_{suffix}{extension}"))
+        };
+        match backend.create_new(&candidate) {
+            Ok(()) => {
+                backend.write(candidate.as_path(), text.as_bytes())?;
+                return Ok(candidate);
+            }
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => suffix += 1,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn reset_external_change_tracking(&mut self, cx: &mut Context<Self>) {
+        let path = self.file_workflow.current_edit_path();
+        let text = self.editor.read(cx).snapshot(cx).value;
+        self.external_change.reset(path, &text);
+    }
+
+    // Runs after each watcher refresh; the watcher already debounces bursts of events.
+    pub(crate) fn check_current_file_external_change(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // The baseline is taken once a background load lands.
+        if self.external_change.prompt_open || self.editor.read(cx).is_loading() {
+            return;
+        }
+        let Some(path) = self.file_workflow.current_edit_path() else {
+            return;
+        };
+        if self.external_change.path() != Some(path.as_path()) {
+            return;
+        }
+        let Ok(disk_text) = crate::editor::read_editor_text_from_disk(path.as_path()) else {
+            // A missing file is a rename or delete; the autosave path handles those.
+            return;
+        };
+        let buffer = self.editor.read(cx).snapshot(cx).value;
+        let dirty = self
+            .editor_autosave
+            .has_pending_payload_for_path(path.as_path());
+        match self.external_change.classify(&disk_text, &buffer, dirty) {
+            ExternalChangeAction::Unchanged => {}
+            ExternalChangeAction::Reload => {
+                trace_debug(format!(
+                    "req-ext1 external change reload path={} dirty=false",
+                    path.display()
+                ));
+                self.reload_current_file_from_disk(path, window, cx);
+            }
+            ExternalChangeAction::Conflict => {
+                let mode = self
+                    .confirmations
+                    .mode(crate::confirmations::ConfirmationKind::OverwriteOnConflict);
+                trace_debug(format!(
+                    "req-ext1 external change conflict path={} dirty=true overwrite_on_conflict={}",
+                    path.display(),
+                    mode.as_str()
+                ));
+                // `always` keeps the buffer over the outside change, `never` keeps the outside
+                // change and saves the buffer as a copy; only `ask` shows the dialog.
+                match mode {
+                    crate::confirmations::ConfirmationMode::Always => {
+                        self.resolve_external_change_conflict(path, Some(1), window, cx)
+                    }
+                    crate::confirmations::ConfirmationMode::Never => {
+                        self.resolve_external_change_conflict(path, Some(2), window, cx)
+                    }
+                    crate::confirmations::ConfirmationMode::Ask => {
+                        self.prompt_external_change_conflict(path, window, cx)
+                    }
+                }
+            }
+        }
+    }
+
+    fn reload_current_file_from_disk(
+        &mut self,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor_autosave.clear_pending_for_path(path.as_path());
+        let snapshot = self.editor.read(cx).snapshot(cx);
+        let reloaded = self.editor.update(cx, |editor, cx| {
+            let reloaded = editor.open_file(path.clone(), window, cx);
+            if reloaded {
+                editor.apply_cursor(snapshot.cursor_line, snapshot.cursor_char, window, cx);
+            }
+            reloaded
+        });
+        if !reloaded {
+            return;
+        }
+        self.reset_external_change_tracking(cx);
+        let text = self.editor.read(cx).snapshot(cx).value;
+        self.record_note_tags(path.as_path(), &text);
+        self.sync_tag_sidebar(cx);
+        self.sync_markdown_preview(true, cx);
+    }
+
+    fn prompt_external_change_conflict(
+        &mut self,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.external_change.prompt_open = true;
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let buttons = [
+            crate::i18n::tr("button.reload_from_disk"),
+            crate::i18n::tr("button.keep_mine"),
+            crate::i18n::tr("button.save_mine_as_copy"),
+        ];
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            &crate::i18n::tr_with("prompt.changed_outside", &[("name", name.as_str())]),
+            Some(&crate::i18n::tr("prompt.changed_outside_detail")),
+            &[
+                buttons[0].as_ref(),
+                buttons[1].as_ref(),
+                buttons[2].as_ref(),
+            ],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let choice = answer.await.ok();
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.external_change.prompt_open = false;
+                app.resolve_external_change_conflict(path, choice, window, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn resolve_external_change_conflict(
+        &mut self,
+        path: PathBuf,
+        choice: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        trace_debug(format!(
+            "req-ext1 conflict choice={choice:?} path={}",
+            path.display()
+        ));
+        // The user may have switched notes while the dialog was open.
+        if self.file_workflow.current_edit_path().as_ref() != Some(&path) {
+            return;
+        }
+        match choice {
+            Some(0) => self.reload_current_file_from_disk(path, window, cx),
+            Some(2) => {
+                let buffer = self.editor.read(cx).snapshot(cx).value;
+                match save_conflict_copy(path.as_path(), &buffer) {
+                    Ok(copy_path) => {
+                        self.record_note_tags(copy_path.as_path(), &buffer);
+                        self.show_transient_notice(
+                            crate::i18n::tr_with(
+                                "notice.edits_saved_to_copy",
+                                &[("path", copy_path.display().to_string().as_str())],
+                            ),
+                            cx,
+                        );
+                        self.reload_current_file_from_disk(path, window, cx);
+                    }
+                    Err(error) => {
+                        trace_debug(format!(
+                            "req-ext1 conflict copy failed path={} error={error}",
+                            path.display()
+                        ));
+                        self.show_transient_notice(
+                            crate::i18n::tr_with(
+                                "notice.edits_copy_failed",
+                                &[("error", error.to_string().as_str())],
+                            ),
+                            cx,
+                        );
+                    }
+                }
+            }
+            // "Keep mine" and a dismissed dialog both keep the buffer; saving it overwrites the
+            // outside change, so its text is accepted as known to avoid asking again.
+            _ => {
+                if let Ok(disk_text) = crate::editor::read_editor_text_from_disk(path.as_path()) {
+                    self.external_change.note_text(&disk_text);
+                }
+                if choice == Some(1) {
+                    self.overwrite_external_change(path, window, cx);
+                }
+            }
+        }
+    }
+
+    fn overwrite_external_change(
+        &mut self,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.snapshot_then(
+            "keep mine overwrite",
+            vec![path],
+            window,
+            cx,
+            |app, _, cx| {
+                app.flush_editor_content_before_context_switch("req-ext1-keep-mine", cx);
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn ext_test1_own_writes_are_ignored_and_outside_changes_reload_or_conflict() {
+        let mut tracker = ExternalChangeTracker::default();
+        tracker.reset(Some(PathBuf::from("note.txt")), "v1");
+        tracker.note_text("v2");
+        tracker.note_text("v3");
+
+        assert_eq!(
+            tracker.classify("v3", "v3", false),
+            ExternalChangeAction::Unchanged
+        );
+        // An older autosave landing after newer typing is still our own write.
+        assert_eq!(
+            tracker.classify("v2", "v3", true),
+            ExternalChangeAction::Unchanged
+        );
+        assert_eq!(
+            tracker.classify("outside", "v3", false),
+            ExternalChangeAction::Reload
+        );
+        assert_eq!(
+            tracker.classify("outside", "v3", true),
+            ExternalChangeAction::Conflict
+        );
+    }
+
+    #[test]
+    fn ext_test2_conflict_copy_is_written_beside_the_note_with_a_free_name() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_external_change_ext_test2_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&root).expect("create temp root");
+        let note = root.join("note.md");
+        fs::write(&note, "disk").expect("write note");
+
+        let first = save_conflict_copy(note.as_path(), "mine 1").expect("first copy");
+        let second = save_conflict_copy(note.as_path(), "mine 2").expect("second copy");
+
+        assert_eq!(first, root.join("note_copy.md"));
+        assert_eq!(second, root.join("note_copy_2.md"));
+        assert_eq!(fs::read_to_string(&second).expect("read copy"), "mine 2");
+        assert_eq!(fs::read_to_string(&note).expect("read note"), "disk");
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,203 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+};
+
+use crate::{file_update_handler::is_path_in_vault, log::trace_debug};
+
+/// Copies an outside file into today's note folder under its own name (`name_2.txt` on a
+/// clash). The original is left where it was.
+pub(crate) fn import_external_file(
+    path: &Path,
+    user_document_dir: &Path,
+    now: DateTime<Local>,
+) -> io::Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "file path has no file name"))?;
+    let bytes = fs::read(path)?;
+    let dir = crate::file_update_handler::ensure_note_directory(user_document_dir, now)?;
+    let mut suffix = 1usize;
+    loop {
+        let candidate = crate::file_update_handler::relocated_daily_candidate_path(
+            dir.as_path(),
+            &file_name,
+            suffix,
+        );
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&candidate)
+        {
+            Ok(mut file) => {
+                io::Write::write_all(&mut file, &bytes)?;
+                return Ok(candidate);
+            }
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => suffix += 1,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+impl crate::app::Papyru2App {
+    /// The open note, when it lives outside the notes folder.
+    pub(crate) fn current_external_file(&self) -> Option<PathBuf> {
+        self.file_workflow
+            .current_edit_path()
+            .filter(|path| !is_path_in_vault(path, self.app_paths.user_document_dir.as_path()))
+    }
+
+    pub(crate) fn prompt_open_external_file(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let answer = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Open".into()),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = answer.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                trace_debug(format!(
+                    "req-ext1 open from anywhere path={}",
+                    path.display()
+                ));
+                if !app.open_file(path.clone(), window, cx) {
+                    app.show_transient_notice(
+                        crate::i18n::tr_with(
+                            "notice.cannot_open_path",
+                            &[("path", path.display().to_string().as_str())],
+                        ),
+                        cx,
+                    );
+                }
+            });
+        })
+        .detach();
+    }
+
+    pub(crate) fn import_current_external_file(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(path) = self.current_external_file() else {
+            return;
+        };
+        if !self.flush_editor_content_before_context_switch("req-ext1-import", cx) {
+            self.show_transient_notice(
+                crate::i18n::tr("notice.import_unsaved_file").to_string(),
+                cx,
+            );
+            return;
+        }
+        match import_external_file(
+            path.as_path(),
+            self.app_paths.user_document_dir.as_path(),
+            Local::now(),
+        ) {
+            Ok(imported) => {
+                trace_debug(format!(
+                    "req-ext1 imported source={} note={}",
+                    path.display(),
+                    imported.display()
+                ));
+                self.apply_file_tree_watcher_refresh(cx);
+                let _ = self.open_file(imported, window, cx);
+            }
+            Err(error) => {
+                self.show_transient_notice(
+                    crate::i18n::tr_with(
+                        "notice.cannot_import",
+                        &[
+                            ("path", path.display().to_string().as_str()),
+                            ("error", error.to_string().as_str()),
+                        ],
+                    ),
+                    cx,
+                );
+            }
+        }
+    }
+
+    pub(crate) fn render_external_file_banner(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let path = self.current_external_file()?;
+        Some(
+            h_flex()
+                .w_full()
+                .gap_2()
+                .px_2()
+                .py_1()
+                .items_center()
+                .text_xs()
+                .bg(cx.theme().warning)
+                .child(div().flex_1().child(format!(
+                    "Editing {} outside the notes folder. Saves go back to this file; the title does not rename it.",
+                    path.display()
+                )))
+                .child(
+                    Button::new("external-file-import")
+                        .small()
+                        .label(crate::i18n::tr("button.import_into_notes"))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.import_current_external_file(window, cx)
+                        })),
+                )
+                .into_any_element(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn xfile_test1_import_copies_into_today_without_touching_source() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_external_files_xfile_test1_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        let vault = root.join("user_document");
+        let outside = root.join("Downloads").join("todo.md");
+        fs::create_dir_all(outside.parent().unwrap()).expect("create outside dir");
+        fs::write(&outside, "- milk").expect("write outside");
+        let now = Local.with_ymd_and_hms(2026, 3, 7, 9, 0, 0).unwrap();
+        let today = vault.join("2026").join("03").join("07");
+        fs::create_dir_all(&today).expect("create today");
+        fs::write(today.join("todo.md"), "taken").expect("write clash");
+
+        let imported =
+            import_external_file(outside.as_path(), vault.as_path(), now).expect("import");
+        assert_eq!(imported, today.join("todo_2.md"));
+        assert_eq!(fs::read_to_string(&imported).expect("read"), "- milk");
+        assert!(outside.is_file());
+        assert!(is_path_in_vault(imported.as_path(), vault.as_path()));
+        assert!(!is_path_in_vault(outside.as_path(), vault.as_path()));
+        let _ = fs::remove_dir_all(root);
+    }
+}
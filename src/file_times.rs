@@ -0,0 +1,251 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::log::trace_debug;
+
+pub(crate) const FILE_TIMES_FILE_NAME: &str = "file_times.json";
+
+static FILE_TIMES_SIDECAR: OnceLock<Mutex<Option<FileTimesSidecar>>> = OnceLock::new();
+
+/// Original creation times of notes whose file system cannot take them back after an atomic
+/// rewrite (Linux and most network shares). Keyed by the note's current path.
+#[derive(Debug)]
+pub(crate) struct FileTimesSidecar {
+    path: PathBuf,
+    created_unix_ms: BTreeMap<PathBuf, u64>,
+}
+
+impl FileTimesSidecar {
+    pub fn open(path: PathBuf) -> Self {
+        let created_unix_ms = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|error| {
+                trace_debug(format!(
+                    "req-ftm1 sidecar corrupt path={} error={error}",
+                    path.display()
+                ));
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+        Self {
+            path,
+            created_unix_ms,
+        }
+    }
+
+    pub fn created(&self, path: &Path) -> Option<SystemTime> {
+        self.created_unix_ms
+            .get(path)
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(*millis))
+    }
+
+    /// Keeps the oldest time seen for `path`; later rewrites must not move it forward.
+    pub fn record_created(&mut self, path: &Path, created: SystemTime) -> bool {
+        let millis = created
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0);
+        match self.created_unix_ms.get_mut(path) {
+            Some(recorded) if *recorded <= millis => false,
+            Some(recorded) => {
+                *recorded = millis;
+                true
+            }
+            None => {
+                self.created_unix_ms.insert(path.to_path_buf(), millis);
+                true
+            }
+        }
+    }
+
+    /// Follows a rename or move of `from`, which may be a directory of notes.
+    pub fn note_moved(&mut self, from: &Path, to: &Path) -> bool {
+        let moved = self
+            .created_unix_ms
+            .keys()
+            .filter(|path| path.starts_with(from))
+            .cloned()
+            .collect::<Vec<_>>();
+        for old in &moved {
+            if let (Some(millis), Ok(rest)) =
+                (self.created_unix_ms.remove(old), old.strip_prefix(from))
+            {
+                let new = if rest.as_os_str().is_empty() {
+                    to.to_path_buf()
+                } else {
+                    to.join(rest)
+                };
+                self.created_unix_ms.insert(new, millis);
+            }
+        }
+        !moved.is_empty()
+    }
+
+    fn save(&self) {
+        let result = serde_json::to_vec(&self.created_unix_ms)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))
+            .and_then(|bytes| crate::atomic_io::write_atomic(self.path.as_path(), &bytes));
+        if let Err(error) = result {
+            trace_debug(format!(
+                "req-ftm1 sidecar write failed path={} error={error}",
+                self.path.display()
+            ));
+        }
+    }
+}
+
+fn with_sidecar<R>(f: impl FnOnce(&mut FileTimesSidecar) -> R) -> Option<R> {
+    let mut sidecar = FILE_TIMES_SIDECAR
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    sidecar.as_mut().map(f)
+}
+
+/// Called once at startup; until then creation times that cannot be restored are lost.
+pub(crate) fn open_file_times_sidecar(data_dir: &Path) {
+    let sidecar = FileTimesSidecar::open(data_dir.join(FILE_TIMES_FILE_NAME));
+    *FILE_TIMES_SIDECAR
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(sidecar);
+}
+
+/// When the note was first created: the sidecar entry if the platform lost it, else the file
+/// system's birth time.
+pub(crate) fn created_time(path: &Path) -> Option<SystemTime> {
+    with_sidecar(|sidecar| sidecar.created(path))
+        .flatten()
+        .or_else(|| {
+            fs::metadata(path)
+                .and_then(|metadata| metadata.created())
+                .ok()
+        })
+}
+
+/// `atomic_io::write_atomic` for an existing note. The replace gives the note a new inode,
+/// so its creation time is put back, or recorded in the sidecar where it cannot be.
+pub(crate) fn write_atomic_preserving_created(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let created = fs::metadata(path)
+        .and_then(|metadata| metadata.created())
+        .ok();
+    crate::atomic_io::write_atomic(path, bytes)?;
+    if let Some(created) = created {
+        keep_created(path, created);
+    }
+    Ok(())
+}
+
+fn keep_created(path: &Path, created: SystemTime) {
+    if fs::metadata(path)
+        .and_then(|metadata| metadata.created())
+        .is_ok_and(|now| now == created)
+    {
+        return;
+    }
+    if let Err(error) = set_created(path, created) {
+        trace_debug(format!(
+            "req-ftm1 creation time kept in sidecar path={} reason={error}",
+            path.display()
+        ));
+        with_sidecar(|sidecar| {
+            if sidecar.record_created(path, created) {
+                sidecar.save();
+            }
+        });
+    }
+}
+
+#[cfg(any(windows, target_os = "macos"))]
+fn set_created(path: &Path, created: SystemTime) -> io::Result<()> {
+    #[cfg(target_os = "macos")]
+    use std::os::macos::fs::FileTimesExt as _;
+    #[cfg(windows)]
+    use std::os::windows::fs::FileTimesExt as _;
+
+    let file = fs::OpenOptions::new().write(true).open(path)?;
+    file.set_times(fs::FileTimes::new().set_created(created))
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn set_created(_path: &Path, _created: SystemTime) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "creation time cannot be set on this platform",
+    ))
+}
+
+/// Renames a note or note folder, failing with `AlreadyExists` rather than replacing `to`. The
+/// rename itself keeps both times on every platform; sidecar entries are moved along with it.
+pub(crate) fn rename_preserving_times(from: &Path, to: &Path) -> io::Result<()> {
+    crate::file_update_handler::ensure_current_worker()?;
+    crate::atomic_io::rename_no_clobber(from, to)?;
+    with_sidecar(|sidecar| {
+        if sidecar.note_moved(from, to) {
+            sidecar.save();
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_file_times_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    #[test]
+    fn ftm_test1_sidecar_keeps_oldest_time_and_follows_moves() {
+        let root = new_temp_root("ftm_test1");
+        let sidecar_path = root.join(FILE_TIMES_FILE_NAME);
+        let mut sidecar = FileTimesSidecar::open(sidecar_path.clone());
+        let note = PathBuf::from("/vault/2020/01/01/plan.md");
+        let first = UNIX_EPOCH + Duration::from_secs(1_000);
+        assert!(sidecar.record_created(&note, first));
+        assert!(!sidecar.record_created(&note, first + Duration::from_secs(60)));
+        assert_eq!(sidecar.created(&note), Some(first));
+
+        assert!(sidecar.note_moved(Path::new("/vault/2020"), Path::new("/vault/archive")));
+        let moved = PathBuf::from("/vault/archive/01/01/plan.md");
+        assert_eq!(sidecar.created(&note), None);
+        assert_eq!(sidecar.created(&moved), Some(first));
+        assert!(!sidecar.note_moved(Path::new("/vault/other.md"), Path::new("/vault/x.md")));
+
+        sidecar.save();
+        let reopened = FileTimesSidecar::open(sidecar_path);
+        assert_eq!(reopened.created(&moved), Some(first));
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn ftm_test2_rewrite_and_rename_keep_note_modified_flow_intact() {
+        let root = new_temp_root("ftm_test2");
+        let note = root.join("plan.md");
+        fs::write(&note, "first").expect("seed");
+        write_atomic_preserving_created(&note, b"second").expect("rewrite");
+        assert_eq!(fs::read_to_string(&note).expect("read"), "second");
+
+        let renamed = root.join("plan renamed.md");
+        rename_preserving_times(&note, &renamed).expect("rename");
+        assert!(!note.exists());
+        assert_eq!(fs::read_to_string(&renamed).expect("read"), "second");
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -1,16 +1,22 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     fs, io,
     path::{Path, PathBuf},
+    time::SystemTime,
 };
 
+use chrono::{Local, Months, NaiveDate};
 use gpui::prelude::FluentBuilder as _;
 use gpui::*;
 use gpui_component::{
-    IconName, h_flex,
+    IconName, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{Input, InputEvent, InputState},
     list::ListItem,
     scroll::{Scrollbar, ScrollbarShow},
     tree::{TreeItem, TreeState, tree},
+    v_flex,
 };
 
 use gpui_component::ActiveTheme as _;
@@ -19,6 +25,18 @@ pub enum FileTreeEvent {
     SelectionChanged(PathBuf),
     OpenFile(PathBuf),
     RecyclebinDeleteRequested(Vec<PathBuf>),
+    TagFilterSelected(Option<String>),
+    BookmarkSelected { path: PathBuf, line: u32 },
+    RenameRequested(PathBuf),
+    DuplicateRequested(PathBuf),
+    RevealRequested(PathBuf),
+    SearchInFolderRequested(PathBuf),
+    MoveRequested { from: PathBuf, to_dir: PathBuf },
+    CreateFolderRequested { parent: PathBuf, name: String },
+    MergeConflictRequested(PathBuf),
+    ExportPlainTextRequested(PathBuf),
+    ConvertToUtf8Requested(PathBuf),
+    OpenInSplitRequested(PathBuf),
 }
 
 pub(crate) fn should_restore_selection_after_watcher_refresh(
@@ -43,6 +61,240 @@ pub(crate) fn should_apply_req_newf38_tree_selection(
     created_stem.starts_with("notitle-")
 }
 
+/// What the right-click menu offers for a tree row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TreeContextAction {
+    NewFolder,
+    Rename,
+    Duplicate,
+    SearchInFolder,
+    Reveal,
+    MoveToTrash,
+    MergeConflict,
+    ExportPlainText,
+    ConvertToUtf8,
+    OpenInSplit,
+}
+
+impl TreeContextAction {
+    pub fn for_row(is_folder: bool) -> &'static [Self] {
+        if is_folder {
+            &[
+                Self::NewFolder,
+                Self::SearchInFolder,
+                Self::ExportPlainText,
+                Self::Reveal,
+                Self::MoveToTrash,
+            ]
+        } else {
+            &[
+                Self::OpenInSplit,
+                Self::Rename,
+                Self::Duplicate,
+                Self::ExportPlainText,
+                Self::ConvertToUtf8,
+                Self::Reveal,
+                Self::MoveToTrash,
+            ]
+        }
+    }
+
+    /// A sync service's conflicted copy offers the merge first; duplicating it would only
+    /// add another copy.
+    pub fn for_conflict_row() -> &'static [Self] {
+        &[
+            Self::MergeConflict,
+            Self::Rename,
+            Self::Reveal,
+            Self::MoveToTrash,
+        ]
+    }
+
+    /// The blank area below the rows stands for the notes folder itself, which cannot be
+    /// trashed.
+    pub fn for_root() -> &'static [Self] {
+        &[Self::NewFolder, Self::SearchInFolder, Self::Reveal]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::NewFolder => "New folder\u{2026}",
+            Self::Rename => "Rename",
+            Self::Duplicate => "Duplicate",
+            Self::SearchInFolder => "Search in this folder",
+            Self::Reveal => {
+                if cfg!(target_os = "windows") {
+                    "Show in Explorer"
+                } else if cfg!(target_os = "macos") {
+                    "Reveal in Finder"
+                } else {
+                    "Show in file manager"
+                }
+            }
+            Self::MoveToTrash => "Move to recycle bin",
+            Self::MergeConflict => "Merge with original\u{2026}",
+            Self::ExportPlainText => "Export as plain text\u{2026}",
+            Self::ConvertToUtf8 => "Convert to UTF-8",
+            Self::OpenInSplit => "Open in split",
+        }
+    }
+
+    // New folder asks for a name in the tree first, so it has no event of its own.
+    fn event(self, path: PathBuf) -> Option<FileTreeEvent> {
+        Some(match self {
+            Self::NewFolder => return None,
+            Self::Rename => FileTreeEvent::RenameRequested(path),
+            Self::Duplicate => FileTreeEvent::DuplicateRequested(path),
+            Self::SearchInFolder => FileTreeEvent::SearchInFolderRequested(path),
+            Self::Reveal => FileTreeEvent::RevealRequested(path),
+            Self::MoveToTrash => FileTreeEvent::RecyclebinDeleteRequested(vec![path]),
+            Self::MergeConflict => FileTreeEvent::MergeConflictRequested(path),
+            Self::ExportPlainText => FileTreeEvent::ExportPlainTextRequested(path),
+            Self::ConvertToUtf8 => FileTreeEvent::ConvertToUtf8Requested(path),
+            Self::OpenInSplit => FileTreeEvent::OpenInSplitRequested(path),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+struct TreeContextMenu {
+    path: PathBuf,
+    is_folder: bool,
+    is_root: bool,
+    position: Point<Pixels>,
+}
+
+struct NewFolderPrompt {
+    parent: PathBuf,
+    input: Entity<InputState>,
+    _subscription: Subscription,
+}
+
+/// The note or folder being dragged onto another folder.
+#[derive(Clone, Debug)]
+struct DraggedTreeEntry {
+    path: PathBuf,
+    label: SharedString,
+}
+
+impl Render for DraggedTreeEntry {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px_2()
+            .py_0p5()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().popover)
+            .text_sm()
+            .child(self.label.clone())
+    }
+}
+
+/// Whether dropping `from` onto `to_dir` would change anything: not its own folder, and a
+/// folder never into itself or below.
+pub(crate) fn is_tree_move_target(from: &Path, to_dir: &Path) -> bool {
+    !is_path_within(to_dir, from)
+        && from
+            .parent()
+            .is_none_or(|parent| !is_same_path(parent, to_dir))
+}
+
+/// Copies `path` next to itself as `name_2.txt` (or the next free suffix), never overwriting.
+/// The copy is written to a temp file and moved onto the free name, so a failed write leaves
+/// nothing behind; the vault's storage backend then picks it up. Runs on the workflow worker.
+pub(crate) fn duplicate_note_file(path: &Path) -> io::Result<PathBuf> {
+    let parent = path.parent().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "note path has no parent directory",
+        )
+    })?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "note path has no file name"))?;
+    let bytes = fs::read(path)?;
+    let mut suffix = 2usize;
+    loop {
+        let candidate =
+            crate::file_update_handler::relocated_daily_candidate_path(parent, &file_name, suffix);
+        match crate::atomic_io::write_atomic_with_replace(
+            candidate.as_path(),
+            &bytes,
+            crate::atomic_io::rename_no_clobber,
+        ) {
+            Ok(()) => {
+                crate::storage_backend::storage_backend_containing(candidate.as_path())
+                    .added(candidate.as_path());
+                return Ok(candidate);
+            }
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => suffix += 1,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum OldFolderMode {
+    Dim,
+    Collapse,
+}
+
+/// Year/month/day folders whose period ended more than `older_than_months` ago are dimmed, or
+/// collapsed into a single "older folders" toggle, until the user asks to show all.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct OldFolderPolicy {
+    pub older_than_months: u32,
+    pub mode: OldFolderMode,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct FileTreeConfigFile {
+    #[serde(default)]
+    file_tree: FileTreeConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct FileTreeConfigSection {
+    old_folders_after_months: Option<u32>,
+    old_folders: Option<String>,
+}
+
+/// `[file_tree] old_folders_after_months = 12` turns the policy on; `old_folders` is `dim`
+/// (default) or `collapse`.
+pub(crate) fn load_old_folder_policy(path: &Path) -> Option<OldFolderPolicy> {
+    let raw = fs::read_to_string(path).ok()?;
+    let parsed: FileTreeConfigFile = match toml::from_str(&raw) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            crate::log::trace_debug(format!(
+                "file_tree req-age1 config parse failed path={} error={error}",
+                path.display()
+            ));
+            return None;
+        }
+    };
+    let older_than_months = parsed
+        .file_tree
+        .old_folders_after_months
+        .filter(|months| *months > 0)?;
+    let mode = match parsed.file_tree.old_folders.as_deref().map(str::trim) {
+        None | Some("dim") => OldFolderMode::Dim,
+        Some("collapse") => OldFolderMode::Collapse,
+        Some(other) => {
+            crate::log::trace_debug(format!(
+                "file_tree req-age1 unknown old_folders value='{other}' fallback=dim"
+            ));
+            OldFolderMode::Dim
+        }
+    };
+    Some(OldFolderPolicy {
+        older_than_months,
+        mode,
+    })
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub(crate) enum ReqFtr23DailyDirPlan {
     RefreshOnly { ensure_error: String },
@@ -168,10 +420,36 @@ pub struct FileTreeView {
     delete_shortcut_armed: bool,
     selection_anchor_item_id: Option<String>,
     visible_item_ids: Vec<String>,
+    /// Kept with `visible_item_ids` so a render (every scroll frame) does not walk the tree;
+    /// the tree widget itself only lays out the rows in view.
+    visible_content_width_px: f32,
+    visible_rows_dirty: bool,
     font_size_logged_once: bool,
     req_ftr26_viewport_height_px: Option<f32>,
     req_ftr26_row_height_px: f32,
     ui_color_config: crate::app::UiColorConfig,
+    tag_counts: Vec<(String, usize)>,
+    tag_filter: Option<String>,
+    tag_filter_paths: HashSet<PathBuf>,
+    bookmarks: Vec<(PathBuf, u32)>,
+    note_counts: HashMap<String, usize>,
+    note_mtimes: Option<HashMap<PathBuf, SystemTime>>,
+    unread_paths: HashSet<PathBuf>,
+    unread_folder_ids: HashSet<String>,
+    open_file_path: Option<PathBuf>,
+    /// Notes open or acknowledged since the last metadata pass; their changes are not news.
+    looked_at_paths: HashSet<PathBuf>,
+    metadata_generation: u64,
+    old_folder_policy: Option<OldFolderPolicy>,
+    show_old_folders: bool,
+    old_item_ids: HashSet<String>,
+    collapsed_old_folder_count: usize,
+    context_menu: Option<TreeContextMenu>,
+    new_folder: Option<NewFolderPrompt>,
+    /// Folders read so far; the rest of the vault is listed when first expanded.
+    dir_listings: HashMap<PathBuf, Vec<DirListingEntry>>,
+    listing_dirs: HashSet<PathBuf>,
+    _tree_state_observer: Subscription,
 }
 
 impl EventEmitter<FileTreeEvent> for FileTreeView {}
@@ -184,6 +462,10 @@ impl FileTreeView {
         cx: &mut Context<Self>,
     ) -> Self {
         let tree_state = cx.new(|cx| TreeState::new(cx));
+        let tree_state_observer = cx.observe(&tree_state, |this, _, cx| {
+            this.visible_rows_dirty = true;
+            this.list_expanded_unlisted_folders(cx);
+        });
         let horizontal_scroll_handle = ScrollHandle::new();
         let focus_handle = cx.focus_handle().tab_stop(true);
 
@@ -199,10 +481,32 @@ impl FileTreeView {
             delete_shortcut_armed: false,
             selection_anchor_item_id: None,
             visible_item_ids: Vec::new(),
+            visible_content_width_px: 1.,
+            visible_rows_dirty: true,
             font_size_logged_once: false,
             req_ftr26_viewport_height_px: None,
             req_ftr26_row_height_px: req_ftr26_tree_row_height_px(f32::from(cx.theme().font_size)),
             ui_color_config,
+            tag_counts: Vec::new(),
+            tag_filter: None,
+            tag_filter_paths: HashSet::new(),
+            bookmarks: Vec::new(),
+            note_counts: HashMap::new(),
+            note_mtimes: None,
+            unread_paths: HashSet::new(),
+            unread_folder_ids: HashSet::new(),
+            open_file_path: None,
+            looked_at_paths: HashSet::new(),
+            metadata_generation: 0,
+            old_folder_policy: None,
+            show_old_folders: false,
+            old_item_ids: HashSet::new(),
+            collapsed_old_folder_count: 0,
+            context_menu: None,
+            new_folder: None,
+            dir_listings: HashMap::new(),
+            listing_dirs: HashSet::new(),
+            _tree_state_observer: tree_state_observer,
         };
         crate::log::trace_debug(format!(
             "file_tree init root_dir={}",
@@ -261,11 +565,305 @@ impl FileTreeView {
         self.load_files(cx);
     }
 
+    pub fn set_tree_root_dir(
+        &mut self,
+        tree_root_dir: PathBuf,
+        protected_delete_roots: Vec<PathBuf>,
+        cx: &mut Context<Self>,
+    ) {
+        crate::log::trace_debug(format!(
+            "file_tree root_dir changed from={} to={}",
+            self.tree_root_dir.display(),
+            tree_root_dir.display()
+        ));
+        self.tree_root_dir = tree_root_dir;
+        self.protected_delete_roots = protected_delete_roots;
+        self.selected_item_ids.clear();
+        self.selection_anchor_item_id = None;
+        self.delete_shortcut_armed = false;
+        self.note_mtimes = None;
+        self.unread_paths.clear();
+        self.dir_listings.clear();
+        self.load_files(cx);
+    }
+
+    pub fn set_old_folder_policy(
+        &mut self,
+        policy: Option<OldFolderPolicy>,
+        cx: &mut Context<Self>,
+    ) {
+        crate::log::trace_debug(format!("file_tree req-age1 policy={policy:?}"));
+        self.old_folder_policy = policy;
+        self.load_files(cx);
+        cx.notify();
+    }
+
+    /// Row text follows the color scheme picked while running.
+    pub fn set_ui_color_config(
+        &mut self,
+        ui_color_config: crate::app::UiColorConfig,
+        cx: &mut Context<Self>,
+    ) {
+        self.ui_color_config = ui_color_config;
+        cx.notify();
+    }
+
+    fn toggle_show_old_folders(&mut self, cx: &mut Context<Self>) {
+        self.show_old_folders = !self.show_old_folders;
+        crate::log::trace_debug(format!(
+            "file_tree req-age1 show_old_folders={}",
+            self.show_old_folders
+        ));
+        self.load_files(cx);
+        cx.notify();
+    }
+
+    fn render_old_folders_toggle(&self, cx: &mut Context<Self>) -> Option<Div> {
+        let policy = self.old_folder_policy?;
+        if self.old_item_ids.is_empty() && self.collapsed_old_folder_count == 0 {
+            return None;
+        }
+        let label = match (self.show_old_folders, policy.mode) {
+            (true, _) => "Showing all folders \u{b7} Recent only".to_string(),
+            (false, OldFolderMode::Dim) => "Older folders dimmed \u{b7} Show all".to_string(),
+            (false, OldFolderMode::Collapse) => format!(
+                "{} older folders collapsed \u{b7} Show all",
+                self.collapsed_old_folder_count
+            ),
+        };
+        Some(
+            div()
+                .w_full()
+                .px_2()
+                .py_0p5()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(
+                    div()
+                        .id("file-tree-old-folders-toggle")
+                        .cursor_pointer()
+                        .child(label)
+                        .on_click(cx.listener(|this, _, _, cx| this.toggle_show_old_folders(cx))),
+                ),
+        )
+    }
+
+    fn open_context_menu(
+        &mut self,
+        path: PathBuf,
+        is_folder: bool,
+        position: Point<Pixels>,
+        cx: &mut Context<Self>,
+    ) {
+        let is_root = is_same_path(path.as_path(), self.tree_root_dir.as_path());
+        crate::log::trace_debug(format!(
+            "file_tree context menu open path={} is_folder={is_folder} is_root={is_root}",
+            path.display()
+        ));
+        self.context_menu = Some(TreeContextMenu {
+            path,
+            is_folder,
+            is_root,
+            position,
+        });
+        cx.notify();
+    }
+
+    fn begin_new_folder(&mut self, parent: PathBuf, window: &mut Window, cx: &mut Context<Self>) {
+        let input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(crate::i18n::tr("placeholder.folder_name"))
+        });
+        let subscription = cx.subscribe_in(&input, window, |this, _, event: &InputEvent, _, cx| {
+            if matches!(event, InputEvent::PressEnter { .. }) {
+                this.confirm_new_folder(cx);
+            }
+        });
+        input.update(cx, |input, cx| input.focus(window, cx));
+        self.new_folder = Some(NewFolderPrompt {
+            parent,
+            input,
+            _subscription: subscription,
+        });
+        cx.notify();
+    }
+
+    fn confirm_new_folder(&mut self, cx: &mut Context<Self>) {
+        let Some(prompt) = self.new_folder.take() else {
+            return;
+        };
+        let name = prompt.input.read(cx).value().to_string();
+        cx.emit(FileTreeEvent::CreateFolderRequested {
+            parent: prompt.parent,
+            name,
+        });
+        cx.notify();
+    }
+
+    fn cancel_new_folder(&mut self, cx: &mut Context<Self>) {
+        if self.new_folder.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    fn render_new_folder_prompt(&self, cx: &mut Context<Self>) -> Option<Div> {
+        let prompt = self.new_folder.as_ref()?;
+        let parent_label = prompt
+            .parent
+            .strip_prefix(&self.tree_root_dir)
+            .ok()
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .map(|relative| relative.display().to_string())
+            .unwrap_or_else(|| "(notes folder)".to_string());
+        Some(
+            v_flex()
+                .w_full()
+                .gap_1()
+                .px_2()
+                .py_1()
+                .text_xs()
+                .child(
+                    div()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(format!("New folder in {parent_label}")),
+                )
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(div().flex_1().child(Input::new(&prompt.input)))
+                        .child(
+                            Button::new("file-tree-new-folder-create")
+                                .xsmall()
+                                .primary()
+                                .label(crate::i18n::tr("button.create"))
+                                .on_click(
+                                    cx.listener(|this, _, _, cx| this.confirm_new_folder(cx)),
+                                ),
+                        )
+                        .child(
+                            Button::new("file-tree-new-folder-cancel")
+                                .xsmall()
+                                .ghost()
+                                .label(crate::i18n::tr("button.cancel"))
+                                .on_click(cx.listener(|this, _, _, cx| this.cancel_new_folder(cx))),
+                        ),
+                ),
+        )
+    }
+
+    /// The vault root, the app data folder and anything that holds one of them stay put.
+    pub(crate) fn is_move_protected(&self, path: &Path) -> bool {
+        self.protected_delete_roots
+            .iter()
+            .any(|protected| is_path_within(protected, path))
+    }
+
+    fn request_move(&mut self, from: PathBuf, to_dir: PathBuf, cx: &mut Context<Self>) {
+        if !is_tree_move_target(from.as_path(), to_dir.as_path()) {
+            return;
+        }
+        crate::log::trace_debug(format!(
+            "file_tree drop move from={} to_dir={}",
+            from.display(),
+            to_dir.display()
+        ));
+        cx.emit(FileTreeEvent::MoveRequested { from, to_dir });
+    }
+
+    fn close_context_menu(&mut self, cx: &mut Context<Self>) {
+        if self.context_menu.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    fn render_context_menu(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let menu = self.context_menu.as_ref()?;
+        let mut items = v_flex()
+            .id("file-tree-context-menu")
+            .min_w(px(180.))
+            .py_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().popover)
+            .shadow_md()
+            .text_sm()
+            .on_mouse_down_out(
+                cx.listener(|this, _: &MouseDownEvent, _, cx| this.close_context_menu(cx)),
+            );
+        let actions = if menu.is_root {
+            TreeContextAction::for_root()
+        } else if !menu.is_folder
+            && crate::sync_conflicts::conflict_original(menu.path.as_path()).is_some()
+        {
+            TreeContextAction::for_conflict_row()
+        } else {
+            TreeContextAction::for_row(menu.is_folder)
+        };
+        for (index, action) in actions.iter().enumerate() {
+            let action = *action;
+            let path = menu.path.clone();
+            items = items.child(
+                div()
+                    .id(("file-tree-context-item", index))
+                    .px_3()
+                    .py_0p5()
+                    .cursor_pointer()
+                    .hover(|item| {
+                        item.bg(cx.theme().accent)
+                            .text_color(cx.theme().accent_foreground)
+                    })
+                    .when(action == TreeContextAction::MoveToTrash, |item| {
+                        item.text_color(cx.theme().danger)
+                    })
+                    .child(action.label())
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.close_context_menu(cx);
+                        match action.event(path.clone()) {
+                            Some(event) => cx.emit(event),
+                            None => this.begin_new_folder(path.clone(), window, cx),
+                        }
+                    })),
+            );
+        }
+        Some(deferred(
+            anchored()
+                .position(menu.position)
+                .snap_to_window()
+                .child(items),
+        ))
+    }
+
+    /// The note open in the editor counts as looked at, and so does the one just left until
+    /// the next metadata pass has taken its last save.
+    pub fn set_open_file_path(&mut self, path: Option<PathBuf>, cx: &mut Context<Self>) {
+        if let Some(path) = path.as_ref() {
+            self.mark_notes_looked_at(std::slice::from_ref(path), cx);
+        }
+        self.open_file_path = path;
+    }
+
+    /// Takes the current on-disk state of `paths` as seen, e.g. after the app rewrote them.
+    pub fn mark_notes_looked_at(&mut self, paths: &[PathBuf], cx: &mut Context<Self>) {
+        let mut changed = false;
+        for path in paths {
+            changed |= self.unread_paths.remove(path);
+            self.looked_at_paths.insert(path.clone());
+        }
+        if changed {
+            self.unread_folder_ids =
+                unread_folder_ids(&self.unread_paths, self.tree_root_dir.as_path());
+            cx.notify();
+        }
+    }
+
     pub fn apply_req_ftr18_startup_daily_folder_position(
         &mut self,
         daily_dir: &Path,
         cx: &mut Context<Self>,
     ) -> Option<(usize, usize)> {
+        if self.list_folder_chain(daily_dir) {
+            self.rebuild_items(cx);
+        }
         let removed_padding_rows = req_ftr18_strip_scroll_padding_items(&mut self.root_items);
         let Some((expanded_count, target_index, real_last_index)) =
             req_ftr18_expand_and_resolve_top_index(
@@ -336,6 +934,12 @@ impl FileTreeView {
     }
 
     pub fn restore_selection_for_path(&mut self, path: &Path, cx: &mut Context<Self>) -> bool {
+        if path
+            .parent()
+            .is_some_and(|parent| self.list_folder_chain(parent))
+        {
+            self.rebuild_items(cx);
+        }
         let item_id = path.to_string_lossy().to_string();
         self.rebuild_visible_item_ids();
         let Some(selected_index) = find_visible_index(&self.visible_item_ids, item_id.as_str())
@@ -444,11 +1048,238 @@ impl FileTreeView {
         ));
     }
 
+    pub fn set_tag_counts(&mut self, tag_counts: Vec<(String, usize)>, cx: &mut Context<Self>) {
+        let filter_tag_gone = self
+            .tag_filter
+            .as_ref()
+            .is_some_and(|tag| !tag_counts.iter().any(|(name, _)| name == tag));
+        self.tag_counts = tag_counts;
+        if filter_tag_gone {
+            crate::log::trace_debug("file_tree req-tag1 filter cleared reason=tag-removed");
+            self.apply_tag_filter(None, Vec::new(), cx);
+        } else {
+            cx.notify();
+        }
+    }
+
+    pub fn apply_tag_filter(
+        &mut self,
+        tag: Option<String>,
+        paths: Vec<PathBuf>,
+        cx: &mut Context<Self>,
+    ) {
+        crate::log::trace_debug(format!(
+            "file_tree req-tag1 filter tag={} path_count={}",
+            tag.as_deref().unwrap_or("<none>"),
+            paths.len()
+        ));
+        self.tag_filter = tag;
+        self.tag_filter_paths = paths
+            .iter()
+            .map(|path| comparable_path(path.as_path()))
+            .collect();
+        self.list_folder_chains_for(&paths);
+        self.load_files(cx);
+        cx.notify();
+    }
+
+    pub fn tag_filter(&self) -> Option<String> {
+        self.tag_filter.clone()
+    }
+
+    // Watcher refreshes can change which notes carry the active tag; reload only on change.
+    pub fn refresh_tag_filter_paths(&mut self, paths: Vec<PathBuf>, cx: &mut Context<Self>) {
+        let comparable = paths
+            .iter()
+            .map(|path| comparable_path(path.as_path()))
+            .collect::<HashSet<_>>();
+        if self.tag_filter.is_none() || comparable == self.tag_filter_paths {
+            return;
+        }
+        self.tag_filter_paths = comparable;
+        self.list_folder_chains_for(&paths);
+        self.load_files(cx);
+        cx.notify();
+    }
+
+    fn on_tag_click(&mut self, tag: &str, cx: &mut Context<Self>) {
+        let next = if self.tag_filter.as_deref() == Some(tag) {
+            None
+        } else {
+            Some(tag.to_string())
+        };
+        cx.emit(FileTreeEvent::TagFilterSelected(next));
+    }
+
+    fn render_tag_sidebar(&self, cx: &mut Context<Self>) -> Option<Div> {
+        if self.tag_counts.is_empty() {
+            return None;
+        }
+        let mut bar = h_flex().w_full().flex_wrap().gap_1().p_1().text_xs();
+        for (index, (tag, count)) in self.tag_counts.iter().enumerate() {
+            let is_active = self.tag_filter.as_deref() == Some(tag.as_str());
+            let tag_for_click = tag.clone();
+            let chip = div()
+                .id(("file-tree-tag", index))
+                .px_1()
+                .rounded_sm()
+                .cursor_pointer()
+                .when(is_active, |chip| {
+                    chip.bg(cx.theme().accent)
+                        .text_color(cx.theme().accent_foreground)
+                })
+                .child(format!("#{tag} ({count})"))
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.on_tag_click(tag_for_click.as_str(), cx);
+                }));
+            bar = bar.child(chip);
+        }
+        Some(bar)
+    }
+
+    pub fn set_bookmarks(&mut self, bookmarks: Vec<(PathBuf, u32)>, cx: &mut Context<Self>) {
+        if bookmarks == self.bookmarks {
+            return;
+        }
+        self.bookmarks = bookmarks;
+        cx.notify();
+    }
+
+    fn render_bookmarks_panel(&self, cx: &mut Context<Self>) -> Option<Div> {
+        if self.bookmarks.is_empty() {
+            return None;
+        }
+        let mut panel = v_flex()
+            .w_full()
+            .p_1()
+            .text_xs()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child("Bookmarks");
+        for (index, (path, line)) in self.bookmarks.iter().enumerate() {
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let path_for_click = path.clone();
+            let line = *line;
+            panel = panel.child(
+                div()
+                    .id(("file-tree-bookmark", index))
+                    .px_1()
+                    .cursor_pointer()
+                    .whitespace_nowrap()
+                    .overflow_hidden()
+                    .child(format!("{file_name}:{}", line + 1))
+                    .on_click(cx.listener(move |_, _, _, cx| {
+                        cx.emit(FileTreeEvent::BookmarkSelected {
+                            path: path_for_click.clone(),
+                            line,
+                        });
+                    })),
+            );
+        }
+        Some(panel)
+    }
+
     fn load_files(&mut self, cx: &mut Context<Self>) {
+        self.dir_listings = refreshed_dir_listings(&self.tree_root_dir, &self.dir_listings);
+        let today = Local::now().date_naive();
+        for dir in eagerly_listed_dirs(&self.tree_root_dir, today, self.old_folder_policy) {
+            self.list_folder_chain(&dir);
+        }
+        self.rebuild_items(cx);
+        self.refresh_note_metadata(cx);
+    }
+
+    // Lists `dir` and the folders between it and the tree root right away, for callers that
+    // need a deep path in the tree now. Returns whether anything new was listed.
+    fn list_folder_chain(&mut self, dir: &Path) -> bool {
+        list_folder_chain(&self.tree_root_dir, dir, &mut self.dir_listings)
+    }
+
+    fn list_folder_chains_for(&mut self, paths: &[PathBuf]) {
+        for path in paths {
+            if let Some(parent) = path.parent() {
+                self.list_folder_chain(parent);
+            }
+        }
+    }
+
+    // TreeState flips `expanded` itself. A folder opened before it was listed is read on the
+    // background executor and its rows replace the placeholder when done.
+    fn list_expanded_unlisted_folders(&mut self, cx: &mut Context<Self>) {
+        let mut dirs = Vec::new();
+        collect_expanded_unlisted_dirs(&self.root_items, &mut dirs);
+        for dir in dirs {
+            if !self.listing_dirs.insert(dir.clone()) {
+                continue;
+            }
+            let listing = cx.background_executor().spawn({
+                let dir = dir.clone();
+                async move { list_directory(&dir) }
+            });
+            cx.spawn(async move |this, cx| {
+                let listing = listing.await;
+                let _ = this.update(cx, |this, cx| this.apply_folder_listing(dir, listing, cx));
+            })
+            .detach();
+        }
+    }
+
+    fn apply_folder_listing(
+        &mut self,
+        dir: PathBuf,
+        listing: io::Result<Vec<DirListingEntry>>,
+        cx: &mut Context<Self>,
+    ) {
+        self.listing_dirs.remove(&dir);
+        match listing {
+            Ok(entries) => {
+                crate::log::trace_debug(format!(
+                    "file_tree lazy listing dir={} entries={}",
+                    dir.display(),
+                    entries.len()
+                ));
+                self.dir_listings.insert(dir, entries);
+                self.rebuild_items(cx);
+            }
+            // Gone in the meantime; the watcher reload drops the row.
+            Err(error) => crate::log::trace_debug(format!(
+                "file_tree lazy listing failed dir={} error={error}",
+                dir.display()
+            )),
+        }
+    }
+
+    fn rebuild_items(&mut self, cx: &mut Context<Self>) {
         let previous_items = self.root_items.clone();
         let expanded_folder_item_ids = expanded_folder_item_ids(&previous_items);
 
-        let mut refreshed_items = build_file_items(&self.tree_root_dir, &self.tree_root_dir);
+        let mut refreshed_items =
+            file_items_from_listings(self.tree_root_dir.as_path(), &self.dir_listings);
+        if self.tag_filter.is_some() {
+            retain_tagged_items(&mut refreshed_items, &self.tag_filter_paths);
+        }
+        self.old_item_ids.clear();
+        self.collapsed_old_folder_count = 0;
+        if let Some(policy) = self.old_folder_policy {
+            let cutoff = old_folder_cutoff(Local::now().date_naive(), policy.older_than_months);
+            collect_old_item_ids(
+                &refreshed_items,
+                self.tree_root_dir.as_path(),
+                cutoff,
+                false,
+                &mut self.old_item_ids,
+            );
+            if self.show_old_folders {
+                self.old_item_ids.clear();
+            } else if policy.mode == OldFolderMode::Collapse {
+                self.collapsed_old_folder_count =
+                    retain_recent_items(&mut refreshed_items, &self.old_item_ids);
+                self.old_item_ids.clear();
+            }
+        }
         let mut directory_item_ids = HashSet::new();
         collect_directory_item_ids_from_tree(&refreshed_items, &mut directory_item_ids);
 
@@ -491,6 +1322,64 @@ impl FileTreeView {
         self.set_items_from_model(cx);
     }
 
+    // Runs on every (watcher-driven) load. The whole vault is walked on the background
+    // executor, folders not listed in the tree included, so counts and unread marks cover
+    // collapsed folders too. Notes that appeared or changed since the previous pass, other
+    // than ones looked at or written by the app itself, are marked unread. The first pass
+    // only takes the snapshot.
+    fn refresh_note_metadata(&mut self, cx: &mut Context<Self>) {
+        self.metadata_generation += 1;
+        let generation = self.metadata_generation;
+        let root = self.tree_root_dir.clone();
+        let metadata = cx.background_executor().spawn(async move {
+            let mut note_counts = HashMap::new();
+            let mut note_mtimes = HashMap::new();
+            collect_note_metadata(&root, &mut note_counts, &mut note_mtimes);
+            (note_counts, note_mtimes)
+        });
+        cx.spawn(async move |this, cx| {
+            let (note_counts, note_mtimes) = metadata.await;
+            let _ = this.update(cx, |this, cx| {
+                if this.metadata_generation == generation {
+                    this.apply_note_metadata(note_counts, note_mtimes, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn apply_note_metadata(
+        &mut self,
+        note_counts: HashMap<String, usize>,
+        note_mtimes: HashMap<PathBuf, SystemTime>,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(previous) = self.note_mtimes.as_ref() {
+            let looked_at_paths = &self.looked_at_paths;
+            apply_external_note_changes(
+                previous,
+                &note_mtimes,
+                |path, modified| {
+                    looked_at_paths.contains(path)
+                        || crate::file_update_handler::is_self_written(path, modified)
+                },
+                &mut self.unread_paths,
+            );
+        }
+        self.looked_at_paths = self.open_file_path.iter().cloned().collect();
+        self.unread_folder_ids =
+            unread_folder_ids(&self.unread_paths, self.tree_root_dir.as_path());
+        crate::log::trace_debug(format!(
+            "file_tree note metadata notes={} folders={} unread={}",
+            note_mtimes.len(),
+            note_counts.len(),
+            self.unread_paths.len()
+        ));
+        self.note_counts = note_counts;
+        self.note_mtimes = Some(note_mtimes);
+        cx.notify();
+    }
+
     fn set_items_from_model(&mut self, cx: &mut Context<Self>) {
         let mut valid_item_ids = HashSet::new();
         collect_tree_item_ids(&self.root_items, &mut valid_item_ids);
@@ -512,7 +1401,7 @@ impl FileTreeView {
         self.rebuild_visible_item_ids();
     }
 
-    fn selected_paths(&self) -> Vec<PathBuf> {
+    pub fn selected_paths(&self) -> Vec<PathBuf> {
         req_ftr20_selected_paths_in_visible_order(&self.selected_item_ids, &self.visible_item_ids)
     }
 
@@ -531,7 +1420,9 @@ impl FileTreeView {
         let Some((_, item_id, is_folder)) = self.current_tree_selection_snapshot(cx) else {
             return;
         };
-        if is_req_ftr18_scroll_padding_item_id(item_id.as_str()) {
+        if is_req_ftr18_scroll_padding_item_id(item_id.as_str())
+            || is_lazy_folder_placeholder_id(item_id.as_str())
+        {
             return;
         }
 
@@ -618,7 +1509,9 @@ impl FileTreeView {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if is_req_ftr18_scroll_padding_item_id(item.id.as_ref()) {
+        if is_req_ftr18_scroll_padding_item_id(item.id.as_ref())
+            || is_lazy_folder_placeholder_id(item.id.as_ref())
+        {
             return;
         }
 
@@ -699,6 +1592,8 @@ impl FileTreeView {
     fn rebuild_visible_item_ids(&mut self) {
         self.visible_item_ids.clear();
         collect_visible_item_ids(&self.root_items, &mut self.visible_item_ids);
+        self.visible_content_width_px = req_ftr25_visible_content_width_px(&self.root_items);
+        self.visible_rows_dirty = false;
     }
 
     fn apply_single_selection_by_id(
@@ -770,7 +1665,10 @@ impl Render for FileTreeView {
         let background_rgb_hex = self.ui_color_config.background_rgb_hex;
         let foreground_rgb_hex = self.ui_color_config.foreground_rgb_hex;
         let req_ftr25_policy = req_ftr25_render_policy();
-        let req_ftr25_content_width_px = req_ftr25_visible_content_width_px(&self.root_items);
+        if self.visible_rows_dirty {
+            self.rebuild_visible_item_ids();
+        }
+        let req_ftr25_content_width_px = self.visible_content_width_px;
         let horizontal_offset_x = self.horizontal_scroll_handle.offset().x;
         self.req_ftr26_row_height_px =
             req_ftr26_tree_row_height_px(f32::from(cx.theme().font_size));
@@ -799,7 +1697,6 @@ impl Render for FileTreeView {
             self.font_size_logged_once = true;
         }
 
-        self.rebuild_visible_item_ids();
         let file_tree_entity = cx.entity();
         let row_entity = file_tree_entity.clone();
 
@@ -820,6 +1717,15 @@ impl Render for FileTreeView {
                             ))
                             .child(" ");
                     }
+                    if is_lazy_folder_placeholder_id(item_id.as_str()) {
+                        return ListItem::new(ix)
+                            .w_full()
+                            .py_0p5()
+                            .px_2()
+                            .pl(px(16.) * entry.depth() + px(8.))
+                            .text_color(cx.theme().muted_foreground)
+                            .child(item.label.clone());
+                    }
 
                     let is_selected = this.selected_item_ids.contains(&item_id);
                     let is_folder = this.directory_item_ids.contains(&item_id) || entry.is_folder();
@@ -846,6 +1752,82 @@ impl Render for FileTreeView {
                             req_ftr25_policy,
                         ))
                     };
+                    let note_count = is_folder
+                        .then(|| this.note_counts.get(&item_id).copied())
+                        .flatten()
+                        .filter(|count| *count > 0);
+                    let is_unread = if is_folder {
+                        this.unread_folder_ids.contains(&item_id)
+                    } else {
+                        this.unread_paths.contains(Path::new(item_id.as_str()))
+                    };
+                    let is_old = this.old_item_ids.contains(&item_id);
+                    let is_conflict = !is_folder
+                        && crate::sync_conflicts::conflict_original(Path::new(item_id.as_str()))
+                            .is_some();
+                    let row_content = row_content
+                        .when(is_old, |row_content| row_content.opacity(0.45))
+                        .children(note_count.map(|count| {
+                            div()
+                                .flex_shrink_0()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(count.to_string())
+                        }))
+                        .when(is_unread, |row_content| {
+                            row_content.child(
+                                div()
+                                    .flex_shrink_0()
+                                    .size(px(6.))
+                                    .rounded_full()
+                                    .bg(cx.theme().accent),
+                            )
+                        })
+                        .when(is_conflict, |row_content| {
+                            row_content.child(
+                                div()
+                                    .flex_shrink_0()
+                                    .px_1()
+                                    .rounded_sm()
+                                    .text_xs()
+                                    .bg(cx.theme().danger)
+                                    .text_color(cx.theme().danger_foreground)
+                                    .child("conflict"),
+                            )
+                        });
+                    let row_path = PathBuf::from(item_id.as_str());
+                    let row_content = row_content
+                        .id(("file-tree-row", ix))
+                        .on_mouse_down(MouseButton::Right, {
+                            let row_path = row_path.clone();
+                            cx.listener(move |this, event: &MouseDownEvent, _, cx| {
+                                this.open_context_menu(
+                                    row_path.clone(),
+                                    is_folder,
+                                    event.position,
+                                    cx,
+                                );
+                                cx.stop_propagation();
+                            })
+                        })
+                        .on_drag(
+                            DraggedTreeEntry {
+                                path: row_path.clone(),
+                                label: item.label.clone(),
+                            },
+                            |entry, _, _, cx| cx.new(|_| entry.clone()),
+                        )
+                        .when(is_folder, |row_content| {
+                            row_content
+                                .drag_over::<DraggedTreeEntry>(|style, _, _, cx| {
+                                    style.bg(cx.theme().accent.opacity(0.3))
+                                })
+                                .on_drop(cx.listener(
+                                    move |this, entry: &DraggedTreeEntry, _, cx| {
+                                        this.request_move(entry.path.clone(), row_path.clone(), cx);
+                                    },
+                                ))
+                        });
                     let row_content = if req_ftr25_policy.row_flex_nowrap {
                         row_content.flex_nowrap()
                     } else {
@@ -886,12 +1868,23 @@ impl Render for FileTreeView {
         ))
         .p_1()
         .h_full()
-        .bg(crate::app::req_colr_rgb_hex_to_hsla(background_rgb_hex))
+        .bg(crate::window_overlay::panel_background(
+            background_rgb_hex,
+            cx,
+        ))
         .text_color(crate::app::req_colr_rgb_hex_to_hsla(foreground_rgb_hex));
 
         let viewport_entity = file_tree_entity.clone();
+        let tag_sidebar = self.render_tag_sidebar(cx);
+        let bookmarks_panel = self.render_bookmarks_panel(cx);
+        let old_folders_toggle = self.render_old_folders_toggle(cx);
+        let context_menu = self.render_context_menu(cx);
+        let new_folder_prompt = self.render_new_folder_prompt(cx);
+        let tree_root_dir = self.tree_root_dir.clone();
         let tree_view = div()
-            .size_full()
+            .w_full()
+            .flex_1()
+            .min_h_0()
             .on_children_prepainted(move |children_bounds, _window, cx| {
                 let viewport_height_px = children_bounds
                     .first()
@@ -910,16 +1903,38 @@ impl Render for FileTreeView {
                     });
                 }
             })
+            .on_mouse_down(MouseButton::Right, {
+                let tree_root_dir = tree_root_dir.clone();
+                cx.listener(move |this, event: &MouseDownEvent, _, cx| {
+                    this.open_context_menu(tree_root_dir.clone(), true, event.position, cx);
+                })
+            })
+            .drag_over::<DraggedTreeEntry>(|style, _, _, cx| {
+                style.bg(cx.theme().accent.opacity(0.1))
+            })
+            .on_drop(cx.listener(move |this, entry: &DraggedTreeEntry, _, cx| {
+                this.request_move(entry.path.clone(), tree_root_dir.clone(), cx);
+            }))
             .child(tree_content);
 
         div()
             .size_full()
             .relative()
-            .bg(crate::app::req_colr_rgb_hex_to_hsla(background_rgb_hex))
+            .flex()
+            .flex_col()
+            .bg(crate::window_overlay::panel_background(
+                background_rgb_hex,
+                cx,
+            ))
             .text_color(crate::app::req_colr_rgb_hex_to_hsla(foreground_rgb_hex))
             .track_focus(&self.focus_handle)
             .capture_key_down(cx.listener(Self::on_key_down))
+            .children(tag_sidebar)
+            .children(bookmarks_panel)
+            .children(old_folders_toggle)
+            .children(new_folder_prompt)
             .child(tree_view)
+            .children(context_menu)
             .when(req_ftr25_policy.horizontal_scrollbar_overlay, |this| {
                 this.child(
                     div()
@@ -938,40 +1953,308 @@ impl Render for FileTreeView {
     }
 }
 
-fn build_file_items(root: &PathBuf, path: &PathBuf) -> Vec<TreeItem> {
-    let mut items = Vec::new();
+const LAZY_FOLDER_PLACEHOLDER_ID_PREFIX: &str = "__lazy_folder_placeholder__";
 
-    if let Ok(entries) = std::fs::read_dir(path) {
-        for entry in entries.flatten() {
-            let path = entry.path();
+fn is_lazy_folder_placeholder_id(item_id: &str) -> bool {
+    item_id.starts_with(LAZY_FOLDER_PLACEHOLDER_ID_PREFIX)
+}
 
-            if path
-                .file_name()
-                .and_then(|name| name.to_str())
-                .is_some_and(|name| name == ".git")
-            {
-                continue;
-            }
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct DirListingEntry {
+    path: PathBuf,
+    is_dir: bool,
+    /// For folders: whether there is anything to expand.
+    has_entries: bool,
+}
 
-            let relative_path = path.strip_prefix(root).unwrap_or(&path);
-            let file_name = relative_path
+// One level of `dir`, without `.git`.
+fn list_directory(dir: &Path) -> io::Result<Vec<DirListingEntry>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if path.file_name().is_some_and(|name| name == ".git") {
+            continue;
+        }
+        let is_dir = path.is_dir();
+        let has_entries =
+            is_dir && fs::read_dir(&path).is_ok_and(|mut inner| inner.next().is_some());
+        entries.push(DirListingEntry {
+            path,
+            is_dir,
+            has_entries,
+        });
+    }
+    Ok(entries)
+}
+
+// Listed folders get their rows; an unlisted folder with something in it gets a single
+// placeholder row, so it still shows as expandable.
+fn file_items_from_listings(
+    dir: &Path,
+    listings: &HashMap<PathBuf, Vec<DirListingEntry>>,
+) -> Vec<TreeItem> {
+    let Some(entries) = listings.get(dir) else {
+        return Vec::new();
+    };
+    let mut items = entries
+        .iter()
+        .map(|entry| {
+            let id = entry.path.to_string_lossy().to_string();
+            let file_name = entry
+                .path
                 .file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or("Unknown")
                 .to_string();
-            let id = path.to_string_lossy().to_string();
-
-            if path.is_dir() {
-                let children = build_file_items(root, &path);
-                items.push(TreeItem::new(id, file_name).children(children));
+            if !entry.is_dir {
+                return TreeItem::new(id, file_name);
+            }
+            let children = if listings.contains_key(&entry.path) {
+                file_items_from_listings(&entry.path, listings)
+            } else if entry.has_entries {
+                vec![
+                    TreeItem::new(
+                        format!("{LAZY_FOLDER_PLACEHOLDER_ID_PREFIX}:{id}"),
+                        "Loading\u{2026}",
+                    )
+                    .disabled(true),
+                ]
             } else {
-                items.push(TreeItem::new(id, file_name));
+                Vec::new()
+            };
+            TreeItem::new(id, file_name).children(children)
+        })
+        .collect::<Vec<_>>();
+    sort_tree_items(&mut items);
+    items
+}
+
+// Expanded folders still showing their placeholder, outermost first.
+fn collect_expanded_unlisted_dirs(items: &[TreeItem], dirs: &mut Vec<PathBuf>) {
+    for item in items.iter().filter(|item| item.is_expanded()) {
+        if item
+            .children
+            .iter()
+            .any(|child| is_lazy_folder_placeholder_id(child.id.as_ref()))
+        {
+            dirs.push(PathBuf::from(item.id.to_string()));
+        } else {
+            collect_expanded_unlisted_dirs(&item.children, dirs);
+        }
+    }
+}
+
+// Folders with a placeholder row, i.e. not listed yet, whether expanded or not.
+fn collect_unlisted_dirs(items: &[TreeItem], dirs: &mut Vec<PathBuf>) {
+    for item in items {
+        if item
+            .children
+            .iter()
+            .any(|child| is_lazy_folder_placeholder_id(child.id.as_ref()))
+        {
+            dirs.push(PathBuf::from(item.id.to_string()));
+        } else {
+            collect_unlisted_dirs(&item.children, dirs);
+        }
+    }
+}
+
+// Every folder listed so far re-read, plus `root`; folders that are gone drop out.
+fn refreshed_dir_listings(
+    root: &Path,
+    listings: &HashMap<PathBuf, Vec<DirListingEntry>>,
+) -> HashMap<PathBuf, Vec<DirListingEntry>> {
+    let mut dirs = listings.keys().cloned().collect::<Vec<_>>();
+    if !listings.contains_key(root) {
+        dirs.push(root.to_path_buf());
+    }
+    dirs.into_iter()
+        .filter_map(|dir| {
+            let entries = list_directory(&dir).ok()?;
+            Some((dir, entries))
+        })
+        .collect()
+}
+
+// Lists `dir` and the folders between it and `root` that are not listed yet; folders that
+// do not exist are skipped. Returns whether anything new was listed.
+fn list_folder_chain(
+    root: &Path,
+    dir: &Path,
+    listings: &mut HashMap<PathBuf, Vec<DirListingEntry>>,
+) -> bool {
+    if !dir.starts_with(root) {
+        return false;
+    }
+    let mut listed = false;
+    for folder in dir.ancestors() {
+        let unlisted = !listings.contains_key(folder);
+        if let Some(entries) = unlisted.then(|| list_directory(folder).ok()).flatten() {
+            listings.insert(folder.to_path_buf(), entries);
+            listed = true;
+        }
+        if folder == root {
+            break;
+        }
+    }
+    listed
+}
+
+// Listed on every load even while collapsed: today's daily folder, so its first note opens
+// it (req-ftr19), and with an old-folder policy the month holding the cutoff, so every old
+// date folder is collapsed and counted, not only those inside folders opened so far.
+fn eagerly_listed_dirs(
+    root: &Path,
+    today: NaiveDate,
+    policy: Option<OldFolderPolicy>,
+) -> Vec<PathBuf> {
+    let month_dir = |date: NaiveDate| {
+        root.join(date.format("%Y").to_string())
+            .join(date.format("%m").to_string())
+    };
+    let mut dirs = vec![month_dir(today).join(today.format("%d").to_string())];
+    if let Some(policy) = policy {
+        dirs.push(month_dir(old_folder_cutoff(
+            today,
+            policy.older_than_months,
+        )));
+    }
+    dirs
+}
+
+// Returns the number of notes under `dir`, recording it per folder id on the way. Reads the
+// disk directly rather than the tree's listings; links are not followed.
+fn collect_note_metadata(
+    dir: &Path,
+    note_counts: &mut HashMap<String, usize>,
+    note_mtimes: &mut HashMap<PathBuf, SystemTime>,
+) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut total = 0;
+    for entry in entries.flatten() {
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        let path = entry.path();
+        if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            let count = collect_note_metadata(&path, note_counts, note_mtimes);
+            note_counts.insert(path.to_string_lossy().to_string(), count);
+            total += count;
+        } else if crate::file_update_handler::NoteFileExtension::from_path(&path).is_some() {
+            if let Ok(modified) = fs::metadata(&path).and_then(|metadata| metadata.modified()) {
+                note_mtimes.insert(path, modified);
             }
+            total += 1;
         }
     }
+    total
+}
 
-    sort_tree_items(&mut items);
-    items
+// `already_seen` covers notes the user looked at and the app's own writes.
+fn apply_external_note_changes(
+    previous: &HashMap<PathBuf, SystemTime>,
+    current: &HashMap<PathBuf, SystemTime>,
+    already_seen: impl Fn(&Path, SystemTime) -> bool,
+    unread_paths: &mut HashSet<PathBuf>,
+) {
+    unread_paths.retain(|path| current.contains_key(path));
+    for (path, modified) in current {
+        if previous.get(path) != Some(modified) && !already_seen(path, *modified) {
+            unread_paths.insert(path.clone());
+        }
+    }
+}
+
+// Folders between the tree root and an unread note, so a collapsed folder still shows it.
+fn unread_folder_ids(unread_paths: &HashSet<PathBuf>, root: &Path) -> HashSet<String> {
+    let mut folder_ids = HashSet::new();
+    for path in unread_paths {
+        for folder in path.ancestors().skip(1) {
+            if folder == root || !folder.starts_with(root) {
+                break;
+            }
+            folder_ids.insert(folder.to_string_lossy().to_string());
+        }
+    }
+    folder_ids
+}
+
+fn old_folder_cutoff(today: NaiveDate, older_than_months: u32) -> NaiveDate {
+    today
+        .checked_sub_months(Months::new(older_than_months))
+        .unwrap_or(NaiveDate::MIN)
+}
+
+// The last day covered by a `YYYY`, `YYYY/MM` or `YYYY/MM/DD` folder under the root.
+fn date_folder_period_end(root: &Path, path: &Path) -> Option<NaiveDate> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts = relative
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    let year = parts
+        .first()
+        .filter(|part| part.len() == 4)?
+        .parse::<i32>()
+        .ok()?;
+    match parts.as_slice() {
+        [_] => NaiveDate::from_ymd_opt(year, 12, 31),
+        [_, month] => {
+            let month = month.parse::<u32>().ok()?;
+            let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+            first.checked_add_months(Months::new(1))?.pred_opt()
+        }
+        [_, month, day] => NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?),
+        _ => None,
+    }
+}
+
+// Ids of date folders that ended before `cutoff`, plus everything inside them.
+fn collect_old_item_ids(
+    items: &[TreeItem],
+    root: &Path,
+    cutoff: NaiveDate,
+    inside_old: bool,
+    old_item_ids: &mut HashSet<String>,
+) {
+    for item in items {
+        let is_old = inside_old
+            || date_folder_period_end(root, Path::new(item.id.as_ref()))
+                .is_some_and(|end| end < cutoff);
+        if is_old {
+            old_item_ids.insert(item.id.to_string());
+        }
+        collect_old_item_ids(&item.children, root, cutoff, is_old, old_item_ids);
+    }
+}
+
+// Drops old items and returns how many outermost ones went.
+fn retain_recent_items(items: &mut Vec<TreeItem>, old_item_ids: &HashSet<String>) -> usize {
+    let mut removed = 0;
+    items.retain_mut(|item| {
+        if old_item_ids.contains(item.id.as_ref()) {
+            removed += 1;
+            return false;
+        }
+        removed += retain_recent_items(&mut item.children, old_item_ids);
+        true
+    });
+    removed
+}
+
+// Keeps files carrying the selected tag and the folders leading to them.
+fn retain_tagged_items(items: &mut Vec<TreeItem>, tagged_paths: &HashSet<PathBuf>) {
+    items.retain_mut(|item| {
+        let path = Path::new(item.id.as_ref());
+        if path.is_dir() {
+            retain_tagged_items(&mut item.children, tagged_paths);
+            !item.children.is_empty()
+        } else {
+            tagged_paths.contains(&comparable_path(path))
+        }
+    });
 }
 
 fn collect_directory_item_ids_from_tree(
@@ -1013,7 +2296,9 @@ fn retain_existing_selections(
 
 fn collect_visible_item_ids(items: &[TreeItem], ids: &mut Vec<String>) {
     for item in items {
-        if is_req_ftr18_scroll_padding_item_id(item.id.as_ref()) {
+        if is_req_ftr18_scroll_padding_item_id(item.id.as_ref())
+            || is_lazy_folder_placeholder_id(item.id.as_ref())
+        {
             continue;
         }
         ids.push(item.id.to_string());
@@ -1184,6 +2469,8 @@ fn req_ftr19_collect_file_counts_by_daily_dir(
     }
 }
 
+// A daily folder that was not listed before (it or a folder above it still showed its
+// placeholder) had files nobody counted, so its first listing is not a first file.
 fn req_ftr19_first_file_daily_dirs(
     previous_items: &[TreeItem],
     refreshed_items: &[TreeItem],
@@ -1191,6 +2478,12 @@ fn req_ftr19_first_file_daily_dirs(
 ) -> HashSet<String> {
     let mut previous_counts = std::collections::HashMap::new();
     req_ftr19_collect_file_counts_by_daily_dir(previous_items, tree_root_dir, &mut previous_counts);
+    let mut previously_unlisted_dirs = Vec::new();
+    collect_unlisted_dirs(previous_items, &mut previously_unlisted_dirs);
+    let previously_unlisted_dirs = previously_unlisted_dirs
+        .iter()
+        .map(|dir| comparable_path(dir))
+        .collect::<Vec<_>>();
 
     let mut refreshed_counts = std::collections::HashMap::new();
     req_ftr19_collect_file_counts_by_daily_dir(
@@ -1204,6 +2497,12 @@ fn req_ftr19_first_file_daily_dirs(
         if refreshed_count == 0 {
             continue;
         }
+        if previously_unlisted_dirs
+            .iter()
+            .any(|dir| Path::new(&daily_dir).starts_with(dir))
+        {
+            continue;
+        }
         let previous_count = previous_counts.get(&daily_dir).copied().unwrap_or(0);
         if previous_count == 0 {
             triggered_daily_dirs.insert(daily_dir);
@@ -1520,7 +2819,7 @@ pub(crate) fn move_entries_to_recyclebin(
         let Some(target) = recyclebin_target_path(source_path.as_path(), recyclebin_dir) else {
             continue;
         };
-        match fs::rename(source_path, &target) {
+        match crate::file_times::rename_preserving_times(source_path, &target) {
             Ok(()) => moved.push((source_path.clone(), target)),
             Err(error) => {
                 crate::log::trace_debug(format!(
@@ -1607,7 +2906,9 @@ pub(crate) fn delete_entries_for_file_tree(
         let Some(target) = recyclebin_target_path(source_path.as_path(), recyclebin_dir) else {
             continue;
         };
-        match fs::rename(source_path, &target) {
+        match crate::storage_backend::storage_backend_containing(source_path)
+            .rename(source_path, &target)
+        {
             Ok(()) => {
                 crate::log::trace_debug(format!(
                     "file_tree recyclebin move success source={} target={}",
@@ -1745,6 +3046,17 @@ fn req_ftr17_deleted_paths_contain_current_edit(
         .any(|source| is_same_path(source.as_path(), current_edit_path))
 }
 
+/// What to do once a note opened through [`crate::app::Papyru2App::open_file_then`] is in
+/// the editor.
+pub(crate) type AfterNoteOpen =
+    Box<dyn FnOnce(&mut crate::app::Papyru2App, &mut Window, &mut Context<crate::app::Papyru2App>)>;
+
+/// A note still being read in the background, and what was asked of it afterwards.
+pub(crate) struct PendingNoteOpen {
+    path: PathBuf,
+    then: AfterNoteOpen,
+}
+
 impl crate::app::Papyru2App {
     pub(crate) fn handle_file_tree_selection_changed(
         &mut self,
@@ -1752,7 +3064,6 @@ impl crate::app::Papyru2App {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        self.sync_singleline_from_file_tree_selection(path.as_path(), window, cx);
         crate::log::trace_debug(format!(
             "file_tree selection load_editor requested path={}",
             path.display()
@@ -1897,6 +3208,11 @@ impl crate::app::Papyru2App {
             current_edit_path.is_some(),
             restored_selection
         ));
+        self.sync_tag_sidebar(cx);
+        self.sync_split_editor_with_workflow(cx);
+        self.calendar
+            .update(cx, |calendar, cx| calendar.refresh(cx));
+        self.duplicate_title_gate.invalidate();
     }
 
     pub(crate) fn select_created_file_in_tree_after_new_file(
@@ -1923,7 +3239,7 @@ impl crate::app::Papyru2App {
     ) {
         crate::log::trace_debug("folder_refresh_button click received");
         let daily_dir_plan =
-            req_ftr23_daily_dir_plan(crate::file_update_handler::ensure_daily_directory(
+            req_ftr23_daily_dir_plan(crate::file_update_handler::ensure_note_directory(
                 self.app_paths.user_document_dir.as_path(),
                 chrono::Local::now(),
             ));
@@ -2025,11 +3341,46 @@ impl crate::app::Papyru2App {
         });
     }
 
+    // Entries already in the recycle bin are deleted for good, which is always confirmed.
     pub(crate) fn on_file_tree_delete_requested(
         &mut self,
         paths: Vec<PathBuf>,
         window: &mut Window,
         cx: &mut Context<Self>,
+    ) {
+        let recyclebin_dir = self.app_paths.recyclebin_dir.clone();
+        let purge_count = paths
+            .iter()
+            .filter(|path| is_path_within(path, recyclebin_dir.as_path()))
+            .count();
+        let (kind, message) = if purge_count > 0 {
+            (
+                crate::confirmations::ConfirmationKind::Purge,
+                format!("Permanently delete {purge_count} item(s) from the recycle bin?"),
+            )
+        } else {
+            (
+                crate::confirmations::ConfirmationKind::Trash,
+                format!("Move {} item(s) to the recycle bin?", paths.len()),
+            )
+        };
+        let policy = self.confirmations;
+        crate::confirmations::run_confirmed(
+            self,
+            policy,
+            kind,
+            &message,
+            window,
+            cx,
+            move |this, window, cx| this.apply_file_tree_delete(paths, window, cx),
+        );
+    }
+
+    fn apply_file_tree_delete(
+        &mut self,
+        paths: Vec<PathBuf>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) {
         crate::log::trace_debug(format!(
             "file_tree delete request selected_count={} recyclebin={}",
@@ -2037,6 +3388,27 @@ impl crate::app::Papyru2App {
             self.app_paths.recyclebin_dir.display()
         ));
 
+        // Entries leaving the recycle bin are gone for good; keep a snapshot of them first.
+        let purged = paths
+            .iter()
+            .filter(|path| is_path_within(path, self.app_paths.recyclebin_dir.as_path()))
+            .cloned()
+            .collect::<Vec<_>>();
+        if purged.is_empty() {
+            self.delete_file_tree_entries(paths, window, cx);
+        } else {
+            self.snapshot_then("purge", purged, window, cx, move |app, window, cx| {
+                app.delete_file_tree_entries(paths, window, cx)
+            });
+        }
+    }
+
+    fn delete_file_tree_entries(
+        &mut self,
+        paths: Vec<PathBuf>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         match delete_entries_for_file_tree(&paths, self.app_paths.recyclebin_dir.as_path()) {
             Ok(outcome) => {
                 crate::log::trace_debug(format!(
@@ -2143,12 +3515,28 @@ impl crate::app::Papyru2App {
         }
     }
 
+    /// Opens `path` in the editor. `false` means the note is not going to open, e.g. the
+    /// open note could not be saved first; a note that still needs the large-file
+    /// confirmation or a background read counts as opening.
     pub(crate) fn open_file(
         &mut self,
         path: PathBuf,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> bool {
+        self.open_file_then(path, window, cx, |_, _, _| {})
+    }
+
+    /// Like [`Self::open_file`], running `then` once the text is in the editor. Caret moves
+    /// belong there: a large note is still being read when `open_file` returns.
+    pub(crate) fn open_file_then(
+        &mut self,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        then: impl FnOnce(&mut Self, &mut Window, &mut Context<Self>) + 'static,
+    ) -> bool {
+        let then: AfterNoteOpen = Box::new(then);
         if !self.flush_editor_content_before_context_switch("req-aus8-open-file", cx) {
             crate::log::trace_debug(format!(
                 "open_file aborted path={} (pre-switch autosave failed)",
@@ -2157,35 +3545,372 @@ impl crate::app::Papyru2App {
             return false;
         }
 
-        let opened = self.editor.update(cx, {
-            let path = path.clone();
-            move |editor, cx| editor.open_file(path, window, cx)
+        let size_bytes = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+        let mode = crate::editor::editor_load_mode(size_bytes);
+        if mode == crate::editor::EditorLoadMode::ConfirmThenBackground {
+            let policy = self.confirmations;
+            if policy.mode(crate::confirmations::ConfirmationKind::LargeFileOpen)
+                == crate::confirmations::ConfirmationMode::Always
+            {
+                return self.open_file_with_mode(path, mode, then, window, cx);
+            }
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let message = format!(
+                "{name} is {} MB. Open it anyway?",
+                size_bytes / (1024 * 1024)
+            );
+            crate::confirmations::run_confirmed(
+                self,
+                policy,
+                crate::confirmations::ConfirmationKind::LargeFileOpen,
+                &message,
+                window,
+                cx,
+                move |this, window, cx| {
+                    // Something may have been typed while the prompt was up.
+                    if this.flush_editor_content_before_context_switch("req-lfo1-open-large", cx) {
+                        this.open_file_with_mode(path, mode, then, window, cx);
+                    }
+                },
+            );
+            return true;
+        }
+        self.open_file_with_mode(path, mode, then, window, cx)
+    }
+
+    /// Reloads the open note after it was rewritten on disk, leaving the caret where it was.
+    pub(crate) fn reopen_note_keeping_caret(
+        &mut self,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.editor.read(cx).snapshot(cx);
+        let _ = self.open_file_then(path, window, cx, move |this, window, cx| {
+            this.editor.update(cx, |editor, cx| {
+                editor.apply_cursor(snapshot.cursor_line, snapshot.cursor_char, window, cx);
+            });
         });
+    }
+
+    fn open_file_with_mode(
+        &mut self,
+        path: PathBuf,
+        mode: crate::editor::EditorLoadMode,
+        then: AfterNoteOpen,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        // Whatever an earlier open wanted to do afterwards no longer applies.
+        self.pending_note_open = None;
+        // One note is never open twice; the split hands it back to the main editor once its
+        // pending edit is on disk.
+        if self
+            .split_editor
+            .as_ref()
+            .is_some_and(|split| split.path == path)
+        {
+            self.close_split_editor_then(
+                move |this, window, cx| {
+                    this.open_file_with_mode(path, mode, then, window, cx);
+                },
+                window,
+                cx,
+            );
+            return true;
+        }
+        let opened = if mode == crate::editor::EditorLoadMode::Inline {
+            self.editor.update(cx, {
+                let path = path.clone();
+                move |editor, cx| editor.open_file(path, window, cx)
+            })
+        } else {
+            self.editor.update(cx, {
+                let path = path.clone();
+                move |editor, cx| editor.open_file_in_background(path, window, cx)
+            });
+            true
+        };
 
         if !opened {
             crate::log::trace_debug(format!("open_file failed path={}", path.display()));
+            let reason = self.editor.read(cx).load_error().map(str::to_string);
+            if let Some(reason) = reason {
+                self.show_cannot_open_toast(path.as_path(), reason.as_str(), cx);
+            }
             return false;
         }
 
         self.file_workflow.set_edit_from_open_file(path.clone());
-        self.sync_current_editing_path_to_components(Some(path), cx);
+        self.sync_singleline_from_file_tree_selection(path.as_path(), window, cx);
+        self.sync_current_editing_path_to_components(Some(path.clone()), cx);
+        if mode == crate::editor::EditorLoadMode::Inline {
+            then(self, window, cx);
+        } else {
+            self.pending_note_open = Some(PendingNoteOpen { path, then });
+        }
         true
     }
+
+    fn show_cannot_open_toast(&mut self, path: &Path, reason: &str, cx: &mut Context<Self>) {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.show_toast(
+            crate::toasts::ToastSeverity::Error,
+            crate::i18n::tr_with(
+                "error.cannot_open",
+                &[("name", name.as_str()), ("reason", reason.as_str())],
+            ),
+            cx,
+        );
+    }
+
+    /// The editor's answer to [`Self::open_file_then`] for a note read in the background.
+    pub(crate) fn finish_background_note_open(
+        &mut self,
+        path: PathBuf,
+        error: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let pending = self
+            .pending_note_open
+            .take_if(|pending| pending.path == path);
+        match error {
+            None => {
+                self.sync_markdown_preview(true, cx);
+                self.reset_external_change_tracking(cx);
+                if let Some(pending) = pending {
+                    (pending.then)(self, window, cx);
+                }
+            }
+            // The editor is empty, not the note: leave the note so nothing autosaves over it.
+            Some(error) => {
+                self.show_cannot_open_toast(path.as_path(), error.as_str(), cx);
+                if self.file_workflow.current_edit_path().as_ref() == Some(&path) {
+                    self.apply_req_ftr17_case3_reset_to_neutral(window, cx);
+                }
+            }
+        }
+    }
+
+    // Renaming goes through the title field: open the note, then hand focus to the title so
+    // typing renames it like any other edit.
+    pub(crate) fn rename_from_tree(
+        &mut self,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let already_open = self.file_workflow.current_edit_path().as_ref() == Some(&path);
+        if !already_open && !self.open_file(path, window, cx) {
+            return;
+        }
+        self.singleline
+            .update(cx, |singleline, cx| singleline.focus(window, cx));
+    }
+
+    pub(crate) fn duplicate_from_tree(
+        &mut self,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // The copy must include edits that are still waiting for autosave.
+        if !self.flush_editor_content_before_context_switch("file-tree-duplicate", cx) {
+            self.show_transient_notice(
+                crate::i18n::tr("notice.duplicate_unsaved_note").to_string(),
+                cx,
+            );
+            return;
+        }
+        let (done_tx, done_rx) = smol::channel::bounded(1);
+        let queued = self
+            .file_workflow
+            .duplicate_note_async(path.clone(), move |result| {
+                let _ = done_tx.try_send(result);
+            });
+        if let Err(error) = queued {
+            self.show_duplicate_failed(path.as_path(), &error, cx);
+            return;
+        }
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(result) = done_rx.recv().await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| match result {
+                Ok(copy) => {
+                    crate::log::trace_debug(format!(
+                        "file_tree duplicate source={} copy={}",
+                        path.display(),
+                        copy.display()
+                    ));
+                    app.apply_file_tree_watcher_refresh(cx);
+                    let _ = app.open_file(copy, window, cx);
+                }
+                Err(error) => app.show_duplicate_failed(path.as_path(), &error, cx),
+            });
+        })
+        .detach();
+    }
+
+    fn show_duplicate_failed(
+        &mut self,
+        path: &Path,
+        error: &crate::workflow_error::WorkflowError,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_transient_notice(
+            crate::i18n::tr_with(
+                "notice.cannot_duplicate",
+                &[
+                    ("path", path.display().to_string().as_str()),
+                    ("error", error.to_string().as_str()),
+                ],
+            ),
+            cx,
+        );
+    }
+
+    pub(crate) fn move_from_tree(
+        &mut self,
+        from: PathBuf,
+        to_dir: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.file_tree.read(cx).is_move_protected(from.as_path()) {
+            crate::log::trace_debug(format!(
+                "file_tree move refused protected from={}",
+                from.display()
+            ));
+            self.show_transient_notice(
+                crate::i18n::tr_with(
+                    "notice.cannot_move_protected",
+                    &[("path", from.display().to_string().as_str())],
+                ),
+                cx,
+            );
+            return;
+        }
+        let open_path = self.file_workflow.current_edit_path();
+        let moves_open_note = open_path
+            .as_ref()
+            .is_some_and(|open_path| is_path_within(open_path, from.as_path()));
+        // Edits waiting for autosave are written to the old path before it goes away.
+        if moves_open_note && !self.flush_editor_content_before_context_switch("file-tree-move", cx)
+        {
+            self.show_transient_notice(crate::i18n::tr("notice.move_unsaved_note").to_string(), cx);
+            return;
+        }
+        match self
+            .file_workflow
+            .move_entry_blocking(from.as_path(), to_dir.as_path())
+        {
+            Ok(moved) => {
+                crate::log::trace_debug(format!(
+                    "file_tree move from={} to={}",
+                    from.display(),
+                    moved.display()
+                ));
+                let edit_path = self.file_workflow.current_edit_path();
+                if moves_open_note
+                    && edit_path != open_path
+                    && let Some(edit_path) = edit_path
+                {
+                    // A name clash in the target folder gives the note a `_2` suffix.
+                    self.sync_singleline_from_file_tree_selection(edit_path.as_path(), window, cx);
+                    self.sync_current_editing_path_to_components(Some(edit_path), cx);
+                }
+                self.apply_file_tree_watcher_refresh(cx);
+                let mut moved_notes = Vec::new();
+                if moved.is_dir() {
+                    let _ = crate::tag_index::collect_note_paths(moved.as_path(), &mut moved_notes);
+                } else {
+                    moved_notes.push(moved.clone());
+                }
+                let moves = moved_notes
+                    .into_iter()
+                    .filter_map(|to| {
+                        let relative = to.strip_prefix(moved.as_path()).ok()?;
+                        let from = if relative.as_os_str().is_empty() {
+                            from.clone()
+                        } else {
+                            from.join(relative)
+                        };
+                        Some((from, to))
+                    })
+                    .collect::<Vec<_>>();
+                self.offer_link_rewrite(moves, window, cx);
+            }
+            Err(error) => {
+                self.show_transient_notice(
+                    error.notice(crate::i18n::tr_with(
+                        "error.cannot_move",
+                        &[("path", from.display().to_string().as_str())],
+                    )),
+                    cx,
+                );
+            }
+        }
+    }
+
+    pub(crate) fn create_folder_from_tree(
+        &mut self,
+        parent: PathBuf,
+        name: &str,
+        cx: &mut Context<Self>,
+    ) {
+        match self
+            .file_workflow
+            .create_dir_blocking(parent.as_path(), name)
+        {
+            Ok(folder) => {
+                crate::log::trace_debug(format!("file_tree new folder path={}", folder.display()));
+                self.apply_file_tree_watcher_refresh(cx);
+            }
+            Err(error) => {
+                self.show_transient_notice(
+                    error.notice(crate::i18n::tr_with(
+                        "error.cannot_create_folder",
+                        &[("path", parent.display().to_string().as_str())],
+                    )),
+                    cx,
+                );
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::{
+        OldFolderMode, OldFolderPolicy, collect_old_item_ids, load_old_folder_policy,
+        old_folder_cutoff, retain_recent_items,
+    };
     use super::{
         ReqFtr17PostDeleteDecision, ReqFtr23DailyDirPlan, TreeItem, apply_expanded_folder_item_ids,
-        build_file_items, collect_tree_item_ids, collect_visible_item_ids,
-        delete_entries_for_file_tree, expanded_folder_item_ids, find_visible_index,
-        is_delete_protected_path, move_entries_to_recyclebin, replace_single_selection,
+        collect_tree_item_ids, collect_visible_item_ids, delete_entries_for_file_tree,
+        expanded_folder_item_ids, find_visible_index, is_delete_protected_path,
+        move_entries_to_recyclebin, replace_single_selection,
         req_ftr17_post_delete_decision_from_filesystem,
         req_ftr17_post_delete_decision_from_remaining_files, req_ftr17_sort_key,
         req_ftr23_daily_dir_plan, retain_existing_selections, select_range_items,
         selected_row_highlight_color, should_restore_selection_after_watcher_refresh,
         toggle_item_selection, use_checkbox_selection_markers,
     };
+    use super::{TreeContextAction, duplicate_note_file, is_tree_move_target};
+    use super::{apply_external_note_changes, collect_note_metadata, unread_folder_ids};
+    use super::{
+        collect_expanded_unlisted_dirs, eagerly_listed_dirs, file_items_from_listings,
+        is_lazy_folder_placeholder_id, list_directory, list_folder_chain, refreshed_dir_listings,
+        req_ftr25_visible_content_width_px,
+    };
     use gpui::hsla;
     use std::{
         collections::HashSet,
@@ -2220,26 +3945,48 @@ mod tests {
         let _ = fs::remove_dir_all(path);
     }
 
+    // The rows the view shows after a load with `opened` folders listed, read the way the
+    // view reads them.
+    fn listed_items(root: &Path, opened: &[&Path]) -> Vec<TreeItem> {
+        let mut listings = refreshed_dir_listings(root, &std::collections::HashMap::new());
+        for dir in opened {
+            list_folder_chain(root, dir, &mut listings);
+        }
+        file_items_from_listings(root, &listings)
+    }
+
     #[test]
     fn ftr_test1_refresh_reflects_create_and_delete_filesystem_changes() {
         let root = new_temp_root("ftr_test1");
         let file_a = root.join("a.txt");
         let file_b = root.join("b.txt");
+        let folder = root.join("folder");
+        let file_c = folder.join("c.txt");
         fs::write(&file_a, "a").expect("seed a");
+        fs::create_dir_all(&folder).expect("seed folder");
+        fs::write(&file_c, "c").expect("seed c");
 
-        let initial_items = build_file_items(&root, &root);
+        let mut listings = refreshed_dir_listings(&root, &std::collections::HashMap::new());
+        list_folder_chain(&root, &folder, &mut listings);
+        let initial_items = file_items_from_listings(&root, &listings);
         let mut initial_ids = HashSet::new();
         collect_tree_item_ids(&initial_items, &mut initial_ids);
         assert!(initial_ids.contains(file_a.to_string_lossy().as_ref()));
+        assert!(initial_ids.contains(file_c.to_string_lossy().as_ref()));
 
         fs::remove_file(&file_a).expect("delete a");
         fs::write(&file_b, "b").expect("seed b");
+        fs::remove_dir_all(&folder).expect("delete folder");
 
-        let refreshed_items = build_file_items(&root, &root);
+        // A watcher reload re-reads the folders listed so far; the removed one drops out.
+        let listings = refreshed_dir_listings(&root, &listings);
+        assert!(!listings.contains_key(&folder));
+        let refreshed_items = file_items_from_listings(&root, &listings);
         let mut refreshed_ids = HashSet::new();
         collect_tree_item_ids(&refreshed_items, &mut refreshed_ids);
         assert!(!refreshed_ids.contains(file_a.to_string_lossy().as_ref()));
         assert!(refreshed_ids.contains(file_b.to_string_lossy().as_ref()));
+        assert!(!refreshed_ids.contains(file_c.to_string_lossy().as_ref()));
         remove_temp_root(root.as_path());
     }
 
@@ -2577,7 +4324,7 @@ mod tests {
             .expect("create date directory");
         fs::create_dir_all(user_document_dir.join("recyclebin")).expect("create recyclebin");
 
-        let items = build_file_items(&user_document_dir, &user_document_dir);
+        let items = listed_items(&user_document_dir, &[]);
         let mut ids = HashSet::new();
         collect_tree_item_ids(&items, &mut ids);
 
@@ -2602,7 +4349,7 @@ mod tests {
         fs::create_dir_all(user_document_dir.join("recyclebin")).expect("create recyclebin");
         fs::create_dir_all(user_document_dir.join("2025")).expect("create another year directory");
 
-        let items = build_file_items(&user_document_dir, &user_document_dir);
+        let items = listed_items(&user_document_dir, &[]);
         let top_labels: Vec<String> = items.iter().map(|item| item.label.to_string()).collect();
 
         assert_eq!(
@@ -3724,7 +5471,7 @@ mod tests {
         let empty_dir_id = empty_dir.to_string_lossy().to_string();
         let file_id = file_path.to_string_lossy().to_string();
 
-        let items = super::build_file_items(&root, &root);
+        let items = listed_items(&root, &[]);
         let mut directory_item_ids = HashSet::new();
         super::collect_directory_item_ids_from_tree(&items, &mut directory_item_ids);
 
@@ -3973,4 +5720,369 @@ mod tests {
         assert!(fallback_padding > measured_padding);
         assert_eq!(fallback_padding, 123);
     }
+
+    #[test]
+    fn ftr_test114_note_metadata_counts_notes_per_folder() {
+        let root = new_temp_root("ftr_test114");
+        let day = root.join("2026").join("10").join("16");
+        fs::create_dir_all(&day).expect("create day dir");
+        fs::write(day.join("a.txt"), "a").expect("write a");
+        fs::write(day.join("b.md"), "b").expect("write b");
+        fs::write(day.join("image.png"), "png").expect("write png");
+        fs::write(root.join("top.txt"), "top").expect("write top");
+
+        // Counted from the disk: none of these folders has been listed in the tree.
+        let mut note_counts = std::collections::HashMap::new();
+        let mut note_mtimes = std::collections::HashMap::new();
+        let total = collect_note_metadata(&root, &mut note_counts, &mut note_mtimes);
+
+        assert_eq!(total, 3);
+        assert_eq!(note_mtimes.len(), 3);
+        assert_eq!(
+            note_counts.get(&day.to_string_lossy().to_string()),
+            Some(&2)
+        );
+        assert_eq!(
+            note_counts.get(&root.join("2026").to_string_lossy().to_string()),
+            Some(&2)
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn ftr_test115_external_changes_mark_notes_and_their_folders_unread() {
+        let root = PathBuf::from("C:/tmp/vault");
+        let open = root.join("2026/10/16/open.txt");
+        let synced = root.join("2026/10/16/synced.txt");
+        let added = root.join("inbox/added.md");
+        let gone = root.join("gone.txt");
+        let before = UNIX_EPOCH;
+        let after = UNIX_EPOCH + std::time::Duration::from_secs(5);
+
+        let previous = [(open.clone(), before), (synced.clone(), before)]
+            .into_iter()
+            .collect();
+        let current = [
+            (open.clone(), after),
+            (synced.clone(), after),
+            (added.clone(), after),
+        ]
+        .into_iter()
+        .collect();
+        let mut unread = HashSet::from([gone]);
+        apply_external_note_changes(
+            &previous,
+            &current,
+            |path, _| path == open.as_path(),
+            &mut unread,
+        );
+
+        assert_eq!(unread, HashSet::from([synced.clone(), added.clone()]));
+        let folders = unread_folder_ids(&unread, root.as_path());
+        assert!(folders.contains(&root.join("2026").to_string_lossy().to_string()));
+        assert!(folders.contains(&root.join("inbox").to_string_lossy().to_string()));
+        assert!(!folders.contains(&root.to_string_lossy().to_string()));
+    }
+
+    #[test]
+    fn ftr_test116_old_date_folders_and_their_contents_are_marked() {
+        let root = PathBuf::from("C:/tmp/vault");
+        let id = |relative: &str| root.join(relative).to_string_lossy().to_string();
+        let mut items = vec![
+            TreeItem::new(id("2024"), "2024").children(vec![
+                TreeItem::new(id("2024/12"), "12")
+                    .children(vec![TreeItem::new(id("2024/12/a.txt"), "a.txt")]),
+            ]),
+            TreeItem::new(id("2025"), "2025").children(vec![
+                TreeItem::new(id("2025/09"), "09"),
+                TreeItem::new(id("2025/11"), "11")
+                    .children(vec![TreeItem::new(id("2025/11/30"), "30")]),
+            ]),
+            TreeItem::new(id("projects"), "projects"),
+        ];
+        let cutoff = old_folder_cutoff(chrono::NaiveDate::from_ymd_opt(2026, 10, 16).unwrap(), 12);
+        assert_eq!(
+            cutoff,
+            chrono::NaiveDate::from_ymd_opt(2025, 10, 16).unwrap()
+        );
+
+        let mut old_item_ids = HashSet::new();
+        collect_old_item_ids(&items, root.as_path(), cutoff, false, &mut old_item_ids);
+        assert_eq!(
+            old_item_ids,
+            HashSet::from([
+                id("2024"),
+                id("2024/12"),
+                id("2024/12/a.txt"),
+                id("2025/09"),
+            ])
+        );
+
+        assert_eq!(retain_recent_items(&mut items, &old_item_ids), 2);
+        let mut remaining = HashSet::new();
+        collect_tree_item_ids(&items, &mut remaining);
+        assert_eq!(
+            remaining,
+            HashSet::from([id("2025"), id("2025/11"), id("2025/11/30"), id("projects")])
+        );
+    }
+
+    #[test]
+    fn ftr_test117_old_folder_policy_requires_an_age() {
+        let root = new_temp_root("ftr_test117");
+        let conf = root.join("papyru2_conf.toml");
+        fs::write(&conf, "[file_tree]\nold_folders = \"collapse\"\n").expect("write conf");
+        assert_eq!(load_old_folder_policy(conf.as_path()), None);
+
+        fs::write(
+            &conf,
+            "[file_tree]\nold_folders_after_months = 6\nold_folders = \"collapse\"\n",
+        )
+        .expect("write conf");
+        assert_eq!(
+            load_old_folder_policy(conf.as_path()),
+            Some(OldFolderPolicy {
+                older_than_months: 6,
+                mode: OldFolderMode::Collapse,
+            })
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn ftr_test118_context_menu_duplicate_never_overwrites() {
+        assert!(!TreeContextAction::for_row(true).contains(&TreeContextAction::Rename));
+        assert!(TreeContextAction::for_row(false).contains(&TreeContextAction::Duplicate));
+
+        let root = new_temp_root("ftr_test118");
+        let note = root.join("plan.md");
+        fs::write(&note, "draft").expect("write note");
+        fs::write(root.join("plan_2.md"), "other").expect("write taken name");
+
+        let copy = duplicate_note_file(note.as_path()).expect("duplicate");
+        assert_eq!(copy, root.join("plan_3.md"));
+        assert_eq!(fs::read_to_string(&copy).expect("read copy"), "draft");
+        assert_eq!(
+            fs::read_to_string(root.join("plan_2.md")).expect("read"),
+            "other"
+        );
+        // Only the three notes: no temp file or empty placeholder is left behind.
+        assert_eq!(fs::read_dir(&root).expect("list root").count(), 3);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn ftr_test119_drop_targets_skip_own_folder_and_descendants() {
+        assert!(TreeContextAction::for_row(true).contains(&TreeContextAction::NewFolder));
+        assert!(!TreeContextAction::for_row(false).contains(&TreeContextAction::NewFolder));
+        assert!(!TreeContextAction::for_root().contains(&TreeContextAction::MoveToTrash));
+
+        let root = Path::new("/notes");
+        let projects = root.join("projects");
+        let note = projects.join("plan.md");
+        assert!(is_tree_move_target(note.as_path(), root));
+        assert!(!is_tree_move_target(note.as_path(), projects.as_path()));
+        assert!(is_tree_move_target(
+            projects.as_path(),
+            &root.join("archive")
+        ));
+        assert!(!is_tree_move_target(projects.as_path(), projects.as_path()));
+        assert!(!is_tree_move_target(
+            projects.as_path(),
+            &projects.join("2026")
+        ));
+        assert!(!is_tree_move_target(projects.as_path(), root));
+    }
+
+    #[test]
+    fn ftr_test120_unlisted_folders_show_a_placeholder_until_expanded_and_listed() {
+        let root = new_temp_root("ftr_test120");
+        let day = root.join("2026").join("10").join("16");
+        fs::create_dir_all(&day).expect("create day dir");
+        fs::create_dir_all(root.join("empty")).expect("create empty dir");
+        fs::write(day.join("a.txt"), "a").expect("write a");
+        fs::write(root.join("top.txt"), "top").expect("write top");
+
+        let mut listings = std::collections::HashMap::new();
+        listings.insert(root.clone(), list_directory(&root).expect("list root"));
+        let items = file_items_from_listings(&root, &listings);
+        let year = items
+            .iter()
+            .find(|item| item.id.to_string() == root.join("2026").to_string_lossy())
+            .expect("year row");
+        assert_eq!(year.children.len(), 1);
+        assert!(is_lazy_folder_placeholder_id(year.children[0].id.as_ref()));
+        let empty = items
+            .iter()
+            .find(|item| item.id.to_string() == root.join("empty").to_string_lossy())
+            .expect("empty row");
+        assert!(empty.children.is_empty());
+
+        let mut dirs = Vec::new();
+        collect_expanded_unlisted_dirs(&items, &mut dirs);
+        assert!(dirs.is_empty());
+        let expanded = items
+            .iter()
+            .map(|item| {
+                if item.id == year.id {
+                    item.clone().expanded(true)
+                } else {
+                    item.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        collect_expanded_unlisted_dirs(&expanded, &mut dirs);
+        assert_eq!(dirs, vec![root.join("2026")]);
+
+        let year_dir = root.join("2026");
+        listings.insert(
+            year_dir.clone(),
+            list_directory(&year_dir).expect("list year"),
+        );
+        let items = file_items_from_listings(&root, &listings);
+        let year = items
+            .iter()
+            .find(|item| item.id.to_string() == year_dir.to_string_lossy())
+            .expect("year row");
+        assert_eq!(
+            year.children[0].id.to_string(),
+            root.join("2026").join("10").to_string_lossy()
+        );
+        assert!(is_lazy_folder_placeholder_id(
+            year.children[0].children[0].id.as_ref()
+        ));
+        remove_temp_root(&root);
+    }
+
+    #[test]
+    fn ftr_test122_auto_open_and_old_folder_collapse_see_folders_through_the_lazy_listing() {
+        let root = new_temp_root("ftr_test122");
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let yesterday = root.join("2026").join("10").join("15");
+        fs::create_dir_all(&yesterday).expect("create yesterday");
+        fs::write(yesterday.join("a.md"), "a").expect("write a");
+        let day_id = |day: &Path| super::comparable_path(day).to_string_lossy().to_string();
+
+        // Listing a folder only to reach a deep path is not a first file.
+        let startup_items = listed_items(&root, &[]);
+        let opened_items = listed_items(&root, &[yesterday.as_path()]);
+        assert!(
+            super::req_ftr19_first_file_daily_dirs(&startup_items, &opened_items, &root).is_empty()
+        );
+
+        // Today's folder is listed on every load, so its first note opens it even while the
+        // year row above it is collapsed.
+        let today_dir = root.join("2026").join("10").join("16");
+        let eager = eagerly_listed_dirs(&root, today, None);
+        assert_eq!(eager, vec![today_dir.clone()]);
+        let previous_items = listed_items(&root, &[eager[0].as_path()]);
+        fs::create_dir_all(&today_dir).expect("create today");
+        fs::write(today_dir.join("first.md"), "first").expect("write first");
+        let refreshed_items = listed_items(&root, &[eager[0].as_path()]);
+        assert_eq!(
+            super::req_ftr19_first_file_daily_dirs(&previous_items, &refreshed_items, &root),
+            HashSet::from([day_id(&today_dir)])
+        );
+
+        // Old days inside the month holding the cutoff are found without opening the month.
+        let policy = OldFolderPolicy {
+            older_than_months: 3,
+            mode: OldFolderMode::Collapse,
+        };
+        let day_dir = |year: &str, month: &str, day: &str| root.join(year).join(month).join(day);
+        for dir in [
+            day_dir("2025", "12", "31"),
+            day_dir("2026", "07", "01"),
+            day_dir("2026", "07", "20"),
+        ] {
+            fs::create_dir_all(&dir).expect("create day");
+            fs::write(dir.join("n.md"), "n").expect("write note");
+        }
+        let eager = eagerly_listed_dirs(&root, today, Some(policy));
+        assert_eq!(eager[1], root.join("2026").join("07"));
+        let opened = eager.iter().map(PathBuf::as_path).collect::<Vec<_>>();
+        let mut items = listed_items(&root, &opened);
+        let cutoff = old_folder_cutoff(today, policy.older_than_months);
+        let mut old_item_ids = HashSet::new();
+        collect_old_item_ids(&items, root.as_path(), cutoff, false, &mut old_item_ids);
+        assert!(old_item_ids.contains(&root.join("2025").to_string_lossy().to_string()));
+        assert!(old_item_ids.contains(&day_dir("2026", "07", "01").to_string_lossy().to_string()));
+        assert!(!old_item_ids.contains(&day_dir("2026", "07", "20").to_string_lossy().to_string()));
+        assert_eq!(retain_recent_items(&mut items, &old_item_ids), 2);
+        remove_temp_root(&root);
+    }
+
+    fn expand_all(items: &[TreeItem]) -> Vec<TreeItem> {
+        items
+            .iter()
+            .map(|item| {
+                let mut expanded = item.clone().expanded(true);
+                expanded.children = expand_all(&item.children);
+                expanded
+            })
+            .collect()
+    }
+
+    // Synthetic vault benchmark; run with
+    // `PAPYRU2_TREE_BENCH_NOTES=50000 cargo test ftr_test121 -- --ignored --nocapture`.
+    #[test]
+    #[ignore = "benchmark: writes a large synthetic vault"]
+    fn ftr_test121_large_vault_tree_benchmark() {
+        let note_count = std::env::var("PAPYRU2_TREE_BENCH_NOTES")
+            .ok()
+            .and_then(|value| value.parse::<usize>().ok())
+            .unwrap_or(20_000);
+        let root = new_temp_root("ftr_test121");
+        // Half the notes spread over daily folders, half in one crowded folder.
+        let crowded = root.join("inbox");
+        fs::create_dir_all(&crowded).expect("create crowded dir");
+        for index in 0..note_count {
+            let dir = if index % 2 == 0 {
+                crowded.clone()
+            } else {
+                let day = index / 2 % 365;
+                root.join("2026")
+                    .join(format!("{:02}", day / 31 + 1))
+                    .join(format!("{:02}", day % 31 + 1))
+            };
+            fs::create_dir_all(&dir).expect("create day dir");
+            fs::write(dir.join(format!("note {index}.md")), "x").expect("write note");
+        }
+
+        let started = std::time::Instant::now();
+        let mut listings = std::collections::HashMap::new();
+        listings.insert(root.clone(), list_directory(&root).expect("list root"));
+        let startup_items = file_items_from_listings(&root, &listings);
+        let lazy_startup = started.elapsed();
+
+        // Every folder opened one level at a time, as expanding them in the view would.
+        let started = std::time::Instant::now();
+        let all_items = loop {
+            let items = expand_all(&file_items_from_listings(&root, &listings));
+            let mut dirs = Vec::new();
+            collect_expanded_unlisted_dirs(&items, &mut dirs);
+            if dirs.is_empty() {
+                break items;
+            }
+            for dir in dirs {
+                let entries = list_directory(&dir).expect("list folder");
+                listings.insert(dir, entries);
+            }
+        };
+        let full_walk = started.elapsed();
+
+        let started = std::time::Instant::now();
+        let mut visible_ids = Vec::new();
+        collect_visible_item_ids(&all_items, &mut visible_ids);
+        let content_width = req_ftr25_visible_content_width_px(&all_items);
+        let visible_rows = started.elapsed();
+
+        eprintln!(
+            "ftr_test121 notes={note_count} lazy_startup={lazy_startup:?} full_walk={full_walk:?} visible_rows={visible_rows:?} rows={} width_px={content_width:.0}",
+            visible_ids.len()
+        );
+        assert_eq!(startup_items.len(), 2);
+        assert!(visible_ids.len() > note_count);
+        remove_temp_root(&root);
+    }
 }
@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeSet,
     io,
     path::{Path, PathBuf},
     sync::mpsc::{self, RecvTimeoutError},
@@ -25,8 +26,12 @@ impl Drop for FileTreeWatcher {
     }
 }
 
-pub fn start_file_tree_watcher(root_dir: PathBuf) -> io::Result<(FileTreeWatcher, Receiver<()>)> {
-    let (refresh_tx, refresh_rx) = channel::unbounded::<()>();
+/// Each refresh carries the paths touched since the previous one (deduplicated, under the
+/// root), so listeners can update incrementally.
+pub fn start_file_tree_watcher(
+    root_dir: PathBuf,
+) -> io::Result<(FileTreeWatcher, Receiver<Vec<PathBuf>>)> {
+    let (refresh_tx, refresh_rx) = channel::unbounded::<Vec<PathBuf>>();
     let (event_tx, event_rx) = mpsc::channel::<notify::Result<Event>>();
 
     let mut watcher = notify::recommended_watcher(move |result| {
@@ -60,9 +65,10 @@ pub fn start_file_tree_watcher(root_dir: PathBuf) -> io::Result<(FileTreeWatcher
 fn watcher_loop(
     root_dir: PathBuf,
     event_rx: mpsc::Receiver<notify::Result<Event>>,
-    refresh_tx: channel::Sender<()>,
+    refresh_tx: channel::Sender<Vec<PathBuf>>,
 ) {
     let mut pending_deadline: Option<Instant> = None;
+    let mut pending_paths = BTreeSet::<PathBuf>::new();
 
     loop {
         let wait_for_event = pending_deadline.map(|deadline| {
@@ -98,6 +104,13 @@ fn watcher_loop(
                             event.paths.len(),
                             first_path
                         ));
+                        pending_paths.extend(
+                            event
+                                .paths
+                                .iter()
+                                .filter(|path| path_is_watched(root_dir.as_path(), path))
+                                .cloned(),
+                        );
                         pending_deadline = Some(Instant::now() + FILE_TREE_WATCH_DEBOUNCE);
                     }
                 }
@@ -113,7 +126,8 @@ fn watcher_loop(
         }
 
         crate::log::trace_debug("file_tree watcher debounce flush");
-        if refresh_tx.send_blocking(()).is_err() {
+        let changed_paths = std::mem::take(&mut pending_paths).into_iter().collect();
+        if refresh_tx.send_blocking(changed_paths).is_err() {
             break;
         }
     }
@@ -129,7 +143,17 @@ pub(crate) fn should_schedule_refresh(root_dir: &Path, event: &Event) -> bool {
     event
         .paths
         .iter()
-        .any(|path| path_is_under_root(root_dir, path.as_path()))
+        .any(|path| path_is_watched(root_dir, path.as_path()))
+}
+
+// Git writes a burst of objects and index updates on every commit; none of them is a note.
+fn path_is_watched(root_dir: &Path, path: &Path) -> bool {
+    path_is_under_root(root_dir, path)
+        && !path
+            .strip_prefix(root_dir)
+            .unwrap_or(path)
+            .components()
+            .any(|component| component.as_os_str() == ".git")
 }
 
 fn event_kind_requires_refresh(kind: &EventKind) -> bool {
@@ -249,4 +273,17 @@ mod tests {
 
         assert!(should_schedule_refresh(root.as_path(), &metadata_modify));
     }
+
+    #[test]
+    fn ftr_test122_watcher_filter_ignores_git_internals() {
+        let root = PathBuf::from("C:/tmp/user_document");
+        let object = root.join(".git/objects/ab/cdef");
+        let note = root.join("2026/03/08/note.txt");
+
+        let commit = event(EventKind::Create(CreateKind::File), vec![object.clone()]);
+        let both = event(EventKind::Create(CreateKind::File), vec![object, note]);
+
+        assert!(!should_schedule_refresh(root.as_path(), &commit));
+        assert!(should_schedule_refresh(root.as_path(), &both));
+    }
 }
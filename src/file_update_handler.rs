@@ -1,10 +1,15 @@
 use std::{
-    collections::VecDeque,
-    fs, io,
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, VecDeque},
+    fmt, fs, io,
     path::{Path, PathBuf},
-    sync::{Arc, Condvar, Mutex, mpsc},
+    sync::{
+        Arc, Condvar, Mutex, OnceLock,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
     thread,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use chrono::{DateTime, Local};
@@ -12,8 +17,22 @@ use filetime::{FileTime, set_file_mtime};
 use gpui::{Context, Window};
 use serde::{Deserialize, Serialize};
 
+use crate::workflow_error::{WorkflowError, WorkflowResult, at_path};
+
 pub const MAX_FILE_STEM_CHARS: usize = 64;
 pub const CREATE_EVENT_MIN_INTERVAL: Duration = Duration::from_secs(1);
+/// How long a single event may keep the worker busy before the watchdog gives up on it.
+pub const WORKFLOW_EVENT_TIMEOUT: Duration = Duration::from_secs(20);
+/// Replace and link-rewrite batches touch many files and get more time.
+pub const WORKFLOW_BATCH_EVENT_TIMEOUT: Duration = Duration::from_secs(180);
+const WORKFLOW_WATCHDOG_TICK: Duration = Duration::from_millis(250);
+/// Attempts for an event whose file another program holds; waits 50, 100, 200 ms between.
+/// Events the dispatcher holds before `[workflow] queue_full` applies.
+pub const WORKFLOW_QUEUE_DEFAULT_CAPACITY: usize = 256;
+/// The longest `queue_full = "block"` holds a producer. Callers may hold the workflow lock
+/// the worker needs to finish, so waiting forever could deadlock.
+const WORKFLOW_QUEUE_BLOCK_LIMIT: Duration = Duration::from_secs(2);
+pub const NEW_FOLDER_DEFAULT_NAME: &str = "New folder";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SinglelineFileState {
@@ -33,6 +52,7 @@ pub struct CreateFileRequest {
     pub user_document_dir: PathBuf,
     pub singleline_value: String,
     pub now: DateTime<Local>,
+    pub extension: NoteFileExtension,
 }
 
 #[derive(Debug, Clone)]
@@ -41,6 +61,42 @@ pub struct RenameFileRequest {
     pub current_path: PathBuf,
     pub singleline_value: String,
     pub now: DateTime<Local>,
+    pub extension: NoteFileExtension,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NoteFileExtension {
+    #[default]
+    Txt,
+    Md,
+}
+
+impl NoteFileExtension {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Txt => "txt",
+            Self::Md => "md",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value
+            .trim()
+            .trim_start_matches('.')
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "txt" => Some(Self::Txt),
+            "md" => Some(Self::Md),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(Self::parse)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -53,6 +109,89 @@ pub struct EditorAutoSavePayload {
 #[derive(Debug, Clone)]
 pub struct AutoSaveFileRequest {
     pub payload: EditorAutoSavePayload,
+    pub previous_identity: Option<FileIdentity>,
+    // Set for a note that is no longer the one being edited: written in place, never
+    // relocated or re-created.
+    pub detached: bool,
+}
+
+/// Device/inode pair used to find the open note again after it was renamed outside the app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileIdentity {
+    device: u64,
+    inode: u64,
+}
+
+#[cfg(unix)]
+pub(crate) fn file_identity(path: &Path) -> Option<FileIdentity> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = fs::metadata(path).ok()?;
+    Some(FileIdentity {
+        device: metadata.dev(),
+        inode: metadata.ino(),
+    })
+}
+
+// Windows exposes file indexes only through unstable APIs; recovery there relies on the
+// previous file name.
+#[cfg(not(unix))]
+pub(crate) fn file_identity(_path: &Path) -> Option<FileIdentity> {
+    None
+}
+
+// Notes the file worker wrote, with the mtime each write left behind. The file tree checks
+// this before flagging a changed note, so the app's own saves never count as outside changes.
+static SELF_WRITTEN_NOTES: OnceLock<Mutex<HashMap<PathBuf, SystemTime>>> = OnceLock::new();
+
+fn self_written_notes() -> &'static Mutex<HashMap<PathBuf, SystemTime>> {
+    SELF_WRITTEN_NOTES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn record_self_written(paths: &[&Path]) {
+    let mut notes = self_written_notes()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    for path in paths {
+        if let Ok(modified) = fs::metadata(path).and_then(|metadata| metadata.modified()) {
+            notes.insert(path.to_path_buf(), modified);
+        }
+    }
+}
+
+// Runs on the worker right after the write, so it is recorded before the watcher reload.
+fn record_self_writes(result: &FileWorkflowEventResult) {
+    match result {
+        FileWorkflowEventResult::Created { path }
+        | FileWorkflowEventResult::Renamed { path }
+        | FileWorkflowEventResult::AutoSaved { path }
+        | FileWorkflowEventResult::RpcPinned { path, .. }
+        | FileWorkflowEventResult::Moved { path }
+        | FileWorkflowEventResult::ConflictMerged { original: path, .. } => {
+            record_self_written(&[path.as_path()])
+        }
+        FileWorkflowEventResult::BulkReplaced { report }
+        | FileWorkflowEventResult::LinksRewritten { report } => record_self_written(
+            &report
+                .replaced
+                .iter()
+                .map(|(path, _)| path.as_path())
+                .collect::<Vec<_>>(),
+        ),
+        FileWorkflowEventResult::DirCreated { .. }
+        | FileWorkflowEventResult::FolderImported { .. }
+        | FileWorkflowEventResult::NotesFileImported { .. }
+        | FileWorkflowEventResult::PlainTextExported { .. }
+        | FileWorkflowEventResult::Snapshotted => {}
+    }
+}
+
+/// Whether `path` still has the mtime the app's own last write left on it.
+pub(crate) fn is_self_written(path: &Path, modified: SystemTime) -> bool {
+    self_written_notes()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(path)
+        == Some(&modified)
 }
 
 #[derive(Debug, Clone)]
@@ -62,6 +201,140 @@ pub struct RpcPinFileRequest {
     pub linenum: u32,
 }
 
+#[derive(Debug, Clone)]
+pub struct BulkReplaceRequest {
+    pub user_document_dir: PathBuf,
+    pub paths: Vec<PathBuf>,
+    pub find: String,
+    pub replace: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateFromTemplateRequest {
+    pub create: CreateFileRequest,
+    pub template: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct PromoteScratchRequest {
+    pub create: CreateFileRequest,
+    pub content: String,
+}
+
+/// Moves a note or folder into `to_dir`, keeping its name (or the next free `_2` suffix).
+#[derive(Debug, Clone)]
+pub struct MoveEntryRequest {
+    pub from: PathBuf,
+    pub to_dir: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateDirRequest {
+    pub parent: PathBuf,
+    pub name: String,
+}
+
+/// Copies the note next to itself under the next free `_N` name.
+#[derive(Debug, Clone)]
+pub struct DuplicateNoteRequest {
+    pub source: PathBuf,
+}
+
+/// Points links in `paths` at the new location of each `(from, to)` note in `moves`. A moved
+/// note listed in `paths` by its new location also has its own relative links re-rooted.
+#[derive(Debug, Clone)]
+pub struct LinkRewriteRequest {
+    pub user_document_dir: PathBuf,
+    pub paths: Vec<PathBuf>,
+    pub moves: Vec<(PathBuf, PathBuf)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FolderImportMode {
+    Copy,
+    /// Falls back to a copy per file when the vault is on another drive.
+    HardLink,
+}
+
+/// Writes a cleaned copy of each of `notes` into `target_dir`; see
+/// [`crate::export::write_plain_text_export`].
+#[derive(Debug, Clone)]
+pub struct PlainTextExportRequest {
+    pub notes: Vec<PathBuf>,
+    pub target_dir: PathBuf,
+    pub profile: crate::export::PlainTextExportProfile,
+}
+
+/// Copies `paths` into a snapshot before the caller overwrites or deletes them. Queued like
+/// any other event, so the copy is done before writes dispatched after it.
+#[derive(Debug, Clone)]
+pub struct SnapshotRequest {
+    pub reason: String,
+    pub paths: Vec<PathBuf>,
+}
+
+/// Writes the resolved `text` over `original` and moves the conflicted copy to the
+/// recyclebin, after a snapshot of both.
+#[derive(Debug, Clone)]
+pub struct ConflictMergeRequest {
+    pub original: PathBuf,
+    pub conflict: PathBuf,
+    pub text: String,
+    pub recyclebin_dir: PathBuf,
+}
+
+/// Brings the .txt/.md files under `source_dir` into the vault's date folders.
+#[derive(Debug, Clone)]
+pub struct FolderImportRequest {
+    pub source_dir: PathBuf,
+    pub user_document_dir: PathBuf,
+    pub mode: FolderImportMode,
+}
+
+/// Converts an Evernote `.enex` export or an `.opml` outline into notes in the vault.
+#[derive(Debug, Clone)]
+pub struct NotesFileImportRequest {
+    pub source: PathBuf,
+    pub user_document_dir: PathBuf,
+    pub format: crate::notes_import::NotesImportFormat,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BulkReplaceReport {
+    pub replaced: Vec<(PathBuf, usize)>,
+    pub unchanged: usize,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl BulkReplaceReport {
+    pub fn total_replacements(&self) -> usize {
+        self.replaced.iter().map(|(_, count)| count).sum()
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = crate::i18n::tr_with(
+            "notice.replaced",
+            &[
+                ("count", self.total_replacements().to_string().as_str()),
+                ("files", self.replaced.len().to_string().as_str()),
+            ],
+        );
+        if self.unchanged > 0 {
+            summary.push_str(&crate::i18n::tr_with(
+                "notice.replaced_unchanged",
+                &[("count", self.unchanged.to_string().as_str())],
+            ));
+        }
+        if !self.failed.is_empty() {
+            summary.push_str(&crate::i18n::tr_with(
+                "notice.imported_failed",
+                &[("count", self.failed.len().to_string().as_str())],
+            ));
+        }
+        summary
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RpcPinFileResult {
     pub path: PathBuf,
@@ -71,23 +344,81 @@ pub struct RpcPinFileResult {
 
 pub const EDITOR_AUTOSAVE_IDLE_DURATION: Duration = Duration::from_secs(6);
 pub const EDITOR_AUTOSAVE_TICK_DURATION: Duration = Duration::from_millis(200);
+pub const EDITOR_AUTOSAVE_JOURNAL_FILE_NAME: &str = "autosave_journal.json";
+/// A journal that could not be parsed is moved here, next to the journal, for inspection.
+pub const EDITOR_AUTOSAVE_CORRUPT_JOURNAL_FILE_NAME: &str = "autosave_journal.corrupt.json";
+/// How long the journal may lag behind the pending edits; typing rewrites it at most this often.
+pub const EDITOR_AUTOSAVE_JOURNAL_DEBOUNCE: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct EditorAutoSaveJournalEntry {
+    payload: EditorAutoSavePayload,
+    journaled_at_unix_ms: u64,
+}
+
+// The journal lists every pending file; journals written before that held a single entry.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum EditorAutoSaveJournalFile {
+    Entries(Vec<EditorAutoSaveJournalEntry>),
+    Single(EditorAutoSaveJournalEntry),
+}
+
+#[derive(Debug)]
+struct PendingAutoSave {
+    payload: EditorAutoSavePayload,
+    pinned_time: Instant,
+    journaled_at_unix_ms: u64,
+    last_delta_trace_secs: Option<u64>,
+}
 
 #[derive(Debug, Default)]
 struct EditorAutoSaveState {
-    pinned_time: Option<Instant>,
-    pending_payload: Option<EditorAutoSavePayload>,
-    last_delta_trace_secs: Option<u64>,
+    // One idle timer per file, so an edit to one note never replaces another's pending text.
+    pending: BTreeMap<PathBuf, PendingAutoSave>,
+    paused: bool,
+    // The note open in the split pane keeps its pending edit across main-editor switches.
+    split_path: Option<PathBuf>,
+    // Set when `pending` changed after the journal was last written.
+    journal_dirty_since: Option<Instant>,
+    // Entries the startup replay could not apply (vault offline, note missing, write failed).
+    // They stay in the journal until the note is edited again or a later start applies them.
+    carried: BTreeMap<PathBuf, EditorAutoSaveJournalEntry>,
 }
 
 #[derive(Clone, Debug)]
 pub struct EditorAutoSaveCoordinator {
     inner: Arc<Mutex<EditorAutoSaveState>>,
+    journal_path: Option<PathBuf>,
 }
 
 impl EditorAutoSaveCoordinator {
+    #[cfg(test)]
     pub fn new() -> Self {
         Self {
             inner: Arc::new(Mutex::new(EditorAutoSaveState::default())),
+            journal_path: None,
+        }
+    }
+
+    // Pending payloads are mirrored to `journal_path` until the idle timer saves them,
+    // so a crash inside the idle window can be recovered by `replay_autosave_journal`.
+    // The autosave worker writes it (`flush_journal`); edits only mark it out of date.
+    // Whatever `replay_autosave_journal` left in the journal is carried over.
+    pub fn with_journal(journal_path: PathBuf) -> Self {
+        let carried = read_autosave_journal(journal_path.as_path())
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| (entry.payload.current_path.clone(), entry))
+            .collect();
+        Self {
+            inner: Arc::new(Mutex::new(EditorAutoSaveState {
+                carried,
+                ..EditorAutoSaveState::default()
+            })),
+            journal_path: Some(journal_path),
         }
     }
 
@@ -96,11 +427,31 @@ impl EditorAutoSaveCoordinator {
             .inner
             .lock()
             .unwrap_or_else(|poisoned| poisoned.into_inner());
-        if state.pinned_time.is_none() {
-            state.pinned_time = Some(now);
-            state.last_delta_trace_secs = None;
-        }
-        state.pending_payload = Some(payload);
+        let journaled_at_unix_ms = unix_millis(SystemTime::now());
+        state.carried.remove(&payload.current_path);
+        state
+            .pending
+            .entry(payload.current_path.clone())
+            .and_modify(|pending| {
+                pending.payload = payload.clone();
+                pending.journaled_at_unix_ms = journaled_at_unix_ms;
+            })
+            .or_insert_with(|| PendingAutoSave {
+                payload,
+                pinned_time: now,
+                journaled_at_unix_ms,
+                last_delta_trace_secs: None,
+            });
+        Self::mark_journal_dirty(&mut state);
+    }
+
+    /// While paused nothing is handed to the writer; pending edits stay in the map and the
+    /// journal and become due as usual once resumed.
+    pub fn set_paused(&self, paused: bool) {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .paused = paused;
     }
 
     pub fn on_edit_path_changed(&self, path: Option<PathBuf>) {
@@ -108,39 +459,119 @@ impl EditorAutoSaveCoordinator {
             .inner
             .lock()
             .unwrap_or_else(|poisoned| poisoned.into_inner());
-        match path {
-            Some(path) => {
-                if let Some(payload) = state.pending_payload.as_ref()
-                    && payload.current_path != path
-                {
-                    crate::log::trace_debug(format!(
-                        "autosave drop pending on path switch old={} new={}",
-                        payload.current_path.display(),
-                        path.display()
-                    ));
-                    state.pinned_time = None;
-                    state.pending_payload = None;
-                    state.last_delta_trace_secs = None;
-                }
-            }
-            None => {
-                state.pinned_time = None;
-                state.pending_payload = None;
-                state.last_delta_trace_secs = None;
+        let before = state.pending.len();
+        let split_path = state.split_path.clone();
+        state.pending.retain(|pending_path, _| {
+            let keep =
+                path.as_ref() == Some(pending_path) || split_path.as_ref() == Some(pending_path);
+            if !keep && let Some(path) = path.as_ref() {
+                crate::log::trace_debug(format!(
+                    "autosave drop pending on path switch old={} new={}",
+                    pending_path.display(),
+                    path.display()
+                ));
             }
+            keep
+        });
+        if state.pending.len() != before {
+            Self::mark_journal_dirty(&mut state);
         }
     }
 
+    pub fn set_split_path(&self, path: Option<PathBuf>) {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .split_path = path;
+    }
+
+    #[cfg(test)]
     pub fn reset_cycle(&self) {
         let mut state = self
             .inner
             .lock()
             .unwrap_or_else(|poisoned| poisoned.into_inner());
-        state.pinned_time = None;
-        state.pending_payload = None;
-        state.last_delta_trace_secs = None;
+        state.pending.clear();
+        Self::mark_journal_dirty(&mut state);
     }
 
+    /// Forgets the pending edit of `path` once its text was saved (or replaced) some other way.
+    pub fn clear_pending_for_path(&self, path: &Path) {
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if state.pending.remove(path).is_some() {
+            Self::mark_journal_dirty(&mut state);
+        }
+    }
+
+    // Called after a due payload was written; edits marked meanwhile keep their entries.
+    pub fn clear_journal_if_idle(&self) {
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        Self::mark_journal_dirty(&mut state);
+    }
+
+    fn mark_journal_dirty(state: &mut EditorAutoSaveState) {
+        state.journal_dirty_since.get_or_insert_with(Instant::now);
+    }
+
+    /// Rewrites the journal once it has been out of date for `debounce`, outside the lock so
+    /// the UI thread never waits on the disk. Only the autosave worker calls this.
+    pub fn flush_journal(&self, now: Instant, debounce: Duration) {
+        let Some(journal_path) = self.journal_path.as_ref() else {
+            return;
+        };
+        let entries = {
+            let mut state = self
+                .inner
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            match state.journal_dirty_since {
+                Some(since) if now.saturating_duration_since(since) >= debounce => {}
+                _ => return,
+            }
+            state.journal_dirty_since = None;
+            state
+                .carried
+                .values()
+                .cloned()
+                .chain(
+                    state
+                        .pending
+                        .values()
+                        .map(|pending| EditorAutoSaveJournalEntry {
+                            payload: pending.payload.clone(),
+                            journaled_at_unix_ms: pending.journaled_at_unix_ms,
+                        }),
+                )
+                .collect::<Vec<_>>()
+        };
+        if entries.is_empty() {
+            if let Err(error) = cleanup_editor_temp_file(journal_path.as_path()) {
+                crate::log::trace_debug(format!(
+                    "autosave journal clear failed path={} error={error}",
+                    journal_path.display()
+                ));
+            }
+            return;
+        }
+        let result = serde_json::to_vec(&entries)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))
+            .and_then(|bytes| crate::atomic_io::write_atomic(journal_path.as_path(), &bytes));
+        if let Err(error) = result {
+            crate::log::trace_debug(format!(
+                "autosave journal write failed path={} error={error}",
+                journal_path.display()
+            ));
+        }
+    }
+
+    /// The longest-waiting payload whose idle timer ran out. The worker calls this until it
+    /// returns None, so every due file is saved in the same tick.
     pub fn pop_due_payload(
         &self,
         now: Instant,
@@ -150,120 +581,607 @@ impl EditorAutoSaveCoordinator {
             .inner
             .lock()
             .unwrap_or_else(|poisoned| poisoned.into_inner());
-        let pinned_time = state.pinned_time?;
-        let delta = now.duration_since(pinned_time);
-        if state.pending_payload.is_some() {
+        if state.paused {
+            return None;
+        }
+        let mut due: Option<(Instant, PathBuf)> = None;
+        for (path, pending) in state.pending.iter_mut() {
+            let delta = now.saturating_duration_since(pending.pinned_time);
             let delta_secs = delta.as_secs();
-            if state.last_delta_trace_secs != Some(delta_secs) {
-                state.last_delta_trace_secs = Some(delta_secs);
+            if pending.last_delta_trace_secs != Some(delta_secs) {
+                pending.last_delta_trace_secs = Some(delta_secs);
                 crate::log::trace_debug(format!(
-                    "autosave step-3 delta_ms={} threshold_ms={} armed=true",
+                    "autosave step-3 path={} delta_ms={} threshold_ms={} armed=true",
+                    path.display(),
                     delta.as_millis(),
                     idle_duration.as_millis()
                 ));
             }
+            if delta >= idle_duration
+                && due
+                    .as_ref()
+                    .is_none_or(|(pinned_time, _)| pending.pinned_time < *pinned_time)
+            {
+                due = Some((pending.pinned_time, path.clone()));
+            }
         }
+        let (_, path) = due?;
+        state.pending.remove(&path).map(|pending| pending.payload)
+    }
 
-        if delta < idle_duration {
-            return None;
-        }
-
-        let payload = state.pending_payload.take();
-        state.pinned_time = None;
-        state.last_delta_trace_secs = None;
-        payload
+    /// Keeps a pending edit when the workflow moved the note it belongs to.
+    pub fn retarget_pending_payload(&self, old_path: &Path, new_path: &Path) {
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Some(mut pending) = state.pending.remove(old_path) else {
+            return;
+        };
+        pending.payload.current_path = new_path.to_path_buf();
+        state.pending.insert(new_path.to_path_buf(), pending);
+        Self::mark_journal_dirty(&mut state);
     }
 
     pub fn has_pending_payload_for_path(&self, path: &Path) -> bool {
         self.inner
             .lock()
             .unwrap_or_else(|poisoned| poisoned.into_inner())
-            .pending_payload
-            .as_ref()
-            .is_some_and(|payload| payload.current_path == path)
+            .pending
+            .contains_key(path)
     }
 
     #[cfg(test)]
     pub fn has_pending_payload(&self) -> bool {
-        self.inner
+        !self
+            .inner
             .lock()
             .unwrap_or_else(|poisoned| poisoned.into_inner())
-            .pending_payload
-            .is_some()
+            .pending
+            .is_empty()
+    }
+}
+
+/// Progress of the idle autosave, reported to the UI so failures are visible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditorAutoSaveEvent {
+    Saving {
+        path: PathBuf,
+    },
+    Saved {
+        path: PathBuf,
+    },
+    Failed {
+        path: PathBuf,
+        error: String,
+        remediation: Option<&'static str>,
+    },
+}
+
+/// Handle to the idle autosave thread, kept for app teardown.
+#[derive(Debug)]
+pub struct EditorAutoSaveWorker {
+    stopping: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl EditorAutoSaveWorker {
+    /// Saves a pending edit right away instead of waiting for the idle timer, then joins the
+    /// thread. A paused coordinator keeps its payload in the journal for the next start.
+    pub fn shutdown(&mut self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        let Some(thread) = self.thread.take() else {
+            return;
+        };
+        if thread.join().is_err() {
+            crate::log::trace_debug("autosave timer thread panicked before shutdown");
+        }
+        crate::log::trace_debug("autosave timer thread joined");
     }
 }
 
 pub fn spawn_editor_autosave_worker(
     autosave_coordinator: EditorAutoSaveCoordinator,
     autosave_workflow: SinglelineCreateFileWorkflow,
+    tag_index: Option<crate::tag_index::TagIndexStore>,
+) -> (
+    EditorAutoSaveWorker,
+    smol::channel::Receiver<EditorAutoSaveEvent>,
 ) {
-    thread::spawn(move || {
+    let (event_tx, event_rx) = smol::channel::unbounded();
+    let stopping = Arc::new(AtomicBool::new(false));
+    let worker_stopping = stopping.clone();
+    let thread = thread::spawn(move || {
         crate::log::trace_debug("autosave timer thread started");
         loop {
             thread::sleep(EDITOR_AUTOSAVE_TICK_DURATION);
-            let Some(payload) =
-                autosave_coordinator.pop_due_payload(Instant::now(), EDITOR_AUTOSAVE_IDLE_DURATION)
-            else {
-                continue;
+            let stopping = worker_stopping.load(Ordering::SeqCst);
+            let idle_duration = if stopping {
+                Duration::ZERO
+            } else {
+                EDITOR_AUTOSAVE_IDLE_DURATION
             };
-
-            let target = payload.current_path.display().to_string();
-            let editor_len = payload.editor_text.len();
-            crate::log::trace_debug(format!(
-                "autosave step-5 raise event path={} text_len={}",
-                target, editor_len
-            ));
-
-            match autosave_workflow.try_autosave_in_edit(payload) {
-                Ok(true) => {
-                    crate::log::trace_debug(format!(
-                        "autosave success path={} text_len={} (step-6 reset)",
-                        target, editor_len
-                    ));
-                }
-                Ok(false) => {
-                    crate::log::trace_debug(format!(
-                        "autosave critical skipped (state/path invalid) path={}",
-                        target
-                    ));
-                    debug_assert!(
-                        false,
-                        "autosave invariant violation: event raised while state/path invalid"
-                    );
-                }
-                Err(error) => {
-                    crate::log::trace_debug(format!(
-                        "autosave failure path={} error={error} (step-6 reset)",
-                        target
-                    ));
-                }
+            while let Some(payload) =
+                autosave_coordinator.pop_due_payload(Instant::now(), idle_duration)
+            {
+                run_due_autosave(
+                    payload,
+                    &autosave_coordinator,
+                    &autosave_workflow,
+                    tag_index.as_ref(),
+                    &event_tx,
+                );
+            }
+            autosave_coordinator.flush_journal(
+                Instant::now(),
+                if stopping {
+                    Duration::ZERO
+                } else {
+                    EDITOR_AUTOSAVE_JOURNAL_DEBOUNCE
+                },
+            );
+            if stopping {
+                crate::log::trace_debug("autosave timer thread stopped");
+                break;
             }
         }
     });
+    (
+        EditorAutoSaveWorker {
+            stopping,
+            thread: Some(thread),
+        },
+        event_rx,
+    )
 }
 
-fn should_flush_pre_switch_editor_content(
-    trigger: &str,
-    current_path: &Path,
-    editor_autosave: &EditorAutoSaveCoordinator,
-) -> bool {
-    if editor_autosave.has_pending_payload_for_path(current_path) {
-        return true;
-    }
+fn run_due_autosave(
+    payload: EditorAutoSavePayload,
+    autosave_coordinator: &EditorAutoSaveCoordinator,
+    autosave_workflow: &SinglelineCreateFileWorkflow,
+    tag_index: Option<&crate::tag_index::TagIndexStore>,
+    event_tx: &smol::channel::Sender<EditorAutoSaveEvent>,
+) {
+    let target_path = payload.current_path.clone();
+    let target = target_path.display().to_string();
+    let editor_len = payload.editor_text.len();
+    // The UI side may be gone during shutdown; saving goes on regardless.
+    let _ = event_tx.send_blocking(EditorAutoSaveEvent::Saving {
+        path: target_path.clone(),
+    });
+    let editor_text = tag_index.map(|_| payload.editor_text.clone());
     crate::log::trace_debug(format!(
-        "autosave pre-switch trigger={} no-op reason=no-pending-user-edit path={}",
-        trigger,
-        current_path.display()
+        "autosave step-5 raise event path={} text_len={}",
+        target, editor_len
     ));
-    false
+
+    // Another file's payload (edited before a switch, once several notes can be open) is
+    // written where it is instead of going through the edit workflow.
+    let saved = if autosave_workflow.edits_path(target_path.as_path()) {
+        autosave_workflow.try_autosave_in_edit(payload)
+    } else {
+        autosave_workflow.save_detached_payload(payload).map(Some)
+    };
+    match saved {
+        Ok(Some(saved_path)) => {
+            autosave_coordinator.clear_journal_if_idle();
+            if let (Some(tag_index), Some(editor_text)) = (tag_index, editor_text.as_deref())
+                && let Err(error) = tag_index.record_note_text(saved_path.as_path(), editor_text)
+            {
+                crate::log::trace_debug(format!(
+                    "req-tag1 tag_index update failed path={} error={error}",
+                    saved_path.display()
+                ));
+            }
+            crate::log::trace_debug(format!(
+                "autosave success path={} text_len={} (step-6 reset)",
+                target, editor_len
+            ));
+            let _ = event_tx.send_blocking(EditorAutoSaveEvent::Saved { path: saved_path });
+        }
+        Ok(None) => {
+            crate::log::trace_debug(format!(
+                "autosave critical skipped (state/path invalid) path={}",
+                target
+            ));
+            debug_assert!(
+                false,
+                "autosave invariant violation: event raised while state/path invalid"
+            );
+            let _ = event_tx.send_blocking(EditorAutoSaveEvent::Failed {
+                path: target_path,
+                error: "note is no longer open for editing".to_string(),
+                remediation: None,
+            });
+        }
+        Err(error) => {
+            crate::log::trace_debug(format!(
+                "autosave failure path={} error={error} (step-6 reset)",
+                target
+            ));
+            let _ = event_tx.send_blocking(EditorAutoSaveEvent::Failed {
+                path: target_path,
+                error: error.to_string(),
+                remediation: error.remediation(),
+            });
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
-pub enum FileWorkflowEvent {
-    Create(CreateFileRequest),
-    Rename(RenameFileRequest),
-    AutoSave(AutoSaveFileRequest),
+/// A title typed into the singleline field, waiting for the typing to pause.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingTitleRename {
+    pub path: PathBuf,
+    pub singleline_value: String,
+    pub user_document_dir: PathBuf,
+}
+
+#[derive(Debug, Default)]
+struct TitleRenameDebounceState {
+    last_edit: Option<Instant>,
+    pending: Option<PendingTitleRename>,
+}
+
+/// Holds back title renames until the singleline field has been idle for `delay`, so a word
+/// typed into the title becomes one rename instead of one per keystroke. Unlike the autosave
+/// slot the clock restarts with every edit.
+#[derive(Clone, Debug)]
+pub struct TitleRenameDebouncer {
+    inner: Arc<Mutex<TitleRenameDebounceState>>,
+    delay: Duration,
+}
+
+impl TitleRenameDebouncer {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(TitleRenameDebounceState::default())),
+            delay,
+        }
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    pub fn mark_title_edit(&self, pending: PendingTitleRename, now: Instant) {
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.last_edit = Some(now);
+        state.pending = Some(pending);
+    }
+
+    pub fn pop_due(&self, now: Instant) -> Option<PendingTitleRename> {
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let last_edit = state.last_edit?;
+        if now.duration_since(last_edit) < self.delay {
+            return None;
+        }
+        state.last_edit = None;
+        state.pending.take()
+    }
+
+    /// The pending title regardless of the delay, for context switches and teardown.
+    pub fn take_pending(&self) -> Option<PendingTitleRename> {
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.last_edit = None;
+        state.pending.take()
+    }
+
+    /// Follows a finished rename of the note the pending title belongs to.
+    pub fn retarget_pending(&self, old_path: &Path, new_path: &Path) {
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(pending) = state.pending.as_mut()
+            && pending.path == old_path
+        {
+            pending.path = new_path.to_path_buf();
+        }
+    }
+
+    pub fn on_edit_path_changed(&self, path: Option<&Path>) {
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if state
+            .pending
+            .as_ref()
+            .is_some_and(|pending| Some(pending.path.as_path()) != path)
+        {
+            crate::log::trace_debug("rename_flow debounce drop pending on path switch");
+            state.last_edit = None;
+            state.pending = None;
+        }
+    }
+}
+
+pub fn dispatch_title_rename(workflow: &SinglelineCreateFileWorkflow, pending: PendingTitleRename) {
+    match workflow.try_rename_in_edit_async(
+        pending.path.as_path(),
+        &pending.singleline_value,
+        pending.user_document_dir.as_path(),
+        Local::now(),
+    ) {
+        Ok(dispatch) => crate::log::trace_input(format!(
+            "rename_flow {dispatch:?} value='{}'",
+            crate::app::compact_text(&pending.singleline_value)
+        )),
+        Err(error) => crate::log::trace_debug(format!(
+            "rename_flow failed value='{}' error={error}",
+            crate::app::compact_text(&pending.singleline_value)
+        )),
+    }
+}
+
+/// Handle to the title rename debounce thread, kept for app teardown.
+#[derive(Debug)]
+pub struct TitleRenameDebounceWorker {
+    stopping: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl TitleRenameDebounceWorker {
+    /// Stops the thread and joins it. A title still pending is left to the caller's flush.
+    pub fn shutdown(&mut self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        let Some(thread) = self.thread.take() else {
+            return;
+        };
+        if thread.join().is_err() {
+            crate::log::trace_debug("rename_flow debounce thread panicked before shutdown");
+        }
+        crate::log::trace_debug("rename_flow debounce thread joined");
+    }
+}
+
+pub fn spawn_title_rename_debounce_worker(
+    debouncer: TitleRenameDebouncer,
+    workflow: SinglelineCreateFileWorkflow,
+    duplicate_title_gate: crate::duplicate_titles::DuplicateTitleGate,
+) -> TitleRenameDebounceWorker {
+    let stopping = Arc::new(AtomicBool::new(false));
+    let worker_stopping = stopping.clone();
+    let thread = thread::spawn(move || {
+        crate::log::trace_debug(format!(
+            "rename_flow debounce thread started delay_ms={}",
+            debouncer.delay().as_millis()
+        ));
+        while !worker_stopping.load(Ordering::SeqCst) {
+            thread::sleep(EDITOR_AUTOSAVE_TICK_DURATION);
+            if let Some(pending) = debouncer.pop_due(Instant::now())
+                && duplicate_title_gate.allow_rename(&pending)
+            {
+                dispatch_title_rename(&workflow, pending);
+            }
+        }
+    });
+    TitleRenameDebounceWorker {
+        stopping,
+        thread: Some(thread),
+    }
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// `None` when there is no journal.
+fn read_autosave_journal(
+    journal_path: &Path,
+) -> io::Result<Option<Vec<EditorAutoSaveJournalEntry>>> {
+    let bytes = match fs::read(journal_path) {
+        Ok(bytes) => bytes,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error),
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(EditorAutoSaveJournalFile::Entries(entries)) => Ok(Some(entries)),
+        Ok(EditorAutoSaveJournalFile::Single(entry)) => Ok(Some(vec![entry])),
+        Err(error) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("autosave journal is corrupt: {error}"),
+        )),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum JournalReplayOutcome {
+    Recovered(PathBuf),
+    /// The note already holds this text or something newer; the entry is done.
+    Settled,
+    /// Not applied this time; the entry stays in the journal.
+    Kept,
+}
+
+// Startup recovery: rewrite each journaled target only when its on-disk mtime predates the
+// journal entry, i.e. the idle autosave never ran. Entries that could not be applied (the
+// note is missing or outside its vault, e.g. the vault is still offline, or the write
+// failed) are written back so they are not lost; a corrupt journal is moved aside.
+pub fn replay_autosave_journal(journal_path: &Path) -> io::Result<Vec<PathBuf>> {
+    let entries = match read_autosave_journal(journal_path) {
+        Ok(Some(entries)) => entries,
+        Ok(None) => return Ok(Vec::new()),
+        Err(error) if error.kind() == io::ErrorKind::InvalidData => {
+            let corrupt_path =
+                journal_path.with_file_name(EDITOR_AUTOSAVE_CORRUPT_JOURNAL_FILE_NAME);
+            fs::rename(journal_path, &corrupt_path)?;
+            crate::log::trace_debug(format!(
+                "autosave journal corrupt, moved to {}",
+                corrupt_path.display()
+            ));
+            return Err(error);
+        }
+        Err(error) => return Err(error),
+    };
+
+    let mut recovered = Vec::new();
+    let mut kept = Vec::new();
+    let mut first_error = None;
+    for entry in entries {
+        match replay_autosave_journal_entry(&entry) {
+            Ok(JournalReplayOutcome::Recovered(path)) => recovered.push(path),
+            Ok(JournalReplayOutcome::Settled) => {}
+            Ok(JournalReplayOutcome::Kept) => kept.push(entry),
+            Err(error) => {
+                crate::log::trace_debug(format!(
+                    "autosave journal replay failed path={} error={error}",
+                    entry.payload.current_path.display()
+                ));
+                first_error.get_or_insert(error);
+                kept.push(entry);
+            }
+        }
+    }
+    if kept.is_empty() {
+        cleanup_editor_temp_file(journal_path)?;
+    } else {
+        let bytes = serde_json::to_vec(&kept)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+        crate::atomic_io::write_atomic(journal_path, &bytes)?;
+    }
+    match first_error {
+        Some(error) if recovered.is_empty() => Err(error),
+        _ => Ok(recovered),
+    }
+}
+
+fn replay_autosave_journal_entry(
+    entry: &EditorAutoSaveJournalEntry,
+) -> io::Result<JournalReplayOutcome> {
+    let target = entry.payload.current_path.clone();
+    // The same on-save transforms a normal save of this note would apply.
+    let text =
+        crate::save_transforms::save_transforms_for(entry.payload.user_document_dir.as_path())
+            .apply(&entry.payload.editor_text);
+    let outcome = if !target.starts_with(entry.payload.user_document_dir.as_path()) {
+        "skip-outside-user-document"
+    } else if !target.is_file() {
+        "skip-target-missing"
+    } else {
+        let target_mtime = fs::metadata(&target)?.modified()?;
+        let on_disk = if unix_millis(target_mtime) >= entry.journaled_at_unix_ms {
+            None
+        } else {
+            Some(fs::read(&target)?)
+        };
+        if on_disk.is_none() {
+            "skip-target-newer"
+        } else if on_disk.as_deref() == Some(text.as_bytes()) {
+            "skip-content-equal"
+        } else {
+            // Nothing has been opened yet this run; the bytes just read tell the encoding.
+            if let Some(Ok((_, encoding))) = on_disk
+                .as_deref()
+                .map(crate::text_encoding::decode_note_bytes)
+            {
+                crate::text_encoding::remember_note_encoding(target.as_path(), encoding);
+            }
+            let bytes = crate::text_encoding::encode_for_save(target.as_path(), &text);
+            crate::storage_backend::storage_backend_for(entry.payload.user_document_dir.as_path())
+                .write(target.as_path(), &bytes)?;
+            "recovered"
+        }
+    };
+
+    crate::log::trace_debug(format!(
+        "autosave journal replay outcome={} path={} text_len={}",
+        outcome,
+        target.display(),
+        entry.payload.editor_text.len()
+    ));
+    Ok(match outcome {
+        "recovered" => JournalReplayOutcome::Recovered(target),
+        "skip-target-newer" | "skip-content-equal" => JournalReplayOutcome::Settled,
+        _ => JournalReplayOutcome::Kept,
+    })
+}
+
+fn should_flush_pre_switch_editor_content(
+    trigger: &str,
+    current_path: &Path,
+    editor_autosave: &EditorAutoSaveCoordinator,
+) -> bool {
+    if editor_autosave.has_pending_payload_for_path(current_path) {
+        return true;
+    }
+    crate::log::trace_debug(format!(
+        "autosave pre-switch trigger={} no-op reason=no-pending-user-edit path={}",
+        trigger,
+        current_path.display()
+    ));
+    false
+}
+
+#[derive(Debug, Clone)]
+pub enum FileWorkflowEvent {
+    Create(CreateFileRequest),
+    CreateFromTemplate(CreateFromTemplateRequest),
+    Rename(RenameFileRequest),
+    AutoSave(AutoSaveFileRequest),
     RpcPin(RpcPinFileRequest),
+    BulkReplace(BulkReplaceRequest),
+    PromoteScratch(PromoteScratchRequest),
+    Move(MoveEntryRequest),
+    CreateDir(CreateDirRequest),
+    Duplicate(DuplicateNoteRequest),
+    RewriteLinks(LinkRewriteRequest),
+    ImportFolder(FolderImportRequest),
+    ImportNotesFile(NotesFileImportRequest),
+    PlainTextExport(PlainTextExportRequest),
+    Snapshot(SnapshotRequest),
+    MergeConflict(ConflictMergeRequest),
+    // Holds the worker for the given time, standing in for a hung network share.
+    #[cfg(test)]
+    Stall(Duration),
+}
+
+impl FileWorkflowEvent {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Create(_) | Self::CreateFromTemplate(_) => "create",
+            Self::Rename(_) => "rename",
+            Self::AutoSave(_) => "autosave",
+            Self::RpcPin(_) => "rpc-pin",
+            Self::BulkReplace(_) => "bulk-replace",
+            Self::PromoteScratch(_) => "scratch-promote",
+            Self::Move(_) => "move",
+            Self::CreateDir(_) => "create-dir",
+            Self::Duplicate(_) => "duplicate",
+            Self::RewriteLinks(_) => "link-rewrite",
+            Self::ImportFolder(_) => "folder-import",
+            Self::ImportNotesFile(_) => "notes-file-import",
+            Self::PlainTextExport(_) => "plain-text-export",
+            Self::Snapshot(_) => "snapshot",
+            Self::MergeConflict(_) => "conflict-merge",
+            #[cfg(test)]
+            Self::Stall(_) => "stall",
+        }
+    }
+
+    fn timeout(&self, timeouts: EventTimeouts) -> Duration {
+        match self {
+            Self::BulkReplace(_)
+            | Self::RewriteLinks(_)
+            | Self::ImportFolder(_)
+            | Self::ImportNotesFile(_)
+            | Self::PlainTextExport(_)
+            | Self::Snapshot(_)
+            | Self::MergeConflict(_) => timeouts.batch,
+            _ => timeouts.single,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -282,71 +1200,435 @@ pub enum FileWorkflowEventResult {
         content: String,
         linenum: u32,
     },
+    BulkReplaced {
+        report: BulkReplaceReport,
+    },
+    Moved {
+        path: PathBuf,
+    },
+    DirCreated {
+        path: PathBuf,
+    },
+    Duplicated {
+        path: PathBuf,
+    },
+    LinksRewritten {
+        report: BulkReplaceReport,
+    },
+    FolderImported {
+        report: crate::folder_import::FolderImportReport,
+    },
+    NotesFileImported {
+        summary: crate::notes_import::NotesImportSummary,
+    },
+    PlainTextExported {
+        report: crate::export::PlainTextExportReport,
+    },
+    Snapshotted,
+    /// `copy_kept` says why the conflicted copy could not be moved to the recyclebin.
+    ConflictMerged {
+        original: PathBuf,
+        copy_kept: Option<String>,
+    },
+}
+
+pub type FileWorkflowEventCallback =
+    Box<dyn FnOnce(WorkflowResult<FileWorkflowEventResult>) + Send + 'static>;
+
+enum EventResponder {
+    Blocking(mpsc::Sender<WorkflowResult<FileWorkflowEventResult>>),
+    Callback(FileWorkflowEventCallback),
+}
+
+impl EventResponder {
+    fn respond(self, result: WorkflowResult<FileWorkflowEventResult>) {
+        match self {
+            Self::Blocking(response_tx) => {
+                let _ = response_tx.send(result);
+            }
+            Self::Callback(callback) => callback(result),
+        }
+    }
+}
+
+impl fmt::Debug for EventResponder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Blocking(_) => f.write_str("EventResponder::Blocking"),
+            Self::Callback(_) => f.write_str("EventResponder::Callback"),
+        }
+    }
 }
 
 #[derive(Debug)]
 struct EventEnvelope {
     event: FileWorkflowEvent,
-    response_tx: mpsc::Sender<io::Result<FileWorkflowEventResult>>,
+    responder: EventResponder,
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Copy, Debug)]
+struct EventTimeouts {
+    single: Duration,
+    batch: Duration,
+}
+
+// The event the worker is running. Its responder lives here rather than on the worker's
+// stack so the watchdog can still answer the caller when the worker never comes back.
+#[derive(Debug)]
+struct InFlightEvent {
+    label: &'static str,
+    started: Instant,
+    timeout: Duration,
+    responder: EventResponder,
+}
+
+/// The watchdog gave up on a stuck event and restarted the worker.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DispatcherStall {
+    pub event: &'static str,
+    pub after: Duration,
+    pub failed_events: usize,
+}
+
+impl DispatcherStall {
+    pub fn notice(&self) -> String {
+        let event = self.event.to_string();
+        let seconds = self.after.as_secs().to_string();
+        let args = [("event", event.as_str()), ("seconds", seconds.as_str())];
+        if self.failed_events == 0 {
+            return crate::i18n::tr_with("notice.worker_restarted", &args);
+        }
+        let count = self.failed_events.to_string();
+        crate::i18n::tr_with(
+            "notice.worker_restarted_cancelled",
+            &[args[0], args[1], ("count", count.as_str())],
+        )
+    }
+}
+
+/// What happens to a new event once the dispatcher queue is full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueueFullPolicy {
+    /// Wait for the worker to make room, for at most a couple of seconds, then fail.
+    Block,
+    /// Discard the oldest queued autosave (a newer save of the buffer follows); fail when
+    /// there is none.
+    DropOldestAutoSave,
+    /// Fail the new event straight away.
+    Error,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueueLimits {
+    pub capacity: usize,
+    pub when_full: QueueFullPolicy,
+}
+
+impl Default for QueueLimits {
+    fn default() -> Self {
+        Self {
+            capacity: WORKFLOW_QUEUE_DEFAULT_CAPACITY,
+            when_full: QueueFullPolicy::DropOldestAutoSave,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct WorkflowConfigFile {
+    #[serde(default)]
+    workflow: WorkflowConfigSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct WorkflowConfigSection {
+    queue_capacity: Option<usize>,
+    queue_full: Option<String>,
+}
+
+/// `[workflow] queue_capacity` (at least 1) and `queue_full = "block" |
+/// "drop_oldest_autosave" | "error"`.
+pub(crate) fn load_queue_limits(path: &Path) -> QueueLimits {
+    let defaults = QueueLimits::default();
+    let Ok(raw) = fs::read_to_string(path) else {
+        return defaults;
+    };
+    let parsed: WorkflowConfigFile = match toml::from_str(&raw) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            crate::log::trace_debug(format!(
+                "file_update_handler queue config parse failed path={} error={error}",
+                path.display()
+            ));
+            return defaults;
+        }
+    };
+    let when_full = match parsed.workflow.queue_full.as_deref().map(str::trim) {
+        None => defaults.when_full,
+        Some("block") => QueueFullPolicy::Block,
+        Some("drop_oldest_autosave") => QueueFullPolicy::DropOldestAutoSave,
+        Some("error") => QueueFullPolicy::Error,
+        Some(other) => {
+            crate::log::trace_debug(format!(
+                "file_update_handler unknown queue_full value='{other}' fallback=drop_oldest_autosave"
+            ));
+            defaults.when_full
+        }
+    };
+    QueueLimits {
+        capacity: parsed
+            .workflow
+            .queue_capacity
+            .unwrap_or(defaults.capacity)
+            .max(1),
+        when_full,
+    }
+}
+
+#[derive(Debug)]
 struct QueueState {
     queue: VecDeque<EventEnvelope>,
+    limits: QueueLimits,
     shutdown: bool,
+    in_flight: Option<InFlightEvent>,
+    // Bumped on every restart; a worker from an older generation may not write any more,
+    // exits once its stuck call returns and drops whatever it produced.
+    generation: u64,
+    timeouts: EventTimeouts,
+    stalls: Option<smol::channel::Sender<DispatcherStall>>,
+}
+
+type DispatcherShared = Arc<(Mutex<QueueState>, Condvar)>;
+
+thread_local! {
+    // Set on worker threads: the dispatcher they serve and the generation they started as.
+    static WORKER_GENERATION: std::cell::RefCell<Option<(DispatcherShared, u64)>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Fails a write on a worker the watchdog has given up on. Its stuck call may still
+/// return, and whatever it would write next (the rest of a bulk edit, the rename after a
+/// relocation) would race the fresh worker; the low-level writers call this first.
+pub(crate) fn ensure_current_worker() -> io::Result<()> {
+    WORKER_GENERATION.with(|current| {
+        let current = current.borrow();
+        let Some((shared, generation)) = current.as_ref() else {
+            return Ok(());
+        };
+        let live = shared
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .generation;
+        if live == *generation {
+            return Ok(());
+        }
+        crate::log::trace_debug(format!(
+            "file_update_handler stale worker generation={generation} write refused live={live}"
+        ));
+        Err(WorkflowError::DispatcherUnavailable(
+            "the file worker was restarted; this write was abandoned".to_string(),
+        )
+        .into())
+    })
 }
 
 #[derive(Clone, Debug)]
 pub struct FileWorkflowEventDispatcher {
-    shared: Arc<(Mutex<QueueState>, Condvar)>,
+    shared: DispatcherShared,
+    worker: Arc<Mutex<Option<thread::JoinHandle<()>>>>,
 }
 
 impl FileWorkflowEventDispatcher {
     pub fn new() -> Self {
-        let shared = Arc::new((Mutex::new(QueueState::default()), Condvar::new()));
-        let worker_shared = shared.clone();
+        Self::with_event_timeouts(WORKFLOW_EVENT_TIMEOUT, WORKFLOW_BATCH_EVENT_TIMEOUT)
+    }
+
+    pub fn with_event_timeouts(single: Duration, batch: Duration) -> Self {
+        let shared = Arc::new((
+            Mutex::new(QueueState {
+                queue: VecDeque::new(),
+                limits: QueueLimits::default(),
+                shutdown: false,
+                in_flight: None,
+                generation: 0,
+                timeouts: EventTimeouts { single, batch },
+                stalls: None,
+            }),
+            Condvar::new(),
+        ));
+        let worker = Arc::new(Mutex::new(Some(spawn_worker(shared.clone(), 0))));
+        let watchdog_shared = shared.clone();
+        let watchdog_worker = worker.clone();
+        thread::spawn(move || watchdog_loop(watchdog_shared, watchdog_worker));
+
+        Self { shared, worker }
+    }
 
-        thread::spawn(move || worker_loop(worker_shared));
+    pub fn set_queue_limits(&self, limits: QueueLimits) {
+        self.shared
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .limits = limits;
+    }
 
-        Self { shared }
+    pub fn subscribe_stalls(&self) -> smol::channel::Receiver<DispatcherStall> {
+        let (tx, rx) = smol::channel::unbounded();
+        self.shared
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .stalls = Some(tx);
+        rx
     }
 
     pub fn dispatch_blocking(
         &self,
         event: FileWorkflowEvent,
-    ) -> io::Result<FileWorkflowEventResult> {
-        let (response_tx, response_rx) = mpsc::channel::<io::Result<FileWorkflowEventResult>>();
-        {
-            let (lock, wakeup) = &*self.shared;
-            let mut state = lock.lock().map_err(|_| {
-                io::Error::other("file_update_handler event queue lock poisoned on enqueue")
-            })?;
-            state.queue.push_back(EventEnvelope { event, response_tx });
-            wakeup.notify_one();
-        }
+    ) -> WorkflowResult<FileWorkflowEventResult> {
+        let (response_tx, response_rx) = mpsc::channel::<WorkflowResult<FileWorkflowEventResult>>();
+        self.enqueue(event, EventResponder::Blocking(response_tx))?;
 
         response_rx.recv().map_err(|_| {
-            io::Error::new(
-                io::ErrorKind::BrokenPipe,
-                "file_update_handler worker terminated before sending response",
+            WorkflowError::DispatcherUnavailable(
+                "worker terminated before sending a response".to_string(),
             )
         })?
     }
 
-    #[cfg(test)]
-    pub fn shutdown(&self) {
+    /// Queues `event` and returns immediately. `callback` runs on the worker thread
+    /// once the event has been processed.
+    pub fn dispatch_async<F>(&self, event: FileWorkflowEvent, callback: F) -> WorkflowResult<()>
+    where
+        F: FnOnce(WorkflowResult<FileWorkflowEventResult>) + Send + 'static,
+    {
+        self.enqueue(event, EventResponder::Callback(Box::new(callback)))
+    }
+
+    fn enqueue(&self, event: FileWorkflowEvent, responder: EventResponder) -> WorkflowResult<()> {
         let (lock, wakeup) = &*self.shared;
-        if let Ok(mut state) = lock.lock() {
-            state.shutdown = true;
-            wakeup.notify_all();
+        let mut state = lock.lock().map_err(|_| {
+            WorkflowError::DispatcherUnavailable("event queue lock poisoned".to_string())
+        })?;
+        if state.shutdown {
+            return Err(WorkflowError::DispatcherUnavailable(
+                "worker is shut down".to_string(),
+            ));
+        }
+        let limits = state.limits;
+        let mut dropped = None;
+        if state.queue.len() >= limits.capacity {
+            match limits.when_full {
+                QueueFullPolicy::Block => {
+                    let deadline = Instant::now() + WORKFLOW_QUEUE_BLOCK_LIMIT;
+                    while state.queue.len() >= limits.capacity && !state.shutdown {
+                        let Some(left) = deadline.checked_duration_since(Instant::now()) else {
+                            break;
+                        };
+                        state = match wakeup.wait_timeout(state, left) {
+                            Ok((state, _)) => state,
+                            Err(poisoned) => poisoned.into_inner().0,
+                        };
+                    }
+                    if state.shutdown {
+                        return Err(WorkflowError::DispatcherUnavailable(
+                            "worker is shut down".to_string(),
+                        ));
+                    }
+                }
+                QueueFullPolicy::DropOldestAutoSave => {
+                    dropped = state
+                        .queue
+                        .iter()
+                        .position(|envelope| {
+                            matches!(envelope.event, FileWorkflowEvent::AutoSave(_))
+                        })
+                        .and_then(|index| state.queue.remove(index));
+                }
+                QueueFullPolicy::Error => {}
+            }
+            if state.queue.len() >= limits.capacity {
+                crate::log::trace_debug(format!(
+                    "file_update_handler queue full event={} capacity={} policy={:?}",
+                    event.label(),
+                    limits.capacity,
+                    limits.when_full
+                ));
+                return Err(WorkflowError::QueueFull {
+                    capacity: limits.capacity,
+                });
+            }
+        }
+        state.queue.push_back(EventEnvelope { event, responder });
+        // Producers waiting for room share the condvar with the worker.
+        wakeup.notify_all();
+        drop(state);
+        if let Some(dropped) = dropped {
+            crate::log::trace_debug(format!(
+                "file_update_handler queue full; dropped oldest autosave capacity={}",
+                limits.capacity
+            ));
+            dropped.responder.respond(Err(WorkflowError::QueueFull {
+                capacity: limits.capacity,
+            }));
         }
+        Ok(())
     }
+
+    /// Stops accepting events, lets the worker finish everything already queued and joins
+    /// it. Later calls, and calls from the worker thread itself, return without waiting; a
+    /// worker the watchdog gave up on is left behind instead of joined.
+    pub fn shutdown(&self) {
+        let (lock, wakeup) = &*self.shared;
+        let generation = {
+            let mut state = lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.shutdown = true;
+            crate::log::trace_debug(format!(
+                "file_update_handler shutdown queued={}",
+                state.queue.len()
+            ));
+            wakeup.notify_all();
+            state.generation
+        };
+        let worker = self
+            .worker
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+        let Some(worker) = worker else {
+            return;
+        };
+        if worker.thread().id() == thread::current().id() {
+            return;
+        }
+        while !worker.is_finished() {
+            let restarted = lock
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .generation
+                != generation;
+            if restarted {
+                crate::log::trace_debug("file_update_handler shutdown left a stuck worker behind");
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        if worker.join().is_err() {
+            crate::log::trace_debug("file_update_handler worker panicked before shutdown");
+        }
+        crate::log::trace_debug("file_update_handler worker joined");
+    }
+}
+
+fn spawn_worker(shared: DispatcherShared, generation: u64) -> thread::JoinHandle<()> {
+    thread::spawn(move || worker_loop(shared, generation))
 }
 
-fn worker_loop(shared: Arc<(Mutex<QueueState>, Condvar)>) {
+fn worker_loop(shared: DispatcherShared, generation: u64) {
+    WORKER_GENERATION.with(|current| *current.borrow_mut() = Some((shared.clone(), generation)));
+    let (lock, wakeup) = &*shared;
     loop {
-        let envelope = {
-            let (lock, wakeup) = &*shared;
+        let event = {
             let mut state = match lock.lock() {
                 Ok(state) => state,
                 Err(poisoned) => poisoned.into_inner(),
@@ -363,39 +1645,452 @@ fn worker_loop(shared: Arc<(Mutex<QueueState>, Condvar)>) {
                 break;
             }
 
-            state.queue.pop_front()
+            let Some(EventEnvelope { event, responder }) = state.queue.pop_front() else {
+                continue;
+            };
+            wakeup.notify_all();
+            state.in_flight = Some(InFlightEvent {
+                label: event.label(),
+                started: Instant::now(),
+                timeout: event.timeout(state.timeouts),
+                responder,
+            });
+            event
+        };
+
+        let result = process_event(event);
+        if let Ok(result) = result.as_ref() {
+            record_self_writes(result);
+        }
+        let in_flight = {
+            let mut state = lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if state.generation != generation {
+                crate::log::trace_debug(format!(
+                    "file_update_handler stale worker generation={generation} finished; result dropped"
+                ));
+                return;
+            }
+            state.in_flight.take()
+        };
+        if let Some(in_flight) = in_flight {
+            in_flight.responder.respond(result);
+        }
+    }
+}
+
+// Fails the stuck event and everything queued behind it, then hands the queue to a fresh
+// worker. The stuck thread cannot be interrupted; once its call returns, every further write
+// it tries is refused (`ensure_current_worker`) and it exits.
+fn watchdog_loop(shared: DispatcherShared, worker: Arc<Mutex<Option<thread::JoinHandle<()>>>>) {
+    let (lock, wakeup) = &*shared;
+    loop {
+        thread::sleep(WORKFLOW_WATCHDOG_TICK);
+        let mut state = lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if state.shutdown && state.in_flight.is_none() && state.queue.is_empty() {
+            break;
+        }
+        let timed_out = state
+            .in_flight
+            .as_ref()
+            .is_some_and(|in_flight| in_flight.started.elapsed() >= in_flight.timeout);
+        let Some(stuck) = state.in_flight.take_if(|_| timed_out) else {
+            continue;
         };
+        let elapsed = stuck.started.elapsed();
+        let queued = state.queue.drain(..).collect::<Vec<_>>();
+        wakeup.notify_all();
+        state.generation += 1;
+        let generation = state.generation;
+        let respawn = !state.shutdown;
+        let stalls = state.stalls.clone();
+        drop(state);
 
-        if let Some(envelope) = envelope {
-            let result = process_event(envelope.event);
-            let _ = envelope.response_tx.send(result);
+        crate::log::trace_debug(format!(
+            "file_update_handler watchdog event={} stuck_ms={} failed_queued={} respawn={respawn}",
+            stuck.label,
+            elapsed.as_millis(),
+            queued.len()
+        ));
+        let stall = DispatcherStall {
+            event: stuck.label,
+            after: elapsed,
+            failed_events: queued.len(),
+        };
+        stuck.responder.respond(Err(WorkflowError::TimedOut {
+            event: stuck.label,
+            after: elapsed,
+        }));
+        for envelope in queued {
+            envelope
+                .responder
+                .respond(Err(WorkflowError::DispatcherUnavailable(format!(
+                    "restarted after {} got stuck",
+                    stuck.label
+                ))));
+        }
+        *worker
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) =
+            respawn.then(|| spawn_worker(shared.clone(), generation));
+        if let Some(stalls) = stalls {
+            let _ = stalls.try_send(stall);
         }
     }
 }
 
-fn process_event(event: FileWorkflowEvent) -> io::Result<FileWorkflowEventResult> {
+// Worker helpers report `io::Error`; each event classifies it against the path it was
+// working on so the UI can tell a name clash from a missing note or a read-only folder.
+fn process_event(event: FileWorkflowEvent) -> WorkflowResult<FileWorkflowEventResult> {
     match event {
         FileWorkflowEvent::Create(request) => {
-            let path = create_new_text_file(&request)?;
+            let path = classify(&request.user_document_dir, create_new_text_file(&request))?;
+            Ok(FileWorkflowEventResult::Created { path })
+        }
+        FileWorkflowEvent::CreateFromTemplate(request) => {
+            let path = classify(
+                &request.create.user_document_dir,
+                create_text_file_from_template(&request),
+            )?;
             Ok(FileWorkflowEventResult::Created { path })
         }
         FileWorkflowEvent::Rename(request) => {
-            let path = rename_text_file(&request)?;
+            let path = classify(&request.current_path, rename_text_file(&request))?;
+            crate::text_encoding::rebase_note_encodings(&request.current_path, &path);
             Ok(FileWorkflowEventResult::Renamed { path })
         }
         FileWorkflowEvent::AutoSave(request) => {
-            let path = save_editor_text_payload_atomic(&request.payload)?;
+            let path = classify(
+                &request.payload.current_path,
+                if request.detached {
+                    save_editor_text_in_place(&request.payload)
+                } else {
+                    save_editor_text_payload_atomic(&request.payload, request.previous_identity)
+                },
+            )?;
             Ok(FileWorkflowEventResult::AutoSaved { path })
         }
         FileWorkflowEvent::RpcPin(request) => {
-            let result = pin_existing_text_file(&request)?;
+            let result = classify(&request.full_path, pin_existing_text_file(&request))?;
             Ok(FileWorkflowEventResult::RpcPinned {
                 path: result.path,
                 content: result.content,
                 linenum: result.linenum,
             })
         }
+        FileWorkflowEvent::BulkReplace(request) => {
+            let report = classify(&request.user_document_dir, bulk_replace_files(&request))?;
+            Ok(FileWorkflowEventResult::BulkReplaced { report })
+        }
+        FileWorkflowEvent::PromoteScratch(request) => {
+            let path = classify(
+                &request.create.user_document_dir,
+                promote_scratch_text(&request),
+            )?;
+            Ok(FileWorkflowEventResult::Created { path })
+        }
+        FileWorkflowEvent::Move(request) => {
+            let path = classify(&request.from, move_entry_into_dir(&request))?;
+            crate::text_encoding::rebase_note_encodings(&request.from, &path);
+            Ok(FileWorkflowEventResult::Moved { path })
+        }
+        FileWorkflowEvent::CreateDir(request) => {
+            let path = classify(&request.parent, create_note_directory(&request))?;
+            Ok(FileWorkflowEventResult::DirCreated { path })
+        }
+        FileWorkflowEvent::Duplicate(request) => {
+            let path = classify(
+                &request.source,
+                crate::file_tree::duplicate_note_file(&request.source),
+            )?;
+            Ok(FileWorkflowEventResult::Duplicated { path })
+        }
+        FileWorkflowEvent::RewriteLinks(request) => {
+            let report = rewrite_links_in_files(&request);
+            Ok(FileWorkflowEventResult::LinksRewritten { report })
+        }
+        FileWorkflowEvent::ImportFolder(request) => {
+            let report = classify(
+                &request.source_dir,
+                crate::folder_import::import_folder(&request),
+            )?;
+            Ok(FileWorkflowEventResult::FolderImported { report })
+        }
+        FileWorkflowEvent::ImportNotesFile(request) => {
+            let summary = classify(
+                &request.source,
+                crate::folder_import::import_notes_file(&request),
+            )?;
+            Ok(FileWorkflowEventResult::NotesFileImported { summary })
+        }
+        FileWorkflowEvent::PlainTextExport(request) => {
+            let report = crate::export::write_plain_text_export(
+                &request.notes,
+                &request.target_dir,
+                &request.profile,
+            );
+            Ok(FileWorkflowEventResult::PlainTextExported { report })
+        }
+        FileWorkflowEvent::Snapshot(request) => {
+            crate::snapshots::snapshot_before(&request.reason, &request.paths);
+            Ok(FileWorkflowEventResult::Snapshotted)
+        }
+        FileWorkflowEvent::MergeConflict(request) => {
+            let copy_kept = classify(&request.original, merge_conflicted_copy(&request))?;
+            Ok(FileWorkflowEventResult::ConflictMerged {
+                original: request.original,
+                copy_kept,
+            })
+        }
+        #[cfg(test)]
+        FileWorkflowEvent::Stall(duration) => {
+            thread::sleep(duration);
+            Err(WorkflowError::InvalidRequest("stalled".to_string()))
+        }
+    }
+}
+
+// Worker helpers retry a busy file around each single IO call (`workflow_error::retry_busy`);
+// a whole event is never run twice, since a half-done bulk edit or import must not repeat.
+fn classify<T>(path: &Path, result: io::Result<T>) -> WorkflowResult<T> {
+    result.map_err(|error| WorkflowError::from_io(error, path))
+}
+
+// A rename that finds no source failed at `from`; anything else (a read-only target folder,
+// a name the file system refuses) failed at `to`.
+fn rename_error(error: io::Error, from: &Path, to: &Path) -> io::Error {
+    let path = if error.kind() == io::ErrorKind::NotFound {
+        from
+    } else {
+        to
+    };
+    at_path(path)(error)
+}
+
+fn create_text_file_from_template(request: &CreateFromTemplateRequest) -> io::Result<PathBuf> {
+    let path = create_new_text_file(&request.create)?;
+    let body = crate::note_templates::render_note_template(&request.template, request.create.now);
+    let backend =
+        crate::storage_backend::storage_backend_for(request.create.user_document_dir.as_path());
+    if let Err(error) = backend.write(path.as_path(), body.as_bytes()) {
+        let _ = fs::remove_file(&path);
+        return Err(at_path(&path)(error));
+    }
+    Ok(path)
+}
+
+// The note is created through the regular naming rules and only then filled; a failed
+// write removes the empty file so a half-promoted scratch pane leaves nothing behind.
+fn promote_scratch_text(request: &PromoteScratchRequest) -> io::Result<PathBuf> {
+    let path = create_new_text_file(&request.create)?;
+    let backend =
+        crate::storage_backend::storage_backend_for(request.create.user_document_dir.as_path());
+    if let Err(error) = backend.write(path.as_path(), request.content.as_bytes()) {
+        let _ = fs::remove_file(&path);
+        return Err(at_path(&path)(error));
+    }
+    Ok(path)
+}
+
+// Moving within the same folder is a no-op; a folder never moves into itself or below.
+fn move_entry_into_dir(request: &MoveEntryRequest) -> io::Result<PathBuf> {
+    if !request.from.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("move source does not exist: {}", request.from.display()),
+        ));
+    }
+    if !request.to_dir.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "move target folder does not exist: {}",
+                request.to_dir.display()
+            ),
+        ));
+    }
+    let from = comparable_path_for_daily_directory(request.from.as_path());
+    let to_dir = comparable_path_for_daily_directory(request.to_dir.as_path());
+    if to_dir.starts_with(&from) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "a folder cannot be moved into itself",
+        ));
+    }
+    if request
+        .from
+        .parent()
+        .map(comparable_path_for_daily_directory)
+        == Some(to_dir)
+    {
+        return Ok(request.from.clone());
+    }
+
+    let file_name = request
+        .from
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "move source has no name"))?;
+    let backend = crate::storage_backend::storage_backend_containing(request.from.as_path());
+    let mut suffix = 1usize;
+    loop {
+        let target = relocated_daily_candidate_path(request.to_dir.as_path(), &file_name, suffix);
+        match backend.rename(&request.from, &target) {
+            Ok(()) => return Ok(target),
+            Err(error) if is_retryable_name_conflict_error(&error) => {
+                suffix += 1;
+            }
+            Err(error) => return Err(rename_error(error, &request.from, &target)),
+        }
+    }
+}
+
+fn create_note_directory(request: &CreateDirRequest) -> io::Result<PathBuf> {
+    if !request.parent.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("parent folder does not exist: {}", request.parent.display()),
+        ));
+    }
+    let name = sanitize_filename_stem(request.name.trim());
+    let name = if name.is_empty() {
+        NEW_FOLDER_DEFAULT_NAME.to_string()
+    } else {
+        name
+    };
+    let backend = crate::storage_backend::storage_backend_containing(request.parent.as_path());
+    let mut suffix = 1usize;
+    loop {
+        let target = if suffix == 1 {
+            request.parent.join(&name)
+        } else {
+            request.parent.join(format!("{name}_{suffix}"))
+        };
+        match backend.create_dir(&target) {
+            Ok(()) => return Ok(target),
+            Err(error) if is_retryable_name_conflict_error(&error) => suffix += 1,
+            Err(error) => return Err(at_path(&target)(error)),
+        }
+    }
+}
+
+// Each file is rewritten atomically on its own; one unreadable or unwritable file is
+// reported and skipped instead of aborting the remaining files.
+fn bulk_replace_files(request: &BulkReplaceRequest) -> io::Result<BulkReplaceReport> {
+    if request.find.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "bulk replace requires a non-empty search text",
+        ));
+    }
+
+    let mut report = BulkReplaceReport::default();
+    let mut rewrites = Vec::new();
+    for path in &request.paths {
+        if !path.starts_with(request.user_document_dir.as_path()) {
+            report
+                .failed
+                .push((path.clone(), "outside user_document_dir".to_string()));
+            continue;
+        }
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) => {
+                report.failed.push((path.clone(), error.to_string()));
+                continue;
+            }
+        };
+        let count = text.matches(request.find.as_str()).count();
+        if count == 0 {
+            report.unchanged += 1;
+            continue;
+        }
+        rewrites.push((path, count, text));
+    }
+
+    // One snapshot of every file about to change, taken before the first write.
+    let changing = rewrites
+        .iter()
+        .map(|(path, _, _)| (*path).clone())
+        .collect::<Vec<_>>();
+    crate::snapshots::snapshot_before("bulk replace", &changing);
+    let backend = crate::storage_backend::storage_backend_for(request.user_document_dir.as_path());
+    for (path, count, text) in rewrites {
+        let rewritten = text.replace(request.find.as_str(), request.replace.as_str());
+        match backend.write(path.as_path(), rewritten.as_bytes()) {
+            Ok(()) => report.replaced.push((path.clone(), count)),
+            Err(error) => report.failed.push((path.clone(), error.to_string())),
+        }
+    }
+
+    crate::log::trace_debug(format!(
+        "req-rep1 bulk replace find_len={} files={} replaced_files={} replacements={} unchanged={} failed={}",
+        request.find.len(),
+        request.paths.len(),
+        report.replaced.len(),
+        report.total_replacements(),
+        report.unchanged,
+        report.failed.len()
+    ));
+    Ok(report)
+}
+
+// Same per-file rules as the bulk replace: each note is rewritten atomically and a failure
+// is reported without stopping the batch.
+fn rewrite_links_in_files(request: &LinkRewriteRequest) -> BulkReplaceReport {
+    let backend = crate::storage_backend::storage_backend_for(request.user_document_dir.as_path());
+    let mut report = BulkReplaceReport::default();
+    let mut note_paths = Vec::new();
+    if let Err(error) =
+        crate::tag_index::collect_note_paths(request.user_document_dir.as_path(), &mut note_paths)
+    {
+        crate::log::trace_debug(format!(
+            "req-lnk1 link rewrite title scan failed dir={} error={error}",
+            request.user_document_dir.display()
+        ));
+    }
+    let moves = crate::note_links::link_moves(&request.moves, &note_paths);
+    for path in &request.paths {
+        if !path.starts_with(request.user_document_dir.as_path()) {
+            report
+                .failed
+                .push((path.clone(), "outside user_document_dir".to_string()));
+            continue;
+        }
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) => {
+                report.failed.push((path.clone(), error.to_string()));
+                continue;
+            }
+        };
+        let note_from = moves
+            .iter()
+            .find(|link_move| link_move.to == *path)
+            .map_or(path.as_path(), |link_move| link_move.from.as_path());
+        let (rewritten, count) =
+            crate::note_links::rewrite_note_links(&text, note_from, path, &moves);
+        if count == 0 {
+            report.unchanged += 1;
+            continue;
+        }
+        match backend.write(path.as_path(), rewritten.as_bytes()) {
+            Ok(()) => report.replaced.push((path.clone(), count)),
+            Err(error) => report.failed.push((path.clone(), error.to_string())),
+        }
     }
+    crate::log::trace_debug(format!(
+        "req-lnk1 link rewrite files={} ambiguous_titles={} rewritten_files={} links={} unchanged={} failed={}",
+        request.paths.len(),
+        moves
+            .iter()
+            .filter(|link_move| !link_move.wikilinks)
+            .count(),
+        report.replaced.len(),
+        report.total_replacements(),
+        report.unchanged,
+        report.failed.len()
+    ));
+    report
 }
 
 fn pin_existing_text_file(request: &RpcPinFileRequest) -> io::Result<RpcPinFileResult> {
@@ -414,11 +2109,18 @@ fn pin_existing_text_file(request: &RpcPinFileRequest) -> io::Result<RpcPinFileR
         request.full_path.display(),
         request.user_document_dir.display()
     ));
-    let relocated_path = move_existing_file_to_daily_directory(
+    let relocated_path = if is_path_in_vault(
         request.full_path.as_path(),
         request.user_document_dir.as_path(),
-        Local::now(),
-    )?;
+    ) {
+        move_existing_file_to_daily_directory(
+            request.full_path.as_path(),
+            request.user_document_dir.as_path(),
+            Local::now(),
+        )?
+    } else {
+        request.full_path.clone()
+    };
 
     let content = fs::read_to_string(relocated_path.as_path())?;
     let total_lines = crate::quic_rpc_protocol::content_line_count(&content);
@@ -450,6 +2152,86 @@ struct WorkflowStateInner {
     state: SinglelineFileState,
     current_edit_path: Option<PathBuf>,
     last_create_event_raised_at: Option<Instant>,
+    new_note_extension: NoteFileExtension,
+    next_note_template: Option<String>,
+    edit_identity: Option<(PathBuf, FileIdentity)>,
+    // The note open in the split pane: an Edit binding of its own that follows moves like
+    // `current_edit_path`, while its saves stay in place.
+    split_edit_path: Option<PathBuf>,
+    edit_path_updates: Option<smol::channel::Sender<PathBuf>>,
+    rename: RenameCoalescing,
+    transition_journal: Option<crate::workflow_journal::WorkflowJournal>,
+}
+
+// At most one title rename is queued on the worker; titles typed meanwhile replace each other
+// in `pending`, so a burst of keystrokes costs two renames instead of one per key.
+#[derive(Debug, Default)]
+struct RenameCoalescing {
+    in_flight: bool,
+    pending: Option<(String, PathBuf, DateTime<Local>)>,
+    outcomes: Option<smol::channel::Sender<RenameOutcome>>,
+    // Earlier names of the open note; autosave payloads pinned to one of them still belong
+    // to it, since the UI learns the new path only after the worker finished.
+    renamed_away: Vec<PathBuf>,
+}
+
+/// A finished title rename. `superseded` is set when a newer title is already queued, in
+/// which case the UI should follow the path but leave the title field alone.
+#[derive(Debug)]
+pub struct RenameOutcome {
+    pub singleline_value: String,
+    pub result: WorkflowResult<PathBuf>,
+    pub superseded: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenameDispatch {
+    Dispatched,
+    Coalesced,
+    Skipped,
+}
+
+// Remembered while the note still exists so a later autosave can locate it after an
+// outside rename.
+fn remember_edit_identity(state: &mut WorkflowStateInner, path: &Path) {
+    if let Some(identity) = file_identity(path) {
+        state.edit_identity = Some((path.to_path_buf(), identity));
+    }
+}
+
+// Marks a rename in flight and builds its request; the caller enqueues it once the state
+// lock is released. `None` when the open note is outside the vault.
+fn reserve_rename(
+    state: &mut WorkflowStateInner,
+    singleline_value: String,
+    user_document_dir: PathBuf,
+    now_local: DateTime<Local>,
+) -> Option<RenameFileRequest> {
+    let current_path = state
+        .current_edit_path
+        .clone()
+        .filter(|path| is_path_in_vault(path, user_document_dir.as_path()))?;
+    state.rename.in_flight = true;
+    Some(RenameFileRequest {
+        user_document_dir,
+        current_path,
+        singleline_value,
+        now: now_local,
+        extension: state.new_note_extension,
+    })
+}
+
+fn record_transition(
+    state: &WorkflowStateInner,
+    transition: crate::workflow_journal::WorkflowTransition,
+) {
+    if let Some(journal) = state.transition_journal.as_ref()
+        && let Err(error) = journal.append(&transition)
+    {
+        crate::log::trace_debug(format!(
+            "workflow journal append failed transition={transition:?} error={error}"
+        ));
+    }
 }
 
 fn rollback_new_to_neutral(state: &mut WorkflowStateInner) {
@@ -457,6 +2239,89 @@ fn rollback_new_to_neutral(state: &mut WorkflowStateInner) {
     state.current_edit_path = None;
 }
 
+fn begin_create_from_neutral(state: &mut WorkflowStateInner, now_instant: Instant) -> bool {
+    if state.state != SinglelineFileState::Neutral {
+        return false;
+    }
+
+    if let Some(last) = state.last_create_event_raised_at {
+        let ready = now_instant
+            .checked_duration_since(last)
+            .map(|elapsed| elapsed > CREATE_EVENT_MIN_INTERVAL)
+            .unwrap_or(false);
+        if !ready {
+            return false;
+        }
+    }
+
+    state.state = SinglelineFileState::New;
+    state.last_create_event_raised_at = Some(now_instant);
+    true
+}
+
+fn create_from_neutral_event(
+    state: &mut WorkflowStateInner,
+    create: CreateFileRequest,
+) -> FileWorkflowEvent {
+    match state.next_note_template.take() {
+        Some(template) => {
+            FileWorkflowEvent::CreateFromTemplate(CreateFromTemplateRequest { create, template })
+        }
+        None => FileWorkflowEvent::Create(create),
+    }
+}
+
+fn complete_create_from_new(
+    state: &mut WorkflowStateInner,
+    result: WorkflowResult<FileWorkflowEventResult>,
+) -> WorkflowResult<Option<PathBuf>> {
+    // Another transition (e.g. open_file or an RPC pin) may have moved the workflow
+    // out of NEW while an async create was in flight; keep that newer state.
+    let still_new = state.state == SinglelineFileState::New;
+    match result {
+        Ok(FileWorkflowEventResult::Created { path }) => {
+            if still_new {
+                state.state = SinglelineFileState::Edit;
+                state.current_edit_path = Some(path.clone());
+                record_transition(
+                    state,
+                    crate::workflow_journal::WorkflowTransition::Created { path: path.clone() },
+                );
+            }
+            Ok(Some(path))
+        }
+        Ok(
+            FileWorkflowEventResult::Renamed { .. }
+            | FileWorkflowEventResult::AutoSaved { .. }
+            | FileWorkflowEventResult::RpcPinned { .. }
+            | FileWorkflowEventResult::BulkReplaced { .. }
+            | FileWorkflowEventResult::Moved { .. }
+            | FileWorkflowEventResult::DirCreated { .. }
+            | FileWorkflowEventResult::LinksRewritten { .. }
+            | FileWorkflowEventResult::FolderImported { .. }
+            | FileWorkflowEventResult::NotesFileImported { .. }
+            | FileWorkflowEventResult::PlainTextExported { .. }
+            | FileWorkflowEventResult::Snapshotted
+            | FileWorkflowEventResult::ConflictMerged { .. },
+        ) => {
+            if still_new {
+                rollback_new_to_neutral(state);
+            }
+            debug_assert!(
+                false,
+                "create invariant violation: create event must only return Created"
+            );
+            Ok(None)
+        }
+        Err(error) => {
+            if still_new {
+                rollback_new_to_neutral(state);
+            }
+            Err(error)
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SinglelineCreateFileWorkflow {
     inner: Arc<Mutex<WorkflowStateInner>>,
@@ -474,6 +2339,13 @@ impl SinglelineCreateFileWorkflow {
                 state: SinglelineFileState::Neutral,
                 current_edit_path: None,
                 last_create_event_raised_at: None,
+                new_note_extension: NoteFileExtension::default(),
+                next_note_template: None,
+                edit_identity: None,
+                split_edit_path: None,
+                edit_path_updates: None,
+                rename: RenameCoalescing::default(),
+                transition_journal: None,
             })),
             dispatcher,
         }
@@ -498,45 +2370,294 @@ impl SinglelineCreateFileWorkflow {
         self.snapshot().current_edit_path
     }
 
-    pub fn reset_startup_to_neutral(&self) {
-        let mut state = self
+    pub fn split_edit_path(&self) -> Option<PathBuf> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .split_edit_path
+            .clone()
+    }
+
+    pub fn bind_split_edit(&self, path: Option<PathBuf>) {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .split_edit_path = path;
+    }
+
+    /// Whether a payload for `path` still belongs to the note being edited, including one
+    /// renamed away by a title edit that the autosave should follow.
+    pub fn edits_path(&self, path: &Path) -> bool {
+        let state = self
             .inner
             .lock()
             .unwrap_or_else(|poisoned| poisoned.into_inner());
-        state.state = SinglelineFileState::Neutral;
-        state.current_edit_path = None;
+        state.state == SinglelineFileState::Edit
+            && (state.current_edit_path.as_deref() == Some(path)
+                || state.rename.renamed_away.iter().any(|old| old == path))
     }
 
-    pub fn set_edit_from_open_file(&self, path: PathBuf) {
+    /// Drains the worker queue, then waits for the transitions it logged to reach the disk.
+    pub fn shutdown_dispatcher(&self) {
+        self.dispatcher.shutdown();
+        let journal = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .transition_journal
+            .clone();
+        if let Some(journal) = journal {
+            journal.flush();
+        }
+    }
+
+    pub fn new_note_extension(&self) -> NoteFileExtension {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .new_note_extension
+    }
+
+    pub fn set_new_note_extension(&self, extension: NoteFileExtension) {
         let mut state = self
             .inner
             .lock()
             .unwrap_or_else(|poisoned| poisoned.into_inner());
-        state.state = SinglelineFileState::Edit;
-        state.current_edit_path = Some(path);
+        state.new_note_extension = extension;
     }
 
-    pub fn transition_edit_to_neutral(&self) -> bool {
+    /// Template body for the next create from NEUTRAL only; cleared by that create.
+    pub fn set_next_note_template(&self, template: Option<String>) {
         let mut state = self
             .inner
             .lock()
             .unwrap_or_else(|poisoned| poisoned.into_inner());
-        if state.state != SinglelineFileState::Edit {
-            return false;
-        }
+        state.next_note_template = template;
+    }
 
+    pub fn reset_startup_to_neutral(&self) {
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
         state.state = SinglelineFileState::Neutral;
         state.current_edit_path = None;
-        true
     }
 
-    pub fn try_create_from_neutral(
-        &self,
-        singleline_value: &str,
+    pub fn set_edit_from_open_file(&self, path: PathBuf) {
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.state = SinglelineFileState::Edit;
+        state.rename.renamed_away.clear();
+        remember_edit_identity(&mut state, path.as_path());
+        record_transition(
+            &state,
+            crate::workflow_journal::WorkflowTransition::Opened { path: path.clone() },
+        );
+        state.current_edit_path = Some(path);
+    }
+
+    /// Every later transition is appended to `journal`, so the next start can rebuild the
+    /// state with `replay_workflow_journal`.
+    pub fn attach_transition_journal(&self, journal: crate::workflow_journal::WorkflowJournal) {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .transition_journal = Some(journal);
+    }
+
+    /// Paths the workflow moved the open note to on its own (daily relocation, recovery after
+    /// an outside rename), so the UI components can follow.
+    pub fn subscribe_edit_path_updates(&self) -> smol::channel::Receiver<PathBuf> {
+        let (tx, rx) = smol::channel::unbounded();
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .edit_path_updates = Some(tx);
+        rx
+    }
+
+    pub fn set_queue_limits(&self, limits: QueueLimits) {
+        self.dispatcher.set_queue_limits(limits);
+    }
+
+    pub fn subscribe_dispatcher_stalls(&self) -> smol::channel::Receiver<DispatcherStall> {
+        self.dispatcher.subscribe_stalls()
+    }
+
+    pub fn subscribe_rename_outcomes(&self) -> smol::channel::Receiver<RenameOutcome> {
+        let (tx, rx) = smol::channel::unbounded();
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .rename
+            .outcomes = Some(tx);
+        rx
+    }
+
+    /// Non-blocking title rename for the UI thread. While a rename is on the worker the
+    /// newest title waits in a single slot and is renamed once the running one finishes.
+    /// Renames `note_path` after `singleline_value`. Skipped unless that note is still the one
+    /// being edited; a path it was already renamed away from still counts as the same note.
+    pub fn try_rename_in_edit_async(
+        &self,
+        note_path: &Path,
+        singleline_value: &str,
+        user_document_dir: &Path,
+        now_local: DateTime<Local>,
+    ) -> WorkflowResult<RenameDispatch> {
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Some(current_path) = state.current_edit_path.as_deref() else {
+            return Ok(RenameDispatch::Skipped);
+        };
+        let same_note = current_path == note_path
+            || state.rename.renamed_away.iter().any(|old| old == note_path);
+        if state.state != SinglelineFileState::Edit || !same_note {
+            return Ok(RenameDispatch::Skipped);
+        }
+        if state.rename.in_flight {
+            state.rename.pending = Some((
+                singleline_value.to_string(),
+                user_document_dir.to_path_buf(),
+                now_local,
+            ));
+            return Ok(RenameDispatch::Coalesced);
+        }
+        let request = reserve_rename(
+            &mut state,
+            singleline_value.to_string(),
+            user_document_dir.to_path_buf(),
+            now_local,
+        );
+        drop(state);
+        if let Some(request) = request {
+            self.enqueue_rename(request)?;
+        }
+        Ok(RenameDispatch::Dispatched)
+    }
+
+    // Called without the state lock: a full queue can make the enqueue wait, and the UI thread
+    // must not wait with it.
+    fn enqueue_rename(&self, request: RenameFileRequest) -> WorkflowResult<()> {
+        let workflow = self.clone();
+        let from_path = request.current_path.clone();
+        let value = request.singleline_value.clone();
+        let enqueued = self
+            .dispatcher
+            .dispatch_async(FileWorkflowEvent::Rename(request), move |result| {
+                workflow.complete_async_rename(from_path, value, result)
+            });
+        if enqueued.is_err() {
+            let mut state = self
+                .inner
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.rename.in_flight = false;
+            // Titles coalesced behind the rename that never ran would otherwise wait forever.
+            state.rename.pending = None;
+        }
+        enqueued
+    }
+
+    // Runs on the worker thread once a queued rename has been processed.
+    fn complete_async_rename(
+        &self,
+        from_path: PathBuf,
+        singleline_value: String,
+        result: WorkflowResult<FileWorkflowEventResult>,
+    ) {
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.rename.in_flight = false;
+        let result = result.and_then(|result| match result {
+            FileWorkflowEventResult::Renamed { path } => Ok(path),
+            _ => Err(WorkflowError::UnexpectedResult { event: "rename" }),
+        });
+        // The user may have opened another note while the rename ran; its title is not ours.
+        let still_editing = state.state == SinglelineFileState::Edit
+            && state.current_edit_path.as_ref() == Some(&from_path);
+        if !still_editing {
+            state.rename.pending = None;
+            return;
+        }
+        if let Ok(path) = result.as_ref() {
+            if *path != from_path {
+                state.rename.renamed_away.push(from_path.clone());
+                record_transition(
+                    &state,
+                    crate::workflow_journal::WorkflowTransition::Renamed {
+                        from: from_path.clone(),
+                        to: path.clone(),
+                    },
+                );
+            }
+            state.current_edit_path = Some(path.clone());
+            remember_edit_identity(&mut state, path.as_path());
+        }
+        let pending = state.rename.pending.take();
+        let superseded = pending.is_some();
+        let next_request = pending.and_then(|(value, user_document_dir, now_local)| {
+            reserve_rename(&mut state, value, user_document_dir, now_local)
+        });
+        let outcomes = state.rename.outcomes.clone();
+        drop(state);
+        if let Some(outcomes) = outcomes.as_ref() {
+            let _ = outcomes.try_send(RenameOutcome {
+                singleline_value,
+                result,
+                superseded,
+            });
+        }
+        let Some(request) = next_request else {
+            return;
+        };
+        let value = request.singleline_value.clone();
+        if let Err(error) = self.enqueue_rename(request) {
+            crate::log::trace_debug(format!(
+                "rename_flow coalesced dispatch failed error={error}"
+            ));
+            // Otherwise the newest title would fail with nothing but a trace line.
+            if let Some(outcomes) = outcomes.as_ref() {
+                let _ = outcomes.try_send(RenameOutcome {
+                    singleline_value: value,
+                    result: Err(error),
+                    superseded: false,
+                });
+            }
+        }
+    }
+
+    pub fn transition_edit_to_neutral(&self) -> bool {
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if state.state != SinglelineFileState::Edit {
+            return false;
+        }
+
+        state.state = SinglelineFileState::Neutral;
+        state.current_edit_path = None;
+        state.rename.renamed_away.clear();
+        record_transition(&state, crate::workflow_journal::WorkflowTransition::Cleared);
+        true
+    }
+
+    #[cfg(test)]
+    pub fn try_create_from_neutral(
+        &self,
+        singleline_value: &str,
         user_document_dir: &Path,
         now_instant: Instant,
         now_local: DateTime<Local>,
-    ) -> io::Result<Option<PathBuf>> {
+    ) -> WorkflowResult<Option<PathBuf>> {
         // Keep workflow-state lock across dispatch to serialize workflow transitions
         // with file-update side effects. This lock does not participate in queue-state
         // lock ordering and therefore does not introduce lock cycles.
@@ -544,63 +2665,75 @@ impl SinglelineCreateFileWorkflow {
             .inner
             .lock()
             .unwrap_or_else(|poisoned| poisoned.into_inner());
-        if state.state != SinglelineFileState::Neutral {
+        if !begin_create_from_neutral(&mut state, now_instant) {
             return Ok(None);
         }
 
-        if let Some(last) = state.last_create_event_raised_at {
-            let ready = now_instant
-                .checked_duration_since(last)
-                .map(|elapsed| elapsed > CREATE_EVENT_MIN_INTERVAL)
-                .unwrap_or(false);
-            if !ready {
-                return Ok(None);
-            }
-        }
+        let create = CreateFileRequest {
+            user_document_dir: user_document_dir.to_path_buf(),
+            singleline_value: singleline_value.to_string(),
+            now: now_local,
+            extension: state.new_note_extension,
+        };
+        let event = create_from_neutral_event(&mut state, create);
+        let result = self.dispatcher.dispatch_blocking(event);
+        complete_create_from_new(&mut state, result)
+    }
 
-        state.state = SinglelineFileState::New;
-        state.last_create_event_raised_at = Some(now_instant);
+    /// Non-blocking variant of [`Self::try_create_from_neutral`]. Returns `Ok(false)`
+    /// when the state/throttle gate rejects the request. Otherwise the workflow stays
+    /// in NEW until the worker finishes; the NEW -> EDIT (or rollback) transition is
+    /// applied before `on_complete` runs on the worker thread.
+    pub fn try_create_from_neutral_async<F>(
+        &self,
+        singleline_value: &str,
+        user_document_dir: &Path,
+        now_instant: Instant,
+        now_local: DateTime<Local>,
+        on_complete: F,
+    ) -> WorkflowResult<bool>
+    where
+        F: FnOnce(WorkflowResult<Option<PathBuf>>) + Send + 'static,
+    {
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if !begin_create_from_neutral(&mut state, now_instant) {
+            return Ok(false);
+        }
 
-        let result =
-            match self
-                .dispatcher
-                .dispatch_blocking(FileWorkflowEvent::Create(CreateFileRequest {
-                    user_document_dir: user_document_dir.to_path_buf(),
-                    singleline_value: singleline_value.to_string(),
-                    now: now_local,
-                })) {
-                Ok(result) => result,
-                Err(error) => {
-                    rollback_new_to_neutral(&mut state);
-                    return Err(error);
-                }
+        let inner = self.inner.clone();
+        let create = CreateFileRequest {
+            user_document_dir: user_document_dir.to_path_buf(),
+            singleline_value: singleline_value.to_string(),
+            now: now_local,
+            extension: state.new_note_extension,
+        };
+        let event = create_from_neutral_event(&mut state, create);
+        let enqueued = self.dispatcher.dispatch_async(event, move |result| {
+            let completed = {
+                let mut state = inner
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                complete_create_from_new(&mut state, result)
             };
-
-        match result {
-            FileWorkflowEventResult::Created { path } => {
-                state.state = SinglelineFileState::Edit;
-                state.current_edit_path = Some(path.clone());
-                Ok(Some(path))
-            }
-            FileWorkflowEventResult::Renamed { .. }
-            | FileWorkflowEventResult::AutoSaved { .. }
-            | FileWorkflowEventResult::RpcPinned { .. } => {
-                rollback_new_to_neutral(&mut state);
-                debug_assert!(
-                    false,
-                    "create invariant violation: create event must only return Created"
-                );
-                Ok(None)
-            }
+            on_complete(completed);
+        });
+        if let Err(error) = enqueued {
+            rollback_new_to_neutral(&mut state);
+            return Err(error);
         }
+        Ok(true)
     }
 
+    #[cfg(test)]
     pub fn try_rename_in_edit(
         &self,
         singleline_value: &str,
         user_document_dir: &Path,
         now_local: DateTime<Local>,
-    ) -> io::Result<Option<PathBuf>> {
+    ) -> WorkflowResult<Option<PathBuf>> {
         let mut state = self
             .inner
             .lock()
@@ -619,6 +2752,7 @@ impl SinglelineCreateFileWorkflow {
                 current_path,
                 singleline_value: singleline_value.to_string(),
                 now: now_local,
+                extension: state.new_note_extension,
             }))?;
 
         match result {
@@ -628,7 +2762,16 @@ impl SinglelineCreateFileWorkflow {
             }
             FileWorkflowEventResult::Created { .. }
             | FileWorkflowEventResult::AutoSaved { .. }
-            | FileWorkflowEventResult::RpcPinned { .. } => {
+            | FileWorkflowEventResult::RpcPinned { .. }
+            | FileWorkflowEventResult::BulkReplaced { .. }
+            | FileWorkflowEventResult::Moved { .. }
+            | FileWorkflowEventResult::DirCreated { .. }
+            | FileWorkflowEventResult::LinksRewritten { .. }
+            | FileWorkflowEventResult::FolderImported { .. }
+            | FileWorkflowEventResult::NotesFileImported { .. }
+            | FileWorkflowEventResult::PlainTextExported { .. }
+            | FileWorkflowEventResult::Snapshotted
+            | FileWorkflowEventResult::ConflictMerged { .. } => {
                 debug_assert!(
                     false,
                     "rename invariant violation: rename event must only return Renamed"
@@ -638,28 +2781,63 @@ impl SinglelineCreateFileWorkflow {
         }
     }
 
-    pub fn try_autosave_in_edit(&self, payload: EditorAutoSavePayload) -> io::Result<bool> {
+    /// Saves the open note; returns the path actually written, which the workflow may have
+    /// moved (daily relocation, a rename that landed meanwhile). None when nothing is open.
+    pub fn try_autosave_in_edit(
+        &self,
+        payload: EditorAutoSavePayload,
+    ) -> WorkflowResult<Option<PathBuf>> {
         let mut state = self
             .inner
             .lock()
             .unwrap_or_else(|poisoned| poisoned.into_inner());
         if state.state != SinglelineFileState::Edit {
-            return Ok(false);
+            return Ok(None);
         }
-        let Some(current_path) = state.current_edit_path.as_ref() else {
-            return Ok(false);
+        let Some(current_path) = state.current_edit_path.clone() else {
+            return Ok(None);
         };
-        if *current_path != payload.current_path {
-            return Ok(false);
+        let mut payload = payload;
+        if current_path != payload.current_path {
+            if !state.rename.renamed_away.contains(&payload.current_path) {
+                return Ok(None);
+            }
+            crate::log::trace_debug(format!(
+                "autosave follows title rename old={} new={}",
+                payload.current_path.display(),
+                current_path.display()
+            ));
+            payload.current_path = current_path.clone();
         }
+        remember_edit_identity(&mut state, current_path.as_path());
+        let previous_identity = state
+            .edit_identity
+            .as_ref()
+            .filter(|(path, _)| *path == current_path)
+            .map(|(_, identity)| *identity);
+        // Async rename completions lock the state on the worker thread, so the lock must not
+        // be held while waiting for the worker.
+        drop(state);
 
         let result = self
             .dispatcher
             .dispatch_blocking(FileWorkflowEvent::AutoSave(AutoSaveFileRequest {
                 payload: payload.clone(),
+                previous_identity,
+                detached: false,
             }))?;
 
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
         match result {
+            // A rename or note switch that landed meanwhile owns the path now.
+            FileWorkflowEventResult::AutoSaved { path }
+                if state.current_edit_path.as_ref() != Some(&current_path) =>
+            {
+                Ok(Some(path))
+            }
             FileWorkflowEventResult::AutoSaved { path } => {
                 if state.current_edit_path.as_ref() != Some(&path) {
                     let previous = state
@@ -672,18 +2850,40 @@ impl SinglelineCreateFileWorkflow {
                         previous,
                         path.display()
                     ));
+                    if let Some(updates) = state.edit_path_updates.as_ref() {
+                        let _ = updates.try_send(path.clone());
+                    }
+                    if let Some(from) = state.current_edit_path.clone() {
+                        record_transition(
+                            &state,
+                            crate::workflow_journal::WorkflowTransition::Relocated {
+                                from,
+                                to: path.clone(),
+                            },
+                        );
+                    }
                 }
-                state.current_edit_path = Some(path);
-                Ok(true)
+                remember_edit_identity(&mut state, path.as_path());
+                state.current_edit_path = Some(path.clone());
+                Ok(Some(path))
             }
             FileWorkflowEventResult::Created { .. }
             | FileWorkflowEventResult::Renamed { .. }
-            | FileWorkflowEventResult::RpcPinned { .. } => {
+            | FileWorkflowEventResult::RpcPinned { .. }
+            | FileWorkflowEventResult::BulkReplaced { .. }
+            | FileWorkflowEventResult::Moved { .. }
+            | FileWorkflowEventResult::DirCreated { .. }
+            | FileWorkflowEventResult::LinksRewritten { .. }
+            | FileWorkflowEventResult::FolderImported { .. }
+            | FileWorkflowEventResult::NotesFileImported { .. }
+            | FileWorkflowEventResult::PlainTextExported { .. }
+            | FileWorkflowEventResult::Snapshotted
+            | FileWorkflowEventResult::ConflictMerged { .. } => {
                 debug_assert!(
                     false,
                     "autosave invariant violation: autosave event must only return AutoSaved"
                 );
-                Ok(false)
+                Ok(None)
             }
         }
     }
@@ -693,7 +2893,7 @@ impl SinglelineCreateFileWorkflow {
         user_document_dir: PathBuf,
         full_path: PathBuf,
         linenum: u32,
-    ) -> io::Result<RpcPinFileResult> {
+    ) -> WorkflowResult<RpcPinFileResult> {
         let result = self
             .dispatcher
             .dispatch_blocking(FileWorkflowEvent::RpcPin(RpcPinFileRequest {
@@ -714,101 +2914,631 @@ impl SinglelineCreateFileWorkflow {
             }),
             FileWorkflowEventResult::Created { .. }
             | FileWorkflowEventResult::Renamed { .. }
-            | FileWorkflowEventResult::AutoSaved { .. } => {
+            | FileWorkflowEventResult::AutoSaved { .. }
+            | FileWorkflowEventResult::BulkReplaced { .. }
+            | FileWorkflowEventResult::Moved { .. }
+            | FileWorkflowEventResult::DirCreated { .. }
+            | FileWorkflowEventResult::LinksRewritten { .. }
+            | FileWorkflowEventResult::FolderImported { .. }
+            | FileWorkflowEventResult::NotesFileImported { .. }
+            | FileWorkflowEventResult::PlainTextExported { .. }
+            | FileWorkflowEventResult::Snapshotted
+            | FileWorkflowEventResult::ConflictMerged { .. } => {
                 debug_assert!(
                     false,
                     "rpc-pin invariant violation: rpc pin event must only return RpcPinned"
                 );
-                Err(io::Error::other(
-                    "rpc-pin invariant violation: unexpected event result variant",
-                ))
+                Err(WorkflowError::UnexpectedResult { event: "rpc-pin" })
             }
         }
     }
 
-    pub fn flush_editor_content_in_edit(
+    /// `save_detached_payload` without waiting; `on_complete` runs on the worker thread.
+    pub fn save_detached_payload_async<F>(
         &self,
-        editor_text: &str,
-        user_document_dir: &Path,
-    ) -> io::Result<bool> {
-        let snapshot = self.snapshot();
-        if snapshot.state != SinglelineFileState::Edit {
-            return Ok(false);
-        }
-        let Some(current_path) = snapshot.current_edit_path else {
-            return Ok(false);
-        };
-
-        self.try_autosave_in_edit(EditorAutoSavePayload {
-            user_document_dir: user_document_dir.to_path_buf(),
-            current_path,
-            editor_text: editor_text.to_string(),
-        })
+        payload: EditorAutoSavePayload,
+        on_complete: F,
+    ) -> WorkflowResult<()>
+    where
+        F: FnOnce(WorkflowResult<PathBuf>) + Send + 'static,
+    {
+        self.dispatcher.dispatch_async(
+            FileWorkflowEvent::AutoSave(AutoSaveFileRequest {
+                payload,
+                previous_identity: None,
+                detached: true,
+            }),
+            move |result| {
+                on_complete(result.and_then(|result| match result {
+                    FileWorkflowEventResult::AutoSaved { path } => Ok(path),
+                    FileWorkflowEventResult::Created { .. }
+                    | FileWorkflowEventResult::Renamed { .. }
+                    | FileWorkflowEventResult::RpcPinned { .. }
+                    | FileWorkflowEventResult::BulkReplaced { .. }
+                    | FileWorkflowEventResult::Moved { .. }
+                    | FileWorkflowEventResult::DirCreated { .. }
+                    | FileWorkflowEventResult::LinksRewritten { .. }
+                    | FileWorkflowEventResult::FolderImported { .. }
+                    | FileWorkflowEventResult::NotesFileImported { .. }
+                    | FileWorkflowEventResult::PlainTextExported { .. }
+                    | FileWorkflowEventResult::Snapshotted
+                    | FileWorkflowEventResult::ConflictMerged { .. } => {
+                        Err(WorkflowError::UnexpectedResult { event: "autosave" })
+                    }
+                }));
+            },
+        )
     }
-}
 
-pub fn invalid_filename_char(ch: char) -> bool {
-    matches!(ch, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') || ch.is_control()
-}
+    /// Saves a pending payload for a note that is no longer the one being edited. It is
+    /// written where it is: no daily relocation and no re-creation if it has gone.
+    pub fn save_detached_payload(&self, payload: EditorAutoSavePayload) -> WorkflowResult<PathBuf> {
+        let result = self
+            .dispatcher
+            .dispatch_blocking(FileWorkflowEvent::AutoSave(AutoSaveFileRequest {
+                payload,
+                previous_identity: None,
+                detached: true,
+            }))?;
+        match result {
+            FileWorkflowEventResult::AutoSaved { path } => Ok(path),
+            FileWorkflowEventResult::Created { .. }
+            | FileWorkflowEventResult::Renamed { .. }
+            | FileWorkflowEventResult::RpcPinned { .. }
+            | FileWorkflowEventResult::BulkReplaced { .. }
+            | FileWorkflowEventResult::Moved { .. }
+            | FileWorkflowEventResult::DirCreated { .. }
+            | FileWorkflowEventResult::LinksRewritten { .. }
+            | FileWorkflowEventResult::FolderImported { .. }
+            | FileWorkflowEventResult::NotesFileImported { .. }
+            | FileWorkflowEventResult::PlainTextExported { .. }
+            | FileWorkflowEventResult::Snapshotted
+            | FileWorkflowEventResult::ConflictMerged { .. } => {
+                debug_assert!(
+                    false,
+                    "autosave invariant violation: autosave event must only return AutoSaved"
+                );
+                Err(WorkflowError::UnexpectedResult { event: "autosave" })
+            }
+        }
+    }
 
-pub fn sanitize_filename_stem(raw: &str) -> String {
-    let replaced: String = raw
-        .chars()
-        .map(|ch| if invalid_filename_char(ch) { '_' } else { ch })
-        .collect();
-    replaced.chars().take(MAX_FILE_STEM_CHARS).collect()
-}
+    /// Queues a bulk replace behind any in-flight create/rename/autosave events so the
+    /// rewrites never interleave with other writes. `on_complete` runs on the worker thread.
+    pub fn bulk_replace_async<F>(
+        &self,
+        request: BulkReplaceRequest,
+        on_complete: F,
+    ) -> WorkflowResult<()>
+    where
+        F: FnOnce(WorkflowResult<BulkReplaceReport>) + Send + 'static,
+    {
+        self.dispatcher
+            .dispatch_async(FileWorkflowEvent::BulkReplace(request), move |result| {
+                on_complete(result.and_then(|result| match result {
+                    FileWorkflowEventResult::BulkReplaced { report } => Ok(report),
+                    FileWorkflowEventResult::Created { .. }
+                    | FileWorkflowEventResult::Renamed { .. }
+                    | FileWorkflowEventResult::AutoSaved { .. }
+                    | FileWorkflowEventResult::RpcPinned { .. }
+                    | FileWorkflowEventResult::Moved { .. }
+                    | FileWorkflowEventResult::DirCreated { .. }
+                    | FileWorkflowEventResult::LinksRewritten { .. }
+                    | FileWorkflowEventResult::FolderImported { .. }
+                    | FileWorkflowEventResult::NotesFileImported { .. }
+                    | FileWorkflowEventResult::PlainTextExported { .. }
+                    | FileWorkflowEventResult::Snapshotted
+                    | FileWorkflowEventResult::ConflictMerged { .. } => {
+                        Err(WorkflowError::UnexpectedResult {
+                            event: "bulk-replace",
+                        })
+                    }
+                }));
+            })
+    }
 
-pub fn notitle_stem(now: DateTime<Local>) -> String {
-    format!("notitle-{}", now.format("%Y%m%d%H%M%S%3f"))
-}
+    /// Queues a link rewrite batch behind other workflow events, like `bulk_replace_async`.
+    pub fn rewrite_links_async<F>(
+        &self,
+        request: LinkRewriteRequest,
+        on_complete: F,
+    ) -> WorkflowResult<()>
+    where
+        F: FnOnce(WorkflowResult<BulkReplaceReport>) + Send + 'static,
+    {
+        self.dispatcher
+            .dispatch_async(FileWorkflowEvent::RewriteLinks(request), move |result| {
+                on_complete(result.and_then(|result| match result {
+                    FileWorkflowEventResult::LinksRewritten { report } => Ok(report),
+                    FileWorkflowEventResult::Created { .. }
+                    | FileWorkflowEventResult::Renamed { .. }
+                    | FileWorkflowEventResult::AutoSaved { .. }
+                    | FileWorkflowEventResult::RpcPinned { .. }
+                    | FileWorkflowEventResult::BulkReplaced { .. }
+                    | FileWorkflowEventResult::Moved { .. }
+                    | FileWorkflowEventResult::DirCreated { .. }
+                    | FileWorkflowEventResult::FolderImported { .. }
+                    | FileWorkflowEventResult::NotesFileImported { .. }
+                    | FileWorkflowEventResult::PlainTextExported { .. }
+                    | FileWorkflowEventResult::Snapshotted
+                    | FileWorkflowEventResult::ConflictMerged { .. } => {
+                        Err(WorkflowError::UnexpectedResult {
+                            event: "link-rewrite",
+                        })
+                    }
+                }));
+            })
+    }
 
-pub fn stem_from_singleline_value(value: &str, now: DateTime<Local>) -> String {
-    if value.is_empty() {
-        return notitle_stem(now);
+    /// Queues a folder import; `on_complete` runs on the worker thread.
+    pub fn folder_import_async<F>(
+        &self,
+        request: FolderImportRequest,
+        on_complete: F,
+    ) -> WorkflowResult<()>
+    where
+        F: FnOnce(WorkflowResult<crate::folder_import::FolderImportReport>) + Send + 'static,
+    {
+        self.dispatcher
+            .dispatch_async(FileWorkflowEvent::ImportFolder(request), move |result| {
+                on_complete(result.and_then(|result| match result {
+                    FileWorkflowEventResult::FolderImported { report } => Ok(report),
+                    _ => Err(WorkflowError::UnexpectedResult {
+                        event: "folder-import",
+                    }),
+                }));
+            })
     }
 
-    let sanitized = sanitize_filename_stem(value);
-    if sanitized.is_empty() {
-        return notitle_stem(now);
+    /// Queues an ENEX/OPML import; `on_complete` runs on the worker thread.
+    pub fn notes_file_import_async<F>(
+        &self,
+        request: NotesFileImportRequest,
+        on_complete: F,
+    ) -> WorkflowResult<()>
+    where
+        F: FnOnce(WorkflowResult<crate::notes_import::NotesImportSummary>) + Send + 'static,
+    {
+        self.dispatcher
+            .dispatch_async(FileWorkflowEvent::ImportNotesFile(request), move |result| {
+                on_complete(result.and_then(|result| match result {
+                    FileWorkflowEventResult::NotesFileImported { summary } => Ok(summary),
+                    _ => Err(WorkflowError::UnexpectedResult {
+                        event: "notes-file-import",
+                    }),
+                }));
+            })
     }
 
-    sanitized
-}
+    /// Queues a plain-text export behind pending saves; `on_complete` runs on the worker thread.
+    pub fn plain_text_export_async<F>(
+        &self,
+        request: PlainTextExportRequest,
+        on_complete: F,
+    ) -> WorkflowResult<()>
+    where
+        F: FnOnce(WorkflowResult<crate::export::PlainTextExportReport>) + Send + 'static,
+    {
+        self.dispatcher
+            .dispatch_async(FileWorkflowEvent::PlainTextExport(request), move |result| {
+                on_complete(result.and_then(|result| match result {
+                    FileWorkflowEventResult::PlainTextExported { report } => Ok(report),
+                    _ => Err(WorkflowError::UnexpectedResult {
+                        event: "plain-text-export",
+                    }),
+                }));
+            })
+    }
 
-#[cfg(test)]
-pub fn daily_directory(user_document_dir: &Path, now: DateTime<Local>) -> PathBuf {
-    user_document_dir.join(now.format("%Y/%m/%d").to_string())
-}
+    /// Queues a copy of `source`; `on_complete` runs on the worker thread with the copy's path.
+    pub fn duplicate_note_async<F>(&self, source: PathBuf, on_complete: F) -> WorkflowResult<()>
+    where
+        F: FnOnce(WorkflowResult<PathBuf>) + Send + 'static,
+    {
+        self.dispatcher.dispatch_async(
+            FileWorkflowEvent::Duplicate(DuplicateNoteRequest { source }),
+            move |result| {
+                on_complete(result.and_then(|result| match result {
+                    FileWorkflowEventResult::Duplicated { path } => Ok(path),
+                    _ => Err(WorkflowError::UnexpectedResult { event: "duplicate" }),
+                }));
+            },
+        )
+    }
 
-pub fn ensure_daily_directory(
-    user_document_dir: &Path,
-    now: DateTime<Local>,
-) -> io::Result<PathBuf> {
-    let dir = user_document_dir.join(now.format("%Y/%m/%d").to_string());
-    fs::create_dir_all(&dir)?;
-    Ok(dir)
-}
+    /// Queues a snapshot of `paths`; `on_complete` runs on the worker thread once it is taken.
+    pub fn snapshot_async<F>(
+        &self,
+        reason: &str,
+        paths: Vec<PathBuf>,
+        on_complete: F,
+    ) -> WorkflowResult<()>
+    where
+        F: FnOnce(WorkflowResult<()>) + Send + 'static,
+    {
+        self.dispatcher.dispatch_async(
+            FileWorkflowEvent::Snapshot(SnapshotRequest {
+                reason: reason.to_string(),
+                paths,
+            }),
+            move |result| {
+                on_complete(result.and_then(|result| match result {
+                    FileWorkflowEventResult::Snapshotted => Ok(()),
+                    _ => Err(WorkflowError::UnexpectedResult { event: "snapshot" }),
+                }));
+            },
+        )
+    }
 
-fn comparable_path_for_daily_directory(path: &Path) -> PathBuf {
-    let path_str = path.to_string_lossy();
-    if let Some(stripped) = path_str.strip_prefix(r"\\?\") {
-        return PathBuf::from(stripped);
+    /// Queues a conflict merge; `on_complete` runs on the worker thread with the reason the
+    /// conflicted copy was kept, if it was.
+    pub fn merge_conflict_async<F>(
+        &self,
+        request: ConflictMergeRequest,
+        on_complete: F,
+    ) -> WorkflowResult<()>
+    where
+        F: FnOnce(WorkflowResult<Option<String>>) + Send + 'static,
+    {
+        self.dispatcher
+            .dispatch_async(FileWorkflowEvent::MergeConflict(request), move |result| {
+                on_complete(result.and_then(|result| match result {
+                    FileWorkflowEventResult::ConflictMerged { copy_kept, .. } => Ok(copy_kept),
+                    _ => Err(WorkflowError::UnexpectedResult {
+                        event: "conflict-merge",
+                    }),
+                }));
+            })
     }
-    path.to_path_buf()
-}
 
-fn is_path_under_daily_directory(current_path: &Path, daily_dir: &Path) -> bool {
-    current_path
-        .parent()
-        .map(|parent| {
-            comparable_path_for_daily_directory(parent)
+    /// Creates a note from scratch-pane text without touching the NEUTRAL/NEW/EDIT state:
+    /// the promoted note is not opened in the editor.
+    pub fn promote_scratch_blocking(
+        &self,
+        first_line: &str,
+        content: &str,
+        user_document_dir: &Path,
+        now_local: DateTime<Local>,
+    ) -> WorkflowResult<PathBuf> {
+        let result = self
+            .dispatcher
+            .dispatch_blocking(FileWorkflowEvent::PromoteScratch(PromoteScratchRequest {
+                create: CreateFileRequest {
+                    user_document_dir: user_document_dir.to_path_buf(),
+                    singleline_value: first_line.to_string(),
+                    now: now_local,
+                    extension: self.new_note_extension(),
+                },
+                content: content.to_string(),
+            }))?;
+        match result {
+            FileWorkflowEventResult::Created { path } => Ok(path),
+            FileWorkflowEventResult::Renamed { .. }
+            | FileWorkflowEventResult::AutoSaved { .. }
+            | FileWorkflowEventResult::RpcPinned { .. }
+            | FileWorkflowEventResult::BulkReplaced { .. }
+            | FileWorkflowEventResult::Moved { .. }
+            | FileWorkflowEventResult::DirCreated { .. }
+            | FileWorkflowEventResult::LinksRewritten { .. }
+            | FileWorkflowEventResult::FolderImported { .. }
+            | FileWorkflowEventResult::NotesFileImported { .. }
+            | FileWorkflowEventResult::PlainTextExported { .. }
+            | FileWorkflowEventResult::Snapshotted
+            | FileWorkflowEventResult::ConflictMerged { .. } => {
+                Err(WorkflowError::UnexpectedResult {
+                    event: "scratch-promote",
+                })
+            }
+        }
+    }
+
+    /// Queues the create workflow for `singleline_value` without touching the NEUTRAL/NEW/EDIT
+    /// state, so the note the editor holds stays open; `on_complete` runs on the worker thread.
+    pub fn create_detached_async<F>(
+        &self,
+        singleline_value: &str,
+        user_document_dir: &Path,
+        now_local: DateTime<Local>,
+        on_complete: F,
+    ) -> WorkflowResult<()>
+    where
+        F: FnOnce(WorkflowResult<PathBuf>) + Send + 'static,
+    {
+        self.dispatcher.dispatch_async(
+            FileWorkflowEvent::Create(CreateFileRequest {
+                user_document_dir: user_document_dir.to_path_buf(),
+                singleline_value: singleline_value.to_string(),
+                now: now_local,
+                extension: self.new_note_extension(),
+            }),
+            move |result| {
+                on_complete(result.and_then(|result| match result {
+                    FileWorkflowEventResult::Created { path } => Ok(path),
+                    FileWorkflowEventResult::Renamed { .. }
+                    | FileWorkflowEventResult::AutoSaved { .. }
+                    | FileWorkflowEventResult::RpcPinned { .. }
+                    | FileWorkflowEventResult::BulkReplaced { .. }
+                    | FileWorkflowEventResult::Moved { .. }
+                    | FileWorkflowEventResult::DirCreated { .. }
+                    | FileWorkflowEventResult::LinksRewritten { .. }
+                    | FileWorkflowEventResult::FolderImported { .. }
+                    | FileWorkflowEventResult::NotesFileImported { .. }
+                    | FileWorkflowEventResult::PlainTextExported { .. }
+                    | FileWorkflowEventResult::Snapshotted
+                    | FileWorkflowEventResult::ConflictMerged { .. } => {
+                        Err(WorkflowError::UnexpectedResult {
+                            event: "create-detached",
+                        })
+                    }
+                }));
+            },
+        )
+    }
+
+    /// Moves a note or folder through the worker. When the open note is the moved entry, or
+    /// sits inside a moved folder, the edit path follows it.
+    pub fn move_entry_blocking(&self, from: &Path, to_dir: &Path) -> WorkflowResult<PathBuf> {
+        let result =
+            self.dispatcher
+                .dispatch_blocking(FileWorkflowEvent::Move(MoveEntryRequest {
+                    from: from.to_path_buf(),
+                    to_dir: to_dir.to_path_buf(),
+                }))?;
+        let FileWorkflowEventResult::Moved { path } = result else {
+            return Err(WorkflowError::UnexpectedResult { event: "move" });
+        };
+
+        let mut state = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(current_path) = state.current_edit_path.clone()
+            && let Ok(relative) = current_path.strip_prefix(from)
+        {
+            let moved_path = if relative.as_os_str().is_empty() {
+                path.clone()
+            } else {
+                path.join(relative)
+            };
+            crate::log::trace_debug(format!(
+                "move_flow edit path follows old={} new={}",
+                current_path.display(),
+                moved_path.display()
+            ));
+            record_transition(
+                &state,
+                crate::workflow_journal::WorkflowTransition::Relocated {
+                    from: current_path.clone(),
+                    to: moved_path.clone(),
+                },
+            );
+            state.rename.renamed_away.push(current_path);
+            state.current_edit_path = Some(moved_path);
+        }
+        if let Some(split_path) = state.split_edit_path.clone()
+            && let Ok(relative) = split_path.strip_prefix(from)
+        {
+            let moved_path = if relative.as_os_str().is_empty() {
+                path.clone()
+            } else {
+                path.join(relative)
+            };
+            crate::log::trace_debug(format!(
+                "move_flow split path follows old={} new={}",
+                split_path.display(),
+                moved_path.display()
+            ));
+            state.split_edit_path = Some(moved_path);
+        }
+        Ok(path)
+    }
+
+    pub fn create_dir_blocking(&self, parent: &Path, name: &str) -> WorkflowResult<PathBuf> {
+        let result = self
+            .dispatcher
+            .dispatch_blocking(FileWorkflowEvent::CreateDir(CreateDirRequest {
+                parent: parent.to_path_buf(),
+                name: name.to_string(),
+            }))?;
+        match result {
+            FileWorkflowEventResult::DirCreated { path } => Ok(path),
+            _ => Err(WorkflowError::UnexpectedResult {
+                event: "create-dir",
+            }),
+        }
+    }
+
+    pub fn flush_editor_content_in_edit(
+        &self,
+        editor_text: &str,
+        user_document_dir: &Path,
+    ) -> WorkflowResult<Option<PathBuf>> {
+        let snapshot = self.snapshot();
+        if snapshot.state != SinglelineFileState::Edit {
+            return Ok(None);
+        }
+        let Some(current_path) = snapshot.current_edit_path else {
+            return Ok(None);
+        };
+
+        self.try_autosave_in_edit(EditorAutoSavePayload {
+            user_document_dir: user_document_dir.to_path_buf(),
+            current_path,
+            editor_text: editor_text.to_string(),
+        })
+    }
+}
+
+pub fn invalid_filename_char(ch: char) -> bool {
+    matches!(ch, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') || ch.is_control()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilenameStemPreview {
+    pub stem: String,
+    pub replaced_chars: Vec<char>,
+    pub truncated_chars: usize,
+    // None when invalid characters are stripped rather than replaced.
+    pub replacement: Option<char>,
+    pub max_chars: usize,
+    pub reserved_name: bool,
+}
+
+impl FilenameStemPreview {
+    pub fn is_exact(&self) -> bool {
+        self.replaced_chars.is_empty() && self.truncated_chars == 0 && !self.reserved_name
+    }
+
+    // Short inline hint for the title field; None when the on-disk stem matches the input.
+    pub fn feedback_message(&self) -> Option<String> {
+        if self.is_exact() {
+            return None;
+        }
+        let mut parts = Vec::new();
+        if !self.replaced_chars.is_empty() {
+            let shown = self
+                .replaced_chars
+                .iter()
+                .map(|ch| {
+                    if ch.is_control() {
+                        format!("U+{:04X}", *ch as u32)
+                    } else {
+                        format!("'{ch}'")
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            match self.replacement {
+                Some(replacement) => {
+                    parts.push(format!("{shown} will be saved as '{replacement}'"))
+                }
+                None => parts.push(format!("{shown} will be left out")),
+            }
+        }
+        if self.truncated_chars > 0 {
+            parts.push(format!(
+                "name is cut to {} chars ({} dropped)",
+                self.max_chars, self.truncated_chars
+            ));
+        }
+        if self.reserved_name {
+            parts.push(format!("reserved on Windows, saved as '{}'", self.stem));
+        }
+        Some(parts.join("; "))
+    }
+}
+
+pub fn sanitize_filename_stem_preview(raw: &str) -> FilenameStemPreview {
+    sanitize_filename_stem_preview_with(raw, &crate::filename_rules::active_filename_rules())
+}
+
+pub(crate) fn sanitize_filename_stem_preview_with(
+    raw: &str,
+    rules: &crate::filename_rules::FilenameRules,
+) -> FilenameStemPreview {
+    use crate::filename_rules::InvalidCharPolicy;
+
+    let mut replaced_chars = Vec::new();
+    let mut stem = String::new();
+    let mut kept_chars = 0usize;
+    let mut truncated_chars = 0usize;
+    for ch in raw.chars() {
+        if kept_chars >= rules.max_stem_chars {
+            truncated_chars += 1;
+            continue;
+        }
+        if rules.is_invalid_char(ch) {
+            if !replaced_chars.contains(&ch) {
+                replaced_chars.push(ch);
+            }
+            match rules.invalid_chars {
+                InvalidCharPolicy::Replace(replacement) => stem.push(replacement),
+                InvalidCharPolicy::Strip => continue,
+            }
+        } else {
+            stem.push(ch);
+        }
+        kept_chars += 1;
+    }
+    let reserved_fix = rules.reserved_name_fix(&stem);
+    let reserved_name = reserved_fix.is_some();
+    if let Some(fixed) = reserved_fix {
+        stem = fixed;
+    }
+    FilenameStemPreview {
+        stem,
+        replaced_chars,
+        truncated_chars,
+        replacement: match rules.invalid_chars {
+            InvalidCharPolicy::Replace(replacement) => Some(replacement),
+            InvalidCharPolicy::Strip => None,
+        },
+        max_chars: rules.max_stem_chars,
+        reserved_name,
+    }
+}
+
+pub fn sanitize_filename_stem(raw: &str) -> String {
+    sanitize_filename_stem_preview(raw).stem
+}
+
+pub fn notitle_stem(now: DateTime<Local>) -> String {
+    format!("notitle-{}", now.format("%Y%m%d%H%M%S%3f"))
+}
+
+pub fn stem_from_singleline_value(value: &str, now: DateTime<Local>) -> String {
+    if value.is_empty() {
+        return notitle_stem(now);
+    }
+
+    let sanitized = sanitize_filename_stem(value);
+    if sanitized.is_empty() {
+        return notitle_stem(now);
+    }
+
+    sanitized
+}
+
+/// Where a note created at `now` belongs under the configured storage layout.
+pub fn note_directory(user_document_dir: &Path, now: DateTime<Local>) -> PathBuf {
+    crate::storage_layout::active_storage_layout().directory(user_document_dir, now)
+}
+
+pub fn ensure_note_directory(
+    user_document_dir: &Path,
+    now: DateTime<Local>,
+) -> io::Result<PathBuf> {
+    let dir = note_directory(user_document_dir, now);
+    fs::create_dir_all(&dir).map_err(at_path(&dir))?;
+    Ok(dir)
+}
+
+fn comparable_path_for_daily_directory(path: &Path) -> PathBuf {
+    let path_str = path.to_string_lossy();
+    if let Some(stripped) = path_str.strip_prefix(r"\\?\") {
+        return PathBuf::from(stripped);
+    }
+    path.to_path_buf()
+}
+
+/// Notes live under the notes folder; anything else was opened from outside and is edited
+/// in place, never moved or renamed.
+pub(crate) fn is_path_in_vault(path: &Path, user_document_dir: &Path) -> bool {
+    comparable_path_for_daily_directory(path)
+        .starts_with(comparable_path_for_daily_directory(user_document_dir))
+}
+
+fn is_path_under_daily_directory(current_path: &Path, daily_dir: &Path) -> bool {
+    current_path
+        .parent()
+        .map(|parent| {
+            comparable_path_for_daily_directory(parent)
                 == comparable_path_for_daily_directory(daily_dir)
         })
         .unwrap_or(false)
 }
 
-fn relocated_daily_candidate_path(
+pub(crate) fn relocated_daily_candidate_path(
     daily_dir: &Path,
     original_file_name: &str,
     suffix: usize,
@@ -847,7 +3577,21 @@ fn move_existing_file_to_daily_directory(
         ));
     }
 
-    let daily_dir = ensure_daily_directory(user_document_dir, now)?;
+    let filed_by_layout = current_path.parent().is_some_and(|parent| {
+        crate::storage_layout::active_storage_layout().is_layout_directory(
+            comparable_path_for_daily_directory(user_document_dir).as_path(),
+            comparable_path_for_daily_directory(parent).as_path(),
+        )
+    });
+    if !filed_by_layout {
+        crate::log::trace_debug(format!(
+            "req-newf35 daily-move skipped path={} is outside the layout folders",
+            current_path.display()
+        ));
+        return Ok(current_path.to_path_buf());
+    }
+
+    let daily_dir = ensure_note_directory(user_document_dir, now)?;
     if is_path_under_daily_directory(current_path, daily_dir.as_path()) {
         crate::log::trace_debug(format!(
             "req-newf35 daily-move noop path={} daily_dir={}",
@@ -874,16 +3618,12 @@ fn move_existing_file_to_daily_directory(
         daily_dir.display()
     ));
 
+    let backend = crate::storage_backend::storage_backend_for(user_document_dir);
     let mut suffix = 1usize;
     loop {
         let target =
             relocated_daily_candidate_path(daily_dir.as_path(), &original_file_name, suffix);
-        if target.exists() {
-            suffix += 1;
-            continue;
-        }
-
-        match fs::rename(current_path, &target) {
+        match backend.rename(current_path, &target) {
             Ok(_) => {
                 crate::log::trace_debug(format!(
                     "req-newf35 daily-move success from={} to={}",
@@ -892,11 +3632,11 @@ fn move_existing_file_to_daily_directory(
                 ));
                 return Ok(target);
             }
-            Err(error) if is_retryable_name_conflict_error(&error) || target.exists() => {
+            Err(error) if is_retryable_name_conflict_error(&error) => {
                 suffix += 1;
                 continue;
             }
-            Err(error) => return Err(error),
+            Err(error) => return Err(rename_error(error, current_path, &target)),
         }
     }
 }
@@ -918,13 +3658,29 @@ pub fn forced_singleline_stem_after_rename(
 }
 
 fn forced_singleline_stem_after_resolution(
-    _singleline_value: &str,
-    _resolved_path: &Path,
+    singleline_value: &str,
+    resolved_path: &Path,
     _now: DateTime<Local>,
 ) -> Option<String> {
-    // req-newf32: disabled forced singleline buffer rewrite even when
-    // create/rename resolution applies collision suffix or sanitization.
-    None
+    // req-newf37: an empty title resolves to notitle-* but the singleline stays blank.
+    if singleline_value.is_empty() {
+        return None;
+    }
+    // req-newf41: push the resolved stem back when collision suffix or sanitization
+    // made the on-disk name differ from the typed title.
+    let resolved_stem = resolved_path.file_stem()?.to_str()?;
+    (resolved_stem != singleline_value).then(|| resolved_stem.to_string())
+}
+
+pub fn forced_singleline_stem_notice(singleline_value: &str, forced_stem: &str) -> String {
+    let sanitized = sanitize_filename_stem(singleline_value);
+    if sanitized == forced_stem {
+        return crate::i18n::tr_with("notice.title_adjusted", &[("stem", forced_stem)]);
+    }
+    crate::i18n::tr_with(
+        "notice.title_taken",
+        &[("title", sanitized.as_str()), ("stem", forced_stem)],
+    )
 }
 
 fn is_retryable_name_conflict_error(error: &io::Error) -> bool {
@@ -943,37 +3699,57 @@ fn is_retryable_name_conflict_error(error: &io::Error) -> bool {
     }
 }
 
-fn txt_candidate_path(dir: &Path, stem: &str, suffix: usize) -> PathBuf {
+pub(crate) fn resolve_unique_path(
+    dir: &Path,
+    stem: &str,
+    extension: &str,
+    suffix: usize,
+) -> PathBuf {
     let file_name = if suffix == 1 {
-        format!("{stem}.txt")
+        format!("{stem}.{extension}")
     } else {
-        format!("{stem}_{suffix}.txt")
+        format!("{stem}_{suffix}.{extension}")
     };
     dir.join(file_name)
 }
 
 pub fn create_new_text_file(request: &CreateFileRequest) -> io::Result<PathBuf> {
-    let dir = ensure_daily_directory(request.user_document_dir.as_path(), request.now)?;
+    let dir = ensure_note_directory(request.user_document_dir.as_path(), request.now)?;
 
     let stem = stem_from_singleline_value(&request.singleline_value, request.now);
+    let backend = crate::storage_backend::storage_backend_for(request.user_document_dir.as_path());
     let mut suffix = 1usize;
     loop {
-        let path = txt_candidate_path(dir.as_path(), &stem, suffix);
-        match fs::OpenOptions::new()
-            .create_new(true)
-            .write(true)
-            .open(&path)
-        {
-            Ok(_) => return Ok(path),
+        let path = resolve_unique_path(dir.as_path(), &stem, request.extension.as_str(), suffix);
+        match backend.create_new(&path) {
+            Ok(()) => return Ok(path),
             Err(error) if is_retryable_name_conflict_error(&error) => {
                 suffix += 1;
                 continue;
             }
-            Err(error) => return Err(error),
+            Err(error) => return Err(at_path(&path)(error)),
         }
     }
 }
 
+// A failed write keeps both files; a copy that cannot be trashed after the write is only
+// reported, since the resolution is already on disk.
+fn merge_conflicted_copy(request: &ConflictMergeRequest) -> io::Result<Option<String>> {
+    crate::snapshots::snapshot_before(
+        "conflict merge",
+        &[request.original.clone(), request.conflict.clone()],
+    );
+    crate::storage_backend::storage_backend_containing(request.original.as_path())
+        .write(request.original.as_path(), request.text.as_bytes())
+        .map_err(at_path(&request.original))?;
+    Ok(crate::file_tree::delete_entries_for_file_tree(
+        std::slice::from_ref(&request.conflict),
+        request.recyclebin_dir.as_path(),
+    )
+    .err()
+    .map(|error| error.to_string()))
+}
+
 pub fn rename_text_file(request: &RenameFileRequest) -> io::Result<PathBuf> {
     if !request.current_path.is_file() {
         return Err(io::Error::new(
@@ -981,6 +3757,15 @@ pub fn rename_text_file(request: &RenameFileRequest) -> io::Result<PathBuf> {
             "current editing file does not exist",
         ));
     }
+    if !is_path_in_vault(
+        request.current_path.as_path(),
+        request.user_document_dir.as_path(),
+    ) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "files outside the notes folder are not renamed from the title",
+        ));
+    }
 
     let relocated_path = move_existing_file_to_daily_directory(
         request.current_path.as_path(),
@@ -994,30 +3779,41 @@ pub fn rename_text_file(request: &RenameFileRequest) -> io::Result<PathBuf> {
         )
     })?;
 
+    // Renames keep the note's own txt/md extension; other files fall back to the
+    // configured new-note extension.
+    let extension =
+        NoteFileExtension::from_path(relocated_path.as_path()).unwrap_or(request.extension);
     let stem = stem_from_singleline_value(&request.singleline_value, request.now);
+    let backend = crate::storage_backend::storage_backend_for(request.user_document_dir.as_path());
     let mut suffix = 1usize;
     loop {
-        let target = txt_candidate_path(parent, &stem, suffix);
+        let target = resolve_unique_path(parent, &stem, extension.as_str(), suffix);
         if target == relocated_path {
             return Ok(target);
         }
-        if target.exists() {
-            suffix += 1;
-            continue;
-        }
 
-        match fs::rename(&relocated_path, &target) {
+        match backend.rename(&relocated_path, &target) {
+            Ok(_) if suffix > 1 => {
+                // The title the user typed belongs to another note; keep both as they were
+                // when the renamed one landed beside it.
+                let intended = resolve_unique_path(parent, &stem, extension.as_str(), 1);
+                crate::snapshots::snapshot_before("rename collision", &[intended, target.clone()]);
+                return Ok(target);
+            }
             Ok(_) => return Ok(target),
-            Err(error) if is_retryable_name_conflict_error(&error) || target.exists() => {
+            Err(error) if is_retryable_name_conflict_error(&error) => {
                 suffix += 1;
                 continue;
             }
-            Err(error) => return Err(error),
+            Err(error) => return Err(rename_error(error, &relocated_path, &target)),
         }
     }
 }
 
-fn save_editor_text_payload_atomic(payload: &EditorAutoSavePayload) -> io::Result<PathBuf> {
+fn save_editor_text_payload_atomic(
+    payload: &EditorAutoSavePayload,
+    previous_identity: Option<FileIdentity>,
+) -> io::Result<PathBuf> {
     // Keep a serde round-trip in event handling to satisfy req-aus4 payload serialization contract,
     // while persisting raw editor text as the file content.
     let serialized = serde_json::to_vec(payload)
@@ -1025,147 +3821,194 @@ fn save_editor_text_payload_atomic(payload: &EditorAutoSavePayload) -> io::Resul
     let decoded: EditorAutoSavePayload = serde_json::from_slice(&serialized)
         .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
 
-    let relocated_path = move_existing_file_to_daily_directory(
+    if !is_path_in_vault(
         decoded.current_path.as_path(),
         decoded.user_document_dir.as_path(),
-        Local::now(),
+    ) {
+        return save_editor_text_in_place(&decoded);
+    }
+    let mut decoded = decoded;
+    if let Cow::Owned(text) =
+        crate::save_transforms::save_transforms_for(decoded.user_document_dir.as_path())
+            .apply(&decoded.editor_text)
+    {
+        decoded.editor_text = text;
+    }
+
+    let now = Local::now();
+    let current_path = if decoded.current_path.is_file() {
+        decoded.current_path.clone()
+    } else {
+        recover_missing_edit_path(
+            decoded.current_path.as_path(),
+            decoded.user_document_dir.as_path(),
+            previous_identity,
+            now,
+        )?
+    };
+    ensure_not_publish_locked(current_path.as_path(), &decoded.editor_text)?;
+    let relocated_path = move_existing_file_to_daily_directory(
+        current_path.as_path(),
+        decoded.user_document_dir.as_path(),
+        now,
     )?;
-    write_editor_text_atomic(relocated_path.as_path(), decoded.editor_text.as_bytes())?;
+    crate::text_encoding::rebase_note_encodings(
+        decoded.current_path.as_path(),
+        relocated_path.as_path(),
+    );
+    if crate::note_metadata::stamps_note(relocated_path.as_path()) {
+        decoded.editor_text = crate::note_metadata::stamp_note_metadata(
+            &decoded.editor_text,
+            crate::file_times::created_time(relocated_path.as_path()),
+            now,
+        );
+    }
+    let bytes =
+        crate::text_encoding::encode_for_save(relocated_path.as_path(), &decoded.editor_text);
+    crate::storage_backend::storage_backend_for(decoded.user_document_dir.as_path())
+        .write(relocated_path.as_path(), &bytes)
+        .map_err(at_path(&relocated_path))?;
     Ok(relocated_path)
 }
 
-fn write_editor_text_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
-    write_editor_text_atomic_with_replace(path, bytes, replace_editor_target_with_temp)
+// An outside file, or a note that is no longer the one being edited, is written back where it
+// is. If it has gone, do not re-create it somewhere the user never chose.
+fn save_editor_text_in_place(payload: &EditorAutoSavePayload) -> io::Result<PathBuf> {
+    if !payload.current_path.is_file() {
+        let what = if is_path_in_vault(
+            payload.current_path.as_path(),
+            payload.user_document_dir.as_path(),
+        ) {
+            "note"
+        } else {
+            "external file"
+        };
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "{what} no longer exists: {}",
+                payload.current_path.display()
+            ),
+        ));
+    }
+    ensure_not_publish_locked(payload.current_path.as_path(), &payload.editor_text)?;
+    let bytes =
+        crate::text_encoding::encode_for_save(payload.current_path.as_path(), &payload.editor_text);
+    if is_path_in_vault(
+        payload.current_path.as_path(),
+        payload.user_document_dir.as_path(),
+    ) {
+        crate::storage_backend::storage_backend_for(payload.user_document_dir.as_path())
+            .write(payload.current_path.as_path(), &bytes)?;
+    } else {
+        crate::workflow_error::retry_busy(payload.current_path.as_path(), || {
+            crate::file_times::write_atomic_preserving_created(
+                payload.current_path.as_path(),
+                &bytes,
+            )
+        })?;
+    }
+    Ok(payload.current_path.clone())
+}
+
+// The editor already blocks typing into a locked note; this catches every other writer
+// (find/replace, RPC, a stale buffer) before the published text diverges.
+fn ensure_not_publish_locked(path: &Path, editor_text: &str) -> io::Result<()> {
+    let Ok((on_disk, _)) = crate::text_encoding::read_note_text(path) else {
+        return Ok(());
+    };
+    if crate::note_front_matter::publish_lock_blocks_save(&on_disk, editor_text) {
+        return Err(WorkflowError::PublishLocked {
+            path: path.to_path_buf(),
+        }
+        .into());
+    }
+    Ok(())
 }
 
-fn write_editor_text_atomic_with_replace<F>(
-    path: &Path,
-    bytes: &[u8],
-    replace_fn: F,
-) -> io::Result<()>
-where
-    F: Fn(&Path, &Path) -> io::Result<()>,
-{
-    let parent = path.parent().ok_or_else(|| {
+// The note vanished under its autosave: find it by identity in its own or today's daily
+// directory, then by its previous name in today's directory, else re-create it in place so
+// the edits are not lost.
+fn recover_missing_edit_path(
+    missing_path: &Path,
+    user_document_dir: &Path,
+    previous_identity: Option<FileIdentity>,
+    now: DateTime<Local>,
+) -> io::Result<PathBuf> {
+    let parent = missing_path.parent().ok_or_else(|| {
         io::Error::new(
             io::ErrorKind::InvalidInput,
-            "editor autosave path has no parent directory",
+            "current editing file path has no parent directory",
         )
     })?;
-    fs::create_dir_all(parent)?;
+    let today_dir = note_directory(user_document_dir, now);
+    let mut search_dirs = vec![parent.to_path_buf()];
+    if comparable_path_for_daily_directory(today_dir.as_path())
+        != comparable_path_for_daily_directory(parent)
+    {
+        search_dirs.push(today_dir.clone());
+    }
 
-    let temp_path = editor_temp_path_for_atomic_write(path)?;
-    if temp_path.is_file() {
-        fs::remove_file(&temp_path)?;
+    if let Some(identity) = previous_identity {
+        for dir in &search_dirs {
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let candidate = entry.path();
+                if candidate.is_file() && file_identity(candidate.as_path()) == Some(identity) {
+                    crate::log::trace_debug(format!(
+                        "req-aus9 autosave recovered by identity old={} new={}",
+                        missing_path.display(),
+                        candidate.display()
+                    ));
+                    return Ok(candidate);
+                }
+            }
+        }
     }
-    let mut temp_file = fs::File::create(&temp_path).map_err(|error| {
-        io::Error::new(
-            error.kind(),
-            format!("editor autosave atomic write failed (create temp): {error}"),
-        )
-    })?;
-    std::io::Write::write_all(&mut temp_file, bytes).map_err(|error| {
-        io::Error::new(
-            error.kind(),
-            format!("editor autosave atomic write failed (write temp): {error}"),
-        )
-    })?;
-    temp_file.sync_all().map_err(|error| {
-        io::Error::new(
-            error.kind(),
-            format!("editor autosave atomic write failed (sync temp): {error}"),
-        )
-    })?;
-    drop(temp_file);
 
-    if let Err(replace_error) = replace_fn(&temp_path, path).map_err(|error| {
-        io::Error::new(
-            error.kind(),
-            format!("editor autosave atomic write failed (replace target): {error}"),
-        )
-    }) {
-        if let Err(cleanup_error) = cleanup_editor_temp_file(&temp_path) {
-            return Err(io::Error::new(
-                replace_error.kind(),
-                format!("{replace_error}; cleanup temp failed: {cleanup_error}"),
+    if let Some(file_name) = missing_path.file_name() {
+        let candidate = today_dir.join(file_name);
+        if candidate != missing_path && candidate.is_file() {
+            crate::log::trace_debug(format!(
+                "req-aus9 autosave recovered by name old={} new={}",
+                missing_path.display(),
+                candidate.display()
             ));
+            return Ok(candidate);
         }
-        return Err(replace_error);
     }
 
-    Ok(())
-}
-
-fn cleanup_editor_temp_file(path: &Path) -> io::Result<()> {
-    match fs::remove_file(path) {
-        Ok(()) => Ok(()),
-        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
-        Err(error) => Err(error),
-    }
-}
-
-fn replace_editor_target_with_temp(temp_path: &Path, target_path: &Path) -> io::Result<()> {
-    // Safety invariant: do not delete existing target before replacement succeeds.
-    // A replace failure must keep the last-good autosave target file intact.
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::ffi::OsStrExt;
-        use std::ptr::{null, null_mut};
-
-        use windows_sys::Win32::Storage::FileSystem::ReplaceFileW;
-
-        if !target_path.exists() {
-            return fs::rename(temp_path, target_path);
-        }
-
-        let mut target_wide = target_path
-            .as_os_str()
-            .encode_wide()
-            .chain(Some(0))
-            .collect::<Vec<u16>>();
-        let mut temp_wide = temp_path
-            .as_os_str()
-            .encode_wide()
-            .chain(Some(0))
-            .collect::<Vec<u16>>();
-
-        let result = unsafe {
-            ReplaceFileW(
-                target_wide.as_mut_ptr(),
-                temp_wide.as_mut_ptr(),
-                null(),
-                0,
-                null_mut(),
-                null_mut(),
-            )
-        };
-        if result == 0 {
-            return Err(io::Error::last_os_error());
-        }
-        return Ok(());
-    }
-
-    #[cfg(not(target_os = "windows"))]
+    // Never re-create files outside the vault, e.g. when the whole document root is gone.
+    if !comparable_path_for_daily_directory(missing_path)
+        .starts_with(comparable_path_for_daily_directory(user_document_dir))
+        || !user_document_dir.is_dir()
     {
-        fs::rename(temp_path, target_path)
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "current editing file does not exist",
+        ));
     }
+    fs::create_dir_all(parent)?;
+    fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(missing_path)?;
+    crate::log::trace_debug(format!(
+        "req-aus9 autosave re-created missing path={}",
+        missing_path.display()
+    ));
+    Ok(missing_path.to_path_buf())
 }
 
-fn editor_temp_path_for_atomic_write(path: &Path) -> io::Result<PathBuf> {
-    let parent = path.parent().ok_or_else(|| {
-        io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "editor autosave path has no parent directory",
-        )
-    })?;
-    let file_name = path.file_name().ok_or_else(|| {
-        io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "editor autosave path has no file name",
-        )
-    })?;
-
-    Ok(parent.join(format!("{}.tmp", file_name.to_string_lossy())))
+fn cleanup_editor_temp_file(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(error),
+    }
 }
 
 impl crate::app::Papyru2App {
@@ -1175,13 +4018,28 @@ impl crate::app::Papyru2App {
         cx: &mut Context<Self>,
     ) {
         let autosave_path = path.clone();
+        if let (Some(old_path), Some(new_path)) = (
+            self.editor.read(cx).current_editing_file_path(),
+            path.as_ref(),
+        ) {
+            self.migrate_bookmarks_on_rename(old_path.as_path(), new_path.as_path());
+        }
         self.singleline.update(cx, |singleline, _| {
             singleline.set_current_editing_file_path(path.clone());
         });
+        self.file_tree.update(cx, |file_tree, cx| {
+            file_tree.set_open_file_path(path.clone(), cx);
+        });
         self.editor.update(cx, |editor, _| {
             editor.set_current_editing_file_path(path);
         });
+        self.title_rename_debouncer
+            .on_edit_path_changed(autosave_path.as_deref());
+        self.release_duplicate_title_hold(autosave_path.as_deref(), cx);
         self.editor_autosave.on_edit_path_changed(autosave_path);
+        self.sync_bookmarks_to_components(cx);
+        self.sync_markdown_preview(true, cx);
+        self.reset_external_change_tracking(cx);
 
         let sl_path = self.singleline.read(cx).current_editing_file_path();
         let ed_path = self.editor.read(cx).current_editing_file_path();
@@ -1198,8 +4056,38 @@ impl crate::app::Papyru2App {
         ));
     }
 
+    pub(crate) fn apply_workflow_edit_path_update(
+        &mut self,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.file_workflow.current_edit_path().as_ref() != Some(&path) {
+            return;
+        }
+        let Some(old_path) = self.editor.read(cx).current_editing_file_path() else {
+            return;
+        };
+        if old_path == path {
+            return;
+        }
+        crate::log::trace_debug(format!(
+            "req-aus9 edit path update old={} new={}",
+            old_path.display(),
+            path.display()
+        ));
+        self.editor_autosave
+            .retarget_pending_payload(old_path.as_path(), path.as_path());
+        self.sync_singleline_from_file_tree_selection(path.as_path(), window, cx);
+        self.sync_current_editing_path_to_components(Some(path.clone()), cx);
+        // A daily relocation moves the note to another folder: links to it, and its own
+        // relative links, are offered for a rewrite like after a move in the tree.
+        self.offer_link_rewrite(vec![(old_path, path)], window, cx);
+    }
+
     pub(crate) fn apply_forced_singleline_stem(
         &mut self,
+        singleline_value: &str,
         forced_stem: Option<String>,
         trace_label: &str,
         window: &mut Window,
@@ -1207,15 +4095,16 @@ impl crate::app::Papyru2App {
     ) {
         let Some(forced_stem) = forced_stem else {
             crate::log::trace_debug(format!(
-                "{trace_label} force singleline stem update skipped (req-newf32)"
+                "{trace_label} force singleline stem update skipped (resolved stem matches title)"
             ));
             return;
         };
 
         crate::log::trace_debug(format!(
-            "{trace_label} force singleline stem update='{}'",
+            "{trace_label} force singleline stem update='{}' (req-newf41)",
             crate::app::compact_text(&forced_stem)
         ));
+        // apply_text_and_cursor marks the change as programmatic, so no rename is re-raised.
         self.singleline.update(cx, |singleline, cx| {
             singleline.apply_text_and_cursor(
                 forced_stem.clone(),
@@ -1224,6 +4113,53 @@ impl crate::app::Papyru2App {
                 cx,
             );
         });
+        self.show_toast(
+            crate::toasts::ToastSeverity::Warning,
+            forced_singleline_stem_notice(singleline_value, &forced_stem),
+            cx,
+        );
+    }
+
+    pub(crate) fn apply_cli_launch_action(
+        &mut self,
+        action: crate::cli::CliLaunchAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        crate::log::trace_debug(format!("cli launch action={action:?}"));
+        match action {
+            crate::cli::CliLaunchAction::OpenFile(path) => {
+                if !path.is_file() {
+                    self.show_transient_notice(
+                        crate::i18n::tr_with(
+                            "notice.cannot_open_not_file",
+                            &[("path", path.display().to_string().as_str())],
+                        ),
+                        cx,
+                    );
+                    return;
+                }
+                if !self.open_file(path.clone(), window, cx) {
+                    self.show_transient_notice(
+                        crate::i18n::tr_with(
+                            "notice.cannot_open_path",
+                            &[("path", path.display().to_string().as_str())],
+                        ),
+                        cx,
+                    );
+                }
+            }
+            crate::cli::CliLaunchAction::NewNote(title) => {
+                if self.file_workflow.state() != SinglelineFileState::Neutral {
+                    return;
+                }
+                let cursor_char = title.chars().count();
+                self.singleline.update(cx, |singleline, cx| {
+                    singleline.apply_text_and_cursor(title, cursor_char, window, cx);
+                });
+                self.ensure_new_file_flow("cli_new", window, cx);
+            }
+        }
     }
 
     pub(crate) fn ensure_new_file_flow(
@@ -1248,108 +4184,256 @@ impl crate::app::Papyru2App {
         ));
 
         let now_local = Local::now();
-        match self.file_workflow.try_create_from_neutral(
+        let note_template = self.take_note_template_for_create(cx);
+        self.file_workflow.set_next_note_template(note_template);
+        let (created_tx, created_rx) = smol::channel::bounded::<WorkflowResult<Option<PathBuf>>>(1);
+        match self.file_workflow.try_create_from_neutral_async(
             &singleline_snapshot.value,
             self.app_paths.user_document_dir.as_path(),
             Instant::now(),
             now_local,
+            move |result| {
+                let _ = created_tx.try_send(result);
+            },
         ) {
-            Ok(Some(path)) => {
-                crate::log::trace_debug(format!("new_file_flow created path={}", path.display()));
-                self.sync_current_editing_path_to_components(Some(path.clone()), cx);
-
-                if crate::app::req_ftr14_create_flow_uses_watcher_refresh_only() {
-                    crate::log::trace_debug("new_file_flow watcher_refresh_only=true");
-                }
-
-                let forced_singleline_stem = forced_singleline_stem_after_create(
-                    &singleline_snapshot.value,
-                    path.as_path(),
-                    now_local,
-                );
-                let should_select_created_path =
-                    crate::file_tree::should_apply_req_newf38_tree_selection(
-                        &singleline_snapshot.value,
-                        path.as_path(),
-                    );
-                if should_select_created_path && forced_singleline_stem.is_none() {
-                    crate::log::trace_debug(format!(
-                        "new_file_flow req-newf37-revert keep_singleline_blank trigger={} singleline_before='{}' created_path={}",
-                        trigger,
-                        crate::app::compact_text(&singleline_snapshot.value),
-                        path.display()
-                    ));
-                }
-                self.apply_forced_singleline_stem(
-                    forced_singleline_stem,
-                    "new_file_flow",
-                    window,
+            Ok(true) => {
+                crate::log::trace_debug(format!(
+                    "new_file_flow trigger={} dispatched async state=NEW",
+                    trigger
+                ));
+            }
+            Ok(false) => {
+                crate::log::trace_debug(format!(
+                    "new_file_flow trigger={} skipped (state/throttle gate)",
+                    trigger
+                ));
+                self.file_workflow.set_next_note_template(None);
+                return;
+            }
+            Err(error) => {
+                crate::log::trace_debug(format!(
+                    "new_file_flow trigger={} failed error={error}",
+                    trigger
+                ));
+                self.show_toast(
+                    crate::toasts::ToastSeverity::Error,
+                    error.notice(crate::i18n::tr("error.create_note")),
                     cx,
                 );
+                return;
+            }
+        }
 
-                self.editor.update(cx, |editor, cx| {
-                    let _ = editor.open_file(path.clone(), window, cx);
-                });
-
-                let restored_selection = if should_select_created_path {
-                    self.select_created_file_in_tree_after_new_file(path.as_path(), cx)
-                } else {
-                    false
-                };
+        let trigger = trigger.to_string();
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(result) = created_rx.recv().await else {
                 crate::log::trace_debug(format!(
-                    "new_file_flow req-newf38 target={} should_select={} restored_selection={}",
-                    path.display(),
-                    should_select_created_path,
-                    restored_selection
+                    "new_file_flow trigger={} completion channel closed",
+                    trigger
                 ));
-                if should_select_created_path {
-                    let singleline_after = self.singleline.read(cx).snapshot(cx);
-                    crate::log::trace_debug(format!(
-                        "new_file_flow req-newf37-revert singleline_after_create value='{}' cursor={} is_blank={}",
-                        crate::app::compact_text(&singleline_after.value),
-                        singleline_after.cursor_char,
-                        singleline_after.value.is_empty()
-                    ));
-                }
-
-                if crate::app::should_restore_singleline_focus_after_new_file(
-                    singleline_was_focused,
-                    editor_was_focused,
-                ) {
-                    let singleline_after = self.singleline.read(cx).snapshot(cx);
-                    let restore_cursor_char = singleline_snapshot
-                        .cursor_char
-                        .min(singleline_after.value.chars().count());
+                return;
+            };
+            let _ = this.update_in(cx, move |app, window, cx| {
+                app.complete_new_file_flow(
+                    trigger.as_str(),
+                    singleline_snapshot,
+                    now_local,
+                    result,
+                    window,
+                    cx,
+                );
+            });
+        })
+        .detach();
+    }
 
-                    crate::log::trace_debug(format!(
-                        "new_file_flow restore singleline focus cursor={} (rule-1)",
-                        restore_cursor_char
-                    ));
-                    self.singleline.update(cx, |singleline, cx| {
-                        singleline.apply_cursor(restore_cursor_char, window, cx);
-                        singleline.focus(window, cx);
-                    });
-                } else {
-                    crate::log::trace_debug("new_file_flow no focus restore (rule-2)");
-                }
-            }
+    fn complete_new_file_flow(
+        &mut self,
+        trigger: &str,
+        singleline_snapshot: crate::singleline_input::SingleLineSnapshot,
+        now_local: DateTime<Local>,
+        result: WorkflowResult<Option<PathBuf>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let path = match result {
+            Ok(Some(path)) => path,
             Ok(None) => {
                 crate::log::trace_debug(format!(
-                    "new_file_flow trigger={} skipped (state/throttle gate)",
+                    "new_file_flow trigger={} completed without path",
                     trigger
                 ));
+                return;
             }
             Err(error) => {
                 crate::log::trace_debug(format!(
                     "new_file_flow trigger={} failed error={error}",
                     trigger
                 ));
+                self.show_toast(
+                    crate::toasts::ToastSeverity::Error,
+                    error.notice(crate::i18n::tr("error.create_note")),
+                    cx,
+                );
+                return;
             }
+        };
+
+        if self.file_workflow.current_edit_path().as_ref() != Some(&path) {
+            crate::log::trace_debug(format!(
+                "new_file_flow created path={} superseded (workflow moved on while create was in flight)",
+                path.display()
+            ));
+            return;
+        }
+
+        crate::log::trace_debug(format!("new_file_flow created path={}", path.display()));
+        self.sync_current_editing_path_to_components(Some(path.clone()), cx);
+
+        if crate::app::req_ftr14_create_flow_uses_watcher_refresh_only() {
+            crate::log::trace_debug("new_file_flow watcher_refresh_only=true");
+        }
+
+        // Only rewrite the title if the user has not typed further while the create was
+        // in flight; otherwise the follow-up rename below resolves the newer value.
+        let singleline_now = self.singleline.read(cx).snapshot(cx).value;
+        let forced_singleline_stem = if singleline_now == singleline_snapshot.value {
+            forced_singleline_stem_after_create(
+                &singleline_snapshot.value,
+                path.as_path(),
+                now_local,
+            )
+        } else {
+            None
+        };
+        let should_select_created_path = crate::file_tree::should_apply_req_newf38_tree_selection(
+            &singleline_snapshot.value,
+            path.as_path(),
+        );
+        if should_select_created_path && forced_singleline_stem.is_none() {
+            crate::log::trace_debug(format!(
+                "new_file_flow req-newf37-revert keep_singleline_blank trigger={} singleline_before='{}' created_path={}",
+                trigger,
+                crate::app::compact_text(&singleline_snapshot.value),
+                path.display()
+            ));
+        }
+        self.apply_forced_singleline_stem(
+            &singleline_snapshot.value,
+            forced_singleline_stem.clone(),
+            "new_file_flow",
+            window,
+            cx,
+        );
+        if forced_singleline_stem.is_none()
+            && let Some(name) = path.file_name()
+        {
+            self.show_toast(
+                crate::toasts::ToastSeverity::Success,
+                crate::i18n::tr_with(
+                    "toast.created",
+                    &[("name", name.to_string_lossy().as_ref())],
+                ),
+                cx,
+            );
+        }
+
+        // Text typed (or transferred) into the editor while the create was in flight
+        // must not be replaced by the empty file content; pin it for autosave instead.
+        let editor_text = self.editor.read(cx).snapshot(cx).value;
+        if editor_text.is_empty() {
+            self.editor.update(cx, |editor, cx| {
+                let _ = editor.open_file(path.clone(), window, cx);
+            });
+        } else {
+            crate::log::trace_debug(format!(
+                "new_file_flow keep in-flight editor buffer path={} text_len={}",
+                path.display(),
+                editor_text.len()
+            ));
+            self.on_editor_user_buffer_changed(&editor_text, cx);
+        }
+
+        let restored_selection = if should_select_created_path {
+            self.select_created_file_in_tree_after_new_file(path.as_path(), cx)
+        } else {
+            false
+        };
+        crate::log::trace_debug(format!(
+            "new_file_flow req-newf38 target={} should_select={} restored_selection={}",
+            path.display(),
+            should_select_created_path,
+            restored_selection
+        ));
+        let singleline_after = self.singleline.read(cx).snapshot(cx);
+        if should_select_created_path {
+            crate::log::trace_debug(format!(
+                "new_file_flow req-newf37-revert singleline_after_create value='{}' cursor={} is_blank={}",
+                crate::app::compact_text(&singleline_after.value),
+                singleline_after.cursor_char,
+                singleline_after.value.is_empty()
+            ));
+        }
+
+        // Focus is evaluated at completion time: handlers such as singleline_down may
+        // have moved focus after the create was dispatched.
+        let singleline_focused = self.singleline.read(cx).is_focused(window, cx);
+        let editor_focused = self.editor.read(cx).is_focused(window, cx);
+        if crate::app::should_restore_singleline_focus_after_new_file(
+            singleline_focused,
+            editor_focused,
+        ) {
+            let restore_cursor_char = singleline_after
+                .cursor_char
+                .min(singleline_after.value.chars().count());
+
+            crate::log::trace_debug(format!(
+                "new_file_flow restore singleline focus cursor={} (rule-1)",
+                restore_cursor_char
+            ));
+            self.singleline.update(cx, |singleline, cx| {
+                singleline.apply_cursor(restore_cursor_char, window, cx);
+                singleline.focus(window, cx);
+            });
+        } else {
+            crate::log::trace_debug("new_file_flow no focus restore (rule-2)");
+        }
+
+        if singleline_after.value != singleline_snapshot.value
+            && forced_singleline_stem.as_ref() != Some(&singleline_after.value)
+        {
+            crate::log::trace_debug(format!(
+                "new_file_flow follow-up rename singleline_before='{}' singleline_now='{}'",
+                crate::app::compact_text(&singleline_snapshot.value),
+                crate::app::compact_text(&singleline_after.value)
+            ));
+            self.on_singleline_value_changed(
+                &singleline_snapshot.value,
+                &singleline_after.value,
+                window,
+                cx,
+            );
         }
     }
 
     pub(crate) fn on_editor_user_buffer_changed(&mut self, value: &str, cx: &mut Context<Self>) {
+        self.external_change.note_text(value);
+        // What lands on disk may be the on-save rewrite of the buffer, which is ours too.
+        let saved =
+            crate::save_transforms::save_transforms_for(self.app_paths.user_document_dir.as_path())
+                .apply(value);
+        if let Cow::Owned(saved) = saved {
+            self.external_change.note_text(&saved);
+        }
         let snapshot = self.file_workflow.snapshot();
+        if snapshot.state == SinglelineFileState::New {
+            crate::log::trace_debug(format!(
+                "autosave user edit deferred (create in flight) text_len={}",
+                value.len()
+            ));
+            return;
+        }
+
         let Some(current_path) = snapshot.current_edit_path.clone() else {
             crate::log::trace_debug(format!(
                 "autosave critical invalid path on user edit state={:?} text_len={}",
@@ -1389,7 +4473,7 @@ impl crate::app::Papyru2App {
             self.sync_current_editing_path_to_components(Some(current_path.clone()), cx);
         }
 
-        crate::log::trace_debug(format!(
+        crate::log::trace_input(format!(
             "autosave step-2 pin user edit path={} text_len={}",
             current_path.display(),
             value.len()
@@ -1405,11 +4489,39 @@ impl crate::app::Papyru2App {
         );
     }
 
+    /// App teardown: the title debounce thread stops before its pending title is flushed, and
+    /// the autosave worker writes what is still pending through the dispatcher, so it stops
+    /// next; the dispatcher then drains its queue. All three threads are joined.
+    pub(crate) fn shutdown_background_workers(&mut self) {
+        crate::log::trace_debug("app teardown shutting down background workers");
+        self.title_rename_debounce_worker.shutdown();
+        self.flush_pending_title_rename("app-teardown");
+        self.editor_autosave_worker.shutdown();
+        self.file_workflow.shutdown_dispatcher();
+    }
+
+    // A title still waiting for the typing pause is renamed now, ahead of the content flush
+    // on the same worker queue. The duplicate title check is skipped: the user is leaving
+    // the note and could not answer the banner anymore.
+    // Returns whether a rename was dispatched.
+    pub(crate) fn flush_pending_title_rename(&mut self, trigger: &str) -> bool {
+        let Some(pending) = self.title_rename_debouncer.take_pending() else {
+            return false;
+        };
+        crate::log::trace_debug(format!(
+            "rename_flow debounce flush trigger={trigger} value='{}'",
+            crate::app::compact_text(&pending.singleline_value)
+        ));
+        dispatch_title_rename(&self.file_workflow, pending);
+        true
+    }
+
     pub(crate) fn flush_editor_content_before_context_switch(
         &mut self,
         trigger: &str,
         cx: &mut Context<Self>,
     ) -> bool {
+        self.flush_pending_title_rename(trigger);
         let snapshot = self.file_workflow.snapshot();
         if snapshot.state != SinglelineFileState::Edit {
             crate::log::trace_debug(format!(
@@ -1431,6 +4543,15 @@ impl crate::app::Papyru2App {
             return false;
         };
 
+        // The editor is empty until a background load lands; there is nothing of ours to keep.
+        if self.editor.read(cx).is_loading() {
+            crate::log::trace_debug(format!(
+                "autosave pre-switch trigger={} skipped editor_loading=true",
+                trigger
+            ));
+            return true;
+        }
+
         if !should_flush_pre_switch_editor_content(
             trigger,
             current_path.as_path(),
@@ -1439,6 +4560,23 @@ impl crate::app::Papyru2App {
             return true;
         }
 
+        if self.vault_availability.is_disconnected() {
+            // Closing keeps the journal for replay on the next start; anything else would
+            // switch away from edits that cannot be written right now.
+            let allow = trigger == "req-aus7-window-close";
+            crate::log::trace_debug(format!(
+                "autosave pre-switch trigger={} skipped vault_disconnected=true allow={}",
+                trigger, allow
+            ));
+            if !allow {
+                self.show_transient_notice(
+                    crate::i18n::tr("notice.vault_unavailable_cannot_save").to_string(),
+                    cx,
+                );
+            }
+            return allow;
+        }
+
         let editor_snapshot = self.editor.read(cx).snapshot(cx);
         crate::log::trace_debug(format!(
             "autosave pre-switch trigger={} raise path={} text_len={}",
@@ -1451,14 +4589,12 @@ impl crate::app::Papyru2App {
             &editor_snapshot.value,
             self.app_paths.user_document_dir.as_path(),
         );
-        self.editor_autosave.reset_cycle();
+        self.editor_autosave
+            .clear_pending_for_path(current_path.as_path());
 
         match flush_result {
-            Ok(true) => {
-                let resolved_path = self
-                    .file_workflow
-                    .current_edit_path()
-                    .unwrap_or_else(|| current_path.clone());
+            Ok(Some(resolved_path)) => {
+                self.record_note_tags(resolved_path.as_path(), &editor_snapshot.value);
                 if resolved_path != current_path {
                     crate::log::trace_debug(format!(
                         "req-newf35 pre-switch path updated old={} new={}",
@@ -1474,7 +4610,7 @@ impl crate::app::Papyru2App {
                 ));
                 true
             }
-            Ok(false) => {
+            Ok(None) => {
                 crate::log::trace_debug(format!(
                     "autosave pre-switch trigger={} no-op by workflow gate path={}",
                     trigger,
@@ -1488,6 +4624,11 @@ impl crate::app::Papyru2App {
                     trigger,
                     current_path.display()
                 ));
+                self.show_toast(
+                    crate::toasts::ToastSeverity::Error,
+                    error.notice(crate::i18n::tr("error.save_open_note")),
+                    cx,
+                );
                 false
             }
         }
@@ -1498,7 +4639,6 @@ impl crate::app::Papyru2App {
 mod tests {
     use super::*;
     use chrono::DateTime;
-    use std::time::UNIX_EPOCH;
 
     fn fixed_now() -> DateTime<Local> {
         DateTime::parse_from_rfc3339("2026-02-28T12:34:56.789+00:00")
@@ -1636,14 +4776,14 @@ mod tests {
     #[test]
     fn newf_test8_daily_directory_uses_yyyy_mm_dd() {
         let root = PathBuf::from("C:/tmp/root");
-        let dir = daily_directory(root.as_path(), fixed_now());
+        let dir = note_directory(root.as_path(), fixed_now());
         assert!(dir.ends_with(Path::new("2026").join("02").join("28")));
     }
 
     #[test]
     fn ftr_test56_startup_daily_directory_helper_creates_missing_yyyy_mm_dd() {
         let root = new_temp_root("ftr_test56");
-        let created = ensure_daily_directory(root.as_path(), fixed_now())
+        let created = ensure_note_directory(root.as_path(), fixed_now())
             .expect("ensure startup daily directory");
 
         assert!(created.ends_with(Path::new("2026").join("02").join("28")));
@@ -1655,7 +4795,7 @@ mod tests {
     #[test]
     fn newf_test9_collision_suffix_appends_before_txt() {
         let root = new_temp_root("newf_test9");
-        let dir = daily_directory(root.as_path(), fixed_now());
+        let dir = note_directory(root.as_path(), fixed_now());
         fs::create_dir_all(&dir).expect("create daily directory");
         fs::write(dir.join("hello.txt"), "").expect("write hello.txt");
         fs::write(dir.join("hello_2.txt"), "").expect("write hello_2.txt");
@@ -1664,6 +4804,7 @@ mod tests {
             user_document_dir: root.clone(),
             singleline_value: "hello".to_string(),
             now: fixed_now(),
+            extension: NoteFileExtension::Txt,
         })
         .expect("create new text file");
 
@@ -1696,6 +4837,48 @@ mod tests {
         assert_eq!(stem.chars().count(), 64);
     }
 
+    #[test]
+    fn newf_test51_req_newf40_stem_preview_reports_replaced_and_truncated_chars() {
+        let exact = sanitize_filename_stem_preview("meeting notes");
+        assert!(exact.is_exact());
+        assert_eq!(exact.feedback_message(), None);
+
+        let raw = format!("a:b?c:{}", "x".repeat(MAX_FILE_STEM_CHARS));
+        let preview = sanitize_filename_stem_preview(&raw);
+        assert_eq!(preview.stem, sanitize_filename_stem(&raw));
+        assert_eq!(preview.replaced_chars, vec![':', '?']);
+        assert_eq!(preview.truncated_chars, 6);
+        assert_eq!(
+            preview.feedback_message().as_deref(),
+            Some("':' '?' will be saved as '_'; name is cut to 64 chars (6 dropped)")
+        );
+    }
+
+    #[test]
+    fn newf_test61_stem_preview_follows_configured_filename_rules() {
+        use crate::filename_rules::{FilenameRules, InvalidCharPolicy};
+
+        let rules = FilenameRules {
+            max_stem_chars: 10,
+            invalid_chars: InvalidCharPolicy::Strip,
+            ..FilenameRules::default()
+        };
+        let preview = sanitize_filename_stem_preview_with("a:b?c-defghijkl", &rules);
+        assert_eq!(preview.stem, "abc-defghi");
+        assert_eq!(
+            preview.feedback_message().as_deref(),
+            Some("':' '?' will be left out; name is cut to 10 chars (3 dropped)")
+        );
+
+        let reserved = sanitize_filename_stem_preview_with("con", &FilenameRules::default());
+        assert_eq!(reserved.stem, "con_");
+        assert!(!reserved.is_exact());
+        assert_eq!(
+            reserved.feedback_message().as_deref(),
+            Some("reserved on Windows, saved as 'con_'")
+        );
+    }
+
     #[test]
     fn newf_test14_open_file_transition_sets_edit_path() {
         let workflow = SinglelineCreateFileWorkflow::new();
@@ -1766,6 +4949,7 @@ mod tests {
                 user_document_dir: root.clone(),
                 singleline_value: "a".to_string(),
                 now: fixed_now(),
+                extension: NoteFileExtension::Txt,
             }))
             .expect("first create");
         let second = dispatcher
@@ -1773,6 +4957,7 @@ mod tests {
                 user_document_dir: root.clone(),
                 singleline_value: "b".to_string(),
                 now: fixed_now(),
+                extension: NoteFileExtension::Txt,
             }))
             .expect("second create");
 
@@ -1805,6 +4990,7 @@ mod tests {
                     user_document_dir: root,
                     singleline_value: format!("p{ix}"),
                     now: fixed_now(),
+                    extension: NoteFileExtension::Txt,
                 }))
             }));
         }
@@ -1814,7 +5000,7 @@ mod tests {
             assert!(result.is_ok());
         }
 
-        let created_dir = daily_directory(root.as_path(), fixed_now());
+        let created_dir = note_directory(root.as_path(), fixed_now());
         let count = fs::read_dir(created_dir).expect("read dir").count();
         assert_eq!(count, 4);
         dispatcher.shutdown();
@@ -1900,51 +5086,67 @@ mod tests {
             user_document_dir: root.clone(),
             singleline_value: "abc".to_string(),
             now: fixed_now(),
+            extension: NoteFileExtension::Txt,
         })
         .expect("create new file");
 
-        let daily = daily_directory(root.as_path(), fixed_now());
+        let daily = note_directory(root.as_path(), fixed_now());
         assert!(path.starts_with(daily));
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn newf_test25_collision_does_not_force_singleline_buffer_stem_update() {
+    fn newf_test25_req_newf41_collision_forces_singleline_buffer_stem_update() {
         let root = new_temp_root("newf_test25");
         let now = fixed_now();
         let _first = create_new_text_file(&CreateFileRequest {
             user_document_dir: root.clone(),
             singleline_value: "filename".to_string(),
             now,
+            extension: NoteFileExtension::Txt,
         })
         .expect("create first file");
         let second = create_new_text_file(&CreateFileRequest {
             user_document_dir: root.clone(),
             singleline_value: "filename".to_string(),
             now,
+            extension: NoteFileExtension::Txt,
         })
         .expect("create second file");
 
         assert!(second.ends_with(Path::new("filename_2.txt")));
         let forced = forced_singleline_stem_after_create("filename", second.as_path(), now);
-        assert!(forced.is_none());
+        assert_eq!(forced.as_deref(), Some("filename_2"));
+        assert_eq!(
+            forced_singleline_stem_notice("filename", "filename_2"),
+            "'filename' already exists; saved as 'filename_2'"
+        );
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn newf_test26_sanitization_does_not_force_singleline_buffer_stem_update() {
+    fn newf_test26_req_newf41_sanitization_forces_singleline_buffer_stem_update() {
         let root = new_temp_root("newf_test26");
         let now = fixed_now();
         let created = create_new_text_file(&CreateFileRequest {
             user_document_dir: root.clone(),
             singleline_value: "file:name".to_string(),
             now,
+            extension: NoteFileExtension::Txt,
         })
         .expect("create sanitized file");
 
         assert!(created.ends_with(Path::new("file_name.txt")));
         let forced = forced_singleline_stem_after_create("file:name", created.as_path(), now);
-        assert!(forced.is_none());
+        assert_eq!(forced.as_deref(), Some("file_name"));
+        assert_eq!(
+            forced_singleline_stem_notice("file:name", "file_name"),
+            "Title adjusted to a valid file name: 'file_name'"
+        );
+        assert_eq!(
+            forced_singleline_stem_after_create("file_name", created.as_path(), now),
+            None
+        );
         remove_temp_root(root.as_path());
     }
 
@@ -1989,7 +5191,7 @@ mod tests {
     fn newf_test29_create_retries_on_name_conflict() {
         let root = new_temp_root("newf_test29");
         let now = fixed_now();
-        let daily = daily_directory(root.as_path(), now);
+        let daily = note_directory(root.as_path(), now);
         fs::create_dir_all(&daily).expect("create daily directory");
         fs::write(daily.join("race.txt"), "existing").expect("seed existing file");
 
@@ -1997,6 +5199,7 @@ mod tests {
             user_document_dir: root.clone(),
             singleline_value: "race".to_string(),
             now,
+            extension: NoteFileExtension::Txt,
         })
         .expect("create with conflict retry");
         assert!(created.ends_with(Path::new("race_2.txt")));
@@ -2011,7 +5214,7 @@ mod tests {
     fn newf_test30_rename_retries_on_name_conflict() {
         let root = new_temp_root("newf_test30");
         let now = fixed_now();
-        let daily = daily_directory(root.as_path(), now);
+        let daily = note_directory(root.as_path(), now);
         fs::create_dir_all(&daily).expect("create daily directory");
         let source = daily.join("source.txt");
         fs::write(&source, "source").expect("seed source");
@@ -2022,6 +5225,7 @@ mod tests {
             current_path: source.clone(),
             singleline_value: "target".to_string(),
             now,
+            extension: NoteFileExtension::Txt,
         })
         .expect("rename with conflict retry");
         assert!(renamed.ends_with(Path::new("target_2.txt")));
@@ -2037,7 +5241,7 @@ mod tests {
     fn newf_test31_req_newf33_create_collision_keeps_existing_file_and_uses_suffix() {
         let root = new_temp_root("newf_test31");
         let now = fixed_now();
-        let daily = daily_directory(root.as_path(), now);
+        let daily = note_directory(root.as_path(), now);
         fs::create_dir_all(&daily).expect("create daily directory");
         let existing = daily.join("same.txt");
         fs::write(&existing, "existing").expect("seed existing file");
@@ -2046,6 +5250,7 @@ mod tests {
             user_document_dir: root.clone(),
             singleline_value: "same".to_string(),
             now,
+            extension: NoteFileExtension::Txt,
         })
         .expect("create with collision suffix");
 
@@ -2061,7 +5266,7 @@ mod tests {
     fn newf_test32_req_newf33_rename_collision_preserves_existing_target_content() {
         let root = new_temp_root("newf_test32");
         let now = fixed_now();
-        let daily = daily_directory(root.as_path(), now);
+        let daily = note_directory(root.as_path(), now);
         fs::create_dir_all(&daily).expect("create daily directory");
         let source = daily.join("source.txt");
         let target = daily.join("target.txt");
@@ -2073,6 +5278,7 @@ mod tests {
             current_path: source.clone(),
             singleline_value: "target".to_string(),
             now,
+            extension: NoteFileExtension::Txt,
         })
         .expect("rename with suffix");
 
@@ -2090,10 +5296,10 @@ mod tests {
     }
 
     #[test]
-    fn newf_test33_req_newf32_forced_singleline_stem_is_disabled_for_rename_resolution() {
+    fn newf_test33_req_newf41_forced_singleline_stem_follows_rename_resolution() {
         let root = new_temp_root("newf_test33");
         let now = fixed_now();
-        let daily = daily_directory(root.as_path(), now);
+        let daily = note_directory(root.as_path(), now);
         fs::create_dir_all(&daily).expect("create daily directory");
         let source_collision = daily.join("source_collision.txt");
         fs::write(&source_collision, "source-collision").expect("seed collision source");
@@ -2104,12 +5310,14 @@ mod tests {
             current_path: source_collision,
             singleline_value: "conflict".to_string(),
             now,
+            extension: NoteFileExtension::Txt,
         })
         .expect("rename collision");
         assert!(renamed_collision.ends_with(Path::new("conflict_2.txt")));
-        assert!(
+        assert_eq!(
             forced_singleline_stem_after_rename("conflict", renamed_collision.as_path(), now)
-                .is_none()
+                .as_deref(),
+            Some("conflict_2")
         );
 
         let source_sanitize = daily.join("source_sanitize.txt");
@@ -2119,12 +5327,14 @@ mod tests {
             current_path: source_sanitize,
             singleline_value: "file:name".to_string(),
             now,
+            extension: NoteFileExtension::Txt,
         })
         .expect("rename sanitize");
         assert!(renamed_sanitize.ends_with(Path::new("file_name.txt")));
-        assert!(
+        assert_eq!(
             forced_singleline_stem_after_rename("file:name", renamed_sanitize.as_path(), now)
-                .is_none()
+                .as_deref(),
+            Some("file_name")
         );
 
         remove_temp_root(root.as_path());
@@ -2134,7 +5344,7 @@ mod tests {
     fn newf_test36_req_newf35_rename_update_moves_existing_file_to_today_and_updates_path() {
         let root = new_temp_root("newf_test36");
         let now = fixed_now();
-        let today_dir = daily_directory(root.as_path(), now);
+        let today_dir = note_directory(root.as_path(), now);
         fs::create_dir_all(&today_dir).expect("create today directory");
         let old_dir = root.join("1999").join("01").join("01");
         fs::create_dir_all(&old_dir).expect("create old directory");
@@ -2178,12 +5388,12 @@ mod tests {
             .expect("autosave after move");
         let after = Local::now();
 
-        assert!(saved);
+        assert!(saved.is_some());
         let current = workflow
             .current_edit_path()
             .expect("current edit path after autosave");
-        let today_before = daily_directory(root.as_path(), before);
-        let today_after = daily_directory(root.as_path(), after);
+        let today_before = note_directory(root.as_path(), before);
+        let today_after = note_directory(root.as_path(), after);
         assert!(
             is_path_under_daily_directory(current.as_path(), today_before.as_path())
                 || is_path_under_daily_directory(current.as_path(), today_after.as_path())
@@ -2202,7 +5412,7 @@ mod tests {
     fn newf_test38_req_newf35_noop_when_path_is_already_today_daily_dir() {
         let root = new_temp_root("newf_test38");
         let now = fixed_now();
-        let today_dir = daily_directory(root.as_path(), now);
+        let today_dir = note_directory(root.as_path(), now);
         fs::create_dir_all(&today_dir).expect("create today directory");
         let source = today_dir.join("fileA.txt");
         fs::write(&source, "A-old").expect("seed source");
@@ -2212,6 +5422,7 @@ mod tests {
             current_path: source.clone(),
             singleline_value: "fileA".to_string(),
             now,
+            extension: NoteFileExtension::Txt,
         })
         .expect("rename no-op in today directory");
 
@@ -2228,7 +5439,7 @@ mod tests {
     fn newf_test39_req_newf36_event_b_rename_uses_event_a_updated_path() {
         let root = new_temp_root("newf_test39");
         let now = fixed_now();
-        let today_dir = daily_directory(root.as_path(), now);
+        let today_dir = note_directory(root.as_path(), now);
         fs::create_dir_all(&today_dir).expect("create today directory");
         let old_dir = root.join("1999").join("01").join("01");
         fs::create_dir_all(&old_dir).expect("create old directory");
@@ -2284,644 +5495,1942 @@ mod tests {
             })
             .expect("stale autosave call");
 
-        assert!(!stale_saved);
+        assert!(stale_saved.is_none());
         assert_eq!(
             fs::read_to_string(&moved_path).expect("read moved path content"),
             "A-old"
         );
-        assert_eq!(workflow.current_edit_path(), Some(moved_path));
+        assert_eq!(workflow.current_edit_path(), Some(moved_path));
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn newf_test41_req_newf37_revert_empty_create_keeps_singleline_blank() {
+        let root = new_temp_root("newf_test41");
+        let now = fixed_now();
+        let workflow = SinglelineCreateFileWorkflow::new();
+
+        let created_path = workflow
+            .try_create_from_neutral("", root.as_path(), Instant::now(), now)
+            .expect("create from empty singleline")
+            .expect("created path");
+        let expected_stem = created_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .expect("created path stem")
+            .to_string();
+
+        let forced = forced_singleline_stem_after_create("", created_path.as_path(), now);
+
+        assert!(expected_stem.starts_with("notitle-"));
+        assert_eq!(forced, None);
+
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn newf_test43_req_newf41_nonempty_collision_forces_resolved_stem() {
+        let root = new_temp_root("newf_test43");
+        let now = fixed_now();
+        let first_instant = Instant::now();
+
+        let workflow = SinglelineCreateFileWorkflow::new();
+        let _first_path = workflow
+            .try_create_from_neutral("filename", root.as_path(), first_instant, now)
+            .expect("first create")
+            .expect("first path");
+        let transitioned = workflow.transition_edit_to_neutral();
+        assert!(transitioned, "workflow should transition back to neutral");
+
+        let second_path = workflow
+            .try_create_from_neutral(
+                "filename",
+                root.as_path(),
+                first_instant + CREATE_EVENT_MIN_INTERVAL + Duration::from_millis(1),
+                now,
+            )
+            .expect("second create with collision")
+            .expect("second path");
+        let second_stem = second_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .expect("second path stem")
+            .to_string();
+
+        assert!(
+            second_stem.starts_with("filename"),
+            "resolved stem should keep filename prefix"
+        );
+        assert_eq!(
+            forced_singleline_stem_after_create("filename", second_path.as_path(), now),
+            Some(second_stem.clone())
+        );
+        assert_eq!(
+            forced_singleline_stem_after_rename("filename", second_path.as_path(), now),
+            Some(second_stem)
+        );
+
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn aus_test1_autosave_event_writes_latest_editor_text() {
+        let root = new_temp_root("aus_test1");
+        let workflow = SinglelineCreateFileWorkflow::new();
+        let path = workflow
+            .try_create_from_neutral("autosave", root.as_path(), Instant::now(), fixed_now())
+            .expect("create")
+            .expect("created path");
+        let payload = EditorAutoSavePayload {
+            user_document_dir: root.clone(),
+            current_path: path,
+            editor_text: "line-1\nline-2".to_string(),
+        };
+
+        let saved = workflow
+            .try_autosave_in_edit(payload)
+            .expect("dispatch autosave");
+        assert!(saved.is_some());
+        let current = workflow
+            .current_edit_path()
+            .expect("current path after autosave");
+        let content = fs::read_to_string(&current).expect("read autosaved file");
+        assert_eq!(content, "line-1\nline-2");
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn aus_test2_autosave_is_noop_when_not_in_edit_state() {
+        let root = new_temp_root("aus_test2");
+        let workflow = SinglelineCreateFileWorkflow::new();
+        let path = root.join("not_edit.txt");
+        let payload = EditorAutoSavePayload {
+            user_document_dir: root.clone(),
+            current_path: path.clone(),
+            editor_text: "content".to_string(),
+        };
+
+        let saved = workflow
+            .try_autosave_in_edit(payload)
+            .expect("autosave in non-edit");
+        assert!(saved.is_none());
+        assert!(!path.exists());
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn aus_test3_autosave_is_noop_when_edit_path_is_missing() {
+        let root = new_temp_root("aus_test3");
+        let workflow = SinglelineCreateFileWorkflow::new();
+        {
+            let mut state = workflow
+                .inner
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.state = SinglelineFileState::Edit;
+            state.current_edit_path = None;
+        }
+        let payload = EditorAutoSavePayload {
+            user_document_dir: root.clone(),
+            current_path: root.join("missing.txt"),
+            editor_text: "content".to_string(),
+        };
+
+        let saved = workflow
+            .try_autosave_in_edit(payload)
+            .expect("autosave with missing edit path");
+        assert!(saved.is_none());
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn aus_test6_atomic_autosave_failure_preserves_last_good_file() {
+        let root = new_temp_root("aus_test6");
+        let path = root.join("atomic.txt");
+        fs::write(&path, "old").expect("seed old file");
+
+        let error = crate::atomic_io::write_atomic_with_replace(&path, b"new", |_temp, _target| {
+            Err(io::Error::other("forced replace failure"))
+        })
+        .expect_err("forced replace failure expected");
+        assert!(error.to_string().contains("replace target"));
+
+        let content = fs::read_to_string(&path).expect("read old content");
+        assert_eq!(content, "old");
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn aus_test14_req_aus9_journal_mirrors_pending_payload_until_reset() {
+        let root = new_temp_root("aus_test14");
+        let journal_path = root.join("data").join(EDITOR_AUTOSAVE_JOURNAL_FILE_NAME);
+        let coordinator = EditorAutoSaveCoordinator::with_journal(journal_path.clone());
+        let payload = EditorAutoSavePayload {
+            user_document_dir: root.clone(),
+            current_path: root.join("note.txt"),
+            editor_text: "unsaved".to_string(),
+        };
+
+        let now = Instant::now();
+        coordinator.mark_user_edit(payload.clone(), now);
+        coordinator.flush_journal(now, EDITOR_AUTOSAVE_JOURNAL_DEBOUNCE);
+        assert!(!journal_path.exists());
+        coordinator.flush_journal(
+            now + EDITOR_AUTOSAVE_JOURNAL_DEBOUNCE,
+            EDITOR_AUTOSAVE_JOURNAL_DEBOUNCE,
+        );
+        let entries: Vec<EditorAutoSaveJournalEntry> =
+            serde_json::from_slice(&fs::read(&journal_path).expect("read journal"))
+                .expect("parse journal");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].payload, payload);
+
+        coordinator.clear_journal_if_idle();
+        coordinator.flush_journal(Instant::now(), Duration::ZERO);
+        assert!(journal_path.is_file());
+        coordinator.reset_cycle();
+        coordinator.flush_journal(Instant::now(), Duration::ZERO);
+        assert!(!journal_path.exists());
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn aus_test15_req_aus9_replay_recovers_edit_when_target_is_older() {
+        let root = new_temp_root("aus_test15");
+        let target = root.join("note.txt");
+        fs::write(&target, "before crash").expect("seed target");
+        set_file_mtime(&target, FileTime::from_unix_time(1_000_000, 0)).expect("age target");
+        let journal_path = root.join(EDITOR_AUTOSAVE_JOURNAL_FILE_NAME);
+        let coordinator = EditorAutoSaveCoordinator::with_journal(journal_path.clone());
+        coordinator.mark_user_edit(
+            EditorAutoSavePayload {
+                user_document_dir: root.clone(),
+                current_path: target.clone(),
+                editor_text: "typed before crash".to_string(),
+            },
+            Instant::now(),
+        );
+        coordinator.flush_journal(Instant::now(), Duration::ZERO);
+
+        let recovered = replay_autosave_journal(journal_path.as_path()).expect("replay");
+        assert_eq!(recovered, vec![target.clone()]);
+        assert_eq!(
+            fs::read_to_string(&target).expect("read target"),
+            "typed before crash"
+        );
+        assert!(!journal_path.exists());
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn aus_test23_replay_applies_the_vault_save_transforms() {
+        let root = new_temp_root("aus_test23");
+        let target = root.join("note.txt");
+        fs::write(&target, "before crash").expect("seed target");
+        set_file_mtime(&target, FileTime::from_unix_time(1_000_000, 0)).expect("age target");
+        // Only this vault trims and ends with a newline; other tests' roots keep the default.
+        crate::save_transforms::activate_save_transforms(
+            root.as_path(),
+            crate::save_transforms::SaveTransforms::default(),
+            Some(&crate::save_transforms::SaveTransformsOverride {
+                trim_trailing_whitespace: Some(true),
+                ensure_final_newline: Some(true),
+            }),
+        );
+        let journal_path = root.join(EDITOR_AUTOSAVE_JOURNAL_FILE_NAME);
+        let entry = EditorAutoSaveJournalEntry {
+            payload: EditorAutoSavePayload {
+                user_document_dir: root.clone(),
+                current_path: target.clone(),
+                editor_text: "typed  \nbefore crash\t".to_string(),
+            },
+            journaled_at_unix_ms: unix_millis(SystemTime::now()),
+        };
+        fs::write(&journal_path, serde_json::to_vec(&entry).expect("encode")).expect("seed");
+
+        let recovered = replay_autosave_journal(journal_path.as_path()).expect("replay");
+        assert_eq!(recovered, vec![target.clone()]);
+        assert_eq!(
+            fs::read_to_string(&target).expect("read target"),
+            "typed\nbefore crash\n"
+        );
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn aus_test16_req_aus9_replay_skips_when_target_is_newer() {
+        let root = new_temp_root("aus_test16");
+        let target = root.join("note.txt");
+        fs::write(&target, "saved later").expect("seed target");
+        let journal_path = root.join(EDITOR_AUTOSAVE_JOURNAL_FILE_NAME);
+        let entry = EditorAutoSaveJournalEntry {
+            payload: EditorAutoSavePayload {
+                user_document_dir: root.clone(),
+                current_path: target.clone(),
+                editor_text: "stale journal".to_string(),
+            },
+            journaled_at_unix_ms: 1_000,
+        };
+        fs::write(&journal_path, serde_json::to_vec(&entry).expect("encode")).expect("seed");
+
+        let recovered = replay_autosave_journal(journal_path.as_path()).expect("replay");
+        assert!(recovered.is_empty());
+        assert_eq!(
+            fs::read_to_string(&target).expect("read target"),
+            "saved later"
+        );
+        assert!(!journal_path.exists());
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn aus_test24_unreplayed_journal_entries_survive_replay_and_the_next_session() {
+        let root = new_temp_root("aus_test24");
+        let vault = root.join("offline_vault");
+        let missing = vault.join("note.txt");
+        let journal_path = root.join(EDITOR_AUTOSAVE_JOURNAL_FILE_NAME);
+        let entry = EditorAutoSaveJournalEntry {
+            payload: EditorAutoSavePayload {
+                user_document_dir: vault.clone(),
+                current_path: missing.clone(),
+                editor_text: "typed while offline".to_string(),
+            },
+            journaled_at_unix_ms: unix_millis(SystemTime::now()),
+        };
+        fs::write(&journal_path, serde_json::to_vec(&entry).expect("encode")).expect("seed");
+
+        // The vault is still offline: the note is missing, so the entry is kept.
+        let recovered = replay_autosave_journal(journal_path.as_path()).expect("replay");
+        assert!(recovered.is_empty());
+        let kept: Vec<EditorAutoSaveJournalEntry> =
+            serde_json::from_slice(&fs::read(&journal_path).expect("read journal"))
+                .expect("parse journal");
+        assert_eq!(kept, vec![entry.clone()]);
+
+        // Edits to other notes this session do not push it out of the journal.
+        let coordinator = EditorAutoSaveCoordinator::with_journal(journal_path.clone());
+        coordinator.mark_user_edit(
+            EditorAutoSavePayload {
+                user_document_dir: root.clone(),
+                current_path: root.join("other.txt"),
+                editor_text: "other".to_string(),
+            },
+            Instant::now(),
+        );
+        coordinator.reset_cycle();
+        coordinator.flush_journal(Instant::now(), Duration::ZERO);
+        let kept: Vec<EditorAutoSaveJournalEntry> =
+            serde_json::from_slice(&fs::read(&journal_path).expect("read journal"))
+                .expect("parse journal");
+        assert_eq!(kept, vec![entry.clone()]);
+
+        // Back online, the next start applies it.
+        fs::create_dir_all(&vault).expect("vault back");
+        fs::write(&missing, "before").expect("seed note");
+        set_file_mtime(&missing, FileTime::from_unix_time(1_000_000, 0)).expect("age note");
+        let recovered = replay_autosave_journal(journal_path.as_path()).expect("replay");
+        assert_eq!(recovered, vec![missing.clone()]);
+        assert_eq!(
+            fs::read_to_string(&missing).expect("read note"),
+            "typed while offline"
+        );
+        assert!(!journal_path.exists());
+
+        fs::write(&journal_path, b"{not json").expect("seed corrupt");
+        assert!(replay_autosave_journal(journal_path.as_path()).is_err());
+        assert!(!journal_path.exists());
+        assert_eq!(
+            fs::read(root.join(EDITOR_AUTOSAVE_CORRUPT_JOURNAL_FILE_NAME)).expect("read aside"),
+            b"{not json"
+        );
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn aus_test17_paused_coordinator_keeps_payload_until_resumed() {
+        let coordinator = EditorAutoSaveCoordinator::new();
+        let now = Instant::now();
+        coordinator.mark_user_edit(
+            EditorAutoSavePayload {
+                user_document_dir: PathBuf::from("C:/tmp"),
+                current_path: PathBuf::from("C:/tmp/a.txt"),
+                editor_text: "offline edit".to_string(),
+            },
+            now,
+        );
+
+        coordinator.set_paused(true);
+        assert!(
+            coordinator
+                .pop_due_payload(now + Duration::from_secs(10), Duration::from_secs(6))
+                .is_none()
+        );
+
+        coordinator.set_paused(false);
+        let payload = coordinator
+            .pop_due_payload(now + Duration::from_secs(10), Duration::from_secs(6))
+            .expect("payload after resume");
+        assert_eq!(payload.editor_text, "offline edit");
+    }
+
+    #[test]
+    fn aus_test8_path_switch_drops_pending_payload() {
+        let coordinator = EditorAutoSaveCoordinator::new();
+        let now = Instant::now();
+        let path_a = PathBuf::from("C:/tmp/a.txt");
+        let path_b = PathBuf::from("C:/tmp/b.txt");
+        coordinator.mark_user_edit(
+            EditorAutoSavePayload {
+                user_document_dir: PathBuf::from("C:/tmp"),
+                current_path: path_a,
+                editor_text: "stale".to_string(),
+            },
+            now,
+        );
+
+        coordinator.on_edit_path_changed(Some(path_b));
+        assert!(!coordinator.has_pending_payload());
+        let due =
+            coordinator.pop_due_payload(now + Duration::from_secs(10), Duration::from_secs(6));
+        assert!(due.is_none());
+    }
+
+    #[test]
+    fn aus_test22_split_pane_edit_survives_a_main_editor_switch() {
+        let coordinator = EditorAutoSaveCoordinator::new();
+        let now = Instant::now();
+        let split = PathBuf::from("C:/tmp/split.txt");
+        coordinator.set_split_path(Some(split.clone()));
+        for path in [PathBuf::from("C:/tmp/a.txt"), split.clone()] {
+            coordinator.mark_user_edit(
+                EditorAutoSavePayload {
+                    user_document_dir: PathBuf::from("C:/tmp"),
+                    current_path: path,
+                    editor_text: "edit".to_string(),
+                },
+                now,
+            );
+        }
+
+        coordinator.on_edit_path_changed(Some(PathBuf::from("C:/tmp/b.txt")));
+        assert!(!coordinator.has_pending_payload_for_path(Path::new("C:/tmp/a.txt")));
+        assert!(coordinator.has_pending_payload_for_path(&split));
+
+        coordinator.set_split_path(None);
+        coordinator.on_edit_path_changed(Some(PathBuf::from("C:/tmp/a.txt")));
+        assert!(!coordinator.has_pending_payload());
+    }
+
+    #[test]
+    fn aus_test21_pending_edits_are_kept_per_file_with_their_own_timer() {
+        let coordinator = EditorAutoSaveCoordinator::new();
+        let base = Instant::now();
+        let autosave_payload = |path: &str, text: &str| EditorAutoSavePayload {
+            user_document_dir: PathBuf::from("C:/tmp"),
+            current_path: PathBuf::from(path),
+            editor_text: text.to_string(),
+        };
+        coordinator.mark_user_edit(autosave_payload("C:/tmp/a.txt", "a1"), base);
+        coordinator.mark_user_edit(
+            autosave_payload("C:/tmp/b.txt", "b1"),
+            base + Duration::from_secs(3),
+        );
+        coordinator.mark_user_edit(
+            autosave_payload("C:/tmp/a.txt", "a2"),
+            base + Duration::from_secs(4),
+        );
+
+        let first = coordinator
+            .pop_due_payload(base + Duration::from_secs(6), Duration::from_secs(6))
+            .expect("a due");
+        assert_eq!(first.current_path, PathBuf::from("C:/tmp/a.txt"));
+        assert_eq!(first.editor_text, "a2");
+        assert!(
+            coordinator
+                .pop_due_payload(base + Duration::from_secs(6), Duration::from_secs(6))
+                .is_none()
+        );
+        assert!(coordinator.has_pending_payload_for_path(Path::new("C:/tmp/b.txt")));
+
+        let second = coordinator
+            .pop_due_payload(base + Duration::from_secs(9), Duration::from_secs(6))
+            .expect("b due");
+        assert_eq!(second.editor_text, "b1");
+        assert!(!coordinator.has_pending_payload());
+    }
+
+    #[test]
+    fn aus_test9_same_path_keeps_pending_payload() {
+        let coordinator = EditorAutoSaveCoordinator::new();
+        let now = Instant::now();
+        let path_a = PathBuf::from("C:/tmp/a.txt");
+        coordinator.mark_user_edit(
+            EditorAutoSavePayload {
+                user_document_dir: PathBuf::from("C:/tmp"),
+                current_path: path_a.clone(),
+                editor_text: "keep".to_string(),
+            },
+            now,
+        );
+
+        coordinator.on_edit_path_changed(Some(path_a.clone()));
+        assert!(coordinator.has_pending_payload());
+        let due = coordinator
+            .pop_due_payload(now + Duration::from_secs(6), Duration::from_secs(6))
+            .expect("due payload should remain");
+        assert_eq!(due.current_path, path_a);
+        assert_eq!(due.editor_text, "keep");
+    }
+
+    #[test]
+    fn aus_test10_autosave_and_path_transition_are_serialized() {
+        use std::sync::{Arc, Barrier, mpsc};
+
+        let root = new_temp_root("aus_test10");
+        let now = fixed_now();
+        let daily = note_directory(root.as_path(), now);
+        fs::create_dir_all(&daily).expect("create daily directory");
+        let path_a = daily.join("fileA.txt");
+        let path_b = daily.join("fileB.txt");
+        fs::write(&path_a, "A-old").expect("seed fileA");
+        fs::write(&path_b, "B-old").expect("seed fileB");
+
+        let workflow = SinglelineCreateFileWorkflow::new();
+        workflow.set_edit_from_open_file(path_a.clone());
+
+        let payload = EditorAutoSavePayload {
+            user_document_dir: root.clone(),
+            current_path: path_a.clone(),
+            editor_text: "A-new".to_string(),
+        };
+
+        let barrier = Arc::new(Barrier::new(3));
+        let (autosave_tx, autosave_rx) = mpsc::channel();
+        let workflow_for_autosave = workflow.clone();
+        let barrier_for_autosave = barrier.clone();
+        let autosave_thread = thread::spawn(move || {
+            barrier_for_autosave.wait();
+            let result = workflow_for_autosave
+                .try_autosave_in_edit(payload)
+                .expect("autosave call");
+            autosave_tx.send(result).expect("send autosave result");
+        });
+
+        let workflow_for_switch = workflow.clone();
+        let barrier_for_switch = barrier.clone();
+        let path_b_for_switch = path_b.clone();
+        let switch_thread = thread::spawn(move || {
+            barrier_for_switch.wait();
+            workflow_for_switch.set_edit_from_open_file(path_b_for_switch);
+        });
+
+        barrier.wait();
+        autosave_thread.join().expect("join autosave thread");
+        switch_thread.join().expect("join switch thread");
+        let autosaved = autosave_rx.recv().expect("receive autosave result");
+
+        let content_b = fs::read_to_string(&path_b).expect("read fileB");
+        assert_eq!(content_b, "B-old");
+
+        let content_a = fs::read_to_string(&path_a).expect("read fileA");
+        if autosaved.is_some() {
+            assert_eq!(content_a, "A-new");
+        } else {
+            assert_eq!(content_a, "A-old");
+        }
+
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn aus_test11_req_aus6_pre_new_file_flushes_before_edit_to_neutral() {
+        let root = new_temp_root("aus_test11");
+        let workflow = SinglelineCreateFileWorkflow::new();
+        let path_a = workflow
+            .try_create_from_neutral("fileA", root.as_path(), Instant::now(), fixed_now())
+            .expect("create")
+            .expect("created path");
+
+        fs::write(&path_a, "A-old").expect("seed fileA");
+        let flushed = workflow
+            .flush_editor_content_in_edit("A-new", root.as_path())
+            .expect("flush before plus");
+        assert!(flushed.is_some());
+        let updated_path = workflow
+            .current_edit_path()
+            .expect("current path after flush");
+        let moved = workflow.transition_edit_to_neutral();
+        assert!(moved);
+
+        let content_a = fs::read_to_string(&updated_path).expect("read updated fileA path");
+        assert_eq!(content_a, "A-new");
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn aus_test12_req_aus8_pre_open_file_flushes_previous_file_before_switch() {
+        let root = new_temp_root("aus_test12");
+        let now = fixed_now();
+        let daily = note_directory(root.as_path(), now);
+        fs::create_dir_all(&daily).expect("create daily directory");
+        let path_a = daily.join("fileA.txt");
+        let path_b = daily.join("fileB.txt");
+        fs::write(&path_a, "A-old").expect("seed fileA");
+        fs::write(&path_b, "B-old").expect("seed fileB");
+
+        let workflow = SinglelineCreateFileWorkflow::new();
+        workflow.set_edit_from_open_file(path_a.clone());
+
+        let flushed = workflow
+            .flush_editor_content_in_edit("A-new", root.as_path())
+            .expect("flush before open fileB");
+        assert!(flushed.is_some());
+        let updated_path_a = workflow
+            .current_edit_path()
+            .expect("current path after pre-open flush");
+        assert_eq!(
+            fs::read_to_string(&updated_path_a).expect("read updated fileA"),
+            "A-new"
+        );
+
+        workflow.set_edit_from_open_file(path_b.clone());
+
+        let content_b = fs::read_to_string(&path_b).expect("read fileB");
+        assert_eq!(content_b, "B-old");
+        assert_eq!(workflow.current_edit_path(), Some(path_b));
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn aus_test13_req_aus7_pre_close_flushes_without_path_transition() {
+        let root = new_temp_root("aus_test13");
+        let now = fixed_now();
+        let daily = note_directory(root.as_path(), now);
+        fs::create_dir_all(&daily).expect("create daily directory");
+        let path_a = daily.join("fileA.txt");
+        fs::write(&path_a, "A-old").expect("seed fileA");
+
+        let workflow = SinglelineCreateFileWorkflow::new();
+        workflow.set_edit_from_open_file(path_a.clone());
+
+        let flushed = workflow
+            .flush_editor_content_in_edit("A-new", root.as_path())
+            .expect("flush before close");
+        assert!(flushed.is_some());
+        let updated_path = workflow
+            .current_edit_path()
+            .expect("current path after pre-close flush");
+        let content_a = fs::read_to_string(&updated_path).expect("read updated fileA");
+        assert_eq!(content_a, "A-new");
+        assert_eq!(workflow.current_edit_path(), Some(updated_path));
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn ftr_test95_req_ftr24_selection_switch_without_pending_edit_does_not_move_previous_file() {
+        let root = new_temp_root("ftr_test95");
+        let old_daily = note_directory(root.as_path(), fixed_now());
+        fs::create_dir_all(&old_daily).expect("create old daily directory");
+        let path_a = old_daily.join("fileA.txt");
+        let path_b = old_daily.join("fileB.txt");
+        fs::write(&path_a, "A-old").expect("seed fileA");
+        fs::write(&path_b, "B-old").expect("seed fileB");
+
+        let today_daily = note_directory(root.as_path(), Local::now());
+        let moved_candidate = today_daily.join("fileA.txt");
+        assert_ne!(path_a, moved_candidate);
+
+        let workflow = SinglelineCreateFileWorkflow::new();
+        let autosave = EditorAutoSaveCoordinator::new();
+        workflow.set_edit_from_open_file(path_a.clone());
+
+        let should_flush = super::should_flush_pre_switch_editor_content(
+            "req-aus8-open-file",
+            path_a.as_path(),
+            &autosave,
+        );
+        assert!(!should_flush);
+
+        workflow.set_edit_from_open_file(path_b.clone());
+        assert_eq!(workflow.current_edit_path(), Some(path_b));
+        assert!(
+            path_a.is_file(),
+            "fileA should remain in original dated directory"
+        );
+        assert_eq!(
+            fs::read_to_string(&path_a).expect("read fileA after selection switch"),
+            "A-old"
+        );
+        assert!(
+            !moved_candidate.exists(),
+            "fileA should not be moved to today's daily directory on selection-only switch"
+        );
+
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn ftr_test96_req_ftr24_dirty_edit_switch_still_flushes_and_relocates() {
+        let root = new_temp_root("ftr_test96");
+        let old_daily = note_directory(root.as_path(), fixed_now());
+        fs::create_dir_all(&old_daily).expect("create old daily directory");
+        let path_a = old_daily.join("fileA.txt");
+        fs::write(&path_a, "A-old").expect("seed fileA");
+
+        let workflow = SinglelineCreateFileWorkflow::new();
+        let autosave = EditorAutoSaveCoordinator::new();
+        workflow.set_edit_from_open_file(path_a.clone());
+
+        autosave.mark_user_edit(
+            EditorAutoSavePayload {
+                user_document_dir: root.clone(),
+                current_path: path_a.clone(),
+                editor_text: "A-new".to_string(),
+            },
+            Instant::now(),
+        );
+        assert!(autosave.has_pending_payload_for_path(path_a.as_path()));
+
+        let should_flush = super::should_flush_pre_switch_editor_content(
+            "req-aus8-open-file",
+            path_a.as_path(),
+            &autosave,
+        );
+        assert!(should_flush);
+        let flushed = workflow
+            .flush_editor_content_in_edit("A-new", root.as_path())
+            .expect("dirty pre-switch flush should succeed");
+        assert!(flushed.is_some());
+
+        let updated_path = workflow
+            .current_edit_path()
+            .expect("current path after dirty pre-switch flush");
+        let today_daily = note_directory(root.as_path(), Local::now());
+        assert!(
+            updated_path.starts_with(today_daily.as_path()),
+            "dirty pre-switch flush should preserve req-newf35 relocation behavior"
+        );
+        assert_eq!(
+            fs::read_to_string(&updated_path).expect("read relocated fileA"),
+            "A-new"
+        );
+
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn ftr_test97_req_ftr24_pre_close_without_pending_edit_is_noop() {
+        let root = new_temp_root("ftr_test97");
+        let old_daily = note_directory(root.as_path(), fixed_now());
+        fs::create_dir_all(&old_daily).expect("create old daily directory");
+        let path_a = old_daily.join("fileA.txt");
+        fs::write(&path_a, "A-old").expect("seed fileA");
+
+        let today_daily = note_directory(root.as_path(), Local::now());
+        let moved_candidate = today_daily.join("fileA.txt");
+        assert_ne!(path_a, moved_candidate);
+
+        let workflow = SinglelineCreateFileWorkflow::new();
+        let autosave = EditorAutoSaveCoordinator::new();
+        workflow.set_edit_from_open_file(path_a.clone());
+
+        let should_flush = super::should_flush_pre_switch_editor_content(
+            "req-aus7-window-close",
+            path_a.as_path(),
+            &autosave,
+        );
+        assert!(!should_flush);
+
+        assert_eq!(workflow.current_edit_path(), Some(path_a.clone()));
+        assert_eq!(
+            fs::read_to_string(&path_a).expect("read fileA after pre-close no-op"),
+            "A-old"
+        );
+        assert!(
+            !moved_candidate.exists(),
+            "pre-close without pending user edit should not relocate file"
+        );
+
         workflow.dispatcher.shutdown();
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn newf_test41_req_newf37_revert_empty_create_keeps_singleline_blank() {
-        let root = new_temp_root("newf_test41");
-        let now = fixed_now();
+    fn ftr_test98_req_ftr24_selection_switch_loads_target_without_mutating_previous_file() {
+        let root = new_temp_root("ftr_test98");
+        let old_daily = note_directory(root.as_path(), fixed_now());
+        fs::create_dir_all(&old_daily).expect("create old daily directory");
+        let path_a = old_daily.join("fileA.txt");
+        let path_b = old_daily.join("fileB.txt");
+        fs::write(&path_a, "A-old").expect("seed fileA");
+        fs::write(&path_b, "B-old").expect("seed fileB");
+
         let workflow = SinglelineCreateFileWorkflow::new();
+        let autosave = EditorAutoSaveCoordinator::new();
 
-        let created_path = workflow
-            .try_create_from_neutral("", root.as_path(), Instant::now(), now)
-            .expect("create from empty singleline")
-            .expect("created path");
-        let expected_stem = created_path
-            .file_stem()
-            .and_then(|stem| stem.to_str())
-            .expect("created path stem")
-            .to_string();
+        workflow.set_edit_from_open_file(path_a.clone());
+        assert!(
+            !super::should_flush_pre_switch_editor_content(
+                "req-aus8-open-file",
+                path_a.as_path(),
+                &autosave
+            ),
+            "selection switch A -> B without user edit should not flush A"
+        );
+        workflow.set_edit_from_open_file(path_b.clone());
 
-        let forced = forced_singleline_stem_after_create("", created_path.as_path(), now);
+        assert!(
+            !super::should_flush_pre_switch_editor_content(
+                "req-aus8-open-file",
+                path_b.as_path(),
+                &autosave
+            ),
+            "selection switch B -> A without user edit should not flush B"
+        );
+        workflow.set_edit_from_open_file(path_a.clone());
 
-        assert!(expected_stem.starts_with("notitle-"));
-        assert_eq!(forced, None);
+        assert_eq!(workflow.current_edit_path(), Some(path_a.clone()));
+        assert_eq!(
+            fs::read_to_string(&path_a).expect("read fileA final"),
+            "A-old"
+        );
+        assert_eq!(
+            fs::read_to_string(&path_b).expect("read fileB final"),
+            "B-old"
+        );
 
         workflow.dispatcher.shutdown();
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn newf_test43_req_newf37_preserves_req_newf32_nonempty_no_forced_rewrite() {
-        let root = new_temp_root("newf_test43");
+    fn qsrv_file_test1_rpc_pin_reads_content_clamps_line_and_updates_mtime() {
+        let root = new_temp_root("qsrv_file_test1");
         let now = fixed_now();
-        let first_instant = Instant::now();
+        let daily = note_directory(root.as_path(), now);
+        fs::create_dir_all(&daily).expect("create daily directory");
+        let target = daily.join("fileA.txt");
+        fs::write(&target, "line1\nline2\nline3").expect("seed target file");
+
+        let old = FileTime::from_unix_time(1, 0);
+        set_file_mtime(target.as_path(), old).expect("set old modified time");
+        let modified_before = fs::metadata(&target)
+            .expect("metadata before rpc pin")
+            .modified()
+            .expect("modified before rpc pin");
 
         let workflow = SinglelineCreateFileWorkflow::new();
-        let _first_path = workflow
-            .try_create_from_neutral("filename", root.as_path(), first_instant, now)
-            .expect("first create")
-            .expect("first path");
-        let transitioned = workflow.transition_edit_to_neutral();
-        assert!(transitioned, "workflow should transition back to neutral");
+        let pinned = workflow
+            .try_pin_file_via_rpc(root.clone(), target.clone(), 999)
+            .expect("rpc pin must succeed");
 
-        let second_path = workflow
-            .try_create_from_neutral(
-                "filename",
-                root.as_path(),
-                first_instant + CREATE_EVENT_MIN_INTERVAL + Duration::from_millis(1),
-                now,
-            )
-            .expect("second create with collision")
-            .expect("second path");
-        let second_stem = second_path
-            .file_stem()
-            .and_then(|stem| stem.to_str())
-            .expect("second path stem")
+        let today_dir = note_directory(root.as_path(), Local::now());
+        let expected_file_name = target
+            .file_name()
+            .expect("target file name exists")
+            .to_string_lossy()
             .to_string();
 
         assert!(
-            second_stem.starts_with("filename"),
-            "resolved stem should keep filename prefix"
-        );
-        assert_eq!(
-            forced_singleline_stem_after_create("filename", second_path.as_path(), now),
-            None
+            pinned.path.starts_with(today_dir.as_path()),
+            "rpc pin should relocate into today's daily directory"
         );
         assert_eq!(
-            forced_singleline_stem_after_rename("filename", second_path.as_path(), now),
-            None
+            pinned
+                .path
+                .file_name()
+                .expect("pinned file name exists")
+                .to_string_lossy(),
+            expected_file_name
         );
+        assert_eq!(pinned.content, "line1\nline2\nline3");
+        assert_eq!(pinned.linenum, 3);
+
+        let modified_after = fs::metadata(&pinned.path)
+            .expect("metadata after rpc pin")
+            .modified()
+            .expect("modified after rpc pin");
+        assert!(modified_after > modified_before);
 
         workflow.dispatcher.shutdown();
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn aus_test1_autosave_event_writes_latest_editor_text() {
-        let root = new_temp_root("aus_test1");
+    fn qsrv_file_test3_req_qsrv4_follow_mtime_modify_event_is_watcher_refresh_eligible() {
+        let root = new_temp_root("qsrv_file_test3");
+        let now = fixed_now();
+        let daily = note_directory(root.as_path(), now);
+        fs::create_dir_all(&daily).expect("create daily directory");
+        let target = daily.join("fileA.txt");
+        fs::write(&target, "line1\nline2\nline3").expect("seed target file");
+
         let workflow = SinglelineCreateFileWorkflow::new();
-        let path = workflow
-            .try_create_from_neutral("autosave", root.as_path(), Instant::now(), fixed_now())
-            .expect("create")
-            .expect("created path");
-        let payload = EditorAutoSavePayload {
-            user_document_dir: root.clone(),
-            current_path: path,
-            editor_text: "line-1\nline-2".to_string(),
+        let pinned = workflow
+            .try_pin_file_via_rpc(root.clone(), target.clone(), 2)
+            .expect("rpc pin must succeed");
+
+        let mtime_modify_event = notify::Event {
+            kind: notify::EventKind::Modify(notify::event::ModifyKind::Metadata(
+                notify::event::MetadataKind::WriteTime,
+            )),
+            paths: vec![pinned.path.clone()],
+            attrs: Default::default(),
         };
+        assert!(crate::file_tree_watcher::should_schedule_refresh(
+            root.as_path(),
+            &mtime_modify_event,
+        ));
 
-        let saved = workflow
-            .try_autosave_in_edit(payload)
-            .expect("dispatch autosave");
-        assert!(saved);
-        let current = workflow
-            .current_edit_path()
-            .expect("current path after autosave");
-        let content = fs::read_to_string(&current).expect("read autosaved file");
-        assert_eq!(content, "line-1\nline-2");
         workflow.dispatcher.shutdown();
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn aus_test2_autosave_is_noop_when_not_in_edit_state() {
-        let root = new_temp_root("aus_test2");
+    fn qsrv_file_test2_rpc_pin_missing_file_returns_not_found() {
+        let root = new_temp_root("qsrv_file_test2");
         let workflow = SinglelineCreateFileWorkflow::new();
-        let path = root.join("not_edit.txt");
-        let payload = EditorAutoSavePayload {
-            user_document_dir: root.clone(),
-            current_path: path.clone(),
-            editor_text: "content".to_string(),
-        };
+        let missing = root.join("2026").join("03").join("22").join("missing.txt");
+
+        let error = workflow
+            .try_pin_file_via_rpc(root.clone(), missing.clone(), 1)
+            .expect_err("missing file must fail");
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(error.to_string().contains("does not exist"));
 
-        let saved = workflow
-            .try_autosave_in_edit(payload)
-            .expect("autosave in non-edit");
-        assert!(!saved);
-        assert!(!path.exists());
         workflow.dispatcher.shutdown();
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn aus_test3_autosave_is_noop_when_edit_path_is_missing() {
-        let root = new_temp_root("aus_test3");
-        let workflow = SinglelineCreateFileWorkflow::new();
-        {
-            let mut state = workflow
-                .inner
-                .lock()
-                .unwrap_or_else(|poisoned| poisoned.into_inner());
-            state.state = SinglelineFileState::Edit;
-            state.current_edit_path = None;
-        }
-        let payload = EditorAutoSavePayload {
+    fn newf_test44_req_newf39_md_extension_is_used_for_create_and_collision_suffix() {
+        let root = new_temp_root("newf_test44");
+        let now = fixed_now();
+        let first = create_new_text_file(&CreateFileRequest {
             user_document_dir: root.clone(),
-            current_path: root.join("missing.txt"),
-            editor_text: "content".to_string(),
-        };
+            singleline_value: "memo".to_string(),
+            now,
+            extension: NoteFileExtension::Md,
+        })
+        .expect("create first md file");
+        let second = create_new_text_file(&CreateFileRequest {
+            user_document_dir: root.clone(),
+            singleline_value: "memo".to_string(),
+            now,
+            extension: NoteFileExtension::Md,
+        })
+        .expect("create second md file");
 
-        let saved = workflow
-            .try_autosave_in_edit(payload)
-            .expect("autosave with missing edit path");
-        assert!(!saved);
-        workflow.dispatcher.shutdown();
+        assert!(first.ends_with(Path::new("memo.md")));
+        assert!(second.ends_with(Path::new("memo_2.md")));
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn aus_test6_atomic_autosave_failure_preserves_last_good_file() {
-        let root = new_temp_root("aus_test6");
-        let path = root.join("atomic.txt");
-        fs::write(&path, "old").expect("seed old file");
+    fn newf_test45_req_newf39_rename_keeps_existing_note_extension() {
+        let root = new_temp_root("newf_test45");
+        let now = fixed_now();
+        let created = create_new_text_file(&CreateFileRequest {
+            user_document_dir: root.clone(),
+            singleline_value: "draft".to_string(),
+            now,
+            extension: NoteFileExtension::Md,
+        })
+        .expect("create md file");
 
-        let error = write_editor_text_atomic_with_replace(&path, b"new", |_temp, _target| {
-            Err(io::Error::other("forced replace failure"))
+        let renamed = rename_text_file(&RenameFileRequest {
+            user_document_dir: root.clone(),
+            current_path: created.clone(),
+            singleline_value: "final".to_string(),
+            now,
+            extension: NoteFileExtension::Txt,
         })
-        .expect_err("forced replace failure expected");
-        assert!(error.to_string().contains("replace target"));
+        .expect("rename md file");
 
-        let content = fs::read_to_string(&path).expect("read old content");
-        assert_eq!(content, "old");
+        assert!(renamed.ends_with(Path::new("final.md")));
+        assert!(!created.exists());
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn aus_test8_path_switch_drops_pending_payload() {
-        let coordinator = EditorAutoSaveCoordinator::new();
-        let now = Instant::now();
-        let path_a = PathBuf::from("C:/tmp/a.txt");
-        let path_b = PathBuf::from("C:/tmp/b.txt");
-        coordinator.mark_user_edit(
-            EditorAutoSavePayload {
-                user_document_dir: PathBuf::from("C:/tmp"),
-                current_path: path_a,
-                editor_text: "stale".to_string(),
-            },
-            now,
+    fn newf_test46_req_newf39_workflow_uses_configured_new_note_extension() {
+        let root = new_temp_root("newf_test46");
+        let workflow = SinglelineCreateFileWorkflow::new();
+        assert_eq!(workflow.new_note_extension(), NoteFileExtension::Txt);
+        workflow.set_new_note_extension(NoteFileExtension::Md);
+
+        let created = workflow
+            .try_create_from_neutral("hello", root.as_path(), Instant::now(), fixed_now())
+            .expect("create from neutral")
+            .expect("created path");
+
+        assert_eq!(
+            created.extension().and_then(|value| value.to_str()),
+            Some("md")
+        );
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn newf_test56_create_from_neutral_fills_next_template_once() {
+        let root = new_temp_root("newf_test56");
+        let workflow = SinglelineCreateFileWorkflow::new();
+        workflow.set_next_note_template(Some("# Standup\n{{date}}\n".to_string()));
+
+        let created = workflow
+            .try_create_from_neutral("standup", root.as_path(), Instant::now(), fixed_now())
+            .expect("create from neutral")
+            .expect("created path");
+
+        assert_eq!(
+            fs::read_to_string(&created).expect("read created"),
+            format!("# Standup\n{}\n", fixed_now().format("%Y-%m-%d"))
         );
-
-        coordinator.on_edit_path_changed(Some(path_b));
-        assert!(!coordinator.has_pending_payload());
-        let due =
-            coordinator.pop_due_payload(now + Duration::from_secs(10), Duration::from_secs(6));
-        assert!(due.is_none());
+        assert!(
+            workflow
+                .inner
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .next_note_template
+                .is_none()
+        );
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn aus_test9_same_path_keeps_pending_payload() {
-        let coordinator = EditorAutoSaveCoordinator::new();
-        let now = Instant::now();
-        let path_a = PathBuf::from("C:/tmp/a.txt");
-        coordinator.mark_user_edit(
-            EditorAutoSavePayload {
-                user_document_dir: PathBuf::from("C:/tmp"),
-                current_path: path_a.clone(),
-                editor_text: "keep".to_string(),
-            },
-            now,
+    fn newf_test47_req_newf39_extension_parse_accepts_txt_and_md_only() {
+        assert_eq!(
+            NoteFileExtension::parse("txt"),
+            Some(NoteFileExtension::Txt)
         );
-
-        coordinator.on_edit_path_changed(Some(path_a.clone()));
-        assert!(coordinator.has_pending_payload());
-        let due = coordinator
-            .pop_due_payload(now + Duration::from_secs(6), Duration::from_secs(6))
-            .expect("due payload should remain");
-        assert_eq!(due.current_path, path_a);
-        assert_eq!(due.editor_text, "keep");
+        assert_eq!(
+            NoteFileExtension::parse(" .MD "),
+            Some(NoteFileExtension::Md)
+        );
+        assert_eq!(NoteFileExtension::parse("rs"), None);
+        assert_eq!(
+            NoteFileExtension::from_path(Path::new("C:/tmp/a.md")),
+            Some(NoteFileExtension::Md)
+        );
+        assert_eq!(NoteFileExtension::from_path(Path::new("C:/tmp/a")), None);
     }
 
     #[test]
-    fn aus_test10_autosave_and_path_transition_are_serialized() {
-        use std::sync::{Arc, Barrier, mpsc};
+    fn newf_test48_dispatch_async_returns_before_callback_and_delivers_result() {
+        let root = new_temp_root("newf_test48");
+        let dispatcher = FileWorkflowEventDispatcher::new();
+        let (result_tx, result_rx) = mpsc::channel();
 
-        let root = new_temp_root("aus_test10");
-        let now = fixed_now();
-        let daily = daily_directory(root.as_path(), now);
-        fs::create_dir_all(&daily).expect("create daily directory");
-        let path_a = daily.join("fileA.txt");
-        let path_b = daily.join("fileB.txt");
-        fs::write(&path_a, "A-old").expect("seed fileA");
-        fs::write(&path_b, "B-old").expect("seed fileB");
+        dispatcher
+            .dispatch_async(
+                FileWorkflowEvent::Create(CreateFileRequest {
+                    user_document_dir: root.clone(),
+                    singleline_value: "async".to_string(),
+                    now: fixed_now(),
+                    extension: NoteFileExtension::Txt,
+                }),
+                move |result| {
+                    let _ = result_tx.send(result);
+                },
+            )
+            .expect("enqueue async create");
+
+        let result = result_rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("callback result")
+            .expect("create result");
+        let FileWorkflowEventResult::Created { path } = result else {
+            panic!("expected Created result");
+        };
+        assert!(path.ends_with(Path::new("async.txt")));
+        assert!(path.is_file());
+        dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
 
+    #[test]
+    fn newf_test49_async_create_transitions_to_edit_before_completion_callback() {
+        let root = new_temp_root("newf_test49");
         let workflow = SinglelineCreateFileWorkflow::new();
-        workflow.set_edit_from_open_file(path_a.clone());
+        let (result_tx, result_rx) = mpsc::channel();
+        let workflow_in_callback = workflow.clone();
 
-        let payload = EditorAutoSavePayload {
-            user_document_dir: root.clone(),
-            current_path: path_a.clone(),
-            editor_text: "A-new".to_string(),
+        let dispatched = workflow
+            .try_create_from_neutral_async(
+                "hello",
+                root.as_path(),
+                Instant::now(),
+                fixed_now(),
+                move |result| {
+                    let snapshot = workflow_in_callback.snapshot();
+                    let _ = result_tx.send((result, snapshot));
+                },
+            )
+            .expect("dispatch async create");
+        assert!(dispatched);
+        assert!(
+            !workflow
+                .try_create_from_neutral_async(
+                    "again",
+                    root.as_path(),
+                    Instant::now(),
+                    fixed_now(),
+                    |_| {},
+                )
+                .expect("second dispatch"),
+            "state gate must reject a second create while NEW/EDIT"
+        );
+
+        let (result, snapshot_in_callback) = result_rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("completion callback");
+        let path = result.expect("create result").expect("created path");
+        assert_eq!(snapshot_in_callback.state, SinglelineFileState::Edit);
+        assert_eq!(snapshot_in_callback.current_edit_path, Some(path));
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn newf_test50_async_create_completion_keeps_newer_open_file_state() {
+        let mut state = WorkflowStateInner {
+            state: SinglelineFileState::Edit,
+            current_edit_path: Some(PathBuf::from("C:/tmp/opened.txt")),
+            last_create_event_raised_at: None,
+            new_note_extension: NoteFileExtension::Txt,
+            next_note_template: None,
+            edit_identity: None,
+            split_edit_path: None,
+            edit_path_updates: None,
+            rename: RenameCoalescing::default(),
+            transition_journal: None,
         };
 
-        let barrier = Arc::new(Barrier::new(3));
-        let (autosave_tx, autosave_rx) = mpsc::channel();
-        let workflow_for_autosave = workflow.clone();
-        let barrier_for_autosave = barrier.clone();
-        let autosave_thread = thread::spawn(move || {
-            barrier_for_autosave.wait();
-            let result = workflow_for_autosave
-                .try_autosave_in_edit(payload)
-                .expect("autosave call");
-            autosave_tx.send(result).expect("send autosave result");
-        });
+        let completed = complete_create_from_new(
+            &mut state,
+            Ok(FileWorkflowEventResult::Created {
+                path: PathBuf::from("C:/tmp/created.txt"),
+            }),
+        )
+        .expect("complete create");
 
-        let workflow_for_switch = workflow.clone();
-        let barrier_for_switch = barrier.clone();
-        let path_b_for_switch = path_b.clone();
-        let switch_thread = thread::spawn(move || {
-            barrier_for_switch.wait();
-            workflow_for_switch.set_edit_from_open_file(path_b_for_switch);
-        });
+        assert_eq!(completed, Some(PathBuf::from("C:/tmp/created.txt")));
+        assert_eq!(state.state, SinglelineFileState::Edit);
+        assert_eq!(
+            state.current_edit_path,
+            Some(PathBuf::from("C:/tmp/opened.txt"))
+        );
 
-        barrier.wait();
-        autosave_thread.join().expect("join autosave thread");
-        switch_thread.join().expect("join switch thread");
-        let autosaved = autosave_rx.recv().expect("receive autosave result");
+        let mut new_state = WorkflowStateInner {
+            state: SinglelineFileState::New,
+            current_edit_path: None,
+            last_create_event_raised_at: None,
+            new_note_extension: NoteFileExtension::Txt,
+            next_note_template: None,
+            edit_identity: None,
+            split_edit_path: None,
+            edit_path_updates: None,
+            rename: RenameCoalescing::default(),
+            transition_journal: None,
+        };
+        let error = complete_create_from_new(
+            &mut new_state,
+            Err(WorkflowError::InvalidRequest("disk gone".to_string())),
+        )
+        .expect_err("create error must propagate");
+        assert_eq!(error.to_string(), "disk gone");
+        assert_eq!(new_state.state, SinglelineFileState::Neutral);
+    }
 
-        let content_b = fs::read_to_string(&path_b).expect("read fileB");
-        assert_eq!(content_b, "B-old");
+    #[test]
+    fn replace_test3_bulk_replace_event_rewrites_matching_files_and_reports() {
+        let root = new_temp_root("replace_test3");
+        let user_document_dir = root.join("user_document");
+        fs::create_dir_all(&user_document_dir).expect("create user_document");
+        let hit = user_document_dir.join("hit.txt");
+        let miss = user_document_dir.join("miss.txt");
+        let outside = root.join("outside.txt");
+        fs::write(&hit, "alpha beta alpha").expect("write hit");
+        fs::write(&miss, "gamma").expect("write miss");
+        fs::write(&outside, "alpha").expect("write outside");
 
-        let content_a = fs::read_to_string(&path_a).expect("read fileA");
-        if autosaved {
-            assert_eq!(content_a, "A-new");
-        } else {
-            assert_eq!(content_a, "A-old");
-        }
+        let workflow = SinglelineCreateFileWorkflow::new();
+        let (report_tx, report_rx) = mpsc::channel();
+        workflow
+            .bulk_replace_async(
+                BulkReplaceRequest {
+                    user_document_dir: user_document_dir.clone(),
+                    paths: vec![hit.clone(), miss.clone(), outside.clone()],
+                    find: "alpha".to_string(),
+                    replace: "omega".to_string(),
+                },
+                move |result| {
+                    let _ = report_tx.send(result);
+                },
+            )
+            .expect("dispatch bulk replace");
+        let report = report_rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("bulk replace callback")
+            .expect("bulk replace report");
+
+        assert_eq!(report.replaced, vec![(hit.clone(), 2)]);
+        assert_eq!(report.unchanged, 1);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(
+            report.summary(),
+            "Replaced 2 occurrence(s) in 1 file(s), 1 without matches, 1 failed"
+        );
+        assert_eq!(
+            fs::read_to_string(&hit).expect("read hit"),
+            "omega beta omega"
+        );
+        assert_eq!(fs::read_to_string(&outside).expect("read outside"), "alpha");
 
+        let empty = bulk_replace_files(&BulkReplaceRequest {
+            user_document_dir,
+            paths: vec![hit],
+            find: String::new(),
+            replace: "x".to_string(),
+        });
+        assert_eq!(
+            empty.expect_err("empty find").kind(),
+            io::ErrorKind::InvalidInput
+        );
         workflow.dispatcher.shutdown();
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn aus_test11_req_aus6_pre_new_file_flushes_before_edit_to_neutral() {
-        let root = new_temp_root("aus_test11");
+    fn newf_test52_promote_scratch_creates_filled_note_and_keeps_state_neutral() {
+        let root = new_temp_root("newf_test52");
+        let user_document_dir = root.join("user_document");
+        fs::create_dir_all(&user_document_dir).expect("create user_document");
         let workflow = SinglelineCreateFileWorkflow::new();
-        let path_a = workflow
-            .try_create_from_neutral("fileA", root.as_path(), Instant::now(), fixed_now())
-            .expect("create")
-            .expect("created path");
 
-        fs::write(&path_a, "A-old").expect("seed fileA");
-        let flushed = workflow
-            .flush_editor_content_in_edit("A-new", root.as_path())
-            .expect("flush before plus");
-        assert!(flushed);
-        let updated_path = workflow
-            .current_edit_path()
-            .expect("current path after flush");
-        let moved = workflow.transition_edit_to_neutral();
-        assert!(moved);
+        let path = workflow
+            .promote_scratch_blocking(
+                "pasted: log",
+                "pasted: log\nline two",
+                user_document_dir.as_path(),
+                fixed_now(),
+            )
+            .expect("promote scratch");
 
-        let content_a = fs::read_to_string(&updated_path).expect("read updated fileA path");
-        assert_eq!(content_a, "A-new");
+        assert_eq!(
+            path,
+            note_directory(user_document_dir.as_path(), fixed_now()).join("pasted_ log.txt")
+        );
+        assert_eq!(
+            fs::read_to_string(&path).expect("read promoted"),
+            "pasted: log\nline two"
+        );
+        assert_eq!(workflow.state(), SinglelineFileState::Neutral);
+        assert_eq!(workflow.current_edit_path(), None);
         workflow.dispatcher.shutdown();
         remove_temp_root(root.as_path());
     }
 
+    #[cfg(unix)]
     #[test]
-    fn aus_test12_req_aus8_pre_open_file_flushes_previous_file_before_switch() {
-        let root = new_temp_root("aus_test12");
-        let now = fixed_now();
-        let daily = daily_directory(root.as_path(), now);
-        fs::create_dir_all(&daily).expect("create daily directory");
-        let path_a = daily.join("fileA.txt");
-        let path_b = daily.join("fileB.txt");
-        fs::write(&path_a, "A-old").expect("seed fileA");
-        fs::write(&path_b, "B-old").expect("seed fileB");
+    fn newf_test53_autosave_follows_note_renamed_outside_the_app() {
+        let root = new_temp_root("newf_test53");
+        let today = ensure_note_directory(root.as_path(), Local::now()).expect("daily dir");
+        let source = today.join("before.txt");
+        let renamed = today.join("after.txt");
+        fs::write(&source, "old").expect("seed source");
 
         let workflow = SinglelineCreateFileWorkflow::new();
-        workflow.set_edit_from_open_file(path_a.clone());
-
-        let flushed = workflow
-            .flush_editor_content_in_edit("A-new", root.as_path())
-            .expect("flush before open fileB");
-        assert!(flushed);
-        let updated_path_a = workflow
-            .current_edit_path()
-            .expect("current path after pre-open flush");
-        assert_eq!(
-            fs::read_to_string(&updated_path_a).expect("read updated fileA"),
-            "A-new"
-        );
+        let updates = workflow.subscribe_edit_path_updates();
+        workflow.set_edit_from_open_file(source.clone());
+        fs::rename(&source, &renamed).expect("outside rename");
 
-        workflow.set_edit_from_open_file(path_b.clone());
+        let saved = workflow
+            .try_autosave_in_edit(EditorAutoSavePayload {
+                user_document_dir: root.clone(),
+                current_path: source.clone(),
+                editor_text: "new".to_string(),
+            })
+            .expect("autosave after outside rename");
 
-        let content_b = fs::read_to_string(&path_b).expect("read fileB");
-        assert_eq!(content_b, "B-old");
-        assert_eq!(workflow.current_edit_path(), Some(path_b));
+        assert!(saved.is_some());
+        assert_eq!(workflow.current_edit_path(), Some(renamed.clone()));
+        assert_eq!(fs::read_to_string(&renamed).expect("read renamed"), "new");
+        assert!(!source.exists());
+        assert_eq!(updates.try_recv().ok(), Some(renamed));
         workflow.dispatcher.shutdown();
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn aus_test13_req_aus7_pre_close_flushes_without_path_transition() {
-        let root = new_temp_root("aus_test13");
-        let now = fixed_now();
-        let daily = daily_directory(root.as_path(), now);
-        fs::create_dir_all(&daily).expect("create daily directory");
-        let path_a = daily.join("fileA.txt");
-        fs::write(&path_a, "A-old").expect("seed fileA");
+    fn newf_test54_autosave_recreates_note_deleted_outside_the_app() {
+        let root = new_temp_root("newf_test54");
+        let today = ensure_note_directory(root.as_path(), Local::now()).expect("daily dir");
+        let source = today.join("gone.txt");
+        fs::write(&source, "old").expect("seed source");
 
         let workflow = SinglelineCreateFileWorkflow::new();
-        workflow.set_edit_from_open_file(path_a.clone());
+        workflow.set_edit_from_open_file(source.clone());
+        fs::remove_file(&source).expect("outside delete");
 
-        let flushed = workflow
-            .flush_editor_content_in_edit("A-new", root.as_path())
-            .expect("flush before close");
-        assert!(flushed);
-        let updated_path = workflow
-            .current_edit_path()
-            .expect("current path after pre-close flush");
-        let content_a = fs::read_to_string(&updated_path).expect("read updated fileA");
-        assert_eq!(content_a, "A-new");
-        assert_eq!(workflow.current_edit_path(), Some(updated_path));
+        let saved = workflow
+            .try_autosave_in_edit(EditorAutoSavePayload {
+                user_document_dir: root.clone(),
+                current_path: source.clone(),
+                editor_text: "kept".to_string(),
+            })
+            .expect("autosave after outside delete");
+
+        assert!(saved.is_some());
+        assert_eq!(workflow.current_edit_path(), Some(source.clone()));
+        assert_eq!(fs::read_to_string(&source).expect("read recreated"), "kept");
         workflow.dispatcher.shutdown();
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn ftr_test95_req_ftr24_selection_switch_without_pending_edit_does_not_move_previous_file() {
-        let root = new_temp_root("ftr_test95");
-        let old_daily = daily_directory(root.as_path(), fixed_now());
-        fs::create_dir_all(&old_daily).expect("create old daily directory");
-        let path_a = old_daily.join("fileA.txt");
-        let path_b = old_daily.join("fileB.txt");
-        fs::write(&path_a, "A-old").expect("seed fileA");
-        fs::write(&path_b, "B-old").expect("seed fileB");
-
-        let today_daily = daily_directory(root.as_path(), Local::now());
-        let moved_candidate = today_daily.join("fileA.txt");
-        assert_ne!(path_a, moved_candidate);
-
+    fn newf_test55_title_typing_storm_renames_at_most_twice_without_blocking() {
+        let root = new_temp_root("newf_test55");
+        let today = ensure_note_directory(root.as_path(), Local::now()).expect("daily dir");
+        let source = today.join("untitled.txt");
+        fs::write(&source, "body").expect("seed source");
         let workflow = SinglelineCreateFileWorkflow::new();
-        let autosave = EditorAutoSaveCoordinator::new();
-        workflow.set_edit_from_open_file(path_a.clone());
-
-        let should_flush = super::should_flush_pre_switch_editor_content(
-            "req-aus8-open-file",
-            path_a.as_path(),
-            &autosave,
-        );
-        assert!(!should_flush);
+        let outcomes = workflow.subscribe_rename_outcomes();
+        workflow.set_edit_from_open_file(source.clone());
 
-        workflow.set_edit_from_open_file(path_b.clone());
-        assert_eq!(workflow.current_edit_path(), Some(path_b));
-        assert!(
-            path_a.is_file(),
-            "fileA should remain in original dated directory"
-        );
-        assert_eq!(
-            fs::read_to_string(&path_a).expect("read fileA after selection switch"),
-            "A-old"
-        );
-        assert!(
-            !moved_candidate.exists(),
-            "fileA should not be moved to today's daily directory on selection-only switch"
-        );
+        // Stall the worker like a slow disk: this event's callback holds it until released.
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        workflow
+            .dispatcher
+            .dispatch_async(
+                FileWorkflowEvent::Rename(RenameFileRequest {
+                    user_document_dir: root.clone(),
+                    current_path: root.join("missing.txt"),
+                    singleline_value: "stall".to_string(),
+                    now: Local::now(),
+                    extension: NoteFileExtension::Txt,
+                }),
+                move |_| {
+                    let _ = release_rx.recv_timeout(Duration::from_secs(5));
+                },
+            )
+            .expect("enqueue stall");
+
+        let title = "a".repeat(48) + "_final_title_16c";
+        assert_eq!(title.chars().count(), 64);
+        let started = Instant::now();
+        let mut dispatched = 0usize;
+        for end in 1..=title.len() {
+            match workflow
+                .try_rename_in_edit_async(
+                    source.as_path(),
+                    &title[..end],
+                    root.as_path(),
+                    Local::now(),
+                )
+                .expect("rename keystroke")
+            {
+                RenameDispatch::Dispatched => dispatched += 1,
+                RenameDispatch::Coalesced => {}
+                RenameDispatch::Skipped => panic!("rename skipped in edit state"),
+            }
+        }
+        // Every keystroke returned while the worker was stalled: nothing blocked on it.
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(dispatched, 1);
+        release_tx.send(()).expect("release worker");
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut renames = Vec::new();
+        while Instant::now() < deadline {
+            match outcomes.try_recv() {
+                Ok(outcome) => {
+                    let done = !outcome.superseded;
+                    renames.push(outcome);
+                    if done {
+                        break;
+                    }
+                }
+                Err(_) => thread::sleep(Duration::from_millis(5)),
+            }
+        }
 
+        assert_eq!(renames.len(), 2);
+        let last = renames.last().expect("final rename");
+        assert_eq!(last.singleline_value, title);
+        let final_path = last.result.as_ref().expect("final rename result").clone();
+        assert_eq!(final_path, today.join(format!("{title}.txt")));
+        assert!(final_path.is_file());
+        assert_eq!(workflow.current_edit_path(), Some(final_path));
         workflow.dispatcher.shutdown();
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn ftr_test96_req_ftr24_dirty_edit_switch_still_flushes_and_relocates() {
-        let root = new_temp_root("ftr_test96");
-        let old_daily = daily_directory(root.as_path(), fixed_now());
-        fs::create_dir_all(&old_daily).expect("create old daily directory");
-        let path_a = old_daily.join("fileA.txt");
-        fs::write(&path_a, "A-old").expect("seed fileA");
+    fn newf_test57_shutdown_drains_queued_events_then_rejects_new_ones() {
+        let root = new_temp_root("newf_test57");
+        let dispatcher = FileWorkflowEventDispatcher::new();
+        let (result_tx, result_rx) = mpsc::channel();
+        for ix in 0..3 {
+            let result_tx = result_tx.clone();
+            dispatcher
+                .dispatch_async(
+                    FileWorkflowEvent::Create(CreateFileRequest {
+                        user_document_dir: root.clone(),
+                        singleline_value: format!("queued-{ix}"),
+                        now: fixed_now(),
+                        extension: NoteFileExtension::Txt,
+                    }),
+                    move |result| {
+                        let _ = result_tx.send(result);
+                    },
+                )
+                .expect("enqueue before shutdown");
+        }
+
+        dispatcher.shutdown();
+        let results = result_rx.try_iter().collect::<Vec<_>>();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(Result::is_ok));
+
+        let error = dispatcher
+            .dispatch_blocking(FileWorkflowEvent::Create(CreateFileRequest {
+                user_document_dir: root.clone(),
+                singleline_value: "late".to_string(),
+                now: fixed_now(),
+                extension: NoteFileExtension::Txt,
+            }))
+            .expect_err("create after shutdown");
+        assert_eq!(error.kind(), io::ErrorKind::BrokenPipe);
+        remove_temp_root(root.as_path());
+    }
 
-        let workflow = SinglelineCreateFileWorkflow::new();
-        let autosave = EditorAutoSaveCoordinator::new();
-        workflow.set_edit_from_open_file(path_a.clone());
+    #[test]
+    fn aus_test18_worker_shutdown_saves_pending_edit_before_joining() {
+        let root = new_temp_root("aus_test18");
+        let daily = note_directory(root.as_path(), Local::now());
+        fs::create_dir_all(&daily).expect("create daily directory");
+        let path = daily.join("note.txt");
+        fs::write(&path, "old").expect("seed note");
 
-        autosave.mark_user_edit(
+        let workflow = SinglelineCreateFileWorkflow::new();
+        workflow.set_edit_from_open_file(path.clone());
+        let coordinator = EditorAutoSaveCoordinator::new();
+        coordinator.mark_user_edit(
             EditorAutoSavePayload {
                 user_document_dir: root.clone(),
-                current_path: path_a.clone(),
-                editor_text: "A-new".to_string(),
+                current_path: path.clone(),
+                editor_text: "typed just before quit".to_string(),
             },
             Instant::now(),
         );
-        assert!(autosave.has_pending_payload_for_path(path_a.as_path()));
 
-        let should_flush = super::should_flush_pre_switch_editor_content(
-            "req-aus8-open-file",
-            path_a.as_path(),
-            &autosave,
-        );
-        assert!(should_flush);
-        let flushed = workflow
-            .flush_editor_content_in_edit("A-new", root.as_path())
-            .expect("dirty pre-switch flush should succeed");
-        assert!(flushed);
+        let (mut worker, _events) =
+            spawn_editor_autosave_worker(coordinator.clone(), workflow.clone(), None);
+        worker.shutdown();
 
-        let updated_path = workflow
-            .current_edit_path()
-            .expect("current path after dirty pre-switch flush");
-        let today_daily = daily_directory(root.as_path(), Local::now());
-        assert!(
-            updated_path.starts_with(today_daily.as_path()),
-            "dirty pre-switch flush should preserve req-newf35 relocation behavior"
-        );
+        assert!(!coordinator.has_pending_payload());
+        let saved_path = workflow.current_edit_path().expect("current path");
         assert_eq!(
-            fs::read_to_string(&updated_path).expect("read relocated fileA"),
-            "A-new"
+            fs::read_to_string(saved_path).expect("read note"),
+            "typed just before quit"
         );
+        workflow.shutdown_dispatcher();
+        remove_temp_root(root.as_path());
+    }
 
-        workflow.dispatcher.shutdown();
+    #[test]
+    fn aus_test19_external_file_is_saved_in_place_and_never_renamed() {
+        let root = new_temp_root("aus_test19");
+        let vault = root.join("user_document");
+        let outside_dir = root.join("elsewhere");
+        fs::create_dir_all(&outside_dir).expect("create outside dir");
+        let path = outside_dir.join("readme.md");
+        fs::write(&path, "old").expect("seed external file");
+
+        let saved = save_editor_text_payload_atomic(
+            &EditorAutoSavePayload {
+                user_document_dir: vault.clone(),
+                current_path: path.clone(),
+                editor_text: "edited".to_string(),
+            },
+            None,
+        )
+        .expect("save external file");
+        assert_eq!(saved, path);
+        assert_eq!(fs::read_to_string(&path).expect("read"), "edited");
+        assert!(!vault.exists());
+
+        let error = rename_text_file(&RenameFileRequest {
+            user_document_dir: vault.clone(),
+            current_path: path.clone(),
+            singleline_value: "new title".to_string(),
+            now: Local::now(),
+            extension: NoteFileExtension::Txt,
+        })
+        .expect_err("external rename refused");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(path.is_file());
+
+        fs::remove_file(&path).expect("remove external file");
+        let error = save_editor_text_payload_atomic(
+            &EditorAutoSavePayload {
+                user_document_dir: vault,
+                current_path: path.clone(),
+                editor_text: "lost".to_string(),
+            },
+            None,
+        )
+        .expect_err("missing external file is not re-created");
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(!path.exists());
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn ftr_test97_req_ftr24_pre_close_without_pending_edit_is_noop() {
-        let root = new_temp_root("ftr_test97");
-        let old_daily = daily_directory(root.as_path(), fixed_now());
-        fs::create_dir_all(&old_daily).expect("create old daily directory");
-        let path_a = old_daily.join("fileA.txt");
-        fs::write(&path_a, "A-old").expect("seed fileA");
+    fn aus_test20_publish_locked_note_is_only_saved_once_unlocked() {
+        let root = new_temp_root("aus_test20");
+        let vault = root.join("user_document");
+        let path = note_directory(vault.as_path(), Local::now()).join("release.md");
+        fs::create_dir_all(path.parent().unwrap()).expect("create daily dir");
+        let locked = "---\npublish_lock: 2026-10-16 09:30\n---\nv1";
+        fs::write(&path, locked).expect("seed locked note");
+        let save = |editor_text: &str| {
+            save_editor_text_payload_atomic(
+                &EditorAutoSavePayload {
+                    user_document_dir: vault.clone(),
+                    current_path: path.clone(),
+                    editor_text: editor_text.to_string(),
+                },
+                None,
+            )
+        };
 
-        let today_daily = daily_directory(root.as_path(), Local::now());
-        let moved_candidate = today_daily.join("fileA.txt");
-        assert_ne!(path_a, moved_candidate);
+        let error = save("---\npublish_lock: 2026-10-16 09:30\n---\nv2").expect_err("locked");
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(fs::read_to_string(&path).expect("read"), locked);
 
-        let workflow = SinglelineCreateFileWorkflow::new();
-        let autosave = EditorAutoSaveCoordinator::new();
-        workflow.set_edit_from_open_file(path_a.clone());
+        save("v1").expect("unlock saves");
+        save("v2").expect("unlocked note saves");
+        assert_eq!(fs::read_to_string(&path).expect("read"), "v2");
+        remove_temp_root(root.as_path());
+    }
 
-        let should_flush = super::should_flush_pre_switch_editor_content(
-            "req-aus7-window-close",
-            path_a.as_path(),
-            &autosave,
-        );
-        assert!(!should_flush);
+    #[test]
+    fn newf_test59_move_entry_keeps_name_and_open_note_follows_folder() {
+        let root = new_temp_root("newf_test59");
+        let projects = root.join("projects");
+        let archive = root.join("archive");
+        fs::create_dir_all(&projects).expect("create projects");
+        fs::create_dir_all(archive.join("projects")).expect("create clashing folder");
+        let note = projects.join("plan.txt");
+        fs::write(&note, "plan").expect("seed note");
 
-        assert_eq!(workflow.current_edit_path(), Some(path_a.clone()));
+        let workflow = SinglelineCreateFileWorkflow::new();
+        workflow.set_edit_from_open_file(note.clone());
+        let moved = workflow
+            .move_entry_blocking(projects.as_path(), archive.as_path())
+            .expect("move folder");
+        assert_eq!(moved, archive.join("projects_2"));
         assert_eq!(
-            fs::read_to_string(&path_a).expect("read fileA after pre-close no-op"),
-            "A-old"
+            workflow.current_edit_path(),
+            Some(archive.join("projects_2").join("plan.txt"))
         );
-        assert!(
-            !moved_candidate.exists(),
-            "pre-close without pending user edit should not relocate file"
+        assert_eq!(
+            fs::read_to_string(archive.join("projects_2").join("plan.txt")).expect("read"),
+            "plan"
         );
 
-        workflow.dispatcher.shutdown();
+        let error = workflow
+            .move_entry_blocking(moved.as_path(), moved.as_path())
+            .expect_err("folder into itself");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        let same = workflow
+            .move_entry_blocking(moved.join("plan.txt").as_path(), moved.as_path())
+            .expect("same folder");
+        assert_eq!(same, moved.join("plan.txt"));
+
+        // A note filed into a folder of the user's own is saved where it is.
+        let saved = workflow
+            .try_autosave_in_edit(EditorAutoSavePayload {
+                user_document_dir: root.clone(),
+                current_path: moved.join("plan.txt"),
+                editor_text: "plan v2".to_string(),
+            })
+            .expect("autosave in custom folder");
+        assert!(saved.is_some());
+        assert_eq!(workflow.current_edit_path(), Some(moved.join("plan.txt")));
+        assert_eq!(
+            fs::read_to_string(moved.join("plan.txt")).expect("read"),
+            "plan v2"
+        );
+        workflow.shutdown_dispatcher();
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn ftr_test98_req_ftr24_selection_switch_loads_target_without_mutating_previous_file() {
-        let root = new_temp_root("ftr_test98");
-        let old_daily = daily_directory(root.as_path(), fixed_now());
-        fs::create_dir_all(&old_daily).expect("create old daily directory");
-        let path_a = old_daily.join("fileA.txt");
-        let path_b = old_daily.join("fileB.txt");
-        fs::write(&path_a, "A-old").expect("seed fileA");
-        fs::write(&path_b, "B-old").expect("seed fileB");
-
+    fn newf_test60_create_dir_sanitizes_name_and_avoids_existing_folders() {
+        let root = new_temp_root("newf_test60");
         let workflow = SinglelineCreateFileWorkflow::new();
-        let autosave = EditorAutoSaveCoordinator::new();
-
-        workflow.set_edit_from_open_file(path_a.clone());
-        assert!(
-            !super::should_flush_pre_switch_editor_content(
-                "req-aus8-open-file",
-                path_a.as_path(),
-                &autosave
-            ),
-            "selection switch A -> B without user edit should not flush A"
-        );
-        workflow.set_edit_from_open_file(path_b.clone());
 
-        assert!(
-            !super::should_flush_pre_switch_editor_content(
-                "req-aus8-open-file",
-                path_b.as_path(),
-                &autosave
-            ),
-            "selection switch B -> A without user edit should not flush B"
-        );
-        workflow.set_edit_from_open_file(path_a.clone());
+        let first = workflow
+            .create_dir_blocking(root.as_path(), "  ")
+            .expect("create default folder");
+        assert_eq!(first, root.join(NEW_FOLDER_DEFAULT_NAME));
+        let second = workflow
+            .create_dir_blocking(root.as_path(), "")
+            .expect("create second default folder");
+        assert_eq!(second, root.join(format!("{NEW_FOLDER_DEFAULT_NAME}_2")));
+        let named = workflow
+            .create_dir_blocking(root.as_path(), "a/b")
+            .expect("create named folder");
+        assert_eq!(named, root.join("a_b"));
+        assert!(named.is_dir());
+        workflow.shutdown_dispatcher();
+        remove_temp_root(root.as_path());
+    }
 
-        assert_eq!(workflow.current_edit_path(), Some(path_a.clone()));
-        assert_eq!(
-            fs::read_to_string(&path_a).expect("read fileA final"),
-            "A-old"
+    #[test]
+    fn newf_test62_watchdog_restarts_stuck_worker_and_fails_queued_events() {
+        let root = new_temp_root("newf_test62");
+        let dispatcher = FileWorkflowEventDispatcher::with_event_timeouts(
+            Duration::from_millis(150),
+            Duration::from_millis(150),
         );
+        let stalls = dispatcher.subscribe_stalls();
+        let (result_tx, result_rx) = mpsc::channel();
+        for event in [
+            FileWorkflowEvent::Stall(Duration::from_secs(3)),
+            FileWorkflowEvent::CreateDir(CreateDirRequest {
+                parent: root.clone(),
+                name: "queued".to_string(),
+            }),
+        ] {
+            let result_tx = result_tx.clone();
+            dispatcher
+                .dispatch_async(event, move |result| {
+                    let _ = result_tx.send(result);
+                })
+                .expect("enqueue");
+        }
+        let stuck = result_rx
+            .recv_timeout(Duration::from_secs(2))
+            .expect("stuck event answered by watchdog");
+        assert!(matches!(
+            stuck,
+            Err(WorkflowError::TimedOut { event: "stall", .. })
+        ));
+        let queued = result_rx
+            .recv_timeout(Duration::from_secs(2))
+            .expect("queued event failed by watchdog");
+        assert!(matches!(
+            queued,
+            Err(WorkflowError::DispatcherUnavailable(_))
+        ));
+        assert!(!root.join("queued").exists());
+        let stall = stalls.try_recv().expect("stall reported");
+        assert_eq!((stall.event, stall.failed_events), ("stall", 1));
+
+        let created = dispatcher
+            .dispatch_blocking(FileWorkflowEvent::CreateDir(CreateDirRequest {
+                parent: root.clone(),
+                name: "after".to_string(),
+            }))
+            .expect("respawned worker handles new events");
         assert_eq!(
-            fs::read_to_string(&path_b).expect("read fileB final"),
-            "B-old"
+            created,
+            FileWorkflowEventResult::DirCreated {
+                path: root.join("after")
+            }
         );
+        dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn newf_test63_detached_autosave_writes_in_place_without_relocating() {
+        let root = new_temp_root("newf_test63");
+        let old_dir = root.join("2020").join("01").join("01");
+        fs::create_dir_all(&old_dir).expect("old daily dir");
+        let note_a = old_dir.join("a.txt");
+        fs::write(&note_a, "a").expect("seed a");
+        let workflow = SinglelineCreateFileWorkflow::new();
+        workflow.set_edit_from_open_file(root.join("b.txt"));
+        assert!(!workflow.edits_path(note_a.as_path()));
+
+        let saved = workflow
+            .save_detached_payload(EditorAutoSavePayload {
+                user_document_dir: root.clone(),
+                current_path: note_a.clone(),
+                editor_text: "a edited".to_string(),
+            })
+            .expect("detached save");
+        assert_eq!(saved, note_a);
+        assert_eq!(fs::read_to_string(&note_a).expect("read a"), "a edited");
 
+        let gone = old_dir.join("gone.txt");
+        let error = workflow
+            .save_detached_payload(EditorAutoSavePayload {
+                user_document_dir: root.clone(),
+                current_path: gone.clone(),
+                editor_text: "lost".to_string(),
+            })
+            .expect_err("missing note is not re-created");
+        assert!(matches!(error, WorkflowError::NotFound { .. }));
+        assert!(!gone.exists());
         workflow.dispatcher.shutdown();
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn qsrv_file_test1_rpc_pin_reads_content_clamps_line_and_updates_mtime() {
-        let root = new_temp_root("qsrv_file_test1");
-        let now = fixed_now();
-        let daily = daily_directory(root.as_path(), now);
-        fs::create_dir_all(&daily).expect("create daily directory");
-        let target = daily.join("fileA.txt");
-        fs::write(&target, "line1\nline2\nline3").expect("seed target file");
+    fn newf_test58_title_rename_waits_for_idle_title_and_follows_note() {
+        let debouncer = TitleRenameDebouncer::new(Duration::from_millis(800));
+        let path_a = PathBuf::from("C:/tmp/a.txt");
+        let pending = |value: &str| PendingTitleRename {
+            path: path_a.clone(),
+            singleline_value: value.to_string(),
+            user_document_dir: PathBuf::from("C:/tmp"),
+        };
+        let start = Instant::now();
+        debouncer.mark_title_edit(pending("m"), start);
+        debouncer.mark_title_edit(pending("mee"), start + Duration::from_millis(500));
+        assert_eq!(debouncer.pop_due(start + Duration::from_millis(900)), None);
+        let due = debouncer
+            .pop_due(start + Duration::from_millis(1300))
+            .expect("due after idle");
+        assert_eq!(due.singleline_value, "mee");
+        assert_eq!(debouncer.pop_due(start + Duration::from_secs(5)), None);
+
+        let renamed = PathBuf::from("C:/tmp/mee.txt");
+        debouncer.mark_title_edit(pending("meeting"), start);
+        debouncer.retarget_pending(path_a.as_path(), renamed.as_path());
+        debouncer.on_edit_path_changed(Some(renamed.as_path()));
+        assert_eq!(
+            debouncer.take_pending().map(|pending| pending.path),
+            Some(renamed)
+        );
 
-        let old = FileTime::from_unix_time(1, 0);
-        set_file_mtime(target.as_path(), old).expect("set old modified time");
-        let modified_before = fs::metadata(&target)
-            .expect("metadata before rpc pin")
-            .modified()
-            .expect("modified before rpc pin");
+        debouncer.mark_title_edit(pending("other"), start);
+        debouncer.on_edit_path_changed(Some(Path::new("C:/tmp/b.txt")));
+        assert_eq!(debouncer.take_pending(), None);
+    }
 
-        let workflow = SinglelineCreateFileWorkflow::new();
-        let pinned = workflow
-            .try_pin_file_via_rpc(root.clone(), target.clone(), 999)
-            .expect("rpc pin must succeed");
+    #[test]
+    fn newf_test65_full_queue_drops_oldest_autosave_fails_or_blocks() {
+        let root = new_temp_root("newf_test65");
+        let dispatcher = FileWorkflowEventDispatcher::new();
+        let (result_tx, result_rx) = mpsc::channel();
+        let enqueue = |event: FileWorkflowEvent, tag: &'static str| {
+            let result_tx = result_tx.clone();
+            dispatcher.dispatch_async(event, move |result| {
+                let _ = result_tx.send((tag, result));
+            })
+        };
+        let create_dir = |name: &str| {
+            FileWorkflowEvent::CreateDir(CreateDirRequest {
+                parent: root.clone(),
+                name: name.to_string(),
+            })
+        };
+        dispatcher.set_queue_limits(QueueLimits {
+            capacity: 2,
+            when_full: QueueFullPolicy::DropOldestAutoSave,
+        });
+        enqueue(
+            FileWorkflowEvent::Stall(Duration::from_millis(400)),
+            "stall",
+        )
+        .expect("stall");
+        thread::sleep(Duration::from_millis(100));
+        let autosave = FileWorkflowEvent::AutoSave(AutoSaveFileRequest {
+            payload: EditorAutoSavePayload {
+                user_document_dir: root.clone(),
+                current_path: root.join("never.txt"),
+                editor_text: "old".to_string(),
+            },
+            previous_identity: None,
+            detached: true,
+        });
+        enqueue(autosave, "autosave").expect("autosave");
+        enqueue(create_dir("a"), "a").expect("a");
+        enqueue(create_dir("b"), "b").expect("b replaces the autosave");
+        let (tag, dropped) = result_rx.recv().expect("dropped autosave answered");
+        assert_eq!(tag, "autosave");
+        assert!(matches!(
+            dropped,
+            Err(WorkflowError::QueueFull { capacity: 2 })
+        ));
+        assert!(matches!(
+            enqueue(create_dir("c"), "c"),
+            Err(WorkflowError::QueueFull { capacity: 2 })
+        ));
 
-        let today_dir = daily_directory(root.as_path(), Local::now());
-        let expected_file_name = target
-            .file_name()
-            .expect("target file name exists")
-            .to_string_lossy()
-            .to_string();
+        dispatcher.set_queue_limits(QueueLimits {
+            capacity: 2,
+            when_full: QueueFullPolicy::Block,
+        });
+        let started = Instant::now();
+        enqueue(create_dir("d"), "d").expect("d waits for room");
+        assert!(started.elapsed() >= Duration::from_millis(150));
+        let mut finished = (0..4)
+            .map(|_| result_rx.recv().expect("result").0)
+            .collect::<Vec<_>>();
+        finished.sort();
+        assert_eq!(finished, ["a", "b", "d", "stall"]);
+        assert!(!root.join("c").exists());
+        assert!(!root.join("never.txt").exists());
+        dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
 
+    #[test]
+    fn newf_test66_abandoned_worker_may_not_write() {
+        let root = new_temp_root("newf_test66");
+        let dispatcher = FileWorkflowEventDispatcher::new();
+        let generation = dispatcher.shared.0.lock().expect("state").generation;
+        WORKER_GENERATION
+            .with(|current| *current.borrow_mut() = Some((dispatcher.shared.clone(), generation)));
+        let note = root.join("note.txt");
+        crate::atomic_io::write_atomic(note.as_path(), b"first").expect("current worker writes");
+
+        dispatcher.shared.0.lock().expect("state").generation += 1;
+        let refused = crate::atomic_io::write_atomic(note.as_path(), b"second")
+            .map_err(|error| WorkflowError::from_io(error, note.as_path()));
+        assert!(matches!(
+            refused,
+            Err(WorkflowError::DispatcherUnavailable(_))
+        ));
         assert!(
-            pinned.path.starts_with(today_dir.as_path()),
-            "rpc pin should relocate into today's daily directory"
-        );
-        assert_eq!(
-            pinned
-                .path
-                .file_name()
-                .expect("pinned file name exists")
-                .to_string_lossy(),
-            expected_file_name
+            crate::file_times::rename_preserving_times(&note, &root.join("moved.txt")).is_err()
         );
-        assert_eq!(pinned.content, "line1\nline2\nline3");
-        assert_eq!(pinned.linenum, 3);
+        WORKER_GENERATION.with(|current| *current.borrow_mut() = None);
+        assert_eq!(fs::read_to_string(&note).expect("read note"), "first");
+        dispatcher.shutdown();
+        remove_temp_root(&root);
+    }
 
-        let modified_after = fs::metadata(&pinned.path)
-            .expect("metadata after rpc pin")
-            .modified()
-            .expect("modified after rpc pin");
-        assert!(modified_after > modified_before);
+    #[test]
+    fn newf_test67_debounced_title_renames_only_its_own_note() {
+        let root = new_temp_root("newf_test67");
+        let today = ensure_note_directory(root.as_path(), Local::now()).expect("daily dir");
+        let first = today.join("first.txt");
+        let second = today.join("second.txt");
+        fs::write(&first, "one").expect("seed first");
+        fs::write(&second, "two").expect("seed second");
+        let workflow = SinglelineCreateFileWorkflow::new();
+        workflow.set_edit_from_open_file(second.clone());
 
+        let stale = workflow
+            .try_rename_in_edit_async(first.as_path(), "hijacked", root.as_path(), Local::now())
+            .expect("stale title");
+        assert_eq!(stale, RenameDispatch::Skipped);
         workflow.dispatcher.shutdown();
+        assert!(first.is_file());
+        assert!(second.is_file());
+        assert!(!today.join("hijacked.txt").exists());
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn qsrv_file_test3_req_qsrv4_follow_mtime_modify_event_is_watcher_refresh_eligible() {
-        let root = new_temp_root("qsrv_file_test3");
-        let now = fixed_now();
-        let daily = daily_directory(root.as_path(), now);
-        fs::create_dir_all(&daily).expect("create daily directory");
-        let target = daily.join("fileA.txt");
-        fs::write(&target, "line1\nline2\nline3").expect("seed target file");
+    fn newf_test68_title_debounce_thread_joins_on_shutdown() {
+        let root = new_temp_root("newf_test68");
+        let workflow = SinglelineCreateFileWorkflow::new();
+        let (gate, _notices) = crate::duplicate_titles::DuplicateTitleGate::new(root.clone());
+        let mut worker = spawn_title_rename_debounce_worker(
+            TitleRenameDebouncer::new(Duration::from_millis(10)),
+            workflow.clone(),
+            gate,
+        );
+        let started = Instant::now();
+        worker.shutdown();
+        assert!(worker.thread.is_none());
+        assert!(started.elapsed() < Duration::from_secs(2));
+        workflow.dispatcher.shutdown();
+        remove_temp_root(root.as_path());
+    }
 
+    #[test]
+    fn newf_test69_worker_writes_are_recorded_as_self_written() {
+        let root = new_temp_root("newf_test69");
+        let note = root.join("left_behind.txt");
+        fs::write(&note, "before").expect("seed note");
         let workflow = SinglelineCreateFileWorkflow::new();
-        let pinned = workflow
-            .try_pin_file_via_rpc(root.clone(), target.clone(), 2)
-            .expect("rpc pin must succeed");
 
-        let mtime_modify_event = notify::Event {
-            kind: notify::EventKind::Modify(notify::event::ModifyKind::Metadata(
-                notify::event::MetadataKind::WriteTime,
-            )),
-            paths: vec![pinned.path.clone()],
-            attrs: Default::default(),
-        };
-        assert!(crate::file_tree_watcher::should_schedule_refresh(
-            root.as_path(),
-            &mtime_modify_event,
+        let saved = workflow
+            .save_detached_payload(EditorAutoSavePayload {
+                user_document_dir: root.clone(),
+                current_path: note.clone(),
+                editor_text: "after".to_string(),
+            })
+            .expect("detached save");
+        let modified = fs::metadata(&saved)
+            .and_then(|metadata| metadata.modified())
+            .expect("saved mtime");
+        assert!(is_self_written(saved.as_path(), modified));
+        assert!(!is_self_written(
+            saved.as_path(),
+            modified + Duration::from_secs(1)
         ));
-
         workflow.dispatcher.shutdown();
         remove_temp_root(root.as_path());
     }
 
     #[test]
-    fn qsrv_file_test2_rpc_pin_missing_file_returns_not_found() {
-        let root = new_temp_root("qsrv_file_test2");
+    fn newf_test70_rename_waiting_for_queue_room_does_not_hold_the_state_lock() {
+        let root = new_temp_root("newf_test70");
+        let today = ensure_note_directory(root.as_path(), Local::now()).expect("daily dir");
+        let source = today.join("untitled.txt");
+        fs::write(&source, "body").expect("seed source");
         let workflow = SinglelineCreateFileWorkflow::new();
-        let missing = root.join("2026").join("03").join("22").join("missing.txt");
-
-        let error = workflow
-            .try_pin_file_via_rpc(root.clone(), missing.clone(), 1)
-            .expect_err("missing file must fail");
-        assert_eq!(error.kind(), io::ErrorKind::NotFound);
-        assert!(error.to_string().contains("does not exist"));
-
+        workflow.set_edit_from_open_file(source.clone());
+        workflow.set_queue_limits(QueueLimits {
+            capacity: 1,
+            when_full: QueueFullPolicy::Block,
+        });
+        workflow
+            .dispatcher
+            .dispatch_async(FileWorkflowEvent::Stall(Duration::from_millis(600)), |_| {})
+            .expect("stall");
+        thread::sleep(Duration::from_millis(50));
+        workflow
+            .dispatcher
+            .dispatch_async(FileWorkflowEvent::Stall(Duration::ZERO), |_| {})
+            .expect("fill the queue");
+
+        let renaming = workflow.clone();
+        let rename_source = source.clone();
+        let rename_root = root.clone();
+        let rename = thread::spawn(move || {
+            renaming.try_rename_in_edit_async(
+                rename_source.as_path(),
+                "renamed",
+                rename_root.as_path(),
+                Local::now(),
+            )
+        });
+        thread::sleep(Duration::from_millis(100));
+        // The UI thread reads the state while the rename waits for room in the queue.
+        let started = Instant::now();
+        assert_eq!(workflow.snapshot().state, SinglelineFileState::Edit);
+        assert!(started.elapsed() < Duration::from_millis(200));
+        assert!(matches!(
+            rename.join().expect("rename thread"),
+            Ok(RenameDispatch::Dispatched)
+        ));
         workflow.dispatcher.shutdown();
         remove_temp_root(root.as_path());
     }
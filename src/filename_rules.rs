@@ -0,0 +1,241 @@
+use std::{
+    fs,
+    path::Path,
+    sync::{Mutex, OnceLock},
+};
+
+use crate::{
+    file_update_handler::{MAX_FILE_STEM_CHARS, invalid_filename_char},
+    log::trace_debug,
+};
+
+static ACTIVE_FILENAME_RULES: OnceLock<Mutex<FilenameRules>> = OnceLock::new();
+
+const MIN_FILE_STEM_CHARS: usize = 8;
+const MAX_CONFIGURABLE_STEM_CHARS: usize = 200;
+
+// Device names Windows refuses as a file name, with or without an extension.
+const WINDOWS_RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// What happens to a character that cannot appear in a file name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum InvalidCharPolicy {
+    Replace(char),
+    Strip,
+}
+
+/// How a title becomes a file stem: length cap, invalid characters, and the extra guards
+/// for names that only some platforms reject.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct FilenameRules {
+    pub max_stem_chars: usize,
+    pub invalid_chars: InvalidCharPolicy,
+    pub allow_unicode_punctuation: bool,
+    pub guard_reserved_names: bool,
+}
+
+impl Default for FilenameRules {
+    fn default() -> Self {
+        Self {
+            max_stem_chars: MAX_FILE_STEM_CHARS,
+            invalid_chars: InvalidCharPolicy::Replace('_'),
+            allow_unicode_punctuation: true,
+            guard_reserved_names: true,
+        }
+    }
+}
+
+impl FilenameRules {
+    pub fn is_invalid_char(&self, ch: char) -> bool {
+        invalid_filename_char(ch) || (!self.allow_unicode_punctuation && unicode_punctuation(ch))
+    }
+
+    /// `CON`, `nul.backup` and friends get a `_` after the device name; None when `stem` is
+    /// fine as it is.
+    pub fn reserved_name_fix(&self, stem: &str) -> Option<String> {
+        if !self.guard_reserved_names {
+            return None;
+        }
+        let (base, rest) = stem.split_at(stem.find('.').unwrap_or(stem.len()));
+        let trimmed = base.trim_end_matches(' ');
+        WINDOWS_RESERVED_NAMES
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(trimmed))
+            .then(|| format!("{trimmed}_{}{rest}", &base[trimmed.len()..]))
+    }
+}
+
+// Non-ASCII punctuation that some sync tools and older file systems mangle: typographic
+// quotes and dashes, the general punctuation block, CJK and fullwidth brackets.
+fn unicode_punctuation(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{00A1}'
+            | '\u{00A7}'
+            | '\u{00AB}'
+            | '\u{00B6}'
+            | '\u{00B7}'
+            | '\u{00BB}'
+            | '\u{00BF}'
+            | '\u{2010}'..='\u{205E}'
+            | '\u{3001}'..='\u{3003}'
+            | '\u{3008}'..='\u{3011}'
+            | '\u{3014}'..='\u{301F}'
+            | '\u{FF01}'..='\u{FF0F}'
+            | '\u{FF1A}'..='\u{FF20}'
+            | '\u{FF3B}'..='\u{FF40}'
+            | '\u{FF5B}'..='\u{FF65}'
+    )
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct FilenameRulesConfigFile {
+    #[serde(default)]
+    filenames: FilenameRulesConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct FilenameRulesConfigSection {
+    max_stem_chars: Option<usize>,
+    invalid_chars: Option<String>,
+    replacement: Option<String>,
+    allow_unicode_punctuation: Option<bool>,
+    reserved_names: Option<String>,
+}
+
+fn parse_filename_rules(section: FilenameRulesConfigSection) -> Result<FilenameRules, String> {
+    let mut rules = FilenameRules::default();
+    if let Some(max) = section.max_stem_chars {
+        if !(MIN_FILE_STEM_CHARS..=MAX_CONFIGURABLE_STEM_CHARS).contains(&max) {
+            return Err(format!(
+                "max_stem_chars {max} is outside {MIN_FILE_STEM_CHARS}..={MAX_CONFIGURABLE_STEM_CHARS}"
+            ));
+        }
+        rules.max_stem_chars = max;
+    }
+    let replacement = match section.replacement.as_deref() {
+        None => '_',
+        Some(value) => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) if !invalid_filename_char(ch) && ch != '.' => ch,
+                _ => return Err(format!("replacement '{value}' must be one valid character")),
+            }
+        }
+    };
+    rules.invalid_chars = match section.invalid_chars.as_deref().map(str::trim) {
+        None | Some("replace") => InvalidCharPolicy::Replace(replacement),
+        Some("strip") => InvalidCharPolicy::Strip,
+        Some(other) => return Err(format!("invalid_chars '{other}' is not replace or strip")),
+    };
+    if let Some(allow) = section.allow_unicode_punctuation {
+        rules.allow_unicode_punctuation = allow;
+    }
+    rules.guard_reserved_names = match section.reserved_names.as_deref().map(str::trim) {
+        None | Some("suffix") => true,
+        Some("allow") => false,
+        Some(other) => return Err(format!("reserved_names '{other}' is not suffix or allow")),
+    };
+    Ok(rules)
+}
+
+/// `[filenames]`: `max_stem_chars`, `invalid_chars = "replace" | "strip"`, `replacement`,
+/// `allow_unicode_punctuation` and `reserved_names = "suffix" | "allow"`.
+pub(crate) fn load_filename_rules(path: &Path) -> Option<FilenameRules> {
+    let raw = fs::read_to_string(path).ok()?;
+    let section = match toml::from_str::<FilenameRulesConfigFile>(&raw) {
+        Ok(parsed) => parsed.filenames,
+        Err(error) => {
+            trace_debug(format!(
+                "req-fnr1 config parse failed path={} error={error}",
+                path.display()
+            ));
+            return None;
+        }
+    };
+    match parse_filename_rules(section) {
+        Ok(rules) => Some(rules),
+        Err(message) => {
+            trace_debug(format!("req-fnr1 config ignored: {message}"));
+            None
+        }
+    }
+}
+
+fn active_filename_rules_lock() -> &'static Mutex<FilenameRules> {
+    ACTIVE_FILENAME_RULES.get_or_init(|| Mutex::new(FilenameRules::default()))
+}
+
+/// Titles are sanitized on the UI thread (inline hint) and on the dispatcher (create,
+/// rename); both read the rules from here.
+pub(crate) fn set_active_filename_rules(rules: FilenameRules) {
+    *active_filename_rules_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = rules;
+}
+
+pub(crate) fn active_filename_rules() -> FilenameRules {
+    active_filename_rules_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnr_test1_config_parses_and_rejects_bad_values() {
+        let parse = |raw: &str| {
+            parse_filename_rules(
+                toml::from_str::<FilenameRulesConfigFile>(raw)
+                    .expect("toml")
+                    .filenames,
+            )
+        };
+        assert_eq!(parse("").expect("default"), FilenameRules::default());
+        let rules = parse(
+            "[filenames]\nmax_stem_chars = 40\ninvalid_chars = \"strip\"\n\
+             allow_unicode_punctuation = false\nreserved_names = \"allow\"\n",
+        )
+        .expect("custom");
+        assert_eq!(rules.max_stem_chars, 40);
+        assert_eq!(rules.invalid_chars, InvalidCharPolicy::Strip);
+        assert!(!rules.allow_unicode_punctuation && !rules.guard_reserved_names);
+        assert_eq!(
+            parse("[filenames]\nreplacement = \"-\"\n")
+                .expect("dash")
+                .invalid_chars,
+            InvalidCharPolicy::Replace('-')
+        );
+        assert!(parse("[filenames]\nmax_stem_chars = 2\n").is_err());
+        assert!(parse("[filenames]\nreplacement = \"?\"\n").is_err());
+        assert!(parse("[filenames]\ninvalid_chars = \"drop\"\n").is_err());
+    }
+
+    #[test]
+    fn fnr_test2_reserved_names_and_unicode_punctuation() {
+        let rules = FilenameRules::default();
+        assert_eq!(rules.reserved_name_fix("CON").as_deref(), Some("CON_"));
+        assert_eq!(
+            rules.reserved_name_fix("nul.backup").as_deref(),
+            Some("nul_.backup")
+        );
+        assert_eq!(rules.reserved_name_fix("lpt1 ").as_deref(), Some("lpt1_ "));
+        assert_eq!(rules.reserved_name_fix("console"), None);
+        assert!(!rules.is_invalid_char('\u{201C}'));
+
+        let strict = FilenameRules {
+            allow_unicode_punctuation: false,
+            guard_reserved_names: false,
+            ..FilenameRules::default()
+        };
+        assert!(strict.is_invalid_char('\u{201C}') && strict.is_invalid_char('\u{3001}'));
+        assert!(!strict.is_invalid_char('é') && !strict.is_invalid_char('こ'));
+        assert_eq!(strict.reserved_name_fix("CON"), None);
+    }
+}
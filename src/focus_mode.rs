@@ -0,0 +1,76 @@
+use std::ops::Range;
+
+use gpui::*;
+
+use crate::log::trace_debug;
+
+/// Widest the editor column grows while focus mode is on.
+pub(crate) const FOCUS_MODE_MAX_LINE_WIDTH_PX: f32 = 760.0;
+/// Text opacity outside the current paragraph.
+pub(crate) const FOCUS_MODE_DIM_OPACITY: f32 = 0.45;
+
+/// Lines of the blank-line separated paragraph holding `cursor_line`. A blank cursor line is
+/// its own paragraph so nothing lights up while moving between paragraphs.
+pub(crate) fn paragraph_line_range(text: &str, cursor_line: u32) -> Range<u32> {
+    let lines = text.split('\n').collect::<Vec<_>>();
+    let cursor = (cursor_line as usize).min(lines.len().saturating_sub(1));
+    if lines[cursor].trim().is_empty() {
+        return cursor as u32..cursor as u32 + 1;
+    }
+    let start = lines[..cursor]
+        .iter()
+        .rposition(|line| line.trim().is_empty())
+        .map(|blank| blank + 1)
+        .unwrap_or(0);
+    let end = lines[cursor..]
+        .iter()
+        .position(|line| line.trim().is_empty())
+        .map(|offset| cursor + offset)
+        .unwrap_or(lines.len());
+    start as u32..end as u32
+}
+
+impl crate::app::Papyru2App {
+    /// Hides the top bars, file tree and status bar for the rest of the session (or until
+    /// toggled again); the flag lives on the app, so switching notes keeps it.
+    pub(crate) fn toggle_focus_mode(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.focus_mode = !self.focus_mode;
+        let focus_mode = self.focus_mode;
+        trace_debug(format!("req-focus focus_mode={focus_mode}"));
+        self.editor.update(cx, |editor, cx| {
+            editor.set_focus_mode(focus_mode, cx);
+            if focus_mode {
+                editor.focus(window, cx);
+            }
+        });
+        self.show_transient_notice(
+            crate::i18n::tr(if focus_mode {
+                "notice.focus_mode_on"
+            } else {
+                "notice.focus_mode_off"
+            })
+            .to_string(),
+            cx,
+        );
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn focus_test1_paragraph_range_stops_at_blank_lines() {
+        let text = "title\n\nfirst a\nfirst b\n  \nsecond\n";
+        assert_eq!(paragraph_line_range(text, 0), 0..1);
+        assert_eq!(paragraph_line_range(text, 1), 1..2);
+        assert_eq!(paragraph_line_range(text, 2), 2..4);
+        assert_eq!(paragraph_line_range(text, 3), 2..4);
+        assert_eq!(paragraph_line_range(text, 4), 4..5);
+        assert_eq!(paragraph_line_range(text, 5), 5..6);
+        assert_eq!(paragraph_line_range(text, 99), 6..7);
+        assert_eq!(paragraph_line_range("", 0), 0..1);
+        assert_eq!(paragraph_line_range("one\ntwo", 1), 0..2);
+    }
+}
@@ -0,0 +1,709 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use filetime::{FileTime, set_file_mtime};
+use gpui::*;
+
+use crate::file_update_handler::{
+    FolderImportMode, FolderImportRequest, NoteFileExtension, NotesFileImportRequest,
+    resolve_unique_path, stem_from_singleline_value,
+};
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+use crate::notes_import::{NotePlacement, NotesImportFormat, NotesImportSummary};
+use crate::toasts::ToastSeverity;
+use crate::workflow_error::WorkflowResult;
+
+/// Lines of renamed/skipped files listed in the report dialog before "... and N more".
+const FOLDER_IMPORT_REPORT_MAX_LINES: usize = 20;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FolderImportReport {
+    pub imported: Vec<PathBuf>,
+    /// Source and destination of files whose name had to change.
+    pub renamed: Vec<(PathBuf, PathBuf)>,
+    /// Source and reason.
+    pub skipped: Vec<(PathBuf, String)>,
+    /// Imports that fell back to a copy because the drive does not take hard links.
+    pub copied_instead_of_linked: usize,
+}
+
+impl FolderImportReport {
+    pub fn summary(&self) -> String {
+        let count = |count: usize| count.to_string();
+        let mut summary = tr_with(
+            "notice.imported_notes",
+            &[("count", count(self.imported.len()).as_str())],
+        );
+        if !self.renamed.is_empty() {
+            summary.push_str(&tr_with(
+                "notice.imported_renamed",
+                &[("count", count(self.renamed.len()).as_str())],
+            ));
+        }
+        if !self.skipped.is_empty() {
+            summary.push_str(&tr_with(
+                "notice.imported_skipped",
+                &[("count", count(self.skipped.len()).as_str())],
+            ));
+        }
+        if self.copied_instead_of_linked > 0 {
+            summary.push_str(&tr_with(
+                "notice.imported_copied_not_linked",
+                &[("count", count(self.copied_instead_of_linked).as_str())],
+            ));
+        }
+        summary
+    }
+
+    fn details(&self, source_dir: &Path, user_document_dir: &Path) -> String {
+        let relative = |path: &Path, base: &Path| {
+            path.strip_prefix(base)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        };
+        let lines = self
+            .renamed
+            .iter()
+            .map(|(from, to)| {
+                tr_with(
+                    "prompt.import_renamed_line",
+                    &[
+                        ("from", relative(from, source_dir).as_str()),
+                        ("to", relative(to, user_document_dir).as_str()),
+                    ],
+                )
+            })
+            .chain(self.skipped.iter().map(|(path, reason)| {
+                tr_with(
+                    "prompt.import_skipped_line",
+                    &[
+                        ("path", relative(path, source_dir).as_str()),
+                        ("reason", reason.as_str()),
+                    ],
+                )
+            }))
+            .collect::<Vec<_>>();
+        let mut details = lines
+            .iter()
+            .take(FOLDER_IMPORT_REPORT_MAX_LINES)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n");
+        if lines.len() > FOLDER_IMPORT_REPORT_MAX_LINES {
+            details.push('\n');
+            details.push_str(&tr_with(
+                "prompt.and_more",
+                &[(
+                    "count",
+                    (lines.len() - FOLDER_IMPORT_REPORT_MAX_LINES)
+                        .to_string()
+                        .as_str(),
+                )],
+            ));
+        }
+        details
+    }
+}
+
+fn collect_import_sources(
+    dir: &Path,
+    sources: &mut Vec<PathBuf>,
+    skipped: &mut Vec<(PathBuf, String)>,
+) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|entry| entry.path());
+    for entry in entries {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if entry.file_name().to_string_lossy().starts_with('.') || file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            collect_import_sources(path.as_path(), sources, skipped)?;
+        } else if NoteFileExtension::from_path(path.as_path()).is_some() {
+            sources.push(path);
+        } else if file_type.is_file() {
+            skipped.push((path, "not a .txt or .md file".to_string()));
+        }
+    }
+    Ok(())
+}
+
+fn import_one(
+    source: &Path,
+    request: &FolderImportRequest,
+    report: &mut FolderImportReport,
+) -> io::Result<()> {
+    let modified = fs::metadata(source)?.modified()?;
+    let modified_at = DateTime::<Local>::from(modified);
+    let extension = NoteFileExtension::from_path(source)
+        .unwrap_or_default()
+        .as_str();
+    let original_stem = source
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stem = stem_from_singleline_value(&original_stem, modified_at);
+    let dir = crate::storage_layout::active_storage_layout()
+        .directory(request.user_document_dir.as_path(), modified_at);
+    fs::create_dir_all(&dir)?;
+    let mut link = request.mode == FolderImportMode::HardLink;
+    let mut suffix = 1usize;
+    // Each name is claimed by the call that creates it, so a note that shows up at the same
+    // name meanwhile is never overwritten; the next suffix is tried instead.
+    let destination = loop {
+        let candidate = resolve_unique_path(dir.as_path(), &stem, extension, suffix);
+        let placed = if link {
+            match fs::hard_link(source, &candidate) {
+                Err(error) if link_unsupported(&error) => {
+                    link = false;
+                    report.copied_instead_of_linked += 1;
+                    copy_new(source, &candidate, modified)
+                }
+                linked => linked,
+            }
+        } else {
+            copy_new(source, &candidate, modified)
+        };
+        match placed {
+            Ok(()) => break candidate,
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => suffix += 1,
+            Err(error) => return Err(error),
+        }
+    };
+    crate::storage_backend::storage_backend_containing(destination.as_path())
+        .added(destination.as_path());
+    if destination.file_name() != source.file_name() {
+        report
+            .renamed
+            .push((source.to_path_buf(), destination.clone()));
+    }
+    report.imported.push(destination);
+    Ok(())
+}
+
+/// Puts every .txt/.md file under `source_dir` into the storage-layout folder for its
+/// modified date, named by the usual title rules (`name_2.md` on a clash). Hidden entries
+/// and symlinks are left out; a file that fails is reported and the rest go on.
+pub(crate) fn import_folder(request: &FolderImportRequest) -> io::Result<FolderImportReport> {
+    let source_dir = fs::canonicalize(&request.source_dir)?;
+    let vault = fs::canonicalize(&request.user_document_dir)
+        .map_err(crate::workflow_error::at_path(&request.user_document_dir))?;
+    if source_dir.starts_with(&vault) || vault.starts_with(&source_dir) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{} overlaps the notes folder; pick a folder outside it",
+                request.source_dir.display()
+            ),
+        ));
+    }
+    let mut report = FolderImportReport::default();
+    let mut sources = Vec::new();
+    collect_import_sources(source_dir.as_path(), &mut sources, &mut report.skipped)?;
+    for source in sources {
+        if let Err(error) = import_one(source.as_path(), request, &mut report) {
+            report.skipped.push((source, error.to_string()));
+        }
+    }
+    trace_debug(format!(
+        "req-imp1 folder import source={} imported={} renamed={} skipped={}",
+        source_dir.display(),
+        report.imported.len(),
+        report.renamed.len(),
+        report.skipped.len()
+    ));
+    Ok(report)
+}
+
+// The drive or file system cannot hold a hard link here; any other failure is a real error.
+fn link_unsupported(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::CrossesDevices | io::ErrorKind::Unsupported
+    )
+}
+
+// Copies `source` to a `destination` that must not exist yet, keeping the modified time.
+fn copy_new(source: &Path, destination: &Path, modified: std::time::SystemTime) -> io::Result<()> {
+    let mut target = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(destination)?;
+    let copied = fs::File::open(source)
+        .and_then(|mut reader| io::copy(&mut reader, &mut target))
+        .and_then(|_| target.sync_all());
+    drop(target);
+    copied
+        .and_then(|()| set_file_mtime(destination, FileTime::from_system_time(modified)))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(destination);
+        })
+}
+
+/// ENEX/OPML notes land where a note typed on their creation date would, named by the
+/// same title rules.
+struct VaultNotePlacement<'a> {
+    user_document_dir: &'a Path,
+}
+
+impl NotePlacement for VaultNotePlacement<'_> {
+    fn vault_root(&self) -> &Path {
+        self.user_document_dir
+    }
+
+    fn directory(&self, created: DateTime<Local>) -> PathBuf {
+        crate::file_update_handler::note_directory(self.user_document_dir, created)
+    }
+
+    fn stem(&self, title: &str, created: DateTime<Local>) -> String {
+        stem_from_singleline_value(title.trim(), created)
+    }
+
+    fn written(&self, path: &Path) {
+        crate::storage_backend::storage_backend_containing(path).added(path);
+    }
+}
+
+pub(crate) fn import_notes_file(
+    request: &NotesFileImportRequest,
+) -> io::Result<NotesImportSummary> {
+    let placement = VaultNotePlacement {
+        user_document_dir: request.user_document_dir.as_path(),
+    };
+    let summary =
+        crate::notes_import::import_notes_file(&request.source, request.format, &placement)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, format!("{error:#}")))?;
+    trace_debug(format!(
+        "req-nimp1 notes file import source={} format={:?} notes={} attachments={} failed={}",
+        request.source.display(),
+        request.format,
+        summary.notes.len(),
+        summary.attachments,
+        summary.failed.len()
+    ));
+    Ok(summary)
+}
+
+impl crate::app::Papyru2App {
+    /// Ctrl+Shift+K.
+    pub(crate) fn prompt_import_folder(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.folder_import_running {
+            self.show_transient_notice(tr("notice.import_running").to_string(), cx);
+            return;
+        }
+        let buttons = [
+            tr("button.import_text_folder"),
+            tr("button.import_enex"),
+            tr("button.import_opml"),
+            tr("button.cancel"),
+        ];
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &tr("prompt.import_notes"),
+            Some(&tr("prompt.import_notes_detail")),
+            &[
+                buttons[0].as_ref(),
+                buttons[1].as_ref(),
+                buttons[2].as_ref(),
+                buttons[3].as_ref(),
+            ],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let format = match answer.await {
+                Ok(0) => None,
+                Ok(1) => Some(NotesImportFormat::Enex),
+                Ok(2) => Some(NotesImportFormat::Opml),
+                _ => return,
+            };
+            let _ = this.update_in(cx, |app, window, cx| match format {
+                None => app.pick_import_folder(window, cx),
+                Some(format) => app.pick_notes_file_to_import(format, window, cx),
+            });
+        })
+        .detach();
+    }
+
+    fn pick_notes_file_to_import(
+        &mut self,
+        format: NotesImportFormat,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let answer = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some(tr_with("button.import_format", &[("format", format.label())]).into()),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = answer.await else {
+                return;
+            };
+            let Some(source) = paths.into_iter().next() else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.run_notes_file_import(source, format, window, cx)
+            });
+        })
+        .detach();
+    }
+
+    fn run_notes_file_import(
+        &mut self,
+        source: PathBuf,
+        format: NotesImportFormat,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let request = NotesFileImportRequest {
+            source: source.clone(),
+            user_document_dir: self.app_paths.user_document_dir.clone(),
+            format,
+        };
+        let (summary_tx, summary_rx) =
+            smol::channel::bounded::<WorkflowResult<NotesImportSummary>>(1);
+        if let Err(error) = self
+            .file_workflow
+            .notes_file_import_async(request, move |result| {
+                let _ = summary_tx.try_send(result);
+            })
+        {
+            self.show_toast(
+                ToastSeverity::Error,
+                error.notice(tr("error.import_failed")),
+                cx,
+            );
+            return;
+        }
+        self.folder_import_running = true;
+        self.show_transient_notice(
+            tr_with(
+                "notice.importing",
+                &[("path", source.display().to_string().as_str())],
+            ),
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(result) = summary_rx.recv().await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.folder_import_running = false;
+                let summary = match result {
+                    Ok(summary) => summary,
+                    Err(error) => {
+                        app.show_toast(
+                            ToastSeverity::Error,
+                            error.notice(tr("error.import_failed")),
+                            cx,
+                        );
+                        return;
+                    }
+                };
+                app.apply_file_tree_watcher_refresh(cx);
+                app.show_toast(ToastSeverity::Success, summary.summary(), cx);
+                if summary.failed.is_empty() {
+                    return;
+                }
+                let details = summary
+                    .failed
+                    .iter()
+                    .take(FOLDER_IMPORT_REPORT_MAX_LINES)
+                    .map(|(title, error)| format!("{title}: {error}"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let _ = window.prompt(
+                    PromptLevel::Info,
+                    &summary.summary(),
+                    Some(&details),
+                    &[tr("button.ok").as_ref()],
+                    cx,
+                );
+            });
+        })
+        .detach();
+    }
+
+    fn pick_import_folder(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let answer = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some(tr("button.import_folder")),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = answer.await else {
+                return;
+            };
+            let Some(source_dir) = paths.into_iter().next() else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.confirm_import_folder(source_dir, window, cx)
+            });
+        })
+        .detach();
+    }
+
+    fn confirm_import_folder(
+        &mut self,
+        source_dir: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let detail = tr_with(
+            "prompt.import_folder_detail",
+            &[("path", source_dir.display().to_string().as_str())],
+        );
+        let buttons = [
+            tr("button.copy_files"),
+            tr("button.link_files"),
+            tr("button.cancel"),
+        ];
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &tr("prompt.import_notes"),
+            Some(&detail),
+            &[
+                buttons[0].as_ref(),
+                buttons[1].as_ref(),
+                buttons[2].as_ref(),
+            ],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let mode = match answer.await {
+                Ok(0) => FolderImportMode::Copy,
+                Ok(1) => FolderImportMode::HardLink,
+                _ => return,
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.run_folder_import(source_dir, mode, window, cx)
+            });
+        })
+        .detach();
+    }
+
+    fn run_folder_import(
+        &mut self,
+        source_dir: PathBuf,
+        mode: FolderImportMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let request = FolderImportRequest {
+            source_dir: source_dir.clone(),
+            user_document_dir: self.app_paths.user_document_dir.clone(),
+            mode,
+        };
+        let (report_tx, report_rx) =
+            smol::channel::bounded::<WorkflowResult<FolderImportReport>>(1);
+        if let Err(error) = self
+            .file_workflow
+            .folder_import_async(request, move |result| {
+                let _ = report_tx.try_send(result);
+            })
+        {
+            self.show_toast(
+                ToastSeverity::Error,
+                error.notice(tr("error.import_failed")),
+                cx,
+            );
+            return;
+        }
+        trace_debug(format!(
+            "req-imp1 folder import queued source={} mode={mode:?}",
+            source_dir.display()
+        ));
+        self.folder_import_running = true;
+        self.show_transient_notice(
+            tr_with(
+                "notice.importing",
+                &[("path", source_dir.display().to_string().as_str())],
+            ),
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(result) = report_rx.recv().await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.folder_import_running = false;
+                let report = match result {
+                    Ok(report) => report,
+                    Err(error) => {
+                        trace_debug(format!("req-imp1 folder import failed error={error}"));
+                        app.show_toast(
+                            ToastSeverity::Error,
+                            error.notice(tr("error.import_failed")),
+                            cx,
+                        );
+                        return;
+                    }
+                };
+                app.apply_file_tree_watcher_refresh(cx);
+                app.show_toast(ToastSeverity::Success, report.summary(), cx);
+                if report.renamed.is_empty() && report.skipped.is_empty() {
+                    return;
+                }
+                let details = report.details(
+                    source_dir.as_path(),
+                    app.app_paths.user_document_dir.as_path(),
+                );
+                let _ = window.prompt(
+                    PromptLevel::Info,
+                    &report.summary(),
+                    Some(&details),
+                    &[tr("button.ok").as_ref()],
+                    cx,
+                );
+            });
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_folder_import_{name}_{}_{stamp}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&root).expect("create temp root");
+        root
+    }
+
+    fn write_with_mtime(path: &Path, text: &str, at: DateTime<Local>) {
+        fs::create_dir_all(path.parent().unwrap()).expect("create parent");
+        fs::write(path, text).expect("write source");
+        set_file_mtime(path, FileTime::from_system_time(at.into())).expect("set mtime");
+    }
+
+    #[test]
+    fn imp_test1_folder_import_places_by_date_and_reports_renames_and_skips() {
+        let root = new_temp_root("imp_test1");
+        let source = root.join("old_notes");
+        let vault = root.join("vault");
+        let march = Local.with_ymd_and_hms(2024, 3, 9, 10, 0, 0).unwrap();
+        let may = Local.with_ymd_and_hms(2024, 5, 1, 8, 30, 0).unwrap();
+        write_with_mtime(&source.join("plan.txt"), "plan", march);
+        write_with_mtime(&source.join("sub").join("plan.txt"), "other plan", march);
+        write_with_mtime(&source.join("sub").join("ab.MD"), "# ab", may);
+        write_with_mtime(&source.join("photo.jpg"), "jpg", may);
+        write_with_mtime(&source.join(".hidden.txt"), "hidden", may);
+        let march_dir = vault.join("2024").join("03").join("09");
+        fs::create_dir_all(&march_dir).expect("create vault day");
+
+        let report = import_folder(&FolderImportRequest {
+            source_dir: source.clone(),
+            user_document_dir: vault.clone(),
+            mode: FolderImportMode::Copy,
+        })
+        .expect("import");
+
+        let source = fs::canonicalize(&source).expect("canonical source");
+        let may_dir = vault.join("2024").join("05").join("01");
+        assert_eq!(
+            report.imported,
+            vec![
+                march_dir.join("plan.txt"),
+                may_dir.join("ab.md"),
+                march_dir.join("plan_2.txt"),
+            ]
+        );
+        assert_eq!(
+            report.renamed,
+            vec![
+                (source.join("sub").join("ab.MD"), may_dir.join("ab.md")),
+                (
+                    source.join("sub").join("plan.txt"),
+                    march_dir.join("plan_2.txt")
+                ),
+            ]
+        );
+        assert_eq!(
+            report.skipped,
+            vec![(
+                source.join("photo.jpg"),
+                "not a .txt or .md file".to_string()
+            )]
+        );
+        assert_eq!(
+            fs::read_to_string(march_dir.join("plan_2.txt")).expect("read"),
+            "other plan"
+        );
+        let copied_mtime = FileTime::from_last_modification_time(
+            &fs::metadata(may_dir.join("ab.md")).expect("metadata"),
+        );
+        assert_eq!(copied_mtime.unix_seconds(), may.timestamp());
+        assert_eq!(report.summary(), "Imported 3 note(s), 2 renamed, 1 skipped");
+
+        let overlapping = import_folder(&FolderImportRequest {
+            source_dir: vault.clone(),
+            user_document_dir: vault.join("2024"),
+            mode: FolderImportMode::Copy,
+        });
+        assert_eq!(
+            overlapping.map_err(|error| error.kind()).err(),
+            Some(io::ErrorKind::InvalidInput)
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn imp_test2_hard_link_import_never_replaces_a_vault_note() {
+        let root = new_temp_root("imp_test2");
+        let source = root.join("old_notes");
+        let vault = root.join("vault");
+        let march = Local.with_ymd_and_hms(2024, 3, 9, 10, 0, 0).unwrap();
+        write_with_mtime(&source.join("plan.txt"), "imported", march);
+        let march_dir = vault.join("2024").join("03").join("09");
+        write_with_mtime(&march_dir.join("plan.txt"), "already here", march);
+
+        let report = import_folder(&FolderImportRequest {
+            source_dir: source.clone(),
+            user_document_dir: vault.clone(),
+            mode: FolderImportMode::HardLink,
+        })
+        .expect("import");
+
+        assert_eq!(report.imported, vec![march_dir.join("plan_2.txt")]);
+        assert_eq!(report.copied_instead_of_linked, 0);
+        assert_eq!(
+            fs::read_to_string(march_dir.join("plan.txt")).expect("read kept"),
+            "already here"
+        );
+        assert_eq!(
+            fs::read_to_string(march_dir.join("plan_2.txt")).expect("read linked"),
+            "imported"
+        );
+        assert!(!link_unsupported(&io::Error::from(
+            io::ErrorKind::AlreadyExists
+        )));
+        assert!(link_unsupported(&io::Error::from(
+            io::ErrorKind::CrossesDevices
+        )));
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,809 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use gpui::*;
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+
+const GIT_DEFAULT_PROGRAM: &str = "git";
+const GIT_DEFAULT_INTERVAL_MINUTES: u64 = 30;
+/// Autosaves this close together end up in one commit.
+const GIT_AUTOSAVE_SETTLE_DURATION: Duration = Duration::from_secs(60);
+const GIT_FALLBACK_USER_NAME: &str = "papyru2";
+const GIT_FALLBACK_USER_EMAIL: &str = "papyru2@localhost";
+// Atomic writes go through `*.tmp` files; one left behind by a crash is not a note.
+const GIT_IGNORE_CONTENT: &str = "recyclebin/\n*.tmp\n";
+const GIT_HISTORY_PAGE_SIZE: usize = 8;
+const GIT_HISTORY_DIFF_MAX_LINES: usize = 40;
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct GitVersioningConfigFile {
+    #[serde(default)]
+    git: GitVersioningConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct GitVersioningConfigSection {
+    enabled: Option<bool>,
+    program: Option<PathBuf>,
+    commit_interval_minutes: Option<u64>,
+    commit_after_autosave: Option<bool>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct GitVersioningConfig {
+    pub program: PathBuf,
+    /// None: no scheduled commits.
+    pub interval: Option<Duration>,
+    pub after_autosave: bool,
+}
+
+/// `[git] enabled = true` turns versioning on. Commits happen every `commit_interval_minutes`
+/// (default 30, 0 turns the schedule off) and, unless `commit_after_autosave = false`, once
+/// autosaves have settled for a minute.
+pub(crate) fn load_git_versioning_config(path: &Path) -> Option<GitVersioningConfig> {
+    let raw = fs::read_to_string(path).ok()?;
+    let section = match toml::from_str::<GitVersioningConfigFile>(&raw) {
+        Ok(parsed) => parsed.git,
+        Err(error) => {
+            trace_debug(format!(
+                "req-git1 config parse failed path={} error={error}",
+                path.display()
+            ));
+            return None;
+        }
+    };
+    if section.enabled != Some(true) {
+        return None;
+    }
+    let minutes = section
+        .commit_interval_minutes
+        .unwrap_or(GIT_DEFAULT_INTERVAL_MINUTES);
+    Some(GitVersioningConfig {
+        program: section
+            .program
+            .filter(|program| !program.as_os_str().is_empty())
+            .unwrap_or_else(|| PathBuf::from(GIT_DEFAULT_PROGRAM)),
+        interval: (minutes > 0).then(|| Duration::from_secs(minutes * 60)),
+        after_autosave: section.commit_after_autosave.unwrap_or(true),
+    })
+}
+
+fn git_command(program: &Path, repo: &Path) -> Command {
+    let mut command = Command::new(program);
+    command
+        .arg("-C")
+        .arg(repo)
+        .args(["-c", "core.quotepath=false"]);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt as _;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command
+}
+
+// Text for the log, status and diffs shown to the user; note contents go through
+// `run_git_bytes` so a note that is not UTF-8 comes back unchanged.
+fn run_git(program: &Path, repo: &Path, args: &[&str]) -> io::Result<String> {
+    run_git_bytes(program, repo, args).map(|stdout| String::from_utf8_lossy(&stdout).into_owned())
+}
+
+fn run_git_bytes(program: &Path, repo: &Path, args: &[&str]) -> io::Result<Vec<u8>> {
+    let output = git_command(program, repo).args(args).output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(io::Error::other(format!(
+            "git {}: {stderr}",
+            args.join(" ")
+        )));
+    }
+    Ok(output.stdout)
+}
+
+// Git wants forward slashes in `<commit>:<path>` on every platform.
+fn git_path(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Turns the vault into a repository on first use; the recycle bin is ignored.
+pub(crate) fn ensure_git_repository(program: &Path, root: &Path) -> io::Result<bool> {
+    let ignore = root.join(".gitignore");
+    if root.join(".git").exists() {
+        // A .gitignore written before `*.tmp` was listed is brought up to date.
+        if fs::read_to_string(&ignore).is_ok_and(|content| content == "recyclebin/\n") {
+            fs::write(&ignore, GIT_IGNORE_CONTENT)?;
+        }
+        return Ok(false);
+    }
+    run_git(program, root, &["init", "-q"])?;
+    if !ignore.exists() {
+        fs::write(&ignore, GIT_IGNORE_CONTENT)?;
+    }
+    trace_debug(format!(
+        "req-git1 repository initialized root={}",
+        root.display()
+    ));
+    Ok(true)
+}
+
+/// Commits every change in the vault; returns the short hash, or None when nothing changed.
+/// A machine without a git identity commits as "papyru2".
+pub(crate) fn commit_vault(
+    program: &Path,
+    root: &Path,
+    now: DateTime<Local>,
+) -> io::Result<Option<String>> {
+    ensure_git_repository(program, root)?;
+    run_git(program, root, &["add", "-A"])?;
+    if run_git(program, root, &["status", "--porcelain"])?
+        .trim()
+        .is_empty()
+    {
+        return Ok(None);
+    }
+    let message = format!("papyru2 {}", now.format("%Y-%m-%d %H:%M:%S"));
+    let mut args = Vec::new();
+    if run_git(program, root, &["config", "user.email"]).is_err() {
+        args.extend([
+            "-c".to_string(),
+            format!("user.name={GIT_FALLBACK_USER_NAME}"),
+            "-c".to_string(),
+            format!("user.email={GIT_FALLBACK_USER_EMAIL}"),
+        ]);
+    }
+    args.extend([
+        "commit".to_string(),
+        "-q".to_string(),
+        "-m".to_string(),
+        message,
+    ]);
+    run_git(
+        program,
+        root,
+        &args.iter().map(String::as_str).collect::<Vec<_>>(),
+    )?;
+    let commit = run_git(program, root, &["rev-parse", "--short", "HEAD"])?
+        .trim()
+        .to_string();
+    Ok(Some(commit))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct NoteRevision {
+    pub commit: String,
+    pub committed_at: String,
+    /// Where the note was in that commit; renames are followed.
+    pub path: String,
+}
+
+/// Commits that touched `note`, newest first.
+pub(crate) fn note_history(
+    program: &Path,
+    root: &Path,
+    note: &Path,
+) -> io::Result<Vec<NoteRevision>> {
+    let relative = git_path(note.strip_prefix(root).unwrap_or(note));
+    let log = run_git(
+        program,
+        root,
+        &[
+            "log",
+            "--follow",
+            "--name-only",
+            "--format=%x00%h%x09%ad",
+            "--date=format:%Y-%m-%d %H:%M",
+            "--",
+            relative.as_str(),
+        ],
+    )?;
+    Ok(log
+        .split('\0')
+        .filter_map(|entry| {
+            let mut lines = entry.lines().filter(|line| !line.is_empty());
+            let (commit, committed_at) = lines.next()?.split_once('\t')?;
+            Some(NoteRevision {
+                commit: commit.to_string(),
+                committed_at: committed_at.to_string(),
+                path: lines.next()?.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// The note's bytes exactly as committed, whatever their encoding.
+pub(crate) fn note_at_revision(
+    program: &Path,
+    root: &Path,
+    revision: &NoteRevision,
+) -> io::Result<Vec<u8>> {
+    run_git_bytes(
+        program,
+        root,
+        &["show", &format!("{}:{}", revision.commit, revision.path)],
+    )
+}
+
+/// What changed from `revision` to the note on disk, as a unified diff.
+pub(crate) fn note_diff_since(
+    program: &Path,
+    root: &Path,
+    revision: &NoteRevision,
+    note: &Path,
+) -> io::Result<String> {
+    let relative = git_path(note.strip_prefix(root).unwrap_or(note));
+    // Comparing blobs keeps the diff readable when the note was renamed since.
+    let current = run_git(
+        program,
+        root,
+        &["hash-object", "-w", "--", relative.as_str()],
+    )?;
+    run_git(
+        program,
+        root,
+        &[
+            "diff",
+            "--no-color",
+            "--no-ext-diff",
+            &format!("{}:{}", revision.commit, revision.path),
+            current.trim(),
+        ],
+    )
+}
+
+pub(crate) enum GitVersioningEvent {
+    Committed(String),
+    Failed(String),
+}
+
+enum GitTrigger {
+    Now,
+    AutosaveSaved,
+}
+
+/// Handle to the commit thread; dropping it stops the thread after the current commit.
+pub(crate) struct GitVersioningWorker {
+    config: GitVersioningConfig,
+    trigger_tx: mpsc::Sender<GitTrigger>,
+}
+
+impl GitVersioningWorker {
+    pub fn config(&self) -> &GitVersioningConfig {
+        &self.config
+    }
+
+    pub fn commit_now(&self) {
+        let _ = self.trigger_tx.send(GitTrigger::Now);
+    }
+
+    pub fn autosave_saved(&self) {
+        if self.config.after_autosave {
+            let _ = self.trigger_tx.send(GitTrigger::AutosaveSaved);
+        }
+    }
+}
+
+/// Commits the active vault on the configured schedule, a minute after the last of a run
+/// of autosaves, and whenever `commit_now` is called.
+pub(crate) fn spawn_git_versioning_worker(
+    config: GitVersioningConfig,
+    active_vault_root: crate::vaults::ActiveVaultRoot,
+) -> (
+    GitVersioningWorker,
+    smol::channel::Receiver<GitVersioningEvent>,
+) {
+    let (trigger_tx, trigger_rx) = mpsc::channel::<GitTrigger>();
+    let (event_tx, event_rx) = smol::channel::unbounded();
+    thread::spawn({
+        let config = config.clone();
+        move || {
+            let mut scheduled = config.interval.map(|interval| Instant::now() + interval);
+            let mut settled = None;
+            loop {
+                let now = Instant::now();
+                let wait = [scheduled, settled]
+                    .into_iter()
+                    .flatten()
+                    .min()
+                    .map(|due| due.saturating_duration_since(now))
+                    .unwrap_or(Duration::from_secs(60 * 60));
+                let commit = match trigger_rx.recv_timeout(wait) {
+                    Ok(GitTrigger::Now) => true,
+                    Ok(GitTrigger::AutosaveSaved) => {
+                        settled = Some(Instant::now() + GIT_AUTOSAVE_SETTLE_DURATION);
+                        false
+                    }
+                    Err(RecvTimeoutError::Timeout) => {
+                        let now = Instant::now();
+                        [scheduled, settled]
+                            .into_iter()
+                            .flatten()
+                            .any(|due| due <= now)
+                    }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+                if !commit {
+                    continue;
+                }
+                settled = None;
+                scheduled = config.interval.map(|interval| Instant::now() + interval);
+                let root = active_vault_root.get();
+                let event =
+                    match commit_vault(config.program.as_path(), root.as_path(), Local::now()) {
+                        Ok(Some(commit)) => GitVersioningEvent::Committed(commit),
+                        Ok(None) => continue,
+                        Err(error) => GitVersioningEvent::Failed(error.to_string()),
+                    };
+                if event_tx.send_blocking(event).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    (GitVersioningWorker { config, trigger_tx }, event_rx)
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn spawn_git_versioning_event_loop(
+        event_rx: smol::channel::Receiver<GitVersioningEvent>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, cx| {
+            while let Ok(event) = event_rx.recv().await {
+                let updated = this.update_in(cx, |app, _, cx| match event {
+                    GitVersioningEvent::Committed(commit) => {
+                        trace_debug(format!("req-git1 committed commit={commit}"));
+                    }
+                    GitVersioningEvent::Failed(message) => {
+                        trace_debug(format!("req-git1 commit failed error={message}"));
+                        app.show_transient_notice(
+                            tr_with("notice.git_commit_failed", &[("reason", message.as_str())]),
+                            cx,
+                        );
+                    }
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Ctrl+Shift+Y: revisions of the open note, newest first.
+    pub(crate) fn show_note_history(
+        &mut self,
+        page: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(program) = self
+            .git_versioning
+            .as_ref()
+            .map(|worker| worker.config().program.clone())
+        else {
+            self.show_transient_notice(tr("notice.git_history_disabled").to_string(), cx);
+            return;
+        };
+        let Some(note) = self.file_workflow.current_edit_path() else {
+            self.show_transient_notice(tr("notice.history_needs_note").to_string(), cx);
+            return;
+        };
+        let root = self.app_paths.user_document_dir.clone();
+        let history_note = note.clone();
+        Self::run_git_task(
+            move || note_history(program.as_path(), root.as_path(), history_note.as_path()),
+            move |app, revisions, window, cx| {
+                app.present_note_history(note, page, revisions, window, cx);
+            },
+            window,
+            cx,
+        );
+    }
+
+    // Git can take a while on a large vault, so it runs on its own thread and only the
+    // answer comes back to the UI.
+    fn run_git_task<T: Send + 'static>(
+        task: impl FnOnce() -> io::Result<T> + Send + 'static,
+        done: impl FnOnce(&mut Self, io::Result<T>, &mut Window, &mut Context<Self>) + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (result_tx, result_rx) = smol::channel::bounded(1);
+        thread::spawn(move || {
+            let _ = result_tx.send_blocking(task());
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(result) = result_rx.recv().await else {
+                return;
+            };
+            let _ = this.update_in(cx, move |app, window, cx| done(app, result, window, cx));
+        })
+        .detach();
+    }
+
+    fn present_note_history(
+        &mut self,
+        note: PathBuf,
+        page: usize,
+        revisions: io::Result<Vec<NoteRevision>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let revisions = match revisions {
+            Ok(revisions) => revisions,
+            Err(error) => {
+                self.show_transient_notice(
+                    tr_with(
+                        "notice.cannot_read_history",
+                        &[("reason", error.to_string().as_str())],
+                    ),
+                    cx,
+                );
+                return;
+            }
+        };
+        if revisions.is_empty() {
+            self.show_transient_notice(tr("notice.note_not_committed").to_string(), cx);
+            return;
+        }
+        let start = page * GIT_HISTORY_PAGE_SIZE;
+        let shown = revisions
+            .iter()
+            .skip(start)
+            .take(GIT_HISTORY_PAGE_SIZE)
+            .cloned()
+            .collect::<Vec<_>>();
+        let has_more = start + shown.len() < revisions.len();
+        let mut labels = shown
+            .iter()
+            .map(|revision| format!("{} ({})", revision.committed_at, revision.commit))
+            .collect::<Vec<_>>();
+        if has_more {
+            labels.push(tr("button.more").to_string());
+        }
+        labels.push(tr("button.cancel").to_string());
+        let label_refs = labels.iter().map(String::as_str).collect::<Vec<_>>();
+        let detail = tr_with(
+            "prompt.note_history_detail",
+            &[
+                ("count", revisions.len().to_string().as_str()),
+                ("path", note.display().to_string().as_str()),
+            ],
+        );
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &tr("prompt.note_history"),
+            Some(&detail),
+            &label_refs,
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(choice) = answer.await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                if let Some(revision) = shown.get(choice) {
+                    app.show_note_revision(note, revision.clone(), window, cx);
+                } else if has_more && choice == shown.len() {
+                    app.show_note_history(page + 1, window, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn show_note_revision(
+        &mut self,
+        note: PathBuf,
+        revision: NoteRevision,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(program) = self
+            .git_versioning
+            .as_ref()
+            .map(|worker| worker.config().program.clone())
+        else {
+            return;
+        };
+        // The diff compares against what is on disk, so it has to include the last keystrokes.
+        let _ = self.flush_editor_content_before_context_switch("req-git1-diff", cx);
+        let root = self.app_paths.user_document_dir.clone();
+        let diff_note = note.clone();
+        let diff_revision = revision.clone();
+        Self::run_git_task(
+            move || {
+                note_diff_since(
+                    program.as_path(),
+                    root.as_path(),
+                    &diff_revision,
+                    &diff_note,
+                )
+            },
+            move |app, diff, window, cx| {
+                app.present_note_revision(note, revision, diff, window, cx)
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn present_note_revision(
+        &mut self,
+        note: PathBuf,
+        revision: NoteRevision,
+        diff: io::Result<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let diff = match diff {
+            Ok(diff) => diff,
+            Err(error) => {
+                self.show_transient_notice(
+                    tr_with(
+                        "notice.cannot_diff_revision",
+                        &[("reason", error.to_string().as_str())],
+                    ),
+                    cx,
+                );
+                return;
+            }
+        };
+        let lines = diff.lines().collect::<Vec<_>>();
+        let mut detail = if lines.is_empty() {
+            tr("prompt.revision_same_as_note").to_string()
+        } else {
+            lines
+                .iter()
+                .take(GIT_HISTORY_DIFF_MAX_LINES)
+                .copied()
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        if lines.len() > GIT_HISTORY_DIFF_MAX_LINES {
+            detail.push('\n');
+            detail.push_str(&tr_with(
+                "prompt.and_more_lines",
+                &[(
+                    "count",
+                    (lines.len() - GIT_HISTORY_DIFF_MAX_LINES)
+                        .to_string()
+                        .as_str(),
+                )],
+            ));
+        }
+        let buttons = [
+            tr("button.restore_revision"),
+            tr("button.back"),
+            tr("button.cancel"),
+        ];
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &tr_with(
+                "prompt.changes_since",
+                &[("time", revision.committed_at.to_string().as_str())],
+            ),
+            Some(&detail),
+            &[
+                buttons[0].as_ref(),
+                buttons[1].as_ref(),
+                buttons[2].as_ref(),
+            ],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(choice) = answer.await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| match choice {
+                0 => app.restore_note_revision(note, revision, window, cx),
+                1 => app.show_note_history(0, window, cx),
+                _ => {}
+            });
+        })
+        .detach();
+    }
+
+    fn restore_note_revision(
+        &mut self,
+        note: PathBuf,
+        revision: NoteRevision,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(program) = self
+            .git_versioning
+            .as_ref()
+            .map(|worker| worker.config().program.clone())
+        else {
+            return;
+        };
+        if !self.flush_editor_content_before_context_switch("req-git1-restore", cx) {
+            self.show_transient_notice(tr("notice.restore_needs_save").to_string(), cx);
+            return;
+        }
+        let root = self.app_paths.user_document_dir.clone();
+        let restore_note = note.clone();
+        let restore_revision = revision.clone();
+        Self::run_git_task(
+            move || {
+                let bytes = note_at_revision(program.as_path(), root.as_path(), &restore_revision)?;
+                crate::snapshots::snapshot_before(
+                    "git restore",
+                    std::slice::from_ref(&restore_note),
+                );
+                crate::storage_backend::storage_backend_for(root.as_path())
+                    .write(restore_note.as_path(), &bytes)
+            },
+            move |app, restored, window, cx| {
+                app.complete_note_revision_restore(note, revision, restored, window, cx);
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn complete_note_revision_restore(
+        &mut self,
+        note: PathBuf,
+        revision: NoteRevision,
+        restored: io::Result<()>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let message = match restored {
+            Ok(()) => {
+                trace_debug(format!(
+                    "req-git1 restored path={} commit={}",
+                    note.display(),
+                    revision.commit
+                ));
+                self.check_current_file_external_change(window, cx);
+                tr_with(
+                    "notice.revision_restored",
+                    &[("time", revision.committed_at.to_string().as_str())],
+                )
+            }
+            Err(error) => tr_with(
+                "notice.revision_restore_failed",
+                &[("reason", error.to_string().as_str())],
+            ),
+        };
+        self.show_transient_notice(message, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_git_versioning_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    #[test]
+    fn git_test1_config_is_off_unless_enabled() {
+        let root = new_temp_root("git_test1");
+        let conf = root.join("papyru2_conf.toml");
+        fs::write(&conf, "[git]\ncommit_interval_minutes = 5\n").expect("write conf");
+        assert_eq!(load_git_versioning_config(&conf), None);
+
+        fs::write(
+            &conf,
+            "[git]\nenabled = true\ncommit_interval_minutes = 0\ncommit_after_autosave = false\n",
+        )
+        .expect("write conf");
+        assert_eq!(
+            load_git_versioning_config(&conf),
+            Some(GitVersioningConfig {
+                program: PathBuf::from("git"),
+                interval: None,
+                after_autosave: false,
+            })
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn git_test2_commits_list_diff_and_read_back_note_revisions() {
+        let program = Path::new(GIT_DEFAULT_PROGRAM);
+        if run_git(program, &std::env::temp_dir(), &["--version"]).is_err() {
+            return;
+        }
+        let root = new_temp_root("git_test2");
+        let day = root.join("2026").join("03").join("01");
+        fs::create_dir_all(&day).expect("create day");
+        fs::create_dir_all(root.join("recyclebin")).expect("create recyclebin");
+        let note = day.join("plan.md");
+        let at = |second| Local.with_ymd_and_hms(2026, 3, 1, 9, 0, second).unwrap();
+        // Enough unchanged lines for git to recognise the later rename.
+        let text = |first: &str| format!("{first}\nagenda\nbudget\nschedule\nowners\n");
+
+        fs::write(&note, text("first")).expect("write first");
+        fs::write(root.join("recyclebin").join("old.md"), "gone").expect("trashed");
+        let first = commit_vault(program, &root, at(0))
+            .expect("first commit")
+            .expect("first commit made");
+        assert_eq!(
+            commit_vault(program, &root, at(1)).expect("no change"),
+            None
+        );
+        fs::write(&note, text("second")).expect("write second");
+        commit_vault(program, &root, at(2)).expect("second commit");
+        fs::write(&note, text("third")).expect("write third");
+        let renamed = day.join("plans.md");
+        fs::rename(&note, &renamed).expect("rename");
+        commit_vault(program, &root, at(3)).expect("rename commit");
+        let note = renamed;
+
+        let history = note_history(program, &root, &note).expect("history");
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].path, "2026/03/01/plans.md");
+        assert_eq!(history[2].commit, first);
+        assert_eq!(history[2].path, "2026/03/01/plan.md");
+        assert_eq!(
+            note_at_revision(program, &root, &history[2]).expect("read first"),
+            text("first").into_bytes()
+        );
+        let diff = note_diff_since(program, &root, &history[2], &note).expect("diff");
+        assert!(diff.contains("-first"));
+        assert!(diff.contains("+third"));
+        assert_eq!(
+            run_git(program, &root, &["ls-files"])
+                .expect("ls-files")
+                .trim(),
+            ".gitignore\n2026/03/01/plans.md"
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn git_test3_revisions_of_non_utf8_notes_read_back_byte_for_byte() {
+        let program = Path::new(GIT_DEFAULT_PROGRAM);
+        if run_git(program, &std::env::temp_dir(), &["--version"]).is_err() {
+            return;
+        }
+        let root = new_temp_root("git_test3");
+        let note = root.join("memo.txt");
+        // "メモ" in Shift_JIS, then a byte that is never valid UTF-8.
+        let bytes = b"\x83\x81\x83\x82\n\xff\n".to_vec();
+        fs::write(&note, &bytes).expect("write note");
+        let at = Local.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+        commit_vault(program, &root, at).expect("commit");
+
+        let history = note_history(program, &root, &note).expect("history");
+        assert_eq!(history.len(), 1);
+        assert_eq!(
+            note_at_revision(program, &root, &history[0]).expect("read revision"),
+            bytes
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+}
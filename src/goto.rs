@@ -0,0 +1,207 @@
+use std::{fs, path::PathBuf};
+
+use chrono::{Local, NaiveDate};
+use gpui::*;
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+use crate::pickers::PickerSelection;
+
+const GOTO_DATE_FORMATS: [&str; 4] = ["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d", "%Y%m%d"];
+
+/// `42` or `42:7` (1-based, as shown in the gutter) to a 0-based (line, char) inside the
+/// note; numbers past the end land on the last line or column.
+pub(crate) fn parse_goto_line(input: &str, line_lengths: &[usize]) -> Result<(u32, u32), String> {
+    let (line, column) = match input.trim().split_once(':') {
+        Some((line, column)) => (line.trim(), Some(column.trim())),
+        None => (input.trim(), None),
+    };
+    let line = line
+        .parse::<usize>()
+        .ok()
+        .filter(|line| *line > 0)
+        .ok_or_else(|| format!("'{line}' is not a line number"))?;
+    let column = match column {
+        Some(column) => column
+            .parse::<usize>()
+            .ok()
+            .filter(|column| *column > 0)
+            .ok_or_else(|| format!("'{column}' is not a column number"))?,
+        None => 1,
+    };
+    let line = (line - 1).min(line_lengths.len().saturating_sub(1));
+    let column = (column - 1).min(line_lengths.get(line).copied().unwrap_or(0));
+    Ok((line as u32, column as u32))
+}
+
+pub(crate) fn parse_goto_date(input: &str) -> Option<NaiveDate> {
+    let input = input.trim();
+    GOTO_DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(input, format).ok())
+}
+
+impl crate::app::Papyru2App {
+    /// Ctrl+G.
+    pub(crate) fn show_goto_line(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.editor.read(cx).current_editing_file_path().is_none() {
+            self.show_transient_notice(tr("notice.goto_needs_note").to_string(), cx);
+            return;
+        }
+        if self.editor.read(cx).is_loading() {
+            self.show_transient_notice(tr("notice.note_still_loading").to_string(), cx);
+            return;
+        }
+        let total_lines = self.editor.read(cx).snapshot(cx).value.split('\n').count();
+        self.open_text_picker(
+            &tr("prompt.goto_line"),
+            &tr("placeholder.goto_line"),
+            &format!("1\u{2013}{total_lines}"),
+            |this, selection, window, cx| {
+                let PickerSelection::Text(input) = selection else {
+                    return;
+                };
+                let value = this.editor.read(cx).snapshot(cx).value;
+                let line_lengths = value
+                    .split('\n')
+                    .map(|line| line.chars().count())
+                    .collect::<Vec<_>>();
+                match parse_goto_line(&input, &line_lengths) {
+                    Ok((line, character)) => {
+                        trace_debug(format!("req-goto line={line} char={character}"));
+                        this.editor.update(cx, |editor, cx| {
+                            editor.apply_cursor(line, character, window, cx);
+                            editor.focus(window, cx);
+                        });
+                    }
+                    Err(message) => this.show_transient_notice(message, cx),
+                }
+            },
+            window,
+            cx,
+        );
+    }
+
+    /// Ctrl+Shift+G: opens that day's folder (as the storage layout names it) in the file
+    /// tree, offering to create it when it does not exist yet.
+    pub(crate) fn show_goto_date(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.open_text_picker(
+            &tr("prompt.goto_date"),
+            "YYYY-MM-DD",
+            &Local::now().format("%Y-%m-%d").to_string(),
+            |this, selection, window, cx| {
+                let PickerSelection::Text(input) = selection else {
+                    return;
+                };
+                let Some(folder) = parse_goto_date(&input).and_then(|date| {
+                    let at = date
+                        .and_hms_opt(12, 0, 0)?
+                        .and_local_timezone(Local)
+                        .earliest()?;
+                    Some(
+                        crate::storage_layout::active_storage_layout()
+                            .directory(this.app_paths.user_document_dir.as_path(), at),
+                    )
+                }) else {
+                    this.show_transient_notice(
+                        tr_with("notice.not_a_date", &[("input", input.as_str())]),
+                        cx,
+                    );
+                    return;
+                };
+                if folder.is_dir() {
+                    this.reveal_folder_in_tree(folder, window, cx);
+                } else {
+                    this.confirm_create_goto_folder(folder, window, cx);
+                }
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn confirm_create_goto_folder(
+        &mut self,
+        folder: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let relative = folder
+            .strip_prefix(&self.app_paths.user_document_dir)
+            .unwrap_or(folder.as_path())
+            .display()
+            .to_string();
+        let buttons = [tr("button.create_folder"), tr("button.cancel")];
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &tr_with("prompt.folder_missing", &[("path", relative.as_str())]),
+            Some(&tr("prompt.folder_missing_detail")),
+            &[buttons[0].as_ref(), buttons[1].as_ref()],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            if answer.await.ok() != Some(0) {
+                return;
+            }
+            let _ = this.update_in(cx, |app, window, cx| {
+                if let Err(error) = fs::create_dir_all(&folder) {
+                    app.show_transient_notice(
+                        tr_with(
+                            "notice.could_not_create",
+                            &[
+                                ("path", relative.as_str()),
+                                ("reason", error.to_string().as_str()),
+                            ],
+                        ),
+                        cx,
+                    );
+                    return;
+                }
+                trace_debug(format!("req-goto created folder={}", folder.display()));
+                app.apply_file_tree_watcher_refresh(cx);
+                app.reveal_folder_in_tree(folder, window, cx);
+            });
+        })
+        .detach();
+    }
+
+    pub(crate) fn reveal_folder_in_tree(
+        &mut self,
+        folder: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        trace_debug(format!("req-goto reveal folder={}", folder.display()));
+        self.apply_req_ftr18_startup_daily_folder_positioning(folder, window, cx);
+        self.file_tree.read(cx).focus(window);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn goto_test1_line_input_is_one_based_and_clamped() {
+        let lengths = [5, 0, 12];
+        assert_eq!(parse_goto_line("1", &lengths), Ok((0, 0)));
+        assert_eq!(parse_goto_line(" 3:4 ", &lengths), Ok((2, 3)));
+        assert_eq!(parse_goto_line("3:99", &lengths), Ok((2, 12)));
+        assert_eq!(parse_goto_line("40", &lengths), Ok((2, 0)));
+        assert_eq!(parse_goto_line("2:3", &lengths), Ok((1, 0)));
+        assert!(parse_goto_line("0", &lengths).is_err());
+        assert!(parse_goto_line("ten", &lengths).is_err());
+        assert!(parse_goto_line("2:x", &lengths).is_err());
+    }
+
+    #[test]
+    fn goto_test2_dates_accept_common_separators() {
+        let expected = NaiveDate::from_ymd_opt(2026, 3, 9);
+        assert_eq!(parse_goto_date("2026-03-09"), expected);
+        assert_eq!(parse_goto_date("2026/03/09"), expected);
+        assert_eq!(parse_goto_date("2026.3.9"), expected);
+        assert_eq!(parse_goto_date(" 20260309 "), expected);
+        assert_eq!(parse_goto_date("2026-02-30"), None);
+        assert_eq!(parse_goto_date("March 9"), None);
+    }
+}
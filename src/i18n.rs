@@ -0,0 +1,218 @@
+use std::{collections::HashMap, fs, path::Path, sync::OnceLock};
+
+use gpui::SharedString;
+
+use crate::log::trace_debug;
+
+pub(crate) const LOCALES_DIR_NAME: &str = "locales";
+const FALLBACK_LANGUAGE: &str = "en";
+
+// Shipped with the binary; `conf/locales/<language>.toml` overrides single keys or adds a
+// language of its own.
+const EMBEDDED_LOCALES: [(&str, &str); 2] = [
+    ("en", include_str!("../assets/locales/en.toml")),
+    ("ja", include_str!("../assets/locales/ja.toml")),
+];
+
+static TRANSLATIONS: OnceLock<Translations> = OnceLock::new();
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct UiConfigFile {
+    #[serde(default)]
+    ui: UiConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct UiConfigSection {
+    language: Option<String>,
+}
+
+/// `[ui] language`: unset or `"auto"` follows the system locale.
+pub(crate) fn load_ui_language(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| {
+            toml::from_str::<UiConfigFile>(&raw)
+                .map_err(|error| {
+                    trace_debug(format!("req-i18n config parse failed error={error}"));
+                })
+                .ok()
+        })
+        .and_then(|parsed| parsed.ui.language)
+}
+
+/// The language code to load: the configured one, else the system's (`ja_JP.UTF-8` -> `ja`).
+pub(crate) fn resolve_language(configured: Option<&str>, system_locale: Option<&str>) -> String {
+    let configured = configured
+        .map(str::trim)
+        .filter(|language| !language.is_empty() && !language.eq_ignore_ascii_case("auto"));
+    configured
+        .or(system_locale)
+        .and_then(|locale| locale.split(['_', '-', '.']).next())
+        .map(str::to_ascii_lowercase)
+        .filter(|language| !language.is_empty() && language != "c" && language != "posix")
+        .unwrap_or_else(|| FALLBACK_LANGUAGE.to_string())
+}
+
+// The Windows and macOS UI language as well as the POSIX variables, e.g. `ja-JP` or
+// `ja_JP.UTF-8`.
+fn system_locale() -> Option<String> {
+    sys_locale::get_locale().filter(|locale| !locale.is_empty())
+}
+
+// Nested tables become dotted keys: `[button] cancel` is `button.cancel`.
+fn flatten_locale(prefix: &str, table: &toml::Table, out: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            toml::Value::String(text) => {
+                out.insert(key, text.clone());
+            }
+            toml::Value::Table(table) => flatten_locale(&key, table, out),
+            _ => trace_debug(format!("req-i18n ignored non-string key={key}")),
+        }
+    }
+}
+
+fn parse_locale(raw: &str) -> Result<HashMap<String, String>, toml::de::Error> {
+    let table = toml::from_str::<toml::Table>(raw)?;
+    let mut strings = HashMap::new();
+    flatten_locale("", &table, &mut strings);
+    Ok(strings)
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Translations {
+    language: String,
+    strings: HashMap<String, String>,
+}
+
+impl Translations {
+    /// English first, then the language's shipped strings, then the user's file, each
+    /// layer replacing the keys it has.
+    pub fn load(language: &str, locales_dir: Option<&Path>) -> Self {
+        let mut strings = HashMap::new();
+        let embedded = EMBEDDED_LOCALES
+            .iter()
+            .filter(|(code, _)| *code == FALLBACK_LANGUAGE || *code == language)
+            .map(|(code, raw)| (code.to_string(), raw.to_string()));
+        let user = locales_dir.and_then(|dir| {
+            let path = dir.join(format!("{language}.toml"));
+            fs::read_to_string(&path)
+                .ok()
+                .map(|raw| (path.display().to_string(), raw))
+        });
+        for (source, raw) in embedded.chain(user) {
+            match parse_locale(&raw) {
+                Ok(layer) => strings.extend(layer),
+                Err(error) => {
+                    trace_debug(format!(
+                        "req-i18n locale parse failed source={source} error={error}"
+                    ));
+                }
+            }
+        }
+        Self {
+            language: language.to_string(),
+            strings,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.strings.get(key).map(String::as_str)
+    }
+}
+
+/// Called once at startup, before any view is built.
+pub(crate) fn activate_translations(language: &str, locales_dir: &Path) {
+    let translations = Translations::load(language, Some(locales_dir));
+    trace_debug(format!(
+        "req-i18n language={} keys={}",
+        translations.language,
+        translations.strings.len()
+    ));
+    if TRANSLATIONS.set(translations).is_err() {
+        trace_debug("req-i18n translations were already active");
+    }
+}
+
+pub(crate) fn activate_translations_from_config(config_path: &Path, conf_dir: &Path) {
+    let configured = load_ui_language(config_path);
+    let language = resolve_language(configured.as_deref(), system_locale().as_deref());
+    activate_translations(&language, conf_dir.join(LOCALES_DIR_NAME).as_path());
+}
+
+fn active_translations() -> &'static Translations {
+    TRANSLATIONS.get_or_init(|| Translations::load(FALLBACK_LANGUAGE, None))
+}
+
+/// The UI string for `key`; an unknown key shows as itself so it is easy to spot.
+pub(crate) fn tr(key: &str) -> SharedString {
+    match active_translations().get(key) {
+        Some(text) => SharedString::from(text.to_string()),
+        None => {
+            trace_debug(format!("req-i18n missing key={key}"));
+            SharedString::from(key.to_string())
+        }
+    }
+}
+
+/// `tr` with `{name}` placeholders filled in.
+pub(crate) fn tr_with(key: &str, args: &[(&str, &str)]) -> String {
+    args.iter()
+        .fold(tr(key).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn i18n_test1_locales_layer_over_english_and_fill_placeholders() {
+        let dir = std::env::temp_dir().join(format!("papyru2_i18n_test1_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("ja.toml"),
+            "[button]\ncancel = \"やめる\"\n[bad]\ncount = 3\n",
+        )
+        .unwrap();
+
+        let ja = Translations::load("ja", Some(&dir));
+        assert_eq!(ja.get("button.cancel"), Some("やめる"));
+        assert_eq!(ja.get("button.close"), Some("閉じる"));
+        assert_eq!(ja.get("bad.count"), None);
+
+        // A language with no shipped file still gets English for every key.
+        let fr = Translations::load("fr", Some(&dir));
+        assert_eq!(fr.get("placeholder.subject"), Some("Subject <Enter>"));
+
+        // Every shipped language covers the English keys.
+        let en = Translations::load("en", None);
+        let ja = Translations::load("ja", None);
+        let ja_only = parse_locale(EMBEDDED_LOCALES[1].1).unwrap();
+        for key in en.strings.keys() {
+            assert!(ja_only.contains_key(key), "ja.toml lacks {key}");
+            assert!(ja.get(key).is_some());
+        }
+
+        assert_eq!(
+            tr_with("error.cannot_open", &[("name", "a.md"), ("reason", "gone")]),
+            "Cannot open a.md: gone"
+        );
+        assert_eq!(tr("no.such.key"), SharedString::from("no.such.key"));
+
+        assert_eq!(resolve_language(None, Some("ja_JP.UTF-8")), "ja");
+        assert_eq!(resolve_language(Some("auto"), Some("en_US")), "en");
+        assert_eq!(resolve_language(Some(" JA "), Some("en_US")), "ja");
+        assert_eq!(resolve_language(None, Some("C")), "en");
+        assert_eq!(resolve_language(None, None), "en");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
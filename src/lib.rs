@@ -1,3 +1,6 @@
+pub mod cli;
+pub mod demo_vault;
+pub mod notes_import;
 pub mod path_resolver;
 pub mod quic_rpc_protocol;
 pub mod textfile_import;
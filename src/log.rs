@@ -84,17 +84,148 @@ pub(crate) fn prepare_startup_log_files(
     Ok(())
 }
 
-pub(crate) fn trace_debug(message: impl AsRef<str>) {
-    if !trace_debug_is_enabled() {
-        return;
+/// Trace categories with independent verbosity: `input` covers per-keystroke lines, which
+/// would otherwise dominate the log; `workflow` is everything traced through `trace_debug`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TraceCategory {
+    Input,
+    Workflow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TraceVerbosity {
+    Off,
+    Sampled,
+    Full,
+}
+
+impl TraceVerbosity {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" => Some(Self::Off),
+            "sampled" => Some(Self::Sampled),
+            "full" => Some(Self::Full),
+            _ => None,
+        }
     }
+}
+
+pub(crate) const TRACE_INPUT_DEFAULT_SAMPLE_EVERY: u32 = 10;
+pub(crate) const TRACE_INPUT_DEFAULT_MAX_PER_SECOND: u32 = 20;
+const TRACE_RATE_WINDOW_MS: u128 = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct TraceCategoryConfig {
+    pub verbosity: TraceVerbosity,
+    /// In `Sampled` mode one line out of this many is written.
+    pub sample_every: u32,
+    /// Lines written per second after sampling; `0` means unlimited.
+    pub max_per_second: u32,
+}
 
-    let now = std::time::SystemTime::now()
+impl TraceCategoryConfig {
+    pub fn default_for(category: TraceCategory) -> Self {
+        match category {
+            TraceCategory::Input => Self {
+                verbosity: TraceVerbosity::Sampled,
+                sample_every: TRACE_INPUT_DEFAULT_SAMPLE_EVERY,
+                max_per_second: TRACE_INPUT_DEFAULT_MAX_PER_SECOND,
+            },
+            TraceCategory::Workflow => Self {
+                verbosity: TraceVerbosity::Full,
+                sample_every: 1,
+                max_per_second: 0,
+            },
+        }
+    }
+}
+
+/// Per-category gate; dropped lines are counted and reported on the next admitted line.
+#[derive(Debug)]
+pub(crate) struct TraceSampler {
+    config: TraceCategoryConfig,
+    seen: u64,
+    window_start_ms: u128,
+    window_count: u32,
+    suppressed: u64,
+}
+
+impl TraceSampler {
+    pub fn new(config: TraceCategoryConfig) -> Self {
+        Self {
+            config,
+            seen: 0,
+            window_start_ms: 0,
+            window_count: 0,
+            suppressed: 0,
+        }
+    }
+
+    /// `Some(lines suppressed since the previous admitted one)` when this line is written.
+    pub fn admit(&mut self, now_ms: u128) -> Option<u64> {
+        let sampled_in = match self.config.verbosity {
+            TraceVerbosity::Off => false,
+            TraceVerbosity::Full => true,
+            TraceVerbosity::Sampled => {
+                let every = u64::from(self.config.sample_every.max(1));
+                self.seen % every == 0
+            }
+        };
+        self.seen = self.seen.wrapping_add(1);
+        if !sampled_in {
+            self.suppressed += 1;
+            return None;
+        }
+        if self.config.max_per_second > 0 {
+            if now_ms.saturating_sub(self.window_start_ms) >= TRACE_RATE_WINDOW_MS {
+                self.window_start_ms = now_ms;
+                self.window_count = 0;
+            }
+            if self.window_count >= self.config.max_per_second {
+                self.suppressed += 1;
+                return None;
+            }
+            self.window_count += 1;
+        }
+        Some(std::mem::take(&mut self.suppressed))
+    }
+}
+
+static TRACE_SAMPLERS: std::sync::OnceLock<std::sync::Mutex<[TraceSampler; 2]>> =
+    std::sync::OnceLock::new();
+
+fn trace_samplers() -> &'static std::sync::Mutex<[TraceSampler; 2]> {
+    TRACE_SAMPLERS.get_or_init(|| {
+        std::sync::Mutex::new([
+            TraceSampler::new(TraceCategoryConfig::default_for(TraceCategory::Input)),
+            TraceSampler::new(TraceCategoryConfig::default_for(TraceCategory::Workflow)),
+        ])
+    })
+}
+
+fn trace_category_index(category: TraceCategory) -> usize {
+    match category {
+        TraceCategory::Input => 0,
+        TraceCategory::Workflow => 1,
+    }
+}
+
+pub(crate) fn configure_trace_category(category: TraceCategory, config: TraceCategoryConfig) {
+    let mut samplers = trace_samplers()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    samplers[trace_category_index(category)] = TraceSampler::new(config);
+}
+
+fn trace_now_ms() -> u128 {
+    std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map(|d| d.as_millis())
-        .unwrap_or(0);
+        .unwrap_or(0)
+}
 
-    let line = format!("[{now}] {}\n", message.as_ref());
+fn write_trace_line(now: u128, message: &str) {
+    let line = format!("[{now}] {message}\n");
     if let Ok(mut file) = std::fs::OpenOptions::new()
         .create(true)
         .append(true)
@@ -104,6 +235,34 @@ pub(crate) fn trace_debug(message: impl AsRef<str>) {
     }
 }
 
+pub(crate) fn trace_category(category: TraceCategory, message: impl AsRef<str>) {
+    if !trace_debug_is_enabled() {
+        return;
+    }
+    let now = trace_now_ms();
+    let admitted = trace_samplers()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())[trace_category_index(category)]
+    .admit(now);
+    match admitted {
+        None => {}
+        Some(0) => write_trace_line(now, message.as_ref()),
+        Some(suppressed) => write_trace_line(
+            now,
+            &format!("{} (+{suppressed} suppressed)", message.as_ref()),
+        ),
+    }
+}
+
+pub(crate) fn trace_debug(message: impl AsRef<str>) {
+    trace_category(TraceCategory::Workflow, message);
+}
+
+/// High-frequency per-keystroke traces; sampled and rate limited by default.
+pub(crate) fn trace_input(message: impl AsRef<str>) {
+    trace_category(TraceCategory::Input, message);
+}
+
 #[derive(Debug, Default, serde::Deserialize)]
 struct ReqLogConfigFile {
     #[serde(default)]
@@ -114,6 +273,14 @@ struct ReqLogConfigFile {
 struct ReqLogDebugSection {
     #[serde(default)]
     log: Option<bool>,
+    #[serde(default)]
+    input_trace: Option<String>,
+    #[serde(default)]
+    input_trace_sample_every: Option<u32>,
+    #[serde(default)]
+    input_trace_max_per_second: Option<u32>,
+    #[serde(default)]
+    workflow_trace: Option<String>,
 }
 
 pub(crate) fn req_log_effective_debug_logging_enabled(
@@ -144,6 +311,59 @@ pub(crate) fn load_req_log_config_override(path: &Path) -> Option<bool> {
     load_req_log_config_override_result(path).ok().flatten()
 }
 
+fn load_trace_category_configs_result(
+    path: &Path,
+) -> std::io::Result<(TraceCategoryConfig, TraceCategoryConfig)> {
+    let mut input = TraceCategoryConfig::default_for(TraceCategory::Input);
+    let mut workflow = TraceCategoryConfig::default_for(TraceCategory::Workflow);
+    if !path.is_file() {
+        return Ok((input, workflow));
+    }
+    let raw = std::fs::read_to_string(path)?;
+    let parsed: ReqLogConfigFile = toml::from_str(&raw)
+        .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error.to_string()))?;
+    let verbosity = |key: &str, value: &Option<String>, fallback: TraceVerbosity| match value {
+        None => Ok(fallback),
+        Some(value) => TraceVerbosity::parse(value).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "req-log invalid debug.{key} value={value} (expected off, sampled or full)"
+                ),
+            )
+        }),
+    };
+    input.verbosity = verbosity("input_trace", &parsed.debug.input_trace, input.verbosity)?;
+    workflow.verbosity = verbosity(
+        "workflow_trace",
+        &parsed.debug.workflow_trace,
+        workflow.verbosity,
+    )?;
+    if let Some(sample_every) = parsed.debug.input_trace_sample_every {
+        input.sample_every = sample_every.max(1);
+    }
+    if let Some(max_per_second) = parsed.debug.input_trace_max_per_second {
+        input.max_per_second = max_per_second;
+    }
+    Ok((input, workflow))
+}
+
+/// Applies `[debug]` category verbosity; a bad value keeps the defaults for both categories.
+pub(crate) fn configure_trace_categories_from_config(path: &Path) {
+    let (input, workflow) = match load_trace_category_configs_result(path) {
+        Ok(configs) => configs,
+        Err(error) => {
+            eprintln!("papyru2 trace category config ignored: {error}");
+            (
+                TraceCategoryConfig::default_for(TraceCategory::Input),
+                TraceCategoryConfig::default_for(TraceCategory::Workflow),
+            )
+        }
+    };
+    configure_trace_category(TraceCategory::Input, input);
+    configure_trace_category(TraceCategory::Workflow, workflow);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,6 +400,7 @@ mod tests {
                 .join("recyclebin"),
             log_dir: app_home.join("log"),
             bin_dir: app_home.join("bin"),
+            override_report: Vec::new(),
         };
         paths.ensure_dirs().expect("ensure app dirs");
         paths
@@ -338,4 +559,54 @@ mod tests {
 
         log_test_cleanup(root.as_path());
     }
+
+    #[test]
+    fn log_test10_req_log7_input_sampling_and_rate_limit_report_suppressed_lines() {
+        let mut sampler = TraceSampler::new(TraceCategoryConfig {
+            verbosity: TraceVerbosity::Sampled,
+            sample_every: 3,
+            max_per_second: 2,
+        });
+        let admitted = (0..9).map(|_| sampler.admit(10_000)).collect::<Vec<_>>();
+        // Lines 0, 3 and 6 are sampled in; the third exceeds two lines per second.
+        assert_eq!(
+            admitted,
+            vec![Some(0), None, None, Some(2), None, None, None, None, None]
+        );
+        // A new second reopens the window; the next sampled line carries the dropped count.
+        assert_eq!(sampler.admit(11_000), Some(5));
+
+        let mut off = TraceSampler::new(TraceCategoryConfig {
+            verbosity: TraceVerbosity::Off,
+            sample_every: 1,
+            max_per_second: 0,
+        });
+        assert_eq!(off.admit(0), None);
+        let mut full = TraceSampler::new(TraceCategoryConfig::default_for(TraceCategory::Workflow));
+        assert!((0..100).all(|_| full.admit(0) == Some(0)));
+    }
+
+    #[test]
+    fn log_test11_req_log7_debug_table_sets_category_verbosity() {
+        let root = log_test_temp_root("log_test11");
+        let config_path = root.join("papyru2_conf.toml");
+        std::fs::write(
+            config_path.as_path(),
+            "[debug]\nlog = true\ninput_trace = \"off\"\nworkflow_trace = \"sampled\"\ninput_trace_max_per_second = 5\n",
+        )
+        .expect("write req-log test config");
+
+        let (input, workflow) =
+            load_trace_category_configs_result(config_path.as_path()).expect("load categories");
+        assert_eq!(input.verbosity, TraceVerbosity::Off);
+        assert_eq!(input.max_per_second, 5);
+        assert_eq!(input.sample_every, TRACE_INPUT_DEFAULT_SAMPLE_EVERY);
+        assert_eq!(workflow.verbosity, TraceVerbosity::Sampled);
+
+        std::fs::write(config_path.as_path(), "[debug]\ninput_trace = \"loud\"\n")
+            .expect("write invalid config");
+        assert!(load_trace_category_configs_result(config_path.as_path()).is_err());
+
+        log_test_cleanup(root.as_path());
+    }
 }
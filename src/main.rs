@@ -3,17 +3,76 @@
     windows_subsystem = "windows"
 )]
 mod app;
+mod atomic_io;
+mod attachments;
+mod bookmarks;
+mod calendar_view;
+mod color_schemes;
+mod confirmations;
+mod daily_review;
+mod duplicate_titles;
 mod editor;
+mod editor_find;
+mod editor_transforms;
+mod export;
+mod external_change;
+mod external_files;
+mod file_times;
 mod file_tree;
 mod file_tree_watcher;
 mod file_update_handler;
+mod filename_rules;
+mod focus_mode;
+mod folder_import;
+mod git_versioning;
+mod goto;
+mod i18n;
 mod log;
+mod markdown_preview;
+mod mirror_backup;
+mod note_front_matter;
+mod note_links;
+mod note_metadata;
+mod note_templates;
+mod note_titles;
+mod pickers;
 mod quic_rpc;
+mod quick_capture;
+mod save_transforms;
+mod scratch_panes;
+mod search_replace;
+mod settings_archive;
+mod sidebar;
+mod single_instance;
 mod singleline_input;
 mod sl_editor_association;
+#[cfg(feature = "smoke-test")]
+mod smoke_test;
+mod snapshots;
+mod spell_check;
+mod split_editor;
+mod status_bar;
+mod storage_backend;
+mod storage_layout;
+mod storage_report;
+mod sync_conflicts;
+mod sync_status;
+mod syntax_language;
+mod tag_index;
+mod text_encoding;
+mod toasts;
 mod top_bars;
+mod ui_state;
+mod vault_availability;
+mod vault_backup;
+mod vaults;
+mod window_overlay;
 mod window_position;
+mod workflow_error;
+mod workflow_journal;
 
+pub use papyru2::cli;
+pub use papyru2::notes_import;
 pub use papyru2::path_resolver;
 pub use papyru2::quic_rpc_protocol;
 
@@ -0,0 +1,299 @@
+use std::{ops::Range, path::PathBuf};
+
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    text::TextView,
+    v_flex,
+};
+
+const MARKDOWN_FENCE_PREFIXES: [&str; 2] = ["```", "~~~"];
+const MARKDOWN_IMAGE_MAX_HEIGHT: f32 = 240.;
+
+/// One rendered preview block and the 0-based editor lines it was built from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MarkdownBlock {
+    pub source_lines: Range<u32>,
+    pub text: String,
+}
+
+fn is_markdown_fence(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    MARKDOWN_FENCE_PREFIXES
+        .iter()
+        .any(|prefix| trimmed.starts_with(prefix))
+}
+
+// Blocks split at blank lines and before headings; fenced code stays in one block even when
+// it contains blank lines. A leading front-matter block is not rendered.
+pub(crate) fn split_markdown_blocks(text: &str) -> Vec<MarkdownBlock> {
+    let lines = text.split('\n').collect::<Vec<_>>();
+    let mut index = lines
+        .first()
+        .filter(|line| line.trim_end() == "---")
+        .and_then(|_| {
+            lines
+                .iter()
+                .skip(1)
+                .position(|line| line.trim_end() == "---")
+        })
+        .map(|close| close + 2)
+        .unwrap_or(0);
+
+    let mut blocks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut current_start = index;
+    let mut in_fence = false;
+    let flush = |blocks: &mut Vec<MarkdownBlock>, current: &mut Vec<&str>, start: usize| {
+        if current.is_empty() {
+            return;
+        }
+        let end = start + current.len();
+        blocks.push(MarkdownBlock {
+            source_lines: start.min(u32::MAX as usize) as u32..end.min(u32::MAX as usize) as u32,
+            text: current.join("\n"),
+        });
+        current.clear();
+    };
+
+    while index < lines.len() {
+        let line = lines[index];
+        if in_fence {
+            current.push(line);
+            in_fence = !is_markdown_fence(line);
+        } else if line.trim().is_empty() {
+            flush(&mut blocks, &mut current, current_start);
+        } else {
+            let starts_block = line.trim_start().starts_with('#') || is_markdown_fence(line);
+            if starts_block {
+                flush(&mut blocks, &mut current, current_start);
+            }
+            if current.is_empty() {
+                current_start = index;
+            }
+            current.push(line);
+            in_fence = is_markdown_fence(line);
+        }
+        index += 1;
+    }
+    flush(&mut blocks, &mut current, current_start);
+    blocks
+}
+
+/// The block containing `line`, or the closest block before it (blank separator lines).
+pub(crate) fn block_index_for_line(blocks: &[MarkdownBlock], line: u32) -> Option<usize> {
+    blocks
+        .iter()
+        .rposition(|block| block.source_lines.start <= line)
+        .or_else(|| (!blocks.is_empty()).then_some(0))
+}
+
+pub struct MarkdownPreview {
+    blocks: Vec<MarkdownBlock>,
+    // Folder of the open note and its vault; relative image links resolve against the
+    // first, `/...` links against the second.
+    base_dir: Option<PathBuf>,
+    vault_root: PathBuf,
+    scroll_handle: ScrollHandle,
+    visible: bool,
+    sync_enabled: bool,
+    synced_block: Option<usize>,
+}
+
+impl MarkdownPreview {
+    pub fn new() -> Self {
+        Self {
+            blocks: Vec::new(),
+            base_dir: None,
+            vault_root: PathBuf::new(),
+            scroll_handle: ScrollHandle::new(),
+            visible: false,
+            sync_enabled: true,
+            synced_block: None,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool, cx: &mut Context<Self>) {
+        self.visible = visible;
+        self.synced_block = None;
+        crate::log::trace_debug(format!("req-mdp1 preview visible={visible}"));
+        cx.notify();
+    }
+
+    pub fn set_source(
+        &mut self,
+        text: &str,
+        base_dir: Option<PathBuf>,
+        vault_root: PathBuf,
+        cx: &mut Context<Self>,
+    ) {
+        let blocks = split_markdown_blocks(text);
+        if blocks != self.blocks || base_dir != self.base_dir || vault_root != self.vault_root {
+            self.blocks = blocks;
+            self.base_dir = base_dir;
+            self.vault_root = vault_root;
+            cx.notify();
+        }
+    }
+
+    fn block_image_path(&self, block: &MarkdownBlock) -> Option<PathBuf> {
+        let target = crate::attachments::markdown_image_target(&block.text)?;
+        let path = crate::attachments::resolve_attachment_path(
+            self.vault_root.as_path(),
+            self.base_dir.as_deref()?,
+            target,
+        )?;
+        path.is_file().then_some(path)
+    }
+
+    // Scrolls the block rendered from the editor's current line into view, unless the user
+    // unlocked the sync to read another part of the preview.
+    pub fn sync_to_source_line(&mut self, line: u32, cx: &mut Context<Self>) {
+        if !self.visible || !self.sync_enabled {
+            return;
+        }
+        let Some(block_index) = block_index_for_line(&self.blocks, line) else {
+            return;
+        };
+        if self.synced_block == Some(block_index) {
+            return;
+        }
+        self.synced_block = Some(block_index);
+        self.scroll_handle.scroll_to_top_of_item(block_index);
+        cx.notify();
+    }
+
+    fn toggle_sync(&mut self, cx: &mut Context<Self>) {
+        self.sync_enabled = !self.sync_enabled;
+        self.synced_block = None;
+        crate::log::trace_debug(format!(
+            "req-mdp1 preview scroll sync enabled={}",
+            self.sync_enabled
+        ));
+        cx.notify();
+    }
+}
+
+impl Render for MarkdownPreview {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut body = div()
+            .id("markdown-preview-scroll")
+            .flex_1()
+            .min_h_0()
+            .overflow_y_scroll()
+            .track_scroll(&self.scroll_handle)
+            .p_2();
+        for (index, block) in self.blocks.iter().enumerate() {
+            // A block holding just a local image (a pasted attachment) renders as a thumbnail.
+            if let Some(path) = self.block_image_path(block) {
+                body = body.child(
+                    div().pb_2().child(
+                        img(path)
+                            .max_w_full()
+                            .max_h(px(MARKDOWN_IMAGE_MAX_HEIGHT))
+                            .object_fit(ObjectFit::ScaleDown),
+                    ),
+                );
+                continue;
+            }
+            body = body.child(div().pb_2().child(TextView::markdown(
+                ("markdown-preview-block", index),
+                block.text.clone(),
+                window,
+                cx,
+            )));
+        }
+
+        v_flex()
+            .size_full()
+            .border_l_1()
+            .border_color(cx.theme().border)
+            .child(
+                h_flex().gap_2().px_2().text_xs().child("Preview").child(
+                    Button::new("markdown-preview-sync")
+                        .small()
+                        .label(if self.sync_enabled {
+                            "Scroll sync: on"
+                        } else {
+                            "Scroll sync: off"
+                        })
+                        .when(self.sync_enabled, |button| button.primary())
+                        .when(!self.sync_enabled, |button| button.ghost())
+                        .on_click(cx.listener(|this, _, _, cx| this.toggle_sync(cx))),
+                ),
+            )
+            .child(body)
+    }
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn toggle_markdown_preview(&mut self, cx: &mut Context<Self>) {
+        let visible = !self.markdown_preview.read(cx).is_visible();
+        self.markdown_preview
+            .update(cx, |preview, cx| preview.set_visible(visible, cx));
+        if visible {
+            self.sync_markdown_preview(true, cx);
+        }
+        cx.notify();
+    }
+
+    pub(crate) fn sync_markdown_preview(&mut self, source_changed: bool, cx: &mut Context<Self>) {
+        if !self.markdown_preview.read(cx).is_visible() {
+            return;
+        }
+        let snapshot = self.editor.read(cx).snapshot(cx);
+        let top_visible_line = self.editor.read(cx).top_visible_line();
+        let base_dir = self
+            .file_workflow
+            .current_edit_path()
+            .and_then(|path| path.parent().map(PathBuf::from));
+        let vault_root = self.app_paths.user_document_dir.clone();
+        self.markdown_preview.update(cx, |preview, cx| {
+            if source_changed {
+                preview.set_source(&snapshot.value, base_dir, vault_root, cx);
+            }
+            preview.sync_to_source_line(top_visible_line, cx);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mdp_test1_blocks_keep_source_line_ranges_and_fences_together() {
+        let text = "---\ntags: a\n---\n# Title\nintro\n\n```\ncode\n\nmore\n```\n## Next\ntext";
+
+        let blocks = split_markdown_blocks(text);
+
+        assert_eq!(
+            blocks
+                .iter()
+                .map(|block| block.source_lines.clone())
+                .collect::<Vec<_>>(),
+            vec![3..5, 6..11, 11..13]
+        );
+        assert_eq!(blocks[1].text, "```\ncode\n\nmore\n```");
+        assert_eq!(blocks[2].text, "## Next\ntext");
+    }
+
+    #[test]
+    fn mdp_test2_line_maps_to_enclosing_or_previous_block() {
+        let blocks = split_markdown_blocks("a\n\nb\nc\n\n\nd");
+
+        assert_eq!(block_index_for_line(&blocks, 0), Some(0));
+        assert_eq!(block_index_for_line(&blocks, 1), Some(0));
+        assert_eq!(block_index_for_line(&blocks, 3), Some(1));
+        assert_eq!(block_index_for_line(&blocks, 5), Some(1));
+        assert_eq!(block_index_for_line(&blocks, 6), Some(2));
+        assert_eq!(block_index_for_line(&[], 3), None);
+    }
+}
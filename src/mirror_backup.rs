@@ -0,0 +1,698 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Local};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+};
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+
+const MIRROR_BACKUP_DEFAULT_INTERVAL_MINUTES: u64 = 60;
+const MIRROR_RESTORE_PAGE_SIZE: usize = 8;
+const MIRROR_TEMP_SUFFIX: &str = ".papyru2-mirror.tmp";
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct MirrorBackupConfigFile {
+    #[serde(default)]
+    backup: MirrorBackupConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct MirrorBackupConfigSection {
+    mirror_dir: Option<PathBuf>,
+    interval_minutes: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MirrorBackupConfig {
+    pub mirror_dir: PathBuf,
+    pub interval: Duration,
+}
+
+/// `[backup] mirror_dir = "..."` turns the job on; `interval_minutes` defaults to 60.
+pub(crate) fn load_mirror_backup_config(path: &Path) -> Option<MirrorBackupConfig> {
+    let raw = fs::read_to_string(path).ok()?;
+    let parsed: MirrorBackupConfigFile = match toml::from_str(&raw) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            trace_debug(format!(
+                "req-bak1 config parse failed path={} error={error}",
+                path.display()
+            ));
+            return None;
+        }
+    };
+    let mirror_dir = parsed
+        .backup
+        .mirror_dir
+        .filter(|dir| !dir.as_os_str().is_empty())?;
+    let minutes = parsed
+        .backup
+        .interval_minutes
+        .unwrap_or(MIRROR_BACKUP_DEFAULT_INTERVAL_MINUTES)
+        .max(1);
+    Some(MirrorBackupConfig {
+        mirror_dir,
+        interval: Duration::from_secs(minutes * 60),
+    })
+}
+
+/// Each vault is mirrored into its own folder, named after the vault folder plus a hash of
+/// its full path, so two vaults that share a folder name never mix their notes.
+pub(crate) fn mirror_target_dir(mirror_dir: &Path, user_document_dir: &Path) -> PathBuf {
+    let name = user_document_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "vault".to_string());
+    mirror_dir.join(format!(
+        "{name}_{}",
+        crate::path_resolver::vault_data_key(user_document_dir)
+    ))
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct MirrorBackupReport {
+    pub copied: usize,
+    pub unchanged: usize,
+    pub bytes_copied: u64,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+fn is_mirrored_file(relative: &Path) -> bool {
+    !relative.starts_with("recyclebin")
+        && !relative
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().ends_with(MIRROR_TEMP_SUFFIX))
+}
+
+// Symlinks are skipped rather than followed, so a link loop cannot send the walk in circles,
+// and so is `skip_dir`: a mirror kept inside the vault would otherwise copy itself.
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    skip_dir: Option<&Path>,
+    files: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        if file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            if skip_dir != Some(path.as_path()) {
+                collect_files(root, path.as_path(), skip_dir, files)?;
+            }
+            continue;
+        }
+        let relative = path.strip_prefix(root).unwrap_or(path.as_path());
+        if is_mirrored_file(relative) {
+            files.push(relative.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+// Size plus modification time decides; the copy takes over the source mtime so an
+// untouched note is skipped on the next run.
+fn is_unchanged(source: &fs::Metadata, target: &Path) -> bool {
+    let Ok(target) = fs::metadata(target) else {
+        return false;
+    };
+    target.len() == source.len() && target.modified().ok() == source.modified().ok()
+}
+
+fn copy_preserving_mtime(source: &Path, target: &Path, modified: SystemTime) -> io::Result<u64> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file_name = target
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp = target.with_file_name(format!(".{file_name}{MIRROR_TEMP_SUFFIX}"));
+    let bytes = fs::copy(source, &temp)?;
+    let file = fs::OpenOptions::new().write(true).open(&temp)?;
+    file.set_modified(modified)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&temp, target)?;
+    Ok(bytes)
+}
+
+/// Copies new and changed notes from the vault into `target`. Files deleted from the vault
+/// stay in the mirror so they can still be restored.
+pub(crate) fn mirror_vault(
+    user_document_dir: &Path,
+    target: &Path,
+) -> io::Result<MirrorBackupReport> {
+    if !crate::vault_availability::is_vault_available(user_document_dir) {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "notes folder {} is unavailable",
+                user_document_dir.display()
+            ),
+        ));
+    }
+    fs::create_dir_all(target)?;
+    // Both sides canonical, so a mirror inside the vault is recognized however it was spelled.
+    let source_root = fs::canonicalize(user_document_dir)?;
+    let target_root = fs::canonicalize(target)?;
+    let mut files = Vec::new();
+    collect_files(
+        source_root.as_path(),
+        source_root.as_path(),
+        Some(target_root.as_path()),
+        &mut files,
+    )?;
+
+    let mut report = MirrorBackupReport::default();
+    for relative in files {
+        let source = user_document_dir.join(&relative);
+        let destination = target.join(&relative);
+        let result = fs::metadata(&source).and_then(|metadata| {
+            if is_unchanged(&metadata, destination.as_path()) {
+                return Ok(None);
+            }
+            copy_preserving_mtime(
+                source.as_path(),
+                destination.as_path(),
+                metadata.modified()?,
+            )
+            .map(Some)
+        });
+        match result {
+            Ok(None) => report.unchanged += 1,
+            Ok(Some(bytes)) => {
+                report.copied += 1;
+                report.bytes_copied += bytes;
+            }
+            Err(error) => report.failed.push((relative, error.to_string())),
+        }
+    }
+    trace_debug(format!(
+        "req-bak1 mirror done source={} target={} copied={} unchanged={} failed={}",
+        user_document_dir.display(),
+        target.display(),
+        report.copied,
+        report.unchanged,
+        report.failed.len()
+    ));
+    Ok(report)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MirrorEntry {
+    pub relative: PathBuf,
+    pub modified: Option<SystemTime>,
+    pub missing_in_vault: bool,
+}
+
+/// Mirrored files that are gone from the vault or differ from it, newest first.
+pub(crate) fn list_restorable_files(
+    user_document_dir: &Path,
+    target: &Path,
+) -> io::Result<Vec<MirrorEntry>> {
+    let mut files = Vec::new();
+    if target.is_dir() {
+        collect_files(target, target, None, &mut files)?;
+    }
+    let mut entries = files
+        .into_iter()
+        .filter_map(|relative| {
+            let mirrored = fs::metadata(target.join(&relative)).ok()?;
+            let current = user_document_dir.join(&relative);
+            if current.is_file() && is_unchanged(&mirrored, current.as_path()) {
+                return None;
+            }
+            Some(MirrorEntry {
+                missing_in_vault: !current.exists(),
+                modified: mirrored.modified().ok(),
+                relative,
+            })
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.modified));
+    Ok(entries)
+}
+
+/// Puts a mirrored file back. A note that still exists is kept and the copy lands next to
+/// it as `name (restored).ext`.
+pub(crate) fn restore_from_mirror(
+    user_document_dir: &Path,
+    target: &Path,
+    relative: &Path,
+) -> io::Result<PathBuf> {
+    let source = target.join(relative);
+    let mut destination = user_document_dir.join(relative);
+    if destination.exists() {
+        let stem = destination
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let extension = destination
+            .extension()
+            .map(|extension| format!(".{}", extension.to_string_lossy()))
+            .unwrap_or_default();
+        let mut suffix = 1;
+        loop {
+            let label = if suffix == 1 {
+                "restored".to_string()
+            } else {
+                format!("restored {suffix}")
+            };
+            let candidate = destination.with_file_name(format!("{stem} ({label}){extension}"));
+            if !candidate.exists() {
+                destination = candidate;
+                break;
+            }
+            suffix += 1;
+        }
+    }
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(&source, &destination)?;
+    trace_debug(format!(
+        "req-bak1 restored from={} to={}",
+        source.display(),
+        destination.display()
+    ));
+    Ok(destination)
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) enum MirrorBackupStatus {
+    #[default]
+    Idle,
+    Running,
+    Finished {
+        at: DateTime<Local>,
+        report: MirrorBackupReport,
+    },
+    Failed {
+        at: DateTime<Local>,
+        message: String,
+    },
+}
+
+impl MirrorBackupStatus {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Idle => "Backup: waiting for first run".to_string(),
+            Self::Running => "Backup: running...".to_string(),
+            Self::Finished { at, report } if report.failed.is_empty() => format!(
+                "Backup: {} ({} copied, {} unchanged)",
+                at.format("%H:%M"),
+                report.copied,
+                report.unchanged
+            ),
+            Self::Finished { at, report } => format!(
+                "Backup: {} ({} copied, {} failed)",
+                at.format("%H:%M"),
+                report.copied,
+                report.failed.len()
+            ),
+            Self::Failed { at, message } => {
+                format!("Backup failed at {}: {message}", at.format("%H:%M"))
+            }
+        }
+    }
+}
+
+pub(crate) enum MirrorBackupEvent {
+    Started,
+    Finished(Result<MirrorBackupReport, String>),
+}
+
+/// Handle to the backup thread; dropping it stops the thread after the current run.
+pub(crate) struct MirrorBackupWorker {
+    config: MirrorBackupConfig,
+    trigger_tx: mpsc::Sender<()>,
+}
+
+impl MirrorBackupWorker {
+    pub fn config(&self) -> &MirrorBackupConfig {
+        &self.config
+    }
+
+    pub fn run_now(&self) {
+        let _ = self.trigger_tx.send(());
+    }
+}
+
+/// Mirrors the active vault every `config.interval` and whenever `run_now` is called.
+pub(crate) fn spawn_mirror_backup_worker(
+    config: MirrorBackupConfig,
+    active_vault_root: crate::vaults::ActiveVaultRoot,
+) -> (
+    MirrorBackupWorker,
+    smol::channel::Receiver<MirrorBackupEvent>,
+) {
+    let (trigger_tx, trigger_rx) = mpsc::channel::<()>();
+    let (event_tx, event_rx) = smol::channel::unbounded();
+    thread::spawn({
+        let config = config.clone();
+        move || {
+            while !matches!(
+                trigger_rx.recv_timeout(config.interval),
+                Err(RecvTimeoutError::Disconnected)
+            ) {
+                if event_tx.send_blocking(MirrorBackupEvent::Started).is_err() {
+                    break;
+                }
+                let root = active_vault_root.get();
+                let target = mirror_target_dir(config.mirror_dir.as_path(), root.as_path());
+                let result = mirror_vault(root.as_path(), target.as_path())
+                    .map_err(|error| error.to_string());
+                if event_tx
+                    .send_blocking(MirrorBackupEvent::Finished(result))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        }
+    });
+    (MirrorBackupWorker { config, trigger_tx }, event_rx)
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn spawn_mirror_backup_event_loop(
+        event_rx: smol::channel::Receiver<MirrorBackupEvent>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, cx| {
+            while let Ok(event) = event_rx.recv().await {
+                let updated = this.update_in(cx, |app, _, cx| {
+                    app.mirror_backup_status = match event {
+                        MirrorBackupEvent::Started => MirrorBackupStatus::Running,
+                        MirrorBackupEvent::Finished(Ok(report)) => MirrorBackupStatus::Finished {
+                            at: Local::now(),
+                            report,
+                        },
+                        MirrorBackupEvent::Finished(Err(message)) => MirrorBackupStatus::Failed {
+                            at: Local::now(),
+                            message,
+                        },
+                    };
+                    cx.notify();
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn run_mirror_backup_now(&mut self, cx: &mut Context<Self>) {
+        let Some(worker) = self.mirror_backup.as_ref() else {
+            return;
+        };
+        if matches!(self.mirror_backup_status, MirrorBackupStatus::Running) {
+            return;
+        }
+        // The open note goes into this run rather than the next one.
+        if !self.flush_editor_content_before_context_switch("req-bak1-backup-now", cx) {
+            return;
+        }
+        worker.run_now();
+    }
+
+    fn show_mirror_restore_browser(
+        &mut self,
+        page: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(worker) = self.mirror_backup.as_ref() else {
+            return;
+        };
+        let root = self.app_paths.user_document_dir.clone();
+        let target = mirror_target_dir(worker.config().mirror_dir.as_path(), root.as_path());
+        let entries = match list_restorable_files(root.as_path(), target.as_path()) {
+            Ok(entries) => entries,
+            Err(error) => {
+                self.show_transient_notice(
+                    tr_with(
+                        "notice.cannot_read_backup",
+                        &[("reason", error.to_string().as_str())],
+                    ),
+                    cx,
+                );
+                return;
+            }
+        };
+        if entries.is_empty() {
+            self.show_transient_notice(tr("notice.vault_matches_backup").to_string(), cx);
+            return;
+        }
+        let start = page * MIRROR_RESTORE_PAGE_SIZE;
+        let shown = entries
+            .iter()
+            .skip(start)
+            .take(MIRROR_RESTORE_PAGE_SIZE)
+            .cloned()
+            .collect::<Vec<_>>();
+        let has_more = start + shown.len() < entries.len();
+        let mut labels = shown
+            .iter()
+            .map(|entry| {
+                let state = if entry.missing_in_vault {
+                    tr("label.deleted")
+                } else {
+                    tr("label.changed")
+                };
+                format!("{} ({state})", entry.relative.display())
+            })
+            .collect::<Vec<_>>();
+        if has_more {
+            labels.push(tr("button.more").to_string());
+        }
+        labels.push(tr("button.cancel").to_string());
+        let label_refs = labels.iter().map(String::as_str).collect::<Vec<_>>();
+        let detail = tr_with(
+            "prompt.restore_from_backup_detail",
+            &[
+                ("count", entries.len().to_string().as_str()),
+                ("path", target.display().to_string().as_str()),
+            ],
+        );
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &tr("prompt.restore_from_backup"),
+            Some(&detail),
+            &label_refs,
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(choice) = answer.await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                if let Some(entry) = shown.get(choice) {
+                    match restore_from_mirror(
+                        root.as_path(),
+                        target.as_path(),
+                        entry.relative.as_path(),
+                    ) {
+                        Ok(restored) => {
+                            app.apply_file_tree_watcher_refresh(cx);
+                            app.open_file(restored, window, cx);
+                        }
+                        Err(error) => {
+                            app.show_transient_notice(
+                                tr_with(
+                                    "notice.restore_failed",
+                                    &[("reason", error.to_string().as_str())],
+                                ),
+                                cx,
+                            );
+                        }
+                    }
+                } else if has_more && choice == shown.len() {
+                    app.show_mirror_restore_browser(page + 1, window, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    pub(crate) fn render_mirror_backup_status(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let worker = self.mirror_backup.as_ref()?;
+        let failed = matches!(self.mirror_backup_status, MirrorBackupStatus::Failed { .. })
+            || matches!(
+                &self.mirror_backup_status,
+                MirrorBackupStatus::Finished { report, .. } if !report.failed.is_empty()
+            );
+        Some(
+            h_flex()
+                .flex_1()
+                .gap_2()
+                .items_center()
+                .when(failed, |status| status.text_color(cx.theme().danger))
+                .child(
+                    div()
+                        .flex_1()
+                        .child(self.mirror_backup_status.label())
+                        .child(format!(" -> {}", worker.config().mirror_dir.display())),
+                )
+                .child(
+                    Button::new("mirror-backup-now")
+                        .xsmall()
+                        .ghost()
+                        .label(crate::i18n::tr("button.back_up_now"))
+                        .on_click(cx.listener(|this, _, _, cx| this.run_mirror_backup_now(cx))),
+                )
+                .child(
+                    Button::new("mirror-backup-restore")
+                        .xsmall()
+                        .ghost()
+                        .label(crate::i18n::tr("button.restore"))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.show_mirror_restore_browser(0, window, cx)
+                        })),
+                )
+                .into_any_element(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_mirror_backup_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    #[test]
+    fn bak_test1_mirror_copies_only_new_or_changed_notes() {
+        let root = new_temp_root("bak_test1");
+        let vault = root.join("user_document");
+        let day = vault.join("2026").join("10").join("16");
+        fs::create_dir_all(&day).expect("create day");
+        fs::create_dir_all(vault.join("recyclebin")).expect("create recyclebin");
+        fs::write(day.join("a.txt"), "alpha").expect("write a");
+        fs::write(vault.join("recyclebin").join("old.txt"), "old").expect("write old");
+        let target = mirror_target_dir(root.join("mirror").as_path(), vault.as_path());
+
+        let first = mirror_vault(vault.as_path(), target.as_path()).expect("first run");
+        assert_eq!((first.copied, first.unchanged), (1, 0));
+        assert!(!target.join("recyclebin").exists());
+
+        let second = mirror_vault(vault.as_path(), target.as_path()).expect("second run");
+        assert_eq!((second.copied, second.unchanged), (0, 1));
+
+        fs::write(day.join("a.txt"), "alpha, longer").expect("rewrite a");
+        let third = mirror_vault(vault.as_path(), target.as_path()).expect("third run");
+        assert_eq!(third.copied, 1);
+        assert_eq!(
+            fs::read_to_string(target.join("2026/10/16/a.txt")).expect("read mirror"),
+            "alpha, longer"
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn bak_test2_restore_lists_deleted_notes_and_keeps_existing_ones() {
+        let root = new_temp_root("bak_test2");
+        let vault = root.join("user_document");
+        fs::create_dir_all(&vault).expect("create vault");
+        fs::write(vault.join("kept.txt"), "v1").expect("write kept");
+        fs::write(vault.join("gone.txt"), "bye").expect("write gone");
+        let target = root.join("mirror");
+        mirror_vault(vault.as_path(), target.as_path()).expect("mirror");
+        assert!(
+            list_restorable_files(vault.as_path(), target.as_path())
+                .expect("list")
+                .is_empty()
+        );
+
+        fs::remove_file(vault.join("gone.txt")).expect("delete gone");
+        fs::write(vault.join("kept.txt"), "v2 edited").expect("edit kept");
+        let entries = list_restorable_files(vault.as_path(), target.as_path()).expect("list");
+        assert_eq!(entries.len(), 2);
+        let gone = entries
+            .iter()
+            .find(|entry| entry.relative == Path::new("gone.txt"))
+            .expect("gone listed");
+        assert!(gone.missing_in_vault);
+
+        let restored =
+            restore_from_mirror(vault.as_path(), target.as_path(), Path::new("kept.txt"))
+                .expect("restore kept");
+        assert_eq!(restored, vault.join("kept (restored).txt"));
+        assert_eq!(fs::read_to_string(restored).expect("read"), "v1");
+        assert_eq!(
+            fs::read_to_string(vault.join("kept.txt")).expect("read kept"),
+            "v2 edited"
+        );
+        assert_eq!(
+            restore_from_mirror(vault.as_path(), target.as_path(), Path::new("gone.txt"))
+                .expect("restore gone"),
+            vault.join("gone.txt")
+        );
+
+        let conf = root.join("papyru2_conf.toml");
+        fs::write(&conf, "[backup]\nmirror_dir = \"/mnt/nas\"\n").expect("write conf");
+        assert_eq!(
+            load_mirror_backup_config(conf.as_path()),
+            Some(MirrorBackupConfig {
+                mirror_dir: PathBuf::from("/mnt/nas"),
+                interval: Duration::from_secs(3600),
+            })
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn bak_test3_mirror_inside_the_vault_and_symlinks_are_not_copied() {
+        let root = new_temp_root("bak_test3");
+        let vault = root.join("a").join("notes");
+        let other = root.join("b").join("notes");
+        fs::create_dir_all(&vault).expect("create vault");
+        fs::write(vault.join("note.txt"), "n").expect("write note");
+        assert_ne!(
+            mirror_target_dir(vault.as_path(), vault.as_path()),
+            mirror_target_dir(vault.as_path(), other.as_path())
+        );
+
+        let target = mirror_target_dir(vault.join("mirror").as_path(), vault.as_path());
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&vault, vault.join("loop")).expect("link loop");
+        let first = mirror_vault(vault.as_path(), target.as_path()).expect("first run");
+        assert_eq!(first.copied, 1);
+        let second = mirror_vault(vault.as_path(), target.as_path()).expect("second run");
+        assert_eq!((second.copied, second.unchanged), (0, 1));
+        assert!(!target.join("mirror").exists());
+        assert!(!target.join("loop").exists());
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,293 @@
+pub(crate) const FRONT_MATTER_FENCE: &str = "---";
+pub(crate) const FRONT_MATTER_WRAP_COLUMN_KEY: &str = "wrap_column";
+pub(crate) const FRONT_MATTER_READONLY_KEY: &str = "readonly";
+pub(crate) const FRONT_MATTER_LANGUAGE_KEY: &str = "language";
+pub(crate) const FRONT_MATTER_TYPEWRITER_KEY: &str = "typewriter";
+// Set after an export to the time it was published; cleared only by an explicit unlock.
+pub(crate) const FRONT_MATTER_PUBLISH_LOCK_KEY: &str = "publish_lock";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct NoteLayoutPrefs {
+    pub wrap_column: Option<u32>,
+    pub readonly: Option<bool>,
+    pub language: Option<String>,
+    pub typewriter: Option<bool>,
+    pub publish_lock: Option<String>,
+}
+
+impl NoteLayoutPrefs {
+    /// A publish-locked note is readonly as well, whatever its `readonly` key says.
+    pub fn is_readonly(&self) -> bool {
+        self.readonly.unwrap_or(false) || self.is_publish_locked()
+    }
+
+    pub fn is_publish_locked(&self) -> bool {
+        self.publish_lock.is_some()
+    }
+
+    pub fn is_typewriter(&self) -> bool {
+        self.typewriter.unwrap_or(false)
+    }
+}
+
+struct FrontMatterBlock<'a> {
+    lines: Vec<&'a str>,
+    body_start: usize,
+    line_ending: &'static str,
+}
+
+// A front-matter block is a leading `---` line, `key: value` lines, and a closing `---` line.
+fn split_front_matter(text: &str) -> Option<FrontMatterBlock<'_>> {
+    let line_ending = if text.starts_with("---\r\n") {
+        "\r\n"
+    } else if text.starts_with("---\n") {
+        "\n"
+    } else {
+        return None;
+    };
+
+    let mut offset = FRONT_MATTER_FENCE.len() + line_ending.len();
+    let mut lines = Vec::new();
+    while offset <= text.len() {
+        let rest = &text[offset..];
+        let (line, consumed) = match rest.find('\n') {
+            Some(index) => (&rest[..index], index + 1),
+            None => (rest, rest.len()),
+        };
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.trim_end() == FRONT_MATTER_FENCE {
+            return Some(FrontMatterBlock {
+                lines,
+                body_start: offset + consumed,
+                line_ending,
+            });
+        }
+        if consumed == 0 {
+            return None;
+        }
+        lines.push(line);
+        offset += consumed;
+    }
+    None
+}
+
+fn parse_front_matter_line(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    let value = value.trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+        .unwrap_or(value);
+    Some((key.trim(), value))
+}
+
+fn parse_front_matter_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" => Some(true),
+        "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+pub(crate) fn parse_note_layout_prefs(text: &str) -> NoteLayoutPrefs {
+    let mut prefs = NoteLayoutPrefs::default();
+    let Some(block) = split_front_matter(text) else {
+        return prefs;
+    };
+
+    for line in block.lines {
+        let Some((key, value)) = parse_front_matter_line(line) else {
+            continue;
+        };
+        match key {
+            FRONT_MATTER_WRAP_COLUMN_KEY => {
+                prefs.wrap_column = value.parse::<u32>().ok().filter(|column| *column > 0);
+            }
+            FRONT_MATTER_READONLY_KEY => prefs.readonly = parse_front_matter_bool(value),
+            FRONT_MATTER_LANGUAGE_KEY => {
+                prefs.language = (!value.is_empty()).then(|| value.to_ascii_lowercase());
+            }
+            FRONT_MATTER_TYPEWRITER_KEY => prefs.typewriter = parse_front_matter_bool(value),
+            FRONT_MATTER_PUBLISH_LOCK_KEY => {
+                prefs.publish_lock = (!value.is_empty()
+                    && parse_front_matter_bool(value) != Some(false))
+                .then(|| value.to_string());
+            }
+            _ => {}
+        }
+    }
+    prefs
+}
+
+/// The value of `key` in the note's front-matter block, unquoted.
+pub(crate) fn front_matter_value(text: &str, key: &str) -> Option<String> {
+    split_front_matter(text)?
+        .lines
+        .into_iter()
+        .filter_map(parse_front_matter_line)
+        .find(|(k, _)| *k == key)
+        .map(|(_, value)| value.to_string())
+}
+
+/// The note body after its front-matter block; text without one comes back unchanged.
+pub(crate) fn strip_front_matter(text: &str) -> &str {
+    split_front_matter(text).map_or(text, |block| &text[block.body_start..])
+}
+
+// Rewrites (or inserts) one `key: value` line, leaving other front-matter keys and the note
+// body untouched. A note without front-matter gets a new block prepended.
+pub(crate) fn upsert_front_matter_value(text: &str, key: &str, value: &str) -> String {
+    let new_line = format!("{key}: {value}");
+    let Some(block) = split_front_matter(text) else {
+        return format!("{FRONT_MATTER_FENCE}\n{new_line}\n{FRONT_MATTER_FENCE}\n{text}");
+    };
+
+    let mut replaced = false;
+    let mut lines = block
+        .lines
+        .iter()
+        .map(|line| {
+            if !replaced && parse_front_matter_line(line).is_some_and(|(k, _)| k == key) {
+                replaced = true;
+                new_line.clone()
+            } else {
+                (*line).to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+    if !replaced {
+        lines.push(new_line);
+    }
+
+    let line_ending = block.line_ending;
+    let mut rewritten = String::with_capacity(text.len() + key.len() + value.len() + 4);
+    rewritten.push_str(FRONT_MATTER_FENCE);
+    rewritten.push_str(line_ending);
+    for line in lines {
+        rewritten.push_str(&line);
+        rewritten.push_str(line_ending);
+    }
+    rewritten.push_str(FRONT_MATTER_FENCE);
+    rewritten.push_str(line_ending);
+    rewritten.push_str(&text[block.body_start..]);
+    rewritten
+}
+
+// Drops one key; a block left empty is removed so a lock added to a plain note leaves no
+// trace once unlocked.
+pub(crate) fn remove_front_matter_value(text: &str, key: &str) -> String {
+    let Some(block) = split_front_matter(text) else {
+        return text.to_string();
+    };
+    let lines = block
+        .lines
+        .iter()
+        .filter(|line| parse_front_matter_line(line).is_none_or(|(k, _)| k != key))
+        .collect::<Vec<_>>();
+    if lines.len() == block.lines.len() {
+        return text.to_string();
+    }
+    if lines.is_empty() {
+        return text[block.body_start..].to_string();
+    }
+    let line_ending = block.line_ending;
+    let mut rewritten = String::with_capacity(text.len());
+    rewritten.push_str(FRONT_MATTER_FENCE);
+    rewritten.push_str(line_ending);
+    for line in lines {
+        rewritten.push_str(line);
+        rewritten.push_str(line_ending);
+    }
+    rewritten.push_str(FRONT_MATTER_FENCE);
+    rewritten.push_str(line_ending);
+    rewritten.push_str(&text[block.body_start..]);
+    rewritten
+}
+
+/// Autosave refuses to change a publish-locked note unless the new text drops the lock
+/// (the explicit unlock); locking a note and rewriting identical text go through.
+pub(crate) fn publish_lock_blocks_save(on_disk: &str, payload: &str) -> bool {
+    on_disk != payload
+        && parse_note_layout_prefs(on_disk).is_publish_locked()
+        && parse_note_layout_prefs(payload).is_publish_locked()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fm_test1_parses_layout_prefs_from_front_matter() {
+        let text = "---\nwrap_column: 80\nreadonly: true\nlanguage: \"Markdown\"\ntypewriter: off\ntags: a\n---\nbody\n";
+
+        let prefs = parse_note_layout_prefs(text);
+
+        assert_eq!(prefs.wrap_column, Some(80));
+        assert_eq!(prefs.readonly, Some(true));
+        assert_eq!(prefs.language.as_deref(), Some("markdown"));
+        assert_eq!(prefs.typewriter, Some(false));
+    }
+
+    #[test]
+    fn fm_test2_text_without_closed_front_matter_has_default_prefs() {
+        assert_eq!(
+            parse_note_layout_prefs("readonly: true\n"),
+            NoteLayoutPrefs::default()
+        );
+        assert_eq!(
+            parse_note_layout_prefs("---\nreadonly: true\nno closing fence"),
+            NoteLayoutPrefs::default()
+        );
+        assert_eq!(
+            parse_note_layout_prefs("---\nwrap_column: zero\n---\n").wrap_column,
+            None
+        );
+    }
+
+    #[test]
+    fn fm_test3_upsert_replaces_existing_key_and_keeps_body() {
+        let text = "---\r\nreadonly: false\r\ntags: a\r\n---\r\nbody\r\n";
+
+        let rewritten = upsert_front_matter_value(text, FRONT_MATTER_READONLY_KEY, "true");
+
+        assert_eq!(
+            rewritten,
+            "---\r\nreadonly: true\r\ntags: a\r\n---\r\nbody\r\n"
+        );
+        assert_eq!(parse_note_layout_prefs(&rewritten).readonly, Some(true));
+    }
+
+    #[test]
+    fn fm_test4_upsert_appends_key_or_prepends_block() {
+        let with_block = upsert_front_matter_value("---\ntags: a\n---\nbody", "typewriter", "true");
+        assert_eq!(with_block, "---\ntags: a\ntypewriter: true\n---\nbody");
+
+        let without_block = upsert_front_matter_value("body", "readonly", "true");
+        assert_eq!(without_block, "---\nreadonly: true\n---\nbody");
+        assert!(parse_note_layout_prefs(&without_block).is_readonly());
+    }
+
+    #[test]
+    fn fm_test5_publish_lock_makes_note_readonly_until_removed() {
+        let locked =
+            upsert_front_matter_value("body", FRONT_MATTER_PUBLISH_LOCK_KEY, "2026-10-16 09:30");
+        let prefs = parse_note_layout_prefs(&locked);
+        assert_eq!(prefs.publish_lock.as_deref(), Some("2026-10-16 09:30"));
+        assert!(prefs.is_readonly());
+        assert!(!parse_note_layout_prefs("---\npublish_lock: false\n---\n").is_publish_locked());
+
+        assert!(publish_lock_blocks_save(
+            &locked,
+            &format!("{locked} edited")
+        ));
+        assert!(!publish_lock_blocks_save(&locked, &locked));
+        assert!(!publish_lock_blocks_save("body", &locked));
+        let unlocked = remove_front_matter_value(&locked, FRONT_MATTER_PUBLISH_LOCK_KEY);
+        assert_eq!(unlocked, "body");
+        assert!(!publish_lock_blocks_save(&locked, &unlocked));
+        assert_eq!(
+            remove_front_matter_value("---\ntags: a\npublish_lock: x\n---\nbody", "publish_lock"),
+            "---\ntags: a\n---\nbody"
+        );
+    }
+}
@@ -0,0 +1,620 @@
+use std::{
+    collections::BTreeSet,
+    ops::Range,
+    path::{Component, Path, PathBuf},
+};
+
+use gpui::*;
+use serde::{Deserialize, Serialize};
+
+use crate::file_update_handler::NoteFileExtension;
+
+/// What one note links to: `[[Title]]` names (lowercase, extension dropped) and the notes
+/// its relative markdown links resolve to.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct NoteLinks {
+    pub wikilinks: BTreeSet<String>,
+    pub paths: BTreeSet<PathBuf>,
+}
+
+impl NoteLinks {
+    pub fn is_empty(&self) -> bool {
+        self.wikilinks.is_empty() && self.paths.is_empty()
+    }
+
+    pub fn links_to(&self, path: &Path) -> bool {
+        self.paths.contains(path)
+            || note_title(path).is_some_and(|title| self.wikilinks.contains(&title.to_lowercase()))
+    }
+}
+
+fn note_title(path: &Path) -> Option<String> {
+    NoteFileExtension::from_path(path)?;
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+}
+
+fn wikilink_name(name: &str) -> &str {
+    let name = name.trim();
+    match name.rsplit_once('.') {
+        Some((stem, extension)) if NoteFileExtension::parse(extension).is_some() => stem,
+        _ => name,
+    }
+}
+
+// `[[name]]`, `[[name#heading]]` and `[[name|alias]]`: the range covers just `name`.
+fn wikilink_name_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+    while let Some(open) = text[offset..].find("[[") {
+        let start = offset + open + 2;
+        let Some(close) = text[start..].find("]]") else {
+            break;
+        };
+        let inner = &text[start..start + close];
+        if !inner.contains('\n') {
+            let end = inner.find(['|', '#']).unwrap_or(inner.len());
+            let leading = inner.len() - inner.trim_start().len();
+            let trailing = inner[..end].len() - inner[..end].trim_end().len();
+            if leading + trailing < end {
+                ranges.push(start + leading..start + end - trailing);
+            }
+        }
+        offset = start + close + 2;
+    }
+    ranges
+}
+
+// `[text](dest)` and `[text](<dest with spaces>)`: the range covers `dest` without the
+// brackets, any `#fragment` or title.
+fn markdown_link_dest_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+    while let Some(found) = text[offset..].find("](") {
+        let start = offset + found + 2;
+        let rest = &text[start..];
+        let (dest_start, dest_len) = if let Some(inner) = rest.strip_prefix('<') {
+            match inner.find(['>', '\n']) {
+                Some(end) if inner[end..].starts_with('>') => (start + 1, end),
+                _ => {
+                    offset = start;
+                    continue;
+                }
+            }
+        } else {
+            (
+                start,
+                rest.find([')', ' ', '\t', '\n']).unwrap_or(rest.len()),
+            )
+        };
+        let dest = &text[dest_start..dest_start + dest_len];
+        let path_len = dest.find('#').unwrap_or(dest.len());
+        if path_len > 0 {
+            ranges.push(dest_start..dest_start + path_len);
+        }
+        offset = dest_start + dest_len;
+    }
+    ranges
+}
+
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+fn resolve_link_dest(note_dir: &Path, dest: &str) -> Option<PathBuf> {
+    if dest.contains("://") || dest.starts_with('/') || dest.starts_with("mailto:") {
+        return None;
+    }
+    let path = normalize_lexically(&note_dir.join(dest.replace("%20", " ")));
+    NoteFileExtension::from_path(path.as_path()).map(|_| path)
+}
+
+/// `target` relative to `from_dir`, with `/` separators and spaces as `%20`.
+fn relative_link_dest(from_dir: &Path, target: &Path) -> String {
+    let from = from_dir.components().collect::<Vec<_>>();
+    let to = target.components().collect::<Vec<_>>();
+    let common = from
+        .iter()
+        .zip(&to)
+        .take_while(|(left, right)| left == right)
+        .count();
+    std::iter::repeat_n("..".to_string(), from.len() - common)
+        .chain(
+            to[common..]
+                .iter()
+                .map(|component| component.as_os_str().to_string_lossy().replace(' ', "%20")),
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub(crate) fn parse_note_links(note_path: &Path, text: &str) -> NoteLinks {
+    let note_dir = note_path.parent().unwrap_or(Path::new(""));
+    NoteLinks {
+        wikilinks: wikilink_name_ranges(text)
+            .into_iter()
+            .map(|range| wikilink_name(&text[range]).to_lowercase())
+            .collect(),
+        paths: markdown_link_dest_ranges(text)
+            .into_iter()
+            .filter_map(|range| resolve_link_dest(note_dir, &text[range]))
+            .collect(),
+    }
+}
+
+/// A note that moved, as the link rewrite sees it. `wikilinks` is false when another note
+/// shares the old or new title: `[[Title]]` cannot say which one it means, so it is left.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct LinkMove {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub wikilinks: bool,
+}
+
+fn fold_title(title: &str) -> String {
+    title.to_lowercase()
+}
+
+/// Marks the moves whose `[[Title]]` links are safe to rewrite: no other note among
+/// `note_paths` (every note now in the vault) carries the old or the new title.
+pub(crate) fn link_moves(moves: &[(PathBuf, PathBuf)], note_paths: &[PathBuf]) -> Vec<LinkMove> {
+    let mut titles = std::collections::BTreeMap::<String, usize>::new();
+    for path in note_paths {
+        if let Some(title) = note_title(path) {
+            *titles.entry(fold_title(&title)).or_default() += 1;
+        }
+    }
+    moves
+        .iter()
+        .map(|(from, to)| {
+            let own = note_title(to).map(|title| fold_title(&title));
+            let others = |title: Option<String>| {
+                title.map(|title| fold_title(&title)).map_or(0, |title| {
+                    let count = titles.get(&title).copied().unwrap_or(0);
+                    count - usize::from(own.as_ref() == Some(&title) && count > 0)
+                })
+            };
+            LinkMove {
+                from: from.clone(),
+                to: to.clone(),
+                wikilinks: others(note_title(from)) == 0 && others(note_title(to)) == 0,
+            }
+        })
+        .collect()
+}
+
+/// Points the links in one note at where their targets moved. The note itself may have
+/// moved from `note_from` to `note_to`; its relative links are then re-rooted at the new
+/// folder. Returns the rewritten text and how many links changed.
+pub(crate) fn rewrite_note_links(
+    text: &str,
+    note_from: &Path,
+    note_to: &Path,
+    moves: &[LinkMove],
+) -> (String, usize) {
+    let old_dir = note_from.parent().unwrap_or(Path::new(""));
+    let new_dir = note_to.parent().unwrap_or(Path::new(""));
+    let mut edits = Vec::<(Range<usize>, String)>::new();
+    for range in wikilink_name_ranges(text) {
+        let name = fold_title(wikilink_name(&text[range.clone()]));
+        let renamed = moves
+            .iter()
+            .filter(|link_move| link_move.wikilinks)
+            .find_map(|link_move| {
+                let old_title = note_title(&link_move.from)?;
+                let new_title = note_title(&link_move.to)?;
+                (old_title != new_title && fold_title(&old_title) == name).then_some(new_title)
+            });
+        if let Some(new_title) = renamed {
+            edits.push((range, new_title));
+        }
+    }
+    for range in markdown_link_dest_ranges(text) {
+        let dest = &text[range.clone()];
+        let Some(target) = resolve_link_dest(old_dir, dest) else {
+            continue;
+        };
+        let target = moves
+            .iter()
+            .find(|link_move| link_move.from == target)
+            .map_or(target, |link_move| link_move.to.clone());
+        if resolve_link_dest(new_dir, dest).as_ref() == Some(&target) {
+            continue;
+        }
+        let mut dest = relative_link_dest(new_dir, &target);
+        // Inside `<...>` spaces are written as they are.
+        if text[..range.start].ends_with('<') {
+            dest = dest.replace("%20", " ");
+        }
+        edits.push((range, dest));
+    }
+    edits.sort_by_key(|(range, _)| range.start);
+    let count = edits.len();
+    let mut rewritten = String::with_capacity(text.len());
+    let mut last = 0;
+    for (range, replacement) in edits {
+        rewritten.push_str(&text[last..range.start]);
+        rewritten.push_str(&replacement);
+        last = range.end;
+    }
+    rewritten.push_str(&text[last..]);
+    (rewritten, count)
+}
+
+/// Renames made from the title field since it was last committed (Enter, or focus leaving
+/// the field). Links are offered for rewrite once per commit instead of after every
+/// debounced rename while the title is still being typed.
+#[derive(Debug, Default)]
+pub(crate) struct TitleLinkMoves {
+    moves: Vec<(PathBuf, PathBuf)>,
+    committed: bool,
+}
+
+impl TitleLinkMoves {
+    pub fn editing(&mut self) {
+        self.committed = false;
+    }
+
+    /// Adds a rename; a note renamed again keeps one entry from where it started. Once the
+    /// title is committed, a rename landing late is handed back to be offered right away.
+    pub fn record(&mut self, from: PathBuf, to: PathBuf) -> Vec<(PathBuf, PathBuf)> {
+        if let Some(index) = self
+            .moves
+            .iter()
+            .position(|(_, moved_to)| *moved_to == from)
+        {
+            self.moves[index].1 = to;
+            if self.moves[index].0 == self.moves[index].1 {
+                self.moves.remove(index);
+            }
+        } else if from != to {
+            self.moves.push((from, to));
+        }
+        if self.committed {
+            std::mem::take(&mut self.moves)
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// The renames to offer now. With a rename still on its way (`rename_in_flight`) they
+    /// wait for it, so the user is asked once.
+    pub fn commit(&mut self, rename_in_flight: bool) -> Vec<(PathBuf, PathBuf)> {
+        self.committed = true;
+        if rename_in_flight {
+            Vec::new()
+        } else {
+            std::mem::take(&mut self.moves)
+        }
+    }
+}
+
+impl crate::app::Papyru2App {
+    /// Enter in the title field, or focus leaving it: the renames typed since are offered
+    /// for a link rewrite once.
+    pub(crate) fn commit_title_link_moves(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let rename_in_flight = self.flush_pending_title_rename("title-commit");
+        let moves = self.title_link_moves.commit(rename_in_flight);
+        if !moves.is_empty() {
+            self.offer_link_rewrite(moves, window, cx);
+        }
+    }
+
+    pub(crate) fn record_title_link_move(
+        &mut self,
+        from: PathBuf,
+        to: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let moves = self.title_link_moves.record(from, to);
+        if !moves.is_empty() {
+            self.offer_link_rewrite(moves, window, cx);
+        }
+    }
+
+    /// After a rename or move, offers to point links in other notes at the new location.
+    /// `moves` pairs each old note path with its new one (a folder move lists every note).
+    pub(crate) fn offer_link_rewrite(
+        &mut self,
+        moves: Vec<(PathBuf, PathBuf)>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(tag_index) = self.tag_index.as_ref() else {
+            return;
+        };
+        let moved_to = moves.iter().map(|(_, to)| to).collect::<BTreeSet<_>>();
+        let linking = moves
+            .iter()
+            .flat_map(|(from, _)| tag_index.notes_linking_to(from.as_path()))
+            .filter(|path| !moved_to.contains(path))
+            .collect::<BTreeSet<_>>();
+        // A note moved to another folder takes its relative links along; they are re-rooted.
+        let rerooted = moves
+            .iter()
+            .filter(|(from, to)| {
+                from.parent() != to.parent()
+                    && (tag_index.has_path_links(from) || tag_index.has_path_links(to))
+            })
+            .map(|(_, to)| to.clone())
+            .collect::<Vec<_>>();
+        if linking.is_empty() && rerooted.is_empty() {
+            return;
+        }
+        crate::log::trace_debug(format!(
+            "req-lnk1 link rewrite offer moves={} linking_notes={} rerooted={}",
+            moves.len(),
+            linking.len(),
+            rerooted.len()
+        ));
+        let message = if linking.is_empty() {
+            crate::i18n::tr("prompt.link_rewrite_own").to_string()
+        } else {
+            crate::i18n::tr_with(
+                "prompt.link_rewrite",
+                &[("count", linking.len().to_string().as_str())],
+            )
+        };
+        let paths = linking.into_iter().chain(rerooted).collect::<Vec<_>>();
+        let policy = self.confirmations;
+        let open_at_offer = self.file_workflow.current_edit_path();
+        crate::confirmations::run_confirmed(
+            self,
+            policy,
+            crate::confirmations::ConfirmationKind::LinkRewrite,
+            &message,
+            window,
+            cx,
+            move |app, window, cx| {
+                // A title typed on while the prompt was open renamed the note again; the
+                // links go to where it is now.
+                let current = app.file_workflow.current_edit_path();
+                let moves = moves
+                    .into_iter()
+                    .filter_map(|(from, to)| {
+                        if to.is_file() {
+                            Some((from, to))
+                        } else if open_at_offer.as_ref() == Some(&to) {
+                            current.clone().map(|current| (from, current))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                // Re-rooted notes are listed by where they were moved to; follow them too.
+                let paths = paths
+                    .into_iter()
+                    .filter_map(|path| {
+                        if path.is_file() {
+                            Some(path)
+                        } else if open_at_offer.as_ref() == Some(&path) {
+                            current.clone()
+                        } else {
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                app.apply_link_rewrite(paths, moves, window, cx);
+            },
+        );
+    }
+
+    fn apply_link_rewrite(
+        &mut self,
+        paths: Vec<PathBuf>,
+        moves: Vec<(PathBuf, PathBuf)>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // The open note may be one of the linking notes; its pending edits go first.
+        if !self.flush_editor_content_before_context_switch("req-lnk1-link-rewrite", cx) {
+            self.show_transient_notice(crate::i18n::tr("notice.links_need_save").to_string(), cx);
+            return;
+        }
+        self.queue_split_editor_save(window, cx);
+        let (report_tx, report_rx) = smol::channel::bounded::<
+            crate::workflow_error::WorkflowResult<crate::file_update_handler::BulkReplaceReport>,
+        >(1);
+        let request = crate::file_update_handler::LinkRewriteRequest {
+            user_document_dir: self.app_paths.user_document_dir.clone(),
+            paths,
+            moves,
+        };
+        if let Err(error) = self
+            .file_workflow
+            .rewrite_links_async(request, move |result| {
+                let _ = report_tx.try_send(result);
+            })
+        {
+            self.show_transient_notice(
+                error.notice(crate::i18n::tr("error.update_links_failed")),
+                cx,
+            );
+            return;
+        }
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(result) = report_rx.recv().await else {
+                return;
+            };
+            let _ = this.update_in(cx, move |app, window, cx| {
+                app.complete_link_rewrite(result, window, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn complete_link_rewrite(
+        &mut self,
+        result: crate::workflow_error::WorkflowResult<
+            crate::file_update_handler::BulkReplaceReport,
+        >,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let report = match result {
+            Ok(report) => report,
+            Err(error) => {
+                self.show_transient_notice(
+                    error.notice(crate::i18n::tr("error.update_links_failed")),
+                    cx,
+                );
+                return;
+            }
+        };
+        let rewritten = report
+            .replaced
+            .iter()
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        for path in &rewritten {
+            if let Ok(text) = std::fs::read_to_string(path) {
+                self.record_note_tags(path.as_path(), &text);
+            }
+        }
+        self.file_tree.update(cx, |file_tree, cx| {
+            file_tree.mark_notes_looked_at(&rewritten, cx);
+        });
+        if let Some(current) = self
+            .file_workflow
+            .current_edit_path()
+            .filter(|current| rewritten.contains(current))
+        {
+            self.reopen_note_keeping_caret(current, window, cx);
+        }
+        self.reload_split_editor_if_rewritten(&rewritten, window, cx);
+        let mut message = crate::i18n::tr_with(
+            "notice.links_updated",
+            &[
+                ("links", report.total_replacements().to_string().as_str()),
+                ("notes", rewritten.len().to_string().as_str()),
+            ],
+        );
+        if !report.failed.is_empty() {
+            message.push_str(&crate::i18n::tr_with(
+                "notice.imported_failed",
+                &[("count", report.failed.len().to_string().as_str())],
+            ));
+        }
+        self.show_transient_notice(message, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lnk_test1_parses_wikilinks_and_relative_markdown_links() {
+        let note = Path::new("/vault/2026/10/16/index.md");
+        let text = "See [[Plan]], [[plan.md#Goals|the plan]] and [[ ]].\n[a](../15/old%20notes.md#top) [b](<../15/old notes.md>) [web](https://x.org/a.md) [img](pic.png)";
+
+        let links = parse_note_links(note, text);
+
+        assert_eq!(links.wikilinks, BTreeSet::from(["plan".to_string()]));
+        assert_eq!(
+            links.paths,
+            BTreeSet::from([PathBuf::from("/vault/2026/10/15/old notes.md")])
+        );
+        assert!(links.links_to(Path::new("/vault/elsewhere/Plan.txt")));
+        assert!(!links.links_to(Path::new("/vault/elsewhere/Other.txt")));
+    }
+
+    #[test]
+    fn lnk_test2_rewrite_keeps_aliases_fragments_and_bracket_style() {
+        let note = Path::new("/vault/2026/10/16/index.md");
+        let from = Path::new("/vault/2026/10/15/old notes.md");
+        let to = Path::new("/vault/archive/new notes.md");
+        let text = "[[Old Notes#Goals|alias]] [[other]]\n[a](../15/old%20notes.md#top) [b](<../15/old notes.md>)";
+        let moves = [LinkMove {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+            wikilinks: true,
+        }];
+
+        let (rewritten, count) = rewrite_note_links(text, note, note, &moves);
+
+        assert_eq!(count, 3);
+        assert_eq!(
+            rewritten,
+            "[[new notes#Goals|alias]] [[other]]\n[a](../../../archive/new%20notes.md#top) [b](<../../../archive/new notes.md>)"
+        );
+        assert_eq!(
+            rewrite_note_links("[[other]]", note, note, &moves),
+            ("[[other]]".to_string(), 0)
+        );
+    }
+
+    #[test]
+    fn lnk_test3_ambiguous_titles_stay_and_moved_notes_reroot_their_links() {
+        let from = PathBuf::from("/vault/a/Ärger.md");
+        let to = PathBuf::from("/vault/b/Plan.md");
+        let other = PathBuf::from("/vault/c/ärger.txt");
+        let moves = [(from.clone(), to.clone())];
+
+        let ambiguous = link_moves(&moves, &[to.clone(), other.clone()]);
+        assert!(!ambiguous[0].wikilinks);
+        let clear = link_moves(&moves, std::slice::from_ref(&to));
+        assert!(clear[0].wikilinks);
+
+        // Unicode titles match without regard to case.
+        let (rewritten, count) = rewrite_note_links(
+            "[[ÄRGER]]",
+            Path::new("/vault/index.md"),
+            Path::new("/vault/index.md"),
+            &clear,
+        );
+        assert_eq!((rewritten.as_str(), count), ("[[Plan]]", 1));
+        let (rewritten, count) = rewrite_note_links(
+            "[[ÄRGER]]",
+            Path::new("/vault/index.md"),
+            Path::new("/vault/index.md"),
+            &ambiguous,
+        );
+        assert_eq!((rewritten.as_str(), count), ("[[ÄRGER]]", 0));
+
+        // The moved note's own links follow it to the new folder, its self link included.
+        let (rewritten, count) = rewrite_note_links(
+            "[x](x.md) [up](../top.md) [me](<Ärger.md>)",
+            from.as_path(),
+            to.as_path(),
+            &clear,
+        );
+        assert_eq!(count, 2);
+        assert_eq!(rewritten, "[x](../a/x.md) [up](../top.md) [me](<Plan.md>)");
+    }
+
+    #[test]
+    fn lnk_test4_title_renames_are_offered_once_per_commit() {
+        let mut moves = TitleLinkMoves::default();
+        let a = PathBuf::from("/vault/a.md");
+        let ab = PathBuf::from("/vault/ab.md");
+        let abc = PathBuf::from("/vault/abc.md");
+
+        moves.editing();
+        assert!(moves.record(a.clone(), ab.clone()).is_empty());
+        assert!(moves.record(ab.clone(), abc.clone()).is_empty());
+        assert!(moves.commit(true).is_empty());
+        // The rename flushed by the commit lands and brings the whole chain along.
+        assert_eq!(
+            moves.record(abc.clone(), ab.clone()),
+            vec![(a.clone(), ab.clone())]
+        );
+        assert!(moves.commit(false).is_empty());
+
+        moves.editing();
+        assert!(moves.record(ab.clone(), a.clone()).is_empty());
+        assert!(moves.record(a, ab).is_empty());
+        assert!(moves.commit(false).is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+use std::{
+    fs,
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+    time::SystemTime,
+};
+
+use chrono::{DateTime, Local};
+
+use crate::log::trace_debug;
+use crate::note_front_matter::{
+    front_matter_value, remove_front_matter_value, upsert_front_matter_value,
+};
+
+pub(crate) const FRONT_MATTER_CREATED_KEY: &str = "created";
+pub(crate) const FRONT_MATTER_MODIFIED_KEY: &str = "modified";
+pub(crate) const FRONT_MATTER_TAGS_KEY: &str = "tags";
+/// The keys autosave owns; everything else in the block is the user's.
+pub(crate) const NOTE_METADATA_KEYS: [&str; 3] = [
+    FRONT_MATTER_CREATED_KEY,
+    FRONT_MATTER_MODIFIED_KEY,
+    FRONT_MATTER_TAGS_KEY,
+];
+const NOTE_METADATA_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%:z";
+
+// Read by the autosave worker, which has no app state to ask.
+static NOTE_METADATA_ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+pub(crate) struct NoteMetadataConfig {
+    /// Keep `created`, `modified` and `tags` in each note's front matter.
+    #[serde(default)]
+    pub front_matter: bool,
+    /// Show notes without those keys; autosave puts them back.
+    #[serde(default)]
+    pub hide_in_editor: bool,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct NoteMetadataConfigFile {
+    #[serde(default)]
+    metadata: NoteMetadataConfig,
+}
+
+pub(crate) fn load_note_metadata_config(path: &Path) -> NoteMetadataConfig {
+    let config = fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| {
+            toml::from_str::<NoteMetadataConfigFile>(&raw)
+                .map_err(|error| {
+                    trace_debug(format!("req-meta1 config parse failed error={error}"));
+                })
+                .ok()
+        })
+        .map(|parsed| parsed.metadata)
+        .unwrap_or_default();
+    trace_debug(format!("req-meta1 config {config:?}"));
+    config
+}
+
+pub(crate) fn set_note_metadata_enabled(enabled: bool) {
+    NOTE_METADATA_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn note_metadata_enabled() -> bool {
+    NOTE_METADATA_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Only Markdown notes carry front matter; a `.txt` note is left exactly as typed.
+pub(crate) fn stamps_note(path: &Path) -> bool {
+    note_metadata_enabled()
+        && crate::file_update_handler::NoteFileExtension::from_path(path)
+            == Some(crate::file_update_handler::NoteFileExtension::Md)
+}
+
+/// The note without the managed keys; a block holding nothing else goes with them.
+pub(crate) fn strip_note_metadata(text: &str) -> String {
+    NOTE_METADATA_KEYS
+        .iter()
+        .fold(text.to_string(), |text, key| {
+            remove_front_matter_value(&text, key)
+        })
+}
+
+/// Brings the managed keys up to date. `created` survives from the text, else it is the note's
+/// recorded creation time; `tags` lists the body's `#tags` and is dropped when none.
+pub(crate) fn stamp_note_metadata(
+    text: &str,
+    created_at: Option<SystemTime>,
+    now: DateTime<Local>,
+) -> String {
+    let created = front_matter_value(text, FRONT_MATTER_CREATED_KEY).unwrap_or_else(|| {
+        created_at
+            .map(DateTime::<Local>::from)
+            .unwrap_or(now)
+            .format(NOTE_METADATA_TIME_FORMAT)
+            .to_string()
+    });
+    let tags = crate::tag_index::parse_tags(crate::note_front_matter::strip_front_matter(text))
+        .into_iter()
+        .collect::<Vec<_>>()
+        .join(", ");
+    let text = upsert_front_matter_value(text, FRONT_MATTER_CREATED_KEY, &created);
+    let text = upsert_front_matter_value(
+        &text,
+        FRONT_MATTER_MODIFIED_KEY,
+        &now.format(NOTE_METADATA_TIME_FORMAT).to_string(),
+    );
+    if tags.is_empty() {
+        remove_front_matter_value(&text, FRONT_MATTER_TAGS_KEY)
+    } else {
+        upsert_front_matter_value(&text, FRONT_MATTER_TAGS_KEY, &tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn meta_test1_stamp_keeps_created_and_strip_hides_managed_keys() {
+        let now = Local.with_ymd_and_hms(2026, 3, 1, 9, 30, 0).unwrap();
+        let stamped = stamp_note_metadata("Plan #idea #todo\n", None, now);
+        let stamp = now.format(NOTE_METADATA_TIME_FORMAT).to_string();
+        assert_eq!(
+            stamped,
+            format!(
+                "---\ncreated: {stamp}\nmodified: {stamp}\ntags: idea, todo\n---\nPlan #idea #todo\n"
+            )
+        );
+        assert_eq!(strip_note_metadata(&stamped), "Plan #idea #todo\n");
+
+        // Hidden in the editor: the buffer has no block, `created` is the file's.
+        let later = Local.with_ymd_and_hms(2026, 3, 2, 8, 0, 0).unwrap();
+        let restamped = stamp_note_metadata(
+            "---\nreadonly: true\n---\nPlan\n",
+            Some(SystemTime::from(now)),
+            later,
+        );
+        assert_eq!(
+            front_matter_value(&restamped, FRONT_MATTER_CREATED_KEY),
+            Some(stamp)
+        );
+        assert_eq!(front_matter_value(&restamped, FRONT_MATTER_TAGS_KEY), None);
+        assert_eq!(
+            strip_note_metadata(&restamped),
+            "---\nreadonly: true\n---\nPlan\n"
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use gpui::*;
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+
+pub(crate) const NOTE_TEMPLATES_DIR_NAME: &str = "templates";
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct NoteTemplateConfigFile {
+    #[serde(default)]
+    templates: NoteTemplateConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct NoteTemplateConfigSection {
+    default: Option<String>,
+}
+
+/// `[templates] default = "meeting.md"` names a file in `conf/templates/` used for every new
+/// note.
+pub(crate) fn load_default_note_template_name(path: &Path) -> Option<String> {
+    let raw = fs::read_to_string(path).ok()?;
+    match toml::from_str::<NoteTemplateConfigFile>(&raw) {
+        Ok(parsed) => parsed
+            .templates
+            .default
+            .filter(|name| !name.trim().is_empty()),
+        Err(error) => {
+            trace_debug(format!(
+                "req-tpl1 config parse failed path={} error={error}",
+                path.display()
+            ));
+            None
+        }
+    }
+}
+
+/// The txt/md files directly inside `templates_dir`, by file name.
+pub(crate) fn list_note_templates(templates_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut templates = match fs::read_dir(templates_dir) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter(|path| {
+                crate::file_update_handler::NoteFileExtension::from_path(path.as_path()).is_some()
+            })
+            .collect::<Vec<_>>(),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(error) => return Err(error),
+    };
+    templates.sort();
+    Ok(templates)
+}
+
+/// Fills `{{date}}`, `{{time}}` and `{{datetime}}`. There is no title placeholder: a note
+/// typed into the title field is created on its first keystroke, long before the title is done.
+pub(crate) fn render_note_template(template: &str, now: DateTime<Local>) -> String {
+    template
+        .replace("{{date}}", &now.format("%Y-%m-%d").to_string())
+        .replace("{{time}}", &now.format("%H:%M").to_string())
+        .replace(
+            "{{datetime}}",
+            &now.format("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+        )
+}
+
+/// Which template the next created note gets: a one-off pick from the Plus button wins over
+/// the configured default.
+#[derive(Debug, Default)]
+pub(crate) struct NoteTemplateChoice {
+    default: Option<PathBuf>,
+    next: Option<Option<PathBuf>>,
+}
+
+impl NoteTemplateChoice {
+    pub fn new(default: Option<PathBuf>) -> Self {
+        Self {
+            default,
+            next: None,
+        }
+    }
+
+    pub fn choose_for_next(&mut self, template: Option<PathBuf>) {
+        self.next = Some(template);
+    }
+
+    pub fn take_for_create(&mut self) -> Option<PathBuf> {
+        self.next.take().unwrap_or_else(|| self.default.clone())
+    }
+}
+
+impl crate::app::Papyru2App {
+    /// The template body for a note about to be created, or `None` when the editor already
+    /// holds text that the new note should keep.
+    pub(crate) fn take_note_template_for_create(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Option<String> {
+        let template = self.note_templates.take_for_create()?;
+        if !self.editor.read(cx).snapshot(cx).value.is_empty() {
+            return None;
+        }
+        match fs::read_to_string(&template) {
+            Ok(body) => Some(body),
+            Err(error) => {
+                trace_debug(format!(
+                    "req-tpl1 template read failed path={} error={error}",
+                    template.display()
+                ));
+                self.show_transient_notice(
+                    tr_with(
+                        "notice.template_unreadable",
+                        &[
+                            ("path", template.display().to_string().as_str()),
+                            ("reason", error.to_string().as_str()),
+                        ],
+                    ),
+                    cx,
+                );
+                None
+            }
+        }
+    }
+
+    pub(crate) fn show_note_template_picker(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let templates_dir = self.app_paths.config_file_path(NOTE_TEMPLATES_DIR_NAME);
+        let templates = match list_note_templates(templates_dir.as_path()) {
+            Ok(templates) => templates,
+            Err(error) => {
+                self.show_transient_notice(
+                    tr_with(
+                        "notice.cannot_list_templates",
+                        &[("reason", error.to_string().as_str())],
+                    ),
+                    cx,
+                );
+                return;
+            }
+        };
+        if templates.is_empty() {
+            self.show_transient_notice(
+                tr_with(
+                    "notice.no_templates",
+                    &[("path", templates_dir.display().to_string().as_str())],
+                ),
+                cx,
+            );
+            return;
+        }
+        let mut labels = templates
+            .iter()
+            .map(|path| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        labels.push(tr("button.blank_note").to_string());
+        labels.push(tr("button.cancel").to_string());
+        let label_refs = labels.iter().map(String::as_str).collect::<Vec<_>>();
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &tr("prompt.note_from_template"),
+            None,
+            &label_refs,
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(choice) = answer.await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                let template = match choice {
+                    index if index < templates.len() => Some(templates[index].clone()),
+                    index if index == templates.len() => None,
+                    _ => return,
+                };
+                trace_debug(format!(
+                    "req-tpl1 next note template={}",
+                    template
+                        .as_ref()
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_else(|| "<blank>".to_string())
+                ));
+                app.note_templates.choose_for_next(template);
+                app.handle_plus_button(window, cx);
+            });
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn tpl_test1_render_fills_dates() {
+        let now = Local.with_ymd_and_hms(2026, 3, 7, 9, 5, 0).unwrap();
+        let rendered = render_note_template("---\ndate: {{date}}\n---\n{{time}} {{title}}\n", now);
+        assert_eq!(rendered, "---\ndate: 2026-03-07\n---\n09:05 {{title}}\n");
+    }
+
+    #[test]
+    fn tpl_test2_one_off_choice_overrides_default_once() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_note_templates_tpl_test2_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        let templates_dir = root.join(NOTE_TEMPLATES_DIR_NAME);
+        fs::create_dir_all(&templates_dir).expect("create templates dir");
+        fs::write(templates_dir.join("meeting.md"), "# {{date}}").expect("write meeting");
+        fs::write(templates_dir.join("journal.txt"), "{{date}}").expect("write journal");
+        fs::write(templates_dir.join("logo.png"), "x").expect("write png");
+        assert_eq!(
+            list_note_templates(templates_dir.as_path()).expect("list"),
+            vec![
+                templates_dir.join("journal.txt"),
+                templates_dir.join("meeting.md")
+            ]
+        );
+
+        let conf = root.join("papyru2_conf.toml");
+        fs::write(&conf, "[templates]\ndefault = \"meeting.md\"\n").expect("write conf");
+        let default =
+            load_default_note_template_name(conf.as_path()).map(|name| templates_dir.join(name));
+        let mut choice = NoteTemplateChoice::new(default.clone());
+        choice.choose_for_next(None);
+        assert_eq!(choice.take_for_create(), None);
+        assert_eq!(choice.take_for_create(), default);
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,255 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use gpui::*;
+
+use crate::log::trace_debug;
+
+// Longer first lines are cut here; `[filenames] max_stem_chars` still applies after.
+const AUTO_TITLE_MAX_CHARS: usize = 80;
+
+/// `[titles] mode`: where a note's title comes from besides the title field itself.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum TitleMode {
+    /// The title field alone names the note.
+    #[default]
+    Manual,
+    /// An untitled note is named after its first non-empty line once typing pauses.
+    FirstLine,
+    /// The title field and the first line are one text; see `sl_editor_association`.
+    Mirror,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct TitlesConfigFile {
+    #[serde(default)]
+    titles: TitlesConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct TitlesConfigSection {
+    #[serde(default)]
+    mode: TitleMode,
+}
+
+pub(crate) fn load_title_mode(path: &Path) -> TitleMode {
+    let mode = fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| {
+            toml::from_str::<TitlesConfigFile>(&raw)
+                .map_err(|error| {
+                    trace_debug(format!("req-ttl1 config parse failed error={error}"));
+                })
+                .ok()
+        })
+        .map(|parsed| parsed.titles.mode)
+        .unwrap_or_default();
+    trace_debug(format!("req-ttl1 title mode={mode:?}"));
+    mode
+}
+
+/// The first non-empty body line without Markdown heading marks; front matter is skipped.
+pub(crate) fn auto_title_from_text(text: &str) -> Option<String> {
+    let line = crate::note_front_matter::strip_front_matter(text)
+        .lines()
+        .map(|line| line.trim_start_matches('#').trim())
+        .find(|line| !line.is_empty())?;
+    Some(
+        line.chars()
+            .take(AUTO_TITLE_MAX_CHARS)
+            .collect::<String>()
+            .trim_end()
+            .to_string(),
+    )
+}
+
+/// A note named after its first line under `TitleMode::FirstLine`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct AutoTitle {
+    pub path: PathBuf,
+    /// What the title field showed when the rename was queued: `""` while untitled, then
+    /// the stem the last rename produced.
+    pub shown: String,
+    /// The sanitized first line the note was last renamed after. A `_2` stem never equals
+    /// the first line, so this is what tells an unchanged first line apart.
+    pub source_stem: String,
+}
+
+/// The note keeps following its first line while the title field still shows the title
+/// taken from it; typing a title of one's own ends that.
+pub(crate) fn follows_first_line(
+    path: &Path,
+    singleline_value: &str,
+    auto_title: Option<&AutoTitle>,
+) -> bool {
+    let untitled = singleline_value.is_empty()
+        && path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem.starts_with("notitle-"));
+    untitled || auto_title.is_some_and(|auto| auto.path == path && auto.shown == singleline_value)
+}
+
+/// The stem a rename to `title` would ask for, or `None` when the note already has it.
+pub(crate) fn auto_title_rename_stem(
+    title: &str,
+    singleline_value: &str,
+    auto_title: Option<&AutoTitle>,
+) -> Option<String> {
+    let stem = crate::file_update_handler::sanitize_filename_stem(title);
+    let unchanged = stem.is_empty()
+        || stem == crate::file_update_handler::sanitize_filename_stem(singleline_value)
+        || auto_title.is_some_and(|auto| auto.source_stem == stem);
+    (!unchanged).then_some(stem)
+}
+
+impl crate::app::Papyru2App {
+    /// Queues a rename to the first line through the same debouncer the title field uses.
+    pub(crate) fn schedule_auto_title(&mut self, text: &str, cx: &mut Context<Self>) {
+        if self.title_mode != TitleMode::FirstLine
+            || self.file_workflow.state() != crate::file_update_handler::SinglelineFileState::Edit
+        {
+            return;
+        }
+        let Some(path) = self.file_workflow.current_edit_path() else {
+            return;
+        };
+        if !crate::file_update_handler::is_path_in_vault(
+            path.as_path(),
+            self.app_paths.user_document_dir.as_path(),
+        ) {
+            return;
+        }
+        let singleline_value = self.singleline.read(cx).snapshot(cx).value;
+        if !follows_first_line(path.as_path(), &singleline_value, self.auto_title.as_ref()) {
+            return;
+        }
+        let Some(title) = auto_title_from_text(text) else {
+            return;
+        };
+        let Some(source_stem) =
+            auto_title_rename_stem(&title, &singleline_value, self.auto_title.as_ref())
+        else {
+            return;
+        };
+        trace_debug(format!(
+            "req-ttl1 auto title path={} title='{}'",
+            path.display(),
+            crate::app::compact_text(&title)
+        ));
+        self.auto_title = Some(AutoTitle {
+            path: path.clone(),
+            shown: singleline_value,
+            source_stem,
+        });
+        // Renames that follow the first line wait for the next title commit like typed ones.
+        self.title_link_moves.editing();
+        self.title_rename_debouncer.mark_title_edit(
+            crate::file_update_handler::PendingTitleRename {
+                path,
+                singleline_value: title,
+                user_document_dir: self.app_paths.user_document_dir.clone(),
+            },
+            std::time::Instant::now(),
+        );
+    }
+
+    /// After the rename lands the title field shows the new name, and stays tied to the
+    /// first line as long as nobody edits it. A title typed while the rename was in flight
+    /// wins: the field is left alone and the note stops following its first line.
+    pub(crate) fn complete_auto_title_rename(
+        &mut self,
+        value: &str,
+        path: &Path,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(auto) = self.auto_title.as_ref().filter(|auto| {
+            auto.source_stem == crate::file_update_handler::sanitize_filename_stem(value)
+        }) else {
+            return false;
+        };
+        let singleline_value = self.singleline.read(cx).snapshot(cx).value;
+        if singleline_value != auto.shown {
+            trace_debug(format!(
+                "req-ttl1 auto title kept typed title path={} title='{}'",
+                path.display(),
+                crate::app::compact_text(&singleline_value)
+            ));
+            self.auto_title = None;
+            return true;
+        }
+        let Some((stem, cursor_char)) = crate::singleline_input::singleline_title_after_open(path)
+        else {
+            return false;
+        };
+        self.singleline.update(cx, |singleline, cx| {
+            singleline.apply_text_and_cursor(stem.clone(), cursor_char, window, cx);
+        });
+        let source_stem = auto.source_stem.clone();
+        self.auto_title = Some(AutoTitle {
+            path: path.to_path_buf(),
+            shown: stem,
+            source_stem,
+        });
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ttl_test1_first_line_titles_untitled_notes_until_the_title_is_edited() {
+        assert_eq!(
+            auto_title_from_text("---\nreadonly: true\n---\n\n## Weekly plan  \nbody"),
+            Some("Weekly plan".to_string())
+        );
+        assert_eq!(auto_title_from_text("\n  \n#\n"), None);
+        assert_eq!(
+            auto_title_from_text(&"x".repeat(200)).map(|title| title.len()),
+            Some(AUTO_TITLE_MAX_CHARS)
+        );
+
+        let untitled = Path::new("/vault/2026/03/01/notitle-20260301093000000.md");
+        assert!(follows_first_line(untitled, "", None));
+        assert!(!follows_first_line(untitled, "My own title", None));
+
+        let renamed = PathBuf::from("/vault/2026/03/01/Weekly plan.md");
+        let auto = AutoTitle {
+            path: renamed.clone(),
+            shown: "Weekly plan".to_string(),
+            source_stem: "Weekly plan".to_string(),
+        };
+        assert!(follows_first_line(&renamed, "Weekly plan", Some(&auto)));
+        assert!(!follows_first_line(&renamed, "Weekly plans", Some(&auto)));
+        assert!(!follows_first_line(&renamed, "", None));
+    }
+
+    #[test]
+    fn ttl_test2_collision_suffix_and_sanitizing_do_not_requeue_the_rename() {
+        let taken = AutoTitle {
+            path: PathBuf::from("/vault/2026/03/01/Weekly plan_2.md"),
+            shown: "Weekly plan_2".to_string(),
+            source_stem: "Weekly plan".to_string(),
+        };
+        assert_eq!(
+            auto_title_rename_stem("Weekly plan", "Weekly plan_2", Some(&taken)),
+            None
+        );
+        assert_eq!(
+            auto_title_rename_stem("Weekly plans", "Weekly plan_2", Some(&taken)),
+            Some("Weekly plans".to_string())
+        );
+        let sanitized = crate::file_update_handler::sanitize_filename_stem("a:b");
+        assert_eq!(auto_title_rename_stem("a:b", &sanitized, None), None);
+        assert_eq!(
+            auto_title_rename_stem("Plan", "", None),
+            Some("Plan".to_string())
+        );
+    }
+}
@@ -0,0 +1,878 @@
+use crate::path_resolver;
+use anyhow::{Context, Result, bail};
+use base64::Engine as _;
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+pub const BINARY_NAME: &str = "papyru2_notes_import";
+pub const ASSETS_DIR_NAME: &str = "assets";
+
+const ENEX_DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const INVALID_STEM_CHARS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+const MAX_STEM_CHARS: usize = 120;
+
+// Attachment links depend on the note's final file name, so the body carries a marker per
+// attachment until the note is written.
+const ATTACHMENT_MARKER_START: char = '\u{E000}';
+const ATTACHMENT_MARKER_END: char = '\u{E001}';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotesImportFormat {
+    /// Evernote `.enex` export.
+    Enex,
+    /// `.opml` outline: one note per top-level item, its children as a nested list.
+    Opml,
+}
+
+impl NotesImportFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "enex" => Some(Self::Enex),
+            "opml" => Some(Self::Opml),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Enex => "Evernote export",
+            Self::Opml => "OPML outline",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedAttachment {
+    pub file_name: String,
+    pub bytes: Vec<u8>,
+    pub is_image: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedNote {
+    pub title: String,
+    pub created: Option<DateTime<Local>>,
+    /// Markdown; attachments are referenced by marker until the note is written.
+    pub body: String,
+    pub attachments: Vec<ImportedAttachment>,
+}
+
+/// Where an imported note goes. The app follows its storage layout and filename rules;
+/// the CLI uses the `YYYY/MM/DD` folders the text file importer uses.
+pub trait NotePlacement {
+    /// Attachments go into `assets` here and are linked from the vault root.
+    fn vault_root(&self) -> &Path;
+    fn directory(&self, created: DateTime<Local>) -> PathBuf;
+    fn stem(&self, title: &str, created: DateTime<Local>) -> String;
+
+    /// Called for each note and attachment file of a note once all of them are written.
+    fn written(&self, _path: &Path) {}
+}
+
+#[derive(Debug, Clone)]
+pub struct DailyNotePlacement {
+    pub user_document_dir: PathBuf,
+}
+
+impl NotePlacement for DailyNotePlacement {
+    fn vault_root(&self) -> &Path {
+        &self.user_document_dir
+    }
+
+    fn directory(&self, created: DateTime<Local>) -> PathBuf {
+        self.user_document_dir
+            .join(created.format("%Y/%m/%d").to_string())
+    }
+
+    fn stem(&self, title: &str, created: DateTime<Local>) -> String {
+        plain_note_stem(title, created)
+    }
+}
+
+/// A file name stem safe on every platform; an empty title becomes `notitle-<time>`.
+pub fn plain_note_stem(title: &str, created: DateTime<Local>) -> String {
+    let stem = title
+        .chars()
+        .map(|ch| {
+            if INVALID_STEM_CHARS.contains(&ch) || ch.is_control() {
+                '_'
+            } else {
+                ch
+            }
+        })
+        .take(MAX_STEM_CHARS)
+        .collect::<String>();
+    let stem = stem.trim().trim_end_matches('.').to_string();
+    if stem.is_empty() {
+        format!("notitle-{}", created.format("%Y%m%d-%H%M%S"))
+    } else {
+        stem
+    }
+}
+
+/// Escapes one path segment of a markdown link target. Everything but ASCII letters, digits
+/// and `-._~` is percent-encoded, so `#`, `%`, spaces and parentheses survive the link.
+pub fn percent_encode_link_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Reverses [`percent_encode_link_segment`]; a `%` not followed by two hex digits is kept.
+pub fn percent_decode_link(target: &str) -> String {
+    let bytes = target.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| bytes.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotesImportSummary {
+    pub notes: Vec<PathBuf>,
+    pub attachments: usize,
+    pub failed: Vec<(String, String)>,
+}
+
+impl NotesImportSummary {
+    pub fn summary(&self) -> String {
+        let notes = self.notes.len().to_string();
+        let attachments = self.attachments.to_string();
+        let mut summary = crate::i18n::tr_with(
+            "notice.imported_notes_with_attachments",
+            &[
+                ("count", notes.as_str()),
+                ("attachments", attachments.as_str()),
+            ],
+        );
+        if !self.failed.is_empty() {
+            summary.push_str(&crate::i18n::tr_with(
+                "notice.imported_failed",
+                &[("count", self.failed.len().to_string().as_str())],
+            ));
+        }
+        summary
+    }
+}
+
+fn parse_xml(text: &str) -> Result<roxmltree::Document<'_>> {
+    roxmltree::Document::parse_with_options(
+        text,
+        roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..roxmltree::ParsingOptions::default()
+        },
+    )
+    .context("not a well-formed XML file")
+}
+
+fn child_text<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.children()
+        .find(|child| child.has_tag_name(name))
+        .and_then(|child| child.text())
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let number = entity.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+fn push_decoded_text(out: &mut String, text: &str) {
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| Some((decode_entity(&rest[1..end])?, end)));
+        match decoded {
+            Some((ch, end)) => {
+                out.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+}
+
+fn ensure_line_start(out: &mut String) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+fn tag_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!("{name}=\""))? + name.len() + 2;
+    let end = tag[start..].find('"')? + start;
+    Some(&tag[start..end])
+}
+
+/// Evernote's note markup (XHTML inside `<en-note>`) as markdown. `<en-media>` elements
+/// become attachment markers in document order, which is also the order of the note's
+/// `<resource>` elements in every export seen so far.
+pub fn enml_to_markdown(enml: &str) -> String {
+    let mut out = String::new();
+    let mut links: Vec<String> = Vec::new();
+    let mut media_index = 0usize;
+    let mut rest = enml;
+    while let Some(start) = rest.find('<') {
+        push_decoded_text(&mut out, &rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|ch: char| ch.is_whitespace() || ch == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match (name.as_str(), closing) {
+            ("br", _) => out.push('\n'),
+            ("div" | "p" | "ul" | "ol" | "tr" | "table" | "blockquote", _) => {
+                ensure_line_start(&mut out)
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                ensure_line_start(&mut out);
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                out.push_str(&"#".repeat(level));
+                out.push(' ');
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) => out.push('\n'),
+            ("li", false) => {
+                ensure_line_start(&mut out);
+                out.push_str("- ");
+            }
+            ("hr", _) => {
+                ensure_line_start(&mut out);
+                out.push_str("---\n");
+            }
+            ("b" | "strong", _) => out.push_str("**"),
+            ("i" | "em", _) => out.push('_'),
+            ("a", false) => {
+                links.push(tag_attribute(tag, "href").unwrap_or_default().to_string());
+                out.push('[');
+            }
+            ("a", true) => {
+                let href = links.pop().unwrap_or_default();
+                out.push_str(&format!("]({href})"));
+            }
+            ("en-todo", _) => {
+                let checked = tag_attribute(tag, "checked") == Some("true");
+                out.push_str(if checked { "[x] " } else { "[ ] " });
+            }
+            ("en-media", false) => {
+                out.push(ATTACHMENT_MARKER_START);
+                out.push_str(&media_index.to_string());
+                out.push(ATTACHMENT_MARKER_END);
+                media_index += 1;
+            }
+            _ => {}
+        }
+    }
+    push_decoded_text(&mut out, rest);
+
+    let mut text = String::with_capacity(out.len());
+    let mut blank_lines = 0usize;
+    for line in out.lines().map(str::trim_end) {
+        if line.is_empty() {
+            blank_lines += 1;
+            if blank_lines > 1 {
+                continue;
+            }
+        } else {
+            blank_lines = 0;
+        }
+        text.push_str(line);
+        text.push('\n');
+    }
+    text.trim().to_string()
+}
+
+fn enex_date(value: &str) -> Option<DateTime<Local>> {
+    let naive = NaiveDateTime::parse_from_str(value.trim(), ENEX_DATE_FORMAT).ok()?;
+    Some(Utc.from_utc_datetime(&naive).with_timezone(&Local))
+}
+
+fn enex_attachment(resource: roxmltree::Node<'_, '_>, index: usize) -> Result<ImportedAttachment> {
+    let data = child_text(resource, "data").unwrap_or_default();
+    let compact = data
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .collect::<String>();
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(compact)
+        .context("attachment data is not base64")?;
+    let mime = child_text(resource, "mime").unwrap_or("application/octet-stream");
+    let file_name = resource
+        .children()
+        .find(|child| child.has_tag_name("resource-attributes"))
+        .and_then(|attributes| child_text(attributes, "file-name"))
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            let extension = mime.rsplit('/').next().unwrap_or("bin");
+            format!("attachment-{}.{extension}", index + 1)
+        });
+    Ok(ImportedAttachment {
+        file_name,
+        bytes,
+        is_image: mime.starts_with("image/"),
+    })
+}
+
+pub fn parse_enex(text: &str) -> Result<Vec<ImportedNote>> {
+    let document = parse_xml(text)?;
+    if !document.root_element().has_tag_name("en-export") {
+        bail!("not an Evernote export (no <en-export> element)");
+    }
+    let mut notes = Vec::new();
+    for note in document
+        .root_element()
+        .children()
+        .filter(|child| child.has_tag_name("note"))
+    {
+        let mut body = enml_to_markdown(child_text(note, "content").unwrap_or_default());
+        let attachments = note
+            .children()
+            .filter(|child| child.has_tag_name("resource"))
+            .enumerate()
+            .map(|(index, resource)| enex_attachment(resource, index))
+            .collect::<Result<Vec<_>>>()?;
+        // Attachments the markup never placed still belong to the note.
+        for index in 0..attachments.len() {
+            let marker = format!("{ATTACHMENT_MARKER_START}{index}{ATTACHMENT_MARKER_END}");
+            if !body.contains(&marker) {
+                body.push_str("\n\n");
+                body.push_str(&marker);
+            }
+        }
+        let tags = note
+            .children()
+            .filter(|child| child.has_tag_name("tag"))
+            .filter_map(|tag| tag.text())
+            .map(|tag| format!("#{}", tag.trim().replace(char::is_whitespace, "-")))
+            .collect::<Vec<_>>();
+        if !tags.is_empty() {
+            body.push_str("\n\n");
+            body.push_str(&tags.join(" "));
+        }
+        notes.push(ImportedNote {
+            title: child_text(note, "title")
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            created: child_text(note, "created").and_then(enex_date),
+            body: body.trim_start().to_string(),
+            attachments,
+        });
+    }
+    Ok(notes)
+}
+
+fn outline_text<'a>(outline: roxmltree::Node<'a, '_>) -> &'a str {
+    outline
+        .attribute("text")
+        .or_else(|| outline.attribute("title"))
+        .unwrap_or_default()
+}
+
+fn push_outline_items(out: &mut String, outline: roxmltree::Node<'_, '_>, depth: usize) {
+    for item in outline
+        .children()
+        .filter(|child| child.has_tag_name("outline"))
+    {
+        let indent = "  ".repeat(depth);
+        out.push_str(&format!("{indent}- {}\n", outline_text(item).trim()));
+        if let Some(note) = item.attribute("_note") {
+            for line in note.lines() {
+                out.push_str(&format!("{indent}  {line}\n"));
+            }
+        }
+        push_outline_items(out, item, depth + 1);
+    }
+}
+
+pub fn parse_opml(text: &str) -> Result<Vec<ImportedNote>> {
+    let document = parse_xml(text)?;
+    let root = document.root_element();
+    if !root.has_tag_name("opml") {
+        bail!("not an OPML file (no <opml> element)");
+    }
+    let head_created = root
+        .children()
+        .find(|child| child.has_tag_name("head"))
+        .and_then(|head| child_text(head, "dateCreated"))
+        .and_then(|date| DateTime::parse_from_rfc2822(date.trim()).ok())
+        .map(|date| date.with_timezone(&Local));
+    let Some(body) = root.children().find(|child| child.has_tag_name("body")) else {
+        bail!("the OPML file has no <body>");
+    };
+    let mut notes = Vec::new();
+    for outline in body
+        .children()
+        .filter(|child| child.has_tag_name("outline"))
+    {
+        let mut text = String::new();
+        if let Some(note) = outline.attribute("_note") {
+            text.push_str(note.trim());
+            text.push_str("\n\n");
+        }
+        push_outline_items(&mut text, outline, 0);
+        let created = outline
+            .attribute("created")
+            .and_then(|date| DateTime::parse_from_rfc2822(date.trim()).ok())
+            .map(|date| date.with_timezone(&Local))
+            .or(head_created);
+        notes.push(ImportedNote {
+            title: outline_text(outline).trim().to_string(),
+            created,
+            body: text.trim_end().to_string(),
+            attachments: Vec::new(),
+        });
+    }
+    Ok(notes)
+}
+
+fn safe_attachment_name(name: &str) -> String {
+    name.chars()
+        .map(|ch| {
+            if INVALID_STEM_CHARS.contains(&ch) || ch.is_control() || matches!(ch, '(' | ')') {
+                '_'
+            } else {
+                ch
+            }
+        })
+        .collect()
+}
+
+fn create_unique_file(dir: &Path, stem: &str, extension: &str) -> io::Result<(PathBuf, fs::File)> {
+    let mut suffix = 1usize;
+    loop {
+        let file_name = match (suffix, extension.is_empty()) {
+            (1, true) => stem.to_string(),
+            (1, false) => format!("{stem}.{extension}"),
+            (_, true) => format!("{stem}_{suffix}"),
+            (_, false) => format!("{stem}_{suffix}.{extension}"),
+        };
+        let path = dir.join(file_name);
+        match fs::OpenOptions::new()
+            .create_new(true)
+            .write(true)
+            .open(&path)
+        {
+            Ok(file) => return Ok((path, file)),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => suffix += 1,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Writes the note and its attachments; on any failure every file it created is removed, so
+/// a half-imported note never stays behind.
+fn write_imported_note(
+    note: &ImportedNote,
+    placement: &dyn NotePlacement,
+    fallback_created: DateTime<Local>,
+) -> io::Result<PathBuf> {
+    let mut created_files = Vec::new();
+    match write_imported_note_files(note, placement, fallback_created, &mut created_files) {
+        Ok(note_path) => {
+            for path in &created_files {
+                placement.written(path);
+            }
+            Ok(note_path)
+        }
+        Err(error) => {
+            for path in created_files.iter().rev() {
+                let _ = fs::remove_file(path);
+            }
+            Err(error)
+        }
+    }
+}
+
+fn write_imported_note_files(
+    note: &ImportedNote,
+    placement: &dyn NotePlacement,
+    fallback_created: DateTime<Local>,
+    created_files: &mut Vec<PathBuf>,
+) -> io::Result<PathBuf> {
+    let created = note.created.unwrap_or(fallback_created);
+    let dir = placement.directory(created);
+    fs::create_dir_all(&dir)?;
+    let stem = placement.stem(&note.title, created);
+    let (note_path, mut note_file) = create_unique_file(&dir, &stem, "md")?;
+    created_files.push(note_path.clone());
+
+    let mut body = note.body.clone();
+    if !note.attachments.is_empty() {
+        let assets_dir = placement.vault_root().join(ASSETS_DIR_NAME);
+        fs::create_dir_all(&assets_dir)?;
+        let note_stem = note_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        for (index, attachment) in note.attachments.iter().enumerate() {
+            let name = safe_attachment_name(&attachment.file_name);
+            let (name_stem, extension) = match name.rsplit_once('.') {
+                Some((name_stem, extension)) if !name_stem.is_empty() => (name_stem, extension),
+                _ => (name.as_str(), ""),
+            };
+            let (attachment_path, mut file) =
+                create_unique_file(&assets_dir, &format!("{note_stem}-{name_stem}"), extension)?;
+            created_files.push(attachment_path.clone());
+            file.write_all(&attachment.bytes)?;
+            let target = format!(
+                "/{ASSETS_DIR_NAME}/{}",
+                attachment_path
+                    .file_name()
+                    .map(|name| percent_encode_link_segment(&name.to_string_lossy()))
+                    .unwrap_or_default()
+            );
+            let link = if attachment.is_image {
+                format!("![]({target})")
+            } else {
+                format!("[{}]({target})", attachment.file_name)
+            };
+            let marker = format!("{ATTACHMENT_MARKER_START}{index}{ATTACHMENT_MARKER_END}");
+            body = body.replace(&marker, &link);
+        }
+    }
+    // Markers past the last resource (a malformed export) are dropped.
+    body.retain(|ch| ch != ATTACHMENT_MARKER_START && ch != ATTACHMENT_MARKER_END);
+    note_file.write_all(body.as_bytes())?;
+    drop(note_file);
+    let modified = filetime::FileTime::from_system_time(created.into());
+    filetime::set_file_mtime(&note_path, modified)?;
+    Ok(note_path)
+}
+
+/// Writes each note with its attachments; a note that fails is reported and the rest go on.
+pub fn write_imported_notes(
+    notes: &[ImportedNote],
+    placement: &dyn NotePlacement,
+    fallback_created: DateTime<Local>,
+) -> NotesImportSummary {
+    let mut summary = NotesImportSummary::default();
+    for note in notes {
+        match write_imported_note(note, placement, fallback_created) {
+            Ok(path) => {
+                summary.attachments += note.attachments.len();
+                summary.notes.push(path);
+            }
+            Err(error) => summary.failed.push((note.title.clone(), error.to_string())),
+        }
+    }
+    summary
+}
+
+/// Parses `source` as `format` and writes its notes. Notes without a date of their own use
+/// the file's modified time.
+pub fn import_notes_file(
+    source: &Path,
+    format: NotesImportFormat,
+    placement: &dyn NotePlacement,
+) -> Result<NotesImportSummary> {
+    let text = fs::read_to_string(source)
+        .with_context(|| format!("failed to read {}", source.display()))?;
+    let notes = match format {
+        NotesImportFormat::Enex => parse_enex(&text),
+        NotesImportFormat::Opml => parse_opml(&text),
+    }
+    .with_context(|| format!("failed to parse {}", source.display()))?;
+    let fallback_created = fs::metadata(source)
+        .and_then(|metadata| metadata.modified())
+        .map(DateTime::<Local>::from)
+        .unwrap_or_else(|_| Local::now());
+    Ok(write_imported_notes(&notes, placement, fallback_created))
+}
+
+fn usage_text() -> &'static str {
+    "usage: papyru2_notes_import <enex|opml> <file>"
+}
+
+pub fn run_cli_with_app_paths<I, T>(
+    args: I,
+    app_paths: &path_resolver::AppPaths,
+    stdout: &mut dyn Write,
+    stderr: &mut dyn Write,
+) -> i32
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+{
+    let args = args
+        .into_iter()
+        .skip(1)
+        .map(|arg| arg.into().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    let (format, source) = match args.as_slice() {
+        [flag] if flag == "-h" || flag == "--help" => {
+            let _ = writeln!(stdout, "{}", usage_text());
+            return 0;
+        }
+        [command, source] if command == "enex" => (NotesImportFormat::Enex, source),
+        [command, source] if command == "opml" => (NotesImportFormat::Opml, source),
+        _ => {
+            let _ = writeln!(stderr, "{BINARY_NAME}: expected a format and a file");
+            let _ = writeln!(stderr);
+            let _ = writeln!(stderr, "{}", usage_text());
+            return 2;
+        }
+    };
+    let placement = DailyNotePlacement {
+        user_document_dir: app_paths.user_document_dir.clone(),
+    };
+    match import_notes_file(Path::new(source), format, &placement) {
+        Ok(summary) => {
+            for (title, error) in &summary.failed {
+                let _ = writeln!(stderr, "{BINARY_NAME}: '{title}' failed: {error}");
+            }
+            let _ = writeln!(stdout, "{}.", summary.summary());
+            if summary.failed.is_empty() { 0 } else { 1 }
+        }
+        Err(error) => {
+            let _ = writeln!(stderr, "{BINARY_NAME}: {error:#}");
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(label: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_notes_import_{label}_{}_{stamp}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&root).expect("create temp root");
+        root
+    }
+
+    #[test]
+    fn nimp_test1_enex_note_becomes_markdown_with_its_attachment() {
+        let enex = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE en-export SYSTEM "http://xml.evernote.com/pub/evernote-export3.dtd">
+<en-export>
+  <note>
+    <title>Trip / plan</title>
+    <content><![CDATA[<?xml version="1.0" encoding="UTF-8"?><!DOCTYPE en-note SYSTEM "http://xml.evernote.com/pub/enml2.dtd"><en-note><div>Pack &amp; go</div><ul><li><en-todo checked="true"/>tickets</li><li>map</li></ul><div><en-media hash="abc" type="image/png"/></div></en-note>]]></content>
+    <created>20260301T101500Z</created>
+    <tag>travel plans</tag>
+    <resource>
+      <data encoding="base64">aGVs
+bG8=</data>
+      <mime>image/png</mime>
+      <resource-attributes><file-name>map shot.png</file-name></resource-attributes>
+    </resource>
+  </note>
+</en-export>"#;
+        let notes = parse_enex(enex).expect("parse enex");
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].title, "Trip / plan");
+        assert_eq!(notes[0].attachments[0].bytes, b"hello");
+
+        let root = new_temp_root("nimp_test1");
+        let placement = DailyNotePlacement {
+            user_document_dir: root.clone(),
+        };
+        let summary = write_imported_notes(&notes, &placement, Local::now());
+        assert_eq!(summary.notes.len(), 1);
+        assert_eq!(summary.attachments, 1);
+        let created = notes[0].created.expect("created");
+        let day = root.join(created.format("%Y/%m/%d").to_string());
+        assert_eq!(summary.notes[0], day.join("Trip _ plan.md"));
+        assert_eq!(
+            fs::read_to_string(&summary.notes[0]).expect("read note"),
+            "Pack & go\n- [x] tickets\n- map\n![](/assets/Trip%20_%20plan-map%20shot.png)\n\n#travel-plans"
+        );
+        assert_eq!(
+            fs::read(root.join("assets").join("Trip _ plan-map shot.png")).expect("read asset"),
+            b"hello"
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn nimp_test2_opml_top_level_items_become_notes_with_nested_lists() {
+        let opml = r#"<?xml version="1.0"?>
+<opml version="2.0">
+  <head><title>Ideas</title><dateCreated>Sun, 01 Mar 2026 10:15:00 GMT</dateCreated></head>
+  <body>
+    <outline text="Garden" _note="Spring list">
+      <outline text="Tomatoes"><outline text="cherry"/></outline>
+      <outline text="Basil" _note="by the window"/>
+    </outline>
+    <outline text=""/>
+  </body>
+</opml>"#;
+        let notes = parse_opml(opml).expect("parse opml");
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].title, "Garden");
+        assert_eq!(
+            notes[0].body,
+            "Spring list\n\n- Tomatoes\n  - cherry\n- Basil\n  by the window"
+        );
+        assert!(notes[0].created.is_some());
+        assert!(parse_opml("<en-export/>").is_err());
+        assert_eq!(
+            NotesImportFormat::from_path(Path::new("export.ENEX")),
+            Some(NotesImportFormat::Enex)
+        );
+    }
+
+    #[test]
+    fn nimp_test3_failed_attachment_rolls_back_the_note_and_links_escape_hash_and_percent() {
+        let note = ImportedNote {
+            title: "Budget".to_string(),
+            created: Some(Local::now()),
+            body: format!("{ATTACHMENT_MARKER_START}0{ATTACHMENT_MARKER_END}"),
+            attachments: vec![ImportedAttachment {
+                file_name: "q1 #2 100%.pdf".to_string(),
+                bytes: b"pdf".to_vec(),
+                is_image: false,
+            }],
+        };
+        let root = new_temp_root("nimp_test3");
+        let placement = DailyNotePlacement {
+            user_document_dir: root.clone(),
+        };
+        // A file where the assets folder should be makes the attachment fail.
+        fs::write(root.join(ASSETS_DIR_NAME), b"").expect("block assets");
+        let summary = write_imported_notes(std::slice::from_ref(&note), &placement, Local::now());
+        assert!(summary.notes.is_empty());
+        assert_eq!(summary.failed.len(), 1);
+        let day = placement.directory(note.created.expect("created"));
+        assert_eq!(fs::read_dir(&day).expect("read day").count(), 0);
+
+        fs::remove_file(root.join(ASSETS_DIR_NAME)).expect("unblock assets");
+        let summary = write_imported_notes(std::slice::from_ref(&note), &placement, Local::now());
+        assert_eq!(summary.notes, vec![day.join("Budget.md")]);
+        assert_eq!(
+            fs::read_to_string(&summary.notes[0]).expect("read note"),
+            "[q1 #2 100%.pdf](/assets/Budget-q1%20%232%20100%25.pdf)"
+        );
+        assert_eq!(
+            percent_decode_link("/assets/Budget-q1%20%232%20100%25.pdf"),
+            "/assets/Budget-q1 #2 100%.pdf"
+        );
+        assert_eq!(percent_decode_link("50%off%2"), "50%off%2");
+        let _ = fs::remove_dir_all(root);
+    }
+
+    struct RecordingPlacement {
+        daily: DailyNotePlacement,
+        written: std::cell::RefCell<Vec<PathBuf>>,
+    }
+
+    impl NotePlacement for RecordingPlacement {
+        fn vault_root(&self) -> &Path {
+            self.daily.vault_root()
+        }
+
+        fn directory(&self, created: DateTime<Local>) -> PathBuf {
+            self.daily.directory(created)
+        }
+
+        fn stem(&self, title: &str, created: DateTime<Local>) -> String {
+            self.daily.stem(title, created)
+        }
+
+        fn written(&self, path: &Path) {
+            self.written.borrow_mut().push(path.to_path_buf());
+        }
+    }
+
+    #[test]
+    fn nimp_test4_only_completed_notes_report_their_files() {
+        let note = ImportedNote {
+            title: "Scan".to_string(),
+            created: Some(Local::now()),
+            body: format!("{ATTACHMENT_MARKER_START}0{ATTACHMENT_MARKER_END}"),
+            attachments: vec![ImportedAttachment {
+                file_name: "page.png".to_string(),
+                bytes: b"png".to_vec(),
+                is_image: true,
+            }],
+        };
+        let root = new_temp_root("nimp_test4");
+        let placement = RecordingPlacement {
+            daily: DailyNotePlacement {
+                user_document_dir: root.clone(),
+            },
+            written: Default::default(),
+        };
+        fs::write(root.join(ASSETS_DIR_NAME), b"").expect("block assets");
+        write_imported_notes(std::slice::from_ref(&note), &placement, Local::now());
+        assert!(placement.written.borrow().is_empty());
+
+        fs::remove_file(root.join(ASSETS_DIR_NAME)).expect("unblock assets");
+        let summary = write_imported_notes(std::slice::from_ref(&note), &placement, Local::now());
+        assert_eq!(
+            *placement.written.borrow(),
+            vec![
+                summary.notes[0].clone(),
+                root.join(ASSETS_DIR_NAME).join("Scan-page.png")
+            ]
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+}
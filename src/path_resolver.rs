@@ -1,17 +1,27 @@
 use std::env;
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 
 pub const APP_NAME: &str = "papyru2";
 pub const APP_HOME_ENV: &str = "PAPYRU2_HOME";
+pub const CONF_DIR_ENV: &str = "PAPYRU2_CONF_DIR";
+pub const DATA_DIR_ENV: &str = "PAPYRU2_DATA_DIR";
+pub const LOG_DIR_ENV: &str = "PAPYRU2_LOG_DIR";
+pub const DOCUMENTS_DIR_ENV: &str = "PAPYRU2_DOCUMENTS_DIR";
+pub const DOCUMENTS_DIR_CLI_FLAG: &str = "--documents";
+pub const DOCUMENTS_DIR_SETTING: &str = "paths.documents_dir";
+pub const CONF_FILE_NAME: &str = "papyru2_conf.toml";
+pub const DOTENV_FILE_NAME: &str = ".env";
 pub const PORTABLE_MARKER_FILE: &str = "papyru2.portable";
+const VAULT_DATA_DIR_NAME: &str = "vaults";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CliRunModeOverride {
     Portable,
     Installed,
+    Demo,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -20,6 +30,7 @@ pub enum RunEnvPattern {
     Portable,
     DevCargoRun,
     Installed,
+    Demo,
 }
 
 impl RunEnvPattern {
@@ -29,10 +40,51 @@ impl RunEnvPattern {
             Self::Portable => "portable_marker_or_layout",
             Self::DevCargoRun => "cargo_target_layout",
             Self::Installed => "installed_home_fallback",
+            Self::Demo => "demo_sandbox",
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathOverrideSource {
+    Cli,
+    ProcessEnv,
+    DotEnv,
+    Settings,
+}
+
+impl PathOverrideSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Cli => "cli",
+            Self::ProcessEnv => "process_env",
+            Self::DotEnv => "dotenv",
+            Self::Settings => "settings",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathDirOverride {
+    pub env_key: &'static str,
+    pub value: PathBuf,
+    pub source: PathOverrideSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathOverrideStatus {
+    Applied,
+    Rejected(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathOverrideReportEntry {
+    pub env_key: &'static str,
+    pub value: PathBuf,
+    pub source: PathOverrideSource,
+    pub status: PathOverrideStatus,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AppPaths {
     pub mode: RunEnvPattern,
@@ -43,26 +95,71 @@ pub struct AppPaths {
     pub recyclebin_dir: PathBuf,
     pub log_dir: PathBuf,
     pub bin_dir: PathBuf,
+    pub override_report: Vec<PathOverrideReportEntry>,
 }
 
 impl AppPaths {
     pub fn resolve() -> io::Result<Self> {
-        Self::resolve_with_cli_override(None)
+        Self::resolve_with_cli_override(None, None)
     }
 
+    /// `documents_dir` is the `--documents` folder; it wins over `PAPYRU2_DOCUMENTS_DIR` and
+    /// `[paths] documents_dir`.
     pub fn resolve_with_cli_override(
         override_mode: Option<CliRunModeOverride>,
+        documents_dir: Option<PathBuf>,
     ) -> io::Result<Self> {
-        let env_home = env::var_os(APP_HOME_ENV).map(PathBuf::from);
+        let dotenv_values = env::current_dir()
+            .ok()
+            .map(|dir| load_dotenv_file(dir.join(DOTENV_FILE_NAME).as_path()))
+            .unwrap_or_default();
+        let lookup = |key: &str| env::var_os(key);
+        let env_home = lookup_env_path(APP_HOME_ENV, &lookup, &dotenv_values)
+            .map(|(value, _)| value)
+            .filter(|value| !value.as_os_str().is_empty());
+        let mut dir_overrides = collect_dir_overrides(&lookup, &dotenv_values);
+        if let Some(value) = documents_dir {
+            dir_overrides.retain(|dir_override| dir_override.env_key != DOCUMENTS_DIR_ENV);
+            dir_overrides.push(PathDirOverride {
+                env_key: DOCUMENTS_DIR_CLI_FLAG,
+                value,
+                source: PathOverrideSource::Cli,
+            });
+        }
         let exe_path = current_exe_path()?;
-        Self::resolve_from_inputs(env_home, exe_path, os_home_dir(), override_mode)
+        Self::resolve_from_inputs_with_dir_overrides(
+            env_home,
+            exe_path,
+            os_home_dir(),
+            override_mode,
+            dir_overrides,
+        )
     }
 
+    #[cfg(test)]
     pub(crate) fn resolve_from_inputs(
         env_home: Option<PathBuf>,
         exe_path: PathBuf,
         user_home: Option<PathBuf>,
         override_mode: Option<CliRunModeOverride>,
+    ) -> io::Result<Self> {
+        Self::resolve_from_inputs_with_dir_overrides(
+            env_home,
+            exe_path,
+            user_home,
+            override_mode,
+            Vec::new(),
+        )
+    }
+
+    // Individual conf/data/log/documents overrides are layered on top of whichever APP_HOME
+    // wins (CLI > PAPYRU2_HOME > portable > dev > installed).
+    pub(crate) fn resolve_from_inputs_with_dir_overrides(
+        env_home: Option<PathBuf>,
+        exe_path: PathBuf,
+        user_home: Option<PathBuf>,
+        override_mode: Option<CliRunModeOverride>,
+        dir_overrides: Vec<PathDirOverride>,
     ) -> io::Result<Self> {
         let exe_dir = exe_path.parent().ok_or_else(|| {
             io::Error::new(
@@ -77,38 +174,107 @@ impl AppPaths {
                     return Self::build_paths(
                         RunEnvPattern::Portable,
                         forced_portable_app_home(exe_dir)?,
+                        &dir_overrides,
                     );
                 }
                 CliRunModeOverride::Installed => {
                     return Self::build_paths(
                         RunEnvPattern::Installed,
                         installed_app_home(user_home)?,
+                        &dir_overrides,
                     );
                 }
+                CliRunModeOverride::Demo => return Self::build_demo_paths(),
             }
         }
 
         if let Some(home) = env_home {
-            return Self::build_paths(RunEnvPattern::EnvOverride, home);
+            return Self::build_paths(RunEnvPattern::EnvOverride, home, &dir_overrides);
         }
 
         if let Some(home) = detect_portable_app_home(exe_dir) {
-            return Self::build_paths(RunEnvPattern::Portable, home);
+            return Self::build_paths(RunEnvPattern::Portable, home, &dir_overrides);
         }
 
         if let Some(home) = detect_dev_app_home(exe_dir) {
-            return Self::build_paths(RunEnvPattern::DevCargoRun, home);
+            return Self::build_paths(RunEnvPattern::DevCargoRun, home, &dir_overrides);
         }
 
-        Self::build_paths(RunEnvPattern::Installed, installed_app_home(user_home)?)
+        Self::build_paths(
+            RunEnvPattern::Installed,
+            installed_app_home(user_home)?,
+            &dir_overrides,
+        )
     }
 
-    fn build_paths(mode: RunEnvPattern, app_home: PathBuf) -> io::Result<Self> {
-        let paths = Self::from_home(mode, app_home);
+    fn build_paths(
+        mode: RunEnvPattern,
+        app_home: PathBuf,
+        dir_overrides: &[PathDirOverride],
+    ) -> io::Result<Self> {
+        let mut paths = Self::from_home(mode, app_home);
+        paths.apply_dir_overrides(dir_overrides);
+        // The settings file lives in the (possibly overridden) conf dir, so it is read last
+        // and only consulted when neither the CLI nor the environment chose a notes folder.
+        if mode != RunEnvPattern::Demo
+            && !dir_overrides
+                .iter()
+                .any(|dir_override| is_documents_dir_key(dir_override.env_key))
+            && let Some(value) =
+                load_documents_dir_setting(paths.config_file_path(CONF_FILE_NAME).as_path())
+        {
+            paths.apply_dir_overrides(&[PathDirOverride {
+                env_key: DOCUMENTS_DIR_SETTING,
+                value,
+                source: PathOverrideSource::Settings,
+            }]);
+        }
         paths.ensure_dirs()?;
         Ok(paths)
     }
 
+    // Demo mode ignores PAPYRU2_* directory overrides so every write stays in the
+    // throwaway sandbox.
+    fn build_demo_paths() -> io::Result<Self> {
+        let app_home = crate::demo_vault::demo_app_home(env::temp_dir().as_path());
+        let paths = Self::build_paths(RunEnvPattern::Demo, app_home, &[])?;
+        crate::demo_vault::populate_demo_vault(
+            paths.user_document_dir.as_path(),
+            chrono::Local::now(),
+        )?;
+        Ok(paths)
+    }
+
+    fn apply_dir_overrides(&mut self, dir_overrides: &[PathDirOverride]) {
+        for dir_override in dir_overrides {
+            let status = match validate_dir_override(dir_override.value.as_path()) {
+                Ok(()) => {
+                    match dir_override.env_key {
+                        CONF_DIR_ENV => self.conf_dir = dir_override.value.clone(),
+                        DATA_DIR_ENV => {
+                            self.data_dir = dir_override.value.clone();
+                            self.user_document_dir = self.data_dir.join("user_document");
+                            self.recyclebin_dir = self.user_document_dir.join("recyclebin");
+                        }
+                        LOG_DIR_ENV => self.log_dir = dir_override.value.clone(),
+                        key if is_documents_dir_key(key) => {
+                            self.switch_user_document_dir(dir_override.value.clone())
+                        }
+                        _ => {}
+                    }
+                    PathOverrideStatus::Applied
+                }
+                Err(reason) => PathOverrideStatus::Rejected(reason),
+            };
+            self.override_report.push(PathOverrideReportEntry {
+                env_key: dir_override.env_key,
+                value: dir_override.value.clone(),
+                source: dir_override.source,
+                status,
+            });
+        }
+    }
+
     pub fn ensure_dirs(&self) -> io::Result<()> {
         fs::create_dir_all(&self.app_home)?;
         fs::create_dir_all(&self.conf_dir)?;
@@ -120,6 +286,29 @@ impl AppPaths {
         Ok(())
     }
 
+    /// Moves the notes root (and the recycle bin inside it) to another vault; conf, data and
+    /// log directories stay where they are.
+    pub fn switch_user_document_dir(&mut self, user_document_dir: PathBuf) {
+        self.recyclebin_dir = user_document_dir.join("recyclebin");
+        self.user_document_dir = user_document_dir;
+    }
+
+    /// Whether `--documents` chose the notes folder for this run; it then also wins over the
+    /// vault remembered as active.
+    pub fn documents_dir_from_cli(&self) -> bool {
+        self.override_report.iter().any(|entry| {
+            entry.source == PathOverrideSource::Cli && entry.status == PathOverrideStatus::Applied
+        })
+    }
+
+    /// `data/vaults/<key>/`: state that belongs to the active vault rather than the app, keyed
+    /// by a stable hash of the vault's full path.
+    pub fn vault_data_dir(&self) -> PathBuf {
+        self.data_dir
+            .join(VAULT_DATA_DIR_NAME)
+            .join(vault_data_key(self.user_document_dir.as_path()))
+    }
+
     pub fn config_file_path(&self, file_name: impl AsRef<Path>) -> PathBuf {
         self.conf_dir.join(file_name)
     }
@@ -141,7 +330,138 @@ impl AppPaths {
             bin_dir: app_home.join("bin"),
             app_home,
             mode,
+            override_report: Vec::new(),
+        }
+    }
+}
+
+/// FNV-1a of the path, so the key stays the same across runs and builds (std's hasher does not
+/// promise that).
+pub(crate) fn vault_data_key(path: &Path) -> String {
+    let hash = path
+        .to_string_lossy()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    format!("{hash:016x}")
+}
+
+fn is_documents_dir_key(key: &str) -> bool {
+    matches!(
+        key,
+        DOCUMENTS_DIR_ENV | DOCUMENTS_DIR_CLI_FLAG | DOCUMENTS_DIR_SETTING
+    )
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct PathsConfigFile {
+    #[serde(default)]
+    paths: PathsConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct PathsConfigSection {
+    documents_dir: Option<PathBuf>,
+}
+
+/// `[paths] documents_dir = "/path/to/synced/notes"`; an unreadable file or section means
+/// the notes folder stays under `data/`.
+fn load_documents_dir_setting(path: &Path) -> Option<PathBuf> {
+    let raw = fs::read_to_string(path).ok()?;
+    toml::from_str::<PathsConfigFile>(&raw)
+        .ok()?
+        .paths
+        .documents_dir
+        .filter(|dir| !dir.as_os_str().is_empty())
+}
+
+fn validate_dir_override(path: &Path) -> Result<(), String> {
+    if path.as_os_str().is_empty() {
+        return Err("empty value".to_string());
+    }
+    if !path.is_absolute() {
+        return Err("path must be absolute".to_string());
+    }
+    if path.exists() && !path.is_dir() {
+        return Err("path exists and is not a directory".to_string());
+    }
+    Ok(())
+}
+
+fn lookup_env_path<F>(
+    key: &str,
+    lookup: &F,
+    dotenv_values: &[(String, String)],
+) -> Option<(PathBuf, PathOverrideSource)>
+where
+    F: Fn(&str) -> Option<OsString>,
+{
+    if let Some(value) = lookup(key) {
+        return Some((PathBuf::from(value), PathOverrideSource::ProcessEnv));
+    }
+
+    dotenv_values
+        .iter()
+        .rev()
+        .find(|(dotenv_key, _)| dotenv_key == key)
+        .map(|(_, value)| (PathBuf::from(value), PathOverrideSource::DotEnv))
+}
+
+pub(crate) fn collect_dir_overrides<F>(
+    lookup: &F,
+    dotenv_values: &[(String, String)],
+) -> Vec<PathDirOverride>
+where
+    F: Fn(&str) -> Option<OsString>,
+{
+    [CONF_DIR_ENV, DATA_DIR_ENV, LOG_DIR_ENV, DOCUMENTS_DIR_ENV]
+        .into_iter()
+        .filter_map(|env_key| {
+            lookup_env_path(env_key, lookup, dotenv_values).map(|(value, source)| PathDirOverride {
+                env_key,
+                value,
+                source,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn parse_dotenv(raw: &str) -> Vec<(String, String)> {
+    let mut values = Vec::new();
+    for line in raw.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        if !key.starts_with("PAPYRU2_") {
+            continue;
         }
+
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|inner| inner.strip_suffix('"'))
+            .or_else(|| {
+                value
+                    .strip_prefix('\'')
+                    .and_then(|inner| inner.strip_suffix('\''))
+            })
+            .unwrap_or(value);
+        values.push((key.to_string(), value.to_string()));
+    }
+    values
+}
+
+fn load_dotenv_file(path: &Path) -> Vec<(String, String)> {
+    match fs::read_to_string(path) {
+        Ok(raw) => parse_dotenv(&raw),
+        Err(_) => Vec::new(),
     }
 }
 
@@ -156,33 +476,9 @@ fn current_exe_path() -> io::Result<PathBuf> {
 pub fn parse_cli_mode_override<I, S>(args: I) -> io::Result<Option<CliRunModeOverride>>
 where
     I: IntoIterator<Item = S>,
-    S: AsRef<str>,
+    S: Into<std::ffi::OsString>,
 {
-    let mut force_portable = false;
-    let mut force_installed = false;
-
-    for arg in args.into_iter().skip(1) {
-        match arg.as_ref() {
-            "--portable" => force_portable = true,
-            "--installed" => force_installed = true,
-            _ => {}
-        }
-    }
-
-    if force_portable && force_installed {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "conflicting CLI overrides: --portable and --installed",
-        ));
-    }
-
-    if force_portable {
-        return Ok(Some(CliRunModeOverride::Portable));
-    }
-    if force_installed {
-        return Ok(Some(CliRunModeOverride::Installed));
-    }
-    Ok(None)
+    crate::cli::parse_cli_args(args).map(|args| args.mode_override)
 }
 
 fn detect_portable_app_home(exe_dir: &Path) -> Option<PathBuf> {
@@ -549,4 +845,270 @@ mod tests {
         assert!(paths.recyclebin_dir.is_dir());
         remove_temp_root(root.as_path());
     }
+
+    #[test]
+    fn path_test18_individual_dir_overrides_replace_home_derived_dirs() {
+        let root = new_temp_root("path_test18");
+        let env_home = root.join("env_home");
+        let exe_path = root.join("other").join("papyru2.exe");
+        let dir_overrides = vec![
+            PathDirOverride {
+                env_key: CONF_DIR_ENV,
+                value: root.join("vol_a").join("conf"),
+                source: PathOverrideSource::ProcessEnv,
+            },
+            PathDirOverride {
+                env_key: DATA_DIR_ENV,
+                value: root.join("vol_b").join("data"),
+                source: PathOverrideSource::DotEnv,
+            },
+            PathDirOverride {
+                env_key: LOG_DIR_ENV,
+                value: root.join("vol_c").join("log"),
+                source: PathOverrideSource::ProcessEnv,
+            },
+        ];
+
+        let result = AppPaths::resolve_from_inputs_with_dir_overrides(
+            Some(env_home.clone()),
+            exe_path,
+            Some(root.join("user_home")),
+            None,
+            dir_overrides,
+        )
+        .unwrap();
+
+        assert_eq!(result.mode, RunEnvPattern::EnvOverride);
+        assert_eq!(result.app_home, env_home);
+        assert_eq!(result.conf_dir, root.join("vol_a").join("conf"));
+        assert_eq!(result.data_dir, root.join("vol_b").join("data"));
+        assert_eq!(
+            result.user_document_dir,
+            root.join("vol_b").join("data").join("user_document")
+        );
+        assert_eq!(
+            result.recyclebin_dir,
+            result.user_document_dir.join("recyclebin")
+        );
+        assert_eq!(result.log_dir, root.join("vol_c").join("log"));
+        assert_eq!(result.bin_dir, env_home.join("bin"));
+        assert!(result.recyclebin_dir.is_dir());
+        assert!(
+            result
+                .override_report
+                .iter()
+                .all(|entry| entry.status == PathOverrideStatus::Applied)
+        );
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn path_test19_invalid_dir_overrides_are_rejected_and_reported() {
+        let root = new_temp_root("path_test19");
+        let blocking_file = root.join("not_a_dir");
+        write_empty_file(blocking_file.as_path());
+        let exe_path = root.join("other").join("papyru2.exe");
+        let dir_overrides = vec![
+            PathDirOverride {
+                env_key: CONF_DIR_ENV,
+                value: PathBuf::from("relative/conf"),
+                source: PathOverrideSource::ProcessEnv,
+            },
+            PathDirOverride {
+                env_key: LOG_DIR_ENV,
+                value: blocking_file,
+                source: PathOverrideSource::DotEnv,
+            },
+        ];
+
+        let result = AppPaths::resolve_from_inputs_with_dir_overrides(
+            Some(root.join("env_home")),
+            exe_path,
+            Some(root.join("user_home")),
+            None,
+            dir_overrides,
+        )
+        .unwrap();
+
+        assert_eq!(result.conf_dir, root.join("env_home").join("conf"));
+        assert_eq!(result.log_dir, root.join("env_home").join("log"));
+        assert_eq!(result.override_report.len(), 2);
+        assert!(matches!(
+            &result.override_report[0].status,
+            PathOverrideStatus::Rejected(reason) if reason.contains("absolute")
+        ));
+        assert!(matches!(
+            &result.override_report[1].status,
+            PathOverrideStatus::Rejected(reason) if reason.contains("not a directory")
+        ));
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn path_test20_dir_overrides_apply_on_top_of_cli_override() {
+        let root = new_temp_root("path_test20");
+        let exe_path = root.join("portable").join("bin").join("papyru2.exe");
+        let cli_override =
+            parse_cli_mode_override(["papyru2.exe", "--portable"]).expect("parse override");
+        let dir_overrides = vec![PathDirOverride {
+            env_key: DATA_DIR_ENV,
+            value: root.join("external_data"),
+            source: PathOverrideSource::ProcessEnv,
+        }];
+
+        let result = AppPaths::resolve_from_inputs_with_dir_overrides(
+            None,
+            exe_path,
+            Some(root.join("user_home")),
+            cli_override,
+            dir_overrides,
+        )
+        .unwrap();
+
+        assert_eq!(result.mode, RunEnvPattern::Portable);
+        assert_eq!(result.conf_dir, root.join("portable").join("conf"));
+        assert_eq!(result.data_dir, root.join("external_data"));
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn path_test21_process_env_takes_precedence_over_dotenv() {
+        let dotenv_values = parse_dotenv(
+            "# comment\nexport PAPYRU2_CONF_DIR=\"/dotenv/conf\"\nPAPYRU2_LOG_DIR='/dotenv/log'\nOTHER=1\n",
+        );
+        let lookup = |key: &str| (key == CONF_DIR_ENV).then(|| OsString::from("/process/conf"));
+
+        let overrides = collect_dir_overrides(&lookup, &dotenv_values);
+
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides[0].env_key, CONF_DIR_ENV);
+        assert_eq!(overrides[0].value, PathBuf::from("/process/conf"));
+        assert_eq!(overrides[0].source, PathOverrideSource::ProcessEnv);
+        assert_eq!(overrides[1].env_key, LOG_DIR_ENV);
+        assert_eq!(overrides[1].value, PathBuf::from("/dotenv/log"));
+        assert_eq!(overrides[1].source, PathOverrideSource::DotEnv);
+    }
+
+    #[test]
+    fn path_test22_dotenv_parser_ignores_unrelated_and_malformed_lines() {
+        let dotenv_values = parse_dotenv("FOO=bar\nPAPYRU2_HOME\n\n  PAPYRU2_HOME = /home/x  \n");
+
+        assert_eq!(
+            dotenv_values,
+            vec![("PAPYRU2_HOME".to_string(), "/home/x".to_string())]
+        );
+    }
+
+    #[test]
+    fn path_test23_cli_demo_override_builds_sandbox_with_sample_vault() {
+        let root = new_temp_root("path_test23");
+        let exe_path = root.join("portable").join("bin").join("papyru2.exe");
+        let cli_override =
+            parse_cli_mode_override(["papyru2.exe", "--demo"]).expect("parse override");
+        let dir_overrides = vec![PathDirOverride {
+            env_key: DATA_DIR_ENV,
+            value: root.join("real_data"),
+            source: PathOverrideSource::ProcessEnv,
+        }];
+
+        let result = AppPaths::resolve_from_inputs_with_dir_overrides(
+            Some(root.join("env_home")),
+            exe_path,
+            Some(root.join("user_home")),
+            cli_override,
+            dir_overrides,
+        )
+        .unwrap();
+
+        assert_eq!(result.mode, RunEnvPattern::Demo);
+        assert!(result.app_home.starts_with(env::temp_dir()));
+        assert_eq!(result.data_dir, result.app_home.join("data"));
+        assert!(result.override_report.is_empty());
+        assert!(!root.join("real_data").exists());
+        let has_sample_note = fs::read_dir(result.user_document_dir.as_path())
+            .expect("read demo user_document")
+            .flatten()
+            .any(|entry| entry.path().is_dir());
+        assert!(has_sample_note);
+        remove_temp_root(result.app_home.as_path());
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn path_test25_documents_dir_comes_from_cli_env_or_settings_in_that_order() {
+        let root = new_temp_root("path_test25");
+        let env_home = root.join("env_home");
+        let exe_path = root.join("portable").join("bin").join("papyru2.exe");
+        let synced = root.join("Dropbox").join("notes");
+        fs::create_dir_all(env_home.join("conf")).expect("conf dir");
+        fs::write(
+            env_home.join("conf").join(CONF_FILE_NAME),
+            format!("[paths]\ndocuments_dir = '{}'\n", synced.display()),
+        )
+        .expect("write settings");
+        let resolve = |dir_overrides| {
+            AppPaths::resolve_from_inputs_with_dir_overrides(
+                Some(env_home.clone()),
+                exe_path.clone(),
+                Some(root.join("user_home")),
+                None,
+                dir_overrides,
+            )
+            .unwrap()
+        };
+
+        let from_settings = resolve(vec![PathDirOverride {
+            env_key: DATA_DIR_ENV,
+            value: root.join("external_data"),
+            source: PathOverrideSource::ProcessEnv,
+        }]);
+        assert_eq!(from_settings.data_dir, root.join("external_data"));
+        assert_eq!(from_settings.user_document_dir, synced);
+        assert_eq!(from_settings.recyclebin_dir, synced.join("recyclebin"));
+        assert!(from_settings.recyclebin_dir.is_dir());
+        assert_eq!(
+            from_settings
+                .override_report
+                .last()
+                .map(|entry| entry.source),
+            Some(PathOverrideSource::Settings)
+        );
+        assert!(!from_settings.documents_dir_from_cli());
+
+        let from_cli = resolve(vec![PathDirOverride {
+            env_key: DOCUMENTS_DIR_CLI_FLAG,
+            value: root.join("cli_notes"),
+            source: PathOverrideSource::Cli,
+        }]);
+        assert_eq!(from_cli.user_document_dir, root.join("cli_notes"));
+        assert_eq!(from_cli.override_report.len(), 1);
+        assert!(from_cli.documents_dir_from_cli());
+
+        let lookup = |key: &str| (key == DOCUMENTS_DIR_ENV).then(|| OsString::from("/env/notes"));
+        let overrides = collect_dir_overrides(&lookup, &[]);
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].env_key, DOCUMENTS_DIR_ENV);
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn path_test24_cli_demo_conflicts_with_other_mode_overrides() {
+        assert!(parse_cli_mode_override(["papyru2.exe", "--demo", "--portable"]).is_err());
+        assert!(parse_cli_mode_override(["papyru2.exe", "--installed", "--demo"]).is_err());
+        assert_eq!(
+            parse_cli_mode_override(["papyru2.exe", "--demo"]).expect("parse demo"),
+            Some(CliRunModeOverride::Demo)
+        );
+    }
+
+    #[test]
+    fn path_test26_vault_data_dir_is_per_vault_and_stable() {
+        let mut paths = AppPaths::from_home(RunEnvPattern::DevCargoRun, PathBuf::from("/papyru2"));
+        paths.switch_user_document_dir(PathBuf::from("/notes/work"));
+        let work = paths.vault_data_dir();
+        assert_eq!(work.parent(), Some(Path::new("/papyru2/data/vaults")));
+        assert_eq!(vault_data_key(Path::new("")), "cbf29ce484222325");
+        paths.switch_user_document_dir(PathBuf::from("/notes/home"));
+        assert_ne!(paths.vault_data_dir(), work);
+    }
 }
@@ -0,0 +1,680 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Datelike, Local, Months, NaiveDate};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    v_flex,
+};
+
+use crate::log::trace_debug;
+
+const FOLDER_PICKER_MAX_HEIGHT_PX: f32 = 240.;
+pub(crate) const CALENDAR_CELL_WIDTH_PX: f32 = 28.;
+pub(crate) const CALENDAR_WEEKDAY_LABELS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum PickerSelection {
+    Folder(PathBuf),
+    DateRange { from: NaiveDate, to: NaiveDate },
+    Text(String),
+}
+
+#[derive(Clone, Debug)]
+pub enum PickerEvent {
+    Picked(PickerSelection),
+    Cancelled,
+}
+
+/// Every folder under `root` (the root itself first, at depth 0) in tree order, skipping
+/// hidden folders and `exclude`. Links are not followed, so a link loop cannot recurse.
+pub(crate) fn list_picker_folders(
+    root: &Path,
+    exclude: &[PathBuf],
+) -> io::Result<Vec<(PathBuf, usize)>> {
+    let mut folders = vec![(root.to_path_buf(), 0)];
+    collect_picker_folders(root, 1, exclude, &mut folders)?;
+    Ok(folders)
+}
+
+fn collect_picker_folders(
+    dir: &Path,
+    depth: usize,
+    exclude: &[PathBuf],
+    folders: &mut Vec<(PathBuf, usize)>,
+) -> io::Result<()> {
+    let mut children = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .map(|entry| entry.path())
+        .filter(|path| {
+            !path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        })
+        .filter(|path| !exclude.contains(path))
+        .collect::<Vec<_>>();
+    children.sort();
+    for child in children {
+        folders.push((child.clone(), depth));
+        collect_picker_folders(child.as_path(), depth + 1, exclude, folders)?;
+    }
+    Ok(())
+}
+
+/// The days of `month` laid out Monday-first, padded with `None` to whole weeks.
+pub(crate) fn month_grid(month: NaiveDate) -> Vec<Option<NaiveDate>> {
+    let first = month.with_day(1).unwrap_or(month);
+    let mut cells = vec![None; first.weekday().num_days_from_monday() as usize];
+    cells.extend(
+        first
+            .iter_days()
+            .take_while(|day| day.month() == first.month())
+            .map(Some),
+    );
+    while cells.len() % 7 != 0 {
+        cells.push(None);
+    }
+    cells
+}
+
+/// Two clicks make a range: the first sets the start, the second the end (in either order).
+/// A third click starts over.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct DateRangeSelection {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+impl DateRangeSelection {
+    pub fn new(from: Option<NaiveDate>, to: Option<NaiveDate>) -> Self {
+        match (from, to) {
+            (Some(from), Some(to)) if to < from => Self {
+                from: Some(to),
+                to: Some(from),
+            },
+            (None, Some(to)) => Self {
+                from: Some(to),
+                to: Some(to),
+            },
+            _ => Self { from, to },
+        }
+    }
+
+    pub fn click(&mut self, date: NaiveDate) {
+        *self = match (self.from, self.to) {
+            (Some(from), None) if date < from => Self {
+                from: Some(date),
+                to: Some(from),
+            },
+            (Some(from), None) => Self {
+                from: Some(from),
+                to: Some(date),
+            },
+            _ => Self {
+                from: Some(date),
+                to: None,
+            },
+        };
+    }
+
+    /// A single clicked day is a one-day range.
+    pub fn range(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let from = self.from?;
+        Some((from, self.to.unwrap_or(from)))
+    }
+
+    fn contains(&self, date: NaiveDate) -> bool {
+        self.range()
+            .is_some_and(|(from, to)| date >= from && date <= to)
+    }
+
+    fn is_endpoint(&self, date: NaiveDate) -> bool {
+        self.from == Some(date) || self.to == Some(date)
+    }
+}
+
+fn render_picker_footer<T: EventEmitter<PickerEvent>>(
+    status: String,
+    confirm_label: &'static str,
+    cx: &mut Context<T>,
+    on_confirm: impl Fn(&mut Window, &mut App) + 'static,
+) -> Div {
+    h_flex()
+        .w_full()
+        .gap_2()
+        .items_center()
+        .child(
+            div()
+                .flex_1()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(status),
+        )
+        .child(
+            Button::new("picker-confirm")
+                .small()
+                .primary()
+                .label(confirm_label)
+                .on_click(move |_, window, cx| on_confirm(window, cx)),
+        )
+        .child(
+            Button::new("picker-cancel")
+                .small()
+                .ghost()
+                .label(crate::i18n::tr("button.cancel"))
+                .on_click(cx.listener(|_, _, _, cx| cx.emit(PickerEvent::Cancelled))),
+        )
+}
+
+pub struct FolderPicker {
+    title: SharedString,
+    root: PathBuf,
+    folders: Vec<(PathBuf, usize)>,
+    loading: bool,
+    selected: Option<PathBuf>,
+}
+
+impl EventEmitter<PickerEvent> for FolderPicker {}
+
+impl FolderPicker {
+    /// Opens with only the notes folder listed; the rest of the vault is walked on the
+    /// background executor and fills in when done.
+    pub fn new(
+        title: impl Into<SharedString>,
+        root: PathBuf,
+        exclude: Vec<PathBuf>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let listing = cx.background_executor().spawn({
+            let root = root.clone();
+            async move { list_picker_folders(root.as_path(), &exclude) }
+        });
+        cx.spawn(async move |this, cx| {
+            let listing = listing.await;
+            let _ = this.update(cx, |picker, cx| picker.apply_listing(listing, cx));
+        })
+        .detach();
+        Self {
+            title: title.into(),
+            folders: vec![(root.clone(), 0)],
+            root,
+            loading: true,
+            selected: None,
+        }
+    }
+
+    fn apply_listing(
+        &mut self,
+        listing: io::Result<Vec<(PathBuf, usize)>>,
+        cx: &mut Context<Self>,
+    ) {
+        self.loading = false;
+        match listing {
+            Ok(folders) => self.folders = folders,
+            Err(error) => trace_debug(format!(
+                "req-pick1 folder list failed root={} error={error}",
+                self.root.display()
+            )),
+        }
+        cx.notify();
+    }
+
+    fn confirm(&mut self, cx: &mut Context<Self>) {
+        if let Some(folder) = self.selected.clone() {
+            cx.emit(PickerEvent::Picked(PickerSelection::Folder(folder)));
+        }
+    }
+}
+
+impl Render for FolderPicker {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut list = v_flex()
+            .id("folder-picker-list")
+            .w_full()
+            .max_h(px(FOLDER_PICKER_MAX_HEIGHT_PX))
+            .overflow_y_scroll()
+            .text_sm();
+        for (index, (folder, depth)) in self.folders.iter().enumerate() {
+            let label = if *depth == 0 {
+                "(notes folder)".to_string()
+            } else {
+                folder
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            };
+            let is_selected = self.selected.as_ref() == Some(folder);
+            let folder_for_click = folder.clone();
+            list = list.child(
+                div()
+                    .id(("folder-picker-row", index))
+                    .pl(px(8. + 16. * *depth as f32))
+                    .py_0p5()
+                    .cursor_pointer()
+                    .when(is_selected, |row| {
+                        row.bg(cx.theme().accent)
+                            .text_color(cx.theme().accent_foreground)
+                    })
+                    .child(label)
+                    .on_click(cx.listener(move |this, event: &ClickEvent, _, cx| {
+                        this.selected = Some(folder_for_click.clone());
+                        if event.click_count() > 1 {
+                            this.confirm(cx);
+                        }
+                        cx.notify();
+                    })),
+            );
+        }
+        if self.loading {
+            list = list.child(
+                div()
+                    .pl(px(24.))
+                    .py_0p5()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("Loading folders..."),
+            );
+        }
+        let status = self
+            .selected
+            .as_ref()
+            .map(|folder| {
+                folder
+                    .strip_prefix(&self.root)
+                    .ok()
+                    .filter(|relative| !relative.as_os_str().is_empty())
+                    .map(|relative| relative.display().to_string())
+                    .unwrap_or_else(|| "(notes folder)".to_string())
+            })
+            .unwrap_or_else(|| "Select a folder".to_string());
+        let picker = cx.entity().downgrade();
+        v_flex()
+            .w_full()
+            .gap_1()
+            .p_1()
+            .border_1()
+            .border_color(cx.theme().border)
+            .child(div().text_sm().child(self.title.clone()))
+            .child(list)
+            .child(render_picker_footer(status, "Choose", cx, move |_, cx| {
+                let _ = picker.update(cx, |picker, cx| picker.confirm(cx));
+            }))
+    }
+}
+
+pub struct DateRangePicker {
+    title: SharedString,
+    month: NaiveDate,
+    selection: DateRangeSelection,
+}
+
+impl EventEmitter<PickerEvent> for DateRangePicker {}
+
+impl DateRangePicker {
+    pub fn new(
+        title: impl Into<SharedString>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Self {
+        let selection = DateRangeSelection::new(from, to);
+        let shown = selection
+            .range()
+            .map(|(_, to)| to)
+            .unwrap_or_else(|| Local::now().date_naive());
+        Self {
+            title: title.into(),
+            month: shown.with_day(1).unwrap_or(shown),
+            selection,
+        }
+    }
+
+    fn shift_month(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let shifted = if forward {
+            self.month.checked_add_months(Months::new(1))
+        } else {
+            self.month.checked_sub_months(Months::new(1))
+        };
+        if let Some(month) = shifted {
+            self.month = month;
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, cx: &mut Context<Self>) {
+        if let Some((from, to)) = self.selection.range() {
+            cx.emit(PickerEvent::Picked(PickerSelection::DateRange { from, to }));
+        }
+    }
+}
+
+impl Render for DateRangePicker {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let today = Local::now().date_naive();
+        let header = h_flex()
+            .gap_2()
+            .items_center()
+            .child(
+                Button::new("date-picker-prev")
+                    .xsmall()
+                    .ghost()
+                    .label("<")
+                    .on_click(cx.listener(|this, _, _, cx| this.shift_month(false, cx))),
+            )
+            .child(
+                div()
+                    .w(px(CALENDAR_CELL_WIDTH_PX * 5.))
+                    .text_center()
+                    .child(self.month.format("%B %Y").to_string()),
+            )
+            .child(
+                Button::new("date-picker-next")
+                    .xsmall()
+                    .ghost()
+                    .label(">")
+                    .on_click(cx.listener(|this, _, _, cx| this.shift_month(true, cx))),
+            );
+        let mut weeks =
+            v_flex()
+                .text_xs()
+                .child(
+                    h_flex().children(CALENDAR_WEEKDAY_LABELS.iter().map(|label| {
+                        div()
+                            .w(px(CALENDAR_CELL_WIDTH_PX))
+                            .text_center()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(*label)
+                    })),
+                );
+        for (week_index, week) in month_grid(self.month).chunks(7).enumerate() {
+            let mut row = h_flex();
+            for (day_index, day) in week.iter().enumerate() {
+                let cell = div().w(px(CALENDAR_CELL_WIDTH_PX)).py_0p5().text_center();
+                let Some(day) = *day else {
+                    row = row.child(cell);
+                    continue;
+                };
+                let in_range = self.selection.contains(day);
+                let is_endpoint = self.selection.is_endpoint(day);
+                row = row.child(
+                    cell.id(("date-picker-day", week_index * 7 + day_index))
+                        .rounded_sm()
+                        .cursor_pointer()
+                        .when(day == today, |cell| {
+                            cell.border_1().border_color(cx.theme().border)
+                        })
+                        .when(in_range, |cell| cell.bg(cx.theme().accent.opacity(0.4)))
+                        .when(is_endpoint, |cell| {
+                            cell.bg(cx.theme().accent)
+                                .text_color(cx.theme().accent_foreground)
+                        })
+                        .child(day.day().to_string())
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.selection.click(day);
+                            cx.notify();
+                        })),
+                );
+            }
+            weeks = weeks.child(row);
+        }
+        let status = match self.selection.range() {
+            Some((from, to)) if from == to => from.format("%Y-%m-%d").to_string(),
+            Some((from, to)) => format!(
+                "{} \u{2013} {}",
+                from.format("%Y-%m-%d"),
+                to.format("%Y-%m-%d")
+            ),
+            None => "Click a start and an end date".to_string(),
+        };
+        let picker = cx.entity().downgrade();
+        v_flex()
+            .w_full()
+            .gap_1()
+            .p_1()
+            .border_1()
+            .border_color(cx.theme().border)
+            .child(div().text_sm().child(self.title.clone()))
+            .child(header)
+            .child(weeks)
+            .child(render_picker_footer(
+                status,
+                "Use dates",
+                cx,
+                move |_, cx| {
+                    let _ = picker.update(cx, |picker, cx| picker.confirm(cx));
+                },
+            ))
+    }
+}
+
+/// One line of text, confirmed with Enter or the button.
+pub struct TextPicker {
+    title: SharedString,
+    hint: SharedString,
+    input: Entity<InputState>,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<PickerEvent> for TextPicker {}
+
+impl TextPicker {
+    pub fn new(
+        title: impl Into<SharedString>,
+        placeholder: impl Into<SharedString>,
+        hint: impl Into<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let placeholder = placeholder.into();
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder(placeholder));
+        let _subscription = cx.subscribe_in(
+            &input,
+            window,
+            |this, _, event: &InputEvent, _window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    this.confirm(cx);
+                }
+            },
+        );
+        input.update(cx, |state, cx| state.focus(window, cx));
+        Self {
+            title: title.into(),
+            hint: hint.into(),
+            input,
+            _subscription,
+        }
+    }
+
+    fn confirm(&mut self, cx: &mut Context<Self>) {
+        let text = self.input.read(cx).value().trim().to_string();
+        if !text.is_empty() {
+            cx.emit(PickerEvent::Picked(PickerSelection::Text(text)));
+        }
+    }
+}
+
+impl Render for TextPicker {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let picker = cx.entity().downgrade();
+        v_flex()
+            .w_full()
+            .gap_1()
+            .p_1()
+            .border_1()
+            .border_color(cx.theme().border)
+            .child(div().text_sm().child(self.title.clone()))
+            .child(Input::new(&self.input).small())
+            .child(render_picker_footer(
+                self.hint.to_string(),
+                "Go",
+                cx,
+                move |_, cx| {
+                    let _ = picker.update(cx, |picker, cx| picker.confirm(cx));
+                },
+            ))
+    }
+}
+
+pub(crate) type PickerCallback = Box<
+    dyn FnOnce(
+        &mut crate::app::Papyru2App,
+        PickerSelection,
+        &mut Window,
+        &mut Context<crate::app::Papyru2App>,
+    ),
+>;
+
+/// The picker shown under the top bar; only one is open at a time.
+pub(crate) struct ActivePicker {
+    view: AnyView,
+    on_pick: Option<PickerCallback>,
+    _subscription: Subscription,
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn open_folder_picker(
+        &mut self,
+        title: &str,
+        on_pick: impl FnOnce(&mut Self, PickerSelection, &mut Window, &mut Context<Self>) + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let root = self.app_paths.user_document_dir.clone();
+        let exclude = vec![self.app_paths.recyclebin_dir.clone()];
+        let title = title.to_string();
+        let picker = cx.new(|cx| FolderPicker::new(title, root, exclude, cx));
+        self.show_picker(picker, Box::new(on_pick), window, cx);
+    }
+
+    pub(crate) fn open_date_range_picker(
+        &mut self,
+        title: &str,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        on_pick: impl FnOnce(&mut Self, PickerSelection, &mut Window, &mut Context<Self>) + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let title = title.to_string();
+        let picker = cx.new(|_| DateRangePicker::new(title, from, to));
+        self.show_picker(picker, Box::new(on_pick), window, cx);
+    }
+
+    pub(crate) fn open_text_picker(
+        &mut self,
+        title: &str,
+        placeholder: &str,
+        hint: &str,
+        on_pick: impl FnOnce(&mut Self, PickerSelection, &mut Window, &mut Context<Self>) + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (title, placeholder, hint) =
+            (title.to_string(), placeholder.to_string(), hint.to_string());
+        let picker = cx.new(|cx| TextPicker::new(title, placeholder, hint, window, cx));
+        self.show_picker(picker, Box::new(on_pick), window, cx);
+    }
+
+    fn show_picker<T: Render + EventEmitter<PickerEvent>>(
+        &mut self,
+        picker: Entity<T>,
+        on_pick: PickerCallback,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let subscription = cx.subscribe_in(
+            &picker,
+            window,
+            |this, _, event: &PickerEvent, window, cx| {
+                this.finish_picker(event.clone(), window, cx)
+            },
+        );
+        self.active_picker = Some(ActivePicker {
+            view: picker.into(),
+            on_pick: Some(on_pick),
+            _subscription: subscription,
+        });
+        cx.notify();
+    }
+
+    fn finish_picker(&mut self, event: PickerEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(mut picker) = self.active_picker.take() else {
+            return;
+        };
+        trace_debug(format!("req-pick1 picker finished event={event:?}"));
+        if let (PickerEvent::Picked(selection), Some(on_pick)) = (event, picker.on_pick.take()) {
+            on_pick(self, selection, window, cx);
+        }
+        cx.notify();
+    }
+
+    pub(crate) fn render_active_picker(&self) -> Option<AnyView> {
+        self.active_picker
+            .as_ref()
+            .map(|picker| picker.view.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn pick_test1_month_grid_starts_monday_and_fills_weeks() {
+        // March 2026 starts on a Sunday.
+        let grid = month_grid(NaiveDate::from_ymd_opt(2026, 3, 15).unwrap());
+        assert_eq!(grid.len() % 7, 0);
+        assert_eq!(&grid[..6], &[None; 6]);
+        assert_eq!(grid[6], NaiveDate::from_ymd_opt(2026, 3, 1));
+        assert_eq!(grid.iter().flatten().count(), 31);
+
+        let mut selection = DateRangeSelection::default();
+        let day = |d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        selection.click(day(20));
+        assert_eq!(selection.range(), Some((day(20), day(20))));
+        selection.click(day(4));
+        assert_eq!(selection.range(), Some((day(4), day(20))));
+        selection.click(day(9));
+        assert_eq!(selection.range(), Some((day(9), day(9))));
+    }
+
+    #[test]
+    fn pick_test2_folder_list_is_tree_ordered_without_hidden_or_excluded() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_pickers_pick_test2_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(root.join("2026").join("03")).expect("create month");
+        fs::create_dir_all(root.join("projects")).expect("create projects");
+        fs::create_dir_all(root.join(".git")).expect("create git");
+        fs::create_dir_all(root.join("recyclebin")).expect("create recyclebin");
+        fs::write(root.join("note.txt"), "x").expect("write note");
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&root, root.join("projects").join("loop")).expect("link loop");
+
+        let folders =
+            list_picker_folders(root.as_path(), &[root.join("recyclebin")]).expect("list");
+        assert_eq!(
+            folders,
+            vec![
+                (root.clone(), 0),
+                (root.join("2026"), 1),
+                (root.join("2026").join("03"), 2),
+                (root.join("projects"), 1),
+            ]
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+}
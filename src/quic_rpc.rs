@@ -11,7 +11,7 @@ pub struct QuicRpcUiCommand {
 }
 
 pub fn spawn_quic_rpc_server(
-    app_paths: crate::path_resolver::AppPaths,
+    active_vault_root: crate::vaults::ActiveVaultRoot,
     file_workflow: crate::file_update_handler::SinglelineCreateFileWorkflow,
     ui_tx: smol::channel::Sender<QuicRpcUiCommand>,
 ) {
@@ -49,14 +49,14 @@ pub fn spawn_quic_rpc_server(
                 server_cert_der.len()
             ));
 
-            let user_document_dir = app_paths.user_document_dir.clone();
             let handler: Handler<crate::quic_rpc_protocol::PinFileRpcService> = Arc::new({
                 let file_workflow = file_workflow.clone();
                 let ui_tx = ui_tx.clone();
                 move |request, _rx_stream, tx_stream| {
                     let file_workflow = file_workflow.clone();
                     let ui_tx = ui_tx.clone();
-                    let user_document_dir = user_document_dir.clone();
+                    // Resolved per request so pins follow a vault switch.
+                    let user_document_dir = active_vault_root.get();
                     Box::pin(async move {
                         let response = handle_pin_file_request(
                             request,
@@ -123,7 +123,7 @@ async fn handle_pin_file_request(
         payload.linenum,
     ) {
         Ok(result) => result,
-        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+        Err(error @ crate::workflow_error::WorkflowError::NotFound { .. }) => {
             crate::log::trace_debug(format!(
                 "quic_rpc pin file not found path={} error={error}",
                 resolved_path.display()
@@ -192,6 +192,14 @@ impl crate::app::Papyru2App {
         self.editor.update(app, |editor, _| {
             editor.set_current_editing_file_path(autosave_path.clone());
         });
+        self.title_rename_debouncer
+            .on_edit_path_changed(autosave_path.as_deref());
+        if self
+            .duplicate_title_gate
+            .on_edit_path_changed(autosave_path.as_deref())
+        {
+            self.duplicate_title_notice = None;
+        }
         self.editor_autosave.on_edit_path_changed(autosave_path);
 
         if let Some(stem) =
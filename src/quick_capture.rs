@@ -0,0 +1,334 @@
+use std::{fs, path::Path};
+
+use chrono::Local;
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState, hotkey::HotKey};
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Root,
+    input::{Input, InputEvent, InputState},
+    v_flex,
+};
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+
+const QUICK_CAPTURE_WINDOW_WIDTH_PX: f32 = 480.0;
+const QUICK_CAPTURE_WINDOW_HEIGHT_PX: f32 = 72.0;
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct QuickCaptureConfigFile {
+    #[serde(default)]
+    quick_capture: QuickCaptureConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct QuickCaptureConfigSection {
+    hotkey: Option<String>,
+}
+
+// The hotkey is opt-in: a system-wide shortcut taken by default would steal it from every
+// other app (Ctrl+Alt is AltGr on Windows). Unset or empty leaves it off.
+fn quick_capture_hotkey_setting(raw: Option<&str>) -> Option<String> {
+    let configured = raw.and_then(|raw| match toml::from_str::<QuickCaptureConfigFile>(raw) {
+        Ok(parsed) => parsed.quick_capture.hotkey,
+        Err(error) => {
+            trace_debug(format!("req-qcap1 config parse failed error={error}"));
+            None
+        }
+    })?;
+    let value = configured.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// `[quick_capture] hotkey = "super+shift+N"` summons the capture window from anywhere.
+pub(crate) fn load_quick_capture_hotkey(path: &Path) -> Option<HotKey> {
+    let raw = fs::read_to_string(path).ok();
+    let value = quick_capture_hotkey_setting(raw.as_deref())?;
+    let hotkey = value.parse::<HotKey>();
+    trace_debug(format!(
+        "req-qcap1 hotkey value='{value}' parsed={:?}",
+        hotkey.as_ref().ok()
+    ));
+    hotkey.ok()
+}
+
+#[derive(Clone, Debug)]
+pub enum QuickCaptureEvent {
+    Submitted(String),
+    Dismissed,
+}
+
+/// The whole capture window: one title line. Enter creates the note, Escape drops it.
+pub struct QuickCaptureView {
+    input: Entity<InputState>,
+    ui_color_config: crate::app::UiColorConfig,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<QuickCaptureEvent> for QuickCaptureView {}
+
+impl QuickCaptureView {
+    pub fn new(
+        ui_color_config: crate::app::UiColorConfig,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(crate::i18n::tr("placeholder.quick_capture"))
+        });
+        let subscription = cx.subscribe_in(&input, window, |this, _, event: &InputEvent, _, cx| {
+            if matches!(event, InputEvent::PressEnter { .. }) {
+                this.submit(cx);
+            }
+        });
+        input.update(cx, |input, cx| input.focus(window, cx));
+        Self {
+            input,
+            ui_color_config,
+            _subscription: subscription,
+        }
+    }
+
+    pub fn set_ui_color_config(
+        &mut self,
+        ui_color_config: crate::app::UiColorConfig,
+        cx: &mut Context<Self>,
+    ) {
+        self.ui_color_config = ui_color_config;
+        cx.notify();
+    }
+
+    fn submit(&mut self, cx: &mut Context<Self>) {
+        let value = self.input.read(cx).value().trim().to_string();
+        if value.is_empty() {
+            cx.emit(QuickCaptureEvent::Dismissed);
+        } else {
+            cx.emit(QuickCaptureEvent::Submitted(value));
+        }
+    }
+
+    fn on_key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if event.keystroke.key.eq_ignore_ascii_case("escape") {
+            cx.emit(QuickCaptureEvent::Dismissed);
+            cx.stop_propagation();
+        } else {
+            cx.propagate();
+        }
+    }
+}
+
+impl Render for QuickCaptureView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .p_3()
+            .gap_1()
+            .bg(crate::app::req_colr_rgb_hex_to_hsla(
+                self.ui_color_config.background_rgb_hex,
+            ))
+            .text_color(crate::app::req_colr_rgb_hex_to_hsla(
+                self.ui_color_config.foreground_rgb_hex,
+            ))
+            .on_key_down(cx.listener(Self::on_key_down))
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(tr("label.quick_capture_header")),
+            )
+            .child(Input::new(&self.input))
+    }
+}
+
+/// The open capture window; it is closed on submit or dismiss.
+pub(crate) struct QuickCaptureWindow {
+    handle: AnyWindowHandle,
+    pub(crate) view: Entity<QuickCaptureView>,
+    _subscription: Subscription,
+}
+
+impl crate::app::Papyru2App {
+    /// Registers the system-wide hotkey. The returned manager keeps the registration alive;
+    /// `None` when the platform refuses it (taken by another app, or no X11 on Linux).
+    pub(crate) fn register_quick_capture_hotkey(
+        hotkey: HotKey,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<GlobalHotKeyManager> {
+        let registered = GlobalHotKeyManager::new().and_then(|manager| {
+            manager.register(hotkey)?;
+            Ok(manager)
+        });
+        let manager = match registered {
+            Ok(manager) => manager,
+            Err(error) => {
+                trace_debug(format!(
+                    "req-qcap1 hotkey registration failed hotkey={hotkey:?} error={error}"
+                ));
+                return None;
+            }
+        };
+        let hotkey_id = hotkey.id();
+        let (pressed_tx, pressed_rx) = smol::channel::unbounded::<()>();
+        GlobalHotKeyEvent::set_event_handler(Some(move |event: GlobalHotKeyEvent| {
+            if event.id == hotkey_id && event.state == HotKeyState::Pressed {
+                let _ = pressed_tx.try_send(());
+            }
+        }));
+        cx.spawn_in(window, async move |this, cx| {
+            while pressed_rx.recv().await.is_ok() {
+                let updated = this.update_in(cx, |app, _, cx| app.open_quick_capture(cx));
+                if updated.is_err() {
+                    break;
+                }
+            }
+            trace_debug("req-qcap1 hotkey loop detached");
+        })
+        .detach();
+        Some(manager)
+    }
+
+    /// Opens the capture window on top of whatever has focus, leaving the main window where
+    /// it is; a second press brings an already open capture window forward.
+    pub(crate) fn open_quick_capture(&mut self, cx: &mut Context<Self>) {
+        if let Some(open) = self.quick_capture.as_ref() {
+            let activated = open
+                .handle
+                .update(cx, |_, window, _| window.activate_window());
+            if activated.is_ok() {
+                return;
+            }
+            self.quick_capture = None;
+        }
+        let bounds = Bounds::centered(
+            None,
+            size(
+                px(QUICK_CAPTURE_WINDOW_WIDTH_PX),
+                px(QUICK_CAPTURE_WINDOW_HEIGHT_PX),
+            ),
+            cx,
+        );
+        let options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(bounds)),
+            titlebar: None,
+            focus: true,
+            show: true,
+            kind: WindowKind::PopUp,
+            is_resizable: false,
+            ..Default::default()
+        };
+        let mut capture_view = None;
+        let ui_color_config = self.ui_color_config;
+        let opened = cx.open_window(options, |window, cx| {
+            let view = cx.new(|cx| QuickCaptureView::new(ui_color_config, window, cx));
+            capture_view = Some(view.clone());
+            cx.new(|cx| Root::new(view, window, cx))
+        });
+        let (handle, view) = match (opened, capture_view) {
+            (Ok(handle), Some(view)) => (handle, view),
+            (Err(error), _) => {
+                trace_debug(format!("req-qcap1 capture window failed error={error}"));
+                return;
+            }
+            (Ok(_), None) => return,
+        };
+        let subscription = cx.subscribe(&view, |app, _, event, cx| {
+            app.handle_quick_capture_event(event, cx);
+        });
+        self.quick_capture = Some(QuickCaptureWindow {
+            handle: handle.into(),
+            view,
+            _subscription: subscription,
+        });
+    }
+
+    fn handle_quick_capture_event(&mut self, event: &QuickCaptureEvent, cx: &mut Context<Self>) {
+        if let Some(open) = self.quick_capture.take() {
+            let _ = open
+                .handle
+                .update(cx, |_, window, _| window.remove_window());
+        }
+        let QuickCaptureEvent::Submitted(title) = event else {
+            trace_debug("req-qcap1 capture dismissed".to_string());
+            return;
+        };
+        // Created beside whatever the editor holds; the open note stays open. The worker may
+        // be busy with a long batch, so the window closes now and the notice follows.
+        let (result_tx, result_rx) = smol::channel::bounded(1);
+        if let Err(error) = self.file_workflow.create_detached_async(
+            title,
+            self.app_paths.user_document_dir.as_path(),
+            Local::now(),
+            move |result| {
+                let _ = result_tx.try_send(result);
+            },
+        ) {
+            self.complete_quick_capture(Err(error), cx);
+            return;
+        }
+        cx.spawn(async move |this, cx| {
+            let Ok(result) = result_rx.recv().await else {
+                return;
+            };
+            let _ = this.update(cx, |app, cx| app.complete_quick_capture(result, cx));
+        })
+        .detach();
+    }
+
+    fn complete_quick_capture(
+        &mut self,
+        result: crate::workflow_error::WorkflowResult<std::path::PathBuf>,
+        cx: &mut Context<Self>,
+    ) {
+        match result {
+            Ok(path) => {
+                trace_debug(format!("req-qcap1 captured path={}", path.display()));
+                self.file_tree.update(cx, |file_tree, cx| {
+                    file_tree.mark_notes_looked_at(std::slice::from_ref(&path), cx);
+                });
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                self.show_transient_notice(
+                    tr_with("notice.captured", &[("name", name.as_str())]),
+                    cx,
+                );
+            }
+            Err(error) => {
+                self.show_transient_notice(
+                    tr_with(
+                        "notice.quick_capture_failed",
+                        &[("reason", error.to_string().as_str())],
+                    ),
+                    cx,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qcap_test1_hotkey_is_off_unless_configured() {
+        assert_eq!(quick_capture_hotkey_setting(None), None);
+        assert_eq!(
+            quick_capture_hotkey_setting(Some("[editor]\nfont_size = 14\n")),
+            None
+        );
+        assert_eq!(
+            quick_capture_hotkey_setting(Some(
+                "[quick_capture]\nhotkey = \" super+shift+Space \"\n"
+            ))
+            .as_deref(),
+            Some("super+shift+Space")
+        );
+        assert_eq!(
+            quick_capture_hotkey_setting(Some("[quick_capture]\nhotkey = \"\"\n")),
+            None
+        );
+    }
+}
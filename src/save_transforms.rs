@@ -0,0 +1,165 @@
+use std::{
+    borrow::Cow,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::log::trace_debug;
+
+/// Rewrites applied to the editor text on its way to disk; the buffer itself is left alone
+/// so the cursor does not jump while typing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct SaveTransforms {
+    pub trim_trailing_whitespace: bool,
+    pub ensure_final_newline: bool,
+}
+
+/// `[vaults.on_save]` in `conf/vaults.toml`; unset keys follow `[on_save]` in the main config.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SaveTransformsOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim_trailing_whitespace: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ensure_final_newline: Option<bool>,
+}
+
+impl SaveTransforms {
+    pub fn with_override(self, vault: Option<&SaveTransformsOverride>) -> Self {
+        let Some(vault) = vault else {
+            return self;
+        };
+        Self {
+            trim_trailing_whitespace: vault
+                .trim_trailing_whitespace
+                .unwrap_or(self.trim_trailing_whitespace),
+            ensure_final_newline: vault
+                .ensure_final_newline
+                .unwrap_or(self.ensure_final_newline),
+        }
+    }
+
+    /// Line endings are kept as they are; an empty note stays empty.
+    pub fn apply(self, text: &str) -> Cow<'_, str> {
+        let mut out = Cow::Borrowed(text);
+        if self.trim_trailing_whitespace {
+            let trimmed = text
+                .split_inclusive('\n')
+                .map(|line| {
+                    let (body, ending) = match line.strip_suffix("\r\n") {
+                        Some(body) => (body, "\r\n"),
+                        None => match line.strip_suffix('\n') {
+                            Some(body) => (body, "\n"),
+                            None => (line, ""),
+                        },
+                    };
+                    format!("{}{ending}", body.trim_end_matches([' ', '\t']))
+                })
+                .collect::<String>();
+            if trimmed != text {
+                out = Cow::Owned(trimmed);
+            }
+        }
+        if self.ensure_final_newline && !out.is_empty() && !out.ends_with('\n') {
+            let ending = if out.contains("\r\n") { "\r\n" } else { "\n" };
+            out.to_mut().push_str(ending);
+        }
+        out
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SaveTransformsConfigFile {
+    #[serde(default)]
+    on_save: SaveTransformsOverride,
+}
+
+/// `[on_save]` in the main config: both transforms are off unless turned on.
+pub(crate) fn load_save_transforms(path: &Path) -> SaveTransforms {
+    let parsed = fs::read_to_string(path).ok().and_then(|raw| {
+        toml::from_str::<SaveTransformsConfigFile>(&raw)
+            .map_err(|error| {
+                trace_debug(format!("req-sav1 config parse failed error={error}"));
+            })
+            .ok()
+    });
+    SaveTransforms::default().with_override(parsed.as_ref().map(|parsed| &parsed.on_save))
+}
+
+#[derive(Debug, Default)]
+struct ActiveSaveTransforms {
+    root: PathBuf,
+    vault: SaveTransforms,
+    base: SaveTransforms,
+}
+
+static ACTIVE_SAVE_TRANSFORMS: OnceLock<Mutex<ActiveSaveTransforms>> = OnceLock::new();
+
+fn active_save_transforms_lock() -> &'static Mutex<ActiveSaveTransforms> {
+    ACTIVE_SAVE_TRANSFORMS.get_or_init(|| Mutex::new(ActiveSaveTransforms::default()))
+}
+
+/// Set together with the storage backend when a vault becomes active.
+pub(crate) fn activate_save_transforms(
+    root: &Path,
+    base: SaveTransforms,
+    vault: Option<&SaveTransformsOverride>,
+) {
+    let active = ActiveSaveTransforms {
+        root: root.to_path_buf(),
+        vault: base.with_override(vault),
+        base,
+    };
+    trace_debug(format!(
+        "req-sav1 on_save root={} transforms={:?}",
+        root.display(),
+        active.vault
+    ));
+    *active_save_transforms_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = active;
+}
+
+/// What the autosave worker applies to a note under `user_document_dir`.
+pub(crate) fn save_transforms_for(user_document_dir: &Path) -> SaveTransforms {
+    let active = active_save_transforms_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if active.root == user_document_dir {
+        active.vault
+    } else {
+        active.base
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sav_test1_transforms_trim_lines_and_end_with_one_newline() {
+        let both = SaveTransforms {
+            trim_trailing_whitespace: true,
+            ensure_final_newline: true,
+        };
+        assert_eq!(both.apply("a  \r\nb\t\r\nc "), "a\r\nb\r\nc\r\n");
+        assert_eq!(both.apply("a \nb"), "a\nb\n");
+        assert_eq!(both.apply(""), "");
+        assert!(matches!(both.apply("done\n"), Cow::Borrowed(_)));
+        assert_eq!(SaveTransforms::default().apply("a  "), "a  ");
+
+        let vault = SaveTransformsOverride {
+            trim_trailing_whitespace: Some(false),
+            ensure_final_newline: None,
+        };
+        assert_eq!(
+            both.with_override(Some(&vault)),
+            SaveTransforms {
+                trim_trailing_whitespace: false,
+                ensure_final_newline: true,
+            }
+        );
+    }
+}
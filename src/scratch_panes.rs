@@ -0,0 +1,346 @@
+use std::rc::Rc;
+
+use chrono::Local;
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{Input, InputState},
+    v_flex,
+};
+
+pub(crate) const SCRATCH_BADGE_TEXT: &str = "SCRATCH - not saved";
+
+/// Runs the regular close sequence (editor flush, window position save); `false` aborts.
+pub(crate) type FinishWindowClose = Rc<dyn Fn(&mut Window, &mut App) -> bool>;
+
+// Scratch panes live only in memory: their inputs are never subscribed by the autosave
+// coordinator or the file workflow, so nothing typed here reaches disk unless promoted.
+pub(crate) struct ScratchPane {
+    id: u64,
+    input: Entity<InputState>,
+}
+
+pub struct ScratchPanes {
+    panes: Vec<ScratchPane>,
+    active: Option<usize>,
+    next_id: u64,
+    close_prompt_open: bool,
+}
+
+/// The first non-blank line names the promoted note, like the singleline subject does.
+pub(crate) fn scratch_first_line(text: &str) -> &str {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("")
+}
+
+pub(crate) fn is_scratch_text_promotable(text: &str) -> bool {
+    !text.trim().is_empty()
+}
+
+impl ScratchPanes {
+    pub fn new() -> Self {
+        Self {
+            panes: Vec::new(),
+            active: None,
+            next_id: 1,
+            close_prompt_open: false,
+        }
+    }
+
+    pub fn has_panes(&self) -> bool {
+        !self.panes.is_empty()
+    }
+
+    pub fn is_scratch_active(&self) -> bool {
+        self.active.is_some()
+    }
+
+    pub fn is_focused(&self, window: &Window, cx: &App) -> bool {
+        self.active
+            .and_then(|index| self.panes.get(index))
+            .is_some_and(|pane| pane.input.read(cx).focus_handle(cx).is_focused(window))
+    }
+
+    pub fn add_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .placeholder(crate::i18n::tr("placeholder.scratch"))
+        });
+        input.update(cx, |state, cx| state.focus(window, cx));
+        let id = self.next_id;
+        self.next_id += 1;
+        self.panes.push(ScratchPane { id, input });
+        self.active = Some(self.panes.len() - 1);
+        crate::log::trace_debug(format!(
+            "req-scr1 scratch add id={id} panes={}",
+            self.panes.len()
+        ));
+        cx.notify();
+    }
+
+    fn activate(&mut self, index: Option<usize>, window: &mut Window, cx: &mut Context<Self>) {
+        self.active = index.filter(|index| *index < self.panes.len());
+        if let Some(pane) = self.active.and_then(|index| self.panes.get(index)) {
+            pane.input.update(cx, |state, cx| state.focus(window, cx));
+        }
+        cx.notify();
+    }
+
+    fn close_pane(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index >= self.panes.len() {
+            return;
+        }
+        let pane = self.panes.remove(index);
+        crate::log::trace_debug(format!(
+            "req-scr1 scratch close id={} panes={}",
+            pane.id,
+            self.panes.len()
+        ));
+        self.active = match self.active {
+            Some(active) if active == index => None,
+            Some(active) if active > index => Some(active - 1),
+            active => active,
+        };
+        cx.notify();
+    }
+
+    /// `(pane id, text)` for every pane with content worth keeping.
+    pub fn promotable_texts(&self, cx: &App) -> Vec<(u64, String)> {
+        self.panes
+            .iter()
+            .map(|pane| (pane.id, pane.input.read(cx).value().to_string()))
+            .filter(|(_, text)| is_scratch_text_promotable(text))
+            .collect()
+    }
+
+    fn remove_panes(&mut self, ids: &[u64], cx: &mut Context<Self>) {
+        self.panes.retain(|pane| !ids.contains(&pane.id));
+        self.active = None;
+        cx.notify();
+    }
+
+    fn clear(&mut self, cx: &mut Context<Self>) {
+        self.panes.clear();
+        self.active = None;
+        cx.notify();
+    }
+}
+
+impl Render for ScratchPanes {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut tabs = h_flex().gap_1().child(
+            Button::new("scratch-tab-note")
+                .small()
+                .label(crate::i18n::tr("label.note"))
+                .when(self.active.is_none(), |button| button.primary())
+                .when(self.active.is_some(), |button| button.ghost())
+                .on_click(cx.listener(|this, _, window, cx| this.activate(None, window, cx))),
+        );
+        for (index, pane) in self.panes.iter().enumerate() {
+            let selected = self.active == Some(index);
+            tabs = tabs.child(
+                h_flex()
+                    .child(
+                        Button::new(("scratch-tab", pane.id))
+                            .small()
+                            .label(format!("Scratch {}", pane.id))
+                            .when(selected, |button| button.primary())
+                            .when(!selected, |button| button.ghost())
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.activate(Some(index), window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new(("scratch-tab-close", pane.id))
+                            .ghost()
+                            .xsmall()
+                            .label("x")
+                            .on_click(
+                                cx.listener(move |this, _, _, cx| this.close_pane(index, cx)),
+                            ),
+                    ),
+            );
+        }
+        tabs = tabs.child(
+            Button::new("scratch-tab-add")
+                .ghost()
+                .small()
+                .label("+")
+                .on_click(cx.listener(|this, _, window, cx| this.add_pane(window, cx))),
+        );
+
+        let active_input = self
+            .active
+            .and_then(|index| self.panes.get(index))
+            .map(|pane| pane.input.clone());
+        v_flex()
+            .w_full()
+            .gap_1()
+            .when(active_input.is_some(), |panel| panel.h_full())
+            .child(tabs)
+            .when_some(active_input, |panel, input| {
+                panel
+                    .child(
+                        div()
+                            .px_2()
+                            .text_xs()
+                            .bg(cx.theme().warning)
+                            .text_color(cx.theme().warning_foreground)
+                            .child(SCRATCH_BADGE_TEXT),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_h_0()
+                            .child(Input::new(&input).size_full()),
+                    )
+            })
+    }
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn add_scratch_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.scratch_panes
+            .update(cx, |scratch_panes, cx| scratch_panes.add_pane(window, cx));
+        cx.notify();
+    }
+
+    /// Promotes every non-empty scratch pane through the create workflow. Promoted panes are
+    /// removed; returns `false` (keeping the failed panes) when any promotion failed.
+    pub(crate) fn promote_scratch_panes(&mut self, cx: &mut Context<Self>) -> bool {
+        let texts = self.scratch_panes.read(cx).promotable_texts(cx);
+        let mut promoted = Vec::new();
+        let mut failed = 0usize;
+        for (id, text) in texts {
+            match self.file_workflow.promote_scratch_blocking(
+                scratch_first_line(&text),
+                &text,
+                self.app_paths.user_document_dir.as_path(),
+                Local::now(),
+            ) {
+                Ok(path) => {
+                    crate::log::trace_debug(format!(
+                        "req-scr1 scratch promoted id={id} path={}",
+                        path.display()
+                    ));
+                    self.record_note_tags(path.as_path(), &text);
+                    self.file_tree.update(cx, |file_tree, cx| {
+                        file_tree.mark_notes_looked_at(std::slice::from_ref(&path), cx);
+                    });
+                    promoted.push(id);
+                }
+                Err(error) => {
+                    crate::log::trace_debug(format!(
+                        "req-scr1 scratch promote failed id={id} error={error}"
+                    ));
+                    failed += 1;
+                }
+            }
+        }
+        self.scratch_panes.update(cx, |scratch_panes, cx| {
+            scratch_panes.remove_panes(&promoted, cx)
+        });
+        self.sync_tag_sidebar(cx);
+        if failed > 0 {
+            self.show_transient_notice(
+                crate::i18n::tr_with(
+                    "notice.scratch_save_failed",
+                    &[("count", failed.to_string().as_str())],
+                ),
+                cx,
+            );
+            return false;
+        }
+        true
+    }
+}
+
+/// Window-close hook: with no scratch content the regular close runs immediately; otherwise
+/// the user chooses to promote, discard, or cancel before the window goes away.
+pub(crate) fn confirm_scratch_panes_before_close(
+    view: &Entity<crate::app::Papyru2App>,
+    finish_close: &FinishWindowClose,
+    window: &mut Window,
+    cx: &mut App,
+) -> bool {
+    let scratch_panes = view.read(cx).scratch_panes.clone();
+    let pending = scratch_panes.read(cx).promotable_texts(cx).len();
+    if pending == 0 {
+        return finish_close(window, cx);
+    }
+    if scratch_panes.read(cx).close_prompt_open {
+        return false;
+    }
+    scratch_panes.update(cx, |scratch_panes, _| {
+        scratch_panes.close_prompt_open = true
+    });
+    crate::log::trace_debug(format!("req-scr1 close prompt scratch_panes={pending}"));
+
+    let detail = crate::i18n::tr_with(
+        "prompt.scratch_close_detail",
+        &[("count", pending.to_string().as_str())],
+    );
+    let buttons = [
+        crate::i18n::tr("button.save_as_notes"),
+        crate::i18n::tr("button.discard"),
+        crate::i18n::tr("button.cancel"),
+    ];
+    let answer = window.prompt(
+        PromptLevel::Warning,
+        &crate::i18n::tr("prompt.scratch_close"),
+        Some(&detail),
+        &[
+            buttons[0].as_ref(),
+            buttons[1].as_ref(),
+            buttons[2].as_ref(),
+        ],
+        cx,
+    );
+    let view = view.clone();
+    let finish_close = finish_close.clone();
+    window
+        .spawn(cx, async move |cx| {
+            let choice = answer.await.ok();
+            let _ = cx.update(|window, cx| {
+                scratch_panes.update(cx, |scratch_panes, _| {
+                    scratch_panes.close_prompt_open = false
+                });
+                crate::log::trace_debug(format!("req-scr1 close prompt choice={choice:?}"));
+                let proceed = match choice {
+                    Some(0) => cx.update_entity(&view, |app, cx| app.promote_scratch_panes(cx)),
+                    Some(1) => {
+                        scratch_panes.update(cx, |scratch_panes, cx| scratch_panes.clear(cx));
+                        true
+                    }
+                    _ => false,
+                };
+                if proceed && finish_close(window, cx) {
+                    window.remove_window();
+                }
+            });
+        })
+        .detach();
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scratch_test1_first_line_names_note_and_blank_panes_are_not_promoted() {
+        assert_eq!(
+            scratch_first_line("\n  \n  pasted log  \nrest"),
+            "pasted log"
+        );
+        assert_eq!(scratch_first_line(""), "");
+        assert!(is_scratch_text_promotable(" x "));
+        assert!(!is_scratch_text_promotable(" \n\t\n"));
+    }
+}
@@ -0,0 +1,755 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local, NaiveDate};
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{Input, InputState},
+    v_flex,
+};
+
+use crate::workflow_error::WorkflowResult;
+
+pub(crate) const SEARCH_REPLACE_MAX_PREVIEW_ROWS: usize = 200;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ReplacePreviewMatch {
+    pub path: PathBuf,
+    pub line_1_based: usize,
+    pub line_text: String,
+    pub occurrences: usize,
+}
+
+#[derive(Clone, Debug)]
+pub enum SearchReplaceEvent {
+    ApplyRequested {
+        paths: Vec<PathBuf>,
+        find: String,
+        replace: String,
+    },
+    PickFolder,
+    PickDateRange {
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    },
+    Closed,
+}
+
+// Selected folders expand to every note below them; an empty selection means the whole vault.
+pub(crate) fn collect_replace_scope(
+    selected: &[PathBuf],
+    user_document_dir: &Path,
+) -> Vec<PathBuf> {
+    let roots = if selected.is_empty() {
+        vec![user_document_dir.to_path_buf()]
+    } else {
+        selected.to_vec()
+    };
+
+    let mut paths = Vec::new();
+    for root in roots {
+        if root.is_dir() {
+            if let Err(error) = crate::tag_index::collect_note_paths(root.as_path(), &mut paths) {
+                crate::log::trace_debug(format!(
+                    "req-rep1 scope scan failed root={} error={error}",
+                    root.display()
+                ));
+            }
+        } else if crate::file_update_handler::NoteFileExtension::from_path(root.as_path()).is_some()
+        {
+            paths.push(root);
+        }
+    }
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Inclusive `YYYY-MM-DD` bounds; either side may be open.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct NoteDateRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl NoteDateRange {
+    pub fn parse(from: &str, to: &str) -> Result<Self, String> {
+        let parse_bound = |label: &str, value: &str| {
+            let value = value.trim();
+            if value.is_empty() {
+                return Ok(None);
+            }
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(Some)
+                .map_err(|_| format!("{label} date '{value}' is not YYYY-MM-DD"))
+        };
+        let range = Self {
+            from: parse_bound("From", from)?,
+            to: parse_bound("To", to)?,
+        };
+        if let (Some(from), Some(to)) = (range.from, range.to)
+            && from > to
+        {
+            return Err(format!("From date {from} is after To date {to}"));
+        }
+        Ok(range)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.from.is_none() && self.to.is_none()
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.from.is_none_or(|from| date >= from) && self.to.is_none_or(|to| date <= to)
+    }
+}
+
+pub(crate) fn filter_scope_by_date(
+    paths: &[PathBuf],
+    range: NoteDateRange,
+    user_document_dir: &Path,
+) -> Vec<PathBuf> {
+    if range.is_open() {
+        return paths.to_vec();
+    }
+    paths
+        .iter()
+        .filter(|path| {
+            crate::storage_layout::note_date(path.as_path(), user_document_dir)
+                .is_some_and(|date| range.contains(date))
+        })
+        .cloned()
+        .collect()
+}
+
+pub(crate) fn preview_replace_matches(paths: &[PathBuf], find: &str) -> Vec<ReplacePreviewMatch> {
+    if find.is_empty() {
+        return Vec::new();
+    }
+    let mut matches = Vec::new();
+    for path in paths {
+        let Ok(text) = fs::read_to_string(path) else {
+            continue;
+        };
+        for (index, line) in text.lines().enumerate() {
+            let occurrences = line.matches(find).count();
+            if occurrences > 0 {
+                matches.push(ReplacePreviewMatch {
+                    path: path.clone(),
+                    line_1_based: index + 1,
+                    line_text: line.trim().to_string(),
+                    occurrences,
+                });
+            }
+        }
+    }
+    matches
+}
+
+fn preview_matched_paths(preview: &[ReplacePreviewMatch]) -> Vec<PathBuf> {
+    let mut paths = preview
+        .iter()
+        .map(|entry| entry.path.clone())
+        .collect::<Vec<_>>();
+    paths.dedup();
+    paths
+}
+
+pub struct SearchReplacePanel {
+    find_input: Entity<InputState>,
+    replace_input: Entity<InputState>,
+    date_from_input: Entity<InputState>,
+    date_to_input: Entity<InputState>,
+    user_document_dir: PathBuf,
+    scope_label: String,
+    base_scope: Vec<PathBuf>,
+    scope: Vec<PathBuf>,
+    preview: Vec<ReplacePreviewMatch>,
+    preview_find: String,
+    // Bumped for every preview started; a result from an older one is dropped.
+    preview_generation: u64,
+    preview_running: bool,
+    report: Option<SharedString>,
+    visible: bool,
+}
+
+impl EventEmitter<SearchReplaceEvent> for SearchReplacePanel {}
+
+impl SearchReplacePanel {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let find_input = cx
+            .new(|cx| InputState::new(window, cx).placeholder(crate::i18n::tr("placeholder.find")));
+        let replace_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(crate::i18n::tr("placeholder.replace_with"))
+        });
+        let date_from_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(crate::i18n::tr("placeholder.date_from"))
+        });
+        let date_to_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(crate::i18n::tr("placeholder.date_to"))
+        });
+        Self {
+            find_input,
+            replace_input,
+            date_from_input,
+            date_to_input,
+            user_document_dir: PathBuf::new(),
+            scope_label: String::new(),
+            base_scope: Vec::new(),
+            scope: Vec::new(),
+            preview: Vec::new(),
+            preview_find: String::new(),
+            preview_generation: 0,
+            preview_running: false,
+            report: None,
+            visible: false,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn open(
+        &mut self,
+        scope: Vec<PathBuf>,
+        scope_label: String,
+        user_document_dir: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        crate::log::trace_debug(format!(
+            "req-rep1 panel open scope_files={} scope='{scope_label}'",
+            scope.len()
+        ));
+        self.scope = scope.clone();
+        self.base_scope = scope;
+        self.scope_label = scope_label;
+        self.user_document_dir = user_document_dir;
+        self.clear_preview();
+        self.report = None;
+        self.visible = true;
+        self.find_input
+            .update(cx, |state, cx| state.focus(window, cx));
+        cx.notify();
+    }
+
+    pub fn close(&mut self, cx: &mut Context<Self>) {
+        if !self.visible {
+            return;
+        }
+        self.visible = false;
+        cx.emit(SearchReplaceEvent::Closed);
+        cx.notify();
+    }
+
+    pub fn set_report(&mut self, summary: String, cx: &mut Context<Self>) {
+        self.report = Some(summary.into());
+        // Files changed on disk; the old preview no longer describes them.
+        self.clear_preview();
+        cx.notify();
+    }
+
+    fn clear_preview(&mut self) {
+        self.preview.clear();
+        self.preview_find.clear();
+        self.preview_generation += 1;
+        self.preview_running = false;
+    }
+
+    pub fn set_date_range(
+        &mut self,
+        from: NaiveDate,
+        to: NaiveDate,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let from = from.format("%Y-%m-%d").to_string();
+        let to = to.format("%Y-%m-%d").to_string();
+        self.date_from_input
+            .update(cx, |state, cx| state.set_value(from, window, cx));
+        self.date_to_input
+            .update(cx, |state, cx| state.set_value(to, window, cx));
+        self.refresh_preview(cx);
+    }
+
+    // The calendar opens on whatever the date inputs already hold, ignoring unparsable text.
+    fn request_date_range_pick(&mut self, cx: &mut Context<Self>) {
+        let range = NoteDateRange::parse(
+            &self.date_from_input.read(cx).value().to_string(),
+            &self.date_to_input.read(cx).value().to_string(),
+        )
+        .unwrap_or_default();
+        cx.emit(SearchReplaceEvent::PickDateRange {
+            from: range.from,
+            to: range.to,
+        });
+    }
+
+    // Reading every note in scope can take a while on a large vault, so the preview is built
+    // in the background; the old one stays on screen until the new one is ready.
+    fn refresh_preview(&mut self, cx: &mut Context<Self>) {
+        let find = self.find_input.read(cx).value().to_string();
+        let date_from = self.date_from_input.read(cx).value().to_string();
+        let date_to = self.date_to_input.read(cx).value().to_string();
+        let range = match NoteDateRange::parse(&date_from, &date_to) {
+            Ok(range) => range,
+            Err(message) => {
+                self.clear_preview();
+                self.report = Some(message.into());
+                cx.notify();
+                return;
+            }
+        };
+        self.preview_generation += 1;
+        self.preview_running = true;
+        let generation = self.preview_generation;
+        let base_scope = self.base_scope.clone();
+        let user_document_dir = self.user_document_dir.clone();
+        let preview = cx.background_executor().spawn(async move {
+            let scope = filter_scope_by_date(&base_scope, range, user_document_dir.as_path());
+            let preview = preview_replace_matches(&scope, &find);
+            (scope, preview, find)
+        });
+        cx.spawn(async move |this, cx| {
+            let (scope, preview, find) = preview.await;
+            let _ = this.update(cx, |panel, cx| {
+                if generation != panel.preview_generation {
+                    return;
+                }
+                crate::log::trace_debug(format!(
+                    "req-rep1 preview range={range:?} scope_files={} matched_lines={}",
+                    scope.len(),
+                    preview.len()
+                ));
+                panel.scope = scope;
+                panel.preview = preview;
+                panel.preview_find = find;
+                panel.preview_running = false;
+                panel.report = None;
+                cx.notify();
+            });
+        })
+        .detach();
+        cx.notify();
+    }
+
+    // Apply only what the user has previewed: a changed search text re-runs the preview first.
+    fn request_apply(&mut self, cx: &mut Context<Self>) {
+        let find = self.find_input.read(cx).value().to_string();
+        if find.is_empty() || self.preview_running {
+            return;
+        }
+        if find != self.preview_find {
+            self.refresh_preview(cx);
+            return;
+        }
+        let paths = preview_matched_paths(&self.preview);
+        if paths.is_empty() {
+            return;
+        }
+        let replace = self.replace_input.read(cx).value().to_string();
+        cx.emit(SearchReplaceEvent::ApplyRequested {
+            paths,
+            find,
+            replace,
+        });
+    }
+
+    fn on_key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        match event.keystroke.key.as_str().to_ascii_lowercase().as_str() {
+            "escape" => {
+                self.close(cx);
+                cx.stop_propagation();
+            }
+            "enter" | "return" => {
+                self.refresh_preview(cx);
+                cx.stop_propagation();
+            }
+            _ => cx.propagate(),
+        }
+    }
+
+    fn render_preview_rows(&self) -> impl IntoElement {
+        let mut rows = v_flex().w_full().text_xs();
+        for entry in self.preview.iter().take(SEARCH_REPLACE_MAX_PREVIEW_ROWS) {
+            let file_name = entry
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            rows = rows.child(
+                h_flex()
+                    .gap_2()
+                    .child(format!("{}:{}", file_name, entry.line_1_based))
+                    .child(
+                        div()
+                            .flex_1()
+                            .overflow_hidden()
+                            .whitespace_nowrap()
+                            .child(crate::app::compact_text(&entry.line_text)),
+                    ),
+            );
+        }
+        if self.preview.len() > SEARCH_REPLACE_MAX_PREVIEW_ROWS {
+            rows = rows.child(format!(
+                "… {} more line(s)",
+                self.preview.len() - SEARCH_REPLACE_MAX_PREVIEW_ROWS
+            ));
+        }
+        rows
+    }
+}
+
+impl Render for SearchReplacePanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let occurrences = self
+            .preview
+            .iter()
+            .map(|entry| entry.occurrences)
+            .sum::<usize>();
+        let status = match self.report.clone() {
+            Some(report) => report,
+            None if self.preview_running => "Searching…".into(),
+            None if self.preview_find.is_empty() => {
+                format!("{} file(s) in {}", self.scope.len(), self.scope_label).into()
+            }
+            None => format!(
+                "{} occurrence(s) in {} file(s) of {} in {}",
+                occurrences,
+                preview_matched_paths(&self.preview).len(),
+                self.scope.len(),
+                self.scope_label
+            )
+            .into(),
+        };
+
+        v_flex()
+            .w_full()
+            .gap_1()
+            .p_1()
+            .border_1()
+            .border_color(cx.theme().border)
+            .on_key_down(cx.listener(Self::on_key_down))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(div().flex_1().child(Input::new(&self.find_input)))
+                    .child(div().flex_1().child(Input::new(&self.replace_input)))
+                    .child(
+                        Button::new("search-replace-preview")
+                            .small()
+                            .label(crate::i18n::tr("button.preview"))
+                            .on_click(cx.listener(|this, _, _, cx| this.refresh_preview(cx))),
+                    )
+                    .child(
+                        Button::new("search-replace-apply")
+                            .small()
+                            .primary()
+                            .label(crate::i18n::tr("button.replace_all"))
+                            .on_click(cx.listener(|this, _, _, cx| this.request_apply(cx))),
+                    )
+                    .child(
+                        Button::new("search-replace-close")
+                            .ghost()
+                            .small()
+                            .label("×")
+                            .on_click(cx.listener(|this, _, _, cx| this.close(cx))),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(div().w(px(140.)).child(Input::new(&self.date_from_input)))
+                    .child(div().w(px(140.)).child(Input::new(&self.date_to_input)))
+                    .child(
+                        Button::new("search-replace-pick-dates")
+                            .small()
+                            .ghost()
+                            .label(crate::i18n::tr("button.pick_dates"))
+                            .on_click(
+                                cx.listener(|this, _, _, cx| this.request_date_range_pick(cx)),
+                            ),
+                    )
+                    .child(
+                        Button::new("search-replace-pick-folder")
+                            .small()
+                            .ghost()
+                            .label(crate::i18n::tr("button.pick_folder"))
+                            .on_click(
+                                cx.listener(|_, _, _, cx| cx.emit(SearchReplaceEvent::PickFolder)),
+                            ),
+                    )
+                    .child(div().flex_1().text_xs().child(status)),
+            )
+            .child(self.render_preview_rows())
+    }
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn toggle_search_replace(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.search_replace.read(cx).is_visible() {
+            self.search_replace.update(cx, |panel, cx| panel.close(cx));
+            return;
+        }
+        let selected = self.file_tree.read(cx).selected_paths();
+        let scope_label = match selected.as_slice() {
+            [] => "the whole vault".to_string(),
+            [single] => single
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| single.display().to_string()),
+            _ => format!("{} selected items", selected.len()),
+        };
+        self.open_search_replace(selected, scope_label, window, cx);
+    }
+
+    pub(crate) fn search_in_folder(
+        &mut self,
+        folder: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let scope_label = folder
+            .file_name()
+            .map(|name| format!("folder {}", name.to_string_lossy()))
+            .unwrap_or_else(|| "the whole vault".to_string());
+        self.open_search_replace(vec![folder], scope_label, window, cx);
+    }
+
+    fn open_search_replace(
+        &mut self,
+        roots: Vec<PathBuf>,
+        scope_label: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let user_document_dir = self.app_paths.user_document_dir.clone();
+        let scope = collect_replace_scope(&roots, user_document_dir.as_path());
+        self.search_replace.update(cx, |panel, cx| {
+            panel.open(scope, scope_label, user_document_dir, window, cx)
+        });
+    }
+
+    pub(crate) fn apply_bulk_replace(
+        &mut self,
+        paths: Vec<PathBuf>,
+        find: String,
+        replace: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Pending editor text must reach disk first, or its autosave would undo the replace.
+        if !self.flush_editor_content_before_context_switch("req-rep1-bulk-replace", cx) {
+            self.show_transient_notice(
+                crate::i18n::tr("notice.replace_needs_save").to_string(),
+                cx,
+            );
+            return;
+        }
+        self.queue_split_editor_save(window, cx);
+
+        let (report_tx, report_rx) = smol::channel::bounded::<
+            WorkflowResult<crate::file_update_handler::BulkReplaceReport>,
+        >(1);
+        let request = crate::file_update_handler::BulkReplaceRequest {
+            user_document_dir: self.app_paths.user_document_dir.clone(),
+            paths,
+            find,
+            replace,
+        };
+        if let Err(error) = self
+            .file_workflow
+            .bulk_replace_async(request, move |result| {
+                let _ = report_tx.try_send(result);
+            })
+        {
+            crate::log::trace_debug(format!(
+                "req-rep1 bulk replace dispatch failed error={error}"
+            ));
+            self.show_transient_notice(error.notice(crate::i18n::tr("error.replace_failed")), cx);
+            return;
+        }
+
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(result) = report_rx.recv().await else {
+                crate::log::trace_debug("req-rep1 bulk replace completion channel closed");
+                return;
+            };
+            let _ = this.update_in(cx, move |app, window, cx| {
+                app.complete_bulk_replace(result, window, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn complete_bulk_replace(
+        &mut self,
+        result: WorkflowResult<crate::file_update_handler::BulkReplaceReport>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let summary = match result {
+            Ok(report) => {
+                for (path, _) in &report.replaced {
+                    if let Ok(text) = fs::read_to_string(path) {
+                        self.record_note_tags(path.as_path(), &text);
+                    }
+                }
+                // Our own rewrite is not an external change worth flagging in the tree.
+                let replaced_paths = report
+                    .replaced
+                    .iter()
+                    .map(|(path, _)| path.clone())
+                    .collect::<Vec<_>>();
+                self.file_tree.update(cx, |file_tree, cx| {
+                    file_tree.mark_notes_looked_at(&replaced_paths, cx);
+                });
+                for (path, error) in &report.failed {
+                    crate::log::trace_debug(format!(
+                        "req-rep1 bulk replace file failed path={} error={error}",
+                        path.display()
+                    ));
+                }
+                let reopen = self
+                    .file_workflow
+                    .current_edit_path()
+                    .filter(|current| report.replaced.iter().any(|(path, _)| path == current));
+                // Text typed while the replace ran must reach disk before the reload, or its
+                // autosave would later write the pre-replace buffer back over the file.
+                if let Some(current) = reopen {
+                    if self.flush_editor_content_before_context_switch(
+                        "req-rep1-bulk-replace-reopen",
+                        cx,
+                    ) {
+                        self.reopen_note_keeping_caret(current, window, cx);
+                    } else {
+                        crate::log::trace_debug(format!(
+                            "req-rep1 reopen skipped (flush failed) path={}",
+                            current.display()
+                        ));
+                    }
+                }
+                self.reload_split_editor_if_rewritten(&replaced_paths, window, cx);
+                report.summary()
+            }
+            Err(error) => error.notice(crate::i18n::tr("error.replace_failed")),
+        };
+        self.sync_tag_sidebar(cx);
+        self.search_replace
+            .update(cx, |panel, cx| panel.set_report(summary.clone(), cx));
+        self.show_transient_notice(summary, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_replace_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    #[test]
+    fn replace_test1_scope_expands_folders_and_defaults_to_vault() {
+        let root = new_temp_root("replace_test1");
+        let day_dir = root.join("2026").join("02").join("28");
+        fs::create_dir_all(&day_dir).expect("create day dir");
+        let note_a = day_dir.join("a.txt");
+        let note_b = root.join("b.md");
+        fs::write(&note_a, "a").expect("write a");
+        fs::write(&note_b, "b").expect("write b");
+        fs::write(root.join("image.png"), "png").expect("write png");
+
+        assert_eq!(
+            collect_replace_scope(&[], root.as_path()),
+            vec![note_a.clone(), note_b.clone()]
+        );
+        assert_eq!(
+            collect_replace_scope(&[day_dir, note_a.clone()], root.as_path()),
+            vec![note_a]
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn replace_test2_preview_lists_matching_lines_per_file() {
+        let root = new_temp_root("replace_test2");
+        let note = root.join("note.txt");
+        fs::write(&note, "todo one\nnothing\n  todo todo\n").expect("write note");
+
+        let preview = preview_replace_matches(std::slice::from_ref(&note), "todo");
+
+        assert_eq!(
+            preview,
+            vec![
+                ReplacePreviewMatch {
+                    path: note.clone(),
+                    line_1_based: 1,
+                    line_text: "todo one".to_string(),
+                    occurrences: 1,
+                },
+                ReplacePreviewMatch {
+                    path: note.clone(),
+                    line_1_based: 3,
+                    line_text: "todo todo".to_string(),
+                    occurrences: 2,
+                },
+            ]
+        );
+        assert_eq!(preview_matched_paths(&preview), vec![note]);
+        assert!(preview_replace_matches(&[root.join("note.txt")], "").is_empty());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn replace_test3_date_range_uses_daily_folders_then_mtime() {
+        let root = new_temp_root("replace_test3");
+        let february = root.join("2026").join("02").join("28");
+        let march = root.join("2026").join("03").join("02");
+        fs::create_dir_all(&february).expect("create february");
+        fs::create_dir_all(&march).expect("create march");
+        let note_feb = february.join("a.txt");
+        let note_mar = march.join("b.txt");
+        let loose = root.join("loose.md");
+        for note in [&note_feb, &note_mar, &loose] {
+            fs::write(note, "x").expect("write note");
+        }
+        let scope = vec![note_feb.clone(), note_mar.clone(), loose.clone()];
+
+        let march_only = NoteDateRange::parse("2026-03-01", "2026-03-31").expect("parse range");
+        assert_eq!(
+            filter_scope_by_date(&scope, march_only, root.as_path()),
+            vec![note_mar]
+        );
+        let from_today =
+            NoteDateRange::parse(&Local::now().format("%Y-%m-%d").to_string(), "").expect("open");
+        assert_eq!(
+            filter_scope_by_date(&scope, from_today, root.as_path()),
+            vec![loose]
+        );
+        assert_eq!(
+            filter_scope_by_date(&scope, NoteDateRange::default(), root.as_path()),
+            scope
+        );
+        assert!(NoteDateRange::parse("2026-03-31", "2026-03-01").is_err());
+        assert!(NoteDateRange::parse("March", "").is_err());
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,445 @@
+use std::{
+    fs, io,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use gpui::*;
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+use crate::path_resolver::AppPaths;
+
+pub(crate) const SETTINGS_ARCHIVE_MANIFEST_NAME: &str = "papyru2_settings.toml";
+pub(crate) const SETTINGS_ARCHIVE_FORMAT_VERSION: u32 = 1;
+const SETTINGS_ARCHIVE_CONF_PREFIX: &str = "conf";
+const SETTINGS_ARCHIVE_DICTIONARIES_PREFIX: &str = "data/dictionaries";
+
+// The archive carries configuration only: everything under conf (app/color settings,
+// keymaps, themes, templates, rules) plus user spell-check dictionaries. Notes, the
+// recycle bin, indexes and logs never leave the machine this way.
+pub(crate) fn is_machine_local_conf_file(relative: &Path) -> bool {
+    relative == Path::new(crate::window_position::WINDOW_POSITION_FILE_NAME)
+        || relative == Path::new(crate::vaults::VAULTS_FILE_NAME)
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct SettingsArchiveManifest {
+    format: u32,
+    exported_from: String,
+    exported_at: String,
+}
+
+/// Every regular file under `root/relative`, relative to `root`. Symlinks are left out, so a
+/// link back up the tree cannot send the walk around in circles.
+pub(crate) fn collect_files(
+    root: &Path,
+    relative: &Path,
+    files: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    let dir = root.join(relative);
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let mut entries = fs::read_dir(&dir)?
+        .flatten()
+        .filter_map(|entry| Some((entry.file_name(), entry.file_type().ok()?)))
+        .collect::<Vec<_>>();
+    entries.sort_by(|(left, _), (right, _)| left.cmp(right));
+    for (name, file_type) in entries {
+        let child = relative.join(name);
+        if file_type.is_dir() {
+            collect_files(root, child.as_path(), files)?;
+        } else if file_type.is_file() {
+            files.push(child);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn archive_entry_name(prefix: &str, relative: &Path) -> String {
+    let mut name = prefix.to_string();
+    for component in relative.components() {
+        name.push('/');
+        name.push_str(&component.as_os_str().to_string_lossy());
+    }
+    name
+}
+
+/// `(archive entry name, source path)` for every exported settings file, in a stable order.
+pub(crate) fn collect_settings_files(app_paths: &AppPaths) -> io::Result<Vec<(String, PathBuf)>> {
+    let mut result = Vec::new();
+
+    let mut conf_files = Vec::new();
+    collect_files(app_paths.conf_dir.as_path(), Path::new(""), &mut conf_files)?;
+    for relative in conf_files {
+        if is_machine_local_conf_file(relative.as_path()) {
+            continue;
+        }
+        result.push((
+            archive_entry_name(SETTINGS_ARCHIVE_CONF_PREFIX, relative.as_path()),
+            app_paths.conf_dir.join(&relative),
+        ));
+    }
+
+    let dictionaries_dir =
+        crate::spell_check::spell_check_dictionaries_dir(app_paths.data_dir.as_path());
+    let mut dictionary_files = Vec::new();
+    collect_files(
+        dictionaries_dir.as_path(),
+        Path::new(""),
+        &mut dictionary_files,
+    )?;
+    for relative in dictionary_files {
+        result.push((
+            archive_entry_name(SETTINGS_ARCHIVE_DICTIONARIES_PREFIX, relative.as_path()),
+            dictionaries_dir.join(&relative),
+        ));
+    }
+    Ok(result)
+}
+
+/// Writes the settings archive to `target` and returns the number of settings files in it.
+pub(crate) fn export_settings_archive(app_paths: &AppPaths, target: &Path) -> io::Result<usize> {
+    let files = collect_settings_files(app_paths)?;
+    let manifest = SettingsArchiveManifest {
+        format: SETTINGS_ARCHIVE_FORMAT_VERSION,
+        exported_from: format!("{:?}", app_paths.mode),
+        exported_at: Local::now().to_rfc3339(),
+    };
+    let manifest_text = toml::to_string(&manifest)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+
+    let mut zip = ZipWriter::new(fs::File::create(target)?);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file(SETTINGS_ARCHIVE_MANIFEST_NAME, options)
+        .map_err(io::Error::other)?;
+    zip.write_all(manifest_text.as_bytes())?;
+    for (entry_name, source) in &files {
+        let bytes = fs::read(source)?;
+        zip.start_file(entry_name.as_str(), options)
+            .map_err(io::Error::other)?;
+        zip.write_all(&bytes)?;
+    }
+    zip.finish().map_err(io::Error::other)?;
+
+    trace_debug(format!(
+        "req-set1 settings export path={} files={}",
+        target.display(),
+        files.len()
+    ));
+    Ok(files.len())
+}
+
+// Maps an archive entry back to its place on this machine; anything outside the known
+// settings locations (or escaping them) is refused.
+fn import_target_for_entry(app_paths: &AppPaths, entry: &Path) -> Option<PathBuf> {
+    if let Ok(relative) = entry.strip_prefix(SETTINGS_ARCHIVE_CONF_PREFIX) {
+        if relative.as_os_str().is_empty() || is_machine_local_conf_file(relative) {
+            return None;
+        }
+        return Some(app_paths.conf_dir.join(relative));
+    }
+    if let Ok(relative) = entry.strip_prefix(SETTINGS_ARCHIVE_DICTIONARIES_PREFIX) {
+        if relative.as_os_str().is_empty() {
+            return None;
+        }
+        return Some(
+            crate::spell_check::spell_check_dictionaries_dir(app_paths.data_dir.as_path())
+                .join(relative),
+        );
+    }
+    None
+}
+
+/// Restores settings files from `archive`, overwriting same-named files; returns the count.
+pub(crate) fn import_settings_archive(app_paths: &AppPaths, archive: &Path) -> io::Result<usize> {
+    let mut zip = ZipArchive::new(fs::File::open(archive)?)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+
+    let manifest_text = {
+        let mut entry = zip.by_name(SETTINGS_ARCHIVE_MANIFEST_NAME).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a papyru2 settings archive (manifest missing)",
+            )
+        })?;
+        let mut text = String::new();
+        entry.read_to_string(&mut text)?;
+        text
+    };
+    let manifest: SettingsArchiveManifest = toml::from_str(&manifest_text)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+    if manifest.format > SETTINGS_ARCHIVE_FORMAT_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "settings archive format {} is newer than supported {}",
+                manifest.format, SETTINGS_ARCHIVE_FORMAT_VERSION
+            ),
+        ));
+    }
+
+    // Resolve every target first so a bad archive changes nothing.
+    let mut planned = Vec::new();
+    for index in 0..zip.len() {
+        let entry = zip.by_index(index).map_err(io::Error::other)?;
+        if entry.is_dir() || entry.name() == SETTINGS_ARCHIVE_MANIFEST_NAME {
+            continue;
+        }
+        let target = entry
+            .enclosed_name()
+            .and_then(|name| import_target_for_entry(app_paths, name.as_path()));
+        match target {
+            Some(target) => planned.push((index, target)),
+            None => trace_debug(format!(
+                "req-set1 settings import skipped entry={}",
+                entry.name()
+            )),
+        }
+    }
+
+    for (index, target) in &planned {
+        let mut entry = zip.by_index(*index).map_err(io::Error::other)?;
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes)?;
+        crate::atomic_io::write_atomic(target.as_path(), &bytes)?;
+    }
+    trace_debug(format!(
+        "req-set1 settings import path={} exported_from={} exported_at={} files={}",
+        archive.display(),
+        manifest.exported_from,
+        manifest.exported_at,
+        planned.len()
+    ));
+    Ok(planned.len())
+}
+
+pub(crate) fn default_settings_archive_name() -> String {
+    format!("papyru2_settings_{}.zip", Local::now().format("%Y%m%d"))
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn export_settings(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let answer = cx.prompt_for_new_path(
+            self.app_paths.app_home.as_path(),
+            Some(&default_settings_archive_name()),
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(target))) = answer.await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, _, cx| {
+                let message = match export_settings_archive(&app.app_paths, target.as_path()) {
+                    Ok(count) => tr_with(
+                        "notice.settings_exported",
+                        &[
+                            ("count", count.to_string().as_str()),
+                            ("path", target.display().to_string().as_str()),
+                        ],
+                    ),
+                    Err(error) => {
+                        trace_debug(format!("req-set1 settings export failed error={error}"));
+                        tr_with(
+                            "notice.settings_export_failed",
+                            &[("reason", error.to_string().as_str())],
+                        )
+                    }
+                };
+                app.show_transient_notice(message, cx);
+            });
+        })
+        .detach();
+    }
+
+    pub(crate) fn import_settings(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let answer = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some(tr("button.import_settings")),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = answer.await else {
+                return;
+            };
+            let Some(archive) = paths.into_iter().next() else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, _, cx| {
+                let message = match import_settings_archive(&app.app_paths, archive.as_path()) {
+                    Ok(count) => {
+                        app.reload_imported_settings(cx);
+                        tr_with(
+                            "notice.settings_imported",
+                            &[("count", count.to_string().as_str())],
+                        )
+                    }
+                    Err(error) => {
+                        trace_debug(format!("req-set1 settings import failed error={error}"));
+                        tr_with(
+                            "notice.settings_import_failed",
+                            &[("reason", error.to_string().as_str())],
+                        )
+                    }
+                };
+                app.show_transient_notice(message, cx);
+            });
+        })
+        .detach();
+    }
+
+    // Settings read once at startup but cheap to re-read take effect right away; colors,
+    // fonts and window options still need a restart.
+    fn reload_imported_settings(&mut self, cx: &mut Context<Self>) {
+        let config_path = self
+            .app_paths
+            .config_file_path(crate::app::PAPYRU2_CONF_FILE_NAME);
+        let confirmations = crate::confirmations::load_confirmation_policy(config_path.as_path());
+        crate::filename_rules::set_active_filename_rules(
+            crate::filename_rules::load_filename_rules(config_path.as_path()).unwrap_or_default(),
+        );
+        self.confirmations = confirmations;
+        self.editor.update(cx, |editor, _| {
+            editor.set_confirmation_policy(confirmations)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path_resolver::RunEnvPattern;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_app_paths(name: &str) -> AppPaths {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let app_home = std::env::temp_dir().join(format!(
+            "papyru2_settings_archive_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        let data_dir = app_home.join("data");
+        AppPaths {
+            mode: RunEnvPattern::Portable,
+            conf_dir: app_home.join("conf"),
+            user_document_dir: data_dir.join("user_document"),
+            recyclebin_dir: data_dir.join("recyclebin"),
+            log_dir: app_home.join("log"),
+            bin_dir: app_home.join("bin"),
+            data_dir,
+            app_home,
+            override_report: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn set_test1_export_then_import_round_trips_settings_but_not_notes() {
+        let source = new_temp_app_paths("set_test1_source");
+        fs::create_dir_all(source.conf_dir.join("themes")).expect("create themes");
+        fs::create_dir_all(&source.user_document_dir).expect("create notes");
+        let dictionaries = crate::spell_check::spell_check_dictionaries_dir(&source.data_dir);
+        fs::create_dir_all(&dictionaries).expect("create dictionaries");
+        fs::write(
+            source.config_file_path(crate::app::PAPYRU2_CONF_FILE_NAME),
+            "[confirmations]\nreplace_all = \"never\"\n",
+        )
+        .expect("write conf");
+        fs::write(source.conf_dir.join("themes").join("dark.toml"), "bg = 1").expect("theme");
+        fs::write(
+            source.config_file_path(crate::window_position::WINDOW_POSITION_FILE_NAME),
+            "x = 1",
+        )
+        .expect("window position");
+        fs::write(dictionaries.join("en.txt"), "papyru\n").expect("dictionary");
+        fs::write(source.user_document_dir.join("note.txt"), "secret").expect("note");
+
+        let archive = source.app_home.join("settings.zip");
+        assert_eq!(
+            export_settings_archive(&source, archive.as_path()).expect("export"),
+            3
+        );
+
+        let target = new_temp_app_paths("set_test1_target");
+        assert_eq!(
+            import_settings_archive(&target, archive.as_path()).expect("import"),
+            3
+        );
+        assert_eq!(
+            fs::read_to_string(target.config_file_path(crate::app::PAPYRU2_CONF_FILE_NAME))
+                .expect("read conf"),
+            "[confirmations]\nreplace_all = \"never\"\n"
+        );
+        assert!(target.conf_dir.join("themes").join("dark.toml").is_file());
+        assert!(
+            crate::spell_check::spell_check_dictionaries_dir(&target.data_dir)
+                .join("en.txt")
+                .is_file()
+        );
+        assert!(
+            !target
+                .config_file_path(crate::window_position::WINDOW_POSITION_FILE_NAME)
+                .exists()
+        );
+        assert!(!target.user_document_dir.join("note.txt").exists());
+        let _ = fs::remove_dir_all(&source.app_home);
+        let _ = fs::remove_dir_all(&target.app_home);
+    }
+
+    #[test]
+    fn set_test2_archive_without_manifest_is_rejected_and_unknown_entries_are_skipped() {
+        let paths = new_temp_app_paths("set_test2");
+        fs::create_dir_all(&paths.app_home).expect("create home");
+        let archive = paths.app_home.join("other.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&archive).expect("create zip"));
+        zip.start_file("conf/papyru2_conf.toml", SimpleFileOptions::default())
+            .expect("start entry");
+        zip.write_all(b"[debug]\n").expect("write entry");
+        zip.finish().expect("finish zip");
+
+        let error = import_settings_archive(&paths, archive.as_path()).expect_err("no manifest");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(!paths.conf_dir.exists());
+
+        assert_eq!(
+            import_target_for_entry(&paths, Path::new("data/user_document/note.txt")),
+            None
+        );
+        assert_eq!(
+            import_target_for_entry(&paths, Path::new("conf/keymap.toml")),
+            Some(paths.conf_dir.join("keymap.toml"))
+        );
+        let _ = fs::remove_dir_all(&paths.app_home);
+    }
+
+    #[test]
+    fn set_test3_collect_files_skips_symlinks_and_ends_on_a_link_loop() {
+        let paths = new_temp_app_paths("set_test3");
+        let themes = paths.conf_dir.join("themes");
+        fs::create_dir_all(&themes).expect("create themes");
+        fs::write(themes.join("dark.json"), "{}").expect("write theme");
+        fs::write(paths.conf_dir.join("keymap.toml"), "").expect("write keymap");
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&paths.conf_dir, themes.join("loop")).expect("link loop");
+            std::os::unix::fs::symlink(paths.conf_dir.join("keymap.toml"), themes.join("keys"))
+                .expect("link file");
+        }
+
+        let mut files = Vec::new();
+        collect_files(paths.conf_dir.as_path(), Path::new(""), &mut files).expect("collect");
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("keymap.toml"),
+                Path::new("themes").join("dark.json")
+            ]
+        );
+        let _ = fs::remove_dir_all(&paths.app_home);
+    }
+}
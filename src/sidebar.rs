@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use gpui::*;
+
+use crate::log::trace_debug;
+
+const SIDEBAR_ANIMATION_FRAMES: u32 = 8;
+const SIDEBAR_ANIMATION_FRAME: Duration = Duration::from_millis(16);
+
+/// Width of the file tree `frame` frames into the fold; ease-out, so it moves fast first.
+pub(crate) fn sidebar_animation_width(full_width: f32, frame: u32, collapsing: bool) -> f32 {
+    let t = (frame.min(SIDEBAR_ANIMATION_FRAMES) as f32) / SIDEBAR_ANIMATION_FRAMES as f32;
+    let eased = 1.0 - (1.0 - t) * (1.0 - t);
+    let shown = if collapsing { 1.0 - eased } else { eased };
+    full_width * shown
+}
+
+impl crate::app::Papyru2App {
+    /// While folded or mid-animation the tree is drawn at this width instead of through the
+    /// resizable split; `None` means the split is in charge.
+    pub(crate) fn sidebar_override_width(&self) -> Option<Pixels> {
+        self.sidebar_animation_width
+            .or_else(|| self.ui_state.file_tree_collapsed.then_some(Pixels::ZERO))
+    }
+
+    /// Folds the file tree away or brings it back at the width it had, animating the left
+    /// panel; the state is kept in `ui_state.toml`.
+    pub(crate) fn toggle_sidebar(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let collapsing = !self.ui_state.file_tree_collapsed;
+        self.ui_state.file_tree_collapsed = collapsing;
+        self.persist_ui_state();
+        self.sidebar_animation_generation += 1;
+        let generation = self.sidebar_animation_generation;
+        let full_width = f32::from(self.split_left_panel_size);
+        trace_debug(format!(
+            "req-side1 sidebar collapsed={collapsing} width={full_width}"
+        ));
+        self.top_bars.update(cx, |top_bars, cx| {
+            top_bars.set_file_tree_collapsed(collapsing, cx);
+        });
+        self.sidebar_animation_width = Some(px(sidebar_animation_width(full_width, 0, collapsing)));
+        cx.notify();
+        cx.spawn_in(window, async move |this, cx| {
+            for frame in 1..=SIDEBAR_ANIMATION_FRAMES {
+                cx.background_executor()
+                    .timer(SIDEBAR_ANIMATION_FRAME)
+                    .await;
+                let updated = this.update(cx, |app, cx| {
+                    // A newer toggle owns the animation now.
+                    if app.sidebar_animation_generation != generation {
+                        return false;
+                    }
+                    app.sidebar_animation_width = (frame < SIDEBAR_ANIMATION_FRAMES)
+                        .then(|| px(sidebar_animation_width(full_width, frame, collapsing)));
+                    cx.notify();
+                    true
+                });
+                if !matches!(updated, Ok(true)) {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn side_test1_animation_runs_from_full_width_to_folded_and_back() {
+        assert_eq!(sidebar_animation_width(300.0, 0, true), 300.0);
+        assert_eq!(
+            sidebar_animation_width(300.0, SIDEBAR_ANIMATION_FRAMES, true),
+            0.0
+        );
+        assert_eq!(sidebar_animation_width(300.0, 0, false), 0.0);
+        assert_eq!(
+            sidebar_animation_width(300.0, SIDEBAR_ANIMATION_FRAMES + 3, false),
+            300.0
+        );
+        let halfway = sidebar_animation_width(300.0, SIDEBAR_ANIMATION_FRAMES / 2, false);
+        assert!(halfway > 150.0 && halfway < 300.0);
+    }
+}
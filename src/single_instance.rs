@@ -0,0 +1,439 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    hash::{BuildHasher, RandomState},
+    io::{self, BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use gpui::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{cli::CliLaunchAction, log::trace_debug};
+
+pub(crate) const INSTANCE_LOCK_FILE_NAME: &str = "papyru2_instance.lock";
+// Kept apart from the lock file: on Windows a locked file cannot be read by other processes.
+pub(crate) const INSTANCE_RECORD_FILE_NAME: &str = "papyru2_instance.json";
+const INSTANCE_HANDOFF_TIMEOUT: Duration = Duration::from_secs(2);
+const INSTANCE_ACQUIRE_ATTEMPTS: usize = 3;
+const INSTANCE_RETRY_DELAY: Duration = Duration::from_millis(150);
+const INSTANCE_HANDOFF_ACK: &str = "ok";
+// A handoff is one short JSON line; anything longer is not from papyru2.
+const INSTANCE_HANDOFF_MAX_BYTES: u64 = 64 * 1024;
+
+#[cfg(unix)]
+type InstanceListener = std::os::unix::net::UnixListener;
+#[cfg(unix)]
+type InstanceStream = std::os::unix::net::UnixStream;
+#[cfg(not(unix))]
+type InstanceListener = std::net::TcpListener;
+#[cfg(not(unix))]
+type InstanceStream = std::net::TcpStream;
+
+/// Written beside the lock file so a later launch knows where the running instance listens.
+/// The record is private to the user, so only their own launches learn the token.
+#[derive(Debug, Serialize, Deserialize)]
+struct InstanceLockRecord {
+    pid: u32,
+    endpoint: String,
+    token: String,
+}
+
+/// One line of JSON sent by a second launch; `None` only asks for focus. `token` must match
+/// the primary's session token or the request is dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct InstanceHandoff {
+    pub token: String,
+    pub launch: Option<CliLaunchAction>,
+}
+
+/// Holds the OS lock on the lock file for as long as the primary instance runs. The OS drops
+/// the lock when the process exits, crash included, so there is no stale lock to take over.
+pub(crate) struct InstanceGuard {
+    _lock_file: File,
+    record_path: PathBuf,
+    endpoint: String,
+}
+
+impl Drop for InstanceGuard {
+    fn drop(&mut self) {
+        // The lock file itself stays: deleting it would let a launch that already opened it
+        // lock an orphaned copy while the next one creates a fresh file.
+        let still_ours = read_lock_record(self.record_path.as_path())
+            .is_ok_and(|record| record.pid == std::process::id());
+        if still_ours {
+            let _ = fs::remove_file(&self.record_path);
+        }
+        remove_socket_endpoint(&self.endpoint);
+    }
+}
+
+// Unix sockets leave a file behind; loopback ports need no cleanup.
+fn remove_socket_endpoint(endpoint: &str) {
+    if cfg!(unix) {
+        let _ = fs::remove_file(endpoint);
+    }
+}
+
+pub(crate) enum InstanceStartup {
+    Primary {
+        guard: InstanceGuard,
+        handoff_rx: smol::channel::Receiver<InstanceHandoff>,
+    },
+    HandedOff,
+    /// The lock could not be taken for a reason unrelated to another instance (read-only
+    /// data dir, a file system without locking); the app runs without the handoff.
+    Unguarded,
+}
+
+#[cfg(unix)]
+fn bind_instance_listener(data_dir: &Path) -> io::Result<(InstanceListener, String)> {
+    let socket_path = data_dir.join(format!("papyru2_instance_{}.sock", std::process::id()));
+    let _ = fs::remove_file(&socket_path);
+    let listener = InstanceListener::bind(&socket_path)?;
+    Ok((listener, socket_path.display().to_string()))
+}
+
+#[cfg(not(unix))]
+fn bind_instance_listener(_data_dir: &Path) -> io::Result<(InstanceListener, String)> {
+    let listener = InstanceListener::bind((std::net::Ipv4Addr::LOCALHOST, 0))?;
+    let endpoint = listener.local_addr()?.to_string();
+    Ok((listener, endpoint))
+}
+
+fn connect_instance_endpoint(endpoint: &str) -> io::Result<InstanceStream> {
+    #[cfg(unix)]
+    {
+        InstanceStream::connect(endpoint)
+    }
+    #[cfg(not(unix))]
+    {
+        let addr: std::net::SocketAddr = endpoint
+            .parse()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "bad instance endpoint"))?;
+        InstanceStream::connect_timeout(&addr, INSTANCE_HANDOFF_TIMEOUT)
+    }
+}
+
+fn private_file_options() -> OpenOptions {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+}
+
+fn read_lock_record(record_path: &Path) -> io::Result<InstanceLockRecord> {
+    let raw = fs::read_to_string(record_path)?;
+    serde_json::from_str(&raw)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))
+}
+
+// Random per launch; `RandomState` is seeded from the OS, which is all the handoff needs.
+fn new_session_token() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or(0);
+    let high = RandomState::new().hash_one((std::process::id(), nanos));
+    let low = RandomState::new().hash_one(high);
+    format!("{high:016x}{low:016x}")
+}
+
+// Called with the OS lock held, so nobody else writes the record meanwhile; the rename
+// keeps readers from seeing half a record.
+fn write_lock_record(record_path: &Path, record: &InstanceLockRecord) -> io::Result<()> {
+    let bytes = serde_json::to_vec(record)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+    let staging_path = record_path.with_extension(format!("json.{}", std::process::id()));
+    let written = private_file_options()
+        .truncate(true)
+        .open(&staging_path)
+        .and_then(|mut file| {
+            file.write_all(&bytes)?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&staging_path, record_path));
+    if written.is_err() {
+        let _ = fs::remove_file(&staging_path);
+    }
+    written
+}
+
+fn send_handoff(endpoint: &str, handoff: &InstanceHandoff) -> io::Result<()> {
+    let mut stream = connect_instance_endpoint(endpoint)?;
+    stream.set_read_timeout(Some(INSTANCE_HANDOFF_TIMEOUT))?;
+    stream.set_write_timeout(Some(INSTANCE_HANDOFF_TIMEOUT))?;
+    let mut line = serde_json::to_string(handoff)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    let mut ack = String::new();
+    BufReader::new(stream).read_line(&mut ack)?;
+    if ack.trim() != INSTANCE_HANDOFF_ACK {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unexpected handoff reply '{}'", ack.trim()),
+        ));
+    }
+    Ok(())
+}
+
+fn serve_handoff_stream(
+    stream: InstanceStream,
+    token: &str,
+    handoff_tx: &smol::channel::Sender<InstanceHandoff>,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(INSTANCE_HANDOFF_TIMEOUT))?;
+    let mut line = String::new();
+    BufReader::new((&stream).take(INSTANCE_HANDOFF_MAX_BYTES)).read_line(&mut line)?;
+    let handoff: InstanceHandoff = serde_json::from_str(line.trim())
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+    if handoff.token != token {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "handoff token mismatch",
+        ));
+    }
+    trace_debug(format!(
+        "req-sin1 handoff received launch={:?}",
+        handoff.launch
+    ));
+    handoff_tx
+        .send_blocking(handoff)
+        .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "instance ui bridge closed"))?;
+    (&stream).write_all(format!("{INSTANCE_HANDOFF_ACK}\n").as_bytes())
+}
+
+fn spawn_handoff_server(
+    listener: InstanceListener,
+    token: String,
+) -> smol::channel::Receiver<InstanceHandoff> {
+    let (handoff_tx, handoff_rx) = smol::channel::unbounded();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let result =
+                stream.and_then(|stream| serve_handoff_stream(stream, &token, &handoff_tx));
+            if let Err(error) = result {
+                trace_debug(format!("req-sin1 handoff failed error={error}"));
+                if error.kind() == io::ErrorKind::BrokenPipe {
+                    break;
+                }
+            }
+        }
+    });
+    handoff_rx
+}
+
+fn become_primary(data_dir: &Path, lock_file: File) -> io::Result<InstanceStartup> {
+    let record_path = data_dir.join(INSTANCE_RECORD_FILE_NAME);
+    // A crashed primary leaves its socket behind; its record names it.
+    if let Ok(previous) = read_lock_record(record_path.as_path()) {
+        remove_socket_endpoint(&previous.endpoint);
+    }
+    let (listener, endpoint) = bind_instance_listener(data_dir)?;
+    let token = new_session_token();
+    let record = InstanceLockRecord {
+        pid: std::process::id(),
+        endpoint: endpoint.clone(),
+        token: token.clone(),
+    };
+    if let Err(error) = write_lock_record(record_path.as_path(), &record) {
+        remove_socket_endpoint(&endpoint);
+        return Err(error);
+    }
+    trace_debug(format!(
+        "req-sin1 primary instance record={} endpoint={endpoint}",
+        record_path.display()
+    ));
+    Ok(InstanceStartup::Primary {
+        guard: InstanceGuard {
+            _lock_file: lock_file,
+            record_path,
+            endpoint,
+        },
+        handoff_rx: spawn_handoff_server(listener, token),
+    })
+}
+
+/// Becomes the primary instance for `data_dir`, or forwards `launch` to the one that already
+/// is. The OS lock on the lock file decides which, so two launches can never both win.
+pub(crate) fn acquire_single_instance(
+    data_dir: &Path,
+    launch: Option<&CliLaunchAction>,
+) -> InstanceStartup {
+    let lock_path = data_dir.join(INSTANCE_LOCK_FILE_NAME);
+    let record_path = data_dir.join(INSTANCE_RECORD_FILE_NAME);
+    for attempt in 0..INSTANCE_ACQUIRE_ATTEMPTS {
+        let lock_file = match private_file_options().truncate(false).open(&lock_path) {
+            Ok(lock_file) => lock_file,
+            Err(error) => {
+                trace_debug(format!(
+                    "req-sin1 lock open failed lock={} error={error}",
+                    lock_path.display()
+                ));
+                return InstanceStartup::Unguarded;
+            }
+        };
+        match lock_file.try_lock() {
+            Ok(()) => {
+                return become_primary(data_dir, lock_file).unwrap_or_else(|error| {
+                    trace_debug(format!("req-sin1 primary setup failed error={error}"));
+                    InstanceStartup::Unguarded
+                });
+            }
+            Err(fs::TryLockError::WouldBlock) => {}
+            Err(fs::TryLockError::Error(error)) => {
+                trace_debug(format!(
+                    "req-sin1 lock failed lock={} error={error}",
+                    lock_path.display()
+                ));
+                return InstanceStartup::Unguarded;
+            }
+        }
+        drop(lock_file);
+
+        // The primary may still be writing its record; a failed send is retried.
+        let sent = read_lock_record(record_path.as_path()).and_then(|record| {
+            send_handoff(
+                &record.endpoint,
+                &InstanceHandoff {
+                    token: record.token,
+                    launch: launch.cloned(),
+                },
+            )
+        });
+        match sent {
+            Ok(()) => {
+                trace_debug(format!(
+                    "req-sin1 handed off to running instance attempt={attempt}"
+                ));
+                return InstanceStartup::HandedOff;
+            }
+            Err(error) => {
+                trace_debug(format!(
+                    "req-sin1 handoff attempt={attempt} failed error={error}"
+                ));
+                thread::sleep(INSTANCE_RETRY_DELAY);
+            }
+        }
+    }
+    InstanceStartup::Unguarded
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn spawn_instance_handoff_loop(
+        handoff_rx: smol::channel::Receiver<InstanceHandoff>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, cx| {
+            while let Ok(handoff) = handoff_rx.recv().await {
+                let updated = this.update_in(cx, |app, window, cx| {
+                    window.activate_window();
+                    if let Some(action) = handoff.launch {
+                        app.apply_cli_launch_action(action, window, cx);
+                    }
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_sin_{name}_{}_{}",
+            std::process::id(),
+            stamp % 1_000_000
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    #[test]
+    fn sin_test1_second_launch_hands_its_file_to_the_primary() {
+        let root = new_temp_root("t1");
+        let InstanceStartup::Primary { guard, handoff_rx } = acquire_single_instance(&root, None)
+        else {
+            panic!("first launch should become primary");
+        };
+        // The OS lock is per open file, so the same process can play the second launch.
+        let action = CliLaunchAction::OpenFile(root.join("note.txt"));
+        assert!(matches!(
+            acquire_single_instance(&root, Some(&action)),
+            InstanceStartup::HandedOff
+        ));
+
+        let received = smol::block_on(handoff_rx.recv()).expect("receive handoff");
+        assert_eq!(received.launch, Some(action));
+        drop(guard);
+        assert!(!root.join(INSTANCE_RECORD_FILE_NAME).exists());
+        let InstanceStartup::Primary { .. } = acquire_single_instance(&root, None) else {
+            panic!("the released lock should be free again");
+        };
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn sin_test2_crashed_primary_record_is_replaced() {
+        let root = new_temp_root("t2");
+        let record_path = root.join(INSTANCE_RECORD_FILE_NAME);
+        let stale_endpoint = if cfg!(unix) {
+            root.join("gone.sock").display().to_string()
+        } else {
+            "127.0.0.1:1".to_string()
+        };
+        fs::write(
+            &record_path,
+            serde_json::to_vec(&InstanceLockRecord {
+                pid: 0,
+                endpoint: stale_endpoint,
+                token: "stale".to_string(),
+            })
+            .expect("encode"),
+        )
+        .expect("write stale record");
+        fs::write(root.join(INSTANCE_LOCK_FILE_NAME), b"").expect("write unlocked lock file");
+
+        let InstanceStartup::Primary { guard, .. } = acquire_single_instance(&root, None) else {
+            panic!("an unlocked lock file should be taken");
+        };
+        let record = read_lock_record(&record_path).expect("read record");
+        assert_eq!(record.pid, std::process::id());
+        assert_ne!(record.token, "stale");
+        drop(guard);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn sin_test3_handoff_without_the_session_token_is_refused() {
+        let root = new_temp_root("t3");
+        let InstanceStartup::Primary { guard, handoff_rx } = acquire_single_instance(&root, None)
+        else {
+            panic!("first launch should become primary");
+        };
+        let record = read_lock_record(&root.join(INSTANCE_RECORD_FILE_NAME)).expect("read record");
+        let forged = InstanceHandoff {
+            token: "guess".to_string(),
+            launch: Some(CliLaunchAction::NewNote("injected".to_string())),
+        };
+        assert!(send_handoff(&record.endpoint, &forged).is_err());
+        assert!(handoff_rx.try_recv().is_err());
+        drop(guard);
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -1,16 +1,29 @@
 use std::path::{Path, PathBuf};
 
 use chrono::Local;
+use gpui::prelude::FluentBuilder as _;
 use gpui::*;
 use gpui_component::input::InputEvent;
 use gpui_component::input::{Input, InputState};
 
 use gpui_component::ActiveTheme as _;
+
+const SINGLELINE_FEEDBACK_RGB_HEX: u32 = 0xb36b00;
+
 #[derive(Clone, Debug)]
 pub enum SingleLineEvent {
     PressEnter,
+    /// Focus left the title field.
+    Blur,
     PressDown,
-    ValueChanged { value: String, cursor_char: usize },
+    /// Clipboard text with line breaks; the title alone cannot hold it.
+    PasteMultiline(String),
+    ValueChanged {
+        /// What the title said before this edit (an IME composition counts as one edit).
+        previous: String,
+        value: String,
+        cursor_char: usize,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -28,6 +41,7 @@ pub struct SingleLineInput {
     _subscriptions: Vec<Subscription>,
     font_size_logged_once: bool,
     ui_color_config: crate::app::UiColorConfig,
+    validation_feedback: Option<SharedString>,
 }
 
 impl EventEmitter<SingleLineEvent> for SingleLineInput {}
@@ -42,29 +56,33 @@ impl SingleLineInput {
         ui_color_config: crate::app::UiColorConfig,
         cx: &mut Context<Self>,
     ) -> Self {
-        let sl_input_state =
-            cx.new(|cx| InputState::new(window, cx).placeholder("Subject <Enter>"));
+        let sl_input_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(crate::i18n::tr("placeholder.subject"))
+        });
         let (last_value, last_cursor) = {
             let initial = sl_input_state.read(cx);
             (initial.value().to_string(), initial.cursor_position())
         };
 
         let _subscriptions = vec![cx.subscribe_in(&sl_input_state, window, {
-            move |this, state, event: &InputEvent, _window, cx| {
+            move |this, state, event: &InputEvent, _, cx| {
+                if let InputEvent::Blur = event {
+                    cx.emit(SingleLineEvent::Blur);
+                }
+                // Marked IME text raises no change event; the commit arrives as one change.
                 if let InputEvent::Change = event {
                     let state = state.read(cx);
                     let value = state.value().to_string();
                     let cursor = state.cursor_position();
+                    this.refresh_validation_feedback(&value);
+                    let previous = std::mem::replace(&mut this.last_value, value.clone());
+                    this.last_cursor = cursor;
                     if this.pending_programmatic_change_events > 0 {
                         this.pending_programmatic_change_events -= 1;
-                        this.last_value = value;
-                        this.last_cursor = cursor;
                         return;
                     }
-
-                    this.last_value = value.clone();
-                    this.last_cursor = cursor;
                     cx.emit(SingleLineEvent::ValueChanged {
+                        previous,
                         value,
                         cursor_char: cursor.character as usize,
                     });
@@ -86,10 +104,32 @@ impl SingleLineInput {
             _subscriptions,
             font_size_logged_once: false,
             ui_color_config,
+            validation_feedback: None,
         }
     }
 
-    fn on_key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+    /// Background and text follow the color scheme picked while running.
+    pub fn set_ui_color_config(
+        &mut self,
+        ui_color_config: crate::app::UiColorConfig,
+        cx: &mut Context<Self>,
+    ) {
+        self.ui_color_config = ui_color_config;
+        cx.notify();
+    }
+
+    fn refresh_validation_feedback(&mut self, value: &str) {
+        let feedback = singleline_validation_feedback(value).map(SharedString::from);
+        if feedback != self.validation_feedback {
+            crate::log::trace_debug(format!(
+                "req-newf40 singleline validation feedback={}",
+                feedback.as_deref().unwrap_or("<none>")
+            ));
+            self.validation_feedback = feedback;
+        }
+    }
+
+    fn on_key_down(&mut self, event: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
         if event.is_held {
             cx.propagate();
             return;
@@ -97,7 +137,18 @@ impl SingleLineInput {
 
         let key_raw = event.keystroke.key.as_str();
         let key = key_raw.to_ascii_lowercase();
-        crate::log::trace_debug(format!("singleline keydown key={key}"));
+        crate::log::trace_input(format!("singleline keydown key={key}"));
+
+        // Enter and the arrows belong to the IME while it composes: Enter commits the text,
+        // Down walks the candidates. Neither is a create or a move to the editor.
+        let composing = self.sl_input_state.update(cx, |state, cx| {
+            state.marked_text_range(window, cx).is_some()
+        });
+        if composing {
+            crate::log::trace_debug("singleline keydown left to the ime composition");
+            cx.propagate();
+            return;
+        }
 
         if key == "enter" || key == "return" {
             crate::log::trace_debug("singleline emit PressEnter");
@@ -106,12 +157,25 @@ impl SingleLineInput {
             return;
         }
 
+        let modifiers = &event.keystroke.modifiers;
+        if modifiers.control && !modifiers.alt && !modifiers.shift && key == "v" {
+            if let Some(text) = cx
+                .read_from_clipboard()
+                .and_then(|item| item.text())
+                .filter(|text| text.contains(['\n', '\r']))
+            {
+                crate::log::trace_debug("singleline emit PasteMultiline");
+                cx.emit(SingleLineEvent::PasteMultiline(text));
+                cx.stop_propagation();
+                return;
+            }
+        }
+
         if key == "down" || key == "arrowdown" {
             let snapshot = self.snapshot(cx);
-            crate::log::trace_debug(format!(
-                "singleline down candidate cursor={} value='{}'",
-                snapshot.cursor_char,
-                crate::app::compact_text(&snapshot.value)
+            crate::log::trace_input(format!(
+                "singleline down candidate cursor={}",
+                snapshot.cursor_char
             ));
             crate::log::trace_debug("singleline emit PressDown");
             cx.emit(SingleLineEvent::PressDown);
@@ -245,9 +309,12 @@ impl Render for SingleLineInput {
             self.font_size_logged_once = true;
         }
 
-        div()
+        gpui_component::v_flex()
             .w_full()
-            .bg(crate::app::req_colr_rgb_hex_to_hsla(background_rgb_hex))
+            .bg(crate::window_overlay::panel_background(
+                background_rgb_hex,
+                cx,
+            ))
             .text_color(crate::app::req_colr_rgb_hex_to_hsla(foreground_rgb_hex))
             .on_key_down(cx.listener(Self::on_key_down))
             .child(
@@ -259,17 +326,45 @@ impl Render for SingleLineInput {
                 )
                 .text_size(experimental_text_size_px),
             )
+            .when_some(self.validation_feedback.clone(), |this, feedback| {
+                this.child(
+                    div()
+                        .w_full()
+                        .px_2()
+                        .text_xs()
+                        .text_color(crate::app::req_colr_rgb_hex_to_hsla(
+                            SINGLELINE_FEEDBACK_RGB_HEX,
+                        ))
+                        .child(feedback),
+                )
+            })
+    }
+}
+
+// Empty titles fall back to the notitle stem, so only non-empty input can surprise the user.
+pub(crate) fn singleline_validation_feedback(value: &str) -> Option<String> {
+    if value.is_empty() {
+        return None;
     }
+    crate::file_update_handler::sanitize_filename_stem_preview(value).feedback_message()
 }
 
 pub(crate) fn singleline_stem_from_file_tree_selection(path: &Path) -> Option<String> {
     let file_name = path.file_name()?.to_string_lossy().to_string();
-    if let Some(stem) = file_name.strip_suffix(".txt") {
-        return Some(stem.to_string());
+    if crate::file_update_handler::NoteFileExtension::from_path(path).is_some()
+        && let Some(stem) = path.file_stem()
+    {
+        return Some(stem.to_string_lossy().to_string());
     }
     Some(file_name)
 }
 
+pub(crate) fn singleline_title_after_open(path: &Path) -> Option<(String, usize)> {
+    let stem = singleline_stem_from_file_tree_selection(path)?;
+    let cursor_char = stem.chars().count();
+    Some((stem, cursor_char))
+}
+
 impl crate::app::Papyru2App {
     pub(crate) fn sync_singleline_from_file_tree_selection(
         &mut self,
@@ -277,7 +372,7 @@ impl crate::app::Papyru2App {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let Some(stem) = singleline_stem_from_file_tree_selection(path) else {
+        let Some((stem, cursor_char)) = singleline_title_after_open(path) else {
             crate::log::trace_debug(format!(
                 "file_tree selection sync skipped path={} (no filename)",
                 path.display()
@@ -286,77 +381,136 @@ impl crate::app::Papyru2App {
         };
 
         crate::log::trace_debug(format!(
-            "file_tree selection sync path={} stem='{}'",
+            "file_tree selection sync path={} stem='{}' cursor={}",
             path.display(),
-            crate::app::compact_text(&stem)
+            crate::app::compact_text(&stem),
+            cursor_char
         ));
         self.singleline.update(cx, |singleline, cx| {
-            singleline.apply_text_value_only(stem.clone(), window, cx);
+            singleline.apply_text_and_cursor(stem.clone(), cursor_char, window, cx);
         });
     }
 
     pub(crate) fn on_singleline_value_changed(
         &mut self,
+        previous: &str,
         value: &str,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        self.title_link_moves.editing();
         match self.file_workflow.state() {
             crate::file_update_handler::SinglelineFileState::Neutral => {
                 self.ensure_new_file_flow("singleline_value_changed", window, cx);
             }
             crate::file_update_handler::SinglelineFileState::Edit => {
-                let now_local = Local::now();
-                let previous_path = self.file_workflow.current_edit_path();
-                match self.file_workflow.try_rename_in_edit(
-                    value,
+                let Some(path) = self.file_workflow.current_edit_path() else {
+                    return;
+                };
+                // Files opened from outside keep their name; the banner offers an import.
+                if !crate::file_update_handler::is_path_in_vault(
+                    path.as_path(),
                     self.app_paths.user_document_dir.as_path(),
-                    now_local,
                 ) {
-                    Ok(Some(path)) => {
-                        crate::log::trace_debug(format!(
-                            "rename_flow success new_path={} value='{}'",
-                            path.display(),
-                            crate::app::compact_text(value)
-                        ));
-                        self.sync_current_editing_path_to_components(Some(path.clone()), cx);
-                        if crate::app::req_ftr14_rename_flow_uses_watcher_refresh_only() {
-                            crate::log::trace_debug(format!(
-                                "rename_flow watcher_refresh_only=true previous_path={} direct_tree_patch_skipped",
-                                previous_path
-                                    .as_ref()
-                                    .map(|path| path.display().to_string())
-                                    .unwrap_or_else(|| "<none>".to_string())
-                            ));
-                        }
-                        self.apply_forced_singleline_stem(
-                            crate::file_update_handler::forced_singleline_stem_after_rename(
-                                value,
-                                path.as_path(),
-                                now_local,
-                            ),
-                            "rename_flow",
-                            window,
-                            cx,
-                        );
-                    }
-                    Ok(None) => {}
-                    Err(error) => {
-                        crate::log::trace_debug(format!(
-                            "rename_flow failed value='{}' error={error}",
-                            crate::app::compact_text(value)
-                        ));
-                    }
+                    return;
+                }
+                self.sync_title_mirror_from_title(previous, value, window, cx);
+                let pending = crate::file_update_handler::PendingTitleRename {
+                    path,
+                    singleline_value: value.to_string(),
+                    user_document_dir: self.app_paths.user_document_dir.clone(),
+                };
+                // The rename runs on the workflow worker once the title has been idle for the
+                // configured delay; `complete_singleline_rename` picks up the result, so
+                // typing never waits on the file system.
+                if self.title_rename_debouncer.delay().is_zero() {
+                    crate::file_update_handler::dispatch_title_rename(&self.file_workflow, pending);
+                } else {
+                    self.title_rename_debouncer
+                        .mark_title_edit(pending, std::time::Instant::now());
                 }
             }
             crate::file_update_handler::SinglelineFileState::New => {}
         }
     }
+
+    pub(crate) fn complete_singleline_rename(
+        &mut self,
+        outcome: crate::file_update_handler::RenameOutcome,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let value = outcome.singleline_value;
+        let path = match outcome.result {
+            Ok(path) => path,
+            Err(error) => {
+                crate::log::trace_debug(format!(
+                    "rename_flow failed value='{}' error={error}",
+                    crate::app::compact_text(&value)
+                ));
+                if !outcome.superseded {
+                    self.show_transient_notice(
+                        error.notice(crate::i18n::tr("error.cannot_rename_note")),
+                        cx,
+                    );
+                }
+                return;
+            }
+        };
+        crate::log::trace_debug(format!(
+            "rename_flow success new_path={} value='{}' superseded={}",
+            path.display(),
+            crate::app::compact_text(&value),
+            outcome.superseded
+        ));
+        let old_path = self.editor.read(cx).current_editing_file_path();
+        if let Some(old_path) = old_path.as_ref() {
+            self.editor_autosave
+                .retarget_pending_payload(old_path.as_path(), path.as_path());
+            self.title_rename_debouncer
+                .retarget_pending(old_path.as_path(), path.as_path());
+        }
+        self.sync_current_editing_path_to_components(Some(path.clone()), cx);
+        let editor_text = self.editor.read(cx).snapshot(cx).value;
+        self.record_note_tags(path.as_path(), &editor_text);
+        if let Some(old_path) = old_path.filter(|old_path| *old_path != path) {
+            self.record_title_link_move(old_path, path.clone(), window, cx);
+        }
+        if crate::app::req_ftr14_rename_flow_uses_watcher_refresh_only() {
+            crate::log::trace_debug(
+                "rename_flow watcher_refresh_only=true direct_tree_patch_skipped",
+            );
+        }
+        if !outcome.superseded
+            && self.complete_auto_title_rename(&value, path.as_path(), window, cx)
+        {
+            return;
+        }
+        // Only the newest title may push its resolved stem back; an older one would undo
+        // what the user typed since.
+        if outcome.superseded || self.singleline.read(cx).snapshot(cx).value != value {
+            return;
+        }
+        self.apply_forced_singleline_stem(
+            &value,
+            crate::file_update_handler::forced_singleline_stem_after_rename(
+                &value,
+                path.as_path(),
+                Local::now(),
+            ),
+            "rename_flow",
+            window,
+            cx,
+        );
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::singleline_stem_from_file_tree_selection;
+    use super::{
+        SinglelineChange, classify_singleline_change, singleline_stem_from_file_tree_selection,
+        singleline_title_after_open, singleline_validation_feedback,
+    };
     use std::path::Path;
 
     #[test]
@@ -371,4 +525,36 @@ mod tests {
             singleline_stem_from_file_tree_selection(Path::new("C:/tmp/こんにちは 世界.txt"));
         assert_eq!(actual.as_deref(), Some("こんにちは 世界"));
     }
+
+    #[test]
+    fn ftr_test111_req_newf39_md_selection_maps_to_singleline_stem() {
+        let actual = singleline_stem_from_file_tree_selection(Path::new("C:/tmp/memo.md"));
+        assert_eq!(actual.as_deref(), Some("memo"));
+
+        let other = singleline_stem_from_file_tree_selection(Path::new("C:/tmp/main.rs"));
+        assert_eq!(other.as_deref(), Some("main.rs"));
+    }
+
+    #[test]
+    fn ftr_test112_req_ftr27_open_title_sync_places_cursor_at_stem_end() {
+        let actual = singleline_title_after_open(Path::new("C:/tmp/こんにちは 世界.txt"));
+        assert_eq!(
+            actual,
+            Some((
+                "こんにちは 世界".to_string(),
+                "こんにちは 世界".chars().count()
+            ))
+        );
+        assert_eq!(singleline_title_after_open(Path::new("/")), None);
+    }
+
+    #[test]
+    fn ftr_test113_req_newf40_singleline_feedback_only_for_surprising_titles() {
+        assert_eq!(singleline_validation_feedback(""), None);
+        assert_eq!(singleline_validation_feedback("plain title"), None);
+        assert_eq!(
+            singleline_validation_feedback("a/b").as_deref(),
+            Some("'/' will be saved as '_'")
+        );
+    }
 }
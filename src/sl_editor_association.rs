@@ -168,6 +168,41 @@ pub fn transfer_on_enter(
     ))
 }
 
+/// Pasting several lines into the title keeps the first line there and inserts the rest at
+/// the editor head, as if Enter had been pressed after the first line. The title text right
+/// of the cursor follows the pasted text, and the editor cursor lands where the paste ends.
+pub fn transfer_on_multiline_paste(
+    singleline_text: &str,
+    singleline_cursor_char: usize,
+    pasted: &str,
+    editor_text: &str,
+) -> Option<EnterTransferResult> {
+    let pasted = pasted.replace("\r\n", "\n").replace('\r', "\n");
+    let (first_line, rest) = pasted.split_once('\n')?;
+    let (left, right) = split_at_char_index(singleline_text, singleline_cursor_char)?;
+
+    let new_singleline_text = format!("{left}{first_line}");
+    let body = format!("{rest}{right}");
+    let new_editor_text = if editor_text.is_empty() {
+        body
+    } else {
+        format!("{body}\n{editor_text}")
+    };
+    let (cursor_line, cursor_char) = match rest.rsplit_once('\n') {
+        Some((before, last)) => (before.matches('\n').count() + 1, last.chars().count()),
+        None => (0, rest.chars().count()),
+    };
+
+    let mut result = make_enter_result(
+        new_singleline_text.clone(),
+        new_singleline_text.chars().count(),
+        new_editor_text,
+    );
+    result.new_editor_cursor_line = cursor_line.min(u32::MAX as usize) as u32;
+    result.new_editor_cursor_char = cursor_char.min(u32::MAX as usize) as u32;
+    Some(result)
+}
+
 pub fn transfer_on_backspace(
     singleline_text: &str,
     singleline_cursor_char: usize,
@@ -239,7 +274,229 @@ pub fn transfer_on_up(
     Some(make_up_result(clamped_cursor_char))
 }
 
+// Mirror mode (`[titles] mode = "mirror"`): the title is a copy of the editor's first line
+// after any front matter, rather than a line of its own above the editor. Rules:
+// - the title is that line without its leading `#` markers, as `auto_title_from_text` reads
+//   it; the markers stay in the note when the title is written back;
+// - an editor edit retitles the note; equal text is a no-op, so the two never ping-pong;
+// - a title edit rewrites the line only while it still showed the previous title (or was
+//   blank); a line that already says something else is kept, and the title goes in above it;
+// - a blank first line leaves the title alone, so clearing the note never renames it;
+// - Enter in the title moves to the line below the mirrored one and splits nothing;
+// - Backspace at the editor head only moves focus to the title tail;
+// - a multi-line paste into the title keeps its first line there and inserts the rest
+//   below the mirrored line.
+fn mirrored_line_span(editor_text: &str) -> (usize, usize) {
+    let start = editor_text.len() - crate::note_front_matter::strip_front_matter(editor_text).len();
+    let end = editor_text[start..]
+        .find('\n')
+        .map_or(editor_text.len(), |index| start + index);
+    let end = if editor_text[start..end].ends_with('\r') {
+        end - 1
+    } else {
+        end
+    };
+    (start, end)
+}
+
+fn mirrored_title_span(editor_text: &str) -> (usize, usize) {
+    let (start, end) = mirrored_line_span(editor_text);
+    let line = &editor_text[start..end];
+    let title = line.trim_start_matches('#').trim_start();
+    let title_start = end - title.len();
+    (title_start, title_start + title.trim_end().len())
+}
+
+fn mirrored_line_index(editor_text: &str) -> usize {
+    let (start, _) = mirrored_line_span(editor_text);
+    editor_text[..start].matches('\n').count()
+}
+
+fn mirrored_line_ending(editor_text: &str) -> &'static str {
+    let (_, end) = mirrored_line_span(editor_text);
+    if editor_text[end..].starts_with("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+pub fn mirrored_first_line(editor_text: &str) -> &str {
+    let (start, end) = mirrored_title_span(editor_text);
+    &editor_text[start..end]
+}
+
+pub fn mirror_title_from_editor(singleline_text: &str, editor_text: &str) -> Option<String> {
+    let line = mirrored_first_line(editor_text);
+    (!line.is_empty() && line != singleline_text).then(|| line.to_string())
+}
+
+pub fn mirror_editor_from_title(
+    singleline_before: &str,
+    singleline_text: &str,
+    editor_text: &str,
+) -> Option<String> {
+    let (start, end) = mirrored_title_span(editor_text);
+    let mirrored = &editor_text[start..end];
+    if mirrored == singleline_text {
+        return None;
+    }
+    if mirrored == singleline_before || mirrored.is_empty() {
+        return Some(format!(
+            "{}{singleline_text}{}",
+            &editor_text[..start],
+            &editor_text[end..]
+        ));
+    }
+    if singleline_text.is_empty() {
+        return None;
+    }
+    let (line_start, _) = mirrored_line_span(editor_text);
+    Some(format!(
+        "{}{singleline_text}{}{}",
+        &editor_text[..line_start],
+        mirrored_line_ending(editor_text),
+        &editor_text[line_start..]
+    ))
+}
+
+pub fn mirror_transfer_on_enter(singleline_text: &str, editor_text: &str) -> EnterTransferResult {
+    let mut new_editor_text =
+        mirror_editor_from_title(singleline_text, singleline_text, editor_text)
+            .unwrap_or_else(|| editor_text.to_string());
+    let (_, end) = mirrored_line_span(&new_editor_text);
+    if !new_editor_text[end..].contains('\n') {
+        new_editor_text.push('\n');
+    }
+    let line = mirrored_line_index(&new_editor_text) + 1;
+    let mut result = make_enter_result(
+        singleline_text.to_string(),
+        singleline_text.chars().count(),
+        new_editor_text,
+    );
+    result.new_editor_cursor_line = line.min(u32::MAX as usize) as u32;
+    result
+}
+
+pub fn mirror_transfer_on_backspace(
+    singleline_text: &str,
+    editor_text: &str,
+) -> BackspaceTransferResult {
+    make_backspace_result(
+        singleline_text.to_string(),
+        singleline_text.chars().count(),
+        editor_text.to_string(),
+    )
+}
+
+pub fn mirror_transfer_on_multiline_paste(
+    singleline_text: &str,
+    singleline_cursor_char: usize,
+    pasted: &str,
+    editor_text: &str,
+) -> Option<EnterTransferResult> {
+    let pasted = pasted.replace("\r\n", "\n").replace('\r', "\n");
+    let (first_line, rest) = pasted.split_once('\n')?;
+    let (left, right) = split_at_char_index(singleline_text, singleline_cursor_char)?;
+
+    let new_singleline_text = format!("{left}{first_line}");
+    let (start, end) = mirrored_title_span(editor_text);
+    let mirrored = &editor_text[start..end];
+    let new_editor_text = if mirrored == singleline_text || mirrored.is_empty() {
+        format!(
+            "{}{new_singleline_text}\n{rest}{right}{}",
+            &editor_text[..start],
+            &editor_text[end..]
+        )
+    } else {
+        let (line_start, _) = mirrored_line_span(editor_text);
+        format!(
+            "{}{new_singleline_text}\n{rest}{right}\n{}",
+            &editor_text[..line_start],
+            &editor_text[line_start..]
+        )
+    };
+    let (rest_lines, cursor_char) = match rest.rsplit_once('\n') {
+        Some((before, last)) => (before.matches('\n').count() + 1, last.chars().count()),
+        None => (0, rest.chars().count()),
+    };
+    let cursor_line = mirrored_line_index(editor_text) + 1 + rest_lines;
+
+    let mut result = make_enter_result(
+        new_singleline_text.clone(),
+        new_singleline_text.chars().count(),
+        new_editor_text,
+    );
+    result.new_editor_cursor_line = cursor_line.min(u32::MAX as usize) as u32;
+    result.new_editor_cursor_char = cursor_char.min(u32::MAX as usize) as u32;
+    Some(result)
+}
+
 impl crate::app::Papyru2App {
+    fn mirrors_first_line(&self) -> bool {
+        self.title_mode == crate::note_titles::TitleMode::Mirror
+    }
+
+    /// Mirror mode: a user edit in the editor retitles the note when its first line changed.
+    pub(crate) fn sync_title_mirror_from_editor(
+        &mut self,
+        editor_text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mirrors_first_line()
+            || self.file_workflow.state() != crate::file_update_handler::SinglelineFileState::Edit
+        {
+            return;
+        }
+        let singleline_before = self.singleline.read(cx).snapshot(cx).value;
+        let Some(title) = mirror_title_from_editor(&singleline_before, editor_text) else {
+            return;
+        };
+        crate::log::trace_debug(format!(
+            "req-ttl2 mirror editor->title '{}'",
+            crate::app::compact_text(&title)
+        ));
+        self.singleline.update(cx, |singleline, cx| {
+            singleline.apply_text_and_cursor(title.clone(), title.chars().count(), window, cx);
+        });
+        self.dispatch_singleline_filename_update_if_changed(
+            "mirror_editor",
+            &singleline_before,
+            &title,
+            window,
+            cx,
+        );
+    }
+
+    /// Mirror mode: a title edit rewrites the editor's first line as one undoable edit and
+    /// saves it like typing.
+    pub(crate) fn sync_title_mirror_from_title(
+        &mut self,
+        singleline_before: &str,
+        singleline_text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mirrors_first_line() {
+            return;
+        }
+        let editor_text = self.editor.read(cx).snapshot(cx).value;
+        let Some(new_editor_text) =
+            mirror_editor_from_title(singleline_before, singleline_text, &editor_text)
+        else {
+            return;
+        };
+        crate::log::trace_debug(format!(
+            "req-ttl2 mirror title->editor '{}'",
+            crate::app::compact_text(singleline_text)
+        ));
+        self.editor.update(cx, |editor, cx| {
+            editor.apply_edit(&new_editor_text, None, window, cx);
+        });
+        self.on_editor_user_buffer_changed(&new_editor_text, cx);
+    }
+
     pub(crate) fn apply_focus_target(
         &mut self,
         focus_target: FocusTarget,
@@ -295,7 +552,7 @@ impl crate::app::Papyru2App {
             "{reason} filename_update_dispatch start value='{}'",
             crate::app::compact_text(singleline_after)
         ));
-        self.on_singleline_value_changed(singleline_after, window, cx);
+        self.on_singleline_value_changed(singleline_before, singleline_after, window, cx);
         crate::log::trace_debug(format!("{reason} filename_update_dispatch done"));
         true
     }
@@ -317,17 +574,76 @@ impl crate::app::Papyru2App {
             editor_snapshot.cursor_char
         ));
 
-        let Some(result) = transfer_on_enter(
+        let result = if self.mirrors_first_line() {
+            Some(mirror_transfer_on_enter(
+                &singleline_snapshot.value,
+                &editor_snapshot.value,
+            ))
+        } else {
+            transfer_on_enter(
+                &singleline_snapshot.value,
+                singleline_snapshot.cursor_char,
+                &editor_snapshot.value,
+            )
+        };
+        let Some(result) = result else {
+            crate::log::trace_debug("transfer_enter skipped (no right side)");
+            return;
+        };
+
+        self.apply_enter_transfer_result(
+            "transfer_enter",
+            &singleline_snapshot.value,
+            &editor_snapshot.value,
+            result,
+            window,
+            cx,
+        );
+    }
+
+    pub(crate) fn paste_multiline_into_singleline(
+        &mut self,
+        pasted: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let singleline_snapshot = self.singleline.read(cx).snapshot(cx);
+        let editor_snapshot = self.editor.read(cx).snapshot(cx);
+        let paste = if self.mirrors_first_line() {
+            mirror_transfer_on_multiline_paste
+        } else {
+            transfer_on_multiline_paste
+        };
+        let Some(result) = paste(
             &singleline_snapshot.value,
             singleline_snapshot.cursor_char,
+            pasted,
             &editor_snapshot.value,
         ) else {
-            crate::log::trace_debug("transfer_enter skipped (no right side)");
+            crate::log::trace_debug("transfer_paste skipped (invalid cursor)");
             return;
         };
+        self.apply_enter_transfer_result(
+            "transfer_paste",
+            &singleline_snapshot.value,
+            &editor_snapshot.value,
+            result,
+            window,
+            cx,
+        );
+    }
 
+    fn apply_enter_transfer_result(
+        &mut self,
+        reason: &str,
+        singleline_before: &str,
+        editor_before: &str,
+        result: EnterTransferResult,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         crate::log::trace_debug(format!(
-            "transfer_enter result sl='{}' sl_cursor={} ed='{}' ed_cursor=({}, {})",
+            "{reason} result sl='{}' sl_cursor={} ed='{}' ed_cursor=({}, {})",
             crate::app::compact_text(&result.new_singleline_text),
             result.new_singleline_cursor_char,
             crate::app::compact_text(&result.new_editor_text),
@@ -345,7 +661,7 @@ impl crate::app::Papyru2App {
         });
 
         self.editor.update(cx, |editor, cx| {
-            if result.new_editor_text == editor_snapshot.value {
+            if result.new_editor_text == editor_before {
                 editor.apply_cursor(
                     result.new_editor_cursor_line,
                     result.new_editor_cursor_char,
@@ -353,10 +669,9 @@ impl crate::app::Papyru2App {
                     cx,
                 );
             } else {
-                editor.apply_text_and_cursor(
-                    result.new_editor_text.clone(),
-                    result.new_editor_cursor_line,
-                    result.new_editor_cursor_char,
+                editor.apply_edit(
+                    &result.new_editor_text,
+                    Some((result.new_editor_cursor_line, result.new_editor_cursor_char)),
                     window,
                     cx,
                 );
@@ -364,8 +679,8 @@ impl crate::app::Papyru2App {
         });
 
         let filename_update_dispatched = self.dispatch_singleline_filename_update_if_changed(
-            "transfer_enter",
-            &singleline_snapshot.value,
+            reason,
+            singleline_before,
             &result.new_singleline_text,
             window,
             cx,
@@ -376,7 +691,7 @@ impl crate::app::Papyru2App {
         let sl_after = self.singleline.read(cx).snapshot(cx);
         let ed_after = self.editor.read(cx).snapshot(cx);
         crate::log::trace_debug(format!(
-            "transfer_enter after sl='{}' sl_cursor={} ed='{}' ed_cursor=({}, {}) filename_update_dispatched={}",
+            "{reason} after sl='{}' sl_cursor={} ed='{}' ed_cursor=({}, {}) filename_update_dispatched={}",
             crate::app::compact_text(&sl_after.value),
             sl_after.cursor_char,
             crate::app::compact_text(&ed_after.value),
@@ -489,11 +804,19 @@ impl crate::app::Papyru2App {
             ));
         }
 
-        let Some(result) = transfer_on_backspace(
-            &singleline_snapshot.value,
-            singleline_snapshot.cursor_char,
-            &editor_snapshot.value,
-        ) else {
+        let result = if self.mirrors_first_line() {
+            Some(mirror_transfer_on_backspace(
+                &singleline_snapshot.value,
+                &editor_snapshot.value,
+            ))
+        } else {
+            transfer_on_backspace(
+                &singleline_snapshot.value,
+                singleline_snapshot.cursor_char,
+                &editor_snapshot.value,
+            )
+        };
+        let Some(result) = result else {
             crate::log::trace_debug("transfer_backspace skipped (invalid singleline cursor)");
             return;
         };
@@ -553,10 +876,9 @@ impl crate::app::Papyru2App {
                     cx,
                 );
             } else {
-                editor.apply_text_and_cursor(
-                    result.new_editor_text.clone(),
-                    result.new_editor_cursor_line,
-                    result.new_editor_cursor_char,
+                editor.apply_edit(
+                    &result.new_editor_text,
+                    Some((result.new_editor_cursor_line, result.new_editor_cursor_char)),
                     window,
                     cx,
                 );
@@ -639,8 +961,10 @@ impl crate::app::Papyru2App {
 #[cfg(test)]
 mod tests {
     use super::{
-        FocusTarget, should_transfer_backspace, transfer_on_backspace, transfer_on_down,
-        transfer_on_enter, transfer_on_up,
+        FocusTarget, mirror_editor_from_title, mirror_title_from_editor,
+        mirror_transfer_on_backspace, mirror_transfer_on_enter, mirror_transfer_on_multiline_paste,
+        should_transfer_backspace, transfer_on_backspace, transfer_on_down, transfer_on_enter,
+        transfer_on_multiline_paste, transfer_on_up,
     };
 
     #[test]
@@ -965,6 +1289,40 @@ mod tests {
         assert_eq!(result.focus_target, FocusTarget::SingleLine);
     }
 
+    #[test]
+    fn assoc_test46_multiline_paste_keeps_first_line_as_title_and_rest_at_editor_head() {
+        let result = transfer_on_multiline_paste("ab|cd", 2, "Title\r\nline 1\nline 22", "old")
+            .expect("expected transfer");
+
+        assert_eq!(result.new_singleline_text, "abTitle");
+        assert_eq!(result.new_singleline_cursor_char, 7);
+        assert_eq!(result.new_editor_text, "line 1\nline 22|cd\nold");
+        assert_eq!(result.new_editor_cursor_line, 1);
+        assert_eq!(result.new_editor_cursor_char, 7);
+        assert_eq!(result.focus_target, FocusTarget::Editor);
+    }
+
+    #[test]
+    fn assoc_test47_multiline_paste_edge_cases_match_enter_semantics() {
+        assert!(transfer_on_multiline_paste("abc", 1, "single line", "").is_none());
+        assert!(transfer_on_multiline_paste("abc", 9, "a\nb", "").is_none());
+
+        let trailing = transfer_on_multiline_paste("", 0, "こんにちは\n", "").expect("transfer");
+        assert_eq!(trailing.new_singleline_text, "こんにちは");
+        assert_eq!(trailing.new_editor_text, "");
+        assert_eq!(
+            (
+                trailing.new_editor_cursor_line,
+                trailing.new_editor_cursor_char
+            ),
+            (0, 0)
+        );
+
+        let enter = transfer_on_enter("abc", 3, "xyz").expect("enter transfer");
+        let paste = transfer_on_multiline_paste("abc", 3, "\n", "xyz").expect("paste transfer");
+        assert_eq!(paste, enter);
+    }
+
     #[test]
     fn assoc_test31_req_assoc16_seam_cursor_matches_old_singleline_len() {
         let before = "abc";
@@ -1105,4 +1463,92 @@ mod tests {
         assert!(!should_transfer_backspace(0, 1));
         assert!(!should_transfer_backspace(1, 0));
     }
+
+    #[test]
+    fn assoc_test48_mirror_mode_syncs_title_and_first_line_without_ping_pong() {
+        assert_eq!(
+            mirror_title_from_editor("Old", "New title\nbody"),
+            Some("New title".to_string())
+        );
+        assert_eq!(mirror_title_from_editor("Same", "Same\nbody"), None);
+        // Clearing the note does not clear (and rename) the title.
+        assert_eq!(mirror_title_from_editor("Kept", "\nbody"), None);
+        assert_eq!(
+            mirror_title_from_editor("", "---\nreadonly: true\n---\nPlan\r\nbody"),
+            Some("Plan".to_string())
+        );
+
+        assert_eq!(
+            mirror_editor_from_title("Plan", "Plan B", "---\nreadonly: true\n---\nPlan\r\nbody"),
+            Some("---\nreadonly: true\n---\nPlan B\r\nbody".to_string())
+        );
+        assert_eq!(
+            mirror_editor_from_title("", "Title", ""),
+            Some("Title".to_string())
+        );
+        let synced = mirror_editor_from_title("Old", "Title", "Old\nbody").expect("rewrite");
+        assert_eq!(mirror_title_from_editor("Title", &synced), None);
+        assert_eq!(mirror_editor_from_title("Title", "Title", &synced), None);
+    }
+
+    #[test]
+    fn assoc_test50_mirror_mode_keeps_headings_and_never_overwrites_other_text() {
+        assert_eq!(
+            mirror_title_from_editor("", "## Weekly plan \nbody"),
+            Some("Weekly plan".to_string())
+        );
+        assert_eq!(
+            mirror_title_from_editor("Weekly plan", "# Weekly plan\n"),
+            None
+        );
+        assert_eq!(
+            mirror_editor_from_title("Weekly plan", "Weekly plans", "## Weekly plan\nbody"),
+            Some("## Weekly plans\nbody".to_string())
+        );
+        // The first line says something else: the title goes in above it.
+        assert_eq!(
+            mirror_editor_from_title("Old name", "New name", "Shopping list\r\nmilk"),
+            Some("New name\r\nShopping list\r\nmilk".to_string())
+        );
+        assert_eq!(
+            mirror_editor_from_title("Old name", "", "Shopping list\nmilk"),
+            None
+        );
+
+        let paste = mirror_transfer_on_multiline_paste("ab", 2, "X\nline 1", "Other\nbody")
+            .expect("expected transfer");
+        assert_eq!(paste.new_singleline_text, "abX");
+        assert_eq!(paste.new_editor_text, "abX\nline 1\nOther\nbody");
+        assert_eq!(
+            (paste.new_editor_cursor_line, paste.new_editor_cursor_char),
+            (1, 6)
+        );
+    }
+
+    #[test]
+    fn assoc_test49_mirror_mode_transfers_move_focus_without_splitting() {
+        let enter = mirror_transfer_on_enter("Title", "Title");
+        assert_eq!(enter.new_singleline_text, "Title");
+        assert_eq!(enter.new_editor_text, "Title\n");
+        assert_eq!(
+            (enter.new_editor_cursor_line, enter.new_editor_cursor_char),
+            (1, 0)
+        );
+        assert_eq!(enter.focus_target, FocusTarget::Editor);
+
+        let backspace = mirror_transfer_on_backspace("Title", "Title\nbody");
+        assert_eq!(backspace.new_editor_text, "Title\nbody");
+        assert_eq!(backspace.new_singleline_cursor_char, 5);
+        assert_eq!(backspace.focus_target, FocusTarget::SingleLine);
+
+        let paste =
+            mirror_transfer_on_multiline_paste("ab|cd", 2, "X\nline 1\nline 2", "ab|cd\nold")
+                .expect("expected transfer");
+        assert_eq!(paste.new_singleline_text, "abX");
+        assert_eq!(paste.new_editor_text, "abX\nline 1\nline 2|cd\nold");
+        assert_eq!(
+            (paste.new_editor_cursor_line, paste.new_editor_cursor_char),
+            (2, 6)
+        );
+    }
 }
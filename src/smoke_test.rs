@@ -0,0 +1,337 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use gpui::*;
+
+use crate::log::trace_debug;
+
+/// Path of a smoke script; set together with `--demo` so the run only touches the sandbox.
+pub(crate) const SMOKE_TEST_ENV: &str = "PAPYRU2_SMOKE_TEST";
+pub(crate) const SMOKE_TEST_REPORT_FILE_NAME: &str = "smoke_test_report.txt";
+
+const SMOKE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const SMOKE_DEFAULT_TIMEOUT: Duration = Duration::from_secs(20);
+// Gives the window a moment to settle before the first step, like a user would.
+const SMOKE_STARTUP_DELAY: Duration = Duration::from_millis(500);
+
+/// One line of a smoke script. Paths are relative to the notes folder; `{today}` expands to
+/// the folder today's notes go to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum SmokeStep {
+    /// Replaces the title as if typed, firing the same change handling.
+    Title(String),
+    Enter,
+    /// Appends to the note body as a user edit; `\n` starts a new line.
+    Body(String),
+    WaitAutosave,
+    /// Retitles the open note and waits until the file carries the new name.
+    Rename(String),
+    Plus,
+    Wait(Duration),
+    ExpectFile(String),
+    ExpectMissing(String),
+    /// `expect-content <path> = <text>`, the whole file compared.
+    ExpectContent {
+        path: String,
+        text: String,
+    },
+}
+
+/// Parses `command argument` lines; blank lines and `#` comments are skipped.
+pub(crate) fn parse_smoke_script(raw: &str) -> Result<Vec<SmokeStep>, String> {
+    let mut steps = Vec::new();
+    for (index, line) in raw.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (command, argument) = line
+            .split_once(char::is_whitespace)
+            .map(|(command, argument)| (command, argument.trim()))
+            .unwrap_or((line, ""));
+        let needs_argument = |what: &str| {
+            if argument.is_empty() {
+                Err(format!("line {}: {command} needs {what}", index + 1))
+            } else {
+                Ok(argument.to_string())
+            }
+        };
+        let step = match command {
+            "title" => SmokeStep::Title(argument.to_string()),
+            "enter" => SmokeStep::Enter,
+            "body" => SmokeStep::Body(argument.replace("\\n", "\n")),
+            "wait-autosave" => SmokeStep::WaitAutosave,
+            "rename" => SmokeStep::Rename(needs_argument("a title")?),
+            "plus" => SmokeStep::Plus,
+            "wait" => SmokeStep::Wait(Duration::from_millis(
+                argument
+                    .parse()
+                    .map_err(|_| format!("line {}: wait needs milliseconds", index + 1))?,
+            )),
+            "expect-file" => SmokeStep::ExpectFile(needs_argument("a path")?),
+            "expect-missing" => SmokeStep::ExpectMissing(needs_argument("a path")?),
+            "expect-content" => {
+                let (path, text) = needs_argument("a path and text")?
+                    .split_once(" = ")
+                    .map(|(path, text)| (path.trim().to_string(), text.replace("\\n", "\n")))
+                    .ok_or_else(|| {
+                        format!("line {}: expect-content needs '<path> = <text>'", index + 1)
+                    })?;
+                SmokeStep::ExpectContent { path, text }
+            }
+            other => return Err(format!("line {}: unknown step '{other}'", index + 1)),
+        };
+        steps.push(step);
+    }
+    Ok(steps)
+}
+
+pub(crate) fn load_smoke_script(path: &Path) -> io::Result<Vec<SmokeStep>> {
+    parse_smoke_script(&fs::read_to_string(path)?)
+        .map_err(|message| io::Error::new(io::ErrorKind::InvalidData, message))
+}
+
+fn resolve_smoke_path(user_document_dir: &Path, path: &str) -> PathBuf {
+    let today = crate::file_update_handler::note_directory(user_document_dir, Local::now());
+    let today = today
+        .strip_prefix(user_document_dir)
+        .map(Path::to_path_buf)
+        .unwrap_or(today);
+    user_document_dir.join(path.replace("{today}", &today.to_string_lossy()))
+}
+
+fn check_expectation(user_document_dir: &Path, step: &SmokeStep) -> Result<(), String> {
+    match step {
+        SmokeStep::ExpectFile(path) => {
+            let full = resolve_smoke_path(user_document_dir, path);
+            full.is_file()
+                .then_some(())
+                .ok_or_else(|| format!("{} does not exist", full.display()))
+        }
+        SmokeStep::ExpectMissing(path) => {
+            let full = resolve_smoke_path(user_document_dir, path);
+            (!full.exists())
+                .then_some(())
+                .ok_or_else(|| format!("{} still exists", full.display()))
+        }
+        SmokeStep::ExpectContent { path, text } => {
+            let full = resolve_smoke_path(user_document_dir, path);
+            let actual = fs::read_to_string(&full)
+                .map_err(|error| format!("{}: {error}", full.display()))?;
+            (actual == *text).then_some(()).ok_or_else(|| {
+                format!(
+                    "{} holds '{}' instead of '{}'",
+                    full.display(),
+                    actual.escape_debug(),
+                    text.escape_debug()
+                )
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether a polled step is done: Some(Ok) settled, Some(Err) failed, None keep waiting.
+type SmokePoll = Option<Result<(), String>>;
+
+struct SmokeProgress {
+    started: Instant,
+    started_at: DateTime<Local>,
+    first_poll: bool,
+}
+
+impl crate::app::Papyru2App {
+    /// Drives `steps` through the same handlers the widgets use, then writes a report next
+    /// to the data dir and exits with 0 (all passed) or 1.
+    pub(crate) fn run_smoke_script(
+        steps: Vec<SmokeStep>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, cx| {
+            cx.background_executor().timer(SMOKE_STARTUP_DELAY).await;
+            let mut report = Vec::new();
+            let mut failure = None;
+            for (index, step) in steps.iter().enumerate() {
+                let timeout = match step {
+                    SmokeStep::Wait(duration) => *duration + SMOKE_DEFAULT_TIMEOUT,
+                    _ => SMOKE_DEFAULT_TIMEOUT,
+                };
+                let mut progress = SmokeProgress {
+                    started: Instant::now(),
+                    started_at: Local::now(),
+                    first_poll: true,
+                };
+                let result = loop {
+                    let polled = this.update_in(cx, |app, window, cx| {
+                        app.smoke_step(step, &progress, window, cx)
+                    });
+                    progress.first_poll = false;
+                    match polled {
+                        Err(error) => break Err(format!("window closed: {error}")),
+                        Ok(Some(result)) => break result,
+                        Ok(None) if progress.started.elapsed() > timeout => {
+                            break Err(format!("timed out after {} s", timeout.as_secs()));
+                        }
+                        Ok(None) => {
+                            cx.background_executor().timer(SMOKE_POLL_INTERVAL).await;
+                        }
+                    }
+                };
+                let line = match &result {
+                    Ok(()) => format!("ok   {:>3} {step:?}", index + 1),
+                    Err(reason) => format!("FAIL {:>3} {step:?}: {reason}", index + 1),
+                };
+                trace_debug(format!("req-smk1 {line}"));
+                report.push(line);
+                if let Err(reason) = result {
+                    failure = Some(format!("step {} failed: {reason}", index + 1));
+                    break;
+                }
+            }
+            let summary = failure
+                .clone()
+                .unwrap_or_else(|| format!("all {} steps passed", steps.len()));
+            report.push(summary.clone());
+            let _ = this.update(cx, |app, _| {
+                let report_path = app.app_paths.data_dir.join(SMOKE_TEST_REPORT_FILE_NAME);
+                if let Err(error) = crate::atomic_io::write_atomic(
+                    report_path.as_path(),
+                    report.join("\n").as_bytes(),
+                ) {
+                    eprintln!("papyru2 smoke test report not written: {error}");
+                }
+                app.file_workflow.shutdown_dispatcher();
+            });
+            eprintln!("papyru2 smoke test: {summary}");
+            std::process::exit(if failure.is_some() { 1 } else { 0 });
+        })
+        .detach();
+    }
+
+    fn smoke_step(
+        &mut self,
+        step: &SmokeStep,
+        progress: &SmokeProgress,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> SmokePoll {
+        match step {
+            SmokeStep::Title(title) => {
+                self.smoke_type_title(title, window, cx);
+                Some(Ok(()))
+            }
+            SmokeStep::Enter => {
+                self.transfer_singleline_enter(window, cx);
+                Some(Ok(()))
+            }
+            SmokeStep::Body(text) => {
+                let snapshot = self.editor.read(cx).snapshot(cx);
+                let value = format!("{}{text}", snapshot.value);
+                let line = value.matches('\n').count() as u32;
+                let column = value
+                    .rsplit('\n')
+                    .next()
+                    .map(|last| last.chars().count())
+                    .unwrap_or(0) as u32;
+                self.editor.update(cx, |editor, cx| {
+                    editor.apply_text_and_cursor(value.clone(), line, column, window, cx);
+                });
+                self.on_editor_user_buffer_changed(&value, cx);
+                Some(Ok(()))
+            }
+            SmokeStep::WaitAutosave => {
+                if let crate::status_bar::AutoSaveIndicator::Failed { error, .. } =
+                    &self.autosave_indicator
+                {
+                    return Some(Err(format!("autosave failed: {error}")));
+                }
+                let pending = self
+                    .file_workflow
+                    .current_edit_path()
+                    .is_some_and(|path| self.editor_autosave.has_pending_payload_for_path(&path));
+                // A save reported before this step belongs to an earlier edit.
+                let settled = !pending
+                    && matches!(
+                        self.autosave_indicator,
+                        crate::status_bar::AutoSaveIndicator::Saved { at } if at >= progress.started_at
+                    );
+                settled.then_some(Ok(()))
+            }
+            SmokeStep::Rename(title) => {
+                if progress.first_poll {
+                    self.smoke_type_title(title, window, cx);
+                }
+                let stem =
+                    crate::file_update_handler::stem_from_singleline_value(title, Local::now());
+                self.file_workflow
+                    .current_edit_path()
+                    .and_then(|path| path.file_stem().map(|name| name == stem.as_str()))
+                    .unwrap_or(false)
+                    .then_some(Ok(()))
+            }
+            SmokeStep::Plus => {
+                self.handle_plus_button(window, cx);
+                Some(Ok(()))
+            }
+            SmokeStep::Wait(duration) => {
+                (progress.started.elapsed() >= *duration).then_some(Ok(()))
+            }
+            SmokeStep::ExpectFile(_)
+            | SmokeStep::ExpectMissing(_)
+            | SmokeStep::ExpectContent { .. } => Some(check_expectation(
+                self.app_paths.user_document_dir.as_path(),
+                step,
+            )),
+        }
+    }
+
+    fn smoke_type_title(&mut self, title: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let cursor_char = title.chars().count();
+        let previous = self.singleline.read(cx).snapshot(cx).value;
+        self.singleline.update(cx, |singleline, cx| {
+            singleline.apply_text_and_cursor(title.to_string(), cursor_char, window, cx);
+        });
+        self.on_singleline_value_changed(&previous, title, window, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smk_test1_script_lines_become_steps() {
+        let steps = parse_smoke_script(
+            "# new note flow\n\ntitle plan\nenter\nbody first line\\nsecond\nwait-autosave\n\
+             rename plan v2\nwait 300\nexpect-content {today}/plan v2.txt = first line\\nsecond\n\
+             plus\nexpect-missing {today}/plan.txt\n",
+        )
+        .expect("parse");
+        assert_eq!(
+            steps,
+            vec![
+                SmokeStep::Title("plan".to_string()),
+                SmokeStep::Enter,
+                SmokeStep::Body("first line\nsecond".to_string()),
+                SmokeStep::WaitAutosave,
+                SmokeStep::Rename("plan v2".to_string()),
+                SmokeStep::Wait(Duration::from_millis(300)),
+                SmokeStep::ExpectContent {
+                    path: "{today}/plan v2.txt".to_string(),
+                    text: "first line\nsecond".to_string(),
+                },
+                SmokeStep::Plus,
+                SmokeStep::ExpectMissing("{today}/plan.txt".to_string()),
+            ]
+        );
+        assert!(parse_smoke_script("jump").is_err());
+        assert!(parse_smoke_script("rename").is_err());
+        assert!(parse_smoke_script("wait soon").is_err());
+        assert!(parse_smoke_script("expect-content plan.txt").is_err());
+    }
+}
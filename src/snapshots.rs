@@ -0,0 +1,577 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+use chrono::{DateTime, Local};
+use gpui::*;
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+
+pub(crate) const SNAPSHOTS_DIR_NAME: &str = "backups";
+const SNAPSHOT_MANIFEST_NAME: &str = "snapshot.toml";
+const SNAPSHOT_FILES_DIR_NAME: &str = "files";
+const SNAPSHOT_DEFAULT_MAX_MB: u64 = 200;
+const SNAPSHOT_BROWSER_PAGE_SIZE: usize = 8;
+const SNAPSHOT_DETAIL_MAX_FILES: usize = 10;
+
+static SNAPSHOT_STORE: OnceLock<Mutex<Option<SnapshotStore>>> = OnceLock::new();
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct SnapshotConfigFile {
+    #[serde(default)]
+    backup: SnapshotConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct SnapshotConfigSection {
+    snapshot_max_mb: Option<u64>,
+}
+
+/// `[backup] snapshot_max_mb` caps the snapshot folder (default 200 MB).
+pub(crate) fn load_snapshot_max_bytes(path: &Path) -> u64 {
+    let megabytes = fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| toml::from_str::<SnapshotConfigFile>(&raw).ok())
+        .and_then(|parsed| parsed.backup.snapshot_max_mb)
+        .unwrap_or(SNAPSHOT_DEFAULT_MAX_MB);
+    megabytes.saturating_mul(1024 * 1024)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct SnapshotFile {
+    pub original: PathBuf,
+    /// Relative to the snapshot folder.
+    pub stored: PathBuf,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct SnapshotManifest {
+    reason: String,
+    created_at: String,
+    files: Vec<SnapshotFile>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Snapshot {
+    pub dir: PathBuf,
+    pub reason: String,
+    pub created_at: String,
+    pub files: Vec<SnapshotFile>,
+}
+
+impl Snapshot {
+    pub fn label(&self) -> String {
+        let when = DateTime::parse_from_rfc3339(&self.created_at)
+            .map(|at| {
+                at.with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_else(|_| self.created_at.clone());
+        format!("{when} {} ({} file(s))", self.reason, self.files.len())
+    }
+}
+
+/// Copies of files taken just before papyru2 overwrites or deletes them for good, one
+/// folder per operation under `data/backups/<timestamp>/`.
+#[derive(Debug)]
+pub(crate) struct SnapshotStore {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+fn collect_snapshot_sources(path: &Path, files: &mut Vec<PathBuf>) {
+    if path.is_dir() {
+        let Ok(entries) = fs::read_dir(path) else {
+            return;
+        };
+        let mut children = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .collect::<Vec<_>>();
+        children.sort();
+        for child in children {
+            collect_snapshot_sources(child.as_path(), files);
+        }
+    } else if path.is_file() {
+        files.push(path.to_path_buf());
+    }
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let mut files = Vec::new();
+    collect_snapshot_sources(path, &mut files);
+    files
+        .iter()
+        .filter_map(|file| fs::metadata(file).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+impl SnapshotStore {
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self { dir, max_bytes }
+    }
+
+    /// Copies every existing file in `paths` (folders recursively). Returns the snapshot
+    /// folder, or None when there was nothing to keep.
+    pub fn take(
+        &self,
+        reason: &str,
+        paths: &[PathBuf],
+        now: DateTime<Local>,
+    ) -> io::Result<Option<PathBuf>> {
+        let mut sources = Vec::new();
+        for path in paths {
+            collect_snapshot_sources(path.as_path(), &mut sources);
+        }
+        if sources.is_empty() {
+            return Ok(None);
+        }
+
+        let stamp = now.format("%Y%m%d_%H%M%S_%3f").to_string();
+        let mut snapshot_dir = self.dir.join(&stamp);
+        let mut suffix = 2;
+        while snapshot_dir.exists() {
+            snapshot_dir = self.dir.join(format!("{stamp}_{suffix}"));
+            suffix += 1;
+        }
+        let files_dir = snapshot_dir.join(SNAPSHOT_FILES_DIR_NAME);
+        fs::create_dir_all(&files_dir)?;
+
+        let mut files = Vec::with_capacity(sources.len());
+        for (index, source) in sources.iter().enumerate() {
+            let name = source
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let stored = Path::new(SNAPSHOT_FILES_DIR_NAME).join(format!("{index:04}_{name}"));
+            fs::copy(source, snapshot_dir.join(&stored))?;
+            files.push(SnapshotFile {
+                original: source.clone(),
+                stored,
+            });
+        }
+        let manifest = SnapshotManifest {
+            reason: reason.to_string(),
+            created_at: now.to_rfc3339(),
+            files,
+        };
+        let manifest_text = toml::to_string(&manifest)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+        crate::atomic_io::write_atomic(
+            snapshot_dir.join(SNAPSHOT_MANIFEST_NAME).as_path(),
+            manifest_text.as_bytes(),
+        )?;
+        trace_debug(format!(
+            "req-snp1 snapshot reason={reason} path={} files={}",
+            snapshot_dir.display(),
+            manifest.files.len()
+        ));
+        self.prune(snapshot_dir.as_path());
+        Ok(Some(snapshot_dir))
+    }
+
+    /// Newest first; folders without a readable manifest are left out.
+    pub fn list(&self) -> Vec<Snapshot> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut dirs = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect::<Vec<_>>();
+        dirs.sort();
+        dirs.into_iter()
+            .rev()
+            .filter_map(|dir| {
+                let raw = fs::read_to_string(dir.join(SNAPSHOT_MANIFEST_NAME)).ok()?;
+                let manifest: SnapshotManifest = toml::from_str(&raw).ok()?;
+                Some(Snapshot {
+                    dir,
+                    reason: manifest.reason,
+                    created_at: manifest.created_at,
+                    files: manifest.files,
+                })
+            })
+            .collect()
+    }
+
+    // Drops the oldest snapshots until the folder fits the cap; the one just taken stays
+    // even when it alone is over the cap.
+    fn prune(&self, newest: &Path) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut dirs = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .map(|path| {
+                let size = dir_size(path.as_path());
+                (path, size)
+            })
+            .collect::<Vec<_>>();
+        dirs.sort();
+        let mut total = dirs.iter().map(|(_, size)| size).sum::<u64>();
+        for (dir, size) in dirs {
+            if total <= self.max_bytes {
+                break;
+            }
+            if dir == newest {
+                continue;
+            }
+            match fs::remove_dir_all(&dir) {
+                Ok(()) => total = total.saturating_sub(size),
+                Err(error) => trace_debug(format!(
+                    "req-snp1 prune failed path={} error={error}",
+                    dir.display()
+                )),
+            }
+        }
+    }
+}
+
+/// Puts every file of `snapshot` back where it was, replacing what is there now.
+pub(crate) fn restore_snapshot(snapshot: &Snapshot) -> io::Result<usize> {
+    for file in &snapshot.files {
+        let bytes = fs::read(snapshot.dir.join(&file.stored))?;
+        crate::storage_backend::storage_backend_containing(file.original.as_path())
+            .write(file.original.as_path(), &bytes)?;
+    }
+    trace_debug(format!(
+        "req-snp1 snapshot restored path={} files={}",
+        snapshot.dir.display(),
+        snapshot.files.len()
+    ));
+    Ok(snapshot.files.len())
+}
+
+fn with_store<R>(f: impl FnOnce(&SnapshotStore) -> R) -> Option<R> {
+    let store = SNAPSHOT_STORE
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    store.as_ref().map(f)
+}
+
+/// Called once at startup; until then destructive operations run without snapshots.
+pub(crate) fn open_snapshot_store(data_dir: &Path, config_path: &Path) {
+    let store = SnapshotStore::new(
+        data_dir.join(SNAPSHOTS_DIR_NAME),
+        load_snapshot_max_bytes(config_path),
+    );
+    *SNAPSHOT_STORE
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(store);
+}
+
+/// Snapshots `paths` before an overwrite or permanent delete. A failed snapshot is logged
+/// and does not stop the operation.
+pub(crate) fn snapshot_before(reason: &str, paths: &[PathBuf]) {
+    with_store(|store| {
+        if let Err(error) = store.take(reason, paths, Local::now()) {
+            trace_debug(format!(
+                "req-snp1 snapshot failed reason={reason} error={error}"
+            ));
+        }
+    });
+}
+
+pub(crate) fn list_snapshots() -> Vec<Snapshot> {
+    with_store(SnapshotStore::list).unwrap_or_default()
+}
+
+impl crate::app::Papyru2App {
+    /// Snapshots `paths` on the file worker, then runs `then` on the UI thread. A snapshot
+    /// that cannot be taken is logged and `then` still runs, as with [`snapshot_before`].
+    pub(crate) fn snapshot_then(
+        &mut self,
+        reason: &str,
+        paths: Vec<PathBuf>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        then: impl FnOnce(&mut Self, &mut Window, &mut Context<Self>) + 'static,
+    ) {
+        let (done_tx, done_rx) = smol::channel::bounded::<()>(1);
+        let queued = self
+            .file_workflow
+            .snapshot_async(reason, paths, move |result| {
+                if let Err(error) = result {
+                    trace_debug(format!("req-snp1 snapshot event failed error={error}"));
+                }
+                let _ = done_tx.try_send(());
+            });
+        if let Err(error) = queued {
+            trace_debug(format!("req-snp1 snapshot not queued error={error}"));
+            then(self, window, cx);
+            return;
+        }
+        cx.spawn_in(window, async move |this, cx| {
+            let _ = done_rx.recv().await;
+            let _ = this.update_in(cx, |app, window, cx| then(app, window, cx));
+        })
+        .detach();
+    }
+
+    pub(crate) fn show_snapshot_browser(
+        &mut self,
+        page: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshots = list_snapshots();
+        if snapshots.is_empty() {
+            self.show_transient_notice(tr("notice.no_snapshots").to_string(), cx);
+            return;
+        }
+        let start = page * SNAPSHOT_BROWSER_PAGE_SIZE;
+        let shown = snapshots
+            .iter()
+            .skip(start)
+            .take(SNAPSHOT_BROWSER_PAGE_SIZE)
+            .cloned()
+            .collect::<Vec<_>>();
+        let has_more = start + shown.len() < snapshots.len();
+        let mut labels = shown.iter().map(Snapshot::label).collect::<Vec<_>>();
+        if has_more {
+            labels.push(tr("button.more").to_string());
+        }
+        labels.push(tr("button.cancel").to_string());
+        let label_refs = labels.iter().map(String::as_str).collect::<Vec<_>>();
+        let detail = tr_with(
+            "prompt.snapshots_detail",
+            &[("count", snapshots.len().to_string().as_str())],
+        );
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &tr("prompt.snapshots"),
+            Some(&detail),
+            &label_refs,
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(choice) = answer.await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                if let Some(snapshot) = shown.get(choice) {
+                    app.show_snapshot_detail(snapshot.clone(), window, cx);
+                } else if has_more && choice == shown.len() {
+                    app.show_snapshot_browser(page + 1, window, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn show_snapshot_detail(
+        &mut self,
+        snapshot: Snapshot,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mut detail = snapshot
+            .files
+            .iter()
+            .take(SNAPSHOT_DETAIL_MAX_FILES)
+            .map(|file| file.original.display().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if snapshot.files.len() > SNAPSHOT_DETAIL_MAX_FILES {
+            detail.push('\n');
+            detail.push_str(&tr_with(
+                "prompt.and_more",
+                &[(
+                    "count",
+                    (snapshot.files.len() - SNAPSHOT_DETAIL_MAX_FILES)
+                        .to_string()
+                        .as_str(),
+                )],
+            ));
+        }
+        let buttons = [
+            tr("button.restore_files"),
+            tr("button.back"),
+            tr("button.cancel"),
+        ];
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            &snapshot.label(),
+            Some(&detail),
+            &[
+                buttons[0].as_ref(),
+                buttons[1].as_ref(),
+                buttons[2].as_ref(),
+            ],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(choice) = answer.await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| match choice {
+                0 => app.restore_snapshot_files(&snapshot, window, cx),
+                1 => app.show_snapshot_browser(0, window, cx),
+                _ => {}
+            });
+        })
+        .detach();
+    }
+
+    fn restore_snapshot_files(
+        &mut self,
+        snapshot: &Snapshot,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.flush_editor_content_before_context_switch("req-snp1-restore", cx) {
+            self.show_transient_notice(tr("notice.restore_needs_save").to_string(), cx);
+            return;
+        }
+        // What the restore replaces gets a snapshot of its own, so the restore can be undone.
+        let current = snapshot
+            .files
+            .iter()
+            .map(|file| file.original.clone())
+            .collect::<Vec<_>>();
+        let snapshot = snapshot.clone();
+        self.snapshot_then(
+            "snapshot restore",
+            current,
+            window,
+            cx,
+            move |app, window, cx| app.finish_snapshot_restore(&snapshot, window, cx),
+        );
+    }
+
+    fn finish_snapshot_restore(
+        &mut self,
+        snapshot: &Snapshot,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let message = match restore_snapshot(snapshot) {
+            Ok(files) => {
+                self.apply_file_tree_watcher_refresh(cx);
+                self.check_current_file_external_change(window, cx);
+                tr_with(
+                    "notice.snapshot_restored",
+                    &[("count", files.to_string().as_str())],
+                )
+            }
+            Err(error) => {
+                trace_debug(format!("req-snp1 snapshot restore failed error={error}"));
+                tr_with(
+                    "notice.snapshot_restore_failed",
+                    &[("reason", error.to_string().as_str())],
+                )
+            }
+        };
+        self.show_transient_notice(message, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_snapshots_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    fn at(second: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 3, 1, 9, 0, second)
+            .single()
+            .expect("local time")
+    }
+
+    #[test]
+    fn snp_test1_snapshot_copies_files_and_restores_them_in_place() {
+        let root = new_temp_root("snp_test1");
+        let notes = root.join("notes");
+        fs::create_dir_all(notes.join("folder")).expect("create notes");
+        fs::write(notes.join("plan.md"), "plan").expect("plan");
+        fs::write(notes.join("folder/a.md"), "a").expect("a");
+        let store = SnapshotStore::new(root.join(SNAPSHOTS_DIR_NAME), u64::MAX);
+
+        assert_eq!(
+            store
+                .take("bulk replace", &[notes.join("missing.md")], at(0))
+                .expect("empty snapshot"),
+            None
+        );
+        let dir = store
+            .take(
+                "bulk replace",
+                &[notes.join("plan.md"), notes.join("folder")],
+                at(1),
+            )
+            .expect("snapshot")
+            .expect("snapshot dir");
+        assert_eq!(
+            dir.file_name().and_then(|name| name.to_str()),
+            Some("20260301_090001_000")
+        );
+
+        fs::write(notes.join("plan.md"), "replaced").expect("overwrite");
+        fs::remove_dir_all(notes.join("folder")).expect("purge");
+        let snapshots = store.list();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].reason, "bulk replace");
+        assert_eq!(restore_snapshot(&snapshots[0]).expect("restore"), 2);
+        assert_eq!(
+            fs::read_to_string(notes.join("plan.md")).expect("read"),
+            "plan"
+        );
+        assert_eq!(
+            fs::read_to_string(notes.join("folder/a.md")).expect("read"),
+            "a"
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn snp_test2_oldest_snapshots_are_dropped_over_the_size_cap() {
+        let root = new_temp_root("snp_test2");
+        let note = root.join("note.md");
+        fs::write(&note, "x".repeat(10_000)).expect("note");
+        let store = SnapshotStore::new(root.join(SNAPSHOTS_DIR_NAME), 25_000);
+
+        for second in 0..3 {
+            store
+                .take("purge", std::slice::from_ref(&note), at(second))
+                .expect("snapshot");
+        }
+        let kept = store.list();
+        assert_eq!(kept.len(), 2);
+        assert!(kept[0].dir.ends_with("20260301_090002_000"));
+        assert!(kept[1].dir.ends_with("20260301_090001_000"));
+
+        // A single snapshot over the cap is still kept.
+        let tight = SnapshotStore::new(root.join("tight"), 100);
+        tight
+            .take("purge", std::slice::from_ref(&note), at(5))
+            .expect("snapshot");
+        assert_eq!(tight.list().len(), 1);
+        let _ = fs::remove_dir_all(root);
+    }
+}
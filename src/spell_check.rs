@@ -0,0 +1,407 @@
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+pub(crate) const SPELL_CHECK_DICTIONARIES_DIR_NAME: &str = "dictionaries";
+pub(crate) const SPELL_CHECK_BUNDLED_LANGUAGE: &str = "en";
+pub(crate) const SPELL_CHECK_MAX_MISSPELLINGS: usize = 500;
+pub(crate) const SPELL_CHECK_MAX_SUGGESTIONS: usize = 5;
+
+const BUNDLED_EN_DICTIONARY: &str = include_str!("../assets/dictionaries/en.txt");
+const SIMPLE_SUFFIXES: [&str; 6] = ["'s", "s", "es", "ed", "ing", "ly"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Misspelling {
+    pub line: u32,
+    pub start_char: u32,
+    pub end_char: u32,
+    pub word: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SpellChecker {
+    languages: Vec<String>,
+    words: HashSet<String>,
+}
+
+fn parse_word_list(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_lowercase)
+}
+
+pub(crate) fn spell_check_dictionaries_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(SPELL_CHECK_DICTIONARIES_DIR_NAME)
+}
+
+// Only plain ASCII words are checked; CJK and other scripts are never flagged.
+fn is_checkable_word(word: &str) -> bool {
+    word.chars().count() > 1
+        && word
+            .chars()
+            .all(|ch| ch.is_ascii_alphabetic() || ch == '\'')
+        && !word.chars().all(|ch| !ch.is_ascii_lowercase())
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '\'' || ch == '_'
+}
+
+// Optimal string alignment distance, so a swapped letter pair ("wrold") costs one edit.
+fn edit_distance(left: &str, right: &str) -> usize {
+    let left = left.chars().collect::<Vec<_>>();
+    let right = right.chars().collect::<Vec<_>>();
+    let mut table = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in table[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=left.len() {
+        for j in 1..=right.len() {
+            let cost = usize::from(left[i - 1] != right[j - 1]);
+            let mut best = (table[i - 1][j] + 1)
+                .min(table[i][j - 1] + 1)
+                .min(table[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && left[i - 1] == right[j - 2] && left[i - 2] == right[j - 1] {
+                best = best.min(table[i - 2][j - 2] + 1);
+            }
+            table[i][j] = best;
+        }
+    }
+    table[left.len()][right.len()]
+}
+
+impl SpellChecker {
+    pub fn bundled() -> Self {
+        let mut checker = Self::default();
+        checker.add_word_list(SPELL_CHECK_BUNDLED_LANGUAGE, BUNDLED_EN_DICTIONARY);
+        checker
+    }
+
+    // Bundled English plus every `<language>.txt` word list under `data_dir/dictionaries`.
+    pub fn load(data_dir: &Path) -> io::Result<Self> {
+        let mut checker = Self::bundled();
+        let dir = spell_check_dictionaries_dir(data_dir);
+        if !dir.is_dir() {
+            return Ok(checker);
+        }
+
+        let mut paths = fs::read_dir(&dir)?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .is_some_and(|ext| ext.eq_ignore_ascii_case("txt"))
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+        for path in paths {
+            let Some(language) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let text = fs::read_to_string(&path)?;
+            checker.add_word_list(language, &text);
+        }
+        Ok(checker)
+    }
+
+    pub fn add_word_list(&mut self, language: &str, text: &str) {
+        let language = language.to_ascii_lowercase();
+        if !self.languages.contains(&language) {
+            self.languages.push(language);
+        }
+        self.words.extend(parse_word_list(text));
+    }
+
+    pub fn languages(&self) -> &[String] {
+        &self.languages
+    }
+
+    pub fn is_known_word(&self, word: &str) -> bool {
+        let lowered = word.to_lowercase();
+        if self.words.contains(&lowered) {
+            return true;
+        }
+        SIMPLE_SUFFIXES.iter().any(|suffix| {
+            lowered
+                .strip_suffix(suffix)
+                .is_some_and(|base| base.len() > 1 && self.words.contains(base))
+        })
+    }
+
+    pub fn check_text(&self, text: &str) -> Vec<Misspelling> {
+        let mut misspellings = Vec::new();
+        for (line_index, line) in text.split('\n').enumerate() {
+            misspellings.extend(self.check_line(line_index, line));
+            if misspellings.len() >= SPELL_CHECK_MAX_MISSPELLINGS {
+                misspellings.truncate(SPELL_CHECK_MAX_MISSPELLINGS);
+                break;
+            }
+        }
+        misspellings
+    }
+
+    fn check_line(&self, line_index: usize, line: &str) -> Vec<Misspelling> {
+        let mut misspellings = Vec::new();
+        let mut word_start: Option<(usize, usize)> = None;
+        let mut char_index = 0usize;
+        for (byte_index, ch) in line.char_indices().chain([(line.len(), ' ')]) {
+            if is_word_char(ch) && byte_index < line.len() {
+                if word_start.is_none() {
+                    word_start = Some((byte_index, char_index));
+                }
+            } else if let Some((start_byte, start_char)) = word_start.take() {
+                let word = line[start_byte..byte_index].trim_matches('\'');
+                if is_checkable_word(word) && !self.is_known_word(word) {
+                    let leading_quotes = line[start_byte..byte_index].len()
+                        - line[start_byte..byte_index].trim_start_matches('\'').len();
+                    let start_char = start_char + leading_quotes;
+                    misspellings.push(Misspelling {
+                        line: line_index.min(u32::MAX as usize) as u32,
+                        start_char: start_char.min(u32::MAX as usize) as u32,
+                        end_char: (start_char + word.chars().count()).min(u32::MAX as usize) as u32,
+                        word: word.to_string(),
+                    });
+                }
+            }
+            char_index += 1;
+        }
+        misspellings
+    }
+
+    pub fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+        let lowered = word.to_lowercase();
+        let mut candidates = self
+            .words
+            .iter()
+            .filter(|candidate| candidate.len().abs_diff(lowered.len()) <= 2)
+            .filter_map(|candidate| {
+                let distance = edit_distance(&lowered, candidate);
+                (distance <= 2).then_some((
+                    distance,
+                    candidate.len().abs_diff(lowered.len()),
+                    candidate.as_str(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        // Closest first; among equally close words, ones of the same length (a swapped or
+        // mistyped letter) before ones that add or drop letters.
+        candidates.sort();
+
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        candidates
+            .into_iter()
+            .take(limit)
+            .map(|(_, _, candidate)| {
+                if !capitalized {
+                    return candidate.to_string();
+                }
+                let mut chars = candidate.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+/// The last checked text, line by line, with each line's misspellings. A re-check only
+/// looks at the lines between the unchanged head and tail of the buffer, so a keystroke
+/// costs one line rather than the whole note.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SpellCheckCache {
+    lines: Vec<String>,
+    misspellings: Vec<Vec<Misspelling>>,
+}
+
+impl SpellCheckCache {
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.misspellings.clear();
+    }
+
+    /// Re-checks the changed lines of `text` and returns how many were checked.
+    pub fn update(&mut self, checker: &SpellChecker, text: &str) -> usize {
+        let lines = text.split('\n').collect::<Vec<_>>();
+        let head = self
+            .lines
+            .iter()
+            .zip(&lines)
+            .take_while(|(old, new)| old.as_str() == **new)
+            .count();
+        let tail = self.lines[head..]
+            .iter()
+            .rev()
+            .zip(lines[head..].iter().rev())
+            .take_while(|(old, new)| old.as_str() == **new)
+            .count();
+        let old_end = self.lines.len() - tail;
+        let new_end = lines.len() - tail;
+
+        let checked = (head..new_end)
+            .map(|index| checker.check_line(index, lines[index]))
+            .collect::<Vec<_>>();
+        self.lines.splice(
+            head..old_end,
+            lines[head..new_end].iter().map(|line| line.to_string()),
+        );
+        self.misspellings.splice(head..old_end, checked);
+        if old_end != new_end {
+            for (index, found) in self.misspellings.iter_mut().enumerate().skip(new_end) {
+                for misspelling in found {
+                    misspelling.line = index.min(u32::MAX as usize) as u32;
+                }
+            }
+        }
+        new_end - head
+    }
+
+    pub fn misspellings(&self) -> Vec<Misspelling> {
+        self.misspellings
+            .iter()
+            .flatten()
+            .take(SPELL_CHECK_MAX_MISSPELLINGS)
+            .cloned()
+            .collect()
+    }
+}
+
+pub(crate) fn misspelling_at(
+    misspellings: &[Misspelling],
+    line: u32,
+    character: u32,
+) -> Option<&Misspelling> {
+    misspellings.iter().find(|misspelling| {
+        misspelling.line == line
+            && misspelling.start_char <= character
+            && character <= misspelling.end_char
+    })
+}
+
+pub(crate) fn replace_misspelling(
+    text: &str,
+    misspelling: &Misspelling,
+    replacement: &str,
+) -> String {
+    let mut rewritten = String::with_capacity(text.len() + replacement.len());
+    for (line_index, line) in text.split('\n').enumerate() {
+        if line_index > 0 {
+            rewritten.push('\n');
+        }
+        if line_index as u32 != misspelling.line {
+            rewritten.push_str(line);
+            continue;
+        }
+        let start = misspelling.start_char as usize;
+        let end = misspelling.end_char as usize;
+        rewritten.extend(line.chars().take(start));
+        rewritten.push_str(replacement);
+        rewritten.extend(line.chars().skip(end));
+    }
+    rewritten
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_spell_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    #[test]
+    fn spell_test1_flags_unknown_ascii_words_with_char_ranges() {
+        let checker = SpellChecker::bundled();
+
+        let found = checker.check_text("hello wrold\nこんにちは notes NASA x2 'helo'");
+
+        assert_eq!(
+            found,
+            vec![
+                Misspelling {
+                    line: 0,
+                    start_char: 6,
+                    end_char: 11,
+                    word: "wrold".to_string(),
+                },
+                Misspelling {
+                    line: 1,
+                    start_char: 21,
+                    end_char: 25,
+                    word: "helo".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn spell_test2_suggestions_rank_by_edit_distance_and_keep_case() {
+        let checker = SpellChecker::bundled();
+
+        let suggestions = checker.suggestions("Wrold", SPELL_CHECK_MAX_SUGGESTIONS);
+
+        assert_eq!(suggestions.first().map(String::as_str), Some("World"));
+        let found = checker.check_text("the wrold");
+        let rewritten = replace_misspelling("the wrold", &found[0], "world");
+        assert_eq!(rewritten, "the world");
+        assert_eq!(misspelling_at(&found, 0, 6), found.first());
+        assert_eq!(misspelling_at(&found, 0, 1), None);
+    }
+
+    #[test]
+    fn spell_test3_user_dictionaries_are_loaded_from_data_dir() {
+        let root = new_temp_root("spell_test3");
+        let dir = spell_check_dictionaries_dir(root.as_path());
+        fs::create_dir_all(&dir).expect("create dictionaries dir");
+        fs::write(dir.join("de.txt"), "# german\nguten\nmorgen\n").expect("write de");
+        assert_eq!(SpellChecker::bundled().check_text("guten morgen").len(), 1);
+
+        let checker = SpellChecker::load(root.as_path()).expect("load dictionaries");
+
+        assert_eq!(checker.languages(), ["en".to_string(), "de".to_string()]);
+        assert!(checker.check_text("guten morgen").is_empty());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn spell_test4_cache_rechecks_only_changed_lines_and_shifts_the_rest() {
+        let checker = SpellChecker::bundled();
+        let mut cache = SpellCheckCache::default();
+
+        assert_eq!(cache.update(&checker, "wrold one\nfine\nthe helo"), 3);
+        assert_eq!(
+            cache.misspellings(),
+            checker.check_text("wrold one\nfine\nthe helo")
+        );
+
+        assert_eq!(cache.update(&checker, "wrold one\nfine\nthe helo"), 0);
+        assert_eq!(cache.update(&checker, "wrold one\nfien\nthe helo"), 1);
+
+        let text = "wrold one\nnew\nlines\nfien\nthe helo";
+        assert_eq!(cache.update(&checker, text), 2);
+        assert_eq!(cache.misspellings(), checker.check_text(text));
+
+        assert_eq!(cache.update(&checker, "the helo"), 0);
+        assert_eq!(cache.misspellings(), checker.check_text("the helo"));
+    }
+}
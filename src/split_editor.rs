@@ -0,0 +1,460 @@
+use std::{path::PathBuf, time::Instant};
+
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    resizable::{ResizablePanelEvent, ResizableState},
+    v_flex,
+};
+
+use crate::file_update_handler::EditorAutoSavePayload;
+use crate::log::trace_debug;
+
+#[derive(Clone, Debug)]
+pub enum SplitEditorEvent {
+    Edited(String),
+    Closed,
+}
+
+/// The second note, shown beside the main editor. Its edits are saved in place as their own
+/// autosave payload; the file workflow keeps a split Edit binding next to the main one so
+/// moves of the note are followed.
+pub struct SplitEditorView {
+    path: PathBuf,
+    input: Entity<InputState>,
+    ui_color_config: crate::app::UiColorConfig,
+    // Text put in from disk (open, reload after a rewrite) is not an edit.
+    pending_programmatic_change_events: usize,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<SplitEditorEvent> for SplitEditorView {}
+
+impl SplitEditorView {
+    pub fn new(
+        path: PathBuf,
+        text: String,
+        ui_color_config: crate::app::UiColorConfig,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let input = cx.new(|cx| InputState::new(window, cx).multi_line(true));
+        input.update(cx, |state, cx| state.set_value(text, window, cx));
+        // Subscribed after the initial value so loading the note is not taken for an edit.
+        let subscription =
+            cx.subscribe(&input, |this: &mut Self, input, event: &InputEvent, cx| {
+                if let InputEvent::Change = event {
+                    if this.pending_programmatic_change_events > 0 {
+                        this.pending_programmatic_change_events -= 1;
+                        return;
+                    }
+                    let text = input.read(cx).value().to_string();
+                    cx.emit(SplitEditorEvent::Edited(text));
+                }
+            });
+        Self {
+            path,
+            input,
+            ui_color_config,
+            pending_programmatic_change_events: 0,
+            _subscription: subscription,
+        }
+    }
+
+    pub fn text(&self, cx: &App) -> String {
+        self.input.read(cx).value().to_string()
+    }
+
+    pub fn set_path(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        self.path = path;
+        cx.notify();
+    }
+
+    pub fn set_text(&mut self, text: String, window: &mut Window, cx: &mut Context<Self>) {
+        self.pending_programmatic_change_events += 1;
+        self.input
+            .update(cx, |state, cx| state.set_value(text, window, cx));
+    }
+
+    pub fn set_ui_color_config(
+        &mut self,
+        ui_color_config: crate::app::UiColorConfig,
+        cx: &mut Context<Self>,
+    ) {
+        self.ui_color_config = ui_color_config;
+        cx.notify();
+    }
+}
+
+impl Render for SplitEditorView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let header = h_flex()
+            .w_full()
+            .gap_2()
+            .items_center()
+            .text_xs()
+            .child(
+                div()
+                    .flex_1()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(name),
+            )
+            .child(
+                Button::new("split-editor-close")
+                    .small()
+                    .ghost()
+                    .label(crate::i18n::tr("button.close"))
+                    .on_click(cx.listener(|_, _, _, cx| cx.emit(SplitEditorEvent::Closed))),
+            );
+        v_flex()
+            .size_full()
+            .pl_2()
+            .gap_1()
+            .bg(crate::window_overlay::panel_background(
+                self.ui_color_config.background_rgb_hex,
+                cx,
+            ))
+            .text_color(crate::app::req_colr_rgb_hex_to_hsla(
+                self.ui_color_config.foreground_rgb_hex,
+            ))
+            .child(header)
+            .child(
+                div()
+                    .flex_1()
+                    .min_h_0()
+                    .child(Input::new(&self.input).size_full()),
+            )
+    }
+}
+
+pub(crate) struct SplitEditor {
+    pub path: PathBuf,
+    pub view: Entity<SplitEditorView>,
+    pub layout_state: Entity<ResizableState>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn open_in_split(
+        &mut self,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if self.file_workflow.current_edit_path().as_ref() == Some(&path) {
+            self.show_transient_notice(
+                crate::i18n::tr_with("notice.already_open", &[("name", name.as_str())]),
+                cx,
+            );
+            return;
+        }
+        if self
+            .split_editor
+            .as_ref()
+            .is_some_and(|split| split.path == path)
+        {
+            return;
+        }
+        // The note shown so far is saved first; the new one opens once that is on disk.
+        if self.split_editor.is_some() {
+            self.close_split_editor_then(
+                move |app, window, cx| app.open_in_split(path, window, cx),
+                window,
+                cx,
+            );
+            return;
+        }
+        let text = match crate::text_encoding::open_note_text(path.as_path()) {
+            Ok(text) => text,
+            Err(error) => {
+                self.show_transient_notice(
+                    crate::i18n::tr_with(
+                        "error.cannot_open",
+                        &[
+                            ("name", name.as_str()),
+                            ("reason", error.to_string().as_str()),
+                        ],
+                    ),
+                    cx,
+                );
+                return;
+            }
+        };
+        trace_debug(format!("req-split1 open path={}", path.display()));
+        let view =
+            cx.new(|cx| SplitEditorView::new(path.clone(), text, self.ui_color_config, window, cx));
+        let subscription = cx.subscribe_in(
+            &view,
+            window,
+            |this, _, event: &SplitEditorEvent, window, cx| {
+                this.handle_split_editor_event(event, window, cx);
+            },
+        );
+        let layout_state = cx.new(|_| ResizableState::default());
+        let layout_subscription = cx.subscribe(
+            &layout_state,
+            |this, layout_state, event: &ResizablePanelEvent, cx| match event {
+                ResizablePanelEvent::Resized => {
+                    let width = layout_state.read(cx).sizes().first().copied();
+                    this.ui_state.editor_split_width = width.map(f32::from);
+                    this.persist_ui_state();
+                }
+            },
+        );
+        self.editor_autosave.set_split_path(Some(path.clone()));
+        self.file_workflow.bind_split_edit(Some(path.clone()));
+        self.split_editor = Some(SplitEditor {
+            path,
+            view,
+            layout_state,
+            _subscriptions: vec![subscription, layout_subscription],
+        });
+        cx.notify();
+    }
+
+    fn handle_split_editor_event(
+        &mut self,
+        event: &SplitEditorEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            SplitEditorEvent::Edited(text) => {
+                let Some(split) = self.split_editor.as_ref() else {
+                    return;
+                };
+                self.editor_autosave.mark_user_edit(
+                    EditorAutoSavePayload {
+                        user_document_dir: self.app_paths.user_document_dir.clone(),
+                        current_path: split.path.clone(),
+                        editor_text: text.clone(),
+                    },
+                    Instant::now(),
+                );
+            }
+            SplitEditorEvent::Closed => {
+                self.close_split_editor_then(|_, _, _| {}, window, cx);
+            }
+        }
+    }
+
+    // The split note's pending edit as a payload taken out of the autosave queue.
+    fn take_split_editor_payload(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Option<EditorAutoSavePayload> {
+        let split = self.split_editor.as_ref()?;
+        if !self
+            .editor_autosave
+            .has_pending_payload_for_path(split.path.as_path())
+        {
+            return None;
+        }
+        let payload = EditorAutoSavePayload {
+            user_document_dir: self.app_paths.user_document_dir.clone(),
+            current_path: split.path.clone(),
+            editor_text: split.view.read(cx).text(cx),
+        };
+        self.editor_autosave
+            .clear_pending_for_path(payload.current_path.as_path());
+        Some(payload)
+    }
+
+    /// Queues the split note's pending edit on the workflow worker, ahead of a batch (bulk
+    /// replace, link rewrite) that may rewrite the same file; a later autosave would undo it.
+    pub(crate) fn queue_split_editor_save(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.split_editor.is_some() {
+            self.save_split_editor_then(|_, _, _| {}, window, cx);
+        }
+    }
+
+    /// Closes the pane. A pending edit is saved through the workflow worker first and `then`
+    /// runs once it is on disk; a failed save keeps the pane open with the edit pending.
+    pub(crate) fn close_split_editor_then(
+        &mut self,
+        then: impl FnOnce(&mut Self, &mut Window, &mut Context<Self>) + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.save_split_editor_then(
+            move |app, window, cx| {
+                // Typing that went on while the save was queued is saved too.
+                let typed_meanwhile = app.split_editor.as_ref().is_some_and(|split| {
+                    app.editor_autosave
+                        .has_pending_payload_for_path(split.path.as_path())
+                });
+                if typed_meanwhile {
+                    app.close_split_editor_then(then, window, cx);
+                    return;
+                }
+                app.discard_split_editor(cx);
+                then(app, window, cx);
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn save_split_editor_then(
+        &mut self,
+        then: impl FnOnce(&mut Self, &mut Window, &mut Context<Self>) + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(payload) = self.take_split_editor_payload(cx) else {
+            then(self, window, cx);
+            return;
+        };
+        let (result_tx, result_rx) =
+            smol::channel::bounded::<crate::workflow_error::WorkflowResult<PathBuf>>(1);
+        if let Err(error) =
+            self.file_workflow
+                .save_detached_payload_async(payload.clone(), move |result| {
+                    let _ = result_tx.try_send(result);
+                })
+        {
+            self.finish_split_editor_save(payload, Err(error), then, window, cx);
+            return;
+        }
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(result) = result_rx.recv().await else {
+                return;
+            };
+            let _ = this.update_in(cx, move |app, window, cx| {
+                app.finish_split_editor_save(payload, result, then, window, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn finish_split_editor_save(
+        &mut self,
+        payload: EditorAutoSavePayload,
+        result: crate::workflow_error::WorkflowResult<PathBuf>,
+        then: impl FnOnce(&mut Self, &mut Window, &mut Context<Self>) + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let path = payload.current_path.clone();
+        match result {
+            Ok(_) => then(self, window, cx),
+            Err(error) => {
+                trace_debug(format!(
+                    "req-split1 save failed path={} error={error}",
+                    path.display()
+                ));
+                self.show_toast(
+                    crate::toasts::ToastSeverity::Error,
+                    crate::i18n::tr_with(
+                        "error.split_save_failed",
+                        &[("error", error.to_string().as_str())],
+                    ),
+                    cx,
+                );
+                // Still shown and not edited since: the text goes back to waiting for autosave.
+                let still_open = self
+                    .split_editor
+                    .as_ref()
+                    .is_some_and(|split| split.path == path);
+                if still_open && !self.editor_autosave.has_pending_payload_for_path(&path) {
+                    self.editor_autosave.mark_user_edit(payload, Instant::now());
+                }
+            }
+        }
+    }
+
+    fn discard_split_editor(&mut self, cx: &mut Context<Self>) {
+        let Some(split) = self.split_editor.take() else {
+            return;
+        };
+        trace_debug(format!("req-split1 close path={}", split.path.display()));
+        self.editor_autosave.set_split_path(None);
+        self.file_workflow.bind_split_edit(None);
+        cx.notify();
+    }
+
+    /// Follows the split note after the tree changed: a move the workflow tracked moves the
+    /// pane along, and a note that is gone closes it instead of failing every autosave.
+    pub(crate) fn sync_split_editor_with_workflow(&mut self, cx: &mut Context<Self>) {
+        let Some(split) = self.split_editor.as_mut() else {
+            return;
+        };
+        let old_path = split.path.clone();
+        if let Some(bound) = self
+            .file_workflow
+            .split_edit_path()
+            .filter(|bound| *bound != old_path)
+        {
+            trace_debug(format!(
+                "req-split1 follow old={} new={}",
+                old_path.display(),
+                bound.display()
+            ));
+            split.path = bound.clone();
+            split
+                .view
+                .update(cx, |view, cx| view.set_path(bound.clone(), cx));
+            self.editor_autosave
+                .retarget_pending_payload(old_path.as_path(), bound.as_path());
+            self.editor_autosave.set_split_path(Some(bound));
+            return;
+        }
+        if old_path.is_file() {
+            return;
+        }
+        self.editor_autosave
+            .clear_pending_for_path(old_path.as_path());
+        self.discard_split_editor(cx);
+        let name = old_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.show_transient_notice(
+            crate::i18n::tr_with("notice.split_note_gone", &[("name", name.as_str())]),
+            cx,
+        );
+    }
+
+    /// Shows the split note as it is on disk again after a batch rewrote it.
+    pub(crate) fn reload_split_editor_if_rewritten(
+        &mut self,
+        rewritten: &[PathBuf],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(split) = self.split_editor.as_ref() else {
+            return;
+        };
+        if !rewritten.contains(&split.path)
+            || self
+                .editor_autosave
+                .has_pending_payload_for_path(split.path.as_path())
+        {
+            return;
+        }
+        match crate::text_encoding::read_note_text(split.path.as_path()) {
+            Ok((text, _)) => {
+                trace_debug(format!("req-split1 reload path={}", split.path.display()));
+                split
+                    .view
+                    .update(cx, |view, cx| view.set_text(text, window, cx));
+            }
+            Err(error) => trace_debug(format!(
+                "req-split1 reload failed path={} error={error}",
+                split.path.display()
+            )),
+        }
+    }
+}
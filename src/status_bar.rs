@@ -0,0 +1,184 @@
+use chrono::{DateTime, Local};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::{ActiveTheme as _, h_flex};
+
+use crate::file_update_handler::EditorAutoSaveEvent;
+
+/// What the status bar says about the idle autosave. A failure stays visible until the
+/// next autosave attempt.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) enum AutoSaveIndicator {
+    #[default]
+    Idle,
+    Saving,
+    Saved {
+        at: DateTime<Local>,
+    },
+    Failed {
+        file_name: String,
+        error: String,
+        remediation: Option<&'static str>,
+    },
+}
+
+impl AutoSaveIndicator {
+    pub fn apply(&mut self, event: &EditorAutoSaveEvent, now: DateTime<Local>) {
+        *self = match event {
+            EditorAutoSaveEvent::Saving { .. } => Self::Saving,
+            EditorAutoSaveEvent::Saved { .. } => Self::Saved { at: now },
+            EditorAutoSaveEvent::Failed {
+                path,
+                error,
+                remediation,
+            } => Self::Failed {
+                file_name: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                error: error.clone(),
+                remediation: *remediation,
+            },
+        };
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed { .. })
+    }
+
+    pub fn label(&self) -> Option<String> {
+        match self {
+            Self::Idle => None,
+            Self::Saving => Some("Saving\u{2026}".to_string()),
+            Self::Saved { at } => Some(format!("Saved {}", at.format("%H:%M:%S"))),
+            Self::Failed {
+                file_name,
+                error,
+                remediation: None,
+            } => Some(format!("Save failed ({file_name}): {error}")),
+            Self::Failed {
+                file_name,
+                error,
+                remediation: Some(hint),
+            } => Some(format!("Save failed ({file_name}): {error}; {hint}")),
+        }
+    }
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn spawn_autosave_status_loop(
+        event_rx: smol::channel::Receiver<EditorAutoSaveEvent>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, cx| {
+            while let Ok(event) = event_rx.recv().await {
+                let updated = this.update_in(cx, |app, window, cx| {
+                    if let EditorAutoSaveEvent::Failed { path, error, .. } = &event {
+                        crate::log::trace_debug(format!(
+                            "autosave status failed path={} error={error}",
+                            path.display()
+                        ));
+                    }
+                    if let (EditorAutoSaveEvent::Saved { .. }, Some(git_versioning)) =
+                        (&event, app.git_versioning.as_ref())
+                    {
+                        git_versioning.autosave_saved();
+                    }
+                    if let EditorAutoSaveEvent::Saved { path } = &event {
+                        app.editor.update(cx, |editor, cx| {
+                            editor.follow_stamped_note_metadata(path, window, cx)
+                        });
+                        app.show_encoding_fallbacks(cx);
+                    }
+                    app.autosave_indicator.apply(&event, Local::now());
+                    if let (true, Some(label)) = (
+                        app.autosave_indicator.is_failed(),
+                        app.autosave_indicator.label(),
+                    ) {
+                        app.show_toast(crate::toasts::ToastSeverity::Error, label, cx);
+                    }
+                    cx.notify();
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    pub(crate) fn render_status_bar(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let autosave = self.autosave_indicator.label().map(|label| {
+            div()
+                .when(self.autosave_indicator.is_failed(), |label| {
+                    label.text_color(cx.theme().danger)
+                })
+                .child(label)
+        });
+        let mirror_backup = self.render_mirror_backup_status(cx);
+        let sync = self.render_sync_status(cx);
+        let language = self.render_syntax_language_status(cx);
+        let notifications = self.render_toast_history_button(cx);
+        if autosave.is_none()
+            && mirror_backup.is_none()
+            && sync.is_none()
+            && language.is_none()
+            && notifications.is_none()
+        {
+            return None;
+        }
+        Some(
+            h_flex()
+                .w_full()
+                .gap_4()
+                .px_2()
+                .items_center()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .children(autosave)
+                .children(mirror_backup)
+                .children(sync)
+                .children(language)
+                .children(notifications)
+                .into_any_element(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn stb_test1_indicator_follows_autosave_events() {
+        let now = Local::now();
+        let path = PathBuf::from("C:/tmp/note.txt");
+        let mut indicator = AutoSaveIndicator::default();
+        assert_eq!(indicator.label(), None);
+
+        indicator.apply(&EditorAutoSaveEvent::Saving { path: path.clone() }, now);
+        assert_eq!(indicator.label().as_deref(), Some("Saving\u{2026}"));
+
+        indicator.apply(
+            &EditorAutoSaveEvent::Failed {
+                path: path.clone(),
+                error: "Permission denied (os error 13)".to_string(),
+                remediation: None,
+            },
+            now,
+        );
+        assert!(indicator.is_failed());
+        assert_eq!(
+            indicator.label().as_deref(),
+            Some("Save failed (note.txt): Permission denied (os error 13)")
+        );
+
+        indicator.apply(&EditorAutoSaveEvent::Saved { path }, now);
+        assert_eq!(
+            indicator.label(),
+            Some(format!("Saved {}", now.format("%H:%M:%S")))
+        );
+    }
+}
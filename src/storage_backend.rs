@@ -0,0 +1,905 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fs, io,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Condvar, Mutex, OnceLock},
+    thread,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::log::trace_debug;
+use crate::workflow_error::retry_busy;
+
+static ACTIVE_STORAGE_BACKEND: OnceLock<Mutex<Arc<dyn StorageBackend>>> = OnceLock::new();
+static REMOTE_SYNC_EVENTS: OnceLock<(
+    smol::channel::Sender<RemoteSyncEvent>,
+    smol::channel::Receiver<RemoteSyncEvent>,
+)> = OnceLock::new();
+
+const WEBDAV_QUEUE_FILE_NAME: &str = "webdav_queue.json";
+const WEBDAV_CORRUPT_QUEUE_FILE_NAME: &str = "webdav_queue.corrupt.json";
+
+const WEBDAV_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const WEBDAV_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const WEBDAV_RETRY_MIN: Duration = Duration::from_secs(5);
+const WEBDAV_RETRY_MAX: Duration = Duration::from_secs(300);
+
+/// Every change the app makes inside a vault. Each backend keeps the vault folder on disk as
+/// the working copy the tree, watcher and editor read, and a call returns once that copy is
+/// changed; a remote backend then carries the change to its server in the background.
+pub(crate) trait StorageBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// The vault this backend serves; other roots always go through the local backend.
+    fn root(&self) -> &Path;
+
+    /// Creates an empty note; `AlreadyExists` when the name is taken so the caller can
+    /// try the next `_N` suffix.
+    fn create_new(&self, path: &Path) -> io::Result<()>;
+
+    /// Fails with `AlreadyExists` when the folder is there.
+    fn create_dir(&self, path: &Path) -> io::Result<()>;
+
+    /// Fails with `AlreadyExists` rather than replacing `to`. A `to` outside the vault (the
+    /// recycle bin) takes `from` out of the vault.
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+
+    fn write(&self, path: &Path, bytes: &[u8]) -> io::Result<()>;
+
+    /// A new file the caller already placed in the working copy itself (a copy, a hard link,
+    /// an imported note or attachment), to be carried like one `create_new` made.
+    fn added(&self, path: &Path);
+}
+
+#[derive(Debug)]
+pub(crate) struct LocalBackend {
+    root: PathBuf,
+}
+
+impl LocalBackend {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+}
+
+impl StorageBackend for LocalBackend {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn root(&self) -> &Path {
+        self.root.as_path()
+    }
+
+    fn create_new(&self, path: &Path) -> io::Result<()> {
+        retry_busy(path, || {
+            fs::OpenOptions::new()
+                .create_new(true)
+                .write(true)
+                .open(path)
+                .map(|_| ())
+        })
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        retry_busy(path, || fs::create_dir(path))
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        retry_busy(from, || {
+            crate::file_times::rename_preserving_times(from, to)
+        })
+    }
+
+    fn write(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        retry_busy(path, || {
+            crate::file_times::write_atomic_preserving_created(path, bytes)
+        })
+    }
+
+    fn added(&self, _path: &Path) {}
+}
+
+/// `[vaults.webdav]` in `conf/vaults.toml`. The password is read from the environment
+/// variable named here so it never lands in the config folder.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct WebDavRemote {
+    pub url: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password_env: Option<String>,
+}
+
+impl WebDavRemote {
+    // Basic auth is the password in clear text, so it only ever goes over https.
+    fn check_transport(&self, has_password: bool) -> io::Result<()> {
+        let has_credentials = !self.username.is_empty() || has_password;
+        if has_credentials && !self.url.to_ascii_lowercase().starts_with("https://") {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "WebDAV credentials are only sent over https, not to {}",
+                    self.url
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn is_url_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+fn percent_encode_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if is_url_unreserved(byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// The remote URL for `path` inside `root`: each folder and the file name become one
+/// percent-encoded segment under `base_url`.
+pub(crate) fn webdav_url(base_url: &str, root: &Path, path: &Path) -> io::Result<String> {
+    let relative = path.strip_prefix(root).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is outside the remote vault", path.display()),
+        )
+    })?;
+    let mut url = base_url.trim_end_matches('/').to_string();
+    for component in relative.components() {
+        let Component::Normal(segment) = component else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a plain vault path", path.display()),
+            ));
+        };
+        url.push('/');
+        url.push_str(&percent_encode_segment(&segment.to_string_lossy()));
+    }
+    Ok(url)
+}
+
+fn webdav_status_error(method: &str, url: &str, status: u16) -> io::Error {
+    let kind = match status {
+        401 | 403 => io::ErrorKind::PermissionDenied,
+        404 | 409 => io::ErrorKind::NotFound,
+        408 | 429 | 502 | 503 | 504 => io::ErrorKind::TimedOut,
+        412 => io::ErrorKind::AlreadyExists,
+        507 => io::ErrorKind::StorageFull,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, format!("WebDAV {method} {url} returned {status}"))
+}
+
+// The server is unreachable or busy: the change waits in the queue and is sent again later.
+// Anything else is a refusal that no retry will change.
+fn is_transient_remote_error(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::NotConnected | io::ErrorKind::TimedOut
+    )
+}
+
+/// One change still to be sent. A `Put` uploads whatever the working copy holds when it is
+/// sent, so a queued upload always carries the latest save.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum RemoteChange {
+    Put { path: PathBuf, only_if_new: bool },
+    Mkcol(PathBuf),
+    Move { from: PathBuf, to: PathBuf },
+    Delete(PathBuf),
+}
+
+impl RemoteChange {
+    fn path(&self) -> &Path {
+        match self {
+            Self::Put { path, .. } | Self::Mkcol(path) | Self::Delete(path) => path,
+            Self::Move { from, .. } => from,
+        }
+    }
+}
+
+/// What a remote vault's backend reports to the app after each change to its queue.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct RemoteSyncEvent {
+    pub root: PathBuf,
+    /// Changes still waiting to be sent.
+    pub pending: usize,
+    pub outcome: RemoteSyncOutcome,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum RemoteSyncOutcome {
+    /// A change joined the queue, or a previous run's queue was picked up.
+    Queued,
+    Sent(PathBuf),
+    /// The server is unreachable or busy; the queue is retried later.
+    Deferred(String),
+    /// The server refused the change for `path`; the working copy has it, the remote does not.
+    Dropped {
+        path: PathBuf,
+        error: String,
+    },
+}
+
+fn report_remote_sync(root: &Path, pending: usize, outcome: RemoteSyncOutcome) {
+    let _ = remote_sync_event_channel().0.try_send(RemoteSyncEvent {
+        root: root.to_path_buf(),
+        pending,
+        outcome,
+    });
+}
+
+/// The app's end of the sync thread's reports, for every remote vault this run.
+pub(crate) fn remote_sync_events() -> smol::channel::Receiver<RemoteSyncEvent> {
+    remote_sync_event_channel().1.clone()
+}
+
+fn remote_sync_event_channel() -> &'static (
+    smol::channel::Sender<RemoteSyncEvent>,
+    smol::channel::Receiver<RemoteSyncEvent>,
+) {
+    REMOTE_SYNC_EVENTS.get_or_init(smol::channel::unbounded)
+}
+
+/// The unsent changes, mirrored to `journal_path` after every change to the queue so what
+/// was not sent before the app quit or switched vaults goes out on the vault's next start.
+#[derive(Default)]
+struct RemoteQueue {
+    pending: VecDeque<RemoteChange>,
+    closing: bool,
+    journal_path: Option<PathBuf>,
+}
+
+impl RemoteQueue {
+    /// Picks up the changes a previous run left unsent. A queue file that cannot be read is
+    /// moved aside rather than overwritten.
+    fn load(journal_path: PathBuf) -> Self {
+        let pending = match fs::read(&journal_path) {
+            Ok(bytes) => match serde_json::from_slice::<VecDeque<RemoteChange>>(&bytes) {
+                Ok(pending) => pending,
+                Err(error) => {
+                    let corrupt = journal_path.with_file_name(WEBDAV_CORRUPT_QUEUE_FILE_NAME);
+                    trace_debug(format!(
+                        "req-dav queue unreadable path={} moved_to={} error={error}",
+                        journal_path.display(),
+                        corrupt.display()
+                    ));
+                    if let Err(error) = fs::rename(&journal_path, &corrupt) {
+                        trace_debug(format!("req-dav queue move aside failed error={error}"));
+                    }
+                    VecDeque::new()
+                }
+            },
+            Err(error) => {
+                if error.kind() != io::ErrorKind::NotFound {
+                    trace_debug(format!(
+                        "req-dav queue read failed path={} error={error}",
+                        journal_path.display()
+                    ));
+                }
+                VecDeque::new()
+            }
+        };
+        if !pending.is_empty() {
+            trace_debug(format!(
+                "req-dav queue resumed pending={} path={}",
+                pending.len(),
+                journal_path.display()
+            ));
+        }
+        Self {
+            pending,
+            closing: false,
+            journal_path: Some(journal_path),
+        }
+    }
+
+    fn push(&mut self, change: RemoteChange) {
+        // Back-to-back saves of one note need one upload.
+        if self.pending.back() == Some(&change) {
+            return;
+        }
+        self.pending.push_back(change);
+        self.persist();
+    }
+
+    fn pop_front(&mut self) {
+        self.pending.pop_front();
+        self.persist();
+    }
+
+    fn persist(&self) {
+        let Some(journal_path) = self.journal_path.as_deref() else {
+            return;
+        };
+        let result = if self.pending.is_empty() {
+            match fs::remove_file(journal_path) {
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+                result => result,
+            }
+        } else {
+            serde_json::to_vec(&self.pending)
+                .map_err(io::Error::other)
+                .and_then(|bytes| crate::atomic_io::write_atomic(journal_path, &bytes))
+        };
+        if let Err(error) = result {
+            trace_debug(format!(
+                "req-dav queue save failed path={} error={error}",
+                journal_path.display()
+            ));
+        }
+    }
+}
+
+struct WebDavClient {
+    root: PathBuf,
+    remote: WebDavRemote,
+    password: Option<String>,
+    client: reqwest::Client,
+    runtime: tokio::runtime::Runtime,
+    known_collections: HashSet<String>,
+}
+
+impl WebDavClient {
+    fn url(&self, path: &Path) -> io::Result<String> {
+        webdav_url(&self.remote.url, self.root.as_path(), path)
+    }
+
+    fn send(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &[(&str, String)],
+        body: Option<Vec<u8>>,
+    ) -> io::Result<u16> {
+        let http_method = reqwest::Method::from_bytes(method.as_bytes())
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error.to_string()))?;
+        let mut request = self.client.request(http_method, url);
+        if !self.remote.username.is_empty() {
+            request = request.basic_auth(&self.remote.username, self.password.as_deref());
+        }
+        for (name, value) in headers {
+            request = request.header(*name, value.as_str());
+        }
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+        let status = self
+            .runtime
+            .block_on(async move { request.send().await.map(|response| response.status()) })
+            .map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::NotConnected,
+                    format!("WebDAV {method} {url} failed: {error}"),
+                )
+            })?;
+        trace_debug(format!(
+            "req-dav {method} url={url} status={}",
+            status.as_u16()
+        ));
+        Ok(status.as_u16())
+    }
+
+    /// MKCOL for every folder between the vault root and `path`; 405 means it is there.
+    fn ensure_collections(&mut self, path: &Path) -> io::Result<()> {
+        let Some(parent) = path.parent() else {
+            return Ok(());
+        };
+        let relative = parent
+            .strip_prefix(self.root.as_path())
+            .unwrap_or(Path::new(""))
+            .to_path_buf();
+        let mut folder = self.root.clone();
+        for component in relative.components() {
+            folder.push(component);
+            self.mkcol(folder.as_path())?;
+        }
+        Ok(())
+    }
+
+    fn mkcol(&mut self, folder: &Path) -> io::Result<()> {
+        let url = self.url(folder)?;
+        if self.known_collections.contains(&url) {
+            return Ok(());
+        }
+        match self.send("MKCOL", &url, &[], None)? {
+            200..=299 | 405 => {
+                self.known_collections.insert(url);
+                Ok(())
+            }
+            status => Err(webdav_status_error("MKCOL", &url, status)),
+        }
+    }
+
+    fn put(&mut self, path: &Path, only_if_new: bool) -> io::Result<()> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            // Renamed or trashed since; the change queued after this one carries it.
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(error) => return Err(error),
+        };
+        self.ensure_collections(path)?;
+        let url = self.url(path)?;
+        let headers = if only_if_new {
+            vec![("If-None-Match", "*".to_string())]
+        } else {
+            Vec::new()
+        };
+        match self.send("PUT", &url, &headers, Some(bytes))? {
+            200..=299 => Ok(()),
+            status => Err(webdav_status_error("PUT", &url, status)),
+        }
+    }
+
+    fn move_remote(&mut self, from: &Path, to: &Path) -> io::Result<()> {
+        self.ensure_collections(to)?;
+        let from_url = self.url(from)?;
+        let to_url = self.url(to)?;
+        match self.send(
+            "MOVE",
+            &from_url,
+            &[("Destination", to_url), ("Overwrite", "F".to_string())],
+            None,
+        )? {
+            200..=299 => Ok(()),
+            // Never uploaded (created while the vault had no remote): send it under the
+            // new name instead.
+            404 if to.is_file() => self.put(to, true),
+            status => Err(webdav_status_error("MOVE", &from_url, status)),
+        }
+    }
+
+    fn delete(&mut self, path: &Path) -> io::Result<()> {
+        let url = self.url(path)?;
+        match self.send("DELETE", &url, &[], None)? {
+            200..=299 | 404 => {
+                self.known_collections
+                    .retain(|known| known != &url && !known.starts_with(&format!("{url}/")));
+                Ok(())
+            }
+            status => Err(webdav_status_error("DELETE", &url, status)),
+        }
+    }
+
+    fn send_change(&mut self, change: &RemoteChange) -> io::Result<()> {
+        match change {
+            RemoteChange::Put { path, only_if_new } => self.put(path, *only_if_new),
+            RemoteChange::Mkcol(path) => {
+                self.ensure_collections(path)?;
+                self.mkcol(path)
+            }
+            RemoteChange::Move { from, to } => self.move_remote(from, to),
+            RemoteChange::Delete(path) => self.delete(path),
+        }
+    }
+}
+
+// Sends queued changes in order. A change the server cannot take right now stays at the
+// head of the queue and is retried with a growing pause; one it refuses is dropped and
+// reported to the app, since no retry will change the answer.
+fn run_remote_sync(mut client: WebDavClient, queue: Arc<(Mutex<RemoteQueue>, Condvar)>) {
+    let (lock, wake) = &*queue;
+    let mut retry = WEBDAV_RETRY_MIN;
+    loop {
+        let change = {
+            let mut state = lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            while state.pending.is_empty() && !state.closing {
+                state = wake
+                    .wait(state)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+            }
+            if state.closing {
+                trace_debug(format!(
+                    "req-dav sync stopped pending={} kept for the next start",
+                    state.pending.len()
+                ));
+                return;
+            }
+            match state.pending.front() {
+                Some(change) => change.clone(),
+                None => return,
+            }
+        };
+        let outcome = match client.send_change(&change) {
+            Ok(()) => {
+                retry = WEBDAV_RETRY_MIN;
+                RemoteSyncOutcome::Sent(change.path().to_path_buf())
+            }
+            Err(error) if is_transient_remote_error(&error) => {
+                let mut state = lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                if state.closing {
+                    continue;
+                }
+                trace_debug(format!(
+                    "req-dav sync deferred pending={} retry_in={}s error={error}",
+                    state.pending.len(),
+                    retry.as_secs()
+                ));
+                report_remote_sync(
+                    client.root.as_path(),
+                    state.pending.len(),
+                    RemoteSyncOutcome::Deferred(error.to_string()),
+                );
+                let _ = wake
+                    .wait_timeout(state, retry)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                retry = (retry * 2).min(WEBDAV_RETRY_MAX);
+                continue;
+            }
+            Err(error) => {
+                trace_debug(format!(
+                    "req-dav sync dropped change={change:?} error={error}"
+                ));
+                RemoteSyncOutcome::Dropped {
+                    path: change.path().to_path_buf(),
+                    error: error.to_string(),
+                }
+            }
+        };
+        let pending = {
+            let mut state = lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.pop_front();
+            state.pending.len()
+        };
+        report_remote_sync(client.root.as_path(), pending, outcome);
+    }
+}
+
+/// Local-first: every change lands in the working copy and returns; a background thread
+/// then sends it to the server with PUT / MKCOL / MOVE / DELETE. While the server is
+/// unreachable the changes queue up, in memory and in `queue_path`, so no edit waits on the
+/// network and an offline edit is still sent after a restart.
+pub(crate) struct WebDavBackend {
+    local: LocalBackend,
+    queue: Arc<(Mutex<RemoteQueue>, Condvar)>,
+}
+
+impl WebDavBackend {
+    pub fn new(root: PathBuf, remote: WebDavRemote, queue_path: PathBuf) -> io::Result<Self> {
+        let password = match remote.password_env.as_deref() {
+            Some(variable) => Some(std::env::var(variable).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("the WebDAV password variable {variable} is not set"),
+                )
+            })?),
+            None => None,
+        };
+        remote.check_transport(password.is_some())?;
+        let client = reqwest::Client::builder()
+            .connect_timeout(WEBDAV_CONNECT_TIMEOUT)
+            .timeout(WEBDAV_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|error| io::Error::other(error.to_string()))?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let client = WebDavClient {
+            root: root.clone(),
+            remote,
+            password,
+            client,
+            runtime,
+            known_collections: HashSet::new(),
+        };
+        let resumed = RemoteQueue::load(queue_path);
+        report_remote_sync(
+            root.as_path(),
+            resumed.pending.len(),
+            RemoteSyncOutcome::Queued,
+        );
+        let queue = Arc::new((Mutex::new(resumed), Condvar::new()));
+        let sync_queue = Arc::clone(&queue);
+        thread::Builder::new()
+            .name("papyru2-webdav-sync".to_string())
+            .spawn(move || run_remote_sync(client, sync_queue))?;
+        Ok(Self {
+            local: LocalBackend::new(root),
+            queue,
+        })
+    }
+
+    fn queue(&self, change: RemoteChange) {
+        let (lock, wake) = &*self.queue;
+        let pending = {
+            let mut state = lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.push(change);
+            state.pending.len()
+        };
+        wake.notify_one();
+        report_remote_sync(self.local.root(), pending, RemoteSyncOutcome::Queued);
+    }
+
+    fn in_vault(&self, path: &Path) -> bool {
+        path.starts_with(self.local.root())
+    }
+}
+
+impl Drop for WebDavBackend {
+    // The sync thread exits after the change it is sending; nothing waits for it. The queue
+    // file keeps the rest for the next backend on this vault, which owns the file from now
+    // on, so the old thread stops writing it.
+    fn drop(&mut self) {
+        let (lock, wake) = &*self.queue;
+        let mut state = lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.closing = true;
+        state.journal_path = None;
+        wake.notify_one();
+    }
+}
+
+impl StorageBackend for WebDavBackend {
+    fn name(&self) -> &'static str {
+        "webdav"
+    }
+
+    fn root(&self) -> &Path {
+        self.local.root()
+    }
+
+    fn create_new(&self, path: &Path) -> io::Result<()> {
+        self.local.create_new(path)?;
+        self.queue(RemoteChange::Put {
+            path: path.to_path_buf(),
+            only_if_new: true,
+        });
+        Ok(())
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        self.local.create_dir(path)?;
+        self.queue(RemoteChange::Mkcol(path.to_path_buf()));
+        Ok(())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.local.rename(from, to)?;
+        match (self.in_vault(from), self.in_vault(to)) {
+            (true, true) => self.queue(RemoteChange::Move {
+                from: from.to_path_buf(),
+                to: to.to_path_buf(),
+            }),
+            (true, false) => self.queue(RemoteChange::Delete(from.to_path_buf())),
+            (false, true) if to.is_file() => self.queue(RemoteChange::Put {
+                path: to.to_path_buf(),
+                only_if_new: true,
+            }),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn write(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        self.local.write(path, bytes)?;
+        self.queue(RemoteChange::Put {
+            path: path.to_path_buf(),
+            only_if_new: false,
+        });
+        Ok(())
+    }
+
+    fn added(&self, path: &Path) {
+        if self.in_vault(path) {
+            self.queue(RemoteChange::Put {
+                path: path.to_path_buf(),
+                only_if_new: true,
+            });
+        }
+    }
+}
+
+fn active_storage_backend_lock() -> &'static Mutex<Arc<dyn StorageBackend>> {
+    ACTIVE_STORAGE_BACKEND.get_or_init(|| Mutex::new(Arc::new(LocalBackend::new(PathBuf::new()))))
+}
+
+/// Picks the backend for the vault at `root` from its `webdav` settings. A remote that
+/// cannot be set up leaves the vault on the local backend and returns the reason. Unsent
+/// remote changes are kept in `vault_data_dir`.
+pub(crate) fn activate_storage_backend(
+    root: &Path,
+    remote: Option<&WebDavRemote>,
+    vault_data_dir: &Path,
+) -> io::Result<&'static str> {
+    let local = || Arc::new(LocalBackend::new(root.to_path_buf())) as Arc<dyn StorageBackend>;
+    let (backend, result) = match remote {
+        None => (local(), Ok("local")),
+        Some(remote) => match WebDavBackend::new(
+            root.to_path_buf(),
+            remote.clone(),
+            vault_data_dir.join(WEBDAV_QUEUE_FILE_NAME),
+        ) {
+            Ok(backend) => (Arc::new(backend) as Arc<dyn StorageBackend>, Ok("webdav")),
+            Err(error) => (local(), Err(error)),
+        },
+    };
+    trace_debug(format!(
+        "req-dav backend root={} backend={}",
+        root.display(),
+        backend.name()
+    ));
+    *active_storage_backend_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = backend;
+    result
+}
+
+/// The backend the workflow should write `user_document_dir` through. A request still
+/// carrying the previous vault's root after a switch gets the local backend.
+pub(crate) fn storage_backend_for(user_document_dir: &Path) -> Arc<dyn StorageBackend> {
+    let active = active_storage_backend_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    if active.root() == user_document_dir {
+        active
+    } else {
+        Arc::new(LocalBackend::new(user_document_dir.to_path_buf()))
+    }
+}
+
+/// The backend for a change to `path` when the caller does not carry the vault root: the
+/// active vault's backend for anything inside it, the local backend otherwise.
+pub(crate) fn storage_backend_containing(path: &Path) -> Arc<dyn StorageBackend> {
+    let active = active_storage_backend_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    if !active.root().as_os_str().is_empty() && path.starts_with(active.root()) {
+        active
+    } else {
+        Arc::new(LocalBackend::new(PathBuf::new()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dav_test1_vault_paths_map_to_encoded_remote_urls() {
+        let root = Path::new("/vault");
+        assert_eq!(
+            webdav_url(
+                "https://dav.example.com/notes/",
+                root,
+                &root
+                    .join("2026")
+                    .join("03")
+                    .join("01")
+                    .join("my plan #2.md")
+            )
+            .expect("url"),
+            "https://dav.example.com/notes/2026/03/01/my%20plan%20%232.md"
+        );
+        assert_eq!(
+            webdav_url("https://dav.example.com/notes", root, root).expect("root url"),
+            "https://dav.example.com/notes"
+        );
+        assert_eq!(
+            webdav_url(
+                "https://dav.example.com",
+                root,
+                Path::new("/elsewhere/a.txt")
+            )
+            .map_err(|error| error.kind()),
+            Err(io::ErrorKind::InvalidInput)
+        );
+        assert_eq!(
+            webdav_status_error("PUT", "u", 412).kind(),
+            io::ErrorKind::AlreadyExists
+        );
+    }
+
+    #[test]
+    fn dav_test2_credentials_need_https_and_repeated_saves_queue_once() {
+        let remote = |url: &str, username: &str| WebDavRemote {
+            url: url.to_string(),
+            username: username.to_string(),
+            password_env: None,
+        };
+        assert!(
+            remote("https://dav.example.com", "me")
+                .check_transport(true)
+                .is_ok()
+        );
+        assert!(
+            remote("http://dav.example.com", "")
+                .check_transport(false)
+                .is_ok()
+        );
+        assert_eq!(
+            remote("http://dav.example.com", "me")
+                .check_transport(false)
+                .map_err(|error| error.kind()),
+            Err(io::ErrorKind::PermissionDenied)
+        );
+        assert!(
+            remote("HTTP://dav.example.com", "")
+                .check_transport(true)
+                .is_err()
+        );
+
+        let put = |path: &str| RemoteChange::Put {
+            path: PathBuf::from(path),
+            only_if_new: false,
+        };
+        let mut queue = RemoteQueue::default();
+        queue.push(put("/vault/a.txt"));
+        queue.push(put("/vault/a.txt"));
+        queue.push(RemoteChange::Move {
+            from: PathBuf::from("/vault/a.txt"),
+            to: PathBuf::from("/vault/b.txt"),
+        });
+        queue.push(put("/vault/b.txt"));
+        queue.push(put("/vault/b.txt"));
+        assert_eq!(queue.pending.len(), 3);
+
+        assert!(is_transient_remote_error(&webdav_status_error(
+            "PUT", "u", 503
+        )));
+        assert!(!is_transient_remote_error(&webdav_status_error(
+            "PUT", "u", 403
+        )));
+    }
+
+    #[test]
+    fn dav_test3_unsent_changes_survive_a_restart_and_a_corrupt_queue_is_moved_aside() {
+        let data_dir = std::env::temp_dir().join(format!(
+            "papyru2_dav_queue_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0)
+        ));
+        fs::create_dir_all(&data_dir).expect("create data dir");
+        let journal_path = data_dir.join(WEBDAV_QUEUE_FILE_NAME);
+        let put = RemoteChange::Put {
+            path: PathBuf::from("/vault/a.txt"),
+            only_if_new: false,
+        };
+        let delete = RemoteChange::Delete(PathBuf::from("/vault/old.txt"));
+
+        let mut queue = RemoteQueue::load(journal_path.clone());
+        assert!(queue.pending.is_empty());
+        queue.push(put.clone());
+        queue.push(delete.clone());
+        drop(queue);
+
+        let mut resumed = RemoteQueue::load(journal_path.clone());
+        assert_eq!(
+            resumed.pending.iter().cloned().collect::<Vec<_>>(),
+            vec![put, delete.clone()]
+        );
+        resumed.pop_front();
+        assert_eq!(
+            RemoteQueue::load(journal_path.clone())
+                .pending
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![delete]
+        );
+        resumed.pop_front();
+        assert!(!journal_path.exists());
+
+        fs::write(&journal_path, b"{not json").expect("write corrupt queue");
+        assert!(RemoteQueue::load(journal_path.clone()).pending.is_empty());
+        assert!(!journal_path.exists());
+        assert_eq!(
+            fs::read(data_dir.join(WEBDAV_CORRUPT_QUEUE_FILE_NAME)).expect("corrupt queue kept"),
+            b"{not json"
+        );
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+}
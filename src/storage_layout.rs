@@ -0,0 +1,524 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+    thread,
+};
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, format::StrftimeItems};
+use gpui::*;
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+
+pub(crate) const STORAGE_LAYOUT_STATE_FILE_NAME: &str = "storage_layout.txt";
+
+static ACTIVE_STORAGE_LAYOUT: OnceLock<Mutex<StorageLayout>> = OnceLock::new();
+
+/// Where new notes go inside the vault: a folder per day (the default), per month, the vault
+/// root itself, or a custom strftime pattern such as `"%Y/week-%V"`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) enum StorageLayout {
+    #[default]
+    Daily,
+    Monthly,
+    Flat,
+    Pattern(String),
+}
+
+impl StorageLayout {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        match value {
+            "daily" => return Ok(Self::Daily),
+            "monthly" => return Ok(Self::Monthly),
+            "flat" => return Ok(Self::Flat),
+            _ => {}
+        }
+        if value.is_empty() {
+            return Err("storage layout pattern is empty".to_string());
+        }
+        if StrftimeItems::new(value).any(|item| matches!(item, chrono::format::Item::Error)) {
+            return Err(format!(
+                "storage layout pattern '{value}' is not a valid date format"
+            ));
+        }
+        let rendered = Local::now().format(value).to_string();
+        let rendered = Path::new(rendered.as_str());
+        if rendered.is_absolute()
+            || rendered
+                .components()
+                .any(|component| !matches!(component, std::path::Component::Normal(_)))
+        {
+            return Err(format!(
+                "storage layout pattern '{value}' must stay inside the vault"
+            ));
+        }
+        Ok(Self::Pattern(value.to_string()))
+    }
+
+    pub fn as_config_value(&self) -> &str {
+        match self {
+            Self::Daily => "daily",
+            Self::Monthly => "monthly",
+            Self::Flat => "flat",
+            Self::Pattern(pattern) => pattern.as_str(),
+        }
+    }
+
+    pub fn directory(&self, user_document_dir: &Path, now: DateTime<Local>) -> PathBuf {
+        let relative = match self {
+            Self::Daily => now.format("%Y/%m/%d").to_string(),
+            Self::Monthly => now.format("%Y/%m").to_string(),
+            Self::Flat => return user_document_dir.to_path_buf(),
+            Self::Pattern(pattern) => now.format(pattern).to_string(),
+        };
+        user_document_dir.join(relative)
+    }
+
+    /// The folder notes dated `date` are filed into.
+    pub fn date_directory(&self, user_document_dir: &Path, date: NaiveDate) -> PathBuf {
+        let noon = date
+            .and_hms_opt(12, 0, 0)
+            .and_then(|noon| noon.and_local_timezone(Local).earliest())
+            .unwrap_or_else(Local::now);
+        self.directory(user_document_dir, noon)
+    }
+
+    /// Whether `dir` is a folder this layout files notes into, for some date. Notes anywhere
+    /// else were put there by hand and are not moved to today's folder when edited.
+    pub fn is_layout_directory(&self, user_document_dir: &Path, dir: &Path) -> bool {
+        let Ok(relative) = dir.strip_prefix(user_document_dir) else {
+            return false;
+        };
+        let sample = self.directory(Path::new(""), Local::now());
+        digit_shape(relative) == digit_shape(sample.as_path())
+    }
+}
+
+// Date folders differ from each other only in their digits.
+fn digit_shape(relative: &Path) -> Vec<String> {
+    relative
+        .components()
+        .map(|component| {
+            component
+                .as_os_str()
+                .to_string_lossy()
+                .chars()
+                .map(|ch| if ch.is_ascii_digit() { '0' } else { ch })
+                .collect()
+        })
+        .collect()
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct StorageLayoutConfigFile {
+    #[serde(default)]
+    storage: StorageLayoutConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct StorageLayoutConfigSection {
+    layout: Option<String>,
+}
+
+/// `[storage] layout = "daily" | "monthly" | "flat" | "<strftime pattern>"`.
+pub(crate) fn load_storage_layout(path: &Path) -> Option<StorageLayout> {
+    let raw = fs::read_to_string(path).ok()?;
+    let layout = match toml::from_str::<StorageLayoutConfigFile>(&raw) {
+        Ok(parsed) => parsed.storage.layout?,
+        Err(error) => {
+            trace_debug(format!(
+                "req-lay1 config parse failed path={} error={error}",
+                path.display()
+            ));
+            return None;
+        }
+    };
+    match StorageLayout::parse(&layout) {
+        Ok(layout) => Some(layout),
+        Err(message) => {
+            trace_debug(format!("req-lay1 config ignored: {message}"));
+            None
+        }
+    }
+}
+
+fn active_storage_layout_lock() -> &'static Mutex<StorageLayout> {
+    ACTIVE_STORAGE_LAYOUT.get_or_init(|| Mutex::new(StorageLayout::default()))
+}
+
+/// The create, rename and autosave paths run on worker threads; they all read the layout
+/// from here.
+pub(crate) fn set_active_storage_layout(layout: StorageLayout) {
+    *active_storage_layout_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = layout;
+}
+
+pub(crate) fn active_storage_layout() -> StorageLayout {
+    active_storage_layout_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// The date a note belongs to: as much as its `YYYY[/MM[/DD]]` folders say, with the
+/// modification time filling in the rest when it agrees.
+pub(crate) fn note_date(path: &Path, user_document_dir: &Path) -> Option<NaiveDate> {
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .map(|modified| DateTime::<Local>::from(modified).date_naive());
+    let folders = path
+        .parent()
+        .and_then(|parent| parent.strip_prefix(user_document_dir).ok())
+        .map(|relative| {
+            relative
+                .components()
+                .filter_map(|component| component.as_os_str().to_str())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let number = |index: usize, len: usize| {
+        folders
+            .get(index)
+            .filter(|part| part.len() == len)
+            .and_then(|part| part.parse::<u32>().ok())
+    };
+    let Some(year) = number(0, 4) else {
+        return modified;
+    };
+    let year = year as i32;
+    match (number(1, 2), number(2, 2)) {
+        (Some(month), Some(day)) => NaiveDate::from_ymd_opt(year, month, day),
+        (Some(month), None) => modified
+            .filter(|date| date.year() == year && date.month() == month)
+            .or_else(|| NaiveDate::from_ymd_opt(year, month, 1)),
+        _ => modified
+            .filter(|date| date.year() == year)
+            .or_else(|| NaiveDate::from_ymd_opt(year, 1, 1)),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct StorageMove {
+    pub from: PathBuf,
+    pub to_dir: PathBuf,
+}
+
+/// Every note under `user_document_dir` that is not where `layout` would put it today.
+pub(crate) fn plan_storage_migration(
+    user_document_dir: &Path,
+    layout: &StorageLayout,
+    exclude: &[PathBuf],
+) -> io::Result<Vec<StorageMove>> {
+    let mut notes = Vec::new();
+    crate::tag_index::collect_note_paths(user_document_dir, &mut notes)?;
+    notes.sort();
+    let moves = notes
+        .into_iter()
+        .filter(|path| !exclude.iter().any(|excluded| path.starts_with(excluded)))
+        .filter_map(|path| {
+            let date = note_date(path.as_path(), user_document_dir)?;
+            let day = date
+                .and_hms_opt(12, 0, 0)?
+                .and_local_timezone(Local)
+                .single()?;
+            let to_dir = layout.directory(user_document_dir, day);
+            (path.parent() != Some(to_dir.as_path())).then_some(StorageMove { from: path, to_dir })
+        })
+        .collect();
+    Ok(moves)
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct StorageMigrationReport {
+    pub moved: Vec<(PathBuf, PathBuf)>,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl StorageMigrationReport {
+    pub fn summary(&self) -> String {
+        let moved = self.moved.len().to_string();
+        if self.failed.is_empty() {
+            tr_with("notice.layout_moved", &[("count", moved.as_str())])
+        } else {
+            tr_with(
+                "notice.layout_moved_with_failures",
+                &[
+                    ("count", moved.as_str()),
+                    ("failed", self.failed.len().to_string().as_str()),
+                ],
+            )
+        }
+    }
+}
+
+/// Moves the planned notes, renaming `name_2.txt` style on collisions, then removes source
+/// folders the move left empty.
+pub(crate) fn apply_storage_migration(
+    user_document_dir: &Path,
+    moves: &[StorageMove],
+) -> StorageMigrationReport {
+    let mut report = StorageMigrationReport::default();
+    for planned in moves {
+        match move_note_into(planned.from.as_path(), planned.to_dir.as_path()) {
+            Ok(target) => report.moved.push((planned.from.clone(), target)),
+            Err(error) => report
+                .failed
+                .push((planned.from.clone(), error.to_string())),
+        }
+    }
+    for (from, _) in &report.moved {
+        let mut dir = from.parent();
+        while let Some(current) = dir {
+            if current == user_document_dir
+                || !current.starts_with(user_document_dir)
+                || fs::remove_dir(current).is_err()
+            {
+                break;
+            }
+            dir = current.parent();
+        }
+    }
+    report
+}
+
+fn move_note_into(from: &Path, to_dir: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(to_dir)?;
+    let file_name = from
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "note path has no file name"))?;
+    let mut suffix = 1usize;
+    loop {
+        let target =
+            crate::file_update_handler::relocated_daily_candidate_path(to_dir, &file_name, suffix);
+        match crate::storage_backend::storage_backend_containing(from).rename(from, &target) {
+            Ok(()) => return Ok(target),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => suffix += 1,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+fn record_storage_layout(state_path: &Path, layout: &StorageLayout) {
+    if let Err(error) =
+        crate::atomic_io::write_atomic(state_path, layout.as_config_value().as_bytes())
+    {
+        trace_debug(format!(
+            "req-lay1 layout state write failed path={} error={error}",
+            state_path.display()
+        ));
+    }
+}
+
+impl crate::app::Papyru2App {
+    /// When the configured layout differs from the one the vault was last organised for,
+    /// offer to move existing notes. Either answer records the new layout.
+    pub(crate) fn check_storage_layout_migration(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let layout = active_storage_layout();
+        let state_path = self.app_paths.data_dir.join(STORAGE_LAYOUT_STATE_FILE_NAME);
+        let previous = fs::read_to_string(&state_path)
+            .ok()
+            .and_then(|raw| StorageLayout::parse(&raw).ok())
+            .unwrap_or_default();
+        if previous == layout {
+            return;
+        }
+        let user_document_dir = self.app_paths.user_document_dir.clone();
+        let exclude = vec![self.app_paths.recyclebin_dir.clone()];
+        let (plan_tx, plan_rx) = smol::channel::bounded::<io::Result<Vec<StorageMove>>>(1);
+        {
+            let layout = layout.clone();
+            let user_document_dir = user_document_dir.clone();
+            thread::spawn(move || {
+                let _ = plan_tx.try_send(plan_storage_migration(
+                    user_document_dir.as_path(),
+                    &layout,
+                    &exclude,
+                ));
+            });
+        }
+
+        cx.spawn_in(window, async move |this, cx| {
+            let moves = match plan_rx.recv().await {
+                Ok(Ok(moves)) => moves,
+                Ok(Err(error)) => {
+                    trace_debug(format!("req-lay1 migration plan failed error={error}"));
+                    return;
+                }
+                Err(_) => return,
+            };
+            if moves.is_empty() {
+                record_storage_layout(state_path.as_path(), &layout);
+                return;
+            }
+            let Ok(answer) = this.update_in(cx, |_, window, cx| {
+                let buttons = [tr("button.move_notes"), tr("button.leave_notes")];
+                window.prompt(
+                    PromptLevel::Info,
+                    &tr_with(
+                        "prompt.layout_changed",
+                        &[
+                            ("from", previous.as_config_value()),
+                            ("to", layout.as_config_value()),
+                        ],
+                    ),
+                    Some(&tr_with(
+                        "prompt.layout_changed_detail",
+                        &[("count", moves.len().to_string().as_str())],
+                    )),
+                    &[buttons[0].as_ref(), buttons[1].as_ref()],
+                    cx,
+                )
+            }) else {
+                return;
+            };
+            let move_notes = matches!(answer.await, Ok(0));
+            record_storage_layout(state_path.as_path(), &layout);
+            if !move_notes {
+                return;
+            }
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.run_storage_migration(moves, window, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn run_storage_migration(
+        &mut self,
+        moves: Vec<StorageMove>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.flush_editor_content_before_context_switch("req-lay1-migrate", cx) {
+            self.show_transient_notice(tr("notice.layout_migration_needs_save").to_string(), cx);
+            return;
+        }
+        let user_document_dir = self.app_paths.user_document_dir.clone();
+        let (report_tx, report_rx) = smol::channel::bounded::<StorageMigrationReport>(1);
+        thread::spawn(move || {
+            let _ =
+                report_tx.try_send(apply_storage_migration(user_document_dir.as_path(), &moves));
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(report) = report_rx.recv().await else {
+                return;
+            };
+            let _ = this.update_in(cx, move |app, window, cx| {
+                for (path, error) in &report.failed {
+                    trace_debug(format!(
+                        "req-lay1 migration move failed path={} error={error}",
+                        path.display()
+                    ));
+                }
+                let moved = report.moved.iter().cloned().collect::<HashMap<_, _>>();
+                if let Some(target) = app
+                    .file_workflow
+                    .current_edit_path()
+                    .and_then(|current| moved.get(&current).cloned())
+                {
+                    let _ = app.open_file(target, window, cx);
+                }
+                app.apply_file_tree_watcher_refresh(cx);
+                app.show_transient_notice(report.summary(), cx);
+            });
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn lay_test1_layouts_place_notes_and_reject_escaping_patterns() {
+        let root = Path::new("/vault");
+        let now = Local.with_ymd_and_hms(2026, 3, 7, 9, 0, 0).unwrap();
+        let directory = |value: &str| {
+            StorageLayout::parse(value)
+                .expect("layout")
+                .directory(root, now)
+        };
+        assert_eq!(directory("daily"), root.join("2026/03/07"));
+        assert_eq!(directory("monthly"), root.join("2026/03"));
+        assert_eq!(directory("flat"), root.to_path_buf());
+        assert_eq!(directory("%Y/week-%V"), root.join("2026/week-10"));
+        assert!(StorageLayout::parse("../%Y").is_err());
+        assert!(StorageLayout::parse("%Q").is_err());
+        assert!(StorageLayout::parse(" ").is_err());
+    }
+
+    #[test]
+    fn lay_test3_only_date_shaped_folders_belong_to_the_layout() {
+        let root = Path::new("/vault");
+        let daily = StorageLayout::Daily;
+        assert!(daily.is_layout_directory(root, &root.join("1999/01/01")));
+        assert!(!daily.is_layout_directory(root, &root.join("projects")));
+        assert!(!daily.is_layout_directory(root, &root.join("2026/03/07/drafts")));
+        assert!(!daily.is_layout_directory(root, root));
+        assert!(StorageLayout::Flat.is_layout_directory(root, root));
+        let weekly = StorageLayout::parse("%Y/week-%V").expect("layout");
+        assert!(weekly.is_layout_directory(root, &root.join("2024/week-52")));
+        assert!(!weekly.is_layout_directory(root, &root.join("2024/03")));
+        assert!(!daily.is_layout_directory(root, Path::new("/elsewhere/2026/03/07")));
+    }
+
+    #[test]
+    fn lay_test2_daily_to_monthly_migration_moves_and_prunes_folders() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_storage_layout_lay_test2_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        let day_a = root.join("2026").join("03").join("07");
+        let day_b = root.join("2026").join("03").join("09");
+        let trash = root.join("recyclebin");
+        for dir in [&day_a, &day_b, &trash] {
+            fs::create_dir_all(dir).expect("create dir");
+        }
+        fs::write(day_a.join("plan.txt"), "a").expect("write a");
+        fs::write(day_b.join("plan.txt"), "b").expect("write b");
+        fs::write(trash.join("old.txt"), "x").expect("write trash");
+
+        let moves = plan_storage_migration(
+            root.as_path(),
+            &StorageLayout::Monthly,
+            std::slice::from_ref(&trash),
+        )
+        .expect("plan");
+        assert_eq!(moves.len(), 2);
+        let report = apply_storage_migration(root.as_path(), &moves);
+        assert!(report.failed.is_empty());
+
+        let month = root.join("2026").join("03");
+        assert_eq!(fs::read_to_string(month.join("plan.txt")).expect("a"), "a");
+        assert_eq!(
+            fs::read_to_string(month.join("plan_2.txt")).expect("b"),
+            "b"
+        );
+        assert!(!day_a.exists() && !day_b.exists());
+        assert!(trash.join("old.txt").is_file());
+        assert!(
+            plan_storage_migration(root.as_path(), &StorageLayout::Monthly, &[trash])
+                .expect("replan")
+                .is_empty()
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+}
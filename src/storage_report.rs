@@ -0,0 +1,485 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use gpui::*;
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+
+const STORAGE_REPORT_TOP_COUNT: usize = 8;
+const BYTES_PER_MEGABYTE: u64 = 1024 * 1024;
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct StorageConfigFile {
+    #[serde(default)]
+    storage: StorageConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct StorageConfigSection {
+    warn_above_mb: Option<u64>,
+}
+
+/// `[storage] warn_above_mb = 500` turns on the vault size warning; returns the limit in bytes.
+pub(crate) fn load_storage_warning_limit(path: &Path) -> Option<u64> {
+    let raw = fs::read_to_string(path).ok()?;
+    let parsed: StorageConfigFile = match toml::from_str(&raw) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            trace_debug(format!(
+                "req-sto1 config parse failed path={} error={error}",
+                path.display()
+            ));
+            return None;
+        }
+    };
+    parsed
+        .storage
+        .warn_above_mb
+        .filter(|megabytes| *megabytes > 0)
+        .map(|megabytes| megabytes.saturating_mul(BYTES_PER_MEGABYTE))
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct StorageReport {
+    pub total_bytes: u64,
+    pub largest_notes: Vec<(PathBuf, u64)>,
+    pub largest_attachment_dirs: Vec<(PathBuf, u64)>,
+    pub skipped_entries: usize,
+}
+
+/// One full walk of the vault: every readable file's size, plus how many entries could
+/// not be read and were left out.
+#[derive(Debug, Default)]
+pub(crate) struct StorageWalk {
+    files: BTreeMap<PathBuf, u64>,
+    skipped_entries: usize,
+}
+
+/// Walks the vault off the UI thread; an unreadable entry is counted, not fatal.
+pub(crate) fn walk_storage(root: &Path) -> StorageWalk {
+    let mut walk = StorageWalk::default();
+    if root.is_dir() {
+        collect_file_sizes(root, &mut walk.files, &mut walk.skipped_entries);
+    }
+    walk
+}
+
+/// File sizes under the active vault. Built by one walk, then kept current from the paths
+/// the file tree watcher reports instead of walking the vault again.
+#[derive(Debug, Default)]
+pub(crate) struct StorageIndex {
+    root: PathBuf,
+    files: BTreeMap<PathBuf, u64>,
+    skipped_entries: usize,
+    built: bool,
+}
+
+impl StorageIndex {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            files: BTreeMap::new(),
+            skipped_entries: 0,
+            built: false,
+        }
+    }
+
+    pub fn is_built(&self) -> bool {
+        self.built
+    }
+
+    pub fn switch_root(&mut self, root: &Path) {
+        *self = Self::new(root.to_path_buf());
+    }
+
+    pub fn install(&mut self, walk: StorageWalk) {
+        self.files = walk.files;
+        self.skipped_entries = walk.skipped_entries;
+        self.built = true;
+    }
+
+    /// Re-reads just the changed paths and returns whether the vault total moved. A path
+    /// that is now a folder (a move into the vault) is walked; one that is gone drops
+    /// everything beneath it.
+    pub fn apply_changed_paths(&mut self, paths: &[PathBuf]) -> bool {
+        if !self.built {
+            return false;
+        }
+        let before = self.total_bytes();
+        for path in paths.iter().filter(|path| path.starts_with(&self.root)) {
+            self.files.retain(|file, _| !file.starts_with(path));
+            match fs::metadata(path) {
+                Ok(metadata) if metadata.is_file() => {
+                    self.files.insert(path.clone(), metadata.len());
+                }
+                Ok(metadata) if metadata.is_dir() => {
+                    collect_file_sizes(path.as_path(), &mut self.files, &mut 0);
+                }
+                _ => {}
+            }
+        }
+        before != self.total_bytes()
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.files.values().sum()
+    }
+
+    pub fn report(&self) -> StorageReport {
+        let mut notes = self
+            .files
+            .iter()
+            .filter(|(path, _)| {
+                crate::file_update_handler::NoteFileExtension::from_path(path.as_path()).is_some()
+            })
+            .map(|(path, size)| (path.clone(), *size))
+            .collect::<Vec<_>>();
+        let mut attachment_dirs = BTreeMap::<PathBuf, u64>::new();
+        for (path, size) in &self.files {
+            if let Some(dir) = path.ancestors().skip(1).find(|dir| {
+                dir.file_name()
+                    .is_some_and(|name| name == crate::attachments::ATTACHMENTS_DIR_NAME)
+            }) {
+                *attachment_dirs.entry(dir.to_path_buf()).or_default() += size;
+            }
+        }
+        let mut attachment_dirs = attachment_dirs.into_iter().collect::<Vec<_>>();
+        for entries in [&mut notes, &mut attachment_dirs] {
+            entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            entries.truncate(STORAGE_REPORT_TOP_COUNT);
+        }
+        StorageReport {
+            total_bytes: self.total_bytes(),
+            largest_notes: notes,
+            largest_attachment_dirs: attachment_dirs,
+            skipped_entries: self.skipped_entries,
+        }
+    }
+}
+
+fn collect_file_sizes(dir: &Path, files: &mut BTreeMap<PathBuf, u64>, skipped: &mut usize) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) => {
+            trace_debug(format!(
+                "req-sto1 storage walk skipped dir={} error={error}",
+                dir.display()
+            ));
+            *skipped += 1;
+            return;
+        }
+    };
+    for entry in entries {
+        let Ok((path, metadata)) = entry.and_then(|entry| Ok((entry.path(), entry.metadata()?)))
+        else {
+            *skipped += 1;
+            continue;
+        };
+        if metadata.is_dir() {
+            collect_file_sizes(path.as_path(), files, skipped);
+        } else if metadata.is_file() {
+            files.insert(path, metadata.len());
+        }
+    }
+}
+
+/// The size index plus the optional warning, which fires once each time the vault grows
+/// past the limit.
+#[derive(Debug)]
+pub(crate) struct StorageMonitor {
+    index: StorageIndex,
+    warn_above_bytes: Option<u64>,
+    warned: bool,
+    // Bumped by every walk started so one for a vault switched away from is dropped.
+    walk_generation: u64,
+}
+
+impl StorageMonitor {
+    pub fn new(root: PathBuf, warn_above_bytes: Option<u64>) -> Self {
+        Self {
+            index: StorageIndex::new(root),
+            warn_above_bytes,
+            warned: false,
+            walk_generation: 0,
+        }
+    }
+
+    /// A message the first time the total crosses the limit; nothing while it stays above.
+    fn take_warning(&mut self) -> Option<String> {
+        let limit = self.warn_above_bytes?;
+        let total = self.index.total_bytes();
+        if total <= limit {
+            self.warned = false;
+            return None;
+        }
+        if self.warned {
+            return None;
+        }
+        self.warned = true;
+        Some(tr_with(
+            "notice.vault_over_limit",
+            &[
+                ("size", crate::export::format_megabytes(total).as_str()),
+                ("limit", crate::export::format_megabytes(limit).as_str()),
+            ],
+        ))
+    }
+}
+
+fn relative_label(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+impl crate::app::Papyru2App {
+    // Only the warning needs the index right away; otherwise the first report builds it.
+    pub(crate) fn start_storage_monitor(&mut self, cx: &mut Context<Self>) {
+        if self.storage_monitor.warn_above_bytes.is_none() {
+            return;
+        }
+        let walk = self.spawn_storage_walk(cx);
+        cx.spawn(async move |this, cx| {
+            let (generation, walk) = walk.await;
+            let _ = this.update(cx, |app, cx| {
+                if app.install_storage_walk(generation, walk) {
+                    app.check_storage_warning(cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn spawn_storage_walk(&mut self, cx: &mut Context<Self>) -> Task<(u64, StorageWalk)> {
+        self.storage_monitor.walk_generation += 1;
+        let generation = self.storage_monitor.walk_generation;
+        let root = self.storage_monitor.index.root.clone();
+        cx.background_executor()
+            .spawn(async move { (generation, walk_storage(&root)) })
+    }
+
+    fn install_storage_walk(&mut self, generation: u64, walk: StorageWalk) -> bool {
+        if self.storage_monitor.walk_generation != generation {
+            return false;
+        }
+        if walk.skipped_entries > 0 {
+            trace_debug(format!(
+                "req-sto1 storage walk skipped_entries={}",
+                walk.skipped_entries
+            ));
+        }
+        self.storage_monitor.index.install(walk);
+        true
+    }
+
+    pub(crate) fn switch_storage_root(&mut self, root: &Path, cx: &mut Context<Self>) {
+        self.storage_monitor.index.switch_root(root);
+        self.storage_monitor.warned = false;
+        self.storage_monitor.walk_generation += 1;
+        self.start_storage_monitor(cx);
+    }
+
+    pub(crate) fn apply_storage_changes(&mut self, paths: &[PathBuf], cx: &mut Context<Self>) {
+        if self.storage_monitor.index.apply_changed_paths(paths) {
+            self.check_storage_warning(cx);
+        }
+    }
+
+    fn check_storage_warning(&mut self, cx: &mut Context<Self>) {
+        if let Some(message) = self.storage_monitor.take_warning() {
+            trace_debug(format!("req-sto1 storage warning {message}"));
+            self.show_transient_notice(message, cx);
+        }
+    }
+
+    pub(crate) fn show_storage_report(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.storage_monitor.index.is_built() {
+            let walk = self.spawn_storage_walk(cx);
+            cx.spawn_in(window, async move |this, cx| {
+                let (generation, walk) = walk.await;
+                let _ = this.update_in(cx, |app, window, cx| {
+                    if app.install_storage_walk(generation, walk) {
+                        app.show_storage_report(window, cx);
+                    }
+                });
+            })
+            .detach();
+            return;
+        }
+        let root = self.app_paths.user_document_dir.clone();
+        let report = self.storage_monitor.index.report();
+        let mut detail = tr_with(
+            "prompt.vault_size",
+            &[(
+                "size",
+                crate::export::format_megabytes(report.total_bytes).as_str(),
+            )],
+        );
+        if let Some(limit) = self.storage_monitor.warn_above_bytes {
+            detail.push_str(&tr_with(
+                "prompt.vault_warning_limit",
+                &[("limit", crate::export::format_megabytes(limit).as_str())],
+            ));
+        }
+        if report.skipped_entries > 0 {
+            detail.push_str(&tr_with(
+                "prompt.vault_skipped_entries",
+                &[("count", report.skipped_entries.to_string().as_str())],
+            ));
+        }
+        if !report.largest_attachment_dirs.is_empty() {
+            detail.push_str("\n\n");
+            detail.push_str(&tr("prompt.largest_attachment_folders"));
+            for (dir, size) in &report.largest_attachment_dirs {
+                detail.push_str(&format!(
+                    "\n{}  {}",
+                    relative_label(root.as_path(), dir),
+                    crate::export::format_megabytes(*size)
+                ));
+            }
+        }
+        detail.push_str("\n\n");
+        detail.push_str(&tr("prompt.largest_notes"));
+        let notes = report.largest_notes;
+        let mut labels = notes
+            .iter()
+            .map(|(path, size)| {
+                format!(
+                    "{}  {}",
+                    relative_label(root.as_path(), path),
+                    crate::export::format_megabytes(*size)
+                )
+            })
+            .collect::<Vec<_>>();
+        labels.push(tr("button.close").to_string());
+        let label_refs = labels.iter().map(String::as_str).collect::<Vec<_>>();
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &tr("prompt.storage_report"),
+            Some(&detail),
+            &label_refs,
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(choice) = answer.await else {
+                return;
+            };
+            let Some((path, _)) = notes.get(choice).cloned() else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                if app.open_file(path.clone(), window, cx) {
+                    app.file_tree.update(cx, |file_tree, cx| {
+                        file_tree.restore_selection_for_path(path.as_path(), cx);
+                    });
+                }
+            });
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_storage_report_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    #[test]
+    fn sto_test1_report_ranks_notes_and_attachment_folders() {
+        let root = new_temp_root("sto_test1");
+        let day = root.join("2026").join("10").join("16");
+        let assets = day.join(crate::attachments::ATTACHMENTS_DIR_NAME);
+        fs::create_dir_all(&assets).expect("create assets");
+        fs::write(day.join("small.txt"), "a").expect("write small");
+        fs::write(day.join("big.md"), "abcdef").expect("write big");
+        fs::write(assets.join("shot.png"), [0u8; 40]).expect("write image");
+
+        let mut index = StorageIndex::new(root.clone());
+        assert!(!index.apply_changed_paths(std::slice::from_ref(&day)));
+        index.install(walk_storage(&root));
+        let report = index.report();
+
+        assert_eq!(report.total_bytes, 47);
+        assert_eq!(
+            report.largest_notes,
+            vec![(day.join("big.md"), 6), (day.join("small.txt"), 1)]
+        );
+        assert_eq!(report.largest_attachment_dirs, vec![(assets.clone(), 40)]);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn sto_test2_changed_paths_update_index_and_warn_once_per_crossing() {
+        let root = new_temp_root("sto_test2");
+        let note = root.join("note.txt");
+        fs::write(&note, "1234").expect("write note");
+        let mut monitor = StorageMonitor::new(root.clone(), Some(10));
+        monitor.index.install(walk_storage(&root));
+        assert_eq!(monitor.take_warning(), None);
+
+        fs::write(&note, "123456789012").expect("grow note");
+        assert!(
+            monitor
+                .index
+                .apply_changed_paths(std::slice::from_ref(&note))
+        );
+        assert!(monitor.take_warning().is_some());
+        assert_eq!(monitor.take_warning(), None);
+
+        let moved = root.join("folder");
+        fs::create_dir_all(&moved).expect("create folder");
+        fs::rename(&note, moved.join("note.txt")).expect("move note");
+        monitor
+            .index
+            .apply_changed_paths(&[note.clone(), moved.clone()]);
+        assert_eq!(monitor.index.total_bytes(), 12);
+
+        fs::remove_dir_all(&moved).expect("remove folder");
+        assert!(monitor.index.apply_changed_paths(&[moved]));
+        assert_eq!(monitor.index.total_bytes(), 0);
+        assert_eq!(monitor.take_warning(), None);
+        assert!(!monitor.warned);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn sto_test3_unreadable_entries_are_counted_not_fatal() {
+        let root = new_temp_root("sto_test3");
+        fs::write(root.join("note.txt"), "1234").expect("write note");
+
+        let mut files = BTreeMap::new();
+        let mut skipped = 0;
+        collect_file_sizes(&root.join("missing"), &mut files, &mut skipped);
+        collect_file_sizes(&root, &mut files, &mut skipped);
+        assert_eq!(skipped, 1);
+        assert_eq!(files.len(), 1);
+
+        let mut index = StorageIndex::new(root.clone());
+        index.install(StorageWalk {
+            files,
+            skipped_entries: skipped,
+        });
+        assert_eq!(index.report().skipped_entries, 1);
+        assert_eq!(index.report().total_bytes, 4);
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,401 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{Input, InputState},
+    v_flex,
+};
+use regex::Regex;
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+
+// Dropbox: `plan (Alice's conflicted copy 2026-03-01).md`.
+// Syncthing: `plan.sync-conflict-20260301-101500-ABCDEFG.md`.
+const CONFLICT_STEM_PATTERNS: [&str; 2] = [
+    r"(?i)^(.+?) \([^()]*conflicted copy[^()]*\)$",
+    r"^(.+)\.sync-conflict-\d{8}-\d{6}(?:-[A-Z0-9]+)?$",
+];
+static CONFLICT_STEM_REGEXES: OnceLock<Vec<Regex>> = OnceLock::new();
+
+fn conflict_stem_regexes() -> &'static [Regex] {
+    CONFLICT_STEM_REGEXES.get_or_init(|| {
+        CONFLICT_STEM_PATTERNS
+            .iter()
+            .filter_map(|pattern| Regex::new(pattern).ok())
+            .collect()
+    })
+}
+
+/// The note a sync service's conflicted copy was split from: the sibling with the stem the
+/// service decorated and the same extension. `None` for anything that is not a copy.
+pub(crate) fn conflict_original(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_str()?;
+    let base = conflict_stem_regexes()
+        .iter()
+        .find_map(|pattern| pattern.captures(stem)?.get(1))?
+        .as_str();
+    let name = match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => format!("{base}.{extension}"),
+        None => base.to_string(),
+    };
+    Some(path.with_file_name(name))
+}
+
+/// Lines that differ once the common head and tail are set aside; an inserted line counts
+/// once instead of shifting everything after it.
+pub(crate) fn differing_line_count(left: &str, right: &str) -> usize {
+    let left = left.lines().collect::<Vec<_>>();
+    let right = right.lines().collect::<Vec<_>>();
+    let head = left
+        .iter()
+        .zip(&right)
+        .take_while(|(left, right)| left == right)
+        .count();
+    let tail = left[head..]
+        .iter()
+        .rev()
+        .zip(right[head..].iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+    (left.len() - head - tail).max(right.len() - head - tail)
+}
+
+#[derive(Clone, Debug)]
+pub enum MergeViewEvent {
+    /// `text` replaces the original and the conflicted copy goes to the recycle bin.
+    Resolved {
+        original: PathBuf,
+        conflict: PathBuf,
+        text: String,
+    },
+    Cancelled,
+}
+
+/// Original on the left, conflicted copy on the right. Both panes are editable, so the
+/// user can carry lines across before keeping one side.
+pub struct MergeView {
+    original: PathBuf,
+    conflict: PathBuf,
+    left: Entity<InputState>,
+    right: Entity<InputState>,
+    differing_lines: usize,
+}
+
+impl EventEmitter<MergeViewEvent> for MergeView {}
+
+impl MergeView {
+    pub fn new(
+        original: PathBuf,
+        original_text: String,
+        conflict: PathBuf,
+        conflict_text: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let differing_lines = differing_line_count(&original_text, &conflict_text);
+        let left = cx.new(|cx| InputState::new(window, cx).multi_line(true));
+        left.update(cx, |state, cx| state.set_value(original_text, window, cx));
+        let right = cx.new(|cx| InputState::new(window, cx).multi_line(true));
+        right.update(cx, |state, cx| state.set_value(conflict_text, window, cx));
+        Self {
+            original,
+            conflict,
+            left,
+            right,
+            differing_lines,
+        }
+    }
+
+    fn keep(&mut self, left: bool, cx: &mut Context<Self>) {
+        let input = if left { &self.left } else { &self.right };
+        let text = input.read(cx).value().to_string();
+        cx.emit(MergeViewEvent::Resolved {
+            original: self.original.clone(),
+            conflict: self.conflict.clone(),
+            text,
+        });
+    }
+
+    fn render_pane(
+        title: String,
+        input: &Entity<InputState>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .gap_1()
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(title),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_h_0()
+                    .child(Input::new(input).size_full()),
+            )
+    }
+}
+
+impl Render for MergeView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let file_name = |path: &Path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        let summary = match self.differing_lines {
+            0 => "The copies have the same text".to_string(),
+            count => format!("{count} line(s) differ"),
+        };
+        let header = h_flex()
+            .w_full()
+            .gap_2()
+            .items_center()
+            .text_xs()
+            .child(div().flex_1().child(summary))
+            .child(
+                Button::new("merge-keep-left")
+                    .small()
+                    .primary()
+                    .label(crate::i18n::tr("button.keep_left"))
+                    .on_click(cx.listener(|this, _, _, cx| this.keep(true, cx))),
+            )
+            .child(
+                Button::new("merge-keep-right")
+                    .small()
+                    .label(crate::i18n::tr("button.keep_right"))
+                    .on_click(cx.listener(|this, _, _, cx| this.keep(false, cx))),
+            )
+            .child(
+                Button::new("merge-cancel")
+                    .small()
+                    .ghost()
+                    .label(crate::i18n::tr("button.cancel"))
+                    .on_click(cx.listener(|_, _, _, cx| cx.emit(MergeViewEvent::Cancelled))),
+            );
+        let left = Self::render_pane(
+            format!("Original: {}", file_name(&self.original)),
+            &self.left,
+            cx,
+        );
+        let right = Self::render_pane(
+            format!("Conflicted copy: {}", file_name(&self.conflict)),
+            &self.right,
+            cx,
+        );
+        v_flex()
+            .size_full()
+            .gap_2()
+            .child(header)
+            .child(h_flex().flex_1().min_h_0().gap_2().child(left).child(right))
+    }
+}
+
+/// The merge view shown in place of the panels until it is resolved or cancelled.
+pub(crate) struct ConflictMerge {
+    pub view: Entity<MergeView>,
+    _subscription: Subscription,
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn open_conflict_merge(
+        &mut self,
+        conflict: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(original) = conflict_original(&conflict) else {
+            return;
+        };
+        if !original.is_file() {
+            self.show_transient_notice(
+                tr_with(
+                    "notice.conflict_original_gone",
+                    &[("path", conflict.display().to_string().as_str())],
+                ),
+                cx,
+            );
+            return;
+        }
+        // The open note may be one of the two; the left pane must show what is on disk.
+        if !self.flush_editor_content_before_context_switch("req-sync-merge", cx) {
+            self.show_transient_notice(tr("notice.merge_needs_save").to_string(), cx);
+            return;
+        }
+        let texts = fs::read_to_string(&original)
+            .and_then(|original_text| Ok((original_text, fs::read_to_string(&conflict)?)));
+        let (original_text, conflict_text) = match texts {
+            Ok(texts) => texts,
+            Err(error) => {
+                self.show_transient_notice(
+                    tr_with(
+                        "notice.cannot_read_notes",
+                        &[("reason", error.to_string().as_str())],
+                    ),
+                    cx,
+                );
+                return;
+            }
+        };
+        trace_debug(format!(
+            "req-sync merge open original={} conflict={}",
+            original.display(),
+            conflict.display()
+        ));
+        let view = cx
+            .new(|cx| MergeView::new(original, original_text, conflict, conflict_text, window, cx));
+        let subscription = cx.subscribe_in(&view, window, |this, _, event, window, cx| {
+            this.handle_merge_view_event(event, window, cx);
+        });
+        self.conflict_merge = Some(ConflictMerge {
+            view,
+            _subscription: subscription,
+        });
+        cx.notify();
+    }
+
+    fn handle_merge_view_event(
+        &mut self,
+        event: &MergeViewEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.conflict_merge = None;
+        cx.notify();
+        let MergeViewEvent::Resolved {
+            original,
+            conflict,
+            text,
+        } = event
+        else {
+            trace_debug("req-sync merge cancelled".to_string());
+            return;
+        };
+        let request = crate::file_update_handler::ConflictMergeRequest {
+            original: original.clone(),
+            conflict: conflict.clone(),
+            text: text.clone(),
+            recyclebin_dir: self.app_paths.recyclebin_dir.clone(),
+        };
+        // The conflicted copy is about to go to the recyclebin; the editor must not stay on it.
+        if self.file_workflow.current_edit_path().as_ref() == Some(conflict) {
+            let opening =
+                self.open_file_then(original.clone(), window, cx, move |app, window, cx| {
+                    app.dispatch_conflict_merge(request, window, cx);
+                });
+            if !opening {
+                self.show_transient_notice(tr("notice.merge_needs_save").to_string(), cx);
+            }
+            return;
+        }
+        self.dispatch_conflict_merge(request, window, cx);
+    }
+
+    fn dispatch_conflict_merge(
+        &mut self,
+        request: crate::file_update_handler::ConflictMergeRequest,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let original = request.original.clone();
+        let conflict = request.conflict.clone();
+        let (done_tx, done_rx) = smol::channel::bounded(1);
+        let queued = self
+            .file_workflow
+            .merge_conflict_async(request, move |result| {
+                let _ = done_tx.try_send(result);
+            });
+        if let Err(error) = queued {
+            self.show_transient_notice(
+                tr_with(
+                    "notice.merge_save_failed",
+                    &[("reason", error.to_string().as_str())],
+                ),
+                cx,
+            );
+            return;
+        }
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(result) = done_rx.recv().await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.finish_conflict_merge(original, conflict, result, window, cx)
+            });
+        })
+        .detach();
+    }
+
+    fn finish_conflict_merge(
+        &mut self,
+        original: PathBuf,
+        conflict: PathBuf,
+        result: crate::workflow_error::WorkflowResult<Option<String>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let message = match result {
+            Ok(None) => tr("notice.merged").to_string(),
+            Ok(Some(reason)) => tr_with("notice.merged_copy_kept", &[("reason", reason.as_str())]),
+            Err(error) => {
+                self.show_transient_notice(
+                    tr_with(
+                        "notice.merge_save_failed",
+                        &[("reason", error.to_string().as_str())],
+                    ),
+                    cx,
+                );
+                return;
+            }
+        };
+        trace_debug(format!(
+            "req-sync merge resolved original={} conflict={}",
+            original.display(),
+            conflict.display()
+        ));
+        self.check_current_file_external_change(window, cx);
+        self.apply_file_tree_watcher_refresh(cx);
+        self.show_transient_notice(message, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_test1_conflicted_copies_map_to_their_original() {
+        let day = Path::new("/vault/2026/03/01");
+        assert_eq!(
+            conflict_original(&day.join("plan (Alice's conflicted copy 2026-03-01).md")),
+            Some(day.join("plan.md"))
+        );
+        assert_eq!(
+            conflict_original(&day.join("my plan (Conflicted Copy).txt")),
+            Some(day.join("my plan.txt"))
+        );
+        assert_eq!(
+            conflict_original(&day.join("plan.sync-conflict-20260301-101500-ABCDEFG.md")),
+            Some(day.join("plan.md"))
+        );
+        assert_eq!(conflict_original(&day.join("plan (draft).md")), None);
+        assert_eq!(conflict_original(&day.join("plan.md")), None);
+
+        assert_eq!(differing_line_count("a\nb\nc", "a\nb\nc"), 0);
+        assert_eq!(differing_line_count("a\nb\nc", "a\nx\nb\nc"), 1);
+        assert_eq!(differing_line_count("a\nb\nc", "a\ny\nz"), 2);
+    }
+}
@@ -0,0 +1,434 @@
+use std::{collections::VecDeque, path::Path};
+
+use chrono::{DateTime, Local};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex, v_flex,
+};
+
+use crate::i18n::{tr, tr_with};
+use crate::storage_backend::{RemoteSyncEvent, RemoteSyncOutcome};
+
+// A session log like the notification drawer, not an archive.
+const SYNC_ACTIVITY_LIMIT: usize = 100;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum SyncActivityKind {
+    Sent { name: String },
+    Deferred { error: String },
+    Refused { name: String, error: String },
+    BackedUp { files: usize, archive: String },
+    BackupFailed { error: String },
+}
+
+impl SyncActivityKind {
+    fn failed(&self) -> bool {
+        matches!(self, Self::Refused { .. } | Self::BackupFailed { .. })
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Self::Sent { name } => tr_with("sync.activity_sent", &[("name", name.as_str())]),
+            Self::Deferred { error } => {
+                tr_with("sync.activity_deferred", &[("reason", error.as_str())])
+            }
+            Self::Refused { name, error } => tr_with(
+                "sync.activity_refused",
+                &[("name", name.as_str()), ("reason", error.as_str())],
+            ),
+            Self::BackedUp { files, archive } => tr_with(
+                "notice.backed_up",
+                &[
+                    ("count", files.to_string().as_str()),
+                    ("path", archive.as_str()),
+                ],
+            ),
+            Self::BackupFailed { error } => {
+                tr_with("sync.activity_backup_failed", &[("reason", error.as_str())])
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SyncActivity {
+    pub at: DateTime<Local>,
+    pub kind: SyncActivityKind,
+}
+
+/// The active vault's remote queue and the last vault backup, for the status bar and its
+/// activity panel.
+#[derive(Debug, Default)]
+pub(crate) struct SyncStatus {
+    /// Set once the vault's remote backend reports; a local vault shows no sync part.
+    remote: bool,
+    pending: usize,
+    last_synced: Option<DateTime<Local>>,
+    /// Why the queue is waiting while the server cannot be reached.
+    offline: Option<String>,
+    /// Changes the server refused since the panel was last opened.
+    refused: usize,
+    backup_failed: bool,
+    activity: VecDeque<SyncActivity>,
+    pub panel_open: bool,
+}
+
+fn file_label(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+// Today's times without the date.
+fn short_time(at: DateTime<Local>, now: DateTime<Local>) -> String {
+    if at.date_naive() == now.date_naive() {
+        at.format("%H:%M").to_string()
+    } else {
+        at.format("%Y-%m-%d %H:%M").to_string()
+    }
+}
+
+impl SyncStatus {
+    pub fn apply_remote(&mut self, event: &RemoteSyncEvent, now: DateTime<Local>) {
+        self.remote = true;
+        self.pending = event.pending;
+        match &event.outcome {
+            RemoteSyncOutcome::Queued => {}
+            RemoteSyncOutcome::Sent(path) => {
+                self.last_synced = Some(now);
+                self.offline = None;
+                self.log(
+                    now,
+                    SyncActivityKind::Sent {
+                        name: file_label(path),
+                    },
+                );
+            }
+            RemoteSyncOutcome::Deferred(error) => {
+                // Retries back off for minutes; the panel only needs the first one.
+                if self.offline.is_none() {
+                    self.log(
+                        now,
+                        SyncActivityKind::Deferred {
+                            error: error.clone(),
+                        },
+                    );
+                }
+                self.offline = Some(error.clone());
+            }
+            RemoteSyncOutcome::Dropped { path, error } => {
+                self.offline = None;
+                self.refused += 1;
+                self.log(
+                    now,
+                    SyncActivityKind::Refused {
+                        name: file_label(path),
+                        error: error.clone(),
+                    },
+                );
+            }
+        }
+    }
+
+    pub fn record_backup(&mut self, result: Result<(usize, &Path), String>, now: DateTime<Local>) {
+        self.backup_failed = result.is_err();
+        let kind = match result {
+            Ok((files, archive)) => SyncActivityKind::BackedUp {
+                files,
+                archive: archive.display().to_string(),
+            },
+            Err(error) => SyncActivityKind::BackupFailed { error },
+        };
+        self.log(now, kind);
+    }
+
+    /// A new vault: its backend reports afresh, the session log stays.
+    pub fn reset_remote(&mut self) {
+        self.remote = false;
+        self.pending = 0;
+        self.last_synced = None;
+        self.offline = None;
+        self.refused = 0;
+    }
+
+    fn log(&mut self, at: DateTime<Local>, kind: SyncActivityKind) {
+        if self.activity.len() == SYNC_ACTIVITY_LIMIT {
+            self.activity.pop_front();
+        }
+        self.activity.push_back(SyncActivity { at, kind });
+    }
+
+    pub fn activity(&self) -> impl DoubleEndedIterator<Item = &SyncActivity> {
+        self.activity.iter()
+    }
+
+    /// Refused changes or a failed backup: shown until the panel is opened or the next
+    /// backup works.
+    pub fn has_error(&self) -> bool {
+        self.refused > 0 || self.backup_failed
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline.is_some()
+    }
+
+    /// `None` for a local vault that has never been backed up.
+    pub fn label(
+        &self,
+        backup_last_at: Option<DateTime<Local>>,
+        backup_running: bool,
+        now: DateTime<Local>,
+    ) -> Option<String> {
+        let count = self.pending.to_string();
+        let sync = self.remote.then(|| {
+            if self.refused > 0 {
+                tr_with(
+                    "sync.refused",
+                    &[("count", self.refused.to_string().as_str())],
+                )
+            } else if self.offline.is_some() {
+                tr_with("sync.offline", &[("count", count.as_str())])
+            } else if self.pending > 0 {
+                tr_with("sync.pending", &[("count", count.as_str())])
+            } else if let Some(at) = self.last_synced {
+                tr_with("sync.synced_at", &[("time", short_time(at, now).as_str())])
+            } else {
+                tr("sync.up_to_date").to_string()
+            }
+        });
+        let backup = if backup_running {
+            Some(tr("sync.backing_up").to_string())
+        } else if self.backup_failed {
+            Some(tr("sync.backup_failed").to_string())
+        } else {
+            backup_last_at.map(|at| {
+                tr_with(
+                    "sync.backed_up_at",
+                    &[("time", short_time(at, now).as_str())],
+                )
+            })
+        };
+        let parts = sync.into_iter().chain(backup).collect::<Vec<_>>();
+        (!parts.is_empty()).then(|| parts.join(" \u{00b7} "))
+    }
+}
+
+impl crate::app::Papyru2App {
+    /// Keeps the indicator on the active vault's queue, and tells the user about every change
+    /// its server refused: the note is saved locally but the remote copy is now behind.
+    pub(crate) fn spawn_remote_sync_loop(window: &mut Window, cx: &mut Context<Self>) {
+        let events = crate::storage_backend::remote_sync_events();
+        cx.spawn_in(window, async move |this, cx| {
+            while let Ok(event) = events.recv().await {
+                let updated = this.update_in(cx, |app, _window, cx| {
+                    // The vault that was just switched away from.
+                    if event.root != app.app_paths.user_document_dir {
+                        return;
+                    }
+                    app.sync_status.apply_remote(&event, Local::now());
+                    if let RemoteSyncOutcome::Dropped { path, error } = &event.outcome {
+                        app.show_toast(
+                            crate::toasts::ToastSeverity::Error,
+                            tr_with(
+                                "notice.remote_change_dropped",
+                                &[
+                                    ("name", file_label(path).as_str()),
+                                    ("reason", error.as_str()),
+                                ],
+                            ),
+                            cx,
+                        );
+                    }
+                    cx.notify();
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    pub(crate) fn toggle_sync_activity(&mut self, cx: &mut Context<Self>) {
+        self.sync_status.panel_open = !self.sync_status.panel_open;
+        if self.sync_status.panel_open {
+            self.sync_status.refused = 0;
+        }
+        cx.notify();
+    }
+
+    pub(crate) fn render_sync_status(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let label = self.sync_status.label(
+            self.vault_backup_last_at,
+            self.vault_backup_running,
+            Local::now(),
+        )?;
+        let badge = if self.sync_status.has_error() {
+            Some(cx.theme().danger)
+        } else if self.sync_status.is_offline() {
+            Some(cx.theme().warning)
+        } else {
+            None
+        };
+        Some(
+            h_flex()
+                .items_center()
+                .children(badge.map(|color| div().text_color(color).child("\u{25cf}")))
+                .child(
+                    Button::new("sync-status")
+                        .xsmall()
+                        .ghost()
+                        .label(label)
+                        .on_click(cx.listener(|this, _, _, cx| this.toggle_sync_activity(cx))),
+                )
+                .into_any_element(),
+        )
+    }
+
+    /// Sync and backup events of this session, newest first.
+    pub(crate) fn render_sync_activity(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        if !self.sync_status.panel_open {
+            return None;
+        }
+        let rows = self.sync_status.activity().rev().map(|entry| {
+            h_flex()
+                .w_full()
+                .gap_2()
+                .text_xs()
+                .child(
+                    div()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(entry.at.format("%H:%M:%S").to_string()),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .min_w_0()
+                        .when(entry.kind.failed(), |row| row.text_color(cx.theme().danger))
+                        .child(entry.kind.message()),
+                )
+        });
+        let empty = self.sync_status.activity().next().is_none();
+        Some(
+            v_flex()
+                .id("sync-activity-panel")
+                .w_full()
+                .max_h(px(240.))
+                .overflow_y_scroll()
+                .gap_1()
+                .p_2()
+                .border_t_1()
+                .border_color(cx.theme().border)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_2()
+                        .items_center()
+                        .text_xs()
+                        .child(div().flex_1().child(tr("sync.activity_title")))
+                        .when_some(self.sync_status.offline.as_ref(), |header, error| {
+                            header.child(div().text_color(cx.theme().warning).child(tr_with(
+                                "sync.activity_deferred",
+                                &[("reason", error.as_str())],
+                            )))
+                        })
+                        .child(
+                            Button::new("sync-activity-close")
+                                .xsmall()
+                                .ghost()
+                                .label(tr("button.close"))
+                                .on_click(
+                                    cx.listener(|this, _, _, cx| this.toggle_sync_activity(cx)),
+                                ),
+                        ),
+                )
+                .when(empty, |panel| {
+                    panel.child(div().text_xs().child(tr("toast.nothing_yet")))
+                })
+                .children(rows)
+                .into_any_element(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone as _;
+    use std::path::PathBuf;
+
+    #[test]
+    fn syn_test1_indicator_follows_the_remote_queue_and_the_last_backup() {
+        let now = Local
+            .with_ymd_and_hms(2026, 3, 1, 9, 30, 0)
+            .single()
+            .expect("local time");
+        let root = PathBuf::from("/vault");
+        let event = |pending, outcome| RemoteSyncEvent {
+            root: root.clone(),
+            pending,
+            outcome,
+        };
+        let mut status = SyncStatus::default();
+        assert_eq!(status.label(None, false, now), None);
+
+        status.apply_remote(&event(2, RemoteSyncOutcome::Queued), now);
+        assert_eq!(
+            status.label(None, false, now).as_deref(),
+            Some("Sync: 2 pending")
+        );
+        status.apply_remote(
+            &event(2, RemoteSyncOutcome::Deferred("timed out".to_string())),
+            now,
+        );
+        status.apply_remote(
+            &event(2, RemoteSyncOutcome::Deferred("timed out".to_string())),
+            now,
+        );
+        assert!(status.is_offline());
+        assert_eq!(
+            status.label(None, false, now).as_deref(),
+            Some("Sync: offline, 2 pending")
+        );
+        status.apply_remote(&event(1, RemoteSyncOutcome::Sent(root.join("a.md"))), now);
+        status.apply_remote(
+            &event(
+                0,
+                RemoteSyncOutcome::Dropped {
+                    path: root.join("b.md"),
+                    error: "WebDAV PUT returned 403".to_string(),
+                },
+            ),
+            now,
+        );
+        assert!(status.has_error());
+        assert_eq!(
+            status.label(None, false, now).as_deref(),
+            Some("Sync: 1 refused")
+        );
+        assert_eq!(status.activity().count(), 3);
+
+        status.refused = 0;
+        status.record_backup(Err("disk full".to_string()), now);
+        assert_eq!(
+            status.label(Some(now), false, now).as_deref(),
+            Some("Synced 09:30 \u{00b7} Backup failed")
+        );
+        status.record_backup(Ok((3, Path::new("/backups/a.zip"))), now);
+        assert!(!status.has_error());
+        assert_eq!(
+            status.label(Some(now - chrono::Duration::days(1)), false, now),
+            Some("Synced 09:30 \u{00b7} Backed up 2026-02-28 09:30".to_string())
+        );
+
+        status.reset_remote();
+        assert_eq!(
+            status.label(None, true, now).as_deref(),
+            Some("Backing up\u{2026}")
+        );
+        assert_eq!(status.activity().count(), 5);
+    }
+}
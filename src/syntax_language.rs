@@ -0,0 +1,211 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use gpui::*;
+use gpui_component::{Sizable as _, button::Button, button::ButtonVariants as _};
+
+use crate::log::trace_debug;
+
+/// Highlighter name that turns syntax highlighting off.
+pub(crate) const PLAIN_TEXT_LANGUAGE: &str = "text";
+
+/// What the picker offers besides "Automatic"; (label, highlighter name).
+const SYNTAX_LANGUAGE_CHOICES: [(&str, &str); 8] = [
+    ("Plain text", PLAIN_TEXT_LANGUAGE),
+    ("Markdown", "markdown"),
+    ("Rust", "rust"),
+    ("JSON", "json"),
+    ("TOML", "toml"),
+    ("YAML", "yaml"),
+    ("Python", "python"),
+    ("JavaScript", "javascript"),
+];
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct LanguageConfigFile {
+    #[serde(default)]
+    languages: BTreeMap<String, String>,
+}
+
+/// Extension -> highlighter name. Extensions without an entry are handed to the highlighter
+/// as they are, which is how `open_file` chose a language before the table existed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct LanguageMap {
+    by_extension: BTreeMap<String, String>,
+}
+
+impl Default for LanguageMap {
+    fn default() -> Self {
+        let by_extension = [("txt", PLAIN_TEXT_LANGUAGE), ("md", "markdown")]
+            .into_iter()
+            .map(|(extension, language)| (extension.to_string(), language.to_string()))
+            .collect();
+        Self { by_extension }
+    }
+}
+
+impl LanguageMap {
+    pub fn insert(&mut self, extension: &str, language: &str) {
+        let extension = extension
+            .trim()
+            .trim_start_matches('.')
+            .to_ascii_lowercase();
+        let language = language.trim().to_ascii_lowercase();
+        if extension.is_empty() || language.is_empty() {
+            return;
+        }
+        self.by_extension.insert(extension, language);
+    }
+
+    pub fn language_for_path(&self, path: &Path) -> String {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("txt")
+            .to_ascii_lowercase();
+        self.by_extension
+            .get(&extension)
+            .cloned()
+            .unwrap_or(extension)
+    }
+}
+
+/// `[languages]` entries such as `log = "text"` or `mdx = "markdown"` extend the defaults.
+pub(crate) fn load_language_map(path: &Path) -> LanguageMap {
+    let mut map = LanguageMap::default();
+    let Ok(raw) = fs::read_to_string(path) else {
+        return map;
+    };
+    match toml::from_str::<LanguageConfigFile>(&raw) {
+        Ok(parsed) => {
+            for (extension, language) in &parsed.languages {
+                map.insert(extension, language);
+            }
+        }
+        Err(error) => trace_debug(format!(
+            "req-lang config parse failed path={} error={error}",
+            path.display()
+        )),
+    }
+    map
+}
+
+/// Status bar label for a highlighter name.
+pub(crate) fn syntax_language_label(language: &str) -> String {
+    SYNTAX_LANGUAGE_CHOICES
+        .iter()
+        .find(|(_, name)| *name == language)
+        .map(|(label, _)| label.to_string())
+        .unwrap_or_else(|| language.to_string())
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn render_syntax_language_status(
+        &self,
+        cx: &mut Context<Self>,
+    ) -> Option<AnyElement> {
+        let editor = self.editor.read(cx);
+        editor.current_editing_file_path()?;
+        let label = syntax_language_label(editor.current_language());
+        let overridden = editor.language_override().is_some();
+        Some(
+            Button::new("syntax-language-picker")
+                .xsmall()
+                .ghost()
+                .label(if overridden {
+                    format!("{label} *")
+                } else {
+                    label
+                })
+                .on_click(
+                    cx.listener(|app, _, window, cx| app.show_syntax_language_picker(window, cx)),
+                )
+                .into_any_element(),
+        )
+    }
+
+    /// Overrides highlighting for the open buffer only; the next opened note starts from
+    /// its extension (or front-matter `language`) again.
+    pub(crate) fn show_syntax_language_picker(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.editor.read(cx).current_editing_file_path().is_none() {
+            return;
+        }
+        let automatic = crate::i18n::tr("button.automatic");
+        let cancel = crate::i18n::tr("button.cancel");
+        let mut labels = vec![automatic.as_ref()];
+        labels.extend(SYNTAX_LANGUAGE_CHOICES.iter().map(|(label, _)| *label));
+        labels.push(cancel.as_ref());
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &crate::i18n::tr("prompt.highlight_as"),
+            Some(&crate::i18n::tr("prompt.highlight_as_detail")),
+            &labels,
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(choice) = answer.await else {
+                return;
+            };
+            let language = match choice {
+                0 => None,
+                index if index <= SYNTAX_LANGUAGE_CHOICES.len() => {
+                    Some(SYNTAX_LANGUAGE_CHOICES[index - 1].1.to_string())
+                }
+                _ => return,
+            };
+            let _ = this.update(cx, |app, cx| {
+                app.editor
+                    .update(cx, |editor, cx| editor.set_language_override(language, cx));
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn lang_test1_map_defaults_and_unknown_extensions() {
+        let map = LanguageMap::default();
+        assert_eq!(map.language_for_path(Path::new("C:/tmp/note.txt")), "text");
+        assert_eq!(
+            map.language_for_path(Path::new("C:/tmp/NOTE.MD")),
+            "markdown"
+        );
+        assert_eq!(map.language_for_path(Path::new("C:/tmp/main.rs")), "rs");
+        assert_eq!(map.language_for_path(Path::new("C:/tmp/README")), "text");
+        assert_eq!(syntax_language_label("text"), "Plain text");
+        assert_eq!(syntax_language_label("rs"), "rs");
+    }
+
+    #[test]
+    fn lang_test2_config_table_extends_and_overrides_defaults() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_lang_test2_{}_{stamp}.toml",
+            std::process::id()
+        ));
+        fs::write(
+            &path,
+            "[languages]\n\".log\" = \"text\"\ntxt = \"Markdown\"\nrs = \"rust\"\nbad = \"\"\n",
+        )
+        .expect("write conf");
+        let map = load_language_map(&path);
+        assert_eq!(map.language_for_path(Path::new("a.log")), "text");
+        assert_eq!(map.language_for_path(Path::new("a.txt")), "markdown");
+        assert_eq!(map.language_for_path(Path::new("a.rs")), "rust");
+        assert_eq!(map.language_for_path(Path::new("a.bad")), "bad");
+        assert_eq!(map.language_for_path(Path::new("a.md")), "markdown");
+        let _ = fs::remove_file(path);
+    }
+}
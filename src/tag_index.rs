@@ -0,0 +1,515 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+pub(crate) const TAG_INDEX_FILE_NAME: &str = "tag_index.json";
+
+fn is_tag_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '-' | '/')
+}
+
+// `#tag` counts only at the start of a line or after whitespace, so `a#b`, `# heading`
+// and URL fragments are not tags. Pure numbers (`#1`) are skipped as well.
+fn tag_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut previous: Option<char> = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        let at_boundary = previous.is_none_or(char::is_whitespace);
+        previous = Some(ch);
+        if ch != '#' || !at_boundary {
+            continue;
+        }
+        let start = index + ch.len_utf8();
+        let mut end = start;
+        while let Some((next_index, next_ch)) = chars.peek().copied() {
+            if !is_tag_char(next_ch) {
+                break;
+            }
+            end = next_index + next_ch.len_utf8();
+            previous = Some(next_ch);
+            chars.next();
+        }
+        let tag = text[start..end].trim_end_matches(['/', '-']);
+        if !tag.is_empty() && !tag.chars().all(|ch| ch.is_ascii_digit()) {
+            ranges.push(index..start + tag.len());
+        }
+    }
+    ranges
+}
+
+pub(crate) fn parse_tags(text: &str) -> BTreeSet<String> {
+    tag_ranges(text)
+        .into_iter()
+        .map(|range| text[range.start + 1..range.end].to_lowercase())
+        .collect()
+}
+
+/// `text` without its `#tags`. A tag takes the blank before it along, or the blank after it
+/// when it starts the line, and a line that held nothing but tags is dropped.
+pub(crate) fn strip_tags(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut copied_to = 0;
+    for range in tag_ranges(text) {
+        stripped.push_str(&text[copied_to..range.start]);
+        let kept = stripped.trim_end_matches([' ', '\t']).len();
+        stripped.truncate(kept);
+        copied_to = range.end;
+        if stripped.is_empty() || stripped.ends_with('\n') {
+            let rest = &text[copied_to..];
+            copied_to += rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        }
+    }
+    stripped.push_str(&text[copied_to..]);
+    // Tags never span lines, so the two line lists stay aligned.
+    text.split('\n')
+        .zip(stripped.split('\n'))
+        .filter(|(original, line)| original.trim().is_empty() || !line.trim().is_empty())
+        .map(|(_, line)| line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Tags per note, plus each note's outgoing links so renames can find the notes that
+/// refer to the renamed one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct TagIndex {
+    notes: BTreeMap<PathBuf, BTreeSet<String>>,
+    #[serde(default)]
+    links: BTreeMap<PathBuf, crate::note_links::NoteLinks>,
+    // False in indexes written before links were recorded; those are rebuilt once.
+    #[serde(default)]
+    links_indexed: bool,
+}
+
+impl TagIndex {
+    pub fn update_note(&mut self, path: &Path, text: &str) -> bool {
+        let links = crate::note_links::parse_note_links(path, text);
+        let links_changed = if links.is_empty() {
+            self.links.remove(path).is_some()
+        } else if self.links.get(path) == Some(&links) {
+            false
+        } else {
+            self.links.insert(path.to_path_buf(), links);
+            true
+        };
+        let tags = parse_tags(text);
+        let tags_changed = if tags.is_empty() {
+            self.notes.remove(path).is_some()
+        } else if self.notes.get(path) == Some(&tags) {
+            false
+        } else {
+            self.notes.insert(path.to_path_buf(), tags);
+            true
+        };
+        tags_changed || links_changed
+    }
+
+    // Other vaults may be unmounted right now, so only notes under `root` are pruned.
+    pub fn prune_missing_under(&mut self, root: &Path) -> usize {
+        let before = self.notes.len() + self.links.len();
+        self.notes
+            .retain(|path, _| !path.starts_with(root) || path.is_file());
+        self.links
+            .retain(|path, _| !path.starts_with(root) || path.is_file());
+        before - self.notes.len() - self.links.len()
+    }
+
+    pub fn contains_notes_under(&self, root: &Path) -> bool {
+        self.notes
+            .keys()
+            .chain(self.links.keys())
+            .any(|path| path.starts_with(root))
+    }
+
+    pub fn notes_linking_to(&self, target: &Path) -> Vec<PathBuf> {
+        self.links
+            .iter()
+            .filter(|(path, links)| path.as_path() != target && links.links_to(target))
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    pub fn has_path_links(&self, path: &Path) -> bool {
+        self.links
+            .get(path)
+            .is_some_and(|links| !links.paths.is_empty())
+    }
+
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        self.tag_counts_matching(|_| true)
+    }
+
+    pub fn tag_counts_under(&self, root: &Path) -> Vec<(String, usize)> {
+        self.tag_counts_matching(|path| path.starts_with(root))
+    }
+
+    fn tag_counts_matching(&self, include: impl Fn(&Path) -> bool) -> Vec<(String, usize)> {
+        let mut counts = BTreeMap::<String, usize>::new();
+        for (_, tags) in self
+            .notes
+            .iter()
+            .filter(|(path, _)| include(path.as_path()))
+        {
+            for tag in tags {
+                *counts.entry(tag.clone()).or_default() += 1;
+            }
+        }
+        counts.into_iter().collect()
+    }
+
+    pub fn paths_with_tag(&self, tag: &str) -> Vec<PathBuf> {
+        self.notes
+            .iter()
+            .filter(|(_, tags)| tags.contains(tag))
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    pub fn paths_with_tag_under(&self, tag: &str, root: &Path) -> Vec<PathBuf> {
+        self.paths_with_tag(tag)
+            .into_iter()
+            .filter(|path| path.starts_with(root))
+            .collect()
+    }
+}
+
+pub(crate) fn collect_note_paths(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        if path.is_dir() {
+            collect_note_paths(path.as_path(), paths)?;
+        } else if crate::file_update_handler::NoteFileExtension::from_path(path.as_path()).is_some()
+        {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// One index covers every vault (paths are absolute); the sidebar only sees the active one.
+#[derive(Clone, Debug)]
+pub(crate) struct TagIndexStore {
+    index_path: PathBuf,
+    inner: Arc<Mutex<TagIndex>>,
+    root: Arc<Mutex<PathBuf>>,
+}
+
+impl TagIndexStore {
+    // Loads `data_dir/tag_index.json`; a missing or unreadable index is rebuilt from every
+    // note under `user_document_dir`, and entries for deleted notes are pruned.
+    pub fn open(data_dir: &Path, user_document_dir: &Path) -> io::Result<Self> {
+        let index_path = data_dir.join(TAG_INDEX_FILE_NAME);
+        let loaded = fs::read(&index_path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<TagIndex>(&bytes).ok())
+            .filter(|index| index.links_indexed);
+        let rebuilt = loaded.is_none();
+        let mut index = loaded.unwrap_or_default();
+        index.links_indexed = true;
+        if rebuilt && user_document_dir.is_dir() {
+            index_notes_under(&mut index, user_document_dir)?;
+        }
+        let pruned = index.prune_missing_under(user_document_dir);
+        crate::log::trace_debug(format!(
+            "req-tag1 tag_index open path={} rebuilt={} pruned={} tags={}",
+            index_path.display(),
+            rebuilt,
+            pruned,
+            index.tag_counts().len()
+        ));
+
+        let store = Self {
+            index_path,
+            inner: Arc::new(Mutex::new(index)),
+            root: Arc::new(Mutex::new(user_document_dir.to_path_buf())),
+        };
+        if rebuilt || pruned > 0 {
+            store.save()?;
+        }
+        Ok(store)
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let index = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let bytes = serde_json::to_vec_pretty(&*index)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+        crate::atomic_io::write_atomic(self.index_path.as_path(), &bytes)
+    }
+
+    pub fn record_note_text(&self, path: &Path, text: &str) -> io::Result<bool> {
+        let changed = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .update_note(path, text);
+        if changed {
+            self.save()?;
+        }
+        Ok(changed)
+    }
+
+    fn root(&self) -> PathBuf {
+        self.root
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Scopes the store to another vault, indexing it on first use.
+    pub fn switch_root(&self, root: &Path) -> io::Result<()> {
+        let indexed = {
+            let mut index = self
+                .inner
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if index.contains_notes_under(root) || !root.is_dir() {
+                false
+            } else {
+                index_notes_under(&mut index, root)?;
+                true
+            }
+        };
+        *self
+            .root
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = root.to_path_buf();
+        crate::log::trace_debug(format!(
+            "req-tag1 tag_index root switched root={} indexed={indexed}",
+            root.display()
+        ));
+        if indexed {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        let root = self.root();
+        let mut index = self
+            .inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        index.prune_missing_under(root.as_path());
+        index.tag_counts_under(root.as_path())
+    }
+
+    /// Notes that still exist and link to `target` by title or relative path.
+    pub fn notes_linking_to(&self, target: &Path) -> Vec<PathBuf> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .notes_linking_to(target)
+            .into_iter()
+            .filter(|path| path.is_file())
+            .collect()
+    }
+
+    /// Whether the note at `path` has relative markdown links of its own.
+    pub fn has_path_links(&self, path: &Path) -> bool {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .has_path_links(path)
+    }
+
+    pub fn paths_with_tag(&self, tag: &str) -> Vec<PathBuf> {
+        let root = self.root();
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .paths_with_tag_under(tag, root.as_path())
+    }
+}
+
+fn index_notes_under(index: &mut TagIndex, root: &Path) -> io::Result<()> {
+    let mut paths = Vec::new();
+    collect_note_paths(root, &mut paths)?;
+    for path in paths {
+        if let Ok(text) = fs::read_to_string(&path) {
+            index.update_note(path.as_path(), &text);
+        }
+    }
+    Ok(())
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn record_note_tags(&self, path: &Path, text: &str) {
+        let Some(tag_index) = self.tag_index.as_ref() else {
+            return;
+        };
+        if let Err(error) = tag_index.record_note_text(path, text) {
+            crate::log::trace_debug(format!(
+                "req-tag1 tag_index update failed path={} error={error}",
+                path.display()
+            ));
+        }
+    }
+
+    pub(crate) fn sync_tag_sidebar(&mut self, cx: &mut gpui::Context<Self>) {
+        let Some(tag_index) = self.tag_index.clone() else {
+            return;
+        };
+        let tag_counts = tag_index.tag_counts();
+        self.file_tree.update(cx, |file_tree, cx| {
+            file_tree.set_tag_counts(tag_counts, cx);
+            if let Some(tag) = file_tree.tag_filter() {
+                file_tree.refresh_tag_filter_paths(tag_index.paths_with_tag(&tag), cx);
+            }
+        });
+    }
+
+    pub(crate) fn apply_tag_filter_selection(
+        &mut self,
+        tag: Option<String>,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        let paths = match (self.tag_index.as_ref(), tag.as_deref()) {
+            (Some(tag_index), Some(tag)) => tag_index.paths_with_tag(tag),
+            _ => Vec::new(),
+        };
+        let tag = tag.filter(|_| self.tag_index.is_some());
+        self.file_tree.update(cx, |file_tree, cx| {
+            file_tree.apply_tag_filter(tag, paths, cx);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_tag_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    #[test]
+    fn tag_test1_parse_tags_requires_boundary_and_non_numeric_name() {
+        let tags =
+            parse_tags("#Work plan\n# heading a#b #1 #日本語 see http://x/#frag #proj/sub- end");
+
+        assert_eq!(
+            tags.into_iter().collect::<Vec<_>>(),
+            vec![
+                "proj/sub".to_string(),
+                "work".to_string(),
+                "日本語".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn tag_test2_index_tracks_note_updates_and_counts() {
+        let mut index = TagIndex::default();
+        let a = PathBuf::from("C:/tmp/a.txt");
+        let b = PathBuf::from("C:/tmp/b.txt");
+
+        assert!(index.update_note(a.as_path(), "#work #home"));
+        assert!(!index.update_note(a.as_path(), "#home #work again"));
+        assert!(index.update_note(b.as_path(), "#work"));
+        assert_eq!(
+            index.tag_counts(),
+            vec![("home".to_string(), 1), ("work".to_string(), 2)]
+        );
+        assert_eq!(index.paths_with_tag("work"), vec![a.clone(), b.clone()]);
+
+        assert!(index.update_note(a.as_path(), "no tags"));
+        assert_eq!(index.paths_with_tag("home"), Vec::<PathBuf>::new());
+    }
+
+    #[test]
+    fn tag_test3_store_rebuilds_missing_index_from_notes() {
+        let root = new_temp_root("tag_test3");
+        let data_dir = root.join("data");
+        let user_document_dir = data_dir.join("user_document");
+        let day_dir = user_document_dir.join("2026").join("02").join("28");
+        fs::create_dir_all(&day_dir).expect("create day dir");
+        let note = day_dir.join("note.md");
+        fs::write(&note, "#idea draft").expect("write note");
+
+        let store = TagIndexStore::open(data_dir.as_path(), user_document_dir.as_path())
+            .expect("open store");
+
+        assert_eq!(store.tag_counts(), vec![("idea".to_string(), 1)]);
+        assert!(data_dir.join(TAG_INDEX_FILE_NAME).is_file());
+        assert!(
+            store
+                .record_note_text(note.as_path(), "#idea #todo")
+                .expect("record")
+        );
+        assert_eq!(store.paths_with_tag("todo"), vec![note]);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn tag_test4_store_scopes_tags_to_the_active_vault() {
+        let root = new_temp_root("tag_test4");
+        let data_dir = root.join("data");
+        let first = root.join("first");
+        let second = root.join("second");
+        fs::create_dir_all(&data_dir).expect("create data dir");
+        fs::create_dir_all(&first).expect("create first");
+        fs::create_dir_all(&second).expect("create second");
+        fs::write(first.join("a.md"), "#alpha").expect("write a");
+        fs::write(second.join("b.md"), "#beta").expect("write b");
+
+        let store = TagIndexStore::open(data_dir.as_path(), first.as_path()).expect("open");
+        assert_eq!(store.tag_counts(), vec![("alpha".to_string(), 1)]);
+
+        store.switch_root(second.as_path()).expect("switch");
+        assert_eq!(store.tag_counts(), vec![("beta".to_string(), 1)]);
+        assert!(store.paths_with_tag("alpha").is_empty());
+
+        store.switch_root(first.as_path()).expect("switch back");
+        assert_eq!(store.paths_with_tag("alpha"), vec![first.join("a.md")]);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn tag_test5_store_finds_notes_linking_to_a_note() {
+        let root = new_temp_root("tag_test5");
+        let data_dir = root.join("data");
+        let vault = root.join("vault");
+        fs::create_dir_all(&data_dir).expect("create data dir");
+        fs::create_dir_all(&vault).expect("create vault");
+        let plan = vault.join("plan.md");
+        let index = vault.join("index.md");
+        fs::write(&plan, "#todo").expect("write plan");
+        fs::write(&index, "see [[Plan]]").expect("write index");
+        // An index from before links were recorded is rebuilt on open.
+        fs::write(data_dir.join(TAG_INDEX_FILE_NAME), r#"{"notes":{}}"#).expect("write old index");
+
+        let store = TagIndexStore::open(data_dir.as_path(), vault.as_path()).expect("open");
+        assert_eq!(store.notes_linking_to(plan.as_path()), vec![index.clone()]);
+        assert_eq!(store.tag_counts(), vec![("todo".to_string(), 1)]);
+
+        store
+            .record_note_text(index.as_path(), "no links now")
+            .expect("record");
+        assert!(store.notes_linking_to(plan.as_path()).is_empty());
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,399 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+use encoding_rs::{SHIFT_JIS, UTF_16BE, UTF_16LE};
+use gpui::*;
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+
+// Same window git uses to call a file binary.
+const BINARY_SNIFF_BYTES: usize = 8000;
+
+// The encoding each note was opened in, kept for its saves. A note not in here is UTF-8.
+static NOTE_ENCODINGS: OnceLock<Mutex<NoteEncodings>> = OnceLock::new();
+
+#[derive(Debug, Default)]
+struct NoteEncodings {
+    by_path: HashMap<PathBuf, NoteEncoding>,
+    // Saves that had to switch a note to UTF-8, waiting for the UI to tell the user.
+    fallbacks: Vec<(PathBuf, NoteEncoding)>,
+}
+
+fn note_encodings() -> std::sync::MutexGuard<'static, NoteEncodings> {
+    NOTE_ENCODINGS
+        .get_or_init(|| Mutex::new(NoteEncodings::default()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The encoding a note was read in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum NoteEncoding {
+    #[default]
+    Utf8,
+    Utf16LeBom,
+    Utf16BeBom,
+    ShiftJis,
+}
+
+impl NoteEncoding {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf16LeBom => "UTF-16 LE",
+            Self::Utf16BeBom => "UTF-16 BE",
+            Self::ShiftJis => "Shift-JIS",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum NoteDecodeError {
+    #[error("it is a binary file, not a text note")]
+    Binary,
+    #[error("its {} text is damaged", encoding.label())]
+    Malformed { encoding: NoteEncoding },
+}
+
+impl From<NoteDecodeError> for io::Error {
+    fn from(error: NoteDecodeError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+/// Text that is not UTF-8 is accepted when it carries a UTF-16 byte order mark or reads
+/// cleanly as Shift-JIS; anything with NUL bytes or that fits neither is binary.
+pub(crate) fn decode_note_bytes(bytes: &[u8]) -> Result<(String, NoteEncoding), NoteDecodeError> {
+    let utf16 = match bytes {
+        [0xFF, 0xFE, rest @ ..] => Some((UTF_16LE, NoteEncoding::Utf16LeBom, rest)),
+        [0xFE, 0xFF, rest @ ..] => Some((UTF_16BE, NoteEncoding::Utf16BeBom, rest)),
+        _ => None,
+    };
+    if let Some((decoder, encoding, rest)) = utf16 {
+        return decoder
+            .decode_without_bom_handling_and_without_replacement(rest)
+            .map(|text| (text.into_owned(), encoding))
+            .ok_or(NoteDecodeError::Malformed { encoding });
+    }
+    if let Some(text) = std::str::from_utf8(bytes)
+        .ok()
+        .filter(|text| !text.contains('\0'))
+    {
+        return Ok((text.to_string(), NoteEncoding::Utf8));
+    }
+    let sniffed = &bytes[..bytes.len().min(BINARY_SNIFF_BYTES)];
+    if sniffed.contains(&0) {
+        return Err(NoteDecodeError::Binary);
+    }
+    let text = SHIFT_JIS
+        .decode_without_bom_handling_and_without_replacement(bytes)
+        .ok_or(NoteDecodeError::Binary)?;
+    let has_control = text
+        .chars()
+        .any(|ch| ch.is_control() && !matches!(ch, '\t' | '\n' | '\r' | '\x0c'));
+    if has_control {
+        return Err(NoteDecodeError::Binary);
+    }
+    Ok((text.into_owned(), NoteEncoding::ShiftJis))
+}
+
+pub(crate) fn read_note_text(path: &Path) -> io::Result<(String, NoteEncoding)> {
+    let bytes = fs::read(path)?;
+    Ok(decode_note_bytes(&bytes)?)
+}
+
+/// `None` when `text` has characters the encoding cannot hold.
+pub(crate) fn encode_note_text(text: &str, encoding: NoteEncoding) -> Option<Vec<u8>> {
+    match encoding {
+        NoteEncoding::Utf8 => Some(text.as_bytes().to_vec()),
+        NoteEncoding::Utf16LeBom => Some(
+            [0xFF, 0xFE]
+                .into_iter()
+                .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+                .collect(),
+        ),
+        NoteEncoding::Utf16BeBom => Some(
+            [0xFE, 0xFF]
+                .into_iter()
+                .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+                .collect(),
+        ),
+        NoteEncoding::ShiftJis => {
+            let (bytes, _, had_unmappable) = SHIFT_JIS.encode(text);
+            (!had_unmappable).then(|| bytes.into_owned())
+        }
+    }
+}
+
+/// Records the encoding `path` was read in, for its later saves.
+pub(crate) fn remember_note_encoding(path: &Path, encoding: NoteEncoding) {
+    let mut encodings = note_encodings();
+    if encoding == NoteEncoding::Utf8 {
+        encodings.by_path.remove(path);
+    } else {
+        encodings.by_path.insert(path.to_path_buf(), encoding);
+    }
+}
+
+pub(crate) fn note_encoding(path: &Path) -> NoteEncoding {
+    note_encodings()
+        .by_path
+        .get(path)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Follows a rename or move of `from`, a note or a folder of notes, to `to`.
+pub(crate) fn rebase_note_encodings(from: &Path, to: &Path) {
+    if from == to {
+        return;
+    }
+    let mut encodings = note_encodings();
+    let moved = encodings
+        .by_path
+        .keys()
+        .filter(|path| path.starts_with(from))
+        .cloned()
+        .collect::<Vec<_>>();
+    for old in moved {
+        if let (Some(encoding), Ok(relative)) =
+            (encodings.by_path.remove(&old), old.strip_prefix(from))
+        {
+            encodings.by_path.insert(to.join(relative), encoding);
+        }
+    }
+}
+
+/// Reads a note the user is opening and remembers its encoding for the saves to come.
+pub(crate) fn open_note_text(path: &Path) -> io::Result<String> {
+    let (text, encoding) = read_note_text(path)?;
+    remember_note_encoding(path, encoding);
+    Ok(text)
+}
+
+/// A note opened as Shift-JIS or UTF-16 is written back the same way, so other tools keep
+/// reading it. Text the encoding cannot hold is written as UTF-8 rather than lose
+/// characters; the note stays UTF-8 from then on and the UI is told.
+pub(crate) fn encode_for_save(path: &Path, text: &str) -> Vec<u8> {
+    let encoding = note_encoding(path);
+    if encoding == NoteEncoding::Utf8 {
+        return text.as_bytes().to_vec();
+    }
+    encode_note_text(text, encoding).unwrap_or_else(|| {
+        trace_debug(format!(
+            "req-enc2 save falls back to UTF-8 path={} encoding={}",
+            path.display(),
+            encoding.label()
+        ));
+        let mut encodings = note_encodings();
+        encodings.by_path.remove(path);
+        encodings.fallbacks.push((path.to_path_buf(), encoding));
+        text.as_bytes().to_vec()
+    })
+}
+
+/// Notes saved as UTF-8 since the last call because their encoding could not hold the text.
+pub(crate) fn take_encoding_fallbacks() -> Vec<(PathBuf, NoteEncoding)> {
+    std::mem::take(&mut note_encodings().fallbacks)
+}
+
+impl crate::app::Papyru2App {
+    /// Rewrites a Shift-JIS or UTF-16 note as UTF-8; later saves keep it UTF-8.
+    pub(crate) fn convert_note_to_utf8(
+        &mut self,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let is_open = self.file_workflow.current_edit_path().as_ref() == Some(&path);
+        if is_open && !self.flush_editor_content_before_context_switch("req-enc2-convert", cx) {
+            self.show_transient_notice(tr("notice.conversion_needs_save").to_string(), cx);
+            return;
+        }
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let (text, encoding) = match read_note_text(path.as_path()) {
+            Ok(read) => read,
+            Err(error) => {
+                self.show_transient_notice(
+                    tr_with(
+                        "notice.cannot_convert",
+                        &[
+                            ("name", name.as_str()),
+                            ("reason", error.to_string().as_str()),
+                        ],
+                    ),
+                    cx,
+                );
+                return;
+            }
+        };
+        if encoding == NoteEncoding::Utf8 {
+            self.show_transient_notice(
+                tr_with("notice.already_utf8", &[("name", name.as_str())]),
+                cx,
+            );
+            return;
+        }
+        self.snapshot_then(
+            "convert to UTF-8",
+            vec![path.clone()],
+            window,
+            cx,
+            move |app, window, cx| app.write_note_as_utf8(path, text, encoding, window, cx),
+        );
+    }
+
+    /// Tells the user about notes a save had to switch to UTF-8.
+    pub(crate) fn show_encoding_fallbacks(&mut self, cx: &mut Context<Self>) {
+        for (path, encoding) in take_encoding_fallbacks() {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            self.show_toast(
+                crate::toasts::ToastSeverity::Warning,
+                tr_with(
+                    "notice.saved_as_utf8",
+                    &[("name", name.as_str()), ("encoding", encoding.label())],
+                ),
+                cx,
+            );
+        }
+    }
+
+    fn write_note_as_utf8(
+        &mut self,
+        path: PathBuf,
+        text: String,
+        encoding: NoteEncoding,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let is_open = self.file_workflow.current_edit_path().as_ref() == Some(&path);
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let written =
+            crate::storage_backend::storage_backend_for(self.app_paths.user_document_dir.as_path())
+                .write(path.as_path(), text.as_bytes());
+        trace_debug(format!(
+            "req-enc2 convert path={} from={} ok={}",
+            path.display(),
+            encoding.label(),
+            written.is_ok()
+        ));
+        match written {
+            Ok(()) => {
+                remember_note_encoding(path.as_path(), NoteEncoding::Utf8);
+                if is_open {
+                    self.check_current_file_external_change(window, cx);
+                }
+                self.show_transient_notice(
+                    tr_with(
+                        "notice.converted_to_utf8",
+                        &[("name", name.as_str()), ("encoding", encoding.label())],
+                    ),
+                    cx,
+                );
+            }
+            Err(error) => {
+                self.show_transient_notice(
+                    tr_with(
+                        "notice.cannot_convert",
+                        &[
+                            ("name", name.as_str()),
+                            ("reason", error.to_string().as_str()),
+                        ],
+                    ),
+                    cx,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enc_test1_text_is_transcoded_and_binary_is_refused() {
+        assert_eq!(
+            decode_note_bytes("plain ノート".as_bytes()).unwrap(),
+            ("plain ノート".to_string(), NoteEncoding::Utf8)
+        );
+
+        let (sjis, _, _) = SHIFT_JIS.encode("日本語のメモ\r\n");
+        assert_eq!(
+            decode_note_bytes(&sjis).unwrap(),
+            ("日本語のメモ\r\n".to_string(), NoteEncoding::ShiftJis)
+        );
+
+        let mut utf16 = vec![0xFF, 0xFE];
+        for unit in "memo ✓".encode_utf16() {
+            utf16.extend_from_slice(&unit.to_le_bytes());
+        }
+        assert_eq!(
+            decode_note_bytes(&utf16).unwrap(),
+            ("memo ✓".to_string(), NoteEncoding::Utf16LeBom)
+        );
+
+        assert!(matches!(
+            decode_note_bytes(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            Err(NoteDecodeError::Binary)
+        ));
+        assert!(matches!(
+            decode_note_bytes(&[0x80, 0x81, 0xfd, 0xfe]),
+            Err(NoteDecodeError::Binary)
+        ));
+    }
+
+    #[test]
+    fn enc_test2_saves_keep_the_encoding_the_note_was_opened_in() {
+        let dir = std::env::temp_dir().join(format!("papyru2_enc_test2_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let sjis_path = dir.join("memo.txt");
+        fs::write(&sjis_path, SHIFT_JIS.encode("古いメモ").0).unwrap();
+        assert_eq!(open_note_text(&sjis_path).unwrap(), "古いメモ");
+        // Saves go by what was recorded at open, not by the bytes on disk now.
+        fs::write(&sjis_path, "overwritten elsewhere").unwrap();
+        let bytes = encode_for_save(&sjis_path, "新しいメモ");
+        assert_eq!(
+            decode_note_bytes(&bytes).unwrap(),
+            ("新しいメモ".to_string(), NoteEncoding::ShiftJis)
+        );
+        // An emoji has no Shift-JIS form; the save keeps it by switching to UTF-8 and says so.
+        assert_eq!(encode_for_save(&sjis_path, "メモ 🙂"), "メモ 🙂".as_bytes());
+        assert_eq!(note_encoding(&sjis_path), NoteEncoding::Utf8);
+        assert!(take_encoding_fallbacks().contains(&(sjis_path.clone(), NoteEncoding::ShiftJis)));
+
+        let utf16_path = dir.join("wide.txt");
+        fs::write(
+            &utf16_path,
+            encode_note_text("old", NoteEncoding::Utf16BeBom).unwrap(),
+        )
+        .unwrap();
+        open_note_text(&utf16_path).unwrap();
+        let renamed = dir.join("sub").join("wide_renamed.txt");
+        rebase_note_encodings(&utf16_path, &renamed);
+        assert_eq!(note_encoding(&utf16_path), NoteEncoding::Utf8);
+        assert_eq!(
+            decode_note_bytes(&encode_for_save(&renamed, "new")).unwrap(),
+            ("new".to_string(), NoteEncoding::Utf16BeBom)
+        );
+
+        assert_eq!(encode_for_save(&dir.join("missing.md"), "fresh"), b"fresh");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
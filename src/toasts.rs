@@ -0,0 +1,301 @@
+use std::{collections::VecDeque, time::Duration};
+
+use chrono::{DateTime, Local};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex, v_flex,
+};
+
+use crate::log::trace_debug;
+
+// Older entries fall out of the drawer; it is a session log, not an archive.
+const TOAST_HISTORY_LIMIT: usize = 100;
+// More than this on screen at once and the oldest goes first.
+const TOAST_VISIBLE_LIMIT: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ToastSeverity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastSeverity {
+    /// Errors stay up longer so there is time to read what went wrong.
+    pub fn dismiss_after(self) -> Duration {
+        match self {
+            Self::Info | Self::Success => Duration::from_secs(4),
+            Self::Warning => Duration::from_secs(6),
+            Self::Error => Duration::from_secs(10),
+        }
+    }
+
+    fn label(self) -> SharedString {
+        crate::i18n::tr(match self {
+            Self::Info => "severity.info",
+            Self::Success => "severity.success",
+            Self::Warning => "severity.warning",
+            Self::Error => "severity.error",
+        })
+    }
+
+    fn color(self, cx: &App) -> Hsla {
+        match self {
+            Self::Info => cx.theme().muted_foreground,
+            Self::Success => cx.theme().success,
+            Self::Warning => cx.theme().warning,
+            Self::Error => cx.theme().danger,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Toast {
+    pub id: u64,
+    pub severity: ToastSeverity,
+    pub message: SharedString,
+    pub at: DateTime<Local>,
+}
+
+/// The toasts on screen and every one shown this session, newest last.
+#[derive(Debug, Default)]
+pub(crate) struct ToastCenter {
+    visible: Vec<Toast>,
+    history: VecDeque<Toast>,
+    next_id: u64,
+    pub drawer_open: bool,
+}
+
+impl ToastCenter {
+    pub fn push(
+        &mut self,
+        severity: ToastSeverity,
+        message: impl Into<SharedString>,
+        at: DateTime<Local>,
+    ) -> u64 {
+        self.next_id += 1;
+        let toast = Toast {
+            id: self.next_id,
+            severity,
+            message: message.into(),
+            at,
+        };
+        if self.history.len() == TOAST_HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+        self.history.push_back(toast.clone());
+        self.visible.push(toast);
+        if self.visible.len() > TOAST_VISIBLE_LIMIT {
+            self.visible.remove(0);
+        }
+        self.next_id
+    }
+
+    /// `false` when it was already gone.
+    pub fn dismiss(&mut self, id: u64) -> bool {
+        let before = self.visible.len();
+        self.visible.retain(|toast| toast.id != id);
+        self.visible.len() != before
+    }
+
+    pub fn visible(&self) -> &[Toast] {
+        &self.visible
+    }
+
+    pub fn history(&self) -> impl DoubleEndedIterator<Item = &Toast> {
+        self.history.iter()
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn show_toast(
+        &mut self,
+        severity: ToastSeverity,
+        message: String,
+        cx: &mut Context<Self>,
+    ) {
+        trace_debug(format!(
+            "toast show severity={severity:?} message='{}'",
+            crate::app::compact_text(&message)
+        ));
+        let id = self.toasts.push(severity, message, Local::now());
+        cx.notify();
+        cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(severity.dismiss_after())
+                .await;
+            let _ = this.update(cx, |app, cx| {
+                if app.toasts.dismiss(id) {
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    pub(crate) fn toggle_toast_history(&mut self, cx: &mut Context<Self>) {
+        self.toasts.drawer_open = !self.toasts.drawer_open;
+        cx.notify();
+    }
+
+    pub(crate) fn render_toasts(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let visible = self.toasts.visible();
+        if visible.is_empty() {
+            return None;
+        }
+        let rows = visible.iter().map(|toast| {
+            let id = toast.id;
+            h_flex()
+                .w_full()
+                .gap_2()
+                .px_2()
+                .items_center()
+                .text_xs()
+                .rounded_sm()
+                .border_l_2()
+                .border_color(toast.severity.color(cx))
+                .child(div().flex_1().min_w_0().child(toast.message.clone()))
+                .child(
+                    Button::new(("toast-dismiss", id))
+                        .xsmall()
+                        .ghost()
+                        .label("\u{00d7}")
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            if this.toasts.dismiss(id) {
+                                cx.notify();
+                            }
+                        })),
+                )
+        });
+        Some(v_flex().w_full().gap_1().children(rows).into_any_element())
+    }
+
+    pub(crate) fn render_toast_history_button(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let count = self.toasts.history().count();
+        (count > 0).then(|| {
+            Button::new("toast-history")
+                .xsmall()
+                .ghost()
+                .label(crate::i18n::tr_with(
+                    "toast.notifications",
+                    &[("count", count.to_string().as_str())],
+                ))
+                .on_click(cx.listener(|this, _, _, cx| this.toggle_toast_history(cx)))
+                .into_any_element()
+        })
+    }
+
+    /// The session's notifications, newest first.
+    pub(crate) fn render_toast_history(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        if !self.toasts.drawer_open {
+            return None;
+        }
+        let rows = self.toasts.history().rev().map(|toast| {
+            h_flex()
+                .w_full()
+                .gap_2()
+                .text_xs()
+                .child(
+                    div()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(toast.at.format("%H:%M:%S").to_string()),
+                )
+                .child(
+                    div()
+                        .text_color(toast.severity.color(cx))
+                        .child(toast.severity.label()),
+                )
+                .child(div().flex_1().min_w_0().child(toast.message.clone()))
+        });
+        let empty = self.toasts.history().next().is_none();
+        Some(
+            v_flex()
+                .id("toast-history-drawer")
+                .w_full()
+                .max_h(px(240.))
+                .overflow_y_scroll()
+                .gap_1()
+                .p_2()
+                .border_t_1()
+                .border_color(cx.theme().border)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_2()
+                        .items_center()
+                        .text_xs()
+                        .child(
+                            div()
+                                .flex_1()
+                                .child(crate::i18n::tr("toast.notifications_title")),
+                        )
+                        .child(
+                            Button::new("toast-history-clear")
+                                .xsmall()
+                                .ghost()
+                                .label(crate::i18n::tr("button.clear"))
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.toasts.clear_history();
+                                    this.toasts.drawer_open = false;
+                                    cx.notify();
+                                })),
+                        )
+                        .child(
+                            Button::new("toast-history-close")
+                                .xsmall()
+                                .ghost()
+                                .label(crate::i18n::tr("button.close"))
+                                .on_click(
+                                    cx.listener(|this, _, _, cx| this.toggle_toast_history(cx)),
+                                ),
+                        ),
+                )
+                .when(empty, |drawer| {
+                    drawer.child(div().text_xs().child(crate::i18n::tr("toast.nothing_yet")))
+                })
+                .children(rows)
+                .into_any_element(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toast_test1_toasts_dismiss_by_id_and_stay_in_history() {
+        let now = Local::now();
+        let mut center = ToastCenter::default();
+        let first = center.push(ToastSeverity::Success, "Created a.md", now);
+        let second = center.push(ToastSeverity::Error, "Save failed", now);
+        assert_eq!(center.visible().len(), 2);
+
+        assert!(center.dismiss(first));
+        assert!(!center.dismiss(first));
+        assert_eq!(center.visible()[0].id, second);
+        assert_eq!(center.history().count(), 2);
+
+        for index in 0..TOAST_HISTORY_LIMIT {
+            center.push(ToastSeverity::Info, format!("note {index}"), now);
+        }
+        assert_eq!(center.visible().len(), TOAST_VISIBLE_LIMIT);
+        assert_eq!(center.history().count(), TOAST_HISTORY_LIMIT);
+        assert_eq!(
+            center.history().next().map(|toast| toast.message.as_ref()),
+            Some("note 0")
+        );
+
+        center.clear_history();
+        assert_eq!(center.history().count(), 0);
+        assert_eq!(center.visible().len(), TOAST_VISIBLE_LIMIT);
+    }
+}
@@ -1,6 +1,8 @@
+use std::time::{Duration, Instant};
+
 use gpui::*;
 use gpui_component::{
-    IconNamed, Sizable,
+    IconName, IconNamed, Sizable,
     button::{Button, ButtonVariants as _},
     h_flex,
     resizable::{ResizableState, h_resizable, resizable_panel},
@@ -24,6 +26,12 @@ pub(crate) const TOP_BARS_BUTTON_ORDER: [TopBarButtonSpec; 2] = [
     TopBarButtonSpec::PlusResetToNeutral,
 ];
 
+pub(crate) const PLUS_LONG_PRESS_THRESHOLD: Duration = Duration::from_millis(500);
+
+pub(crate) fn is_plus_long_press(held: Duration) -> bool {
+    held >= PLUS_LONG_PRESS_THRESHOLD
+}
+
 pub(crate) const FOLDER_REFRESH_ICON_PATH: &str = "icons/folder-refresh.svg";
 
 const FOLDER_REFRESH_ICON_SVG: &[u8] = br#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M18 14.5C19.11 14.5 20.11 14.95 20.83 15.67L22 14.5V18.5H18L19.77 16.73C19.32 16.28 18.69 16 18 16C16.62 16 15.5 17.12 15.5 18.5C15.5 19.88 16.62 21 18 21C18.82 21 19.54 20.61 20 20H21.71C21.12 21.47 19.68 22.5 18 22.5C15.79 22.5 14 20.71 14 18.5C14 16.29 15.79 14.5 18 14.5M20 8H4V18H12L12 18.5C12 19 12.06 19.5 12.17 20H4C2.89 20 2 19.1 2 18L2 6C2 4.89 2.89 4 4 4H10L12 6H20C21.1 6 22 6.89 22 8V13C21.39 12.63 20.72 12.34 20 12.17V8Z" /></svg>"#;
@@ -56,6 +64,9 @@ pub(crate) fn list_top_bars_icon_assets(path: &str, assets: &mut Vec<SharedStrin
 pub enum TopBarsEvent {
     PressFolderRefresh,
     PressPlus,
+    LongPressPlus,
+    PressSidebarToggle,
+    PressVaultSwitcher,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -77,6 +88,9 @@ pub struct TopBars {
     singleline: Entity<SingleLineInput>,
     layout_split_state: Entity<ResizableState>,
     left_panel_size: Pixels,
+    active_vault_name: SharedString,
+    file_tree_collapsed: bool,
+    plus_pressed_at: Option<Instant>,
 }
 
 impl EventEmitter<TopBarsEvent> for TopBars {}
@@ -94,6 +108,9 @@ impl TopBars {
             singleline,
             layout_split_state,
             left_panel_size,
+            active_vault_name: SharedString::default(),
+            file_tree_collapsed: false,
+            plus_pressed_at: None,
         }
     }
 
@@ -110,14 +127,66 @@ impl TopBars {
         self.left_panel_size = left_panel_size;
     }
 
-    fn render_plus_button(&self, cx: &mut Context<Self>) -> impl IntoElement {
-        Button::new("round-button1")
+    pub fn set_active_vault_name(&mut self, name: impl Into<SharedString>, cx: &mut Context<Self>) {
+        self.active_vault_name = name.into();
+        cx.notify();
+    }
+
+    pub fn set_file_tree_collapsed(&mut self, collapsed: bool, cx: &mut Context<Self>) {
+        self.file_tree_collapsed = collapsed;
+        cx.notify();
+    }
+
+    fn render_sidebar_toggle_button(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        Button::new("sidebar-toggle")
             .ghost()
-            .large()
-            .icon(TopBarsIconName::PlusThin)
+            .small()
+            .icon(if self.file_tree_collapsed {
+                IconName::PanelLeftOpen
+            } else {
+                IconName::PanelLeftClose
+            })
+            .tooltip("Toggle file tree (Ctrl+\\)")
             .on_click(cx.listener(|_, _, _, cx| {
-                cx.emit(TopBarsEvent::PressPlus);
+                cx.emit(TopBarsEvent::PressSidebarToggle);
+            }))
+    }
+
+    fn render_vault_switcher_button(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        Button::new("vault-switcher")
+            .ghost()
+            .small()
+            .label(self.active_vault_name.clone())
+            .on_click(cx.listener(|_, _, _, cx| {
+                cx.emit(TopBarsEvent::PressVaultSwitcher);
+            }))
+    }
+
+    // Holding the button past PLUS_LONG_PRESS_THRESHOLD picks a template instead; the
+    // press time is captured before the button sees the mouse down.
+    fn render_plus_button(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .capture_any_mouse_down(cx.listener(|this, _: &MouseDownEvent, _, _| {
+                this.plus_pressed_at = Some(Instant::now());
             }))
+            .child(
+                Button::new("round-button1")
+                    .ghost()
+                    .large()
+                    .icon(TopBarsIconName::PlusThin)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        let held = this
+                            .plus_pressed_at
+                            .take()
+                            .map(|pressed_at| pressed_at.elapsed())
+                            .unwrap_or_default();
+                        if is_plus_long_press(held) {
+                            cx.emit(TopBarsEvent::LongPressPlus);
+                        } else {
+                            cx.emit(TopBarsEvent::PressPlus);
+                        }
+                    })),
+            )
     }
 
     fn render_refresh_button(&self, cx: &mut Context<Self>) -> impl IntoElement {
@@ -139,6 +208,9 @@ impl TopBars {
         } else {
             button_group.justify_start()
         };
+        let button_group = button_group
+            .child(self.render_sidebar_toggle_button(cx))
+            .child(self.render_vault_switcher_button(cx));
 
         match TOP_BARS_BUTTON_ORDER {
             [
@@ -0,0 +1,103 @@
+use std::{fs, io, path::Path};
+
+use gpui::*;
+use serde::{Deserialize, Serialize};
+
+use crate::log::trace_debug;
+
+pub(crate) const UI_STATE_FILE_NAME: &str = "ui_state.toml";
+
+/// Panel sizes kept between launches; the window's own bounds live in `window_position.toml`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct UiState {
+    /// Width of the file tree column, which the top bar split follows.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_tree_width: Option<f32>,
+    /// Width of the main editor while a second note is open beside it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editor_split_width: Option<f32>,
+    /// The file tree was folded away with the sidebar toggle.
+    #[serde(default)]
+    pub file_tree_collapsed: bool,
+}
+
+fn valid_panel_size(size: Option<f32>) -> Option<f32> {
+    size.filter(|size| size.is_finite() && *size > 0.0)
+}
+
+impl UiState {
+    pub fn file_tree_width(&self) -> Option<f32> {
+        valid_panel_size(self.file_tree_width)
+    }
+
+    pub fn editor_split_width(&self) -> Option<Pixels> {
+        valid_panel_size(self.editor_split_width).map(px)
+    }
+}
+
+/// A missing or unreadable file starts from the default layout.
+pub(crate) fn load_ui_state(path: &Path) -> UiState {
+    let Ok(raw) = fs::read_to_string(path) else {
+        return UiState::default();
+    };
+    toml::from_str::<UiState>(&raw)
+        .inspect(|state| trace_debug(format!("req-ui1 ui_state restore {state:?}")))
+        .unwrap_or_else(|error| {
+            trace_debug(format!(
+                "req-ui1 ui_state parse failed path={} error={error}",
+                path.display()
+            ));
+            UiState::default()
+        })
+}
+
+pub(crate) fn save_ui_state_atomic(path: &Path, state: &UiState) -> io::Result<()> {
+    let serialized = toml::to_string_pretty(state)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+    crate::atomic_io::write_atomic(path, serialized.as_bytes())
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn persist_ui_state(&self) {
+        let path = self.app_paths.config_file_path(UI_STATE_FILE_NAME);
+        if let Err(error) = save_ui_state_atomic(path.as_path(), &self.ui_state) {
+            trace_debug(format!(
+                "req-ui1 ui_state save failed path={} error={error}",
+                path.display()
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ui_test1_panel_sizes_round_trip_and_bad_values_are_ignored() {
+        let dir = std::env::temp_dir().join(format!("papyru2_ui_test1_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join(UI_STATE_FILE_NAME);
+        assert_eq!(load_ui_state(&path), UiState::default());
+
+        let state = UiState {
+            file_tree_width: Some(280.0),
+            editor_split_width: Some(640.0),
+            file_tree_collapsed: true,
+        };
+        save_ui_state_atomic(&path, &state).unwrap();
+        let loaded = load_ui_state(&path);
+        assert_eq!(loaded, state);
+        assert_eq!(loaded.file_tree_width(), Some(280.0));
+        assert_eq!(loaded.editor_split_width(), Some(px(640.0)));
+
+        fs::write(&path, "file_tree_width = -5.0\n").unwrap();
+        let loaded = load_ui_state(&path);
+        assert_eq!(loaded.file_tree_width(), None);
+        assert_eq!(loaded.editor_split_width(), None);
+
+        fs::write(&path, "file_tree_width = \"wide\"\n").unwrap();
+        assert_eq!(load_ui_state(&path), UiState::default());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
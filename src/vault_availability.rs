@@ -0,0 +1,287 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use gpui::*;
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+};
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+
+pub(crate) const VAULT_AVAILABILITY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The notes root is usable when it still resolves to a readable directory; an unplugged
+/// drive or a dropped network share fails one of the two.
+pub(crate) fn is_vault_available(user_document_dir: &Path) -> bool {
+    fs::metadata(user_document_dir).is_ok_and(|metadata| metadata.is_dir())
+        && fs::read_dir(user_document_dir).is_ok()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum VaultTransition {
+    Disconnected,
+    Reconnected,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct VaultAvailability {
+    disconnected_since: Option<DateTime<Local>>,
+}
+
+impl VaultAvailability {
+    pub fn is_disconnected(&self) -> bool {
+        self.disconnected_since.is_some()
+    }
+
+    pub fn disconnected_since(&self) -> Option<DateTime<Local>> {
+        self.disconnected_since
+    }
+
+    pub fn observe(&mut self, available: bool, now: DateTime<Local>) -> Option<VaultTransition> {
+        match (available, self.disconnected_since.is_some()) {
+            (false, false) => {
+                self.disconnected_since = Some(now);
+                Some(VaultTransition::Disconnected)
+            }
+            (true, true) => {
+                self.disconnected_since = None;
+                Some(VaultTransition::Reconnected)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Polls the active notes root on its own thread and reports every availability change
+/// together with the root it was observed on. The thread ends once the receiver is dropped.
+pub(crate) fn spawn_vault_availability_monitor(
+    active_vault_root: crate::vaults::ActiveVaultRoot,
+) -> smol::channel::Receiver<(PathBuf, bool)> {
+    let (tx, rx) = smol::channel::unbounded();
+    thread::spawn(move || {
+        let mut watched_root = active_vault_root.get();
+        let mut last = true;
+        loop {
+            thread::sleep(VAULT_AVAILABILITY_POLL_INTERVAL);
+            let user_document_dir = active_vault_root.get();
+            if user_document_dir != watched_root {
+                watched_root = user_document_dir.clone();
+                last = true;
+            }
+            let available = is_vault_available(user_document_dir.as_path());
+            if available == last {
+                continue;
+            }
+            last = available;
+            trace_debug(format!(
+                "req-vol1 vault availability changed available={available} root={}",
+                user_document_dir.display()
+            ));
+            if tx.send_blocking((user_document_dir, available)).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn apply_vault_availability(
+        &mut self,
+        root: &Path,
+        available: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // A report about the vault that was just switched away from.
+        if root != self.app_paths.user_document_dir {
+            return;
+        }
+        match self.vault_availability.observe(available, Local::now()) {
+            None => {}
+            Some(VaultTransition::Disconnected) => {
+                // Edits keep accumulating in the autosave slot and its journal (which lives
+                // under the app data dir) until the root is back.
+                self.editor_autosave.set_paused(true);
+                trace_debug(format!(
+                    "req-vol1 vault disconnected root={}",
+                    self.app_paths.user_document_dir.display()
+                ));
+            }
+            Some(VaultTransition::Reconnected) => {
+                self.editor_autosave.set_paused(false);
+                self.restart_file_tree_watcher(window, cx);
+                self.apply_file_tree_watcher_refresh(cx);
+                self.check_current_file_external_change(window, cx);
+                trace_debug(format!(
+                    "req-vol1 vault reconnected root={}",
+                    self.app_paths.user_document_dir.display()
+                ));
+                self.show_transient_notice(tr("notice.vault_available_again").to_string(), cx);
+            }
+        }
+        cx.notify();
+    }
+
+    fn retry_vault_availability(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let root = self.app_paths.user_document_dir.clone();
+        let available = is_vault_available(root.as_path());
+        trace_debug(format!("req-vol1 vault retry available={available}"));
+        self.apply_vault_availability(root.as_path(), available, window, cx);
+        if !available {
+            self.show_transient_notice(tr("notice.vault_still_unavailable").to_string(), cx);
+        }
+    }
+
+    // Lets the user keep the open note somewhere reachable while the notes folder is gone.
+    fn save_current_note_elsewhere(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let file_name = self
+            .file_workflow
+            .current_edit_path()
+            .and_then(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| format!("papyru2_{}.txt", Local::now().format("%Y%m%d_%H%M%S")));
+        let directory = self.app_paths.app_home.clone();
+        let answer = cx.prompt_for_new_path(directory.as_path(), Some(&file_name));
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(target))) = answer.await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, _, cx| {
+                let text = app.editor.read(cx).snapshot(cx).value;
+                let message =
+                    match crate::atomic_io::write_atomic(target.as_path(), text.as_bytes()) {
+                        Ok(()) => tr_with(
+                            "notice.saved_copy_to",
+                            &[("path", target.display().to_string().as_str())],
+                        ),
+                        Err(error) => tr_with(
+                            "notice.could_not_save_note",
+                            &[("reason", error.to_string().as_str())],
+                        ),
+                    };
+                trace_debug(format!(
+                    "req-vol1 save elsewhere path={} message='{message}'",
+                    target.display()
+                ));
+                app.show_transient_notice(message, cx);
+            });
+        })
+        .detach();
+    }
+
+    pub(crate) fn render_vault_disconnected_banner(
+        &self,
+        cx: &mut Context<Self>,
+    ) -> Option<AnyElement> {
+        let since = self.vault_availability.disconnected_since()?;
+        let message = tr_with(
+            "label.vault_disconnected",
+            &[
+                (
+                    "path",
+                    self.app_paths
+                        .user_document_dir
+                        .display()
+                        .to_string()
+                        .as_str(),
+                ),
+                ("since", since.format("%H:%M:%S").to_string().as_str()),
+            ],
+        );
+        Some(
+            h_flex()
+                .w_full()
+                .gap_2()
+                .px_2()
+                .py_1()
+                .text_xs()
+                .bg(cx.theme().danger)
+                .text_color(cx.theme().danger_foreground)
+                .child(div().flex_1().child(message))
+                .child(
+                    Button::new("vault-retry")
+                        .small()
+                        .label(crate::i18n::tr("button.retry"))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.retry_vault_availability(window, cx)
+                        })),
+                )
+                .child(
+                    Button::new("vault-switch")
+                        .small()
+                        .ghost()
+                        .label(tr("button.switch_vault"))
+                        .on_click(
+                            cx.listener(|this, _, window, cx| this.show_vault_switcher(window, cx)),
+                        ),
+                )
+                .child(
+                    Button::new("vault-save-elsewhere")
+                        .small()
+                        .ghost()
+                        .label(tr("button.save_note_elsewhere"))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.save_current_note_elsewhere(window, cx)
+                        })),
+                )
+                .into_any_element(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn vol_test1_availability_reports_each_transition_once() {
+        let mut availability = VaultAvailability::default();
+        let now = Local::now();
+        assert_eq!(availability.observe(true, now), None);
+        assert_eq!(
+            availability.observe(false, now),
+            Some(VaultTransition::Disconnected)
+        );
+        assert_eq!(availability.observe(false, now), None);
+        assert!(availability.is_disconnected());
+        assert_eq!(
+            availability.observe(true, now),
+            Some(VaultTransition::Reconnected)
+        );
+        assert!(!availability.is_disconnected());
+    }
+
+    #[test]
+    fn vol_test2_missing_or_file_root_is_unavailable() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_vault_availability_vol_test2_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&root).expect("create root");
+        assert!(is_vault_available(root.as_path()));
+
+        let file_root = root.join("not_a_dir.txt");
+        fs::write(&file_root, "x").expect("write file");
+        assert!(!is_vault_available(file_root.as_path()));
+
+        fs::remove_dir_all(&root).expect("remove root");
+        assert!(!is_vault_available(root.as_path()));
+    }
+}
@@ -0,0 +1,711 @@
+use std::{
+    fs, io,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use gpui::*;
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+use crate::settings_archive::{archive_entry_name, collect_files, is_machine_local_conf_file};
+use crate::workflow_error::{WorkflowError, WorkflowResult, at_path};
+
+pub(crate) const VAULT_BACKUP_MANIFEST_NAME: &str = "papyru2_backup.toml";
+pub(crate) const VAULT_BACKUP_FORMAT_VERSION: u32 = 1;
+const VAULT_BACKUP_NOTES_PREFIX: &str = "notes";
+const VAULT_BACKUP_CONF_PREFIX: &str = "conf";
+const VAULT_BACKUP_FILE_PREFIX: &str = "papyru2_backup_";
+const VAULT_BACKUP_DEFAULT_DIR_NAME: &str = "vault_backups";
+const VAULT_BACKUP_DEFAULT_KEEP: usize = 10;
+// How often a scheduled backup checks whether the newest archive has grown old enough.
+const VAULT_BACKUP_SCHEDULE_CHECK: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct VaultBackupConfigFile {
+    #[serde(default)]
+    backup: VaultBackupConfigSection,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+struct VaultBackupConfigSection {
+    archive_dir: Option<PathBuf>,
+    archive_interval_hours: Option<u64>,
+    archive_keep: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct VaultBackupConfig {
+    pub archive_dir: PathBuf,
+    /// None: archives are only written on demand.
+    pub interval: Option<Duration>,
+    pub keep: usize,
+}
+
+/// `[backup] archive_dir` defaults to `data/vault_backups`; `archive_interval_hours` turns on
+/// scheduled archives and `archive_keep` (default 10) caps how many are kept.
+pub(crate) fn load_vault_backup_config(path: &Path, data_dir: &Path) -> VaultBackupConfig {
+    let section = fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| match toml::from_str::<VaultBackupConfigFile>(&raw) {
+            Ok(parsed) => Some(parsed.backup),
+            Err(error) => {
+                trace_debug(format!(
+                    "req-vbk1 config parse failed path={} error={error}",
+                    path.display()
+                ));
+                None
+            }
+        })
+        .unwrap_or_default();
+    VaultBackupConfig {
+        archive_dir: section
+            .archive_dir
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or_else(|| data_dir.join(VAULT_BACKUP_DEFAULT_DIR_NAME)),
+        interval: section
+            .archive_interval_hours
+            .filter(|hours| *hours > 0)
+            .map(|hours| Duration::from_secs(hours * 60 * 60)),
+        keep: section
+            .archive_keep
+            .unwrap_or(VAULT_BACKUP_DEFAULT_KEEP)
+            .max(1),
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct VaultBackupManifest {
+    format: u32,
+    vault: String,
+    created_at: String,
+    files: usize,
+}
+
+pub(crate) fn vault_backup_archive_name(now: DateTime<Local>) -> String {
+    format!(
+        "{VAULT_BACKUP_FILE_PREFIX}{}.zip",
+        now.format("%Y%m%d_%H%M%S")
+    )
+}
+
+/// When the archive at `path` was taken, read back from its name.
+pub(crate) fn vault_backup_taken_at(path: &Path) -> Option<DateTime<Local>> {
+    let stamp = path
+        .file_stem()?
+        .to_str()?
+        .strip_prefix(VAULT_BACKUP_FILE_PREFIX)?;
+    let naive = NaiveDateTime::parse_from_str(stamp, "%Y%m%d_%H%M%S").ok()?;
+    Local.from_local_datetime(&naive).earliest()
+}
+
+/// A scheduled backup is due once the newest archive is `interval` old, or when there is
+/// none at all; sessions shorter than the interval still get one.
+pub(crate) fn vault_backup_due(
+    latest: Option<DateTime<Local>>,
+    interval: Duration,
+    now: DateTime<Local>,
+) -> bool {
+    latest.is_none_or(|latest| {
+        now.signed_duration_since(latest)
+            .to_std()
+            .is_ok_and(|age| age >= interval)
+    })
+}
+
+// Notes (minus the recycle bin) and portable conf files; window position and the vault
+// list describe this machine, not the vault.
+fn collect_vault_backup_files(
+    user_document_dir: &Path,
+    conf_dir: &Path,
+) -> io::Result<Vec<(String, PathBuf)>> {
+    let mut result = Vec::new();
+    let mut note_files = Vec::new();
+    collect_files(user_document_dir, Path::new(""), &mut note_files)?;
+    for relative in note_files {
+        if relative.starts_with("recyclebin") {
+            continue;
+        }
+        result.push((
+            archive_entry_name(VAULT_BACKUP_NOTES_PREFIX, relative.as_path()),
+            user_document_dir.join(&relative),
+        ));
+    }
+    let mut conf_files = Vec::new();
+    collect_files(conf_dir, Path::new(""), &mut conf_files)?;
+    for relative in conf_files {
+        if is_machine_local_conf_file(relative.as_path()) {
+            continue;
+        }
+        result.push((
+            archive_entry_name(VAULT_BACKUP_CONF_PREFIX, relative.as_path()),
+            conf_dir.join(&relative),
+        ));
+    }
+    Ok(result)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VaultBackupReport {
+    pub archive: PathBuf,
+    pub files: usize,
+}
+
+/// Zips the vault and conf into `archive_dir` under a timestamped name, then drops the
+/// oldest archives beyond `keep`. The archive appears under its final name only once complete.
+pub(crate) fn write_vault_backup(
+    user_document_dir: &Path,
+    conf_dir: &Path,
+    archive_dir: &Path,
+    keep: usize,
+    now: DateTime<Local>,
+) -> io::Result<VaultBackupReport> {
+    let files = collect_vault_backup_files(user_document_dir, conf_dir)
+        .map_err(at_path(user_document_dir))?;
+    let manifest = VaultBackupManifest {
+        format: VAULT_BACKUP_FORMAT_VERSION,
+        vault: user_document_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        created_at: now.to_rfc3339(),
+        files: files.len(),
+    };
+    let manifest_text = toml::to_string(&manifest)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+
+    fs::create_dir_all(archive_dir)?;
+    let archive = archive_dir.join(vault_backup_archive_name(now));
+    let partial = archive.with_extension("zip.partial");
+    let written = (|| -> io::Result<()> {
+        let mut zip = ZipWriter::new(fs::File::create(&partial)?);
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        zip.start_file(VAULT_BACKUP_MANIFEST_NAME, options)
+            .map_err(io::Error::other)?;
+        zip.write_all(manifest_text.as_bytes())?;
+        for (entry_name, source) in &files {
+            let bytes = fs::read(source).map_err(at_path(source))?;
+            zip.start_file(entry_name.as_str(), options)
+                .map_err(io::Error::other)?;
+            zip.write_all(&bytes)?;
+        }
+        zip.finish().map_err(io::Error::other)?.sync_all()
+    })();
+    if let Err(error) = written.and_then(|_| fs::rename(&partial, &archive)) {
+        let _ = fs::remove_file(&partial);
+        return Err(error);
+    }
+    trace_debug(format!(
+        "req-vbk1 backup written path={} files={}",
+        archive.display(),
+        files.len()
+    ));
+    prune_vault_backups(archive_dir, keep);
+    Ok(VaultBackupReport {
+        archive,
+        files: files.len(),
+    })
+}
+
+/// Archives in `archive_dir`, oldest first; the timestamp in the name sorts them.
+pub(crate) fn list_vault_backups(archive_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(archive_dir) else {
+        return Vec::new();
+    };
+    let mut archives = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().is_some_and(|extension| extension == "zip")
+                && path.file_name().is_some_and(|name| {
+                    name.to_string_lossy().starts_with(VAULT_BACKUP_FILE_PREFIX)
+                })
+        })
+        .collect::<Vec<_>>();
+    archives.sort();
+    archives
+}
+
+fn prune_vault_backups(archive_dir: &Path, keep: usize) {
+    let archives = list_vault_backups(archive_dir);
+    let excess = archives.len().saturating_sub(keep);
+    for old in archives.into_iter().take(excess) {
+        if let Err(error) = fs::remove_file(&old) {
+            trace_debug(format!(
+                "req-vbk1 prune failed path={} error={error}",
+                old.display()
+            ));
+        }
+    }
+}
+
+fn invalid_backup(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+// Maps an archive entry back to its place on this machine; entries outside notes/ and
+// conf/, or escaping them, are refused.
+fn restore_target_for_entry(
+    user_document_dir: &Path,
+    conf_dir: &Path,
+    entry: &Path,
+) -> Option<PathBuf> {
+    if let Ok(relative) = entry.strip_prefix(VAULT_BACKUP_NOTES_PREFIX) {
+        return (!relative.as_os_str().is_empty()).then(|| user_document_dir.join(relative));
+    }
+    if let Ok(relative) = entry.strip_prefix(VAULT_BACKUP_CONF_PREFIX) {
+        if relative.as_os_str().is_empty() || is_machine_local_conf_file(relative) {
+            return None;
+        }
+        return Some(conf_dir.join(relative));
+    }
+    None
+}
+
+/// Checks the whole archive before anything is written: the manifest, the file count, every
+/// entry name and every entry's checksum. Returns `(entry index, target path)` pairs.
+fn validate_vault_backup(
+    zip: &mut ZipArchive<fs::File>,
+    user_document_dir: &Path,
+    conf_dir: &Path,
+) -> io::Result<Vec<(usize, PathBuf)>> {
+    let manifest_text = {
+        let mut entry = zip
+            .by_name(VAULT_BACKUP_MANIFEST_NAME)
+            .map_err(|_| invalid_backup("not a papyru2 vault backup (manifest missing)"))?;
+        let mut text = String::new();
+        entry.read_to_string(&mut text)?;
+        text
+    };
+    let manifest: VaultBackupManifest = toml::from_str(&manifest_text)
+        .map_err(|error| invalid_backup(format!("backup manifest unreadable: {error}")))?;
+    if manifest.format > VAULT_BACKUP_FORMAT_VERSION {
+        return Err(invalid_backup(format!(
+            "backup format {} is newer than supported {}",
+            manifest.format, VAULT_BACKUP_FORMAT_VERSION
+        )));
+    }
+
+    let mut planned = Vec::new();
+    let mut entries = 0;
+    for index in 0..zip.len() {
+        let mut entry = zip
+            .by_index(index)
+            .map_err(|error| invalid_backup(error.to_string()))?;
+        if entry.is_dir() || entry.name() == VAULT_BACKUP_MANIFEST_NAME {
+            continue;
+        }
+        entries += 1;
+        let name = entry.name().to_string();
+        let Some(enclosed) = entry.enclosed_name() else {
+            return Err(invalid_backup(format!("unsafe entry name {name}")));
+        };
+        // Reading to the end makes the zip reader verify the entry's CRC.
+        io::copy(&mut entry, &mut io::sink())
+            .map_err(|error| invalid_backup(format!("{name} is damaged: {error}")))?;
+        match restore_target_for_entry(user_document_dir, conf_dir, enclosed.as_path()) {
+            Some(target) => planned.push((index, target)),
+            None => trace_debug(format!("req-vbk1 restore skipped entry={name}")),
+        }
+    }
+    if entries != manifest.files {
+        return Err(invalid_backup(format!(
+            "backup is incomplete: {entries} of {} files present",
+            manifest.files
+        )));
+    }
+    Ok(planned)
+}
+
+/// Restores notes and conf from `archive`, overwriting same-named files and leaving
+/// everything else alone. A damaged or foreign archive is rejected before any write.
+pub(crate) fn restore_vault_backup(
+    archive: &Path,
+    user_document_dir: &Path,
+    conf_dir: &Path,
+) -> io::Result<usize> {
+    let mut zip = ZipArchive::new(fs::File::open(archive)?)
+        .map_err(|error| invalid_backup(error.to_string()))?;
+    let planned = validate_vault_backup(&mut zip, user_document_dir, conf_dir)?;
+    for (index, target) in &planned {
+        let mut entry = zip.by_index(*index).map_err(io::Error::other)?;
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes)?;
+        crate::storage_backend::storage_backend_containing(target.as_path())
+            .write(target.as_path(), &bytes)
+            .map_err(at_path(target))?;
+    }
+    trace_debug(format!(
+        "req-vbk1 restore path={} files={}",
+        archive.display(),
+        planned.len()
+    ));
+    Ok(planned.len())
+}
+
+impl crate::app::Papyru2App {
+    /// Backs up on a thread of its own once the open note is on disk; a large vault can take
+    /// longer to zip than any file event is allowed. The UI only waits for the notice.
+    pub(crate) fn run_vault_backup(
+        &mut self,
+        reason: &'static str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.vault_backup_running {
+            return;
+        }
+        if !self.flush_editor_content_before_context_switch("req-vbk1-backup", cx) {
+            self.show_transient_notice(tr("notice.backup_needs_save").to_string(), cx);
+            return;
+        }
+        let user_document_dir = self.app_paths.user_document_dir.clone();
+        let conf_dir = self.app_paths.conf_dir.clone();
+        let archive_dir = self.vault_backup.archive_dir.clone();
+        let keep = self.vault_backup.keep;
+        let now = Local::now();
+        let (report_tx, report_rx) = smol::channel::bounded::<WorkflowResult<VaultBackupReport>>(1);
+        thread::spawn(move || {
+            let result = write_vault_backup(&user_document_dir, &conf_dir, &archive_dir, keep, now)
+                .map_err(|error| WorkflowError::from_io(error, archive_dir.as_path()));
+            let _ = report_tx.try_send(result);
+        });
+        trace_debug(format!("req-vbk1 backup started reason={reason}"));
+        self.vault_backup_running = true;
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(result) = report_rx.recv().await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, _, cx| {
+                app.vault_backup_running = false;
+                let message = match result {
+                    Ok(report) => {
+                        app.vault_backup_last_at = Some(now);
+                        app.sync_status.record_backup(
+                            Ok((report.files, report.archive.as_path())),
+                            Local::now(),
+                        );
+                        tr_with(
+                            "notice.backed_up",
+                            &[
+                                ("count", report.files.to_string().as_str()),
+                                ("path", report.archive.display().to_string().as_str()),
+                            ],
+                        )
+                    }
+                    Err(error) => {
+                        trace_debug(format!("req-vbk1 backup failed error={error}"));
+                        app.sync_status
+                            .record_backup(Err(error.to_string()), Local::now());
+                        error.notice(tr("error.backup_failed"))
+                    }
+                };
+                app.show_transient_notice(message, cx);
+            });
+        })
+        .detach();
+    }
+
+    /// Checks every few minutes, starting right away, whether the newest archive is older
+    /// than `interval`.
+    pub(crate) fn spawn_vault_backup_schedule(
+        interval: Duration,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, cx| {
+            loop {
+                let updated = this.update_in(cx, |app, window, cx| {
+                    if vault_backup_due(app.vault_backup_last_at, interval, Local::now()) {
+                        app.run_vault_backup("schedule", window, cx);
+                    }
+                });
+                if updated.is_err() {
+                    break;
+                }
+                cx.background_executor()
+                    .timer(VAULT_BACKUP_SCHEDULE_CHECK.min(interval))
+                    .await;
+            }
+        })
+        .detach();
+    }
+
+    pub(crate) fn show_vault_backup_menu(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let latest = list_vault_backups(self.vault_backup.archive_dir.as_path())
+            .pop()
+            .and_then(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+            });
+        let archive_dir = self.vault_backup.archive_dir.display().to_string();
+        let detail = match latest {
+            Some(name) => tr_with(
+                "prompt.latest_backup",
+                &[("name", name.as_str()), ("path", archive_dir.as_str())],
+            ),
+            None => tr_with("prompt.no_backups", &[("path", archive_dir.as_str())]),
+        };
+        let buttons = [
+            tr("button.back_up_now"),
+            tr("button.restore_from_backup"),
+            tr("button.browse_snapshots"),
+            tr("button.export_vault"),
+            tr("button.cancel"),
+        ];
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &tr("prompt.vault_backup"),
+            Some(&detail),
+            &[
+                buttons[0].as_ref(),
+                buttons[1].as_ref(),
+                buttons[2].as_ref(),
+                buttons[3].as_ref(),
+                buttons[4].as_ref(),
+            ],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(choice) = answer.await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| match choice {
+                0 => app.run_vault_backup("manual", window, cx),
+                1 => app.pick_vault_backup_to_restore(window, cx),
+                2 => app.show_snapshot_browser(0, window, cx),
+                3 => app.choose_vault_export_format(window, cx),
+                _ => {}
+            });
+        })
+        .detach();
+    }
+
+    fn pick_vault_backup_to_restore(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let answer = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some(tr("button.restore_backup")),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = answer.await else {
+                return;
+            };
+            let Some(archive) = paths.into_iter().next() else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.confirm_vault_restore(archive, window, cx)
+            });
+        })
+        .detach();
+    }
+
+    fn confirm_vault_restore(
+        &mut self,
+        archive: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let detail = tr_with(
+            "prompt.confirm_restore_detail",
+            &[("path", archive.display().to_string().as_str())],
+        );
+        let buttons = [tr("button.restore_now"), tr("button.cancel")];
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            &tr("prompt.confirm_restore"),
+            Some(&detail),
+            &[buttons[0].as_ref(), buttons[1].as_ref()],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            if !matches!(answer.await, Ok(0)) {
+                return;
+            }
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.run_vault_restore(archive, window, cx)
+            });
+        })
+        .detach();
+    }
+
+    fn run_vault_restore(&mut self, archive: PathBuf, window: &mut Window, cx: &mut Context<Self>) {
+        if self.vault_backup_running {
+            return;
+        }
+        // Pending text would otherwise be saved over the restored note afterwards.
+        if !self.flush_editor_content_before_context_switch("req-vbk1-restore", cx) {
+            self.show_transient_notice(tr("notice.restore_needs_save").to_string(), cx);
+            return;
+        }
+        let user_document_dir = self.app_paths.user_document_dir.clone();
+        let conf_dir = self.app_paths.conf_dir.clone();
+        let (result_tx, result_rx) = smol::channel::bounded::<WorkflowResult<usize>>(1);
+        thread::spawn(move || {
+            let result = restore_vault_backup(&archive, &user_document_dir, &conf_dir)
+                .map_err(|error| WorkflowError::from_io(error, archive.as_path()));
+            let _ = result_tx.try_send(result);
+        });
+        self.vault_backup_running = true;
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(result) = result_rx.recv().await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.vault_backup_running = false;
+                let message = match result {
+                    Ok(files) => {
+                        app.apply_file_tree_watcher_refresh(cx);
+                        app.check_current_file_external_change(window, cx);
+                        tr_with(
+                            "notice.vault_restored",
+                            &[("count", files.to_string().as_str())],
+                        )
+                    }
+                    Err(error) => {
+                        trace_debug(format!("req-vbk1 restore failed error={error}"));
+                        error.notice(tr("error.restore_failed"))
+                    }
+                };
+                app.show_transient_notice(message, cx);
+            });
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_vault_backup_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    fn at(second: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 3, 1, 9, 0, second)
+            .single()
+            .expect("local time")
+    }
+
+    #[test]
+    fn vbk_test1_backup_then_restore_round_trips_notes_and_conf_and_prunes_old_archives() {
+        let root = new_temp_root("vbk_test1");
+        let notes = root.join("user_document");
+        let conf = root.join("conf");
+        let archives = root.join("backups");
+        fs::create_dir_all(notes.join("2026/03/01")).expect("create notes");
+        fs::create_dir_all(notes.join("recyclebin")).expect("create recyclebin");
+        fs::create_dir_all(&conf).expect("create conf");
+        fs::write(notes.join("2026/03/01/plan.md"), "first").expect("note");
+        fs::write(notes.join("recyclebin/old.md"), "gone").expect("trashed");
+        fs::write(conf.join("papyru2_conf.toml"), "[debug]\n").expect("conf");
+        fs::write(
+            conf.join(crate::window_position::WINDOW_POSITION_FILE_NAME),
+            "x = 1",
+        )
+        .expect("window position");
+
+        let report = write_vault_backup(&notes, &conf, &archives, 2, at(0)).expect("backup");
+        assert_eq!(report.files, 2);
+        assert_eq!(
+            report.archive.file_name().and_then(|name| name.to_str()),
+            Some("papyru2_backup_20260301_090000.zip")
+        );
+        write_vault_backup(&notes, &conf, &archives, 2, at(1)).expect("second backup");
+        write_vault_backup(&notes, &conf, &archives, 2, at(2)).expect("third backup");
+        let kept = list_vault_backups(&archives);
+        assert_eq!(kept.len(), 2);
+        assert!(!kept.contains(&report.archive));
+
+        fs::write(notes.join("2026/03/01/plan.md"), "edited").expect("edit");
+        fs::write(notes.join("2026/03/01/later.md"), "new").expect("later note");
+        let restored = restore_vault_backup(&kept[1], &notes, &conf).expect("restore");
+        assert_eq!(restored, 2);
+        assert_eq!(
+            fs::read_to_string(notes.join("2026/03/01/plan.md")).expect("read"),
+            "first"
+        );
+        assert!(notes.join("2026/03/01/later.md").is_file());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn vbk_test3_schedule_is_due_from_the_newest_archive_not_the_session_start() {
+        let archive = PathBuf::from("/backups").join(vault_backup_archive_name(at(0)));
+        let taken = vault_backup_taken_at(&archive);
+        assert_eq!(taken, Some(at(0)));
+        assert_eq!(vault_backup_taken_at(Path::new("/backups/notes.zip")), None);
+
+        let hour = Duration::from_secs(60 * 60);
+        assert!(vault_backup_due(None, hour, at(0)));
+        assert!(!vault_backup_due(taken, hour, at(59)));
+        assert!(vault_backup_due(
+            taken,
+            hour,
+            at(0) + chrono::Duration::hours(1)
+        ));
+    }
+
+    #[test]
+    fn vbk_test2_damaged_or_foreign_archive_is_rejected_before_any_write() {
+        let root = new_temp_root("vbk_test2");
+        let notes = root.join("user_document");
+        let conf = root.join("conf");
+        fs::create_dir_all(&notes).expect("create notes");
+        fs::write(notes.join("plan.md"), "current").expect("note");
+
+        let foreign = root.join("foreign.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&foreign).expect("create zip"));
+        zip.start_file("notes/plan.md", SimpleFileOptions::default())
+            .expect("start entry");
+        zip.write_all(b"overwritten").expect("write entry");
+        zip.finish().expect("finish zip");
+        let error = restore_vault_backup(&foreign, &notes, &conf).expect_err("no manifest");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        // The manifest promises a file the archive no longer has.
+        let truncated = root.join("truncated.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&truncated).expect("create zip"));
+        zip.start_file(VAULT_BACKUP_MANIFEST_NAME, SimpleFileOptions::default())
+            .expect("start manifest");
+        zip.write_all(b"format = 1\nvault = \"user_document\"\ncreated_at = \"\"\nfiles = 2\n")
+            .expect("write manifest");
+        zip.start_file("notes/plan.md", SimpleFileOptions::default())
+            .expect("start entry");
+        zip.write_all(b"overwritten").expect("write entry");
+        zip.finish().expect("finish zip");
+        let error = restore_vault_backup(&truncated, &notes, &conf).expect_err("incomplete");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        assert_eq!(
+            fs::read_to_string(notes.join("plan.md")).expect("read"),
+            "current"
+        );
+        assert_eq!(
+            restore_target_for_entry(&notes, &conf, Path::new("data/log.txt")),
+            None
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,550 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use gpui::*;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+
+pub(crate) const VAULTS_FILE_NAME: &str = "vaults.toml";
+pub(crate) const DEFAULT_VAULT_NAME: &str = "Default";
+/// Under the data dir: edits of a note whose vault was unreachable when the user switched away.
+pub(crate) const UNSAVED_EDITS_DIR_NAME: &str = "unsaved_edits";
+
+/// The notes root background threads (rpc server, availability monitor) should use right
+/// now; the UI side switches it together with `AppPaths::user_document_dir`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ActiveVaultRoot(Arc<Mutex<PathBuf>>);
+
+impl ActiveVaultRoot {
+    pub fn new(root: PathBuf) -> Self {
+        Self(Arc::new(Mutex::new(root)))
+    }
+
+    pub fn get(&self) -> PathBuf {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    pub fn set(&self, root: PathBuf) {
+        *self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = root;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct VaultEntry {
+    pub name: String,
+    /// The local folder; for a remote vault this is the working copy kept in sync with it.
+    pub path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webdav: Option<crate::storage_backend::WebDavRemote>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_save: Option<crate::save_transforms::SaveTransformsOverride>,
+}
+
+/// `conf/vaults.toml`: every known notes root plus the one in use. The root resolved by
+/// `AppPaths` is always listed first as the default vault.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct VaultRegistry {
+    pub active: Option<PathBuf>,
+    #[serde(default)]
+    pub vaults: Vec<VaultEntry>,
+}
+
+impl VaultRegistry {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let raw = fs::read_to_string(path)?;
+        toml::from_str(&raw)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let text = toml::to_string(self)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+        crate::atomic_io::write_atomic(path, text.as_bytes())
+    }
+
+    pub fn ensure_default(&mut self, default_root: &Path) {
+        if self.vaults.iter().any(|vault| vault.path == default_root) {
+            return;
+        }
+        self.vaults.insert(
+            0,
+            VaultEntry {
+                name: DEFAULT_VAULT_NAME.to_string(),
+                path: default_root.to_path_buf(),
+                webdav: None,
+                on_save: None,
+            },
+        );
+    }
+
+    pub fn find(&self, root: &Path) -> Option<&VaultEntry> {
+        self.vaults.iter().find(|vault| vault.path == root)
+    }
+
+    /// Registers `root` under its folder name, numbered when that name is taken.
+    pub fn add(&mut self, root: PathBuf) -> VaultEntry {
+        if let Some(existing) = self.find(root.as_path()) {
+            return existing.clone();
+        }
+        let base = root
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| root.display().to_string());
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while self.vaults.iter().any(|vault| vault.name == name) {
+            name = format!("{base} {suffix}");
+            suffix += 1;
+        }
+        let entry = VaultEntry {
+            name,
+            path: root,
+            webdav: None,
+            on_save: None,
+        };
+        self.vaults.push(entry.clone());
+        entry
+    }
+
+    /// The active vault if it is still registered and present, otherwise `default_root`.
+    pub fn active_root(&self, default_root: &Path) -> PathBuf {
+        self.active
+            .as_ref()
+            .filter(|active| self.find(active.as_path()).is_some() && active.is_dir())
+            .cloned()
+            .unwrap_or_else(|| default_root.to_path_buf())
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct VaultManager {
+    file_path: PathBuf,
+    registry: VaultRegistry,
+}
+
+impl VaultManager {
+    pub fn load(file_path: PathBuf, default_root: &Path) -> Self {
+        let mut registry = VaultRegistry::load(file_path.as_path()).unwrap_or_else(|error| {
+            trace_debug(format!(
+                "req-vlt1 vaults load failed path={} error={error}",
+                file_path.display()
+            ));
+            VaultRegistry::default()
+        });
+        registry.ensure_default(default_root);
+        Self {
+            file_path,
+            registry,
+        }
+    }
+
+    pub fn registry(&self) -> &VaultRegistry {
+        &self.registry
+    }
+
+    pub fn vault_name(&self, root: &Path) -> String {
+        self.registry
+            .find(root)
+            .map(|vault| vault.name.clone())
+            .unwrap_or_else(|| root.display().to_string())
+    }
+
+    /// Switches the workflow's storage backend to the one `root` is configured for.
+    pub fn activate_storage_backend(
+        &self,
+        root: &Path,
+        vault_data_dir: &Path,
+    ) -> io::Result<&'static str> {
+        let remote = self
+            .registry
+            .find(root)
+            .and_then(|vault| vault.webdav.as_ref());
+        crate::storage_backend::activate_storage_backend(root, remote, vault_data_dir)
+    }
+
+    /// Applies `root`'s `on_save` overrides on top of the main config's `[on_save]`.
+    pub fn activate_save_transforms(
+        &self,
+        root: &Path,
+        base: crate::save_transforms::SaveTransforms,
+    ) {
+        let vault = self
+            .registry
+            .find(root)
+            .and_then(|vault| vault.on_save.as_ref());
+        crate::save_transforms::activate_save_transforms(root, base, vault);
+    }
+
+    pub fn add_and_activate(&mut self, root: PathBuf) -> io::Result<VaultEntry> {
+        let entry = self.registry.add(root);
+        self.registry.active = Some(entry.path.clone());
+        self.registry.save(self.file_path.as_path())?;
+        Ok(entry)
+    }
+}
+
+impl crate::app::Papyru2App {
+    pub(crate) fn show_vault_switcher(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let current = self.app_paths.user_document_dir.clone();
+        let vaults = self.vaults.registry().vaults.clone();
+        let mut labels = vaults
+            .iter()
+            .map(|vault| {
+                if vault.path == current {
+                    tr_with("label.current", &[("name", vault.name.as_str())])
+                } else {
+                    vault.name.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        labels.push(tr("button.add_vault").to_string());
+        labels.push(tr("button.cancel").to_string());
+        let label_refs = labels.iter().map(String::as_str).collect::<Vec<_>>();
+        let detail = tr_with(
+            "prompt.switch_vault_detail",
+            &[("path", current.display().to_string().as_str())],
+        );
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &tr("prompt.switch_vault"),
+            Some(&detail),
+            &label_refs,
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(choice) = answer.await else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                if let Some(vault) = vaults.get(choice) {
+                    app.switch_vault(vault.path.clone(), window, cx);
+                } else if choice == vaults.len() {
+                    app.add_vault(window, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn add_vault(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let answer = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some(tr("button.use_as_vault")),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = answer.await else {
+                return;
+            };
+            let Some(root) = paths.into_iter().next() else {
+                return;
+            };
+            let _ = this.update_in(cx, |app, window, cx| {
+                app.switch_vault(root, window, cx);
+            });
+        })
+        .detach();
+    }
+
+    /// Points the tree, watcher, create workflow and background services at `root` without a
+    /// restart. The open note is saved and closed first.
+    pub(crate) fn switch_vault(
+        &mut self,
+        root: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if root == self.app_paths.user_document_dir {
+            return;
+        }
+        if !self.flush_before_vault_switch(cx) {
+            trace_debug("req-vlt1 switch aborted (pre-switch autosave failed)");
+            self.show_transient_notice(tr("notice.vault_switch_unsaved_note").to_string(), cx);
+            return;
+        }
+        let mut switched_paths = self.app_paths.clone();
+        switched_paths.switch_user_document_dir(root.clone());
+        if let Err(error) = fs::create_dir_all(&switched_paths.recyclebin_dir).and_then(|_| {
+            crate::file_update_handler::ensure_note_directory(
+                switched_paths.user_document_dir.as_path(),
+                chrono::Local::now(),
+            )
+            .map(|_| ())
+        }) {
+            self.show_transient_notice(
+                tr_with(
+                    "notice.cannot_use_vault",
+                    &[
+                        ("path", root.display().to_string().as_str()),
+                        ("reason", error.to_string().as_str()),
+                    ],
+                ),
+                cx,
+            );
+            return;
+        }
+        let entry = match self.vaults.add_and_activate(root.clone()) {
+            Ok(entry) => entry,
+            Err(error) => {
+                trace_debug(format!("req-vlt1 vaults save failed error={error}"));
+                self.vaults
+                    .registry()
+                    .find(root.as_path())
+                    .cloned()
+                    .unwrap_or(VaultEntry {
+                        name: root.display().to_string(),
+                        path: root.clone(),
+                        webdav: None,
+                        on_save: None,
+                    })
+            }
+        };
+
+        self.handle_plus_button(window, cx);
+        if let Err(error) = self
+            .vaults
+            .activate_storage_backend(root.as_path(), switched_paths.vault_data_dir().as_path())
+        {
+            self.show_transient_notice(
+                tr_with(
+                    "notice.remote_vault_saving_locally",
+                    &[("reason", error.to_string().as_str())],
+                ),
+                cx,
+            );
+        }
+        self.vaults.activate_save_transforms(
+            root.as_path(),
+            crate::save_transforms::load_save_transforms(
+                self.app_paths
+                    .config_file_path(crate::app::PAPYRU2_CONF_FILE_NAME)
+                    .as_path(),
+            ),
+        );
+        self.app_paths = switched_paths;
+        self.sync_status.reset_remote();
+        self.file_workflow.attach_transition_journal(
+            crate::workflow_journal::WorkflowJournal::new(
+                crate::workflow_journal::workflow_journal_path(&self.app_paths),
+            ),
+        );
+        self.active_vault_root.set(root.clone());
+        self.vault_availability = crate::vault_availability::VaultAvailability::default();
+        self.editor_autosave.set_paused(false);
+
+        let protected_delete_roots = vec![self.app_paths.data_dir.clone(), root.clone()];
+        let daily_dir =
+            crate::file_update_handler::note_directory(root.as_path(), chrono::Local::now());
+        self.file_tree.update(cx, |file_tree, cx| {
+            file_tree.set_tree_root_dir(root.clone(), protected_delete_roots, cx);
+            file_tree.apply_req_ftr18_startup_daily_folder_position(daily_dir.as_path(), cx);
+        });
+        self.restart_file_tree_watcher(window, cx);
+        self.calendar
+            .update(cx, |calendar, cx| calendar.set_root(root.clone(), cx));
+        self.duplicate_title_gate.set_root(root.clone());
+        self.duplicate_title_notice = None;
+        let tag_switch = self
+            .tag_index
+            .as_ref()
+            .map(|tag_index| tag_index.switch_root(root.as_path()));
+        if let Some(Err(error)) = tag_switch {
+            trace_debug(format!("req-vlt1 tag_index switch failed error={error}"));
+        }
+        self.sync_tag_sidebar(cx);
+        self.switch_storage_root(root.as_path(), cx);
+        self.top_bars.update(cx, |top_bars, cx| {
+            top_bars.set_active_vault_name(entry.name.clone(), cx);
+        });
+        trace_debug(format!(
+            "req-vlt1 vault switched name={} root={}",
+            entry.name,
+            root.display()
+        ));
+        self.show_transient_notice(
+            tr_with("notice.switched_vault", &[("name", entry.name.as_str())]),
+            cx,
+        );
+        cx.notify();
+    }
+
+    // The pending title rename and autosave go to the vault being left. When that vault is
+    // unreachable nothing can be written there, so the unsaved text is kept as a copy under
+    // the data dir instead of being dropped with the switch.
+    fn flush_before_vault_switch(&mut self, cx: &mut Context<Self>) -> bool {
+        let previous_root = self.app_paths.user_document_dir.clone();
+        if self.vault_availability.is_disconnected()
+            && crate::vault_availability::is_vault_available(previous_root.as_path())
+        {
+            self.vault_availability = crate::vault_availability::VaultAvailability::default();
+            self.editor_autosave.set_paused(false);
+        }
+        if !self.vault_availability.is_disconnected() {
+            return self.flush_editor_content_before_context_switch("req-vlt1-switch", cx);
+        }
+
+        if let Some(pending) = self.title_rename_debouncer.take_pending() {
+            trace_debug(format!(
+                "req-vlt1 pending title dropped (vault unavailable) value='{}'",
+                crate::app::compact_text(&pending.singleline_value)
+            ));
+        }
+        let Some(current_path) = self.file_workflow.current_edit_path() else {
+            return true;
+        };
+        if !self
+            .editor_autosave
+            .has_pending_payload_for_path(current_path.as_path())
+        {
+            return true;
+        }
+        let text = self.editor.read(cx).snapshot(cx).value;
+        let copy_path = unsaved_edits_copy_path(
+            self.app_paths.data_dir.as_path(),
+            current_path.as_path(),
+            chrono::Local::now(),
+        );
+        let written = copy_path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| crate::atomic_io::write_atomic(copy_path.as_path(), text.as_bytes()));
+        if let Err(error) = written {
+            trace_debug(format!(
+                "req-vlt1 unsaved edits copy failed path={} error={error}",
+                copy_path.display()
+            ));
+            return false;
+        }
+        self.editor_autosave
+            .clear_pending_for_path(current_path.as_path());
+        trace_debug(format!(
+            "req-vlt1 unsaved edits kept note={} copy={}",
+            current_path.display(),
+            copy_path.display()
+        ));
+        self.show_transient_notice(
+            tr_with(
+                "notice.unsaved_edits_kept",
+                &[("path", copy_path.display().to_string().as_str())],
+            ),
+            cx,
+        );
+        true
+    }
+}
+
+fn unsaved_edits_copy_path(
+    data_dir: &Path,
+    note_path: &Path,
+    now: chrono::DateTime<chrono::Local>,
+) -> PathBuf {
+    let stem = note_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "note".to_string());
+    let extension = note_path
+        .extension()
+        .map(|extension| extension.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "txt".to_string());
+    data_dir.join(UNSAVED_EDITS_DIR_NAME).join(format!(
+        "{stem}_{}.{extension}",
+        now.format("%Y%m%d_%H%M%S")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "papyru2_vaults_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&path).expect("create temp root");
+        path
+    }
+
+    #[test]
+    fn vlt_test1_registry_round_trips_and_names_vaults_uniquely() {
+        let root = new_temp_root("vlt_test1");
+        let default_root = root.join("user_document");
+        let work_a = root.join("a").join("work");
+        let work_b = root.join("b").join("work");
+        fs::create_dir_all(&work_a).expect("create a");
+        let file_path = root.join(VAULTS_FILE_NAME);
+
+        let mut manager = VaultManager::load(file_path.clone(), default_root.as_path());
+        assert_eq!(manager.registry().vaults[0].name, DEFAULT_VAULT_NAME);
+        manager.add_and_activate(work_b.clone()).expect("add b");
+        let entry = manager.add_and_activate(work_a.clone()).expect("add a");
+        assert_eq!(entry.name, "work 2");
+
+        let reloaded = VaultManager::load(file_path, default_root.as_path());
+        assert_eq!(reloaded.registry().vaults.len(), 3);
+        assert_eq!(reloaded.registry().active, Some(work_a.clone()));
+        assert_eq!(reloaded.vault_name(work_b.as_path()), "work");
+        assert_eq!(
+            reloaded.registry().active_root(default_root.as_path()),
+            work_a
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn vlt_test2_missing_or_unknown_active_vault_falls_back_to_default() {
+        let root = new_temp_root("vlt_test2");
+        let default_root = root.join("user_document");
+        let mut registry = VaultRegistry::default();
+        registry.ensure_default(default_root.as_path());
+        let gone = root.join("unplugged");
+        registry.add(gone.clone());
+        registry.active = Some(gone);
+        assert_eq!(registry.active_root(default_root.as_path()), default_root);
+
+        registry.active = Some(root.clone());
+        assert_eq!(registry.active_root(default_root.as_path()), default_root);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn vlt_test3_unsaved_edits_copy_lands_in_the_data_dir() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-03-01T09:08:07+00:00")
+            .expect("parse time")
+            .with_timezone(&chrono::Local);
+        let data_dir = PathBuf::from("/data");
+        let copy = unsaved_edits_copy_path(
+            data_dir.as_path(),
+            Path::new("/usb/notes/2026/03/plan.md"),
+            now,
+        );
+        assert_eq!(
+            copy.parent(),
+            Some(data_dir.join(UNSAVED_EDITS_DIR_NAME).as_path())
+        );
+        let name = copy.file_name().expect("file name").to_string_lossy();
+        assert!(name.starts_with("plan_") && name.ends_with(".md"), "{name}");
+    }
+}
@@ -0,0 +1,210 @@
+use gpui::*;
+use gpui_component::{ActiveTheme as _, Theme};
+
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::{
+    HWND_NOTOPMOST, HWND_TOPMOST, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SetWindowPos,
+};
+
+use crate::i18n::{tr, tr_with};
+use crate::log::trace_debug;
+
+// Below this the notes are too faint to read against whatever is behind the window.
+const MIN_WINDOW_OPACITY: f32 = 0.3;
+/// Ctrl+Shift+J steps through these, then back to opaque.
+const WINDOW_OPACITY_STEPS: [f32; 4] = [1.0, 0.85, 0.7, 0.55];
+
+/// `None` for an opaque window or a value that is not an opacity.
+pub(crate) fn normalize_window_opacity(opacity: f32) -> Option<f32> {
+    (opacity.is_finite() && (MIN_WINDOW_OPACITY..1.0).contains(&opacity)).then_some(opacity)
+}
+
+/// The next step below `current`, wrapping around to opaque.
+pub(crate) fn next_window_opacity(current: f32) -> f32 {
+    WINDOW_OPACITY_STEPS
+        .iter()
+        .copied()
+        .find(|step| *step < current - f32::EPSILON)
+        .unwrap_or(WINDOW_OPACITY_STEPS[0])
+}
+
+#[cfg(target_os = "windows")]
+fn apply_native_always_on_top(window: &Window, always_on_top: bool) -> bool {
+    let Some(hwnd) = crate::app::window_hwnd(window) else {
+        return false;
+    };
+    let insert_after = if always_on_top {
+        HWND_TOPMOST
+    } else {
+        HWND_NOTOPMOST
+    };
+    let status = unsafe {
+        SetWindowPos(
+            hwnd,
+            insert_after,
+            0,
+            0,
+            0,
+            0,
+            SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+        )
+    };
+    if let Err(error) = status {
+        trace_debug(format!(
+            "req-overlay always_on_top apply failed value={always_on_top} error={error}"
+        ));
+        return false;
+    }
+    true
+}
+
+#[cfg(not(target_os = "windows"))]
+fn apply_native_always_on_top(_window: &Window, _always_on_top: bool) -> bool {
+    false
+}
+
+/// Sets the theme background, keeping `alpha` as the window opacity. The code editor paints
+/// its own copy of the background, so while the window is see-through that copy is dropped
+/// rather than stacked on top of the root's.
+pub(crate) fn set_theme_background(theme: &mut Theme, background: Hsla, alpha: f32) {
+    theme.background = background.alpha(alpha);
+    let mut highlight_theme = (*theme.highlight_theme).clone();
+    highlight_theme.style.editor_background = Some(if alpha < 1.0 {
+        transparent_black()
+    } else {
+        theme.background
+    });
+    theme.highlight_theme = std::sync::Arc::new(highlight_theme);
+}
+
+/// The fill for a view that paints the note background itself: none while the window is
+/// see-through, because the root already paints the translucent theme background.
+pub(crate) fn panel_background(rgb_hex: u32, cx: &App) -> Hsla {
+    if cx.theme().background.a < 1.0 {
+        transparent_black()
+    } else {
+        crate::app::req_colr_rgb_hex_to_hsla(rgb_hex)
+    }
+}
+
+// gpui presents through a DirectComposition swap chain on Windows, which layered-window
+// alpha never reaches. The window is composited as transparent instead and the theme
+// background carries the opacity, so text stays fully opaque on every platform.
+fn apply_window_opacity(window: &mut Window, opacity: f32, cx: &mut App) {
+    let alpha = normalize_window_opacity(opacity).unwrap_or(1.0);
+    window.set_background_appearance(if alpha < 1.0 {
+        WindowBackgroundAppearance::Transparent
+    } else {
+        WindowBackgroundAppearance::Opaque
+    });
+    let theme = Theme::global_mut(cx);
+    let background = theme.background;
+    set_theme_background(theme, background, alpha);
+    window.refresh();
+}
+
+impl crate::app::Papyru2App {
+    /// The overlay settings saved in `window_position.toml`, applied once the window exists.
+    pub(crate) fn restore_window_overlay(
+        &mut self,
+        always_on_top: bool,
+        opacity: Option<f32>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        trace_debug(format!(
+            "req-overlay restore always_on_top={always_on_top} opacity={opacity:?}"
+        ));
+        if always_on_top {
+            self.always_on_top = apply_native_always_on_top(window, true);
+        }
+        if let Some(opacity) = opacity {
+            apply_window_opacity(window, opacity, cx);
+            self.window_opacity = opacity;
+        }
+    }
+
+    /// Ctrl+Shift+P.
+    pub(crate) fn toggle_always_on_top(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let always_on_top = !self.always_on_top;
+        if !apply_native_always_on_top(window, always_on_top) {
+            self.show_transient_notice(tr("notice.always_on_top_unavailable").to_string(), cx);
+            return;
+        }
+        self.always_on_top = always_on_top;
+        trace_debug(format!("req-overlay always_on_top={always_on_top}"));
+        self.show_transient_notice(
+            if always_on_top {
+                tr("notice.always_on_top_on").to_string()
+            } else {
+                tr("notice.always_on_top_off").to_string()
+            },
+            cx,
+        );
+        self.save_window_overlay(window, cx);
+    }
+
+    /// Ctrl+Shift+J: 100%, 85%, 70%, 55%, then opaque again.
+    pub(crate) fn cycle_window_opacity(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let opacity = next_window_opacity(self.window_opacity);
+        apply_window_opacity(window, opacity, cx);
+        self.window_opacity = opacity;
+        trace_debug(format!("req-overlay opacity={opacity}"));
+        self.show_transient_notice(
+            tr_with(
+                "notice.window_opacity",
+                &[("percent", (opacity * 100.0).round().to_string().as_str())],
+            ),
+            cx,
+        );
+        self.save_window_overlay(window, cx);
+    }
+
+    fn save_window_overlay(&self, window: &Window, cx: &App) {
+        let path = self
+            .app_paths
+            .config_file_path(crate::window_position::WINDOW_POSITION_FILE_NAME);
+        let state = self.capture_window_position_state(window, cx);
+        if let Err(error) =
+            crate::window_position::save_window_position_atomic(path.as_path(), &state)
+        {
+            trace_debug(format!(
+                "req-overlay save failed path={} error={error}",
+                path.display()
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_test1_opacity_steps_wrap_and_out_of_range_values_mean_opaque() {
+        assert_eq!(next_window_opacity(1.0), 0.85);
+        assert_eq!(next_window_opacity(0.85), 0.7);
+        assert_eq!(next_window_opacity(0.55), 1.0);
+        // A value set by hand in the file joins the cycle at the next step below it.
+        assert_eq!(next_window_opacity(0.9), 0.85);
+        assert_eq!(next_window_opacity(0.4), 1.0);
+
+        assert_eq!(normalize_window_opacity(0.7), Some(0.7));
+        assert_eq!(normalize_window_opacity(1.0), None);
+        assert_eq!(normalize_window_opacity(0.1), None);
+        assert_eq!(normalize_window_opacity(f32::NAN), None);
+
+        let state = crate::window_position::WindowPositionState::from_window_bounds(
+            WindowBounds::Windowed(bounds(point(px(0.0), px(0.0)), size(px(800.0), px(600.0)))),
+            None,
+            None,
+            None,
+        )
+        .with_window_overlay(true, Some(0.7));
+        let loaded: crate::window_position::WindowPositionState =
+            toml::from_str(&toml::to_string_pretty(&state).unwrap()).unwrap();
+        assert!(loaded.always_on_top);
+        assert_eq!(loaded.opacity(), Some(0.7));
+        assert_eq!(state.with_window_overlay(false, Some(1.0)).opacity, None);
+    }
+}
@@ -1,6 +1,6 @@
 use std::fs;
 use std::io;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use gpui::{App, Bounds, Pixels, Window, WindowBounds, bounds, point, px, size};
 use serde::{Deserialize, Serialize};
@@ -9,6 +9,7 @@ pub const WINDOW_POSITION_FILE_NAME: &str = "window_position.toml";
 pub const FIRST_LAUNCH_DISPLAY_RATIO: f32 = 0.7;
 const MIN_WINDOW_DIMENSION: f32 = 120.0;
 const MAX_ABS_COORDINATE: f32 = 1_000_000.0;
+const DPI_SCALE_EPSILON: f32 = 0.01;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -30,6 +31,12 @@ pub struct WindowPositionState {
     pub dpi_scale: Option<f32>,
     #[serde(default)]
     pub splitter_sizes: Option<Vec<f32>>,
+    #[serde(default)]
+    pub editor_zoom: Option<f32>,
+    #[serde(default)]
+    pub always_on_top: bool,
+    #[serde(default)]
+    pub opacity: Option<f32>,
 }
 
 impl WindowPositionState {
@@ -66,6 +73,9 @@ impl WindowPositionState {
             monitor_uuid,
             dpi_scale,
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         }
     }
 
@@ -75,6 +85,49 @@ impl WindowPositionState {
         self
     }
 
+    /// Some platforms report a maximized window's current geometry as its restore bounds;
+    /// the last windowed bounds seen take their place, so un-maximizing after a restart goes
+    /// back to the size the window had before it was maximized.
+    pub fn with_windowed_restore_bounds(mut self, windowed: Option<Bounds<Pixels>>) -> Self {
+        if self.window_mode == PersistedWindowMode::Windowed {
+            return self;
+        }
+        if let Some(windowed) = windowed.filter(|windowed| {
+            is_valid_coordinate(f32::from(windowed.origin.x))
+                && is_valid_coordinate(f32::from(windowed.origin.y))
+                && is_valid_dimension(f32::from(windowed.size.width))
+                && is_valid_dimension(f32::from(windowed.size.height))
+        }) {
+            self.x = f32::from(windowed.origin.x);
+            self.y = f32::from(windowed.origin.y);
+            self.width = f32::from(windowed.size.width);
+            self.height = f32::from(windowed.size.height);
+        }
+        self
+    }
+
+    pub fn with_editor_zoom(mut self, zoom: Option<f32>) -> Self {
+        self.editor_zoom = zoom.and_then(crate::editor::normalize_editor_zoom);
+        self
+    }
+
+    pub fn with_window_overlay(mut self, always_on_top: bool, opacity: Option<f32>) -> Self {
+        self.always_on_top = always_on_top;
+        self.opacity = opacity.and_then(crate::window_overlay::normalize_window_opacity);
+        self
+    }
+
+    /// `None` for a fully opaque window.
+    pub fn opacity(&self) -> Option<f32> {
+        self.opacity
+            .and_then(crate::window_overlay::normalize_window_opacity)
+    }
+
+    pub fn editor_zoom(&self) -> Option<f32> {
+        self.editor_zoom
+            .and_then(crate::editor::normalize_editor_zoom)
+    }
+
     pub fn splitter_left_size(&self) -> Option<f32> {
         self.splitter_sizes
             .as_ref()
@@ -97,6 +150,26 @@ impl WindowPositionState {
         );
         Some(window_bounds_from_parts(self.window_mode, restore_bounds))
     }
+
+    /// The bounds are logical pixels at `dpi_scale`; on a monitor with another scale the same
+    /// physical spot is at `x * dpi_scale / current_scale`. `None` when nothing changes.
+    pub fn rescaled_for_dpi(&self, current_scale: f32) -> Option<Self> {
+        let saved_scale = self.dpi_scale.filter(|scale| is_valid_dpi_scale(*scale))?;
+        if !is_valid_dpi_scale(current_scale)
+            || (saved_scale - current_scale).abs() <= DPI_SCALE_EPSILON
+        {
+            return None;
+        }
+        let ratio = saved_scale / current_scale;
+        Some(Self {
+            x: self.x * ratio,
+            y: self.y * ratio,
+            width: self.width * ratio,
+            height: self.height * ratio,
+            dpi_scale: Some(current_scale),
+            ..self.clone()
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -195,13 +268,17 @@ pub fn load_window_position(path: &Path) -> io::Result<Option<WindowPositionStat
 pub fn save_window_position_atomic(path: &Path, state: &WindowPositionState) -> io::Result<()> {
     let serialized = toml::to_string_pretty(state)
         .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
-    write_atomic(path, serialized.as_bytes())
+    crate::atomic_io::write_atomic(path, serialized.as_bytes())
 }
 
+/// Restores onto the display the window was on (by uuid, then id). When that display is
+/// gone the bounds are kept on whichever connected display they still overlap, else clamped
+/// onto the primary one.
 pub fn resolve_startup_window_bounds(
     persisted: Option<&WindowPositionState>,
     fallback: WindowBounds,
-    display_bounds: Option<Bounds<Pixels>>,
+    available_displays: &[StartupDisplaySnapshot],
+    primary_monitor_id: Option<u32>,
     ignore_exact_position: bool,
 ) -> WindowBounds {
     let Some(persisted) = persisted else {
@@ -212,6 +289,18 @@ pub fn resolve_startup_window_bounds(
         return fallback;
     };
 
+    let resolution =
+        resolve_startup_display_resolution(Some(persisted), available_displays, primary_monitor_id);
+    let display_bounds = match resolution.source {
+        StartupDisplayResolutionSource::PersistedUuid
+        | StartupDisplayResolutionSource::PersistedMonitorId => resolution.display_bounds,
+        _ => available_displays
+            .iter()
+            .map(|display| display.bounds)
+            .find(|display| intersects(raw_bounds.get_bounds(), *display))
+            .or(resolution.display_bounds),
+    };
+
     sanitize_window_bounds(raw_bounds, fallback, display_bounds, ignore_exact_position)
 }
 
@@ -257,136 +346,6 @@ pub fn should_ignore_exact_position_for_wayland() -> bool {
     }
 }
 
-fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
-    write_atomic_with_replace(path, bytes, replace_target_with_temp)
-}
-
-fn write_atomic_with_replace<F>(path: &Path, bytes: &[u8], replace_fn: F) -> io::Result<()>
-where
-    F: Fn(&Path, &Path) -> io::Result<()>,
-{
-    let parent = path.parent().ok_or_else(|| {
-        io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "window position path has no parent directory",
-        )
-    })?;
-    fs::create_dir_all(parent)?;
-
-    let temp_path = temp_path_for_atomic_write(path)?;
-    if temp_path.is_file() {
-        fs::remove_file(&temp_path)?;
-    }
-    let mut temp_file = fs::File::create(&temp_path).map_err(|error| {
-        io::Error::new(
-            error.kind(),
-            format!("atomic write stage failed (create temp): {error}"),
-        )
-    })?;
-    std::io::Write::write_all(&mut temp_file, bytes).map_err(|error| {
-        io::Error::new(
-            error.kind(),
-            format!("atomic write stage failed (write temp): {error}"),
-        )
-    })?;
-    temp_file.sync_all().map_err(|error| {
-        io::Error::new(
-            error.kind(),
-            format!("atomic write stage failed (sync temp): {error}"),
-        )
-    })?;
-    drop(temp_file);
-
-    if let Err(replace_error) = replace_fn(&temp_path, path).map_err(|error| {
-        io::Error::new(
-            error.kind(),
-            format!("atomic write stage failed (replace target): {error}"),
-        )
-    }) {
-        if let Err(cleanup_error) = cleanup_temp_file(&temp_path) {
-            return Err(io::Error::new(
-                replace_error.kind(),
-                format!("{replace_error}; cleanup temp failed: {cleanup_error}"),
-            ));
-        }
-
-        return Err(replace_error);
-    }
-
-    Ok(())
-}
-
-fn cleanup_temp_file(path: &Path) -> io::Result<()> {
-    match fs::remove_file(path) {
-        Ok(()) => Ok(()),
-        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
-        Err(error) => Err(error),
-    }
-}
-
-fn replace_target_with_temp(temp_path: &Path, target_path: &Path) -> io::Result<()> {
-    // Safety invariant: never delete the existing target before a replacement operation succeeds.
-    // On replace failure, caller keeps the last-good target file intact.
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::ffi::OsStrExt;
-        use std::ptr::{null, null_mut};
-
-        use windows_sys::Win32::Storage::FileSystem::ReplaceFileW;
-
-        if !target_path.exists() {
-            return fs::rename(temp_path, target_path);
-        }
-
-        let mut target_wide = target_path
-            .as_os_str()
-            .encode_wide()
-            .chain(Some(0))
-            .collect::<Vec<u16>>();
-        let mut temp_wide = temp_path
-            .as_os_str()
-            .encode_wide()
-            .chain(Some(0))
-            .collect::<Vec<u16>>();
-
-        let result = unsafe {
-            ReplaceFileW(
-                target_wide.as_mut_ptr(),
-                temp_wide.as_mut_ptr(),
-                null(),
-                0,
-                null_mut(),
-                null_mut(),
-            )
-        };
-        if result == 0 {
-            return Err(io::Error::last_os_error());
-        }
-        return Ok(());
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        fs::rename(temp_path, target_path)
-    }
-}
-
-fn temp_path_for_atomic_write(path: &Path) -> io::Result<PathBuf> {
-    let parent = path.parent().ok_or_else(|| {
-        io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "window position path has no parent directory",
-        )
-    })?;
-    let file_name = path.file_name().ok_or_else(|| {
-        io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "window position path has no file name",
-        )
-    })?;
-    Ok(parent.join(format!("{}.tmp", file_name.to_string_lossy())))
-}
-
 fn sanitize_window_bounds(
     raw_bounds: WindowBounds,
     fallback: WindowBounds,
@@ -474,6 +433,10 @@ fn is_valid_coordinate(value: f32) -> bool {
     value.is_finite() && value.abs() <= MAX_ABS_COORDINATE
 }
 
+fn is_valid_dpi_scale(value: f32) -> bool {
+    value.is_finite() && value > 0.0
+}
+
 fn is_valid_splitter_size(value: f32) -> bool {
     value.is_finite() && value > 0.0 && value <= MAX_ABS_COORDINATE
 }
@@ -508,13 +471,18 @@ impl crate::app::Papyru2App {
                 actual_splitter_sizes
             ));
         }
-        WindowPositionState::from_window(window, cx).with_splitter_sizes(&splitter_sizes)
+        WindowPositionState::from_window(window, cx)
+            .with_windowed_restore_bounds(self.last_windowed_bounds)
+            .with_splitter_sizes(&splitter_sizes)
+            .with_editor_zoom(Some(self.editor.read(cx).zoom()))
+            .with_window_overlay(self.always_on_top, Some(self.window_opacity))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::PathBuf;
     use std::time::{SystemTime, UNIX_EPOCH};
 
     fn new_temp_root(name: &str) -> PathBuf {
@@ -570,7 +538,12 @@ mod tests {
         let resolved = resolve_startup_window_bounds(
             loaded.as_ref(),
             fallback,
-            Some(display_bounds(3000.0, 2000.0)),
+            &[startup_display_snapshot(
+                0,
+                None,
+                display_bounds(3000.0, 2000.0),
+            )],
+            Some(0),
             false,
         );
 
@@ -594,6 +567,9 @@ mod tests {
             monitor_uuid: Some("display-uuid".to_string()),
             dpi_scale: Some(1.5),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
         save_window_position_atomic(path.as_path(), &saved).expect("save state");
 
@@ -601,7 +577,12 @@ mod tests {
         let resolved = resolve_startup_window_bounds(
             loaded.as_ref(),
             fallback,
-            Some(display_bounds(3000.0, 2000.0)),
+            &[startup_display_snapshot(
+                0,
+                None,
+                display_bounds(3000.0, 2000.0),
+            )],
+            Some(0),
             false,
         );
 
@@ -624,6 +605,9 @@ mod tests {
             monitor_uuid: None,
             dpi_scale: Some(1.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
 
         save_window_position_atomic(path.as_path(), &state).expect("save state");
@@ -646,6 +630,9 @@ mod tests {
             monitor_uuid: Some("monitor-3".to_string()),
             dpi_scale: Some(2.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
 
         save_window_position_atomic(path.as_path(), &state).expect("save state");
@@ -724,12 +711,20 @@ window_mode = "minimized"
             monitor_uuid: None,
             dpi_scale: Some(1.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
 
         let resolved = resolve_startup_window_bounds(
             Some(&state),
             fallback,
-            Some(display_bounds(1920.0, 1080.0)),
+            &[startup_display_snapshot(
+                0,
+                None,
+                display_bounds(1920.0, 1080.0),
+            )],
+            Some(0),
             false,
         );
 
@@ -749,12 +744,20 @@ window_mode = "minimized"
             monitor_uuid: None,
             dpi_scale: Some(1.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
 
         let resolved = resolve_startup_window_bounds(
             Some(&state),
             fallback,
-            Some(display_bounds(1920.0, 1080.0)),
+            &[startup_display_snapshot(
+                0,
+                None,
+                display_bounds(1920.0, 1080.0),
+            )],
+            Some(0),
             false,
         );
 
@@ -775,6 +778,9 @@ window_mode = "minimized"
             monitor_uuid: Some("old".to_string()),
             dpi_scale: Some(1.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
         let new = WindowPositionState {
             monitor_uuid: Some("new".to_string()),
@@ -783,12 +789,16 @@ window_mode = "minimized"
 
         save_window_position_atomic(path.as_path(), &old).expect("save old");
         let new_bytes = toml::to_string_pretty(&new).expect("serialize new");
-        let result = write_atomic_with_replace(&path, new_bytes.as_bytes(), |_temp, _target| {
-            Err(io::Error::new(
-                io::ErrorKind::PermissionDenied,
-                "forced replace failure",
-            ))
-        });
+        let result = crate::atomic_io::write_atomic_with_replace(
+            &path,
+            new_bytes.as_bytes(),
+            |_temp, _target| {
+                Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "forced replace failure",
+                ))
+            },
+        );
         assert!(result.is_err());
 
         let loaded = load_window_position(path.as_path()).expect("load old state");
@@ -810,6 +820,9 @@ window_mode = "minimized"
             monitor_uuid: Some("old".to_string()),
             dpi_scale: Some(1.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
         let new = WindowPositionState {
             x: 33.0,
@@ -821,6 +834,9 @@ window_mode = "minimized"
             monitor_uuid: Some("new".to_string()),
             dpi_scale: Some(2.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
 
         save_window_position_atomic(path.as_path(), &old).expect("save old");
@@ -845,6 +861,9 @@ window_mode = "minimized"
             monitor_uuid: Some("old".to_string()),
             dpi_scale: Some(1.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
         let new = WindowPositionState {
             monitor_uuid: Some("new".to_string()),
@@ -853,14 +872,18 @@ window_mode = "minimized"
 
         save_window_position_atomic(path.as_path(), &old).expect("save old");
         let new_bytes = toml::to_string_pretty(&new).expect("serialize new");
-        let result = write_atomic_with_replace(&path, new_bytes.as_bytes(), |temp, _target| {
-            fs::remove_file(temp)?;
-            fs::create_dir_all(temp)?;
-            Err(io::Error::new(
-                io::ErrorKind::PermissionDenied,
-                "forced replace failure",
-            ))
-        });
+        let result = crate::atomic_io::write_atomic_with_replace(
+            &path,
+            new_bytes.as_bytes(),
+            |temp, _target| {
+                fs::remove_file(temp)?;
+                fs::create_dir_all(temp)?;
+                Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "forced replace failure",
+                ))
+            },
+        );
         assert!(result.is_err());
         let error_text = result.err().expect("error").to_string();
         assert!(error_text.contains("replace target"));
@@ -884,12 +907,20 @@ window_mode = "minimized"
             monitor_uuid: None,
             dpi_scale: Some(1.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
 
         let resolved = resolve_startup_window_bounds(
             Some(&state),
             fallback,
-            Some(display_bounds(1920.0, 1080.0)),
+            &[startup_display_snapshot(
+                0,
+                None,
+                display_bounds(1920.0, 1080.0),
+            )],
+            Some(0),
             true,
         );
 
@@ -904,7 +935,12 @@ window_mode = "minimized"
         let resolved = resolve_startup_window_bounds(
             None,
             fallback,
-            Some(display_bounds(2000.0, 1000.0)),
+            &[startup_display_snapshot(
+                0,
+                None,
+                display_bounds(2000.0, 1000.0),
+            )],
+            Some(0),
             false,
         );
 
@@ -945,13 +981,22 @@ window_mode = "minimized"
             monitor_uuid: None,
             dpi_scale: Some(1.0),
             splitter_sizes: Some(vec![f32::NAN, 980.0]),
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
         let invalid_count = WindowPositionState {
             splitter_sizes: Some(vec![420.0]),
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
             ..invalid_nan.clone()
         };
         let missing = WindowPositionState {
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
             ..invalid_nan.clone()
         };
 
@@ -972,6 +1017,9 @@ window_mode = "minimized"
             monitor_uuid: Some("display-1".to_string()),
             dpi_scale: Some(1.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
         let displays = vec![
             startup_display_snapshot(
@@ -1012,6 +1060,9 @@ window_mode = "minimized"
             monitor_uuid: Some("missing-uuid".to_string()),
             dpi_scale: Some(1.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
         let displays = vec![
             startup_display_snapshot(
@@ -1052,6 +1103,9 @@ window_mode = "minimized"
             monitor_uuid: Some("missing-uuid".to_string()),
             dpi_scale: Some(1.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
         let displays = vec![
             startup_display_snapshot(
@@ -1092,6 +1146,9 @@ window_mode = "minimized"
             monitor_uuid: Some("missing-uuid".to_string()),
             dpi_scale: Some(1.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
         let displays = vec![
             startup_display_snapshot(
@@ -1120,7 +1177,8 @@ window_mode = "minimized"
         let resolved_bounds = resolve_startup_window_bounds(
             Some(&state),
             fallback_bounds,
-            display_resolution.display_bounds,
+            displays.as_slice(),
+            Some(0),
             false,
         );
 
@@ -1139,6 +1197,9 @@ window_mode = "minimized"
             monitor_uuid: Some("display-1".to_string()),
             dpi_scale: Some(1.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
         let displays = vec![
             startup_display_snapshot(
@@ -1168,7 +1229,8 @@ window_mode = "minimized"
         let resolved_bounds = resolve_startup_window_bounds(
             Some(&state),
             fallback_bounds,
-            display_resolution.display_bounds,
+            displays.as_slice(),
+            Some(0),
             false,
         );
 
@@ -1187,6 +1249,9 @@ window_mode = "minimized"
             monitor_uuid: Some("display-1".to_string()),
             dpi_scale: Some(1.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
         let displays = vec![
             startup_display_snapshot(
@@ -1216,7 +1281,8 @@ window_mode = "minimized"
         let resolved_bounds = resolve_startup_window_bounds(
             Some(&state),
             fallback_bounds,
-            display_resolution.display_bounds,
+            displays.as_slice(),
+            Some(0),
             false,
         );
 
@@ -1235,6 +1301,9 @@ window_mode = "minimized"
             monitor_uuid: Some("display-1".to_string()),
             dpi_scale: Some(1.0),
             splitter_sizes: None,
+            editor_zoom: None,
+            always_on_top: false,
+            opacity: None,
         };
         let displays = vec![
             startup_display_snapshot(
@@ -1264,10 +1333,190 @@ window_mode = "minimized"
         let resolved_bounds = resolve_startup_window_bounds(
             Some(&state),
             fallback_bounds,
-            display_resolution.display_bounds,
+            displays.as_slice(),
+            Some(0),
             false,
         );
 
         assert_eq!(resolved_bounds, windowed(2050.0, 120.0, 900.0, 700.0));
     }
+
+    #[test]
+    fn win_test22_editor_zoom_round_trips_and_older_files_load_without_it() {
+        let root = new_temp_root("win_test22");
+        let path = root.join("conf").join(WINDOW_POSITION_FILE_NAME);
+        let state = WindowPositionState::from_window_bounds(
+            windowed(10.0, 20.0, 1200.0, 800.0),
+            None,
+            None,
+            Some(1.0),
+        )
+        .with_editor_zoom(Some(1.3));
+        save_window_position_atomic(path.as_path(), &state).expect("save state");
+        let loaded = load_window_position(path.as_path())
+            .expect("load state")
+            .expect("state exists");
+        assert_eq!(loaded.editor_zoom(), Some(1.3));
+
+        assert_eq!(state.clone().with_editor_zoom(Some(40.0)).editor_zoom, None);
+
+        fs::write(
+            path.as_path(),
+            "x = 10.0\ny = 20.0\nwidth = 1200.0\nheight = 800.0\nwindow_mode = \"windowed\"\n",
+        )
+        .expect("write legacy state");
+        let legacy = load_window_position(path.as_path())
+            .expect("load legacy state")
+            .expect("legacy state exists");
+        assert_eq!(legacy.editor_zoom(), None);
+        remove_temp_root(root.as_path());
+    }
+
+    #[test]
+    fn win_test23_bounds_saved_at_another_dpi_scale_are_rescaled_onto_the_display() {
+        let state = WindowPositionState::from_window_bounds(
+            windowed(1600.0, 400.0, 1200.0, 800.0),
+            Some(1),
+            None,
+            Some(2.0),
+        );
+        assert_eq!(state.rescaled_for_dpi(2.0), None);
+        assert_eq!(state.rescaled_for_dpi(f32::NAN), None);
+
+        let rescaled = state.rescaled_for_dpi(1.0).expect("rescaled state");
+        assert_eq!(
+            (rescaled.x, rescaled.y, rescaled.width, rescaled.height),
+            (3200.0, 800.0, 2400.0, 1600.0)
+        );
+        assert_eq!(rescaled.dpi_scale, Some(1.0));
+        assert_eq!(rescaled.monitor_id, Some(1));
+
+        // Whatever the rescale gives, the restored window still lands on the display.
+        let display = display_bounds(1920.0, 1080.0);
+        let fallback =
+            first_launch_fallback_bounds(Some(display), windowed(0.0, 0.0, 800.0, 600.0));
+        let undocked = state.rescaled_for_dpi(1.5).expect("rescaled state");
+        let restored = resolve_startup_window_bounds(
+            Some(&undocked),
+            fallback,
+            &[startup_display_snapshot(0, None, display)],
+            Some(0),
+            false,
+        )
+        .get_bounds();
+        assert!(f32::from(restored.origin.x) + f32::from(restored.size.width) <= 1920.0);
+        assert!(f32::from(restored.origin.y) + f32::from(restored.size.height) <= 1080.0);
+
+        let legacy = WindowPositionState {
+            dpi_scale: None,
+            ..state
+        };
+        assert_eq!(legacy.rescaled_for_dpi(1.0), None);
+    }
+
+    #[test]
+    fn win_test24_restore_follows_the_saved_display_and_clamps_to_primary_when_it_is_gone() {
+        let left = display_bounds_at(0.0, 0.0, 1920.0, 1080.0);
+        let right = display_bounds_at(1920.0, 0.0, 2560.0, 1440.0);
+        let displays = vec![
+            startup_display_snapshot(0, Some("display-0"), left),
+            startup_display_snapshot(7, Some("display-right"), right),
+        ];
+        let state = WindowPositionState {
+            monitor_id: Some(3),
+            monitor_uuid: Some("display-right".to_string()),
+            ..WindowPositionState::from_window_bounds(
+                windowed(4000.0, 100.0, 900.0, 700.0),
+                None,
+                None,
+                Some(1.0),
+            )
+        };
+        let fallback = first_launch_fallback_bounds(Some(left), windowed(0.0, 0.0, 1200.0, 800.0));
+
+        // Found by uuid even though the monitor id changed; clamped inside that display.
+        let restored = resolve_startup_window_bounds(
+            Some(&state),
+            fallback,
+            displays.as_slice(),
+            Some(0),
+            false,
+        );
+        assert_eq!(restored, windowed(3580.0, 100.0, 900.0, 700.0));
+
+        // Ids were renumbered and there is no uuid: the bounds stay on the display they overlap.
+        let renumbered = WindowPositionState {
+            monitor_uuid: None,
+            ..state.clone()
+        };
+        let restored = resolve_startup_window_bounds(
+            Some(&renumbered),
+            fallback,
+            displays.as_slice(),
+            Some(0),
+            false,
+        );
+        assert_eq!(restored, windowed(3580.0, 100.0, 900.0, 700.0));
+
+        // The right display is unplugged: back onto the primary one.
+        let restored =
+            resolve_startup_window_bounds(Some(&state), fallback, &displays[..1], Some(0), false);
+        assert_eq!(restored, fallback);
+    }
+
+    #[test]
+    fn win_test25_maximized_window_keeps_the_windowed_bounds_it_had_before() {
+        let windowed_bounds = display_bounds_at(200.0, 150.0, 1000.0, 700.0);
+        let maximized = WindowPositionState::from_window_bounds(
+            WindowBounds::Maximized(display_bounds(1920.0, 1040.0)),
+            Some(0),
+            None,
+            Some(1.0),
+        )
+        .with_windowed_restore_bounds(Some(windowed_bounds));
+        assert_eq!(maximized.window_mode, PersistedWindowMode::Maximized);
+        assert_eq!(
+            maximized.to_window_bounds(),
+            Some(WindowBounds::Maximized(windowed_bounds))
+        );
+
+        let root = new_temp_root("win_test25");
+        let path = root.join("conf").join(WINDOW_POSITION_FILE_NAME);
+        save_window_position_atomic(path.as_path(), &maximized).expect("save state");
+        let loaded = load_window_position(path.as_path()).expect("load state");
+        let restored = resolve_startup_window_bounds(
+            loaded.as_ref(),
+            windowed(0.0, 0.0, 800.0, 600.0),
+            &[startup_display_snapshot(
+                0,
+                None,
+                display_bounds(1920.0, 1080.0),
+            )],
+            Some(0),
+            false,
+        );
+        assert_eq!(restored, WindowBounds::Maximized(windowed_bounds));
+        remove_temp_root(root.as_path());
+
+        // A windowed window is already reporting its own bounds.
+        let windowed_state = WindowPositionState::from_window_bounds(
+            windowed(10.0, 10.0, 640.0, 480.0),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(
+            windowed_state
+                .clone()
+                .with_windowed_restore_bounds(Some(windowed_bounds)),
+            windowed_state
+        );
+        let unknown = WindowPositionState::from_window_bounds(
+            WindowBounds::Maximized(display_bounds(1920.0, 1040.0)),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(unknown.clone().with_windowed_restore_bounds(None), unknown);
+    }
 }
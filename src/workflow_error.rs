@@ -0,0 +1,333 @@
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub type WorkflowResult<T> = Result<T, WorkflowError>;
+
+const BUSY_RETRY_MAX_ATTEMPTS: u32 = 4;
+const BUSY_RETRY_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Why a file workflow event failed, sorted by what the user can do about it. Worker code
+/// below the dispatcher still speaks `io::Error`; the dispatcher classifies at its boundary.
+#[derive(Debug, thiserror::Error)]
+pub enum WorkflowError {
+    #[error("{} already exists", path.display())]
+    NameCollision { path: PathBuf },
+    #[error("{} does not exist", path.display())]
+    NotFound { path: PathBuf },
+    #[error("permission denied for {}", path.display())]
+    PermissionDenied {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("{} is locked after publishing", path.display())]
+    PublishLocked { path: PathBuf },
+    #[error("no space left on the drive holding {}", path.display())]
+    DiskFull {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("{} is in use by another program (tried {attempts} times)", path.display())]
+    FileBusy {
+        path: PathBuf,
+        attempts: u32,
+        #[source]
+        source: io::Error,
+    },
+    #[error("{0}")]
+    InvalidRequest(String),
+    #[error("file worker unavailable: {0}")]
+    DispatcherUnavailable(String),
+    #[error("{event} did not finish within {} s", after.as_secs())]
+    TimedOut {
+        event: &'static str,
+        after: Duration,
+    },
+    #[error("{capacity} file operations are already waiting")]
+    QueueFull { capacity: usize },
+    #[error("{event} returned an unexpected result")]
+    UnexpectedResult { event: &'static str },
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+impl WorkflowError {
+    /// Classifies an `io::Error` raised while working on `path`. A `WorkflowError` that was
+    /// wrapped into an `io::Error` further down comes back out unchanged.
+    pub fn from_io(error: io::Error, path: &Path) -> Self {
+        let error = match error.downcast::<WorkflowError>() {
+            Ok(workflow_error) => return workflow_error,
+            Err(error) => error,
+        };
+        let kind = error.kind();
+        let path = path.to_path_buf();
+        if is_sharing_violation(&error) {
+            return Self::FileBusy {
+                path,
+                attempts: 1,
+                source: error,
+            };
+        }
+        match kind {
+            io::ErrorKind::AlreadyExists => Self::NameCollision { path },
+            io::ErrorKind::NotFound => Self::NotFound { path },
+            io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem => {
+                Self::PermissionDenied {
+                    path,
+                    source: error,
+                }
+            }
+            io::ErrorKind::StorageFull | io::ErrorKind::QuotaExceeded => Self::DiskFull {
+                path,
+                source: error,
+            },
+            io::ErrorKind::ResourceBusy
+            | io::ErrorKind::WouldBlock
+            | io::ErrorKind::Interrupted => Self::FileBusy {
+                path,
+                attempts: 1,
+                source: error,
+            },
+            io::ErrorKind::InvalidInput => Self::InvalidRequest(error.to_string()),
+            io::ErrorKind::BrokenPipe => Self::DispatcherUnavailable(error.to_string()),
+            _ => Self::Io {
+                path,
+                source: error,
+            },
+        }
+    }
+
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Self::NameCollision { .. } => io::ErrorKind::AlreadyExists,
+            Self::NotFound { .. } => io::ErrorKind::NotFound,
+            Self::PermissionDenied { .. } | Self::PublishLocked { .. } => {
+                io::ErrorKind::PermissionDenied
+            }
+            Self::InvalidRequest(_) => io::ErrorKind::InvalidInput,
+            Self::DispatcherUnavailable(_) => io::ErrorKind::BrokenPipe,
+            Self::TimedOut { .. } => io::ErrorKind::TimedOut,
+            Self::QueueFull { .. } => io::ErrorKind::QuotaExceeded,
+            Self::UnexpectedResult { .. } => io::ErrorKind::Other,
+            Self::DiskFull { source, .. }
+            | Self::FileBusy { source, .. }
+            | Self::Io { source, .. } => source.kind(),
+        }
+    }
+
+    /// Worth another attempt after a short wait: the file was held by a sync client, a
+    /// virus scanner or another editor.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::FileBusy { .. })
+    }
+
+    /// What the user can do next, for the notice or status bar; None when there is nothing
+    /// better to suggest than trying again.
+    pub fn remediation(&self) -> Option<&'static str> {
+        match self {
+            Self::NameCollision { .. } => Some("choose another title"),
+            Self::NotFound { .. } => {
+                Some("it was moved or deleted outside papyru2; reopen it from the file tree")
+            }
+            Self::PermissionDenied { .. } => {
+                Some("check the folder permissions or whether the drive is read-only")
+            }
+            Self::PublishLocked { .. } => Some("unlock the note to edit it"),
+            Self::DiskFull { .. } => Some("free up space on the drive and try again"),
+            Self::FileBusy { .. } => Some(
+                "close the program using the file (sync client, virus scanner, another editor) and try again",
+            ),
+            Self::DispatcherUnavailable(_) => {
+                Some("try again; restart papyru2 if it keeps failing")
+            }
+            Self::TimedOut { .. } | Self::QueueFull { .. } => {
+                Some("check that the notes folder (network share, external drive) is reachable")
+            }
+            Self::InvalidRequest(_) | Self::UnexpectedResult { .. } | Self::Io { .. } => None,
+        }
+    }
+
+    /// `"{action}: {error}"` plus the remediation hint, ready for a transient notice.
+    pub fn notice(&self, action: impl fmt::Display) -> String {
+        match self.remediation() {
+            Some(hint) => format!("{action}: {self}; {hint}"),
+            None => format!("{action}: {self}"),
+        }
+    }
+}
+
+// ERROR_SHARING_VIOLATION / ERROR_LOCK_VIOLATION: Windows reports a file another process
+// holds open this way, and std has no `ErrorKind` for either. ReplaceFileW, behind the atomic
+// save, fails with ERROR_UNABLE_TO_REMOVE_REPLACED / ERROR_UNABLE_TO_MOVE_REPLACEMENT for the
+// same reason, leaving the original in place.
+fn is_sharing_violation(error: &io::Error) -> bool {
+    cfg!(target_os = "windows") && matches!(error.raw_os_error(), Some(32 | 33 | 1175 | 1176))
+}
+
+fn is_busy(error: &io::Error) -> bool {
+    is_sharing_violation(error)
+        || matches!(
+            error.kind(),
+            io::ErrorKind::ResourceBusy | io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted
+        )
+}
+
+/// Runs one IO call on `path` again while another program (a sync client, a virus scanner,
+/// another editor) holds the file, waiting a little longer each time. Only for a call that
+/// either completes or changes nothing: one atomic write, one rename, one create. The last
+/// busy failure comes back as `FileBusy` with the number of attempts.
+pub fn retry_busy<T>(path: &Path, mut call: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    let mut backoff = BUSY_RETRY_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        crate::file_update_handler::ensure_current_worker()?;
+        let error = match call() {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        if !is_busy(&error) {
+            return Err(error);
+        }
+        if attempt >= BUSY_RETRY_MAX_ATTEMPTS {
+            return Err(WorkflowError::FileBusy {
+                path: path.to_path_buf(),
+                attempts: attempt,
+                source: error,
+            }
+            .into());
+        }
+        crate::log::trace_debug(format!(
+            "workflow busy retry path={} attempt={attempt} backoff_ms={} error={error}",
+            path.display(),
+            backoff.as_millis()
+        ));
+        std::thread::sleep(backoff);
+        backoff *= 2;
+        attempt += 1;
+    }
+}
+
+/// Classifies an error right where it happened, for a helper that touches more than one path:
+/// `from_io` at the dispatcher then keeps this path instead of the one the event started from.
+pub fn at_path(path: &Path) -> impl FnOnce(io::Error) -> io::Error + '_ {
+    move |error| WorkflowError::from_io(error, path).into()
+}
+
+impl From<WorkflowError> for io::Error {
+    fn from(error: WorkflowError) -> Self {
+        io::Error::new(error.kind(), error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wfe_test1_io_errors_are_classified_with_their_path() {
+        let path = Path::new("notes/plan.txt");
+        let collision = WorkflowError::from_io(io::Error::from(io::ErrorKind::AlreadyExists), path);
+        assert!(matches!(collision, WorkflowError::NameCollision { .. }));
+        assert_eq!(
+            collision.notice("Cannot rename"),
+            "Cannot rename: notes/plan.txt already exists; choose another title"
+        );
+        let denied = WorkflowError::from_io(io::Error::from(io::ErrorKind::PermissionDenied), path);
+        assert!(matches!(denied, WorkflowError::PermissionDenied { .. }));
+        assert_eq!(denied.kind(), io::ErrorKind::PermissionDenied);
+        let other = WorkflowError::from_io(io::Error::other("disk gone"), path);
+        assert!(matches!(other, WorkflowError::Io { .. }));
+        assert_eq!(other.remediation(), None);
+    }
+
+    #[test]
+    fn wfe_test2_wrapped_workflow_error_round_trips_through_io_error() {
+        let locked = io::Error::from(WorkflowError::PublishLocked {
+            path: PathBuf::from("notes/post.md"),
+        });
+        assert_eq!(locked.kind(), io::ErrorKind::PermissionDenied);
+        let unwrapped = WorkflowError::from_io(locked, Path::new("elsewhere.md"));
+        assert!(matches!(
+            unwrapped,
+            WorkflowError::PublishLocked { ref path } if path == Path::new("notes/post.md")
+        ));
+    }
+
+    #[test]
+    fn wfe_test3_disk_full_and_busy_files_are_classified() {
+        let path = Path::new("notes/plan.txt");
+        let full = WorkflowError::from_io(io::Error::from(io::ErrorKind::StorageFull), path);
+        assert!(matches!(full, WorkflowError::DiskFull { .. }));
+        assert!(!full.is_transient());
+        assert_eq!(full.kind(), io::ErrorKind::StorageFull);
+        assert_eq!(
+            full.notice("Cannot save the open note"),
+            "Cannot save the open note: no space left on the drive holding notes/plan.txt; free up space on the drive and try again"
+        );
+        let busy = WorkflowError::from_io(io::Error::from(io::ErrorKind::ResourceBusy), path);
+        assert!(matches!(busy, WorkflowError::FileBusy { attempts: 1, .. }));
+        assert!(busy.is_transient());
+        assert!(
+            !WorkflowError::from_io(io::Error::from(io::ErrorKind::NotFound), path).is_transient()
+        );
+    }
+
+    #[test]
+    fn wfe_test4_busy_calls_are_retried_and_other_errors_are_not() {
+        let path = Path::new("C:/tmp/busy.txt");
+        let mut calls = 0;
+        let saved = retry_busy(path, || {
+            calls += 1;
+            if calls < 3 {
+                Err(io::Error::from(io::ErrorKind::ResourceBusy))
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(saved.expect("third attempt succeeds"), 3);
+
+        let mut calls = 0;
+        let busy = retry_busy(path, || {
+            calls += 1;
+            Err::<(), _>(io::Error::from(io::ErrorKind::ResourceBusy))
+        })
+        .map_err(|error| WorkflowError::from_io(error, Path::new("elsewhere.txt")));
+        assert_eq!(calls, BUSY_RETRY_MAX_ATTEMPTS);
+        assert!(matches!(
+            busy,
+            Err(WorkflowError::FileBusy { ref path, attempts, .. })
+                if attempts == BUSY_RETRY_MAX_ATTEMPTS && path == Path::new("C:/tmp/busy.txt")
+        ));
+
+        let mut calls = 0;
+        let full = retry_busy(path, || {
+            calls += 1;
+            Err::<(), _>(io::Error::from(io::ErrorKind::StorageFull))
+        });
+        assert_eq!(calls, 1);
+        assert_eq!(
+            full.map_err(|error| error.kind()),
+            Err(io::ErrorKind::StorageFull)
+        );
+    }
+
+    #[test]
+    fn wfe_test5_error_tagged_at_its_path_keeps_that_path() {
+        let tagged = at_path(Path::new("notes/daily/plan_2.txt"))(io::Error::from(
+            io::ErrorKind::PermissionDenied,
+        ));
+        assert_eq!(tagged.kind(), io::ErrorKind::PermissionDenied);
+        assert!(matches!(
+            WorkflowError::from_io(tagged, Path::new("notes/plan.txt")),
+            WorkflowError::PermissionDenied { ref path, .. } if path == Path::new("notes/daily/plan_2.txt")
+        ));
+    }
+}
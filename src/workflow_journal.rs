@@ -0,0 +1,376 @@
+use std::{
+    fs, io,
+    io::Write as _,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::file_update_handler::SinglelineFileState;
+
+pub(crate) const WORKFLOW_JOURNAL_FILE_NAME: &str = "workflow_events.jsonl";
+// Older entries are dropped at startup once the log grows past this; the tail is enough to
+// see how the current state was reached.
+const WORKFLOW_JOURNAL_MAX_ENTRIES: usize = 2000;
+
+/// One step of the NEUTRAL/NEW/EDIT workflow, as seen from the outside.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WorkflowTransition {
+    Opened {
+        path: PathBuf,
+    },
+    Created {
+        path: PathBuf,
+    },
+    Renamed {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    /// The workflow moved the open note on its own: daily relocation, recovery, tree moves.
+    Relocated {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    /// Plus button or a delete of the open note: back to NEUTRAL.
+    Cleared,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkflowJournalEntry {
+    pub at_unix_ms: u64,
+    #[serde(flatten)]
+    pub transition: WorkflowTransition,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestoredWorkflow {
+    pub state: SinglelineFileState,
+    pub current_edit_path: Option<PathBuf>,
+    pub entries: usize,
+}
+
+impl Default for RestoredWorkflow {
+    fn default() -> Self {
+        Self {
+            state: SinglelineFileState::Neutral,
+            current_edit_path: None,
+            entries: 0,
+        }
+    }
+}
+
+impl RestoredWorkflow {
+    fn apply(&mut self, transition: &WorkflowTransition) {
+        match transition {
+            WorkflowTransition::Opened { path } | WorkflowTransition::Created { path } => {
+                self.state = SinglelineFileState::Edit;
+                self.current_edit_path = Some(path.clone());
+            }
+            // A rename or move of a note that is no longer open changes nothing.
+            WorkflowTransition::Renamed { from, to }
+            | WorkflowTransition::Relocated { from, to } => {
+                if self.current_edit_path.as_ref() == Some(from) {
+                    self.current_edit_path = Some(to.clone());
+                }
+            }
+            WorkflowTransition::Cleared => {
+                self.state = SinglelineFileState::Neutral;
+                self.current_edit_path = None;
+            }
+        }
+    }
+}
+
+/// The log of one vault: each vault keeps its own, so switching vaults never replays another
+/// vault's note.
+pub(crate) fn workflow_journal_path(app_paths: &crate::path_resolver::AppPaths) -> PathBuf {
+    app_paths.vault_data_dir().join(WORKFLOW_JOURNAL_FILE_NAME)
+}
+
+enum JournalWrite {
+    Line(Vec<u8>),
+    Flushed(mpsc::Sender<()>),
+}
+
+/// Append-only log of workflow transitions. Lines are written and synced to disk by a thread
+/// of its own in the order they were appended, so a transition never waits on the disk; a
+/// crash loses at most the lines still queued.
+#[derive(Clone, Debug)]
+pub struct WorkflowJournal {
+    writer: mpsc::Sender<JournalWrite>,
+}
+
+impl WorkflowJournal {
+    pub fn new(path: PathBuf) -> Self {
+        let (writer, lines) = mpsc::channel::<JournalWrite>();
+        thread::spawn(move || {
+            let mut file = None;
+            for write in lines {
+                match write {
+                    JournalWrite::Line(line) => {
+                        if let Err(error) = write_journal_line(&path, &mut file, &line) {
+                            crate::log::trace_debug(format!(
+                                "workflow journal write failed path={} error={error}",
+                                path.display()
+                            ));
+                        }
+                    }
+                    JournalWrite::Flushed(done) => {
+                        let _ = done.send(());
+                    }
+                }
+            }
+        });
+        Self { writer }
+    }
+
+    pub fn append(&self, transition: &WorkflowTransition) -> io::Result<()> {
+        let entry = WorkflowJournalEntry {
+            at_unix_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis() as u64)
+                .unwrap_or(0),
+            transition: transition.clone(),
+        };
+        let mut line = serde_json::to_vec(&entry)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+        line.push(b'\n');
+        self.writer
+            .send(JournalWrite::Line(line))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "journal writer is gone"))
+    }
+
+    /// Waits until every line appended so far is on disk; for teardown.
+    pub fn flush(&self) {
+        let (done_tx, done_rx) = mpsc::channel();
+        if self.writer.send(JournalWrite::Flushed(done_tx)).is_ok() {
+            let _ = done_rx.recv();
+        }
+    }
+}
+
+fn write_journal_line(path: &Path, file: &mut Option<fs::File>, line: &[u8]) -> io::Result<()> {
+    if file.is_none() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // A line torn by a crash must not swallow the first entry of this session.
+        let torn_tail = fs::read(path)
+            .map(|bytes| bytes.last().is_some_and(|last| *last != b'\n'))
+            .unwrap_or(false);
+        let mut handle = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        if torn_tail {
+            handle.write_all(b"\n")?;
+        }
+        *file = Some(handle);
+    }
+    let Some(handle) = file.as_mut() else {
+        return Ok(());
+    };
+    handle.write_all(line)?;
+    handle.sync_data()
+}
+
+/// Rebuilds the workflow state from the log. A torn or unreadable line (a crash mid-append)
+/// is skipped; the log is trimmed to its newest entries when it has grown too long.
+pub fn replay_workflow_journal(path: &Path) -> io::Result<RestoredWorkflow> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return Ok(RestoredWorkflow::default());
+        }
+        Err(error) => return Err(error),
+    };
+    let entries = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<WorkflowJournalEntry>(line).ok())
+        .collect::<Vec<_>>();
+    let mut restored = RestoredWorkflow::default();
+    for entry in &entries {
+        restored.apply(&entry.transition);
+    }
+    restored.entries = entries.len();
+
+    if entries.len() > WORKFLOW_JOURNAL_MAX_ENTRIES {
+        // The dropped head is folded into one leading entry, so replaying the trimmed log
+        // still ends in the same state.
+        let cut = entries.len() + 1 - WORKFLOW_JOURNAL_MAX_ENTRIES;
+        let mut head = RestoredWorkflow::default();
+        for entry in &entries[..cut] {
+            head.apply(&entry.transition);
+        }
+        let snapshot = WorkflowJournalEntry {
+            at_unix_ms: entries[cut - 1].at_unix_ms,
+            transition: match head.current_edit_path {
+                Some(path) if head.state == SinglelineFileState::Edit => {
+                    WorkflowTransition::Opened { path }
+                }
+                _ => WorkflowTransition::Cleared,
+            },
+        };
+        let mut kept = Vec::new();
+        for entry in std::iter::once(&snapshot).chain(&entries[cut..]) {
+            kept.extend(
+                serde_json::to_vec(entry).map_err(|error| {
+                    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+                })?,
+            );
+            kept.push(b'\n');
+        }
+        crate::atomic_io::write_atomic(path, &kept)?;
+    }
+    crate::log::trace_debug(format!(
+        "workflow journal replay entries={} state={:?} path={:?}",
+        restored.entries, restored.state, restored.current_edit_path
+    ));
+    Ok(restored)
+}
+
+impl crate::app::Papyru2App {
+    /// Reopens the note that was open when the app last stopped, when it still exists.
+    pub(crate) fn restore_workflow_session(
+        &mut self,
+        restored: RestoredWorkflow,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        let Some(path) = restored
+            .current_edit_path
+            .filter(|_| restored.state == SinglelineFileState::Edit)
+        else {
+            return;
+        };
+        if !path.is_file() {
+            crate::log::trace_debug(format!(
+                "workflow journal restore skipped missing path={}",
+                path.display()
+            ));
+            return;
+        }
+        if self.open_file(path.clone(), window, cx) {
+            self.file_tree.update(cx, |file_tree, cx| {
+                file_tree.restore_selection_for_path(path.as_path(), cx);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_temp_root(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "papyru2_workflow_journal_{name}_{}_{}",
+            std::process::id(),
+            stamp
+        ));
+        fs::create_dir_all(&root).expect("create temp root");
+        root
+    }
+
+    #[test]
+    fn wfj_test1_replay_follows_renames_and_skips_torn_tail() {
+        let root = new_temp_root("wfj_test1");
+        let path = root.join(WORKFLOW_JOURNAL_FILE_NAME);
+        let journal = WorkflowJournal::new(path.clone());
+        let a = root.join("a.txt");
+        let b = root.join("b.txt");
+        let c = root.join("c.txt");
+        for transition in [
+            WorkflowTransition::Created { path: a.clone() },
+            WorkflowTransition::Renamed {
+                from: a.clone(),
+                to: b.clone(),
+            },
+            WorkflowTransition::Relocated {
+                from: root.join("other.txt"),
+                to: c.clone(),
+            },
+        ] {
+            journal.append(&transition).expect("append");
+        }
+        journal.flush();
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("open journal");
+        file.write_all(b"{\"at_unix_ms\":1,\"kind\":\"ope")
+            .expect("write torn line");
+
+        let restored = replay_workflow_journal(path.as_path()).expect("replay");
+        assert_eq!(restored.state, SinglelineFileState::Edit);
+        assert_eq!(restored.current_edit_path, Some(b));
+        assert_eq!(restored.entries, 3);
+
+        // The next session appends after the torn line instead of onto it.
+        let next_session = WorkflowJournal::new(path.clone());
+        next_session
+            .append(&WorkflowTransition::Cleared)
+            .expect("append");
+        next_session.flush();
+        let restored = replay_workflow_journal(path.as_path()).expect("replay");
+        assert_eq!(restored.state, SinglelineFileState::Neutral);
+        assert_eq!(restored.current_edit_path, None);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn wfj_test2_long_log_is_trimmed_without_changing_state() {
+        let root = new_temp_root("wfj_test2");
+        let path = root.join(WORKFLOW_JOURNAL_FILE_NAME);
+        let journal = WorkflowJournal::new(path.clone());
+        for index in 0..WORKFLOW_JOURNAL_MAX_ENTRIES + 5 {
+            journal
+                .append(&WorkflowTransition::Opened {
+                    path: root.join(format!("{index}.txt")),
+                })
+                .expect("append");
+        }
+        journal.flush();
+
+        let restored = replay_workflow_journal(path.as_path()).expect("replay");
+        let last = root.join(format!("{}.txt", WORKFLOW_JOURNAL_MAX_ENTRIES + 4));
+        assert_eq!(restored.current_edit_path, Some(last.clone()));
+        let trimmed = replay_workflow_journal(path.as_path()).expect("replay trimmed");
+        assert_eq!(trimmed.entries, WORKFLOW_JOURNAL_MAX_ENTRIES);
+        assert_eq!(trimmed.current_edit_path, Some(last));
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn wfj_test3_workflow_transitions_rebuild_the_open_note() {
+        let root = new_temp_root("wfj_test3");
+        let path = root.join(WORKFLOW_JOURNAL_FILE_NAME);
+        let archive = root.join("archive");
+        fs::create_dir_all(&archive).expect("create archive");
+        let note = root.join("plan.txt");
+        fs::write(&note, "plan").expect("seed note");
+
+        let workflow = crate::file_update_handler::SinglelineCreateFileWorkflow::new();
+        workflow.attach_transition_journal(WorkflowJournal::new(path.clone()));
+        workflow.set_edit_from_open_file(note.clone());
+        workflow
+            .move_entry_blocking(note.as_path(), archive.as_path())
+            .expect("move note");
+        workflow.shutdown_dispatcher();
+
+        let restored = replay_workflow_journal(path.as_path()).expect("replay");
+        assert_eq!(restored.state, SinglelineFileState::Edit);
+        assert_eq!(restored.current_edit_path, Some(archive.join("plan.txt")));
+        assert_eq!(restored.entries, 2);
+        let _ = fs::remove_dir_all(root);
+    }
+}